@@ -1,6 +1,11 @@
+use std::fmt;
+
+// Le of 0 encodes these maximums in short and extended coding respectively.
+pub const MAX_SHORT_LE: usize = 256;
+pub const MAX_EXTENDED_LE: usize = 65536;
+
 // T4T 1.1 5.1.2 Format of Command-APDU
-// Lc is encoded when serialized.
-// WCA doesn't use Le, and it's optional, so it is unimplemented.
+// Lc and Le are encoded when serialized.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Command {
     pub cla: u8,
@@ -8,6 +13,8 @@ pub struct Command {
     pub p1: u8,
     pub p2: u8,
     pub data: Option<Vec<u8>>,
+    // Maximum number of response bytes expected, up to MAX_EXTENDED_LE.
+    pub le: Option<usize>,
 }
 
 // T4T 1.1 5.1.3 Format of Response-APDU
@@ -19,6 +26,45 @@ pub struct Response {
     pub sw2: u8,
 }
 
+// ISO 7816-4 5.1.3 Status bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusWord {
+    // 9000
+    Success,
+    // 61XX: XX more response bytes are available through GET RESPONSE (00 means 256 or more)
+    BytesRemaining(u8),
+    // 62XX
+    WarningStateUnchanged(u8),
+    // 63XX
+    WarningStateChanged(u8),
+    // 64XX
+    ExecutionErrorStateUnchanged(u8),
+    // 65XX
+    ExecutionErrorStateChanged(u8),
+    // 66XX
+    SecurityError(u8),
+    // 6700
+    WrongLength,
+    // 68XX
+    FunctionNotSupported(u8),
+    // 69XX
+    CommandNotAllowed(u8),
+    // 6AXX
+    WrongParameters(u8),
+    // 6B00
+    IncorrectParameters,
+    // 6CXX: the command should be resent with Le set to XX (00 means 256)
+    WrongLe(u8),
+    // 6D00
+    InstructionNotSupported,
+    // 6E00
+    ClassNotSupported,
+    // 6F00
+    NoPreciseDiagnosis,
+    // Proprietary or otherwise unrecognised status words
+    Other(u8, u8),
+}
+
 impl Command {
     pub fn new(cla: u8, ins: u8, p1: u8, p2: u8, data: Vec<u8>) -> Self {
         Self {
@@ -27,6 +73,7 @@ impl Command {
             p1,
             p2,
             data: Some(data),
+            le: None,
         }
     }
 
@@ -37,28 +84,58 @@ impl Command {
             p1,
             p2,
             data: None,
+            le: None,
+        }
+    }
+
+    pub fn with_le(self, le: usize) -> Self {
+        Self {
+            le: Some(le.min(MAX_EXTENDED_LE)),
+            ..self
         }
     }
 
     pub fn serialize(self) -> Vec<u8> {
         let mut out = vec![self.cla, self.ins, self.p1, self.p2];
 
+        // T4T 1.1 Table 19: Coding of Lc field
+        // Lc and Le must both use the same coding, so a long Le forces an extended Lc and vice
+        // versa.
+        let short_lc = self
+            .data
+            .as_ref()
+            .map_or(true, |d| (1..=255).contains(&d.len()));
+        let short_le = self.le.map_or(true, |le| le <= MAX_SHORT_LE);
+        let extended = !short_lc || !short_le;
+
         // Data length (Lc) and data, if present
-        if let Some(d) = self.data {
-            // T4T 1.1 Table 19: Coding of Lc field
+        if let Some(d) = &self.data {
             let lc = d.len() as u16;
-            match lc {
-                1..=255 => {
-                    // Short coding
-                    out.push(lc as u8);
-                }
-                _ => {
-                    // Extended; 0 is prefixed by big-endian Lc
+            if extended {
+                // Extended; 0 is prefixed by big-endian Lc
+                out.push(0);
+                out.extend(lc.to_be_bytes());
+            } else {
+                // Short coding
+                out.push(lc as u8);
+            }
+        }
+        let has_lc = self.data.is_some();
+        if let Some(d) = self.data {
+            out.extend(d);
+        }
+
+        // Expected response length (Le), if present
+        if let Some(le) = self.le {
+            if extended {
+                // Extended; 0 is prefixed only when there is no extended Lc to signal it
+                if !has_lc {
                     out.push(0);
-                    out.extend(lc.to_be_bytes());
                 }
+                out.extend(((le % MAX_EXTENDED_LE) as u16).to_be_bytes());
+            } else {
+                out.push((le % MAX_SHORT_LE) as u8);
             }
-            out.extend(d);
         }
 
         out
@@ -73,7 +150,11 @@ impl From<Command> for Vec<u8> {
 
 impl Response {
     pub fn is_ok(&self) -> bool {
-        (self.sw1 == 0x90 || self.sw1 == 0x91) && (self.sw2 == 0x00)
+        self.status().is_success()
+    }
+
+    pub fn status(&self) -> StatusWord {
+        StatusWord::from([self.sw1, self.sw2])
     }
 }
 
@@ -98,6 +179,149 @@ impl From<Vec<u8>> for Response {
     }
 }
 
+impl StatusWord {
+    pub fn to_bytes(self) -> [u8; 2] {
+        match self {
+            StatusWord::Success => [0x90, 0x00],
+            StatusWord::BytesRemaining(xx) => [0x61, xx],
+            StatusWord::WarningStateUnchanged(xx) => [0x62, xx],
+            StatusWord::WarningStateChanged(xx) => [0x63, xx],
+            StatusWord::ExecutionErrorStateUnchanged(xx) => [0x64, xx],
+            StatusWord::ExecutionErrorStateChanged(xx) => [0x65, xx],
+            StatusWord::SecurityError(xx) => [0x66, xx],
+            StatusWord::WrongLength => [0x67, 0x00],
+            StatusWord::FunctionNotSupported(xx) => [0x68, xx],
+            StatusWord::CommandNotAllowed(xx) => [0x69, xx],
+            StatusWord::WrongParameters(xx) => [0x6a, xx],
+            StatusWord::IncorrectParameters => [0x6b, 0x00],
+            StatusWord::WrongLe(xx) => [0x6c, xx],
+            StatusWord::InstructionNotSupported => [0x6d, 0x00],
+            StatusWord::ClassNotSupported => [0x6e, 0x00],
+            StatusWord::NoPreciseDiagnosis => [0x6f, 0x00],
+            StatusWord::Other(sw1, sw2) => [sw1, sw2],
+        }
+    }
+
+    // The command completed. Some readers report 9100 rather than 9000. 61XX isn't included,
+    // since chaining has to finish before the response is complete.
+    pub fn is_success(self) -> bool {
+        matches!(self, StatusWord::Success | StatusWord::Other(0x91, 0x00))
+    }
+}
+
+impl From<[u8; 2]> for StatusWord {
+    fn from(sw: [u8; 2]) -> Self {
+        match sw {
+            [0x90, 0x00] => StatusWord::Success,
+            [0x61, xx] => StatusWord::BytesRemaining(xx),
+            [0x62, xx] => StatusWord::WarningStateUnchanged(xx),
+            [0x63, xx] => StatusWord::WarningStateChanged(xx),
+            [0x64, xx] => StatusWord::ExecutionErrorStateUnchanged(xx),
+            [0x65, xx] => StatusWord::ExecutionErrorStateChanged(xx),
+            [0x66, xx] => StatusWord::SecurityError(xx),
+            [0x67, 0x00] => StatusWord::WrongLength,
+            [0x68, xx] => StatusWord::FunctionNotSupported(xx),
+            [0x69, xx] => StatusWord::CommandNotAllowed(xx),
+            [0x6a, xx] => StatusWord::WrongParameters(xx),
+            [0x6b, 0x00] => StatusWord::IncorrectParameters,
+            [0x6c, xx] => StatusWord::WrongLe(xx),
+            [0x6d, 0x00] => StatusWord::InstructionNotSupported,
+            [0x6e, 0x00] => StatusWord::ClassNotSupported,
+            [0x6f, 0x00] => StatusWord::NoPreciseDiagnosis,
+            [sw1, sw2] => StatusWord::Other(sw1, sw2),
+        }
+    }
+}
+
+impl From<StatusWord> for [u8; 2] {
+    fn from(sw: StatusWord) -> Self {
+        sw.to_bytes()
+    }
+}
+
+impl fmt::Display for StatusWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [sw1, sw2] = self.to_bytes();
+        write!(f, "{sw1:02X}{sw2:02X}")
+    }
+}
+
+// ISO 7816-4 5.3.4 GET RESPONSE, for cards that use the interindustry class.
+pub const ISO_GET_RESPONSE: Command = Command {
+    cla: 0x00,
+    ins: 0xc0,
+    p1: 0x00,
+    p2: 0x00,
+    data: None,
+    le: None,
+};
+
+// What to do next while exchanging a command with a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    Transmit(Command),
+    Complete(Response),
+}
+
+// Drives a command through ISO 7816-4 response chaining without doing any I/O itself:
+// transmit the command, pass each response to `receive`, and transmit whatever it returns until
+// the exchange is complete.
+//
+// 61XX responses are accumulated by issuing `get_response`, and a 6CXX response causes the
+// command to be resent once with the Le the card asked for. The completed response carries all
+// of the accumulated data and the final status word.
+#[derive(Debug, Clone)]
+pub struct Exchange {
+    command: Command,
+    get_response: Command,
+    data: Vec<u8>,
+    resent: bool,
+}
+
+impl Exchange {
+    pub fn new(command: Command, get_response: Command) -> Self {
+        Self {
+            command,
+            get_response,
+            data: vec![],
+            resent: false,
+        }
+    }
+
+    pub fn command(&self) -> &Command {
+        &self.command
+    }
+
+    pub fn receive(&mut self, response: Response) -> Step {
+        match response.status() {
+            StatusWord::BytesRemaining(xx) => {
+                self.data.extend(response.data);
+                Step::Transmit(self.get_response.clone().with_le(le_from_sw2(xx)))
+            }
+            StatusWord::WrongLe(xx) if !self.resent => {
+                self.resent = true;
+                Step::Transmit(self.command.clone().with_le(le_from_sw2(xx)))
+            }
+            _ => {
+                let mut data = std::mem::take(&mut self.data);
+                data.extend(response.data);
+                Step::Complete(Response {
+                    data,
+                    sw1: response.sw1,
+                    sw2: response.sw2,
+                })
+            }
+        }
+    }
+}
+
+fn le_from_sw2(sw2: u8) -> usize {
+    match sw2 {
+        0 => MAX_SHORT_LE,
+        xx => xx as usize,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,4 +392,133 @@ mod tests {
         assert_eq!(0x91, rsp.sw1);
         assert_eq!(0x00, rsp.sw2);
     }
+
+    #[test]
+    fn short_le() {
+        assert_eq!(
+            vec![1, 2, 3, 4, 0x10],
+            Command::new_header(1, 2, 3, 4).with_le(0x10).serialize()
+        );
+        assert_eq!(
+            vec![1, 2, 3, 4, 0],
+            Command::new_header(1, 2, 3, 4)
+                .with_le(MAX_SHORT_LE)
+                .serialize()
+        );
+        assert_eq!(
+            vec![1, 2, 3, 4, 1, 0xff, 0x10],
+            Command::new(1, 2, 3, 4, vec![0xff])
+                .with_le(0x10)
+                .serialize()
+        );
+    }
+
+    #[test]
+    fn extended_le() {
+        assert_eq!(
+            vec![1, 2, 3, 4, 0, 0x02, 0x00],
+            Command::new_header(1, 2, 3, 4).with_le(512).serialize()
+        );
+        assert_eq!(
+            vec![1, 2, 3, 4, 0, 0, 0],
+            Command::new_header(1, 2, 3, 4)
+                .with_le(MAX_EXTENDED_LE)
+                .serialize()
+        );
+        // A long Le forces an extended Lc, and Le then isn't prefixed by 0
+        assert_eq!(
+            vec![1, 2, 3, 4, 0, 0, 1, 0xff, 0x02, 0x00],
+            Command::new(1, 2, 3, 4, vec![0xff])
+                .with_le(512)
+                .serialize()
+        );
+        // ...and a long Lc forces an extended Le
+        let ser = Command::new(1, 2, 3, 4, vec![0xaf; 512])
+            .with_le(0x10)
+            .serialize();
+        assert_eq!(vec![0, 0x02, 0x00], ser[4..7]);
+        assert_eq!(vec![0x00, 0x10], ser[ser.len() - 2..]);
+    }
+
+    #[test]
+    fn status_words() {
+        for (bytes, sw) in [
+            ([0x90, 0x00], StatusWord::Success),
+            ([0x61, 0x20], StatusWord::BytesRemaining(0x20)),
+            ([0x67, 0x00], StatusWord::WrongLength),
+            ([0x69, 0x85], StatusWord::CommandNotAllowed(0x85)),
+            ([0x6c, 0x08], StatusWord::WrongLe(0x08)),
+            ([0x6d, 0x00], StatusWord::InstructionNotSupported),
+            ([0x91, 0x00], StatusWord::Other(0x91, 0x00)),
+        ] {
+            assert_eq!(sw, StatusWord::from(bytes));
+            assert_eq!(bytes, sw.to_bytes());
+        }
+        assert_eq!("6985", StatusWord::CommandNotAllowed(0x85).to_string());
+
+        let rsp = Response::from(vec![0xaa, 0x6a, 0x82]);
+        assert_eq!(StatusWord::WrongParameters(0x82), rsp.status());
+        assert!(!rsp.status().is_success());
+    }
+
+    #[test]
+    fn is_ok_matches_status() {
+        for sw in [
+            [0x90, 0x00],
+            [0x91, 0x00],
+            [0x61, 0x10],
+            [0x91, 0x01],
+            [0x6f, 0x00],
+        ] {
+            let rsp = Response::from(sw.to_vec());
+            assert_eq!(rsp.is_ok(), rsp.status().is_success(), "{:?}", sw);
+        }
+        assert!(StatusWord::from([0x91, 0x00]).is_success());
+        assert!(!StatusWord::BytesRemaining(0x10).is_success());
+    }
+
+    #[test]
+    fn exchange_chains_get_response() {
+        let command = Command::new(1, 2, 3, 4, vec![0xff]);
+        let mut exchange = Exchange::new(command.clone(), ISO_GET_RESPONSE);
+        assert_eq!(&command, exchange.command());
+
+        assert_eq!(
+            Step::Transmit(ISO_GET_RESPONSE.with_le(2)),
+            exchange.receive(Response::from(vec![0xaa, 0xbb, 0x61, 0x02]))
+        );
+        assert_eq!(
+            Step::Transmit(ISO_GET_RESPONSE.with_le(MAX_SHORT_LE)),
+            exchange.receive(Response::from(vec![0xcc, 0x61, 0x00]))
+        );
+        assert_eq!(
+            Step::Complete(Response::from(vec![0xaa, 0xbb, 0xcc, 0xdd, 0x90, 0x00])),
+            exchange.receive(Response::from(vec![0xdd, 0x90, 0x00]))
+        );
+    }
+
+    #[test]
+    fn exchange_resends_with_corrected_le() {
+        let command = Command::new_header(1, 2, 3, 4);
+        let mut exchange = Exchange::new(command.clone(), ISO_GET_RESPONSE);
+
+        assert_eq!(
+            Step::Transmit(command.with_le(8)),
+            exchange.receive(Response::from(vec![0x6c, 0x08]))
+        );
+        // A card that keeps asking for a different Le isn't retried forever
+        assert_eq!(
+            Step::Complete(Response::from(vec![0x6c, 0x04])),
+            exchange.receive(Response::from(vec![0x6c, 0x04]))
+        );
+    }
+
+    #[test]
+    fn exchange_completes_on_error() {
+        let mut exchange = Exchange::new(Command::new_header(1, 2, 3, 4), ISO_GET_RESPONSE);
+        assert_eq!(
+            Step::Complete(Response::from(vec![0x6d, 0x00])),
+            exchange.receive(Response::from(vec![0x6d, 0x00]))
+        );
+    }
 }
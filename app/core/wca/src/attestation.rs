@@ -120,6 +120,23 @@ impl Attestation {
         &self,
         identity_cert_der: Vec<u8>,
         batch_cert_der: Vec<u8>,
+    ) -> Result<String, AttestationError> {
+        self.verify_device_identity_cert_chain_with_roots(
+            identity_cert_der,
+            batch_cert_der,
+            SILABS_FACTORY_INTERMEDIATE,
+            SILABS_DEVICE_ROOT,
+        )
+    }
+
+    /// Verify a certificate chain for a Bitkey against the given factory intermediate and device
+    /// root, rather than the production Silicon Labs certificates.
+    pub fn verify_device_identity_cert_chain_with_roots(
+        &self,
+        identity_cert_der: Vec<u8>,
+        batch_cert_der: Vec<u8>,
+        factory_intermediate_der: &[u8],
+        device_root_der: &[u8],
     ) -> Result<String, AttestationError> {
         let Ok((_, identity_cert)) = X509Certificate::from_der(&identity_cert_der) else {
             return Err(AttestationError::ParseFailure);
@@ -129,13 +146,12 @@ impl Attestation {
             return Err(AttestationError::ParseFailure);
         };
 
-        let Ok((_, silabs_factory_intermediate)) =
-            X509Certificate::from_der(SILABS_FACTORY_INTERMEDIATE)
+        let Ok((_, factory_intermediate)) = X509Certificate::from_der(factory_intermediate_der)
         else {
             return Err(AttestationError::ParseFailure);
         };
 
-        let Ok((_, silabs_root)) = X509Certificate::from_der(SILABS_DEVICE_ROOT) else {
+        let Ok((_, device_root)) = X509Certificate::from_der(device_root_der) else {
             return Err(AttestationError::ParseFailure);
         };
 
@@ -144,8 +160,8 @@ impl Attestation {
         if verify_cert_chain(vec![
             &identity_cert,
             &batch_cert,
-            &silabs_factory_intermediate,
-            &silabs_root,
+            &factory_intermediate,
+            &device_root,
         ]) {
             Ok(serial)
         } else {
@@ -11,6 +11,8 @@ pub enum State<T> {
 
 pub trait Command<T, E> {
     fn next(&self, response: Vec<u8>) -> Result<State<T>, E>;
+    /// Forget the responses seen so far, so the next call to `next` starts the command over.
+    fn restart(&self) -> Result<(), E>;
 }
 
 /// The command! macro wraps a next_gen::prelude::Generator with a concrete Command trait that's exportable via UniFFI.
@@ -105,22 +107,27 @@ macro_rules! command {
                 next_gen::generator::GeneratorState::Returned(value) => Ok($crate::command_interface::State::Result { value: value? }),
             }
         }
+
+        fn restart(&self) -> std::result::Result<(), $crate::errors::CommandError> {
+            self._lock.write()?.clear();
+            Ok(())
+        }
     };
 }
 pub use command;
 
 #[macro_export]
 macro_rules! yield_from_ {
-    ($generator_name:ident($x:expr)) => {
-        yield_from_!(yield_from_impl $generator_name(($x,)))
+    ($($generator_name:ident)::+($x:expr)) => {
+        yield_from_!(yield_from_impl $($generator_name)::+(($x,)))
     };
 
-    ($generator_name:ident($($x:expr),*)) => {
-        yield_from_!(yield_from_impl $generator_name(($($x),*)))
+    ($($generator_name:ident)::+($($x:expr),*)) => {
+        yield_from_!(yield_from_impl $($generator_name)::+(($($x),*)))
     };
 
-    (yield_from_impl $generator_name:ident($args:expr)) => {{
-        let mut gen = next_gen::generator_fn::CallBoxed::call_boxed($generator_name, $args);
+    (yield_from_impl $($generator_name:ident)::+($args:expr)) => {{
+        let mut gen = next_gen::generator_fn::CallBoxed::call_boxed($($generator_name)::+, $args);
         let mut result = Default::default();
         loop {
             match gen.as_mut().resume(result) {
@@ -188,4 +195,25 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn restart() -> Result<(), CommandError> {
+        let command = Unary::new("first argument".into());
+        command.next(vec![])?;
+        assert_eq!(
+            command.next("response".into())?,
+            State::Result {
+                value: "response".into()
+            }
+        );
+
+        command.restart()?;
+        assert_eq!(
+            command.next(vec![])?,
+            State::Data {
+                response: "unary first argument".into()
+            }
+        );
+        Ok(())
+    }
 }
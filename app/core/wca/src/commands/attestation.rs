@@ -6,14 +6,14 @@ use crate::fwpb::cert_get_rsp::CertGetRspStatus;
 use crate::fwpb::{
     wallet_rsp::Msg, CertGetCmd, CertGetRsp, HardwareAttestationCmd, HardwareAttestationRsp,
 };
+use crate::yield_from_;
 use crate::{command, errors::CommandError, wca};
 
 #[generator(yield(Vec<u8>), resume(Vec<u8>))]
 fn get_cert(kind: CertType) -> Result<Vec<u8>, CommandError> {
     let apdu: apdu::Command = CertGetCmd { kind: kind.into() }.try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -45,8 +45,7 @@ fn sign_verify_attestation_challenge(
     }
     .try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
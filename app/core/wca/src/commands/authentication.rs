@@ -1,5 +1,6 @@
 use miniscript::DescriptorPublicKey;
 
+use crate::yield_from_;
 use crate::{EllipticCurve, KeyEncoding, PublicKeyHandle, PublicKeyMetadata, SignatureContext};
 use bitcoin::{
     hashes::{sha256, Hash},
@@ -38,8 +39,7 @@ fn get_authentication_key_v2() -> Result<PublicKeyHandle, CommandError> {
     }
     .try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -65,8 +65,7 @@ fn sign_challenge_v2(challenge: Vec<u8>) -> Result<SignatureContext, CommandErro
         hash,
     }
     .try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -95,8 +94,7 @@ fn get_authentication_key() -> Result<PublicKey, CommandError> {
         ..Default::default()
     }
     .try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -132,8 +130,7 @@ fn sign_challenge(challenge: Vec<u8>) -> Result<Signature, CommandError> {
         hash,
     }
     .try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -161,8 +158,7 @@ fn sign_challenge(challenge: Vec<u8>) -> Result<Signature, CommandError> {
 fn lock_device() -> Result<bool, CommandError> {
     let apdu: apdu::Command = LockDeviceCmd {}.try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -1,6 +1,7 @@
 use next_gen::generator;
 use teltra::TelemetryIdentifiers;
 
+use crate::yield_from_;
 use crate::{
     commands::metadata::FirmwareSlot,
     errors::CommandError,
@@ -44,8 +45,7 @@ pub struct DeviceInfo {
 fn device_id() -> Result<DeviceIdentifiers, CommandError> {
     let apdu: apdu::Command = DeviceIdCmd {}.try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -74,8 +74,7 @@ fn device_id() -> Result<DeviceIdentifiers, CommandError> {
 fn telemetry_id() -> Result<TelemetryIdentifiers, CommandError> {
     let apdu: apdu::Command = TelemetryIdGetCmd {}.try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -119,8 +118,7 @@ fn telemetry_id() -> Result<TelemetryIdentifiers, CommandError> {
 fn device_info() -> Result<DeviceInfo, CommandError> {
     let apdu: apdu::Command = DeviceInfoCmd {}.try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -1,5 +1,6 @@
 use next_gen::generator;
 
+use crate::yield_from_;
 use crate::{
     errors::CommandError,
     fwpb::{
@@ -65,8 +66,7 @@ pub struct FirmwareFeatureFlagCfg {
 fn feature_flags_get() -> Result<Vec<FirmwareFeatureFlagCfg>, CommandError> {
     let apdu: apdu::Command = FeatureFlagsGetCmd {}.try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -110,8 +110,7 @@ fn feature_flags_set(flags: Vec<FirmwareFeatureFlag>, enabled: bool) -> Result<b
     }
     .try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -1,5 +1,6 @@
 use next_gen::generator;
 
+use crate::yield_from_;
 use crate::{
     errors::CommandError,
     fwpb,
@@ -36,8 +37,7 @@ fn fwup_start(patch_size: Option<u32>, fwup_mode: FwupMode) -> Result<bool, Comm
     }
     .try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -71,8 +71,7 @@ fn fwup_transfer(
     }
     .try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -105,8 +104,7 @@ fn fwup_finish(
     }
     .try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
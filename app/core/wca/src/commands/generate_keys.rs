@@ -21,8 +21,7 @@ pub(crate) fn derive(
         derivation_path: Some(derivation_path.into()),
     }
     .try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -8,14 +8,14 @@ use crate::{
         GetFingerprintEnrollmentStatusCmd, GetFingerprintEnrollmentStatusRsp, WalletRsp,
     },
 };
+use crate::{wca, yield_from_};
 
 use crate::command_interface::command;
 
 #[generator(yield(Vec<u8>), resume(Vec<u8>))]
 fn get_fingerprint_enrollment_status() -> Result<FingerprintEnrollmentStatus, CommandError> {
     let apdu: apdu::Command = GetFingerprintEnrollmentStatusCmd {}.try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = WalletRsp::decode(std::io::Cursor::new(response.data))?
         .msg
         .ok_or(CommandError::MissingMessage)?;
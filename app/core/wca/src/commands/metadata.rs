@@ -7,8 +7,9 @@ use crate::fwpb::meta_rsp::MetaRspStatus;
 use crate::fwpb::wallet_rsp::Msg;
 use crate::fwpb::{MetaCmd, MetaRsp};
 use crate::wca;
+use crate::yield_from_;
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FirmwareSlot {
     A,
     B,
@@ -30,8 +31,7 @@ pub struct FirmwareMetadata {
 fn metadata() -> Result<FirmwareMetadata, CommandError> {
     let apdu: apdu::Command = MetaCmd {}.try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
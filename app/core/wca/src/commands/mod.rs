@@ -53,6 +53,7 @@ pub use metadata::GetFirmwareMetadata;
 pub use query_authentication::QueryAuthentication;
 pub use seal_key::SealKey;
 pub use sign_sighash::SignedSighash;
+pub use sign_sighash::SignedTapSighash;
 pub use sign_transaction::SignTransaction;
 pub use start_fingerprint_enrollment::StartFingerprintEnrollment;
 pub use telemetry::EventFragment;
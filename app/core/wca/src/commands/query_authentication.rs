@@ -1,5 +1,6 @@
 use next_gen::generator;
 
+use crate::yield_from_;
 use crate::{
     errors::CommandError,
     fwpb::{
@@ -14,8 +15,7 @@ use crate::command_interface::command;
 #[generator(yield(Vec<u8>), resume(Vec<u8>))]
 fn query_authentication() -> Result<bool, CommandError> {
     let apdu: apdu::Command = QueryAuthenticationCmd {}.try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -1,18 +1,34 @@
-use bitcoin::{secp256k1::ecdsa::Signature, util::bip32::DerivationPath, Sighash};
+use bitcoin::{
+    secp256k1::{ecdsa::Signature, schnorr},
+    util::{
+        bip32::DerivationPath,
+        taproot::{TapSighashHash, TapTweakHash},
+    },
+    Sighash,
+};
 use miniscript::DescriptorPublicKey;
 use next_gen::generator;
 use prost::Message;
 
 use crate::{
     errors::CommandError,
-    fwpb::{self, derive_and_sign_rsp::DeriveAndSignRspStatus, DeriveKeyDescriptorAndSignCmd},
+    fwpb::{
+        self, derive_and_sign_rsp::DeriveAndSignRspStatus, DeriveKeyDescriptorAndSignCmd,
+        DeriveKeyDescriptorAndSignSchnorrCmd,
+    },
 };
+use crate::{wca, yield_from_};
 
 pub struct SignedSighash {
     pub signature: Signature,
     pub descriptor: DescriptorPublicKey,
 }
 
+pub struct SignedTapSighash {
+    pub signature: schnorr::Signature,
+    pub descriptor: DescriptorPublicKey,
+}
+
 #[generator(yield(Vec<u8>), resume(Vec<u8>))]
 pub(crate) fn derive_and_sign(
     sighash: Sighash,
@@ -23,8 +39,33 @@ pub(crate) fn derive_and_sign(
         hash: sighash.to_vec(),
     }
     .try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
+    let signature = signature_from_response(response)?;
+
+    Ok(Signature::from_compact(&signature)?)
+}
+
+/// Sign a taproot sighash with the key at `derivation_path`, tweaked by `tap_tweak` for key-path
+/// spends.
+#[generator(yield(Vec<u8>), resume(Vec<u8>))]
+pub(crate) fn derive_and_sign_schnorr(
+    sighash: TapSighashHash,
+    tap_tweak: Option<TapTweakHash>,
+    derivation_path: &DerivationPath,
+) -> Result<schnorr::Signature, CommandError> {
+    let apdu: apdu::Command = DeriveKeyDescriptorAndSignSchnorrCmd {
+        derivation_path: Some(derivation_path.into()),
+        hash: sighash.to_vec(),
+        tap_tweak: tap_tweak.map(|tweak| tweak.to_vec()).unwrap_or_default(),
+    }
+    .try_into()?;
+    let response = yield_from_!(wca::transceive(apdu))?;
+    let signature = signature_from_response(response)?;
+
+    Ok(schnorr::Signature::from_slice(&signature)?)
+}
+
+fn signature_from_response(response: apdu::Response) -> Result<Vec<u8>, CommandError> {
     let message = fwpb::WalletRsp::decode(std::io::Cursor::new(response.data))?
         .msg
         .ok_or(CommandError::MissingMessage)?;
@@ -32,7 +73,7 @@ pub(crate) fn derive_and_sign(
     match message {
         fwpb::wallet_rsp::Msg::DeriveAndSignRsp(fwpb::DeriveAndSignRsp { status, signature }) => {
             match DeriveAndSignRspStatus::from_i32(status) {
-                Some(DeriveAndSignRspStatus::Success) => Ok(Signature::from_compact(&signature)?),
+                Some(DeriveAndSignRspStatus::Success) => Ok(signature),
                 Some(DeriveAndSignRspStatus::DerivationFailed) => {
                     Err(CommandError::KeyGenerationFailed)
                 }
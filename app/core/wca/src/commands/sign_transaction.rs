@@ -7,14 +7,14 @@ use std::result::Result;
 
 use crate::{
     command_interface::command,
-    commands::SignedSighash,
+    commands::{SignedSighash, SignedTapSighash},
     errors::CommandError,
-    signing::{derived::DerivedKeySigner, sign, Signer},
+    signing::{derived::DerivedKeySigner, sign, sign_taproot, SignableSighash, Signer},
     yield_from_,
 };
 
 use super::generate_keys::derive;
-use super::sign_sighash::derive_and_sign;
+use super::sign_sighash::{derive_and_sign, derive_and_sign_schnorr};
 
 #[generator(yield(Vec<u8>), resume(Vec<u8>))]
 fn sign_transaction(
@@ -41,12 +41,38 @@ fn sign_transaction(
     for signable in derived_signables {
         let path = &signable.path;
         let descriptor = yield_from_!(derive(Default::default(), path))?;
-        let signature = yield_from_!(derive_and_sign(signable.sighash, path))?;
-        let signed_sighash = SignedSighash {
-            signature,
-            descriptor,
-        };
-        sign(&mut psbt, signable.input_index, signed_sighash)?
+        match signable.sighash {
+            SignableSighash::Ecdsa(sighash) => {
+                let signature = yield_from_!(derive_and_sign(sighash, path))?;
+                let signed_sighash = SignedSighash {
+                    signature,
+                    descriptor,
+                };
+                sign(&mut psbt, signable.input_index, signed_sighash)?
+            }
+            SignableSighash::TaprootKeySpend { sighash, tap_tweak } => {
+                let signature =
+                    yield_from_!(derive_and_sign_schnorr(sighash, Some(tap_tweak), path))?;
+                let signed_sighash = SignedTapSighash {
+                    signature,
+                    descriptor,
+                };
+                sign_taproot(&mut psbt, signable.input_index, None, signed_sighash)?
+            }
+            SignableSighash::TaprootScriptSpend { sighash, leaf_hash } => {
+                let signature = yield_from_!(derive_and_sign_schnorr(sighash, None, path))?;
+                let signed_sighash = SignedTapSighash {
+                    signature,
+                    descriptor,
+                };
+                sign_taproot(
+                    &mut psbt,
+                    signable.input_index,
+                    Some(leaf_hash),
+                    signed_sighash,
+                )?
+            }
+        }
     }
 
     let _ = psbt.finalize_mut(&Secp256k1::verification_only()); // Optimistically finalize the PSBT; it's OK if this fails (e.g. if the application hasn't co-signed)
@@ -1,6 +1,7 @@
 use next_gen::generator;
 use prost::Message;
 
+use crate::yield_from_;
 use crate::{
     errors::CommandError,
     fwpb::{
@@ -21,8 +22,7 @@ fn unseal_key(sealed_key: SealedKey) -> Result<UnsealedKey, CommandError> {
     }
     .try_into()?;
 
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = wca::decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
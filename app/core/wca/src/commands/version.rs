@@ -1,5 +1,6 @@
 use next_gen::generator;
 
+use crate::yield_from_;
 use crate::{errors::CommandError, wca};
 
 use crate::command_interface::command;
@@ -7,8 +8,7 @@ use crate::command_interface::command;
 #[generator(yield(Vec<u8>), resume(Vec<u8>))]
 fn version() -> Result<u16, CommandError> {
     let apdu: apdu::Command = wca::WCA::Version.try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data)
+    let response = yield_from_!(wca::transceive(apdu))?
         .data
         .try_into()
         .map_err(|_| CommandError::InvalidResponse)?;
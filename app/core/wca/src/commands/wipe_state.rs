@@ -5,14 +5,14 @@ use crate::{
     fwpb::{wallet_rsp::Msg, wipe_state_rsp::WipeStateRspStatus, WipeStateCmd, WipeStateRsp},
     wca::decode_and_check,
 };
+use crate::{wca, yield_from_};
 
 use crate::command_interface::command;
 
 #[generator(yield(Vec<u8>), resume(Vec<u8>))]
 fn wipe_state() -> Result<bool, CommandError> {
     let apdu: apdu::Command = WipeStateCmd {}.try_into()?;
-    let data = yield_!(apdu.into());
-    let response = apdu::Response::from(data);
+    let response = yield_from_!(wca::transceive(apdu))?;
     let message = decode_and_check(response)?
         .msg
         .ok_or(CommandError::MissingMessage)?;
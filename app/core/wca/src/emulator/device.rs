@@ -0,0 +1,768 @@
+use std::collections::VecDeque;
+
+use bitcoin::{
+    hashes::{sha256, Hash},
+    secp256k1::{All, KeyPair, Message, Scalar, Secp256k1},
+    util::bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey},
+};
+use prost::Message as _;
+use rand_core::{OsRng, RngCore};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    hmac,
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, Ed25519KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+
+use crate::fwpb::{
+    self,
+    cert_get_cmd::CertType,
+    cert_get_rsp::CertGetRspStatus,
+    coredump_get_cmd::CoredumpGetType,
+    coredump_get_rsp::CoredumpGetRspStatus,
+    derive_and_sign_rsp::DeriveAndSignRspStatus,
+    derive_rsp::DeriveRspStatus,
+    device_info_rsp::DeviceInfoRspStatus,
+    events_get_rsp::EventsGetRspStatus,
+    feature_flags_get_rsp::FeatureFlagsGetRspStatus,
+    feature_flags_set_rsp::FeatureFlagsSetRspStatus,
+    fwup_finish_rsp::FwupFinishRspStatus,
+    fwup_start_rsp::FwupStartRspStatus,
+    fwup_transfer_rsp::FwupTransferRspStatus,
+    get_fingerprint_enrollment_status_rsp::{
+        FingerprintEnrollmentStatus, GetFingerprintEnrollmentStatusRspStatus,
+    },
+    meta_rsp::MetaRspStatus,
+    query_authentication_rsp::QueryAuthenticationRspStatus,
+    seal_csek_rsp::SealCsekRspStatus,
+    start_fingerprint_enrollment_rsp::StartFingerprintEnrollmentRspStatus,
+    telemetry_id_get_rsp::TelemetryIdGetRspStatus,
+    unseal_csek_rsp::UnsealCsekRspStatus,
+    wallet_cmd, wallet_rsp,
+    wipe_state_rsp::WipeStateRspStatus,
+    Curve, FeatureFlag, FeatureFlagCfg, FirmwareSlot, SealedData, Status, WalletRsp,
+};
+
+use super::{TEST_BATCH_CERT, TEST_IDENTITY_CERT, TEST_IDENTITY_KEY};
+
+const SERIAL: &str = "EMU0000000000001";
+const MLB_SERIAL: &str = "EMUMLB0000000001";
+const SW_TYPE: &str = "app-a-emulator";
+const HW_REVISION: &str = "emulator";
+const SEAL_KEY_LABEL: &[u8] = b"BK-EMULATOR-SEAL-V1";
+const ATTESTATION_PREFIX: &[u8] = b"ATV1";
+const COREDUMP_FRAGMENT_SIZE: usize = 452;
+
+/// The state held by an emulated Bitkey, and the firmware's handling of each `wallet_cmd`.
+pub(crate) struct Device {
+    secp: Secp256k1<All>,
+    seed: [u8; 32],
+    enrollment: FingerprintEnrollmentStatus,
+    authenticated: bool,
+    active_slot: FirmwareSlot,
+    slot_a: fwpb::FirmwareMetadata,
+    slot_b: fwpb::FirmwareMetadata,
+    update: Option<FirmwareUpdate>,
+    feature_flags: Vec<FeatureFlagCfg>,
+    coredumps: VecDeque<Vec<u8>>,
+}
+
+struct FirmwareUpdate {
+    image: Vec<u8>,
+    chunk_size: Option<usize>,
+}
+
+impl Device {
+    pub(crate) fn new(seed: [u8; 32]) -> Self {
+        Self {
+            secp: Secp256k1::new(),
+            seed,
+            enrollment: FingerprintEnrollmentStatus::NotInProgress,
+            authenticated: false,
+            active_slot: FirmwareSlot::SlotA,
+            slot_a: firmware_metadata(&[]),
+            slot_b: fwpb::FirmwareMetadata::default(),
+            update: None,
+            feature_flags: [
+                FeatureFlag::Telemetry,
+                FeatureFlag::DeviceInfoFlag,
+                FeatureFlag::RateLimitTemplateUpdate,
+                FeatureFlag::Unlock,
+            ]
+            .into_iter()
+            .map(|flag| FeatureFlagCfg {
+                flag: flag.into(),
+                enabled: true,
+            })
+            .collect(),
+            coredumps: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn seed(&self) -> [u8; 32] {
+        self.seed
+    }
+
+    /// Skip fingerprint enrollment, as if the owner had already enrolled and is touching the
+    /// sensor.
+    pub(crate) fn enroll(&mut self) {
+        self.enrollment = FingerprintEnrollmentStatus::Complete;
+        self.authenticated = true;
+    }
+
+    /// Unlock the device, as if the owner touched the sensor. A device without an enrolled
+    /// fingerprint stays locked.
+    pub(crate) fn unlock(&mut self) {
+        self.authenticated = self.enrollment == FingerprintEnrollmentStatus::Complete;
+    }
+
+    pub(crate) fn push_coredump(&mut self, coredump: Vec<u8>) {
+        self.coredumps.push_back(coredump);
+    }
+
+    pub(crate) fn handle(&mut self, command: &[u8]) -> WalletRsp {
+        let msg = match fwpb::WalletCmd::decode(command) {
+            Ok(fwpb::WalletCmd { msg: Some(msg), .. }) => msg,
+            Ok(fwpb::WalletCmd { msg: None, .. }) => return status(Status::UnknownMessage),
+            Err(_) => return status(Status::Error),
+        };
+
+        let rsp = match msg {
+            wallet_cmd::Msg::MetaCmd(_) => self.meta(),
+            wallet_cmd::Msg::DeviceIdCmd(_) => self.device_id(),
+            wallet_cmd::Msg::DeviceInfoCmd(_) => self.device_info(),
+            wallet_cmd::Msg::TelemetryIdGetCmd(_) => self.telemetry_id(),
+            wallet_cmd::Msg::StartFingerprintEnrollmentCmd(_) => {
+                self.start_fingerprint_enrollment()
+            }
+            wallet_cmd::Msg::GetFingerprintEnrollmentStatusCmd(_) => {
+                self.get_fingerprint_enrollment_status()
+            }
+            wallet_cmd::Msg::QueryAuthenticationCmd(_) => self.query_authentication(),
+            wallet_cmd::Msg::LockDeviceCmd(_) => self.lock_device(),
+            wallet_cmd::Msg::WipeStateCmd(_) => self.wipe_state(),
+            wallet_cmd::Msg::DeriveKeyDescriptorCmd(cmd) => {
+                self.derive_key_descriptor(cmd.network, cmd.derivation_path)
+            }
+            wallet_cmd::Msg::DeriveKeyDescriptorAndSignCmd(cmd) => {
+                self.derive_and_sign(cmd.derivation_path, cmd.hash)
+            }
+            wallet_cmd::Msg::DeriveKeyDescriptorAndSignSchnorrCmd(cmd) => {
+                self.derive_and_sign_schnorr(cmd.derivation_path, cmd.hash, cmd.tap_tweak)
+            }
+            wallet_cmd::Msg::DerivePublicKeyCmd(cmd) => {
+                self.derive_public_key(cmd.curve, cmd.label)
+            }
+            wallet_cmd::Msg::DerivePublicKeyAndSignCmd(cmd) => {
+                self.derive_public_key_and_sign(cmd.curve, cmd.label, cmd.hash)
+            }
+            #[allow(deprecated)] // The emulator doesn't establish a secure channel
+            wallet_cmd::Msg::SealCsekCmd(cmd) => self.seal_csek(cmd.unsealed_csek),
+            wallet_cmd::Msg::UnsealCsekCmd(cmd) => self.unseal_csek(cmd.sealed_csek),
+            wallet_cmd::Msg::FwupStartCmd(_) => self.fwup_start(),
+            wallet_cmd::Msg::FwupTransferCmd(cmd) => {
+                self.fwup_transfer(cmd.sequence_id, cmd.fwup_data, cmd.offset)
+            }
+            wallet_cmd::Msg::FwupFinishCmd(cmd) => {
+                self.fwup_finish(cmd.app_properties_offset, cmd.signature_offset)
+            }
+            wallet_cmd::Msg::CoredumpGetCmd(cmd) => self.coredump_get(cmd.r#type, cmd.offset),
+            wallet_cmd::Msg::EventsGetCmd(_) => self.events_get(),
+            wallet_cmd::Msg::FeatureFlagsGetCmd(_) => self.feature_flags_get(),
+            wallet_cmd::Msg::FeatureFlagsSetCmd(cmd) => self.feature_flags_set(cmd.flags),
+            wallet_cmd::Msg::CertGetCmd(cmd) => self.cert_get(cmd.kind),
+            wallet_cmd::Msg::HardwareAttestationCmd(cmd) => self.hardware_attestation(cmd.nonce),
+            _ => Err(Status::UnknownMessage),
+        };
+
+        match rsp {
+            Ok(msg) => WalletRsp {
+                msg: Some(msg),
+                status: Status::Success.into(),
+                ..Default::default()
+            },
+            Err(s) => status(s),
+        }
+    }
+
+    fn meta(&self) -> Result<wallet_rsp::Msg, Status> {
+        Ok(wallet_rsp::Msg::MetaRsp(fwpb::MetaRsp {
+            rsp_status: MetaRspStatus::Success.into(),
+            meta_bl: Some(firmware_metadata(&[])),
+            meta_slot_a: Some(self.slot_a.clone()),
+            meta_slot_b: Some(self.slot_b.clone()),
+            active_slot: self.active_slot.into(),
+        }))
+    }
+
+    fn active_firmware(&self) -> &fwpb::FirmwareMetadata {
+        match self.active_slot {
+            FirmwareSlot::SlotB => &self.slot_b,
+            _ => &self.slot_a,
+        }
+    }
+
+    fn device_id(&self) -> Result<wallet_rsp::Msg, Status> {
+        Ok(wallet_rsp::Msg::DeviceIdRsp(fwpb::DeviceIdRsp {
+            mlb_serial: MLB_SERIAL.into(),
+            mlb_serial_valid: true,
+            assy_serial: SERIAL.into(),
+            assy_serial_valid: true,
+        }))
+    }
+
+    fn device_info(&self) -> Result<wallet_rsp::Msg, Status> {
+        Ok(wallet_rsp::Msg::DeviceInfoRsp(fwpb::DeviceInfoRsp {
+            rsp_status: DeviceInfoRspStatus::Success.into(),
+            version: self.active_firmware().version.clone(),
+            serial: SERIAL.into(),
+            sw_type: SW_TYPE.into(),
+            hw_revision: HW_REVISION.into(),
+            active_slot: self.active_slot.into(),
+            battery_charge: 100_000,
+            vcell: 4200,
+            avg_current_ma: 0,
+            battery_cycles: 0,
+            secure_boot_config: fwpb::SecureBootConfig::Dev.into(),
+        }))
+    }
+
+    fn telemetry_id(&self) -> Result<wallet_rsp::Msg, Status> {
+        Ok(wallet_rsp::Msg::TelemetryIdGetRsp(
+            fwpb::TelemetryIdGetRsp {
+                rsp_status: TelemetryIdGetRspStatus::Success.into(),
+                serial: SERIAL.into(),
+                version: self.active_firmware().version.clone(),
+                sw_type: SW_TYPE.into(),
+                hw_revision: HW_REVISION.into(),
+            },
+        ))
+    }
+
+    fn start_fingerprint_enrollment(&mut self) -> Result<wallet_rsp::Msg, Status> {
+        let rsp_status =
+            if self.enrollment == FingerprintEnrollmentStatus::Complete && !self.authenticated {
+                StartFingerprintEnrollmentRspStatus::Unauthenticated
+            } else {
+                self.enrollment = FingerprintEnrollmentStatus::Incomplete;
+                StartFingerprintEnrollmentRspStatus::Success
+            };
+
+        Ok(wallet_rsp::Msg::StartFingerprintEnrollmentRsp(
+            fwpb::StartFingerprintEnrollmentRsp {
+                rsp_status: rsp_status.into(),
+            },
+        ))
+    }
+
+    /// The emulated owner keeps their finger on the sensor, so an enrollment in progress
+    /// completes the first time its status is checked.
+    fn get_fingerprint_enrollment_status(&mut self) -> Result<wallet_rsp::Msg, Status> {
+        if self.enrollment == FingerprintEnrollmentStatus::Incomplete {
+            self.enroll();
+        }
+
+        Ok(wallet_rsp::Msg::GetFingerprintEnrollmentStatusRsp(
+            fwpb::GetFingerprintEnrollmentStatusRsp {
+                rsp_status: GetFingerprintEnrollmentStatusRspStatus::Success.into(),
+                fingerprint_status: self.enrollment.into(),
+                pass_count: 0,
+                fail_count: 0,
+            },
+        ))
+    }
+
+    fn query_authentication(&self) -> Result<wallet_rsp::Msg, Status> {
+        let rsp_status = match self.authenticated {
+            true => QueryAuthenticationRspStatus::Authenticated,
+            false => QueryAuthenticationRspStatus::Unauthenticated,
+        };
+
+        Ok(wallet_rsp::Msg::QueryAuthenticationRsp(
+            fwpb::QueryAuthenticationRsp {
+                rsp_status: rsp_status.into(),
+            },
+        ))
+    }
+
+    fn lock_device(&mut self) -> Result<wallet_rsp::Msg, Status> {
+        self.authenticated = false;
+        Ok(wallet_rsp::Msg::LockDeviceRsp(fwpb::LockDeviceRsp {}))
+    }
+
+    fn wipe_state(&mut self) -> Result<wallet_rsp::Msg, Status> {
+        let rsp_status = if self.authenticated {
+            let mut seed = [0u8; 32];
+            OsRng.fill_bytes(&mut seed);
+            *self = Self {
+                slot_a: self.slot_a.clone(),
+                slot_b: self.slot_b.clone(),
+                active_slot: self.active_slot,
+                ..Self::new(seed)
+            };
+            WipeStateRspStatus::Success
+        } else {
+            WipeStateRspStatus::Unauthenticated
+        };
+
+        Ok(wallet_rsp::Msg::WipeStateRsp(fwpb::WipeStateRsp {
+            rsp_status: rsp_status.into(),
+        }))
+    }
+
+    fn derive(
+        &self,
+        network: bitcoin::Network,
+        derivation_path: Option<fwpb::DerivationPath>,
+    ) -> Option<(ExtendedPrivKey, ExtendedPrivKey, DerivationPath)> {
+        let path: DerivationPath = derivation_path
+            .map(|path| path.child.into_iter().map(ChildNumber::from).collect())
+            .unwrap_or_default();
+        let master = ExtendedPrivKey::new_master(network, &self.seed).ok()?;
+        let derived = master.derive_priv(&self.secp, &path).ok()?;
+        Some((master, derived, path))
+    }
+
+    fn derive_key_descriptor(
+        &self,
+        network: i32,
+        derivation_path: Option<fwpb::DerivationPath>,
+    ) -> Result<wallet_rsp::Msg, Status> {
+        let derive_rsp = |status: DeriveRspStatus, descriptor| {
+            Ok(wallet_rsp::Msg::DeriveRsp(fwpb::DeriveRsp {
+                status: status.into(),
+                descriptor,
+            }))
+        };
+
+        if !self.authenticated {
+            return derive_rsp(DeriveRspStatus::Unauthenticated, None);
+        }
+        let Some(network) = fwpb::BtcNetwork::from_i32(network) else {
+            return derive_rsp(DeriveRspStatus::Error, None);
+        };
+        let Some((master, derived, path)) = self.derive(network.into(), derivation_path) else {
+            return derive_rsp(DeriveRspStatus::DerivationFailed, None);
+        };
+
+        let xpub = ExtendedPubKey::from_priv(&self.secp, &derived);
+        derive_rsp(
+            DeriveRspStatus::Success,
+            Some(fwpb::KeyDescriptor {
+                origin_fingerprint: master.fingerprint(&self.secp)[..].to_vec(),
+                origin_path: match path.is_master() {
+                    true => None,
+                    false => Some((&path).into()),
+                },
+                bare_bip32_key: xpub.encode().to_vec(),
+                xpub_path: None,
+                wildcard: fwpb::Wildcard::Unhardened.into(),
+            }),
+        )
+    }
+
+    fn derive_and_sign(
+        &self,
+        derivation_path: Option<fwpb::DerivationPath>,
+        hash: Vec<u8>,
+    ) -> Result<wallet_rsp::Msg, Status> {
+        let signature = self.signing_key(derivation_path).and_then(|derived| {
+            let message = Message::from_slice(&hash).map_err(|_| DeriveAndSignRspStatus::Error)?;
+            Ok(self
+                .secp
+                .sign_ecdsa(&message, &derived.private_key)
+                .serialize_compact()
+                .to_vec())
+        });
+
+        Ok(derive_and_sign_rsp(signature))
+    }
+
+    fn derive_and_sign_schnorr(
+        &self,
+        derivation_path: Option<fwpb::DerivationPath>,
+        hash: Vec<u8>,
+        tap_tweak: Vec<u8>,
+    ) -> Result<wallet_rsp::Msg, Status> {
+        let signature = self.signing_key(derivation_path).and_then(|derived| {
+            let message = Message::from_slice(&hash).map_err(|_| DeriveAndSignRspStatus::Error)?;
+            let mut keypair = KeyPair::from_secret_key(&self.secp, &derived.private_key);
+            if !tap_tweak.is_empty() {
+                let tweak = tap_tweak
+                    .try_into()
+                    .ok()
+                    .and_then(|tweak| Scalar::from_be_bytes(tweak).ok())
+                    .ok_or(DeriveAndSignRspStatus::Error)?;
+                keypair = keypair
+                    .add_xonly_tweak(&self.secp, &tweak)
+                    .map_err(|_| DeriveAndSignRspStatus::DerivationFailed)?;
+            }
+            Ok(self
+                .secp
+                .sign_schnorr_no_aux_rand(&message, &keypair)
+                .as_ref()
+                .to_vec())
+        });
+
+        Ok(derive_and_sign_rsp(signature))
+    }
+
+    fn signing_key(
+        &self,
+        derivation_path: Option<fwpb::DerivationPath>,
+    ) -> Result<ExtendedPrivKey, DeriveAndSignRspStatus> {
+        if !self.authenticated {
+            return Err(DeriveAndSignRspStatus::Unauthenticated);
+        }
+
+        // The network only affects how keys are serialized, not the keys themselves.
+        self.derive(bitcoin::Network::Bitcoin, derivation_path)
+            .map(|(_, derived, _)| derived)
+            .ok_or(DeriveAndSignRspStatus::DerivationFailed)
+    }
+
+    fn labelled_key(&self, curve: i32, label: &[u8]) -> Result<Ed25519KeyPair, Status> {
+        if !self.authenticated {
+            return Err(Status::Unauthenticated);
+        }
+        if Curve::from_i32(curve) != Some(Curve::Ed25519) {
+            return Err(Status::FeatureNotSupported);
+        }
+
+        let key = hmac::Key::new(hmac::HMAC_SHA256, &self.seed);
+        Ed25519KeyPair::from_seed_unchecked(hmac::sign(&key, label).as_ref())
+            .map_err(|_| Status::KeyDerivationFailed)
+    }
+
+    fn derive_public_key(&self, curve: i32, label: Vec<u8>) -> Result<wallet_rsp::Msg, Status> {
+        use ring::signature::KeyPair as _;
+
+        let keypair = self.labelled_key(curve, &label)?;
+        Ok(wallet_rsp::Msg::DerivePublicKeyRsp(
+            fwpb::DerivePublicKeyRsp {
+                pubkey: keypair.public_key().as_ref().to_vec(),
+            },
+        ))
+    }
+
+    fn derive_public_key_and_sign(
+        &self,
+        curve: i32,
+        label: Vec<u8>,
+        hash: Vec<u8>,
+    ) -> Result<wallet_rsp::Msg, Status> {
+        use ring::signature::KeyPair as _;
+
+        let keypair = self.labelled_key(curve, &label)?;
+        Ok(wallet_rsp::Msg::DerivePublicKeyAndSignRsp(
+            fwpb::DerivePublicKeyAndSignRsp {
+                pubkey: keypair.public_key().as_ref().to_vec(),
+                signature: keypair.sign(&hash).as_ref().to_vec(),
+            },
+        ))
+    }
+
+    fn seal_key(&self) -> LessSafeKey {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, &self.seed);
+        let key_material = hmac::sign(&key, SEAL_KEY_LABEL);
+        LessSafeKey::new(
+            UnboundKey::new(&AES_256_GCM, key_material.as_ref()).expect("valid AES-256 key"),
+        )
+    }
+
+    fn seal_csek(&self, unsealed_csek: Vec<u8>) -> Result<wallet_rsp::Msg, Status> {
+        if !self.authenticated {
+            return Err(Status::Unauthenticated);
+        }
+
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let mut data = unsealed_csek;
+        let sealed_csek = self
+            .seal_key()
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut data,
+            )
+            .map(|tag| SealedData {
+                data,
+                nonce: nonce.to_vec(),
+                tag: tag.as_ref().to_vec(),
+            });
+
+        Ok(wallet_rsp::Msg::SealCsekRsp(match sealed_csek {
+            Ok(sealed_csek) => fwpb::SealCsekRsp {
+                rsp_status: SealCsekRspStatus::Success.into(),
+                sealed_csek: Some(sealed_csek),
+            },
+            Err(_) => fwpb::SealCsekRsp {
+                rsp_status: SealCsekRspStatus::SealError.into(),
+                sealed_csek: None,
+            },
+        }))
+    }
+
+    fn unseal_csek(&self, sealed_csek: Option<SealedData>) -> Result<wallet_rsp::Msg, Status> {
+        let unsealed_csek = if !self.authenticated {
+            Err(UnsealCsekRspStatus::Unauthenticated)
+        } else {
+            sealed_csek
+                .and_then(|sealed| {
+                    let nonce = Nonce::try_assume_unique_for_key(&sealed.nonce).ok()?;
+                    let mut in_out = [sealed.data, sealed.tag].concat();
+                    self.seal_key()
+                        .open_in_place(nonce, Aad::empty(), &mut in_out)
+                        .ok()
+                        .map(|plaintext| plaintext.to_vec())
+                })
+                .ok_or(UnsealCsekRspStatus::UnsealError)
+        };
+
+        Ok(wallet_rsp::Msg::UnsealCsekRsp(match unsealed_csek {
+            Ok(unsealed_csek) => fwpb::UnsealCsekRsp {
+                rsp_status: UnsealCsekRspStatus::Success.into(),
+                unsealed_csek,
+            },
+            Err(rsp_status) => fwpb::UnsealCsekRsp {
+                rsp_status: rsp_status.into(),
+                unsealed_csek: vec![],
+            },
+        }))
+    }
+
+    fn fwup_start(&mut self) -> Result<wallet_rsp::Msg, Status> {
+        let rsp_status = if self.authenticated {
+            self.update = Some(FirmwareUpdate {
+                image: vec![],
+                chunk_size: None,
+            });
+            FwupStartRspStatus::Success
+        } else {
+            FwupStartRspStatus::Unauthenticated
+        };
+
+        Ok(wallet_rsp::Msg::FwupStartRsp(fwpb::FwupStartRsp {
+            rsp_status: rsp_status.into(),
+        }))
+    }
+
+    /// Write a chunk of the image at `offset + sequence_id * chunk_size`, where the chunk size is
+    /// taken from the first chunk of each asset.
+    fn fwup_transfer(
+        &mut self,
+        sequence_id: u32,
+        fwup_data: Vec<u8>,
+        offset: u32,
+    ) -> Result<wallet_rsp::Msg, Status> {
+        let rsp_status = match (self.authenticated, self.update.as_mut()) {
+            (false, _) => FwupTransferRspStatus::Unauthenticated,
+            (true, None) => FwupTransferRspStatus::Error,
+            (true, Some(update)) => {
+                if sequence_id == 0 {
+                    update.chunk_size = Some(fwup_data.len());
+                }
+                match update.chunk_size {
+                    Some(chunk_size) => {
+                        let start = offset as usize + sequence_id as usize * chunk_size;
+                        let end = start + fwup_data.len();
+                        if update.image.len() < end {
+                            update.image.resize(end, 0xff);
+                        }
+                        update.image[start..end].copy_from_slice(&fwup_data);
+                        FwupTransferRspStatus::Success
+                    }
+                    None => FwupTransferRspStatus::Error,
+                }
+            }
+        };
+
+        Ok(wallet_rsp::Msg::FwupTransferRsp(fwpb::FwupTransferRsp {
+            rsp_status: rsp_status.into(),
+        }))
+    }
+
+    /// Install the transferred image into the inactive slot and boot from it. The emulator
+    /// doesn't check the image's signature, only that the offsets fall inside the image.
+    fn fwup_finish(
+        &mut self,
+        app_properties_offset: u32,
+        signature_offset: u32,
+    ) -> Result<wallet_rsp::Msg, Status> {
+        let rsp_status = match (self.authenticated, self.update.take()) {
+            (false, _) => FwupFinishRspStatus::Unauthenticated,
+            (true, None) => FwupFinishRspStatus::Error,
+            (true, Some(FirmwareUpdate { image, .. })) => {
+                if app_properties_offset as usize >= image.len()
+                    || signature_offset as usize >= image.len()
+                {
+                    FwupFinishRspStatus::SignatureInvalid
+                } else {
+                    let metadata = firmware_metadata(&image);
+                    match self.active_slot {
+                        FirmwareSlot::SlotB => {
+                            self.slot_a = metadata;
+                            self.active_slot = FirmwareSlot::SlotA;
+                        }
+                        _ => {
+                            self.slot_b = metadata;
+                            self.active_slot = FirmwareSlot::SlotB;
+                        }
+                    }
+                    FwupFinishRspStatus::Success
+                }
+            }
+        };
+
+        Ok(wallet_rsp::Msg::FwupFinishRsp(fwpb::FwupFinishRsp {
+            rsp_status: rsp_status.into(),
+        }))
+    }
+
+    /// Serve the oldest coredump in fragments; it is discarded once its last fragment is read.
+    fn coredump_get(&mut self, kind: i32, offset: u32) -> Result<wallet_rsp::Msg, Status> {
+        let coredump_rsp = |rsp_status: CoredumpGetRspStatus, coredump_fragment, coredump_count| {
+            Ok(wallet_rsp::Msg::CoredumpGetRsp(fwpb::CoredumpGetRsp {
+                rsp_status: rsp_status.into(),
+                coredump_fragment,
+                coredump_count,
+            }))
+        };
+
+        match CoredumpGetType::from_i32(kind) {
+            Some(CoredumpGetType::Count) => coredump_rsp(
+                CoredumpGetRspStatus::Success,
+                None,
+                self.coredumps.len() as u32,
+            ),
+            Some(CoredumpGetType::Coredump) => {
+                let Some(coredump) = self.coredumps.front() else {
+                    return coredump_rsp(CoredumpGetRspStatus::Error, None, 0);
+                };
+                let start = (offset as usize).min(coredump.len());
+                let end = (start + COREDUMP_FRAGMENT_SIZE).min(coredump.len());
+                let data = coredump[start..end].to_vec();
+                let complete = end == coredump.len();
+                if complete {
+                    self.coredumps.pop_front();
+                }
+
+                coredump_rsp(
+                    CoredumpGetRspStatus::Success,
+                    Some(fwpb::CoredumpFragment {
+                        data,
+                        offset: end as i32,
+                        complete,
+                        coredumps_remaining: self.coredumps.len() as i32,
+                    }),
+                    self.coredumps.len() as u32,
+                )
+            }
+            _ => coredump_rsp(CoredumpGetRspStatus::Error, None, 0),
+        }
+    }
+
+    fn events_get(&self) -> Result<wallet_rsp::Msg, Status> {
+        Ok(wallet_rsp::Msg::EventsGetRsp(fwpb::EventsGetRsp {
+            rsp_status: EventsGetRspStatus::Success.into(),
+            version: 1,
+            fragment: Some(fwpb::EventFragment {
+                data: vec![],
+                remaining_size: 0,
+            }),
+        }))
+    }
+
+    fn feature_flags_get(&self) -> Result<wallet_rsp::Msg, Status> {
+        Ok(wallet_rsp::Msg::FeatureFlagsGetRsp(
+            fwpb::FeatureFlagsGetRsp {
+                rsp_status: FeatureFlagsGetRspStatus::Success.into(),
+                flags: self.feature_flags.clone(),
+            },
+        ))
+    }
+
+    fn feature_flags_set(&mut self, flags: Vec<FeatureFlagCfg>) -> Result<wallet_rsp::Msg, Status> {
+        for cfg in flags {
+            match self.feature_flags.iter_mut().find(|f| f.flag == cfg.flag) {
+                Some(existing) => existing.enabled = cfg.enabled,
+                None => self.feature_flags.push(cfg),
+            }
+        }
+
+        Ok(wallet_rsp::Msg::FeatureFlagsSetRsp(
+            fwpb::FeatureFlagsSetRsp {
+                rsp_status: FeatureFlagsSetRspStatus::Success.into(),
+            },
+        ))
+    }
+
+    fn cert_get(&self, kind: i32) -> Result<wallet_rsp::Msg, Status> {
+        let (rsp_status, cert) = match CertType::from_i32(kind) {
+            Some(CertType::BatchCert) => (CertGetRspStatus::Success, TEST_BATCH_CERT.to_vec()),
+            Some(CertType::DeviceHostCert) => {
+                (CertGetRspStatus::Success, TEST_IDENTITY_CERT.to_vec())
+            }
+            Some(CertType::DeviceSeCert) => (CertGetRspStatus::Unimplemented, vec![]),
+            _ => (CertGetRspStatus::CertReadFail, vec![]),
+        };
+
+        Ok(wallet_rsp::Msg::CertGetRsp(fwpb::CertGetRsp {
+            rsp_status: rsp_status.into(),
+            cert,
+        }))
+    }
+
+    fn hardware_attestation(&self, nonce: Vec<u8>) -> Result<wallet_rsp::Msg, Status> {
+        let rng = SystemRandom::new();
+        let identity_key =
+            EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, TEST_IDENTITY_KEY, &rng)
+                .map_err(|_| Status::Error)?;
+        let signature = identity_key
+            .sign(&rng, &[ATTESTATION_PREFIX, &nonce].concat())
+            .map_err(|_| Status::SigningFailed)?;
+
+        Ok(wallet_rsp::Msg::HardwareAttestationRsp(
+            fwpb::HardwareAttestationRsp {
+                signature: signature.as_ref().to_vec(),
+            },
+        ))
+    }
+}
+
+fn status(status: Status) -> WalletRsp {
+    WalletRsp {
+        status: status.into(),
+        ..Default::default()
+    }
+}
+
+fn derive_and_sign_rsp(signature: Result<Vec<u8>, DeriveAndSignRspStatus>) -> wallet_rsp::Msg {
+    wallet_rsp::Msg::DeriveAndSignRsp(match signature {
+        Ok(signature) => fwpb::DeriveAndSignRsp {
+            status: DeriveAndSignRspStatus::Success.into(),
+            signature,
+        },
+        Err(status) => fwpb::DeriveAndSignRsp {
+            status: status.into(),
+            signature: vec![],
+        },
+    })
+}
+
+fn firmware_metadata(image: &[u8]) -> fwpb::FirmwareMetadata {
+    fwpb::FirmwareMetadata {
+        valid: true,
+        git_id: "emulator".into(),
+        git_branch: "main".into(),
+        version: Some(fwpb::Semver {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        }),
+        build: "dev".into(),
+        timestamp: 0,
+        hash: sha256::Hash::hash(image).to_vec(),
+        hw_revision: HW_REVISION.into(),
+    }
+}
@@ -0,0 +1,287 @@
+//! An in-process emulation of a Bitkey, for exercising the `commands` end-to-end without a reader
+//! attached.
+//!
+//! [`EmulatedTransactor`] speaks the same WCA APDUs as the firmware: it reassembles `wallet_cmd`
+//! protos sent across `Proto` and `ProtoContinuation` APDUs, answers them from an emulated device
+//! holding a seed, fingerprint authentication state and firmware metadata, and returns responses
+//! that don't fit in a single APDU through `GetResponse`.
+//!
+//! Attestation is answered with certificates issued by a test CA, which can be verified with
+//! [`Attestation::verify_device_identity_cert_chain_with_roots`] against [`TEST_FACTORY_CERT`] and
+//! [`TEST_DEVICE_ROOT_CERT`].
+//!
+//! [`Attestation::verify_device_identity_cert_chain_with_roots`]: crate::attestation::Attestation::verify_device_identity_cert_chain_with_roots
+
+mod device;
+
+use std::sync::Mutex;
+
+use apdu::StatusWord;
+use rand_core::{OsRng, RngCore};
+
+use crate::pcsc::Transactor;
+use crate::wca::{
+    MAX_PROTO_SIZE, MAX_WCA_BUFFER_SIZE, WCA_CLA, WCA_INS_GET_RESPONSE, WCA_INS_PROTO,
+    WCA_INS_PROTO_CONTINUATION, WCA_INS_VERSION,
+};
+use device::Device;
+
+pub const TEST_DEVICE_ROOT_CERT: &[u8] = include_bytes!("test-ca/root.der");
+pub const TEST_FACTORY_CERT: &[u8] = include_bytes!("test-ca/factory.der");
+pub const TEST_BATCH_CERT: &[u8] = include_bytes!("test-ca/batch.der");
+pub const TEST_IDENTITY_CERT: &[u8] = include_bytes!("test-ca/identity.der");
+const TEST_IDENTITY_KEY: &[u8] = include_bytes!("test-ca/identity-key.pk8");
+
+const WCA_VERSION: u16 = 1;
+
+const CONDITIONS_NOT_SATISFIED: StatusWord = StatusWord::CommandNotAllowed(0x85);
+
+pub struct EmulatedTransactor {
+    state: Mutex<State>,
+}
+
+struct State {
+    device: Device,
+    command: Option<Vec<u8>>,
+    response: Vec<u8>,
+}
+
+impl Default for EmulatedTransactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmulatedTransactor {
+    /// A factory-fresh device with a random seed and no fingerprint enrolled.
+    pub fn new() -> Self {
+        let mut seed = [0u8; 32];
+        OsRng.fill_bytes(&mut seed);
+        Self::from_seed(seed)
+    }
+
+    /// A factory-fresh device with no fingerprint enrolled, whose keys derive from `seed`.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self {
+            state: Mutex::new(State {
+                device: Device::new(seed),
+                command: None,
+                response: vec![],
+            }),
+        }
+    }
+
+    /// Mark a fingerprint as already enrolled, and unlock the device.
+    pub fn enrolled(self) -> Self {
+        self.state.lock().unwrap().device.enroll();
+        self
+    }
+
+    /// Queue a coredump to be served by `GetCoredumpFragment`.
+    pub fn with_coredump(self, coredump: Vec<u8>) -> Self {
+        self.state.lock().unwrap().device.push_coredump(coredump);
+        self
+    }
+
+    /// The seed the device's keys derive from; this changes when the device is wiped.
+    pub fn seed(&self) -> [u8; 32] {
+        self.state.lock().unwrap().device.seed()
+    }
+
+    /// Touch the fingerprint sensor, unlocking the device if a fingerprint is enrolled.
+    pub fn unlock(&self) {
+        self.state.lock().unwrap().device.unlock();
+    }
+}
+
+impl Transactor for EmulatedTransactor {
+    fn transmit(&self, buffer: &[u8]) -> Result<Vec<u8>, pcsc::Error> {
+        Ok(self.state.lock().unwrap().process(buffer))
+    }
+
+    fn reset(&mut self) -> Result<(), pcsc::Error> {
+        let state = self.state.get_mut().unwrap();
+        state.command = None;
+        state.response.clear();
+        Ok(())
+    }
+}
+
+impl State {
+    fn process(&mut self, buffer: &[u8]) -> Vec<u8> {
+        let Some(command) = parse_command(buffer) else {
+            return StatusWord::WrongLength.to_bytes().to_vec();
+        };
+        if command.cla != WCA_CLA {
+            return StatusWord::ClassNotSupported.to_bytes().to_vec();
+        }
+
+        match command.ins {
+            WCA_INS_VERSION => [WCA_VERSION.to_be_bytes(), StatusWord::Success.to_bytes()].concat(),
+            WCA_INS_PROTO | WCA_INS_PROTO_CONTINUATION => {
+                let fragment = command.data.unwrap_or_default();
+                let fragment_size = u16::from_be_bytes([command.p1, command.p2]) as usize;
+                if fragment_size != fragment.len() {
+                    return StatusWord::WrongLength.to_bytes().to_vec();
+                }
+
+                let proto = match (command.ins, self.command.take()) {
+                    (WCA_INS_PROTO, _) => fragment,
+                    (_, Some(proto)) => [proto, fragment].concat(),
+                    (_, None) => return CONDITIONS_NOT_SATISFIED.to_bytes().to_vec(),
+                };
+
+                // A full fragment means the host has more of this proto to send.
+                if fragment_size == MAX_PROTO_SIZE {
+                    self.command = Some(proto);
+                    return StatusWord::Success.to_bytes().to_vec();
+                }
+
+                self.response = prost::Message::encode_to_vec(&self.device.handle(&proto));
+                self.next_response()
+            }
+            WCA_INS_GET_RESPONSE if !self.response.is_empty() => self.next_response(),
+            WCA_INS_GET_RESPONSE => CONDITIONS_NOT_SATISFIED.to_bytes().to_vec(),
+            _ => StatusWord::InstructionNotSupported.to_bytes().to_vec(),
+        }
+    }
+
+    /// Return as much of the pending response as fits in one APDU, and report how much remains
+    /// to be read with `GetResponse`.
+    fn next_response(&mut self) -> Vec<u8> {
+        let split = self.response.len().min(MAX_WCA_BUFFER_SIZE);
+        let mut rv: Vec<u8> = self.response.drain(..split).collect();
+        match self.response.len() {
+            0 => rv.extend(StatusWord::Success.to_bytes()),
+            remaining => rv.extend(
+                StatusWord::BytesRemaining(u8::try_from(remaining).unwrap_or(0)).to_bytes(),
+            ),
+        }
+        rv
+    }
+}
+
+/// Parse a command APDU with a short or extended Lc, ignoring any trailing Le.
+fn parse_command(buffer: &[u8]) -> Option<apdu::Command> {
+    if buffer.len() < 4 {
+        return None;
+    }
+    let (header, body) = buffer.split_at(4);
+    let (data, rest) = match body {
+        [] => (None, &[][..]),
+        [0, hi, lo, rest @ ..] => {
+            let lc = u16::from_be_bytes([*hi, *lo]) as usize;
+            (Some(rest.get(..lc)?), rest.get(lc..)?)
+        }
+        [lc, rest @ ..] => (Some(rest.get(..*lc as usize)?), rest.get(*lc as usize..)?),
+    };
+    if rest.len() > 3 {
+        return None;
+    }
+
+    Some(apdu::Command {
+        cla: header[0],
+        ins: header[1],
+        p1: header[2],
+        p2: header[3],
+        data: data.map(|d| d.to_vec()),
+        le: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+    use crate::fwpb::{self, wallet_cmd::Msg, WalletRsp};
+
+    fn transmit(transactor: &EmulatedTransactor, command: apdu::Command) -> apdu::Response {
+        apdu::Response::from(transactor.transmit(&command.serialize()).unwrap())
+    }
+
+    #[test]
+    fn version() {
+        let transactor = EmulatedTransactor::new();
+        let response = transmit(
+            &transactor,
+            apdu::Command::new_header(WCA_CLA, WCA_INS_VERSION, 0, 0),
+        );
+        assert!(response.is_ok());
+        assert_eq!(response.data, WCA_VERSION.to_be_bytes());
+    }
+
+    #[test]
+    fn rejects_unknown_instructions() {
+        let transactor = EmulatedTransactor::new();
+        let response = transmit(&transactor, apdu::Command::new_header(0x00, 0xa4, 0, 0));
+        assert_eq!(response.status(), StatusWord::ClassNotSupported);
+        let response = transmit(&transactor, apdu::Command::new_header(WCA_CLA, 0x01, 0, 0));
+        assert_eq!(response.status(), StatusWord::InstructionNotSupported);
+        let response = transmit(
+            &transactor,
+            apdu::Command::new_header(WCA_CLA, WCA_INS_GET_RESPONSE, 0, 0),
+        );
+        assert_eq!(response.status(), CONDITIONS_NOT_SATISFIED);
+    }
+
+    #[test]
+    fn reassembles_continued_protos() {
+        let transactor = EmulatedTransactor::new();
+        // A feature flags command padded out past a single APDU with repeated flags.
+        let flags = (0..300)
+            .map(|_| fwpb::FeatureFlagCfg {
+                flag: fwpb::FeatureFlag::Unlock.into(),
+                enabled: true,
+            })
+            .collect();
+        let msg = Msg::FeatureFlagsSetCmd(fwpb::FeatureFlagsSetCmd { flags });
+        let commands: Vec<apdu::Command> = msg.try_into().unwrap();
+        assert!(commands.len() > 1);
+
+        let mut responses = commands
+            .into_iter()
+            .map(|command| transmit(&transactor, command))
+            .collect::<Vec<_>>();
+        let last = responses.pop().unwrap();
+        assert!(responses.iter().all(|r| r.is_ok() && r.data.is_empty()));
+        assert!(last.is_ok());
+        assert!(matches!(
+            WalletRsp::decode(&*last.data).unwrap().msg,
+            Some(fwpb::wallet_rsp::Msg::FeatureFlagsSetRsp(_))
+        ));
+    }
+
+    #[test]
+    fn chains_long_responses() {
+        let transactor = EmulatedTransactor::new();
+        let mut state = transactor.state.lock().unwrap();
+        state.response = vec![0xaa; MAX_WCA_BUFFER_SIZE + 10];
+
+        let first = apdu::Response::from(state.next_response());
+        assert_eq!(first.data.len(), MAX_WCA_BUFFER_SIZE);
+        assert_eq!(first.status(), StatusWord::BytesRemaining(10));
+        drop(state);
+
+        let rest = transmit(
+            &transactor,
+            apdu::Command::new_header(WCA_CLA, WCA_INS_GET_RESPONSE, 0, 0),
+        );
+        assert!(rest.is_ok());
+        assert_eq!(rest.data.len(), 10);
+    }
+
+    #[test]
+    fn answers_undecodable_protos_with_an_error_status() {
+        let transactor = EmulatedTransactor::new();
+        let garbage = vec![0xff; 3];
+        let response = transmit(
+            &transactor,
+            apdu::Command::new(WCA_CLA, WCA_INS_PROTO, 0, garbage.len() as u8, garbage),
+        );
+        assert_eq!(
+            WalletRsp::decode(&*response.data).unwrap().status,
+            fwpb::Status::Error as i32
+        );
+    }
+}
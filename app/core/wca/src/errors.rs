@@ -80,6 +80,8 @@ pub enum CommandError {
     CertReadFail,
     #[error("attestation error")]
     AttestationError,
+    #[error("command was unsuccessful: status word {0}")]
+    UnsuccessfulStatusWord(apdu::StatusWord),
 }
 
 impl<T> From<PoisonError<T>> for CommandError {
@@ -3,6 +3,8 @@
 pub mod attestation;
 pub mod command_interface;
 pub mod commands;
+#[cfg(feature = "pcsc")]
+pub mod emulator;
 pub mod errors;
 #[cfg(feature = "pcsc")]
 pub mod pcsc;
@@ -2,7 +2,16 @@ use crate::{
     command_interface::{Command, State},
     errors::CommandError,
 };
-use pcsc::{Card, Context, Protocols, Scope, ShareMode, MAX_BUFFER_SIZE_EXTENDED};
+use std::{
+    ffi::{CStr, CString},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use pcsc::{
+    Card, Context, Protocols, ReaderState, Scope, ShareMode, State as ReaderStatus,
+    MAX_BUFFER_SIZE_EXTENDED, PNP_NOTIFICATION,
+};
 
 pub trait Transactor: Send + Sync {
     fn transmit(&self, buffer: &[u8]) -> Result<Vec<u8>, pcsc::Error>;
@@ -15,15 +24,30 @@ pub trait Performer<T: Transactor + ?Sized> {
         TransactorError: From<E>;
 }
 
+/// How many times a command is started over after the card is reset or removed part way through.
+const MAX_COMMAND_RESTARTS: usize = 3;
+
 impl<T: Transactor + ?Sized> Performer<T> for T {
     fn perform<V, E>(&self, command: impl Command<V, E>) -> Result<V, TransactorError>
     where
         TransactorError: From<E>,
     {
+        let mut restarts = 0;
         let mut response = vec![];
         loop {
             response = match command.next(response)? {
-                State::Data { response } => self.transmit(&response)?,
+                State::Data { response } => match self.transmit(&response) {
+                    // The card lost whatever part of the command it had already been sent, so
+                    // resending this APDU alone would leave it mid-way through a chunked command.
+                    Err(pcsc::Error::ResetCard | pcsc::Error::RemovedCard)
+                        if restarts < MAX_COMMAND_RESTARTS =>
+                    {
+                        restarts += 1;
+                        command.restart()?;
+                        vec![]
+                    }
+                    result => result?,
+                },
                 State::Result { value } => break Ok(value),
             }
         }
@@ -43,13 +67,29 @@ impl Transactor for NullTransactor {
 }
 
 pub struct PCSCTransactor {
-    card: Option<Card>,
+    context: Context,
+    reader: CString,
+    card: Mutex<Option<Card>>,
+    timeout: Option<Duration>,
+}
+
+/// Which reader to connect to, and how long to wait for a card to be presented to it.
+#[derive(Clone, Debug, Default)]
+pub struct ReaderOptions {
+    /// Connect to the reader with this name, or failing that the first reader whose name
+    /// contains it (ignoring case). Any reader will do if unset.
+    pub reader: Option<String>,
+    /// How long to wait for a card, both when connecting and when reconnecting after the card is
+    /// removed mid-command. If unset, a missing card is an error straight away.
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum TransactorError {
     #[error("no smartcard reader not found")]
     ReaderNotFound,
+    #[error("timed out waiting for a card")]
+    Timeout,
     #[error("reader error")]
     ReaderError(#[from] pcsc::Error),
     #[error("command error")]
@@ -57,41 +97,250 @@ pub enum TransactorError {
 }
 
 impl PCSCTransactor {
+    /// Connect to the card on the first reader, failing if there isn't one.
     pub fn new() -> Result<Self, TransactorError> {
+        Self::connect(&ReaderOptions::default())
+    }
+
+    pub fn connect(options: &ReaderOptions) -> Result<Self, TransactorError> {
         let context = Context::establish(Scope::User)?;
-        let readers = context.list_readers_owned()?;
-        let reader = readers.first().ok_or(TransactorError::ReaderNotFound)?;
-        let card = context.connect(reader, ShareMode::Shared, Protocols::ANY)?;
-        Ok(Self { card: Some(card) })
+        let reader = match options.timeout {
+            Some(timeout) => wait_for_card(&context, options.reader.as_deref(), timeout)?,
+            None => find_readers(&context, options.reader.as_deref())?
+                .into_iter()
+                .next()
+                .ok_or(TransactorError::ReaderNotFound)?,
+        };
+        let card = context.connect(&reader, ShareMode::Shared, Protocols::ANY)?;
+        Ok(Self {
+            context,
+            reader,
+            card: Mutex::new(Some(card)),
+            timeout: options.timeout,
+        })
     }
+
+    /// The name of the reader the card is connected through.
+    pub fn reader(&self) -> &CStr {
+        &self.reader
+    }
+
+    /// Reconnect to the card after it was reset or removed, waiting for it to be presented again
+    /// if need be.
+    fn reconnect(&self, card: &mut Card) -> Result<(), pcsc::Error> {
+        if let Some(timeout) = self.timeout {
+            let deadline = Instant::now() + timeout;
+            let mut states = [ReaderState::new(self.reader.clone(), ReaderStatus::UNAWARE)];
+            self.context
+                .get_status_change(Duration::ZERO, &mut states)?;
+            while !is_card_present(&states[0]) {
+                states[0].sync_current_state();
+                self.context.get_status_change(
+                    deadline.saturating_duration_since(Instant::now()),
+                    &mut states,
+                )?;
+            }
+        }
+
+        card.reconnect(
+            ShareMode::Shared,
+            Protocols::ANY,
+            pcsc::Disposition::LeaveCard,
+        )
+    }
+}
+
+/// Readers matching `pattern`, with exact name matches first.
+fn find_readers(context: &Context, pattern: Option<&str>) -> Result<Vec<CString>, pcsc::Error> {
+    let readers = match context.list_readers_owned() {
+        Ok(readers) => readers,
+        Err(pcsc::Error::NoReadersAvailable) => vec![],
+        Err(err) => return Err(err),
+    };
+    let Some(pattern) = pattern else {
+        return Ok(readers);
+    };
+
+    let (mut exact, partial): (Vec<_>, Vec<_>) = readers
+        .into_iter()
+        .filter(|reader| {
+            reader
+                .to_string_lossy()
+                .to_lowercase()
+                .contains(&pattern.to_lowercase())
+        })
+        .partition(|reader| reader.to_string_lossy() == pattern);
+    exact.extend(partial);
+    Ok(exact)
+}
+
+/// Block until a card is presented to a reader matching `pattern`, watching for readers being
+/// plugged in along the way, and return that reader.
+fn wait_for_card(
+    context: &Context,
+    pattern: Option<&str>,
+    timeout: Duration,
+) -> Result<CString, TransactorError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let mut states: Vec<ReaderState> = find_readers(context, pattern)?
+            .into_iter()
+            .map(|reader| ReaderState::new(reader, ReaderStatus::UNAWARE))
+            .chain([ReaderState::new(PNP_NOTIFICATION(), ReaderStatus::UNAWARE)])
+            .collect();
+
+        context.get_status_change(Duration::ZERO, &mut states)?;
+        if let Some(state) = states.iter().find(|state| is_card_present(state)) {
+            return Ok(state.name().to_owned());
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(TransactorError::Timeout);
+        }
+        states.iter_mut().for_each(ReaderState::sync_current_state);
+        match context.get_status_change(remaining, &mut states) {
+            Ok(()) => continue,
+            Err(pcsc::Error::Timeout) => return Err(TransactorError::Timeout),
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+fn is_card_present(state: &ReaderState) -> bool {
+    state.event_state().contains(ReaderStatus::PRESENT)
+        && !state.event_state().contains(ReaderStatus::MUTE)
 }
 
 impl Drop for PCSCTransactor {
     fn drop(&mut self) {
-        self.card
-            .take()
-            .unwrap()
-            .disconnect(pcsc::Disposition::LeaveCard)
-            .unwrap_or_else(|_| panic!("Could not disconnect PCSC card"));
+        // Disconnecting fails if the card has already gone away, which leaves nothing to clean up.
+        if let Some(card) = self.card.get_mut().ok().and_then(Option::take) {
+            let _ = card.disconnect(pcsc::Disposition::LeaveCard);
+        }
     }
 }
 
 impl Transactor for PCSCTransactor {
     fn transmit(&self, send_buffer: &[u8]) -> Result<Vec<u8>, pcsc::Error> {
+        let mut card = self.card.lock().map_err(|_| pcsc::Error::InternalError)?;
+        let card = card.as_mut().ok_or(pcsc::Error::NoSmartcard)?;
         let mut receive_buffer = [0; MAX_BUFFER_SIZE_EXTENDED];
-        Ok(self
-            .card
-            .as_ref()
-            .unwrap()
-            .transmit(send_buffer, &mut receive_buffer)?
-            .into())
+
+        // A flaky tap resets or drops the card between APDUs. Pick the session back up, then
+        // report the error so the command is started over from its first APDU.
+        match card.transmit(send_buffer, &mut receive_buffer) {
+            Err(err @ (pcsc::Error::ResetCard | pcsc::Error::RemovedCard)) => {
+                self.reconnect(card)?;
+                Err(err)
+            }
+            result => Ok(result?.into()),
+        }
     }
 
     fn reset(&mut self) -> Result<(), pcsc::Error> {
-        self.card.as_mut().unwrap().reconnect(
+        let card = self
+            .card
+            .get_mut()
+            .map_err(|_| pcsc::Error::InternalError)?;
+        card.as_mut().ok_or(pcsc::Error::NoSmartcard)?.reconnect(
             ShareMode::Shared,
             Protocols::ANY,
             pcsc::Disposition::LeaveCard,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use next_gen::generator;
+
+    use super::*;
+    use crate::command_interface::command;
+
+    /// Sends three single byte fragments and returns the responses to them.
+    #[generator(yield(Vec<u8>), resume(Vec<u8>))]
+    fn fragments() -> Result<Vec<u8>, CommandError> {
+        let mut responses = vec![];
+        for fragment in 0..3 {
+            responses.extend(yield_!(vec![fragment]));
+        }
+        Ok(responses)
+    }
+    command!(Fragments = fragments -> Vec<u8>);
+
+    /// Echoes every APDU back, except that the transmits numbered in `failures` fail.
+    struct FlakyTransactor {
+        failures: Vec<(usize, pcsc::Error)>,
+        sent: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl FlakyTransactor {
+        fn new(failures: Vec<(usize, pcsc::Error)>) -> Self {
+            Self {
+                failures,
+                sent: Mutex::new(vec![]),
+            }
+        }
+
+        fn sent(&self) -> Vec<u8> {
+            self.sent.lock().unwrap().concat()
+        }
+    }
+
+    impl Transactor for FlakyTransactor {
+        fn transmit(&self, buffer: &[u8]) -> Result<Vec<u8>, pcsc::Error> {
+            let mut sent = self.sent.lock().unwrap();
+            let attempt = sent.len();
+            sent.push(buffer.to_vec());
+            match self.failures.iter().find(|(at, _)| *at == attempt) {
+                Some((_, err)) => Err(*err),
+                None => Ok(buffer.to_vec()),
+            }
+        }
+
+        fn reset(&mut self) -> Result<(), pcsc::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reset_card_restarts_the_command() {
+        let transactor = FlakyTransactor::new(vec![(2, pcsc::Error::ResetCard)]);
+        assert_eq!(transactor.perform(Fragments::new()).unwrap(), [0, 1, 2]);
+        assert_eq!(transactor.sent(), [0, 1, 2, 0, 1, 2]);
+    }
+
+    #[test]
+    fn removed_card_restarts_the_command() {
+        let transactor = FlakyTransactor::new(vec![
+            (1, pcsc::Error::RemovedCard),
+            (3, pcsc::Error::RemovedCard),
+        ]);
+        assert_eq!(transactor.perform(Fragments::new()).unwrap(), [0, 1, 2]);
+        assert_eq!(transactor.sent(), [0, 1, 0, 1, 0, 1, 2]);
+    }
+
+    #[test]
+    fn repeated_resets_give_up() {
+        let failures = (0..=MAX_COMMAND_RESTARTS)
+            .map(|at| (at, pcsc::Error::ResetCard))
+            .collect();
+        let transactor = FlakyTransactor::new(failures);
+        assert!(matches!(
+            transactor.perform(Fragments::new()),
+            Err(TransactorError::ReaderError(pcsc::Error::ResetCard))
+        ));
+        assert_eq!(transactor.sent().len(), MAX_COMMAND_RESTARTS + 1);
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let transactor = FlakyTransactor::new(vec![(1, pcsc::Error::Timeout)]);
+        assert!(matches!(
+            transactor.perform(Fragments::new()),
+            Err(TransactorError::ReaderError(pcsc::Error::Timeout))
+        ));
+        assert_eq!(transactor.sent(), [0, 1]);
+    }
+}
@@ -3,10 +3,14 @@ use bitcoin::{
     util::{
         bip32::{DerivationPath, Fingerprint},
         sighash::SighashCache,
+        taproot::TapTweakHash,
     },
 };
 
-use super::{is_finalised, sighash, DescriptorExtendedKey, Error, Signable, Signer};
+use super::{
+    is_finalised, is_taproot, sighash, tap_sighash, DescriptorExtendedKey, Error, Signable,
+    SignableSighash, Signer,
+};
 
 pub(crate) struct DerivedKeySigner {
     spending_key: DescriptorExtendedKey,
@@ -16,40 +20,116 @@ impl DerivedKeySigner {
     pub(crate) fn new(spending_key: DescriptorExtendedKey) -> Self {
         Self { spending_key }
     }
-}
 
-impl Signer for DerivedKeySigner {
-    fn signables_for(&self, psbt: &PartiallySignedTransaction) -> Result<Vec<Signable>, Error> {
-        let mut cache: SighashCache<&bitcoin::Transaction> = SighashCache::new(&psbt.unsigned_tx);
+    fn ecdsa_signables_for(
+        &self,
+        cache: &mut SighashCache<&bitcoin::Transaction>,
+        psbt: &PartiallySignedTransaction,
+        input_index: usize,
+    ) -> Result<Vec<Signable>, Error> {
+        let input = &psbt.inputs[input_index];
+        let sighash = sighash(cache, psbt, input_index)?;
+
         let mut signables = vec![];
-        for (input_index, input) in psbt.inputs.iter().enumerate() {
-            if is_finalised(input) {
+        for (target_public_key, (target_origin_fingerprint, target_derivation_path)) in
+            &input.bip32_derivation
+        {
+            if input
+                .partial_sigs
+                .contains_key(&bitcoin::PublicKey::new(*target_public_key))
+            {
                 continue;
             }
 
-            let sighash = sighash(&mut cache, psbt, input_index)?;
+            if let Some(path) = path_to_derive(
+                &self.spending_key,
+                target_origin_fingerprint,
+                target_derivation_path,
+            ) {
+                signables.push(Signable {
+                    path,
+                    sighash: SignableSighash::Ecdsa(sighash),
+                    input_index,
+                });
+            }
+        }
 
-            for (target_public_key, (target_origin_fingerprint, target_derivation_path)) in
-                &input.bip32_derivation
-            {
+        Ok(signables)
+    }
+
+    fn taproot_signables_for(
+        &self,
+        cache: &mut SighashCache<&bitcoin::Transaction>,
+        psbt: &PartiallySignedTransaction,
+        input_index: usize,
+    ) -> Result<Vec<Signable>, Error> {
+        let input = &psbt.inputs[input_index];
+
+        let mut signables = vec![];
+        for (
+            target_public_key,
+            (leaf_hashes, (target_origin_fingerprint, target_derivation_path)),
+        ) in &input.tap_key_origins
+        {
+            let path = match path_to_derive(
+                &self.spending_key,
+                target_origin_fingerprint,
+                target_derivation_path,
+            ) {
+                Some(path) => path,
+                None => continue,
+            };
+
+            if input.tap_internal_key == Some(*target_public_key) && input.tap_key_sig.is_none() {
+                signables.push(Signable {
+                    path: path.clone(),
+                    sighash: SignableSighash::TaprootKeySpend {
+                        sighash: tap_sighash(cache, psbt, input_index, None)?,
+                        tap_tweak: TapTweakHash::from_key_and_tweak(
+                            *target_public_key,
+                            input.tap_merkle_root,
+                        ),
+                    },
+                    input_index,
+                });
+            }
+
+            for leaf_hash in leaf_hashes {
                 if input
-                    .partial_sigs
-                    .contains_key(&bitcoin::PublicKey::new(*target_public_key))
+                    .tap_script_sigs
+                    .contains_key(&(*target_public_key, *leaf_hash))
                 {
                     continue;
                 }
 
-                if let Some(path) = path_to_derive(
-                    &self.spending_key,
-                    target_origin_fingerprint,
-                    target_derivation_path,
-                ) {
-                    signables.push(Signable {
-                        path,
-                        sighash,
-                        input_index,
-                    });
-                }
+                signables.push(Signable {
+                    path: path.clone(),
+                    sighash: SignableSighash::TaprootScriptSpend {
+                        sighash: tap_sighash(cache, psbt, input_index, Some(*leaf_hash))?,
+                        leaf_hash: *leaf_hash,
+                    },
+                    input_index,
+                });
+            }
+        }
+
+        Ok(signables)
+    }
+}
+
+impl Signer for DerivedKeySigner {
+    fn signables_for(&self, psbt: &PartiallySignedTransaction) -> Result<Vec<Signable>, Error> {
+        let mut cache: SighashCache<&bitcoin::Transaction> = SighashCache::new(&psbt.unsigned_tx);
+        let mut signables = vec![];
+        for (input_index, input) in psbt.inputs.iter().enumerate() {
+            if is_finalised(input) {
+                continue;
+            }
+
+            if is_taproot(input) {
+                signables.extend(self.taproot_signables_for(&mut cache, psbt, input_index)?);
+            } else {
+                signables.extend(self.ecdsa_signables_for(&mut cache, psbt, input_index)?);
             }
         }
 
@@ -93,9 +173,17 @@ mod tests {
     use std::str::FromStr;
 
     use bdk::wallet::{get_funded_wallet, AddressIndex};
-    use miniscript::{Descriptor, DescriptorPublicKey};
+    use bitcoin::{
+        secp256k1::{KeyPair, Message, Secp256k1},
+        util::bip32::{ExtendedPrivKey, ExtendedPubKey},
+        Network,
+    };
+    use miniscript::{psbt::PsbtExt, Descriptor, DescriptorPublicKey};
 
-    use crate::signing::Signer;
+    use crate::{
+        commands::SignedTapSighash,
+        signing::{sign_taproot, SignableSighash, Signer},
+    };
 
     use super::DerivedKeySigner;
 
@@ -198,9 +286,102 @@ mod tests {
         assert!(signables.is_empty());
     }
 
+    #[test]
+    fn test_signs_taproot_key_path() {
+        let psbt_dpub = DescriptorPublicKey::from_str("[96ae1927/86'/1'/0']tpubDDTqca3h8xPvEas4gMwWuqVhnaPyfBQapLj3jkr7j7M9WVBDx6PiVec5XJBbWgP4UmuLSYW9pr36Lc2iyCLJZ2KQD2ggAX2dyRcVbcM9Ygn/*").unwrap();
+        let signing_dxpub = match psbt_dpub {
+            DescriptorPublicKey::XPub(ref dxpub) => dxpub.clone(),
+            _ => unimplemented!(),
+        };
+
+        let psbt = get_drain_psbt_for(&format!("tr({psbt_dpub})"));
+        let signables = DerivedKeySigner::new(signing_dxpub)
+            .signables_for(&psbt)
+            .unwrap();
+
+        assert!(!signables.is_empty());
+        assert!(signables
+            .iter()
+            .all(|s| matches!(s.sighash, SignableSighash::TaprootKeySpend { .. })));
+    }
+
+    #[test]
+    fn test_signs_taproot_script_path() {
+        let internal_dpub = DescriptorPublicKey::from_str("tpubD6NzVbkrYhZ4XFo7hggmFF9qDqwrR9aqZv6j2Sgp1N5aVyxyMXxQG14grtRa3ob8ddZqxbd2hbPU7dEXvPRDRuQJ3NsMaGDaZXkLEewdthy/*").unwrap();
+        let psbt_dpub = DescriptorPublicKey::from_str("[96ae1927/86'/1'/0']tpubDDTqca3h8xPvEas4gMwWuqVhnaPyfBQapLj3jkr7j7M9WVBDx6PiVec5XJBbWgP4UmuLSYW9pr36Lc2iyCLJZ2KQD2ggAX2dyRcVbcM9Ygn/*").unwrap();
+        let signing_dxpub = match psbt_dpub {
+            DescriptorPublicKey::XPub(ref dxpub) => dxpub.clone(),
+            _ => unimplemented!(),
+        };
+
+        let psbt = get_drain_psbt_for(&format!("tr({internal_dpub},pk({psbt_dpub}))"));
+        let signables = DerivedKeySigner::new(signing_dxpub)
+            .signables_for(&psbt)
+            .unwrap();
+
+        assert!(!signables.is_empty());
+        assert!(signables
+            .iter()
+            .all(|s| matches!(s.sighash, SignableSighash::TaprootScriptSpend { .. })));
+    }
+
+    #[test]
+    fn test_taproot_key_path_signature_finalizes() {
+        let secp = Secp256k1::new();
+        let xprv = ExtendedPrivKey::new_master(Network::Testnet, &[7u8; 32]).unwrap();
+        let xpub = ExtendedPubKey::from_priv(&secp, &xprv);
+        let psbt_dpub = DescriptorPublicKey::from_str(&format!("{xpub}/*")).unwrap();
+        let signing_dxpub = match psbt_dpub {
+            DescriptorPublicKey::XPub(ref dxpub) => dxpub.clone(),
+            _ => unimplemented!(),
+        };
+
+        let mut psbt = get_drain_psbt_for(&format!("tr({psbt_dpub})"));
+        let signables = DerivedKeySigner::new(signing_dxpub)
+            .signables_for(&psbt)
+            .unwrap();
+        assert!(!signables.is_empty());
+
+        // Emulate the hardware: derive, tweak and sign in software.
+        for signable in signables {
+            let (sighash, tap_tweak) = match signable.sighash {
+                SignableSighash::TaprootKeySpend { sighash, tap_tweak } => (sighash, tap_tweak),
+                _ => unreachable!(),
+            };
+            let child = xprv.derive_priv(&secp, &signable.path).unwrap();
+            let keypair = KeyPair::from_secret_key(&secp, &child.private_key)
+                .add_xonly_tweak(&secp, &tap_tweak.to_scalar())
+                .unwrap();
+            let signature =
+                secp.sign_schnorr(&Message::from_slice(&sighash[..]).unwrap(), &keypair);
+            let descriptor = DescriptorPublicKey::from_str(&format!(
+                "{}",
+                ExtendedPubKey::from_priv(&secp, &child)
+            ))
+            .unwrap();
+
+            sign_taproot(
+                &mut psbt,
+                signable.input_index,
+                None,
+                SignedTapSighash {
+                    signature,
+                    descriptor,
+                },
+            )
+            .unwrap();
+        }
+
+        assert!(psbt.finalize_mut(&secp).is_ok());
+    }
+
     fn get_drain_psbt(dpub: DescriptorPublicKey) -> bitcoin::psbt::PartiallySignedTransaction {
         let descriptor = Descriptor::<DescriptorPublicKey>::new_wpkh(dpub).unwrap();
-        let (wallet, _, _) = get_funded_wallet(&descriptor.to_string());
+        get_drain_psbt_for(&descriptor.to_string())
+    }
+
+    fn get_drain_psbt_for(descriptor: &str) -> bitcoin::psbt::PartiallySignedTransaction {
+        let (wallet, _, _) = get_funded_wallet(descriptor);
         let mut builder = wallet.build_tx();
         builder.drain_wallet().drain_to(
             wallet
@@ -5,9 +5,10 @@ use bitcoin::{
     psbt::{Input, PartiallySignedTransaction},
     util::{
         bip32::{ChildNumber, DerivationPath, ExtendedPubKey},
-        sighash::SighashCache,
+        sighash::{self, SighashCache},
+        taproot::{TapLeafHash, TapSighashHash, TapTweakHash},
     },
-    EcdsaSig, Sighash, Transaction,
+    EcdsaSig, SchnorrSig, Sighash, Transaction,
 };
 use miniscript::{
     descriptor::{DescriptorSecretKey, DescriptorXKey},
@@ -15,7 +16,7 @@ use miniscript::{
     DescriptorPublicKey,
 };
 
-use crate::commands::SignedSighash;
+use crate::commands::{SignedSighash, SignedTapSighash};
 
 type DescriptorExtendedKey = DescriptorXKey<ExtendedPubKey>;
 
@@ -25,17 +26,33 @@ pub enum Error {
     MissingHdKeypath,
     #[error(transparent)]
     InvalidSighash(#[from] SighashError),
-    #[error("no taproot signing support, yet!")]
-    TapRootUnsupported,
+    #[error("sighash does not match the input's script type")]
+    MismatchedSighash,
     #[error("attempted sign with a descriptor lacking an xpub")]
     InvalidDescriptor,
     #[error("non-standard ECDSA sighash type")]
     NonStandardSighashType(#[from] NonStandardSighashType),
+    #[error("invalid Schnorr sighash type")]
+    InvalidSchnorrSighashType(#[from] sighash::Error),
+}
+
+pub(crate) enum SignableSighash {
+    Ecdsa(Sighash),
+    /// BIP-341 key-path spend; the derived key is tweaked before signing.
+    TaprootKeySpend {
+        sighash: TapSighashHash,
+        tap_tweak: TapTweakHash,
+    },
+    /// BIP-342 script-path spend of a single leaf, signed with the untweaked derived key.
+    TaprootScriptSpend {
+        sighash: TapSighashHash,
+        leaf_hash: TapLeafHash,
+    },
 }
 
 pub(crate) struct Signable {
     pub(crate) path: DerivationPath,
-    pub(crate) sighash: Sighash,
+    pub(crate) sighash: SignableSighash,
     pub(crate) input_index: usize,
 }
 
@@ -47,6 +64,15 @@ fn is_finalised(input: &Input) -> bool {
     input.final_script_sig.is_some() || input.final_script_witness.is_some()
 }
 
+fn is_taproot(input: &Input) -> bool {
+    input.tap_internal_key.is_some()
+        || !input.tap_key_origins.is_empty()
+        || input
+            .witness_utxo
+            .as_ref()
+            .map_or(false, |utxo| utxo.script_pubkey.is_v1_p2tr())
+}
+
 pub(crate) fn sign(
     psbt: &mut PartiallySignedTransaction,
     input_index: usize,
@@ -71,17 +97,61 @@ pub(crate) fn sign(
     Ok(())
 }
 
+/// Attach a Schnorr signature to a taproot input: the key-path signature when `leaf_hash` is
+/// `None`, otherwise the script-path signature for that leaf.
+pub(crate) fn sign_taproot(
+    psbt: &mut PartiallySignedTransaction,
+    input_index: usize,
+    leaf_hash: Option<TapLeafHash>,
+    signed_sighash: SignedTapSighash,
+) -> Result<(), Error> {
+    let input = &mut psbt.inputs[input_index];
+
+    let (public_key, _) = match signed_sighash.descriptor {
+        DescriptorPublicKey::XPub(xpub) => xpub.xkey.public_key.x_only_public_key(),
+        _ => return Err(Error::InvalidDescriptor),
+    };
+    assert!(input.tap_key_origins.contains_key(&public_key));
+
+    let signature = SchnorrSig {
+        sig: signed_sighash.signature,
+        hash_ty: input.schnorr_hash_ty()?,
+    };
+    match leaf_hash {
+        None => input.tap_key_sig = Some(signature),
+        Some(leaf_hash) => {
+            input
+                .tap_script_sigs
+                .insert((public_key, leaf_hash), signature);
+        }
+    }
+
+    Ok(())
+}
+
 pub(crate) fn sighash(
     cache: &mut SighashCache<&Transaction>,
     psbt: &PartiallySignedTransaction,
     input_index: usize,
 ) -> Result<Sighash, Error> {
     match psbt.sighash_msg(input_index, cache, None)? {
-        PsbtSighashMsg::TapSighash(_) => Err(Error::TapRootUnsupported),
+        PsbtSighashMsg::TapSighash(_) => Err(Error::MismatchedSighash),
         PsbtSighashMsg::EcdsaSighash(sighash) => Ok(sighash),
     }
 }
 
+pub(crate) fn tap_sighash(
+    cache: &mut SighashCache<&Transaction>,
+    psbt: &PartiallySignedTransaction,
+    input_index: usize,
+    leaf_hash: Option<TapLeafHash>,
+) -> Result<TapSighashHash, Error> {
+    match psbt.sighash_msg(input_index, cache, leaf_hash)? {
+        PsbtSighashMsg::TapSighash(sighash) => Ok(sighash),
+        PsbtSighashMsg::EcdsaSighash(_) => Err(Error::MismatchedSighash),
+    }
+}
+
 pub trait ExtendDerivationPath {
     fn extend_derivation_path(&self, path: &[ChildNumber]) -> Self;
 }
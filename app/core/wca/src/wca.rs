@@ -1,17 +1,18 @@
+use next_gen::generator;
 use prost::Message;
 
-use crate::errors::EncodeError;
+use crate::errors::{CommandError, EncodeError};
 use std::time::SystemTime;
 
-const WCA_CLA: u8 = 0x87;
-const WCA_INS_VERSION: u8 = 0x74;
-const WCA_INS_PROTO: u8 = 0x75;
-const WCA_INS_PROTO_CONTINUATION: u8 = 0x77;
-const WCA_INS_GET_RESPONSE: u8 = 0x78;
+pub(crate) const WCA_CLA: u8 = 0x87;
+pub(crate) const WCA_INS_VERSION: u8 = 0x74;
+pub(crate) const WCA_INS_PROTO: u8 = 0x75;
+pub(crate) const WCA_INS_PROTO_CONTINUATION: u8 = 0x77;
+pub(crate) const WCA_INS_GET_RESPONSE: u8 = 0x78;
 
-const MAX_WCA_BUFFER_SIZE: usize = 512;
+pub(crate) const MAX_WCA_BUFFER_SIZE: usize = 512;
 const APDU_OVERHEAD_SIZE: usize = 7; // This could be 5 in some situation ... but why bother?
-const MAX_PROTO_SIZE: usize = MAX_WCA_BUFFER_SIZE - APDU_OVERHEAD_SIZE;
+pub(crate) const MAX_PROTO_SIZE: usize = MAX_WCA_BUFFER_SIZE - APDU_OVERHEAD_SIZE;
 
 pub enum WCA {
     Version,
@@ -104,6 +105,7 @@ fn build_cmd(msg: crate::fwpb::wallet_cmd::Msg) -> crate::fwpb::WalletCmd {
 }
 
 adpu_from_proto!(DeriveKeyDescriptorAndSignCmd);
+adpu_from_proto!(DeriveKeyDescriptorAndSignSchnorrCmd);
 adpu_from_proto!(DeriveKeyDescriptorCmd);
 adpu_from_proto!(DeviceIdCmd);
 adpu_from_proto!(EventsGetCmd);
@@ -157,10 +159,29 @@ impl TryFrom<crate::fwpb::wallet_cmd::Msg> for Vec<apdu::Command> {
     }
 }
 
+/// Exchange a command APDU with the hardware, following `GetResponse` chaining until the whole
+/// response has been read.
+#[generator(yield(Vec<u8>), resume(Vec<u8>))]
+pub(crate) fn transceive(command: apdu::Command) -> Result<apdu::Response, CommandError> {
+    let mut exchange = apdu::Exchange::new(command, WCA::GetResponse.try_into()?);
+    let mut data = yield_!(exchange.command().clone().into());
+    loop {
+        match exchange.receive(apdu::Response::from(data)) {
+            apdu::Step::Transmit(command) => data = yield_!(command.into()),
+            apdu::Step::Complete(response) => break Ok(response),
+        }
+    }
+}
+
 /// Decode an APDU response into a protobuf, and check for errors set on the global status fields.
 pub fn decode_and_check(
     response: apdu::Response,
 ) -> Result<crate::fwpb::WalletRsp, crate::errors::CommandError> {
+    if !response.is_ok() {
+        return Err(crate::errors::CommandError::UnsuccessfulStatusWord(
+            response.status(),
+        ));
+    }
     let message = crate::fwpb::WalletRsp::decode(std::io::Cursor::new(response.data))?;
 
     match crate::fwpb::Status::from_i32(message.status) {
@@ -197,3 +218,50 @@ pub fn decode_and_check(
         None => Ok(message), // TODO(W-1211): Same as above comment.
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use next_gen::generator::GeneratorState;
+    use next_gen::generator_fn::CallBoxed;
+
+    use super::*;
+    use crate::errors::CommandError;
+
+    #[test]
+    fn transceive_follows_get_response_chaining() {
+        let command = apdu::Command::new_header(WCA_CLA, WCA_INS_VERSION, 0, 0);
+        let mut gen = CallBoxed::call_boxed(transceive, (command.clone(),));
+
+        assert!(matches!(
+            gen.as_mut().resume(vec![]),
+            GeneratorState::Yielded(apdu) if apdu == command.serialize()
+        ));
+        assert!(matches!(
+            gen.as_mut().resume(vec![0xaa, 0x61, 0x01]),
+            GeneratorState::Yielded(apdu) if apdu == [WCA_CLA, WCA_INS_GET_RESPONSE, 0, 0, 1]
+        ));
+        assert!(matches!(
+            gen.as_mut().resume(vec![0xbb, 0x90, 0x00]),
+            GeneratorState::Returned(Ok(response)) if response.data == [0xaa, 0xbb] && response.is_ok()
+        ));
+    }
+
+    #[test]
+    fn decode_and_check_rejects_failed_status_words() {
+        assert!(matches!(
+            decode_and_check(apdu::Response::from(vec![0x6f, 0x00])),
+            Err(CommandError::UnsuccessfulStatusWord(
+                apdu::StatusWord::NoPreciseDiagnosis
+            ))
+        ));
+    }
+
+    #[test]
+    fn decode_and_check_accepts_every_successful_status_word() {
+        for sw in [[0x90, 0x00], [0x91, 0x00]] {
+            let response = apdu::Response::from(sw.to_vec());
+            assert!(response.is_ok());
+            assert!(decode_and_check(response).is_ok());
+        }
+    }
+}
@@ -186,3 +186,212 @@ mod recordings {
         assert!(finalized);
     }
 }
+
+#[cfg(feature = "pcsc")]
+mod emulated {
+    use bdk::{database::AnyDatabase, wallet::AddressIndex, Wallet};
+    use bitcoin::{
+        hashes::sha256,
+        secp256k1::{Message, Secp256k1},
+        util::bip32::ChildNumber,
+    };
+    use miniscript::{descriptor::DescriptorXKey, Descriptor, DescriptorPublicKey};
+    use wca::{
+        attestation::Attestation,
+        commands::{
+            FingerprintEnrollmentStatus, FirmwareSlot, FwupFinishRspStatus, FwupMode,
+            GetAuthenticationKey, GetCert, GetCoredumpCount, GetCoredumpFragment,
+            GetFingerprintEnrollmentStatus, GetFirmwareMetadata, GetInitialSpendingKey, LockDevice,
+            QueryAuthentication, SealKey, SignChallenge, SignTransaction,
+            SignVerifyAttestationChallenge, StartFingerprintEnrollment, UnsealKey, WipeState,
+        },
+        emulator::{EmulatedTransactor, TEST_DEVICE_ROOT_CERT, TEST_FACTORY_CERT},
+        errors::CommandError,
+        fwpb::{cert_get_cmd::CertType, BtcNetwork::Signet},
+        pcsc::{Performer, TransactorError},
+    };
+
+    fn get_funded_wallet(base: &DescriptorPublicKey) -> Wallet<AnyDatabase> {
+        let spending = match base {
+            DescriptorPublicKey::XPub(xpub) => DescriptorPublicKey::XPub(DescriptorXKey {
+                derivation_path: xpub
+                    .derivation_path
+                    .extend([ChildNumber::Normal { index: 0 }]),
+                origin: xpub.origin.clone(),
+                ..*xpub
+            }),
+            _ => unimplemented!(),
+        };
+        let descriptor = Descriptor::<DescriptorPublicKey>::new_wpkh(spending).unwrap();
+        let (wallet, _, _) = bdk::wallet::get_funded_wallet(&descriptor.to_string());
+        wallet
+    }
+
+    #[test]
+    fn test_enrollment_and_authentication() {
+        let device = EmulatedTransactor::new();
+
+        assert!(!device.perform(QueryAuthentication::new()).unwrap());
+        assert!(matches!(
+            device.perform(GetAuthenticationKey::new()),
+            Err(TransactorError::CommandError(CommandError::Unauthenticated))
+        ));
+
+        assert!(device.perform(StartFingerprintEnrollment::new()).unwrap());
+        assert_eq!(
+            device
+                .perform(GetFingerprintEnrollmentStatus::new())
+                .unwrap(),
+            FingerprintEnrollmentStatus::Complete
+        );
+        assert!(device.perform(QueryAuthentication::new()).unwrap());
+
+        let challenge = "0123456789abcdef".as_bytes();
+        let authentication_key = device.perform(GetAuthenticationKey::new()).unwrap();
+        let signature = device
+            .perform(SignChallenge::new(challenge.to_vec()))
+            .unwrap();
+        Secp256k1::new()
+            .verify_ecdsa(
+                &Message::from_hashed_data::<sha256::Hash>(challenge),
+                &signature,
+                &authentication_key,
+            )
+            .unwrap();
+
+        assert!(device.perform(LockDevice::new()).unwrap());
+        assert!(!device.perform(QueryAuthentication::new()).unwrap());
+        device.unlock();
+        assert!(device.perform(QueryAuthentication::new()).unwrap());
+    }
+
+    #[test]
+    fn test_keys_derive_from_seed() {
+        let seed = [7u8; 32];
+        let a = EmulatedTransactor::from_seed(seed).enrolled();
+        let b = EmulatedTransactor::from_seed(seed).enrolled();
+
+        let key = a.perform(GetInitialSpendingKey::new(Signet)).unwrap();
+        assert_eq!(key, b.perform(GetInitialSpendingKey::new(Signet)).unwrap());
+        assert!(key.to_string().contains("/84'/1'/0']tpub"));
+
+        assert!(a.perform(WipeState::new()).unwrap());
+        assert_ne!(a.seed(), seed);
+        assert!(!a.perform(QueryAuthentication::new()).unwrap());
+    }
+
+    #[test]
+    fn test_sign_transaction() {
+        let device = EmulatedTransactor::new().enrolled();
+
+        let source = device.perform(GetInitialSpendingKey::new(Signet)).unwrap();
+        let source_wallet = get_funded_wallet(&source);
+        let destination = source_wallet.get_address(AddressIndex::New).unwrap();
+        let mut builder = source_wallet.build_tx();
+        builder.add_recipient(destination.script_pubkey(), 5000);
+        let (unsigned, _) = builder.finish().unwrap();
+
+        let mut signed = device.perform(SignTransaction::new(unsigned)).unwrap();
+        assert!(source_wallet
+            .finalize_psbt(&mut signed, Default::default())
+            .unwrap());
+    }
+
+    #[test]
+    fn test_seal_and_unseal() {
+        let device = EmulatedTransactor::new().enrolled();
+        let key = [42u8; 32];
+
+        let sealed = device.perform(SealKey::new(key)).unwrap();
+        assert_eq!(device.perform(UnsealKey::new(sealed.clone())).unwrap(), key);
+
+        let other = EmulatedTransactor::new().enrolled();
+        assert!(matches!(
+            other.perform(UnsealKey::new(sealed)),
+            Err(TransactorError::CommandError(
+                CommandError::SealCsekResponseUnsealError
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_firmware_update() {
+        let device = EmulatedTransactor::new().enrolled();
+        let before = device.perform(GetFirmwareMetadata::new()).unwrap();
+        assert!(matches!(before.active_slot, FirmwareSlot::A));
+
+        let image = vec![0x5a; 1000];
+        assert!(device
+            .perform(wca::commands::FwupStart::new(None, FwupMode::Normal))
+            .unwrap());
+        for (sequence_id, chunk) in image.chunks(452).enumerate() {
+            assert!(device
+                .perform(wca::commands::FwupTransfer::new(
+                    sequence_id as u32,
+                    chunk.to_vec(),
+                    0,
+                    FwupMode::Normal,
+                ))
+                .unwrap());
+        }
+        assert_eq!(
+            device
+                .perform(wca::commands::FwupFinish::new(0, 900, FwupMode::Normal))
+                .unwrap(),
+            FwupFinishRspStatus::Success
+        );
+
+        let after = device.perform(GetFirmwareMetadata::new()).unwrap();
+        assert!(matches!(after.active_slot, FirmwareSlot::B));
+        assert_ne!(before.hash, after.hash);
+    }
+
+    #[test]
+    fn test_coredumps() {
+        let coredump = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+        let device = EmulatedTransactor::new().with_coredump(coredump.clone());
+        assert_eq!(device.perform(GetCoredumpCount::new()).unwrap(), 1);
+
+        let mut fetched = vec![];
+        loop {
+            let fragment = device
+                .perform(GetCoredumpFragment::new(fetched.len() as u32))
+                .unwrap();
+            fetched.extend(fragment.data);
+            if fragment.complete {
+                assert_eq!(fragment.coredumps_remaining, 0);
+                break;
+            }
+        }
+
+        assert_eq!(fetched, coredump);
+        assert_eq!(device.perform(GetCoredumpCount::new()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_attestation() {
+        let device = EmulatedTransactor::new();
+        let identity = device
+            .perform(GetCert::new(CertType::DeviceHostCert))
+            .unwrap();
+        let batch = device.perform(GetCert::new(CertType::BatchCert)).unwrap();
+
+        let attestation = Attestation::new();
+        assert!(attestation
+            .verify_device_identity_cert_chain_with_roots(
+                identity.clone(),
+                batch.clone(),
+                TEST_FACTORY_CERT,
+                TEST_DEVICE_ROOT_CERT,
+            )
+            .is_ok());
+        assert!(attestation
+            .verify_device_identity_cert_chain(identity.clone(), batch)
+            .is_err());
+
+        let challenge = attestation.generate_challenge().unwrap();
+        assert!(device
+            .perform(SignVerifyAttestationChallenge::new(identity, challenge))
+            .unwrap());
+    }
+}
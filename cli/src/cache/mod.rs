@@ -2,10 +2,12 @@ use std::collections::HashSet;
 
 use anyhow::Result;
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 
 use crate::{
-    db::transactions::{FromDatabase, ToDatabase},
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
     entities::{Account, AuthenticationToken, DescriptorKeyset, Keyset},
     requests::{helper::EndpointExt, KeysetsRequest},
 };
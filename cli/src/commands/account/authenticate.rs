@@ -10,15 +10,16 @@ use tokio::runtime::Runtime;
 use wca::pcsc::{NullTransactor, Transactor};
 
 use crate::db::transactions::{FromDatabase, ToDatabase};
+use crate::db::Db;
 use crate::entities::{Account, AuthenticationToken, SignerHistory};
 use crate::serde_helpers::AccountId;
 use crate::signers::Authentication;
 
-pub(crate) fn authenticate_with_app_key(db: &sled::Db, auth_client_id: &str) -> Result<()> {
+pub(crate) fn authenticate_with_app_key(db: &Db, auth_client_id: &str) -> Result<()> {
     Runtime::new()?.block_on(authenticate(db, auth_client_id))
 }
 
-async fn authenticate(db: &sled::Db, auth_client_id: &str) -> Result<()> {
+async fn authenticate(db: &Db, auth_client_id: &str) -> Result<()> {
     authenticate_with_signer(
         auth_client_id,
         &Account::from_database(db)?.id,
@@ -31,14 +32,43 @@ async fn authenticate(db: &sled::Db, auth_client_id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Authenticates with the recovery key, for the routes that only take a recovery token. The token
+/// isn't kept, since the app key's token is the one every other command uses.
+pub(crate) fn authenticate_with_recovery_key(
+    db: &Db,
+    auth_client_id: &str,
+) -> Result<AuthenticationToken> {
+    let account_id = Account::from_database(db)?.id;
+    let signer = SignerHistory::from_database(db)?
+        .active
+        .application
+        .recovery_authentication();
+
+    Runtime::new()?.block_on(authenticate_with_username(
+        auth_client_id,
+        &format!("{account_id}-recovery"),
+        &signer,
+        &NullTransactor,
+    ))
+}
+
 pub(crate) async fn authenticate_with_signer(
     auth_client_id: &str,
     account_id: &AccountId,
     signer: &impl Authentication,
     context: &impl Transactor,
+) -> Result<AuthenticationToken> {
+    authenticate_with_username(auth_client_id, &account_id.to_string(), signer, context).await
+}
+
+async fn authenticate_with_username(
+    auth_client_id: &str,
+    username: &str,
+    signer: &impl Authentication,
+    context: &impl Transactor,
 ) -> Result<AuthenticationToken> {
     let client = Client::new(&(get_aws_config().await));
-    let initial_auth_response = initate_auth(&client, auth_client_id, account_id).await?;
+    let initial_auth_response = initate_auth(&client, auth_client_id, username).await?;
     let initial_auth_session = initial_auth_response
         .session()
         .context("no session from cognito")?;
@@ -53,7 +83,7 @@ pub(crate) async fn authenticate_with_signer(
         auth_client_id,
         initial_auth_session,
         signed,
-        account_id,
+        username,
     )
     .await?;
     let access_token = respond_to_auth_response
@@ -75,13 +105,13 @@ async fn get_aws_config() -> SdkConfig {
 async fn initate_auth(
     client: &Client,
     auth_client_id: &str,
-    account_id: &AccountId,
+    username: &str,
 ) -> Result<InitiateAuthOutput> {
     let response = client
         .initiate_auth()
         .client_id(auth_client_id)
         .auth_flow(AuthFlowType::CustomAuth)
-        .auth_parameters("USERNAME", account_id.to_string())
+        .auth_parameters("USERNAME", username)
         .send()
         .await?;
     Ok(response)
@@ -92,7 +122,7 @@ async fn respond_to_auth_challenge(
     auth_client_id: &str,
     initial_auth_session: &str,
     signed: Signature,
-    account_id: &AccountId,
+    username: &str,
 ) -> Result<RespondToAuthChallengeOutput> {
     let response = client
         .respond_to_auth_challenge()
@@ -100,7 +130,7 @@ async fn respond_to_auth_challenge(
         .challenge_name(ChallengeNameType::CustomChallenge)
         .session(initial_auth_session)
         .challenge_responses("ANSWER", signed.to_string())
-        .challenge_responses("USERNAME", account_id.to_string())
+        .challenge_responses("USERNAME", username)
         .send()
         .await?;
     Ok(response)
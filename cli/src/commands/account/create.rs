@@ -1,8 +1,8 @@
 use anyhow::{bail, Context, Result};
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 
 use crate::db::transactions::{FromDatabase, ToDatabase};
+use crate::db::Db;
 use crate::entities::{Account, DescriptorKeyset, Keyset, SignerHistory};
 use crate::requests::helper::EndpointExt;
 use crate::requests::CreateAccount;
@@ -24,6 +24,13 @@ pub fn create(client: &Client, db: &Db) -> Result<()> {
         auth: AuthKeypairRequest {
             app: Authentication::public_key(&signers.active.application),
             hardware: Authentication::public_key(&signers.active.hardware),
+            recovery: Some(
+                signers
+                    .active
+                    .application
+                    .recovery_authentication()
+                    .public_key(),
+            ),
         },
         spending: SpendingKeysetRequest {
             network,
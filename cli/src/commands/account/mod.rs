@@ -4,8 +4,8 @@ mod lookup;
 mod recover;
 mod rotate;
 
-pub(crate) use authenticate::authenticate_with_app_key;
+pub(crate) use authenticate::{authenticate_with_app_key, authenticate_with_recovery_key};
 pub(crate) use create::create;
 pub(crate) use lookup::lookup;
 pub(crate) use recover::recover;
-pub(crate) use rotate::rotate;
+pub(crate) use rotate::{activate_keyset, rotate};
@@ -1,12 +1,14 @@
 use anyhow::{Context, Result};
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 use tokio::runtime::Runtime;
 
 use crate::{
     cache::FromCache,
     commands::account::authenticate::authenticate_with_signer,
-    db::transactions::{FromDatabase, ToDatabase},
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
     entities::{Account, SignerHistory},
     requests::{helper::EndpointExt, HardwareAuthenticationRequest},
     signers::Authentication,
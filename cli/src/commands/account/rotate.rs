@@ -3,15 +3,21 @@ use rustify::blocking::clients::reqwest::Client;
 
 use crate::{
     cache::FromCache,
-    db::transactions::{FromDatabase, ToDatabase},
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        wallet::clear_wallet_cache,
+        Db,
+    },
     entities::{Account, AuthenticationToken, SignerHistory, SignerPair},
+    nfc::SafeTransactor,
     requests::{
         helper::EndpointExt, CreateKeysetRequest, SetActiveKeysetRequest, SpendingKeysetRequest,
     },
+    serde_helpers::AccountId,
     signers::Spending,
 };
 
-pub(crate) fn rotate(client: &Client, db: &sled::Db) -> Result<()> {
+pub(crate) fn rotate(client: &Client, db: &Db) -> Result<()> {
     let signers = SignerHistory::from_database(db)?;
     println!("{}", &signers.active);
     let context = signers.active.hardware.sign_context()?;
@@ -35,34 +41,39 @@ pub(crate) fn rotate(client: &Client, db: &sled::Db) -> Result<()> {
     let signers = if signers.active == new_active {
         signers
     } else {
-        SignerHistory {
-            active: new_active,
-            inactive: {
-                let mut inactive = vec![signers.active];
-                inactive.extend(signers.inactive);
-                inactive
-            },
-        }
-        .to_database(db)?;
+        signers.rotate_to(new_active).to_database(db)?;
         SignerHistory::from_database(db)?
     };
 
+    activate_keyset(client, db, account_id, &signers.active, &context)?;
+
+    Ok(())
+}
+
+/// Registers the spending keys of `signers` as a new keyset and makes it the account's active one.
+pub(crate) fn activate_keyset(
+    client: &Client,
+    db: &Db,
+    account_id: AccountId,
+    signers: &SignerPair,
+    context: &SafeTransactor,
+) -> Result<()> {
     let token = AuthenticationToken::from_database(db)?;
 
     let keyset = CreateKeysetRequest {
         account_id: account_id.clone(),
         spending: SpendingKeysetRequest {
-            network: signers.active.network,
-            app: signers.active.application.public_key(),
-            hardware: signers.active.hardware.public_key(),
+            network: signers.network,
+            app: signers.application.public_key(),
+            hardware: signers.hardware.public_key(),
         },
     }
     .exec_keyproofed(
         client,
         &token,
-        Some(&signers.active.application),
-        Some(&signers.active.hardware),
-        &context,
+        Some(&signers.application),
+        Some(&signers.hardware),
+        context,
     )?;
 
     println!("{}", keyset.keyset_id);
@@ -75,10 +86,12 @@ pub(crate) fn rotate(client: &Client, db: &sled::Db) -> Result<()> {
     .exec_keyproofed(
         client,
         &token,
-        Some(&signers.active.application),
-        Some(&signers.active.hardware),
-        &context,
+        Some(&signers.application),
+        Some(&signers.hardware),
+        context,
     )?;
 
+    clear_wallet_cache(db)?;
+
     Ok(())
 }
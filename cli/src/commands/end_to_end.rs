@@ -1,12 +1,13 @@
 use anyhow::{anyhow, bail, Context, Result};
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 use std::str::FromStr;
 use tracing::info;
 
 use crate::cache::FromCache;
 use crate::commands;
+use crate::db::profiles::DEFAULT_PROFILE;
 use crate::db::transactions::FromDatabase;
+use crate::db::Db;
 use crate::entities::{Account, SignerHistory};
 use bdk::bitcoin::secp256k1::rand;
 use bdk::bitcoin::{Address, Network};
@@ -53,7 +54,8 @@ fn open_database() -> Result<Db> {
     let db_filename = format!("test-{suffix}.db");
 
     info!("db filename: {db_filename} (in case you need to look at it later)");
-    sled::open(db_filename).context("opening sled database")
+    let db = sled::open(db_filename).context("opening sled database")?;
+    Db::open(db, DEFAULT_PROFILE)
 }
 
 fn fund_wallet_from_treasury(
@@ -1,24 +1,71 @@
 use std::{
     fs::File,
     io::{Cursor, Read, Seek},
+    thread::sleep,
+    time::Duration,
 };
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use bdk::bitcoin::hashes::{sha256, Hash};
+use data_encoding::BASE64;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_FIXED};
 use rustify::blocking::clients::reqwest::Client;
 use serde::Deserialize;
 
-use wca::{commands::FirmwareSlot, pcsc::PCSCTransactor};
+use wca::{
+    commands::{DeviceInfo, FirmwareMetadata, FirmwareSlot, FwupFinishRspStatus, FwupMode},
+    pcsc::{PCSCTransactor, Transactor},
+};
 use zip::ZipArchive;
 
-use crate::nfc::{NFCTransactions, Upload};
+use crate::{
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
+    entities::FwupCheckpoint,
+    nfc::{self, NFCTransactions, PairingError, Upload},
+};
 
 pub(crate) fn metadata() -> Result<()> {
-    println!("{:?}", PCSCTransactor::new()?.metadata()?);
+    println!("{:?}", nfc::connect()?.metadata()?);
     Ok(())
 }
 
 const MANIFEST_FILE: &str = "fwup-manifest.json";
+const DELTA_MANIFEST_FILE: &str = "fwup-delta-manifest.json";
+const MANIFEST_VERSION: &str = "0.0.1";
+const SIGNATURE_SIZE: usize = 64;
+const REBOOT_ATTEMPTS: usize = 60;
 const MEMFAULT_PROJECT_KEY: &str = "cuMF7SryHhQQcs2gcuEaHqDWV0Z43ha4";
+/// Unused flash between the end of an image and its signature is erased, and is signed as such.
+const FLASH_ERASED_VALUE: u8 = 0xff;
+/// DER prefix of a P-256 SubjectPublicKeyInfo, which is followed by the uncompressed point.
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+const DEV_APPLICATION_KEYS: &[&str] = &[
+    include_str!("../../../firmware/config/keys/w1a-dev/w1a-app-signing-key-dev.1.pub.pem"),
+    include_str!("../../../firmware/config/keys/w1a-dev/w1a-app-signing-key-dev.2.pub.pem"),
+    include_str!(
+        "../../../firmware/config/keys/w1a-dev/w1a-app-signing-key-dev-development.1.pub.pem"
+    ),
+    include_str!("../../../firmware/config/keys/w1a-dev/w1a-app-signing-key-dev-staging.1.pub.pem"),
+];
+const DEV_PATCH_KEYS: &[&str] = &[include_str!(
+    "../../../firmware/config/keys/w1a-dev/w1a-patch-signing-key-dev.1.pub.pem"
+)];
+const PROD_APPLICATION_KEYS: &[&str] = &[include_str!(
+    "../../../firmware/config/keys/w1a-prod/w1a-app-signing-key-prod.1.pub.pem"
+)];
+const PROD_PROTO_APPLICATION_KEYS: &[&str] = &[include_str!(
+    "../../../firmware/config/keys/w1a-prod-proto/w1a-app-signing-key-prod.pub.pem"
+)];
+const PROD_PATCH_KEYS: &[&str] = &[include_str!(
+    "../../../firmware/config/keys/w1a-prod/w1a-patch-signing-key-prod.1.pub.pem"
+)];
 
 #[derive(Debug, Deserialize)]
 struct Manifest {
@@ -58,6 +105,26 @@ struct FileReference {
     name: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct DeltaManifest {
+    manifest_version: String,
+    fwup_bundle: DeltaFwupBundle,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeltaFwupBundle {
+    from_version: String,
+    to_version: String,
+    assets: DeltaAssets,
+    parameters: Parameters,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeltaAssets {
+    a2b_patch: Asset,
+    b2a_patch: Asset,
+}
+
 #[derive(Debug, Deserialize)]
 struct Parameters {
     wca_chunk_size: usize,
@@ -71,8 +138,8 @@ fn read_from_zip<R: Read + Seek>(zip: &mut ZipArchive<R>, name: &str) -> Result<
     Ok(buf)
 }
 
-pub(crate) fn upload_latest(client: &Client) -> Result<()> {
-    let transactor = PCSCTransactor::new()?;
+pub(crate) fn upload_latest(client: &Client, db: &Db) -> Result<()> {
+    let transactor = nfc::connect()?;
 
     let device_info = transactor.device_info()?;
 
@@ -101,66 +168,518 @@ pub(crate) fn upload_latest(client: &Client) -> Result<()> {
 
     let firmware = client.http.get(firmware_url).send()?.bytes()?;
 
-    upload(transactor, ZipArchive::new(Cursor::new(&firmware))?)?;
+    upload(
+        db,
+        transactor,
+        &device_info,
+        ZipArchive::new(Cursor::new(&firmware))?,
+    )?;
 
     Ok(())
 }
 
-pub(crate) fn upload_bundle(bundle: std::path::PathBuf) -> Result<()> {
+pub(crate) fn upload_bundle(db: &Db, bundle: std::path::PathBuf) -> Result<()> {
+    let transactor = nfc::connect()?;
+    let device_info = transactor.device_info()?;
     upload(
-        PCSCTransactor::new()?,
+        db,
+        transactor,
+        &device_info,
         ZipArchive::new(File::open(bundle)?)?,
     )
 }
 
-fn upload<R: Read + Seek>(mut transactor: PCSCTransactor, mut zip: ZipArchive<R>) -> Result<()> {
-    let manifest: Manifest = serde_json::from_reader(zip.by_name(MANIFEST_FILE)?)?;
-
-    let target_slot = match transactor.metadata()?.active_slot {
+fn upload<R: Read + Seek>(
+    db: &Db,
+    mut transactor: PCSCTransactor,
+    device_info: &DeviceInfo,
+    mut zip: ZipArchive<R>,
+) -> Result<()> {
+    let current = transactor.metadata()?;
+    let target_slot = match current.active_slot {
         FirmwareSlot::A => FirmwareSlot::B,
         FirmwareSlot::B => FirmwareSlot::A,
     };
-    let application = match target_slot {
-        FirmwareSlot::A => manifest.fwup_bundle.assets.application_a,
-        FirmwareSlot::B => manifest.fwup_bundle.assets.application_b,
+
+    let (version, application, parameters, mode) = if zip.by_name(MANIFEST_FILE).is_ok() {
+        let manifest: Manifest = serde_json::from_reader(zip.by_name(MANIFEST_FILE)?)?;
+        check_manifest_version(&manifest.manifest_version)?;
+        let bundle = manifest.fwup_bundle;
+        let application = match target_slot {
+            FirmwareSlot::A => bundle.assets.application_a,
+            FirmwareSlot::B => bundle.assets.application_b,
+        };
+        (
+            bundle.version,
+            application,
+            bundle.parameters,
+            FwupMode::Normal,
+        )
+    } else {
+        let manifest: DeltaManifest = serde_json::from_reader(zip.by_name(DELTA_MANIFEST_FILE)?)?;
+        check_manifest_version(&manifest.manifest_version)?;
+        let bundle = manifest.fwup_bundle;
+        if bundle.from_version != current.version {
+            bail!(
+                "Patch is for {from}, but the hardware is running {current}",
+                from = bundle.from_version,
+                current = current.version
+            );
+        }
+        let patch = match target_slot {
+            FirmwareSlot::A => bundle.assets.b2a_patch,
+            FirmwareSlot::B => bundle.assets.a2b_patch,
+        };
+        (bundle.to_version, patch, bundle.parameters, FwupMode::Delta)
     };
 
     let upload = Upload {
-        chunk_size: manifest.fwup_bundle.parameters.wca_chunk_size,
-        app_properties_offset: manifest.fwup_bundle.parameters.app_properties_offset,
+        mode,
+        chunk_size: parameters.wca_chunk_size,
+        app_properties_offset: parameters.app_properties_offset,
         application: crate::nfc::Asset {
             data: read_from_zip(&mut zip, &application.image.name)?,
             offset: 0,
         },
         signature: crate::nfc::Asset {
             data: read_from_zip(&mut zip, &application.signature.name)?,
-            offset: manifest.fwup_bundle.parameters.signature_offset,
+            offset: parameters.signature_offset,
         },
     };
+    verify_bundle(&upload, &SigningKeys::for_device(device_info)?)?;
+
+    let progress = UploadProgress::new(db, &upload);
+    let resume_from = progress.resume_from();
+    let mut checkpoint = |next_sequence_id| progress.save(next_sequence_id);
+
+    let status = match resume_from {
+        Some(sequence_id) => {
+            println!(
+                "Resuming upload of {version} to slot {target_slot:?} from chunk {sequence_id}..."
+            );
+            match transactor.upload(&upload, resume_from, &mut checkpoint) {
+                // The hardware forgot the interrupted upload (e.g. it restarted), so start over.
+                Err(PairingError::Transaction(err)) => {
+                    println!("Could not resume ({err}), starting over...");
+                    transactor.upload(&upload, None, &mut checkpoint)?
+                }
+                result => result?,
+            }
+        }
+        None => {
+            println!("Uploading {version} to slot {target_slot:?}...");
+            transactor.upload(&upload, None, &mut checkpoint)?
+        }
+    };
 
-    println!(
-        "Uploading {version} to slot {target_slot:?}...",
-        version = manifest.fwup_bundle.version
-    );
-    match transactor.upload(&upload)? {
-        wca::commands::FwupFinishRspStatus::Unspecified => {
-            println!("Upload failed due to an unspecified error. :-(")
+    match status {
+        FwupFinishRspStatus::Success | FwupFinishRspStatus::WillApplyPatch => {
+            progress.clear()?;
+            if let FwupFinishRspStatus::WillApplyPatch = status {
+                println!("Patch uploaded. Waiting for hardware to apply patch...");
+            }
         }
-        wca::commands::FwupFinishRspStatus::Success => println!("Upload successful!"),
-        wca::commands::FwupFinishRspStatus::SignatureInvalid => {
-            println!("Upload failed due to an invalid signature. :-(")
+        FwupFinishRspStatus::Unspecified => {
+            bail!("Upload failed due to an unspecified error. :-(")
         }
-        wca::commands::FwupFinishRspStatus::VersionInvalid => {
-            println!("Upload failed due to an invalid version. :-(")
+        FwupFinishRspStatus::SignatureInvalid => {
+            progress.clear()?;
+            bail!("Upload failed due to an invalid signature. :-(")
         }
-        wca::commands::FwupFinishRspStatus::WillApplyPatch => {
-            println!("Patch uploaded. Waiting for hardware to apply patch...")
+        FwupFinishRspStatus::VersionInvalid => {
+            progress.clear()?;
+            bail!("Upload failed due to an invalid version. :-(")
         }
-        wca::commands::FwupFinishRspStatus::Unauthenticated => {
-            println!("Unauthenticated. Please unlock your hardware.")
+        FwupFinishRspStatus::Unauthenticated => {
+            bail!("Unauthenticated. Please unlock your hardware and try again.")
         }
-        wca::commands::FwupFinishRspStatus::Error => println!("Upload failed due to an error. :-("),
+        FwupFinishRspStatus::Error => bail!("Upload failed due to an error. :-("),
     };
 
+    println!("Waiting for the hardware to restart; keep it on the reader...");
+    let updated = wait_for_metadata(&mut transactor)?;
+    if updated.active_slot != target_slot || updated.version != version {
+        bail!(
+            "Hardware is running {running} from slot {slot:?}, expected {version} in slot {target_slot:?}",
+            running = updated.version,
+            slot = updated.active_slot
+        );
+    }
+    println!("Upload successful! Hardware is running {version} from slot {target_slot:?}.");
+
+    Ok(())
+}
+
+fn check_manifest_version(manifest_version: &str) -> Result<()> {
+    if manifest_version != MANIFEST_VERSION {
+        bail!("Unknown bundle manifest version {manifest_version}");
+    }
+    Ok(())
+}
+
+/// Where an interrupted upload of the same application got to, kept so it can carry on from there.
+struct UploadProgress<'a> {
+    db: &'a Db,
+    digest: String,
+}
+
+impl<'a> UploadProgress<'a> {
+    fn new(db: &'a Db, upload: &Upload) -> Self {
+        Self {
+            db,
+            digest: sha256::Hash::hash(&upload.application.data).to_string(),
+        }
+    }
+
+    /// The next sequence ID to send, if an earlier upload of this application was interrupted.
+    fn resume_from(&self) -> Option<u32> {
+        FwupCheckpoint::from_database(self.db)
+            .ok()
+            .filter(|checkpoint| checkpoint.digest == self.digest)
+            .map(|checkpoint| checkpoint.next_sequence_id)
+    }
+
+    fn save(&self, next_sequence_id: u32) {
+        let checkpoint = FwupCheckpoint {
+            digest: self.digest.clone(),
+            next_sequence_id,
+        };
+        if let Err(err) = checkpoint.to_database(self.db) {
+            println!("Warning: could not save upload progress ({err})");
+        }
+    }
+
+    fn clear(&self) -> Result<()> {
+        FwupCheckpoint::clear(self.db)
+    }
+}
+
+/// The public keys the hardware accepts firmware signatures from.
+struct SigningKeys {
+    application: Vec<Vec<u8>>,
+    patch: Vec<Vec<u8>>,
+}
+
+impl SigningKeys {
+    fn for_device(device_info: &DeviceInfo) -> Result<Self> {
+        let (application, patch) = match device_info.sw_type.split('-').last() {
+            Some("dev") => (DEV_APPLICATION_KEYS, DEV_PATCH_KEYS),
+            Some("prod") if device_info.hw_revision.ends_with("-proto") => {
+                (PROD_PROTO_APPLICATION_KEYS, PROD_PATCH_KEYS)
+            }
+            Some("prod") => (PROD_APPLICATION_KEYS, PROD_PATCH_KEYS),
+            Some(_) | None => bail!("Invalid software type ('{}')", device_info.sw_type),
+        };
+        Self::from_pems(application, patch)
+    }
+
+    fn from_pems(application: &[&str], patch: &[&str]) -> Result<Self> {
+        Ok(Self {
+            application: application
+                .iter()
+                .map(|pem| public_key_from_pem(pem))
+                .collect::<Result<_>>()?,
+            patch: patch
+                .iter()
+                .map(|pem| public_key_from_pem(pem))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// Decode a PEM encoded P-256 public key into the uncompressed point `ring` verifies with.
+fn public_key_from_pem(pem: &str) -> Result<Vec<u8>> {
+    let base64 = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect::<String>();
+    let der = BASE64
+        .decode(base64.as_bytes())
+        .context("Signing key is not valid PEM")?;
+    match der.strip_prefix(&P256_SPKI_PREFIX) {
+        Some(point) if point.len() == 65 => Ok(point.to_vec()),
+        _ => bail!("Signing key is not a P-256 public key"),
+    }
+}
+
+fn is_signed_by(keys: &[Vec<u8>], message: &[u8], signature: &[u8]) -> bool {
+    keys.iter().any(|key| {
+        UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, key)
+            .verify(message, signature)
+            .is_ok()
+    })
+}
+
+/// Check the bundle's offsets and signature before spending a tap on transferring it.
+fn verify_bundle(upload: &Upload, keys: &SigningKeys) -> Result<()> {
+    if upload.chunk_size == 0 {
+        bail!("Bundle has a chunk size of 0");
+    }
+    if upload.signature.data.len() != SIGNATURE_SIZE {
+        bail!(
+            "Bundle signature is {len} bytes, expected {SIGNATURE_SIZE}",
+            len = upload.signature.data.len()
+        );
+    }
+    if upload.app_properties_offset >= upload.signature.offset {
+        bail!(
+            "App properties offset ({app_properties}) is not before the signature offset ({signature})",
+            app_properties = upload.app_properties_offset,
+            signature = upload.signature.offset
+        );
+    }
+
+    match upload.mode {
+        FwupMode::Normal => {
+            let image_size = upload.application.data.len();
+            if upload.app_properties_offset as usize >= image_size {
+                bail!(
+                    "App properties offset ({offset}) is past the end of the {image_size} byte image",
+                    offset = upload.app_properties_offset
+                );
+            }
+            if image_size > upload.signature.offset as usize {
+                bail!(
+                    "The {image_size} byte image overlaps the signature at offset {offset}",
+                    offset = upload.signature.offset
+                );
+            }
+
+            // The whole slot up to the signature is signed, including the erased flash after the image.
+            let mut signed = upload.application.data.clone();
+            signed.resize(upload.signature.offset as usize, FLASH_ERASED_VALUE);
+            if !is_signed_by(&keys.application, &signed, &upload.signature.data) {
+                bail!("Bundle signature does not verify with the hardware's signing keys");
+            }
+        }
+        // A patch is applied by the hardware, so the image it produces can't be checked here.
+        // Instead the patch carries its own signature in its last bytes.
+        FwupMode::Delta => {
+            let patch = &upload.application.data;
+            if patch.len() < SIGNATURE_SIZE {
+                bail!(
+                    "The {len} byte patch is too short to be signed",
+                    len = patch.len()
+                );
+            }
+            let (signed, signature) = patch.split_at(patch.len() - SIGNATURE_SIZE);
+            if !is_signed_by(&keys.patch, signed, signature) {
+                bail!("Patch signature does not verify with the hardware's patch signing keys");
+            }
+        }
+    }
+
     Ok(())
 }
+
+fn wait_for_metadata(transactor: &mut PCSCTransactor) -> Result<FirmwareMetadata> {
+    for _ in 0..REBOOT_ATTEMPTS {
+        sleep(Duration::from_secs(1));
+        if transactor.reset().is_err() {
+            continue;
+        }
+        if let Ok(metadata) = transactor.metadata() {
+            return Ok(metadata);
+        }
+    }
+
+    bail!("Hardware did not come back after the upload")
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::{
+        rand::SystemRandom,
+        signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+    };
+    use wca::commands::FirmwareSlot;
+
+    use super::*;
+
+    const SIGNATURE_OFFSET: u32 = 1024;
+
+    fn key_pair() -> EcdsaKeyPair {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng).unwrap();
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref(), &rng).unwrap()
+    }
+
+    fn sign(key: &EcdsaKeyPair, message: &[u8]) -> Vec<u8> {
+        key.sign(&SystemRandom::new(), message)
+            .unwrap()
+            .as_ref()
+            .to_vec()
+    }
+
+    fn keys(application: &EcdsaKeyPair, patch: &EcdsaKeyPair) -> SigningKeys {
+        SigningKeys {
+            application: vec![application.public_key().as_ref().to_vec()],
+            patch: vec![patch.public_key().as_ref().to_vec()],
+        }
+    }
+
+    fn upload(mode: FwupMode, application: Vec<u8>, signature: Vec<u8>) -> Upload {
+        Upload {
+            mode,
+            chunk_size: 128,
+            app_properties_offset: 16,
+            application: nfc::Asset {
+                data: application,
+                offset: 0,
+            },
+            signature: nfc::Asset {
+                data: signature,
+                offset: SIGNATURE_OFFSET,
+            },
+        }
+    }
+
+    fn signed_image(key: &EcdsaKeyPair) -> Upload {
+        let image = vec![0x42; 700];
+        let mut slot = image.clone();
+        slot.resize(SIGNATURE_OFFSET as usize, FLASH_ERASED_VALUE);
+        let signature = sign(key, &slot);
+        upload(FwupMode::Normal, image, signature)
+    }
+
+    fn signed_patch(key: &EcdsaKeyPair) -> Upload {
+        let mut patch = vec![0x17; 300];
+        patch.extend(sign(key, &patch));
+        upload(FwupMode::Delta, patch, vec![0; SIGNATURE_SIZE])
+    }
+
+    fn device_info(sw_type: &str, hw_revision: &str) -> DeviceInfo {
+        DeviceInfo {
+            version: "1.0.0".to_string(),
+            serial: "serial".to_string(),
+            sw_type: sw_type.to_string(),
+            hw_revision: hw_revision.to_string(),
+            active_slot: FirmwareSlot::A,
+            battery_charge: 0.0,
+            vcell: 0,
+            avg_current_ma: 0,
+            battery_cycles: 0,
+            secure_boot_config: None,
+        }
+    }
+
+    #[test]
+    fn signed_image_verifies() {
+        let (application, patch) = (key_pair(), key_pair());
+        verify_bundle(&signed_image(&application), &keys(&application, &patch)).unwrap();
+    }
+
+    #[test]
+    fn image_signed_by_another_key_is_rejected() {
+        let (application, patch) = (key_pair(), key_pair());
+        assert!(verify_bundle(&signed_image(&key_pair()), &keys(&application, &patch)).is_err());
+        assert!(verify_bundle(&signed_image(&patch), &keys(&application, &patch)).is_err());
+    }
+
+    #[test]
+    fn tampered_image_is_rejected() {
+        let (application, patch) = (key_pair(), key_pair());
+        let keys = keys(&application, &patch);
+
+        let mut tampered = signed_image(&application);
+        tampered.application.data[100] ^= 1;
+        assert!(verify_bundle(&tampered, &keys).is_err());
+
+        // Padding is part of what was signed, so appending erased flash changes nothing...
+        let mut padded = signed_image(&application);
+        padded.application.data.resize(800, FLASH_ERASED_VALUE);
+        verify_bundle(&padded, &keys).unwrap();
+
+        // ...but anything else in the gap does.
+        let mut extended = signed_image(&application);
+        extended.application.data.resize(800, 0);
+        assert!(verify_bundle(&extended, &keys).is_err());
+    }
+
+    #[test]
+    fn malformed_image_is_rejected() {
+        let (application, patch) = (key_pair(), key_pair());
+        let keys = keys(&application, &patch);
+
+        let mut bundle = signed_image(&application);
+        bundle.chunk_size = 0;
+        assert!(verify_bundle(&bundle, &keys).is_err());
+
+        let mut bundle = signed_image(&application);
+        bundle.signature.data.pop();
+        assert!(verify_bundle(&bundle, &keys).is_err());
+
+        let mut bundle = signed_image(&application);
+        bundle.app_properties_offset = 800;
+        assert!(verify_bundle(&bundle, &keys).is_err());
+
+        let mut bundle = signed_image(&application);
+        bundle
+            .application
+            .data
+            .resize(SIGNATURE_OFFSET as usize + 1, 0);
+        assert!(verify_bundle(&bundle, &keys).is_err());
+    }
+
+    #[test]
+    fn signed_patch_verifies() {
+        let (application, patch) = (key_pair(), key_pair());
+        verify_bundle(&signed_patch(&patch), &keys(&application, &patch)).unwrap();
+    }
+
+    #[test]
+    fn patch_signed_by_another_key_is_rejected() {
+        let (application, patch) = (key_pair(), key_pair());
+        let keys = keys(&application, &patch);
+        assert!(verify_bundle(&signed_patch(&application), &keys).is_err());
+
+        let mut tampered = signed_patch(&patch);
+        tampered.application.data[0] ^= 1;
+        assert!(verify_bundle(&tampered, &keys).is_err());
+
+        let mut truncated = signed_patch(&patch);
+        truncated.application.data.truncate(SIGNATURE_SIZE - 1);
+        assert!(verify_bundle(&truncated, &keys).is_err());
+    }
+
+    #[test]
+    fn signing_keys_match_the_software_type() {
+        let dev = SigningKeys::for_device(&device_info("w1a-dev", "w1a-dvt")).unwrap();
+        assert_eq!(dev.application.len(), DEV_APPLICATION_KEYS.len());
+        assert_eq!(dev.patch.len(), DEV_PATCH_KEYS.len());
+
+        let prod = SigningKeys::for_device(&device_info("w1a-prod", "w1a-dvt")).unwrap();
+        let proto = SigningKeys::for_device(&device_info("w1a-prod", "w1a-proto")).unwrap();
+        assert_ne!(prod.application, dev.application);
+        assert_ne!(prod.application, proto.application);
+        assert_eq!(prod.patch, proto.patch);
+
+        assert!(SigningKeys::for_device(&device_info("w1a-other", "w1a-dvt")).is_err());
+        assert!(public_key_from_pem("-----BEGIN PUBLIC KEY-----\nAAAA\n").is_err());
+    }
+
+    #[test]
+    fn upload_progress_resumes_only_the_same_application() {
+        let db = Db::open(
+            sled::Config::new().temporary(true).open().unwrap(),
+            crate::db::profiles::DEFAULT_PROFILE,
+        )
+        .unwrap();
+        let key = key_pair();
+        let first = signed_image(&key);
+        let mut second = signed_image(&key);
+        second.application.data[0] ^= 1;
+
+        let progress = UploadProgress::new(&db, &first);
+        assert_eq!(progress.resume_from(), None);
+
+        progress.save(3);
+        progress.save(4);
+        assert_eq!(progress.resume_from(), Some(4));
+        assert_eq!(UploadProgress::new(&db, &first).resume_from(), Some(4));
+        assert_eq!(UploadProgress::new(&db, &second).resume_from(), None);
+
+        progress.clear().unwrap();
+        assert_eq!(progress.resume_from(), None);
+    }
+}
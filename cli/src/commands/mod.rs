@@ -5,5 +5,7 @@ pub mod check_keyproofs;
 pub mod end_to_end;
 pub mod firmware;
 pub mod pair;
+pub mod profile;
+pub(crate) mod social;
 pub mod wallet;
 mod wipe;
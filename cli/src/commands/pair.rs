@@ -2,24 +2,26 @@ use std::{thread::sleep, time::Duration};
 
 use anyhow::Result;
 use bdk::bitcoin::Network;
-use wca::pcsc::{PCSCTransactor, Transactor, TransactorError};
+use wca::{
+    emulator::EmulatedTransactor,
+    pcsc::{Transactor, TransactorError},
+};
 
 use crate::{
-    db::transactions::{FromDatabase, ToDatabase},
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
     entities::{HardwareSignerProxy, SignerHistory, SignerPair},
-    nfc::{NFCTransactions, PairingError},
+    nfc::{self, NFCTransactions, PairingError},
     signers::{hardware::HardwareSigner, seed::SeedSigner},
 };
 
-pub(crate) fn pair(db: &sled::Db, network: Network, use_fake_hardware: bool) -> Result<()> {
+pub(crate) fn pair(db: &Db, network: Network, use_fake_hardware: bool) -> Result<()> {
     let active = SignerPair {
         network,
         application: SeedSigner::new(network, 0),
-        hardware: if use_fake_hardware {
-            HardwareSignerProxy::Fake(SeedSigner::new(network, 0))
-        } else {
-            HardwareSignerProxy::Real(pair_real(network, &mut PCSCTransactor::new()?)?)
-        },
+        hardware: pair_hardware(network, use_fake_hardware)?,
     };
 
     let inactive = SignerHistory::from_database(db)
@@ -35,6 +37,23 @@ pub(crate) fn pair(db: &sled::Db, network: Network, use_fake_hardware: bool) ->
     Ok(())
 }
 
+pub(crate) fn pair_hardware(
+    network: Network,
+    use_fake_hardware: bool,
+) -> Result<HardwareSignerProxy> {
+    let hardware = if use_fake_hardware {
+        let mut transactor = EmulatedTransactor::new().enrolled();
+        let signer = pair_real(network, &mut transactor)?;
+        HardwareSignerProxy::Emulated {
+            seed: transactor.seed(),
+            signer,
+        }
+    } else {
+        HardwareSignerProxy::Real(pair_real(network, &mut nfc::connect()?)?)
+    };
+    Ok(hardware)
+}
+
 pub(crate) fn pair_real(
     network: Network,
     transactor: &mut impl Transactor,
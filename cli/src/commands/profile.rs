@@ -0,0 +1,41 @@
+use anyhow::Result;
+
+use crate::{
+    db::{
+        profiles,
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
+    entities::ProfileConfig,
+};
+
+pub(crate) fn create(db: sled::Db, name: &str, config: ProfileConfig) -> Result<()> {
+    profiles::create(&db, name)?;
+    config.to_database(&Db::open(db, name)?)
+}
+
+pub(crate) fn list(db: sled::Db) -> Result<()> {
+    let selected = profiles::selected(&db)?;
+    for name in profiles::list(&db) {
+        let config =
+            ProfileConfig::from_database(&Db::open(db.clone(), &name)?).unwrap_or_default();
+        let marker = if name == selected { "*" } else { " " };
+        let network = config
+            .network
+            .map_or("-".to_string(), |network| network.to_string());
+        println!(
+            "{marker} {name}\tnetwork: {network}\tserver: {}\telectrum: {}",
+            config.server.as_deref().unwrap_or("-"),
+            config.electrum.as_deref().unwrap_or("-"),
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn select(db: sled::Db, name: &str) -> Result<()> {
+    profiles::select(&db, name)
+}
+
+pub(crate) fn delete(db: sled::Db, name: &str) -> Result<()> {
+    profiles::delete(&db, name)
+}
@@ -0,0 +1,173 @@
+use anyhow::{bail, ensure, Context, Result};
+use rustify::blocking::clients::reqwest::Client;
+
+use crate::{
+    commands::account::authenticate_with_recovery_key,
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
+    entities::{Account, PakeKey, SocialChallenge, SocialRecovery},
+    requests::{
+        helper::EndpointExt, FetchSocialChallengeRequest, RespondToSocialChallengeRequest,
+        StartChallengeTrustedContact, StartSocialChallengeRequest, VerifySocialChallengeRequest,
+    },
+    serde_helpers::RecoveryRelationshipId,
+};
+
+use super::{
+    crypto::{answer, PAKE_RECOVERY_AAD},
+    join_code, split_code,
+};
+
+/// Challenges every endorsed trusted contact to return the data encryption key. The code printed
+/// is the one to give them.
+pub(crate) fn start_challenge(client: &Client, db: &Db, auth_client_id: &str) -> Result<()> {
+    let account_id = Account::from_database(db)?.id;
+    let mut social = SocialRecovery::from_database(db).unwrap_or_default();
+    let backup = social
+        .backup
+        .as_ref()
+        .context("no trusted contacts endorsed from this profile; run `social endorse` first")?;
+
+    let pake = PakeKey::generate()?;
+    let trusted_contacts = backup
+        .sealed_deks
+        .iter()
+        .map(|(id, sealed_dek)| StartChallengeTrustedContact {
+            recovery_relationship_id: id.clone(),
+            protected_customer_recovery_pake_pubkey: pake.public_key_hex(),
+            sealed_dek: sealed_dek.clone(),
+        })
+        .collect();
+
+    let challenge = StartSocialChallengeRequest {
+        account_id,
+        trusted_contacts,
+    }
+    .exec_authenticated(client, &authenticate_with_recovery_key(db, auth_client_id)?)?
+    .social_challenge;
+
+    println!("{}", join_code(challenge.counter, &pake.code));
+
+    social.challenge = Some(SocialChallenge {
+        id: challenge.social_challenge_id,
+        counter: challenge.counter,
+        pake,
+    });
+    social.to_database(db)?;
+
+    Ok(())
+}
+
+/// Answers a protected customer's challenge by resealing their data encryption key to them.
+pub(crate) fn respond(
+    client: &Client,
+    db: &Db,
+    auth_client_id: &str,
+    code: &str,
+    recovery_relationship_id: Option<String>,
+) -> Result<()> {
+    let account_id = Account::from_database(db)?.id;
+    let social = SocialRecovery::from_database(db).unwrap_or_default();
+    let (counter, pake_code) = split_code(code)?;
+    let counter = counter
+        .parse()
+        .context("malformed code; the server part should be the challenge counter")?;
+
+    let (recovery_relationship_id, key) = match recovery_relationship_id {
+        Some(id) => {
+            let id = RecoveryRelationshipId(id);
+            let key = social
+                .delegated_decryption_keys
+                .get(&id)
+                .context("this profile didn't accept that relationship")?;
+            (id, key)
+        }
+        None => match social.delegated_decryption_keys.len() {
+            1 => social
+                .delegated_decryption_keys
+                .iter()
+                .next()
+                .map(|(id, key)| (id.clone(), key))
+                .expect("there's exactly one key"),
+            0 => bail!("this profile hasn't accepted any invitations"),
+            _ => bail!(
+                "this profile is a trusted contact for several customers; pick a relationship"
+            ),
+        },
+    };
+
+    let token = authenticate_with_recovery_key(db, auth_client_id)?;
+    let challenge = VerifySocialChallengeRequest {
+        account_id: account_id.clone(),
+        recovery_relationship_id,
+        counter,
+    }
+    .exec_authenticated(client, &token)?
+    .social_challenge;
+
+    let dek = key.open(&challenge.sealed_dek)?;
+    let answer = answer(
+        &pake_code,
+        &challenge.protected_customer_recovery_pake_pubkey,
+        &dek,
+        PAKE_RECOVERY_AAD,
+    )?;
+
+    RespondToSocialChallengeRequest {
+        account_id,
+        social_challenge_id: challenge.social_challenge_id,
+        trusted_contact_recovery_pake_pubkey: answer.public_key,
+        recovery_pake_confirmation: answer.confirmation,
+        resealed_dek: answer.sealed,
+    }
+    .exec_authenticated(client, &token)?;
+
+    Ok(())
+}
+
+/// Checks the trusted contacts' responses to our challenge, and that each of them returned the data
+/// encryption key we sealed to them.
+pub(crate) fn verify(client: &Client, db: &Db, auth_client_id: &str) -> Result<()> {
+    let account_id = Account::from_database(db)?.id;
+    let social = SocialRecovery::from_database(db).unwrap_or_default();
+    let (Some(backup), Some(challenge)) = (&social.backup, &social.challenge) else {
+        bail!("no social challenge started from this profile; run `social start-challenge` first");
+    };
+
+    let responses = FetchSocialChallengeRequest {
+        account_id,
+        social_challenge_id: challenge.id.clone(),
+    }
+    .exec_authenticated(client, &authenticate_with_recovery_key(db, auth_client_id)?)?
+    .social_challenge
+    .responses;
+
+    let mut verified = 0;
+    for response in &responses {
+        let id = &response.recovery_relationship_id;
+        match challenge.pake.open(
+            &response.trusted_contact_recovery_pake_pubkey,
+            &response.recovery_pake_confirmation,
+            &response.resealed_dek,
+            PAKE_RECOVERY_AAD,
+        ) {
+            Ok(dek) if dek == backup.dek => {
+                verified += 1;
+                println!("{id}: verified");
+            }
+            Ok(_) => println!("{id}: returned the wrong data encryption key"),
+            Err(e) => println!("{id}: {e:#}"),
+        }
+    }
+
+    ensure!(
+        verified > 0,
+        "none of the {} responses to challenge {} verified",
+        responses.len(),
+        challenge.counter
+    );
+
+    Ok(())
+}
@@ -0,0 +1,308 @@
+//! The social recovery cryptography, as the app does it.
+//!
+//! The protected customer (SPAKE2 Alice) and a trusted contact (Bob) share a PAKE code out of band.
+//! Whatever the trusted contact hands back over the resulting channel is sealed with Bob's
+//! encryption key, and the customer only opens it once Bob's key confirmation checks out. Data
+//! encryption keys are sealed to a trusted contact's delegated decryption key with CryptoBox.
+
+use anyhow::{anyhow, ensure, Context, Result};
+use bdk::bitcoin::secp256k1::rand::{thread_rng, Rng};
+use crypto::{
+    chacha20poly1305::XChaCha20Poly1305,
+    crypto_box::{CryptoBox, CryptoBoxKeyPair},
+    spake2::{Spake2Context, Spake2Role},
+};
+use data_encoding::{BASE64_NOPAD, HEXLOWER_PERMISSIVE};
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{DelegatedDecryptionKey, PakeKey};
+
+pub(super) const PAKE_ENROLLMENT_AAD: &[u8] = b"Bitkey Social Recovery PAKE Enrollment Version 1.0";
+pub(super) const PAKE_RECOVERY_AAD: &[u8] = b"Bitkey Social Recovery PAKE Recovery Version 1.0";
+
+const PROTECTED_CUSTOMER: &str = "Protected Customer";
+const TRUSTED_CONTACT: &str = "Trusted Contact";
+
+const XCHACHA20POLY1305: &str = "XChaCha20Poly1305";
+const CRYPTO_BOX: &str = "CryptoBox";
+
+impl PakeKey {
+    /// Generates the protected customer's side of a PAKE exchange, along with a fresh code for the
+    /// trusted contact.
+    pub(crate) fn generate() -> Result<Self> {
+        let code: [u8; 16] = thread_rng().gen();
+        let context = protected_customer_context()?;
+        let public_key = context.generate_msg(code.to_vec())?;
+
+        Ok(Self {
+            code,
+            private_key: context
+                .read_private_key()
+                .try_into()
+                .map_err(|_| anyhow!("unexpected PAKE private key length"))?,
+            public_key: public_key
+                .try_into()
+                .map_err(|_| anyhow!("unexpected PAKE public key length"))?,
+        })
+    }
+
+    pub(crate) fn public_key_hex(&self) -> String {
+        HEXLOWER_PERMISSIVE.encode(&self.public_key)
+    }
+
+    /// Finishes the exchange with a trusted contact's answer, and opens what they sealed over it.
+    pub(crate) fn open(
+        &self,
+        their_public_key: &str,
+        confirmation: &str,
+        sealed: &str,
+        aad: &[u8],
+    ) -> Result<Vec<u8>> {
+        let context = protected_customer_context()?;
+        // Generating a message is what loads the code into the context; the key pair is then
+        // swapped for the one the trusted contact answered
+        context.generate_msg(self.code.to_vec())?;
+        context.write_key_pair(self.private_key.to_vec(), self.public_key.to_vec())?;
+        let keys = context.process_msg(
+            HEXLOWER_PERMISSIVE.decode(their_public_key.as_bytes())?,
+            Some(aad.to_vec()),
+        )?;
+        context
+            .process_key_conf_msg(HEXLOWER_PERMISSIVE.decode(confirmation.as_bytes())?, &keys)
+            .context("the trusted contact's key confirmation didn't match; was the code right?")?;
+
+        let sealed = XSealedData::decode(sealed, XCHACHA20POLY1305)?;
+        Ok(XChaCha20Poly1305::new(&keys.bob_encryption_key)?.decrypt(
+            &sealed.nonce,
+            &sealed.ciphertext,
+            aad,
+        )?)
+    }
+}
+
+impl DelegatedDecryptionKey {
+    pub(crate) fn generate() -> Self {
+        let key_pair = CryptoBoxKeyPair::new();
+        Self {
+            secret_key: key_pair
+                .secret_key()
+                .try_into()
+                .expect("CryptoBox secret keys are 32 bytes"),
+            public_key: key_pair
+                .public_key()
+                .try_into()
+                .expect("CryptoBox public keys are 32 bytes"),
+        }
+    }
+
+    /// Opens something a protected customer sealed to this key with [`seal_to`].
+    pub(crate) fn open(&self, sealed: &str) -> Result<Vec<u8>> {
+        let sealed = XSealedData::decode(sealed, CRYPTO_BOX)?;
+        let their_public_key = sealed
+            .public_key
+            .context("sealed data is missing the sender's public key")?;
+
+        Ok(CryptoBox::new(&their_public_key, &self.secret_key)?
+            .decrypt(&sealed.nonce, &sealed.ciphertext)?)
+    }
+}
+
+/// What a trusted contact hands back to the protected customer over a PAKE channel.
+pub(super) struct PakeAnswer {
+    pub(super) public_key: String,
+    pub(super) confirmation: String,
+    pub(super) sealed: String,
+}
+
+/// Answers the protected customer's PAKE public key, sealing `plaintext` so only they can open it.
+pub(super) fn answer(
+    code: &[u8],
+    their_public_key: &str,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<PakeAnswer> {
+    let context = Spake2Context::new(
+        Spake2Role::Bob,
+        TRUSTED_CONTACT.to_string(),
+        PROTECTED_CUSTOMER.to_string(),
+    )?;
+    let public_key = context.generate_msg(code.to_vec())?;
+    let keys = context.process_msg(
+        HEXLOWER_PERMISSIVE.decode(their_public_key.as_bytes())?,
+        Some(aad.to_vec()),
+    )?;
+    let confirmation = context.generate_key_conf_msg(&keys)?;
+
+    let nonce = nonce();
+    let ciphertext =
+        XChaCha20Poly1305::new(&keys.bob_encryption_key)?.encrypt(&nonce, plaintext, aad)?;
+
+    Ok(PakeAnswer {
+        public_key: HEXLOWER_PERMISSIVE.encode(&public_key),
+        confirmation: HEXLOWER_PERMISSIVE.encode(&confirmation),
+        sealed: XSealedData {
+            algorithm: XCHACHA20POLY1305.to_string(),
+            ciphertext,
+            nonce: nonce.to_vec(),
+            public_key: None,
+        }
+        .encode(),
+    })
+}
+
+/// Seals `plaintext` to a trusted contact's delegated decryption key. It's sent from a throwaway
+/// identity key, whose public half travels with the ciphertext.
+pub(super) fn seal_to(public_key: &[u8], plaintext: &[u8]) -> Result<String> {
+    let identity = CryptoBoxKeyPair::new();
+    let nonce = nonce();
+    let ciphertext =
+        CryptoBox::new(public_key, &identity.secret_key())?.encrypt(&nonce, plaintext)?;
+
+    Ok(XSealedData {
+        algorithm: CRYPTO_BOX.to_string(),
+        ciphertext,
+        nonce: nonce.to_vec(),
+        public_key: Some(identity.public_key()),
+    }
+    .encode())
+}
+
+fn protected_customer_context() -> Result<Spake2Context> {
+    Ok(Spake2Context::new(
+        Spake2Role::Alice,
+        PROTECTED_CUSTOMER.to_string(),
+        TRUSTED_CONTACT.to_string(),
+    )?)
+}
+
+fn nonce() -> [u8; 24] {
+    thread_rng().gen()
+}
+
+#[derive(Deserialize, Serialize)]
+struct Header {
+    #[serde(rename = "v", default = "Header::default_version")]
+    version: u8,
+    #[serde(rename = "alg")]
+    algorithm: String,
+}
+
+impl Header {
+    fn default_version() -> u8 {
+        1
+    }
+}
+
+/// The app's `XCiphertext`: the JSON header, ciphertext and nonce, each base64 encoded and joined
+/// with periods. Version 2 appends the sender's public key.
+#[derive(Debug, PartialEq)]
+struct XSealedData {
+    algorithm: String,
+    ciphertext: Vec<u8>,
+    nonce: Vec<u8>,
+    public_key: Option<Vec<u8>>,
+}
+
+impl XSealedData {
+    fn encode(&self) -> String {
+        let header = Header {
+            version: if self.public_key.is_some() { 2 } else { 1 },
+            algorithm: self.algorithm.clone(),
+        };
+        let mut parts = vec![
+            BASE64_NOPAD.encode(&serde_json::to_vec(&header).expect("header serializes")),
+            BASE64_NOPAD.encode(&self.ciphertext),
+            BASE64_NOPAD.encode(&self.nonce),
+        ];
+        if let Some(public_key) = &self.public_key {
+            parts.push(BASE64_NOPAD.encode(public_key));
+        }
+        parts.join(".")
+    }
+
+    fn decode(sealed: &str, algorithm: &str) -> Result<Self> {
+        let parts = sealed
+            .split('.')
+            .map(|part| BASE64_NOPAD.decode(part.trim_end_matches('=').as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .context("sealed data isn't base64")?;
+        let header: Header = serde_json::from_slice(parts.first().context("empty sealed data")?)?;
+        ensure!(
+            header.algorithm == algorithm,
+            "expected data sealed with {algorithm}, not {}",
+            header.algorithm
+        );
+
+        match (header.version, parts.as_slice()) {
+            (1, [_, ciphertext, nonce]) => Ok(Self {
+                algorithm: header.algorithm,
+                ciphertext: ciphertext.clone(),
+                nonce: nonce.clone(),
+                public_key: None,
+            }),
+            (2, [_, ciphertext, nonce, public_key]) => Ok(Self {
+                algorithm: header.algorithm,
+                ciphertext: ciphertext.clone(),
+                nonce: nonce.clone(),
+                public_key: Some(public_key.clone()),
+            }),
+            (version, parts) => Err(anyhow!(
+                "malformed sealed data (version {version} with {} parts)",
+                parts.len()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_data_round_trips() {
+        for public_key in [None, Some(vec![0x42; 32])] {
+            let sealed = XSealedData {
+                algorithm: CRYPTO_BOX.to_string(),
+                ciphertext: vec![1, 2, 3, 4, 5],
+                nonce: vec![6; 24],
+                public_key,
+            };
+            assert_eq!(
+                XSealedData::decode(&sealed.encode(), CRYPTO_BOX).unwrap(),
+                sealed
+            );
+        }
+    }
+
+    #[test]
+    fn sealed_data_from_the_app_decodes() {
+        // The app leaves the default version out of the header
+        let sealed = format!(
+            "{}.AQID.BAUG",
+            BASE64_NOPAD.encode(br#"{"alg":"XChaCha20Poly1305"}"#)
+        );
+        let sealed = XSealedData::decode(&sealed, XCHACHA20POLY1305).unwrap();
+        assert_eq!(sealed.ciphertext, vec![1, 2, 3]);
+        assert_eq!(sealed.nonce, vec![4, 5, 6]);
+        assert_eq!(sealed.public_key, None);
+
+        assert!(XSealedData::decode(
+            &format!(
+                "{}.AQID.BAUG",
+                BASE64_NOPAD.encode(br#"{"v":2,"alg":"CryptoBox"}"#)
+            ),
+            CRYPTO_BOX
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn delegated_decryption_key_opens_what_is_sealed_to_it() {
+        let key = DelegatedDecryptionKey::generate();
+        let sealed = seal_to(&key.public_key, b"data encryption key").unwrap();
+        assert_eq!(key.open(&sealed).unwrap(), b"data encryption key");
+
+        let other = DelegatedDecryptionKey::generate();
+        assert!(other.open(&sealed).is_err());
+    }
+}
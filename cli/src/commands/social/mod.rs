@@ -0,0 +1,51 @@
+//! Social recovery, from either side of a relationship: the protected customer invites and endorses
+//! trusted contacts and later challenges them, and trusted contacts accept and respond.
+
+mod challenge;
+mod crypto;
+mod relationships;
+
+use std::fmt::Display;
+
+use anyhow::{anyhow, Context, Result};
+use data_encoding::HEXLOWER_PERMISSIVE;
+
+pub(crate) use challenge::{respond, start_challenge, verify};
+pub(crate) use relationships::{accept, endorse, invite, relationships};
+
+/// Joins what the server hands out (an invitation code or a challenge counter) with a PAKE code the
+/// server never sees. Unlike the app's codes these aren't checksummed or compacted, since they're
+/// only meant to be pasted from one CLI to another.
+fn join_code(server_part: impl Display, pake_code: &[u8; 16]) -> String {
+    format!("{server_part}-{}", HEXLOWER_PERMISSIVE.encode(pake_code))
+}
+
+fn split_code(code: &str) -> Result<(&str, [u8; 16])> {
+    let (server_part, pake_code) = code
+        .trim()
+        .rsplit_once('-')
+        .context("malformed code; expected the server part and the PAKE code")?;
+    let pake_code = HEXLOWER_PERMISSIVE
+        .decode(pake_code.as_bytes())?
+        .try_into()
+        .map_err(|_| anyhow!("malformed code; the PAKE code is the wrong length"))?;
+
+    Ok((server_part, pake_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        let pake_code = [0xab; 16];
+        for server_part in ["8f3a9c", "12"] {
+            let code = join_code(server_part, &pake_code);
+            assert_eq!(split_code(&code).unwrap(), (server_part, pake_code));
+        }
+
+        assert!(split_code("8f3a9c").is_err());
+        assert!(split_code("8f3a9c-abab").is_err());
+    }
+}
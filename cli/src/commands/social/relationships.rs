@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use bdk::bitcoin::secp256k1::{
+    ecdsa::Signature,
+    rand::{thread_rng, Rng},
+    PublicKey,
+};
+use data_encoding::{BASE64, HEXLOWER};
+use rustify::blocking::clients::reqwest::Client;
+use serde::Serialize;
+
+use crate::{
+    commands::account::authenticate_with_recovery_key,
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
+    entities::{
+        Account, AuthenticationToken, DelegatedDecryptionKey, PakeKey, SignerHistory, SignerPair,
+        SocialBackup, SocialRecovery,
+    },
+    nfc::SafeTransactor,
+    requests::{
+        helper::EndpointExt, AcceptRecoveryRelationshipRequest, CreateRecoveryRelationshipRequest,
+        EndorseRecoveryRelationshipsRequest, InvitationForCodeRequest, RecoveryRelationshipAction,
+        RecoveryRelationshipEndorsement, RecoveryRelationshipsRequest,
+    },
+    signers::Authentication,
+};
+
+use super::{
+    crypto::{answer, seal_to, PAKE_ENROLLMENT_AAD},
+    join_code, split_code,
+};
+
+/// Same shape as the app's, so either can verify the other's endorsements.
+#[derive(Serialize)]
+struct TrustedContactKeyCertificate {
+    delegated_decryption_key: String,
+    hw_endorsement_key: PublicKey,
+    app_endorsement_key: PublicKey,
+    hw_signature: Signature,
+    app_signature: Signature,
+}
+
+pub(crate) fn invite(client: &Client, db: &Db, trusted_contact_alias: String) -> Result<()> {
+    let account_id = Account::from_database(db)?.id;
+    let signers = SignerHistory::from_database(db)?;
+    let context = signers.active.hardware.sign_context()?;
+    let pake = PakeKey::generate()?;
+
+    let invitation = CreateRecoveryRelationshipRequest {
+        account_id,
+        trusted_contact_alias,
+        protected_customer_enrollment_pake_pubkey: pake.public_key_hex(),
+    }
+    .exec_keyproofed(
+        client,
+        &AuthenticationToken::from_database(db)?,
+        Some(&signers.active.application),
+        Some(&signers.active.hardware),
+        &context,
+    )?
+    .invitation;
+
+    println!("{}", join_code(&invitation.code, &pake.code));
+
+    let mut social = SocialRecovery::from_database(db).unwrap_or_default();
+    social
+        .invitations
+        .insert(invitation.recovery_relationship_id, pake);
+    social.to_database(db)?;
+
+    Ok(())
+}
+
+pub(crate) fn relationships(client: &Client, db: &Db) -> Result<()> {
+    let response = RecoveryRelationshipsRequest {
+        account_id: Account::from_database(db)?.id,
+    }
+    .exec_authenticated(client, &AuthenticationToken::from_database(db)?)?;
+
+    print!("{response}");
+
+    Ok(())
+}
+
+pub(crate) fn accept(
+    client: &Client,
+    db: &Db,
+    auth_client_id: &str,
+    code: &str,
+    customer_alias: String,
+) -> Result<()> {
+    let account_id = Account::from_database(db)?.id;
+    let (code, pake_code) = split_code(code)?;
+    // Only a recovery token can accept, since that's what the trusted contact will respond with
+    let token = authenticate_with_recovery_key(db, auth_client_id)?;
+
+    let invitation = InvitationForCodeRequest {
+        account_id: account_id.clone(),
+        code: code.to_string(),
+    }
+    .exec_authenticated(client, &token)?
+    .invitation;
+
+    let key = DelegatedDecryptionKey::generate();
+    let answer = answer(
+        &pake_code,
+        &invitation.protected_customer_enrollment_pake_pubkey,
+        &key.public_key,
+        PAKE_ENROLLMENT_AAD,
+    )?;
+
+    AcceptRecoveryRelationshipRequest {
+        account_id,
+        recovery_relationship_id: invitation.recovery_relationship_id.clone(),
+        action: RecoveryRelationshipAction::Accept,
+        code: code.to_string(),
+        customer_alias,
+        trusted_contact_enrollment_pake_pubkey: answer.public_key,
+        enrollment_pake_confirmation: answer.confirmation,
+        sealed_delegated_decryption_pubkey: answer.sealed,
+    }
+    .exec_authenticated(client, &token)?;
+
+    println!("{}", invitation.recovery_relationship_id);
+
+    let mut social = SocialRecovery::from_database(db).unwrap_or_default();
+    social
+        .delegated_decryption_keys
+        .insert(invitation.recovery_relationship_id, key);
+    social.to_database(db)?;
+
+    Ok(())
+}
+
+/// Endorses every trusted contact that accepted one of our invitations, and seals the data
+/// encryption key to each of them.
+pub(crate) fn endorse(client: &Client, db: &Db) -> Result<()> {
+    let account_id = Account::from_database(db)?.id;
+    let token = AuthenticationToken::from_database(db)?;
+    let signers = SignerHistory::from_database(db)?;
+    let mut social = SocialRecovery::from_database(db).unwrap_or_default();
+
+    let unendorsed = RecoveryRelationshipsRequest {
+        account_id: account_id.clone(),
+    }
+    .exec_authenticated(client, &token)?
+    .unendorsed_trusted_contacts;
+    if unendorsed.is_empty() {
+        println!("No trusted contacts to endorse");
+        return Ok(());
+    }
+
+    let context = signers.active.hardware.sign_context()?;
+    let mut backup = social.backup.take().unwrap_or_else(|| SocialBackup {
+        dek: thread_rng().gen(),
+        sealed_deks: BTreeMap::new(),
+    });
+    let mut endorsements = vec![];
+    for contact in unendorsed {
+        let id = contact.recovery_relationship_id;
+        let Some(pake) = social.invitations.get(&id) else {
+            println!("{id}: not invited from this profile");
+            continue;
+        };
+        let delegated_decryption_key = match pake.open(
+            &contact.trusted_contact_enrollment_pake_pubkey,
+            &contact.enrollment_pake_confirmation,
+            &contact.sealed_delegated_decryption_pubkey,
+            PAKE_ENROLLMENT_AAD,
+        ) {
+            Ok(key) => key,
+            Err(e) => {
+                println!("{id}: {e:#}");
+                continue;
+            }
+        };
+
+        endorsements.push(RecoveryRelationshipEndorsement {
+            recovery_relationship_id: id.clone(),
+            delegated_decryption_pubkey_certificate: certificate(
+                &signers.active,
+                &delegated_decryption_key,
+                &context,
+            )?,
+        });
+        backup
+            .sealed_deks
+            .insert(id, seal_to(&delegated_decryption_key, &backup.dek)?);
+    }
+
+    if endorsements.is_empty() {
+        return Ok(());
+    }
+    let endorsed: Vec<_> = endorsements
+        .iter()
+        .map(|endorsement| endorsement.recovery_relationship_id.clone())
+        .collect();
+
+    EndorseRecoveryRelationshipsRequest {
+        account_id,
+        endorsements,
+    }
+    .exec_authenticated(client, &token)?;
+
+    for id in endorsed {
+        social.invitations.remove(&id);
+        println!("{id}: endorsed");
+    }
+    social.backup = Some(backup);
+    social.to_database(db)?;
+
+    Ok(())
+}
+
+fn certificate(
+    signers: &SignerPair,
+    delegated_decryption_key: &[u8],
+    context: &SafeTransactor,
+) -> Result<String> {
+    let app_endorsement_key = signers.application.public_key();
+    let hw_endorsement_key = signers.hardware.public_key();
+    let hw_signature = signers
+        .hardware
+        .sign(app_endorsement_key.to_string().as_bytes(), context)?;
+    let app_signature = signers.application.sign(
+        &[delegated_decryption_key, &hw_endorsement_key.serialize()].concat(),
+        context,
+    )?;
+
+    let certificate = TrustedContactKeyCertificate {
+        delegated_decryption_key: HEXLOWER.encode(delegated_decryption_key),
+        hw_endorsement_key,
+        app_endorsement_key,
+        hw_signature,
+        app_signature,
+    };
+    Ok(BASE64.encode(&serde_json::to_vec(&certificate)?))
+}
@@ -4,11 +4,11 @@ use rustify::blocking::clients::reqwest::Client;
 
 use crate::{
     cache::FromCache,
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, SignerHistory},
 };
 
-pub fn balance(client: &Client, db: &sled::Db, blockchain: ElectrumBlockchain) -> Result<()> {
+pub fn balance(client: &Client, db: &Db, blockchain: ElectrumBlockchain) -> Result<()> {
     let account = Account::from_cache(client, db)?;
     let wallet = SignerHistory::from_database(db)?
         .active
@@ -5,11 +5,10 @@ use bdk::{
     FeeRate,
 };
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 
 use crate::{
     cache::FromCache,
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, SignerHistory},
 };
 
@@ -4,12 +4,11 @@ use bdk::{
     blockchain::{Blockchain, ElectrumBlockchain},
 };
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 
 use crate::{
     cache::FromCache,
     commands::wallet::psbt_from,
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, SignerHistory},
 };
 
@@ -14,6 +14,7 @@ mod balance;
 mod debug;
 mod drain;
 mod hardware_send;
+pub mod psbt;
 mod receive;
 pub mod recovery;
 mod server_send;
@@ -0,0 +1,477 @@
+//! Reading and writing PSBTs in the formats other wallets and signing devices use.
+//!
+//! BBQr (https://bbqr.org) and UR (BCR-2020-005/006) split a PSBT across several QR-sized parts,
+//! which are written one per line.
+
+use std::{io::Read, str::FromStr};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use bdk::bitcoin::{
+    consensus::encode::{deserialize, serialize},
+    psbt::PartiallySignedTransaction,
+};
+use clap::ValueEnum;
+use data_encoding::{BASE32_NOPAD, HEXUPPER_PERMISSIVE};
+use flate2::read::DeflateDecoder;
+
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+/// Base32 characters per BBQr part; a multiple of 8 so every part decodes on its own.
+const BBQR_PART_LENGTH: usize = 400;
+/// Message bytes per UR part.
+const UR_FRAGMENT_LENGTH: usize = 200;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Format {
+    #[default]
+    Base64,
+    Binary,
+    Bbqr,
+    Ur,
+}
+
+pub(crate) fn encode(psbt: &PartiallySignedTransaction, format: Format) -> Vec<u8> {
+    match format {
+        Format::Base64 => psbt.to_string().into_bytes(),
+        Format::Binary => serialize(psbt),
+        Format::Bbqr => lines(bbqr::encode(&serialize(psbt))),
+        Format::Ur => lines(ur::encode(&serialize(psbt))),
+    }
+}
+
+/// Decodes a PSBT in any of the supported formats, working out which one it is from the contents.
+pub(crate) fn decode(contents: &[u8]) -> Result<PartiallySignedTransaction> {
+    if contents.starts_with(PSBT_MAGIC) {
+        return Ok(deserialize(contents)?);
+    }
+
+    let text = std::str::from_utf8(contents).context("PSBT is neither binary nor text")?;
+    let parts = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    let first = parts.first().ok_or_else(|| anyhow!("PSBT is empty"))?;
+
+    let binary = if first.starts_with("B$") {
+        bbqr::decode(&parts)?
+    } else if first.to_ascii_lowercase().starts_with("ur:") {
+        ur::decode(&parts)?
+    } else {
+        return PartiallySignedTransaction::from_str(&parts.concat())
+            .context("couldn't decode base64 PSBT");
+    };
+    ensure!(binary.starts_with(PSBT_MAGIC), "decoded data isn't a PSBT");
+    Ok(deserialize(&binary)?)
+}
+
+fn lines(parts: Vec<String>) -> Vec<u8> {
+    let mut text = parts.join("\n");
+    text.push('\n');
+    text.into_bytes()
+}
+
+mod bbqr {
+    use super::*;
+
+    const FILE_TYPE_PSBT: char = 'P';
+
+    pub(super) fn encode(data: &[u8]) -> Vec<String> {
+        let encoded = BASE32_NOPAD.encode(data);
+        let chunks = encoded
+            .as_bytes()
+            .chunks(BBQR_PART_LENGTH)
+            .collect::<Vec<_>>();
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                format!(
+                    "B$2{FILE_TYPE_PSBT}{}{}{}",
+                    base36(chunks.len()),
+                    base36(index),
+                    std::str::from_utf8(chunk).expect("base32 is ascii"),
+                )
+            })
+            .collect()
+    }
+
+    pub(super) fn decode(parts: &[&str]) -> Result<Vec<u8>> {
+        let mut encoding = None;
+        let mut chunks: Vec<Option<&str>> = Vec::new();
+
+        for part in parts {
+            // The header is sliced by byte, which only lines up with chars for ASCII
+            ensure!(part.is_ascii(), "BBQr part isn't ASCII");
+            ensure!(part.len() >= 8, "BBQr part is too short");
+            let (header, data) = part.split_at(8);
+            let mut chars = header[2..4].chars();
+            let (part_encoding, file_type) = (chars.next(), chars.next());
+            ensure!(file_type == Some(FILE_TYPE_PSBT), "BBQr data isn't a PSBT");
+            ensure!(
+                encoding.is_none() || encoding == part_encoding,
+                "BBQr parts use different encodings"
+            );
+            encoding = part_encoding;
+
+            let total = from_base36(&header[4..6])?;
+            let index = from_base36(&header[6..8])?;
+            ensure!(total > 0 && index < total, "BBQr part {index} of {total}?");
+            if chunks.is_empty() {
+                chunks.resize(total, None);
+            }
+            ensure!(chunks.len() == total, "BBQr parts disagree on the total");
+            chunks[index] = Some(data);
+        }
+
+        let missing = chunks.iter().filter(|chunk| chunk.is_none()).count();
+        ensure!(
+            missing == 0,
+            "missing {missing} of {} BBQr parts",
+            chunks.len()
+        );
+        let data = chunks.into_iter().flatten().collect::<String>();
+
+        match encoding {
+            Some('H') => Ok(HEXUPPER_PERMISSIVE.decode(data.as_bytes())?),
+            Some('2') => Ok(BASE32_NOPAD.decode(data.as_bytes())?),
+            Some('Z') => {
+                let compressed = BASE32_NOPAD.decode(data.as_bytes())?;
+                let mut decompressed = Vec::new();
+                DeflateDecoder::new(compressed.as_slice()).read_to_end(&mut decompressed)?;
+                Ok(decompressed)
+            }
+            other => bail!("unsupported BBQr encoding {other:?}"),
+        }
+    }
+
+    fn base36(n: usize) -> String {
+        const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        assert!(n < 36 * 36, "too many BBQr parts");
+        format!("{}{}", DIGITS[n / 36] as char, DIGITS[n % 36] as char)
+    }
+
+    fn from_base36(digits: &str) -> Result<usize> {
+        usize::from_str_radix(digits, 36).map_err(|_| anyhow!("bad BBQr header {digits}"))
+    }
+}
+
+mod ur {
+    use super::*;
+
+    const TYPE: &str = "crypto-psbt";
+
+    #[rustfmt::skip]
+    const BYTEWORDS: [&str; 256] = [
+        "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald", "barn", "belt", "beta", "bias",
+        "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash", "cats", "chef", "city", "claw", "code", "cola", "cook", "cost",
+        "crux", "curl", "cusp", "cyan", "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+        "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair", "fern", "figs", "film", "fish",
+        "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel", "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow",
+        "good", "gray", "grim", "guru", "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+        "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade", "jazz", "join", "jolt", "jowl",
+        "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept", "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb",
+        "lava", "lazy", "leaf", "legs", "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+        "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need", "news", "next", "noon", "note",
+        "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls", "paid", "part", "peck", "play", "plus", "poem", "pool", "pose",
+        "puff", "puma", "purr", "quad", "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+        "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub", "surf", "swan", "taco", "task",
+        "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys", "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user",
+        "vast", "very", "veto", "vial", "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+        "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero", "zest", "zinc", "zone", "zoom",
+    ];
+
+    pub(super) fn encode(data: &[u8]) -> Vec<String> {
+        let message = cbor::bytes(data);
+        if message.len() <= UR_FRAGMENT_LENGTH {
+            return vec![format!("ur:{TYPE}/{}", bytewords(&message))];
+        }
+
+        let checksum = crc32fast::hash(&message);
+        let fragments = message.chunks(UR_FRAGMENT_LENGTH).collect::<Vec<_>>();
+        fragments
+            .iter()
+            .enumerate()
+            .map(|(index, fragment)| {
+                // Every fragment is the same length, so the last one is padded with zeroes
+                let mut padded = fragment.to_vec();
+                padded.resize(UR_FRAGMENT_LENGTH, 0);
+
+                let mut part = cbor::head(cbor::ARRAY, 5);
+                part.extend(cbor::uint(index as u64 + 1));
+                part.extend(cbor::uint(fragments.len() as u64));
+                part.extend(cbor::uint(message.len() as u64));
+                part.extend(cbor::uint(checksum.into()));
+                part.extend(cbor::bytes(&padded));
+
+                format!(
+                    "ur:{TYPE}/{}-{}/{}",
+                    index + 1,
+                    fragments.len(),
+                    bytewords(&part)
+                )
+            })
+            .collect()
+    }
+
+    /// Decodes single-part URs and multi-part URs from their simple parts; parts mixed by the
+    /// fountain encoder (sequence numbers past the part count) aren't supported.
+    pub(super) fn decode(parts: &[&str]) -> Result<Vec<u8>> {
+        let mut fragments: Vec<Option<Vec<u8>>> = Vec::new();
+        let mut expected = None;
+
+        for part in parts {
+            let part = part.to_ascii_lowercase();
+            let path = part
+                .strip_prefix(&format!("ur:{TYPE}/"))
+                .ok_or_else(|| anyhow!("UR isn't a {TYPE}"))?;
+
+            let Some((sequence, words)) = path.split_once('/') else {
+                return cbor::read_bytes(&mut from_bytewords(path)?.as_slice());
+            };
+
+            let (number, count) = sequence
+                .split_once('-')
+                .and_then(|(n, c)| Some((n.parse::<usize>().ok()?, c.parse::<usize>().ok()?)))
+                .ok_or_else(|| anyhow!("bad UR sequence {sequence}"))?;
+            ensure!(
+                number >= 1 && number <= count,
+                "UR part {number} of {count} is fountain encoded, which isn't supported"
+            );
+
+            let body = from_bytewords(words)?;
+            let mut reader = body.as_slice();
+            ensure!(
+                cbor::read_head(&mut reader, cbor::ARRAY)? == 5,
+                "bad UR part"
+            );
+            let header = (
+                cbor::read_uint(&mut reader)? as usize,
+                cbor::read_uint(&mut reader)? as usize,
+                cbor::read_uint(&mut reader)? as usize,
+                cbor::read_uint(&mut reader)? as u32,
+            );
+            let fragment = cbor::read_bytes(&mut reader)?;
+            ensure!(
+                header.0 == number && header.1 == count,
+                "UR part header doesn't match its sequence"
+            );
+            ensure!(
+                expected.is_none() || expected == Some((header.1, header.2, header.3)),
+                "UR parts belong to different messages"
+            );
+            expected = Some((header.1, header.2, header.3));
+
+            if fragments.is_empty() {
+                fragments.resize(count, None);
+            }
+            fragments[number - 1] = Some(fragment);
+        }
+
+        let (_, length, checksum) = expected.ok_or_else(|| anyhow!("no UR parts"))?;
+        let missing = fragments.iter().filter(|f| f.is_none()).count();
+        ensure!(
+            missing == 0,
+            "missing {missing} of {} UR parts",
+            fragments.len()
+        );
+
+        let mut message = fragments
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect::<Vec<_>>();
+        ensure!(message.len() >= length, "UR parts are too short");
+        message.truncate(length);
+        ensure!(
+            crc32fast::hash(&message) == checksum,
+            "UR message checksum mismatch"
+        );
+
+        cbor::read_bytes(&mut message.as_slice())
+    }
+
+    /// Minimal bytewords (the first and last letter of each word) with a CRC-32 suffix.
+    fn bytewords(data: &[u8]) -> String {
+        let checksum = crc32fast::hash(data).to_be_bytes();
+        data.iter()
+            .chain(checksum.iter())
+            .flat_map(|&byte| {
+                let word = BYTEWORDS[byte as usize].as_bytes();
+                [word[0] as char, word[3] as char]
+            })
+            .collect()
+    }
+
+    fn from_bytewords(text: &str) -> Result<Vec<u8>> {
+        ensure!(
+            text.len() % 2 == 0 && text.is_ascii(),
+            "bad bytewords length"
+        );
+        let mut data = text
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| {
+                BYTEWORDS
+                    .iter()
+                    .position(|word| word.as_bytes()[0] == pair[0] && word.as_bytes()[3] == pair[1])
+                    .map(|byte| byte as u8)
+                    .ok_or_else(|| anyhow!("bad byteword {}", String::from_utf8_lossy(pair)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        ensure!(data.len() > 4, "bytewords are too short");
+        let checksum = data.split_off(data.len() - 4);
+        ensure!(
+            crc32fast::hash(&data).to_be_bytes() == checksum.as_slice(),
+            "bytewords checksum mismatch"
+        );
+        Ok(data)
+    }
+}
+
+/// Just enough CBOR for UR: unsigned integers, byte strings and array headers.
+mod cbor {
+    use super::*;
+
+    pub(super) const UINT: u8 = 0;
+    pub(super) const BYTES: u8 = 2;
+    pub(super) const ARRAY: u8 = 4;
+
+    pub(super) fn head(major: u8, value: u64) -> Vec<u8> {
+        let major = major << 5;
+        match value {
+            0..=23 => vec![major | value as u8],
+            24..=0xff => vec![major | 24, value as u8],
+            0x100..=0xffff => [vec![major | 25], (value as u16).to_be_bytes().to_vec()].concat(),
+            0x10000..=0xffff_ffff => {
+                [vec![major | 26], (value as u32).to_be_bytes().to_vec()].concat()
+            }
+            _ => [vec![major | 27], value.to_be_bytes().to_vec()].concat(),
+        }
+    }
+
+    pub(super) fn uint(value: u64) -> Vec<u8> {
+        head(UINT, value)
+    }
+
+    pub(super) fn bytes(data: &[u8]) -> Vec<u8> {
+        [head(BYTES, data.len() as u64), data.to_vec()].concat()
+    }
+
+    pub(super) fn read_head(reader: &mut &[u8], major: u8) -> Result<u64> {
+        let (&initial, rest) = reader
+            .split_first()
+            .ok_or_else(|| anyhow!("truncated CBOR"))?;
+        ensure!(initial >> 5 == major, "unexpected CBOR type");
+
+        let length = match initial & 0x1f {
+            n @ 0..=23 => {
+                *reader = rest;
+                return Ok(n.into());
+            }
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => bail!("unsupported CBOR length"),
+        };
+        let value = take(reader, rest, length)?;
+        Ok(value.iter().fold(0, |acc, &b| acc << 8 | b as u64))
+    }
+
+    pub(super) fn read_uint(reader: &mut &[u8]) -> Result<u64> {
+        read_head(reader, UINT)
+    }
+
+    pub(super) fn read_bytes(reader: &mut &[u8]) -> Result<Vec<u8>> {
+        let length = read_head(reader, BYTES)? as usize;
+        let rest = *reader;
+        Ok(take(reader, rest, length)?.to_vec())
+    }
+
+    /// Takes `length` bytes from `rest`, leaving `reader` pointing after them.
+    fn take<'a>(reader: &mut &'a [u8], rest: &'a [u8], length: usize) -> Result<&'a [u8]> {
+        ensure!(rest.len() >= length, "truncated CBOR");
+        let (value, remaining) = rest.split_at(length);
+        *reader = remaining;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::{PackedLockTime, Script, Transaction, TxIn, TxOut};
+
+    use super::*;
+
+    fn psbt(outputs: usize) -> PartiallySignedTransaction {
+        PartiallySignedTransaction::from_unsigned_tx(Transaction {
+            version: 2,
+            lock_time: PackedLockTime(0),
+            input: vec![TxIn::default()],
+            output: (0..outputs)
+                .map(|value| TxOut {
+                    value: value as u64,
+                    script_pubkey: Script::new_op_return(&[0x42; 32]),
+                })
+                .collect(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        for outputs in [1, 50] {
+            let psbt = psbt(outputs);
+            for format in Format::value_variants() {
+                let encoded = encode(&psbt, *format);
+                assert_eq!(decode(&encoded).unwrap(), psbt, "{format:?} {outputs}");
+            }
+        }
+    }
+
+    #[test]
+    fn animated_formats_split_into_parts() {
+        let psbt = psbt(50);
+
+        let bbqr = String::from_utf8(encode(&psbt, Format::Bbqr)).unwrap();
+        let parts = bbqr.lines().collect::<Vec<_>>();
+        assert_eq!(parts.len(), 10);
+        assert!(parts[0].starts_with("B$2P0A00"));
+
+        let ur = String::from_utf8(encode(&psbt, Format::Ur)).unwrap();
+        let parts = ur.lines().collect::<Vec<_>>();
+        assert!(parts.len() > 1);
+        assert!(parts[0].starts_with(&format!("ur:crypto-psbt/1-{}/", parts.len())));
+
+        // Parts can be scanned in any order, but all of them are needed
+        let reversed = parts.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(decode(reversed.join("\n").as_bytes()).unwrap(), psbt);
+        assert!(decode(parts[1..].join("\n").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn bbqr_hex_parts_decode() {
+        let data = serialize(&psbt(1));
+        let hex = HEXUPPER_PERMISSIVE.encode(&data);
+        let (first, second) = hex.split_at(20);
+        let parts = format!("B$HP0201{second}\nB$HP0200{first}\n");
+        assert_eq!(decode(parts.as_bytes()).unwrap(), psbt(1));
+    }
+
+    #[test]
+    fn non_ascii_bbqr_is_rejected() {
+        for part in ["B$\u{e9}P0100AAAA", "B$2P\u{e9}0AAAA", "B$2P0100\u{1f600}"] {
+            assert!(decode(part.as_bytes()).is_err(), "{part}");
+        }
+    }
+
+    #[test]
+    fn corrupt_ur_is_rejected() {
+        let mut ur = String::from_utf8(encode(&psbt(1), Format::Ur)).unwrap();
+        // Swap the last byteword so the checksum no longer matches
+        ur.replace_range(ur.len() - 3..ur.len() - 1, "ae");
+        assert!(decode(ur.as_bytes()).is_err());
+    }
+}
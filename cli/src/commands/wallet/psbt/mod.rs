@@ -0,0 +1,257 @@
+//! Building, signing and broadcasting transactions as separate steps, passing the PSBT between
+//! them as a file so it can travel to other machines and signing devices in between.
+
+mod format;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use bdk::{
+    bitcoin::{psbt::PartiallySignedTransaction, Address, OutPoint},
+    blockchain::{log_progress, Blockchain, ElectrumBlockchain},
+    FeeRate, SignOptions,
+};
+use clap::Args;
+use rustify::blocking::clients::reqwest::Client;
+
+pub(crate) use format::Format;
+
+use crate::{
+    cache::FromCache,
+    db::{transactions::FromDatabase, Db},
+    entities::{Account, AuthenticationToken, SignerHistory},
+    requests::{helper::EndpointExt, SignTransactionRequest},
+};
+
+#[derive(Clone, Args)]
+pub(crate) struct Spend {
+    recipient: Address,
+    amount: u64,
+    /// Fee rate in sat/vB (defaults to BDK's estimate)
+    #[clap(long)]
+    fee_rate: Option<f32>,
+    /// Spend only these UTXOs (txid:vout); may be repeated
+    #[clap(long = "utxo")]
+    utxos: Vec<OutPoint>,
+}
+
+#[derive(Clone, Args)]
+pub(crate) struct Export {
+    /// File to write the PSBT to (defaults to stdout)
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+    #[clap(short, long, value_enum, default_value_t)]
+    format: Format,
+}
+
+pub(crate) fn create(
+    client: &Client,
+    db: &Db,
+    blockchain: ElectrumBlockchain,
+    spend: Spend,
+    export: &Export,
+) -> Result<()> {
+    let account = Account::from_cache(client, db)?;
+    let wallet = SignerHistory::from_database(db)?
+        .active
+        .wallet(&account, db, None)?;
+    wallet.sync(
+        &blockchain,
+        bdk::SyncOptions {
+            progress: Some(Box::new(log_progress())),
+        },
+    )?;
+
+    let mut builder = wallet.build_tx();
+    builder
+        .add_recipient(spend.recipient.script_pubkey(), spend.amount)
+        .enable_rbf();
+    if let Some(fee_rate) = spend.fee_rate {
+        builder.fee_rate(FeeRate::from_sat_per_vb(fee_rate));
+    }
+    if !spend.utxos.is_empty() {
+        builder.add_utxos(&spend.utxos)?.manually_selected_only();
+    }
+    let (psbt, _) = builder.finish()?;
+
+    write(&psbt, export)
+}
+
+pub(crate) fn show(client: &Client, db: &Db, input: &Path) -> Result<()> {
+    let psbt = read(input)?;
+    let account = Account::from_cache(client, db)?;
+    let signers = SignerHistory::from_database(db)?;
+    let wallet = signers.active.wallet(&account, db, None)?;
+    let keyset = signers.active.keyset(&account);
+    let fingerprints = [
+        ("app", keyset.application.master_fingerprint()),
+        ("hardware", keyset.hardware.master_fingerprint()),
+        ("server", keyset.server.master_fingerprint()),
+    ];
+
+    let transaction = &psbt.unsigned_tx;
+    println!("txid: {}", transaction.txid());
+
+    let mut total_in = Some(0);
+    println!("inputs:");
+    for (txin, input) in transaction.input.iter().zip(&psbt.inputs) {
+        let value = input.witness_utxo.as_ref().map(|utxo| utxo.value);
+        total_in = total_in.zip(value).map(|(total, value)| total + value);
+
+        let status = if input.final_script_witness.is_some() {
+            "finalised".to_string()
+        } else {
+            let signed = fingerprints
+                .iter()
+                .filter(|(_, fingerprint)| {
+                    input.partial_sigs.keys().any(|key| {
+                        input
+                            .bip32_derivation
+                            .get(&key.inner)
+                            .is_some_and(|(f, _)| f == fingerprint)
+                    })
+                })
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>();
+            if signed.is_empty() {
+                "unsigned".to_string()
+            } else {
+                format!("signed by {}", signed.join(", "))
+            }
+        };
+
+        match value {
+            Some(value) => println!("  {} {value} sats ({status})", txin.previous_output),
+            None => println!("  {} ? sats ({status})", txin.previous_output),
+        }
+    }
+
+    println!("outputs:");
+    for txout in &transaction.output {
+        let address = Address::from_script(&txout.script_pubkey, signers.active.network)
+            .map_or_else(|_| txout.script_pubkey.to_string(), |a| a.to_string());
+        let change = if wallet.is_mine(&txout.script_pubkey)? {
+            " (change)"
+        } else {
+            ""
+        };
+        println!("  {address} {} sats{change}", txout.value);
+    }
+
+    if let Some(total_in) = total_in {
+        let total_out = transaction.output.iter().map(|o| o.value).sum::<u64>();
+        let fee = total_in.saturating_sub(total_out);
+        let vsize = transaction.vsize() as f32;
+        println!(
+            "fee: {fee} sats (~{:.1} sat/vB unsigned)",
+            fee as f32 / vsize
+        );
+    }
+
+    Ok(())
+}
+
+pub(crate) fn sign(
+    client: &Client,
+    db: &Db,
+    input: &Path,
+    hardware: bool,
+    server: bool,
+    export: &Export,
+) -> Result<()> {
+    let mut psbt = read(input)?;
+    let account = Account::from_cache(client, db)?;
+    let signers = SignerHistory::from_database(db)?;
+
+    let context = if hardware {
+        Some(signers.active.hardware.sign_context()?)
+    } else {
+        None
+    };
+    let wallet = signers.active.wallet(&account, db, context.as_ref())?;
+    wallet.sign(
+        &mut psbt,
+        SignOptions {
+            try_finalize: false,
+            ..Default::default()
+        },
+    )?;
+
+    if server {
+        let response = SignTransactionRequest {
+            account_id: account.id,
+            psbt: psbt.clone(),
+            settings: Default::default(),
+        }
+        .exec_authenticated(client, &AuthenticationToken::from_database(db)?)?;
+
+        // Don't trust the server too much!
+        psbt.combine(response.tx).context("psbt combine error")?;
+    }
+
+    write(&psbt, export)
+}
+
+pub(crate) fn combine(inputs: &[PathBuf], export: &Export) -> Result<()> {
+    let (first, rest) = inputs.split_first().context("nothing to combine")?;
+
+    let mut psbt = read(first)?;
+    for input in rest {
+        psbt.combine(read(input)?)
+            .with_context(|| format!("combining {}", input.display()))?;
+    }
+
+    write(&psbt, export)
+}
+
+pub(crate) fn convert(input: &Path, export: &Export) -> Result<()> {
+    write(&read(input)?, export)
+}
+
+pub(crate) fn broadcast(
+    client: &Client,
+    db: &Db,
+    blockchain: ElectrumBlockchain,
+    input: &Path,
+) -> Result<()> {
+    let mut psbt = read(input)?;
+    let account = Account::from_cache(client, db)?;
+    let wallet = SignerHistory::from_database(db)?
+        .active
+        .wallet(&account, db, None)?;
+
+    let finalised = wallet.finalize_psbt(&mut psbt, Default::default())?;
+    ensure!(finalised, "PSBT doesn't have enough signatures to finalise");
+
+    let transaction = psbt.extract_tx();
+    blockchain.broadcast(&transaction)?;
+    println!("{}", transaction.txid());
+
+    Ok(())
+}
+
+fn read(input: &Path) -> Result<PartiallySignedTransaction> {
+    let contents = fs::read(input).with_context(|| format!("reading {}", input.display()))?;
+    format::decode(&contents).with_context(|| format!("decoding {}", input.display()))
+}
+
+fn write(psbt: &PartiallySignedTransaction, export: &Export) -> Result<()> {
+    let format = export.format;
+    let encoded = format::encode(psbt, format);
+    match &export.output {
+        Some(output) => {
+            fs::write(output, encoded).with_context(|| format!("writing {}", output.display()))
+        }
+        None if format == Format::Binary => bail!("binary PSBTs need an --output file"),
+        None => {
+            print!("{}", String::from_utf8(encoded)?);
+            if format == Format::Base64 {
+                println!();
+            }
+            Ok(())
+        }
+    }
+}
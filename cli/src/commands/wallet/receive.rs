@@ -2,11 +2,10 @@ use anyhow::Result;
 use bdk::wallet::AddressIndex;
 use qrcode::{render::unicode, QrCode};
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 
 use crate::{
     cache::FromCache,
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, SignerHistory},
 };
 
@@ -1,14 +1,23 @@
 use anyhow::Result;
 use rustify::blocking::clients::reqwest::Client;
+use wca::pcsc::NullTransactor;
 
 use crate::{
-    db::transactions::FromDatabase,
-    entities::{Account, AuthenticationToken, SignerHistory},
+    commands::pair::pair_hardware,
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
+    entities::{
+        Account, AuthenticationToken, HardwareSignerProxy, RecoveryDestination, SignerHistory,
+        SignerPair,
+    },
+    nfc::SafeTransactor,
     requests::{helper::EndpointExt, AuthKeypairRequest, CreateAccountDelayNotifyRequest, Factor},
     signers::{seed::SeedSigner, Authentication},
 };
 
-pub fn lost_app(client: &Client, db: &sled::Db) -> Result<()> {
+pub fn lost_app(client: &Client, db: &Db) -> Result<()> {
     let account_id = Account::from_database(db)?.id;
     let signers = SignerHistory::from_database(db)?;
     let transactor = signers.active.hardware.sign_context()?;
@@ -20,6 +29,13 @@ pub fn lost_app(client: &Client, db: &sled::Db) -> Result<()> {
         auth: AuthKeypairRequest {
             app: signers.active.application.public_key(),
             hardware: signers.active.hardware.public_key(),
+            recovery: Some(
+                signers
+                    .active
+                    .application
+                    .recovery_authentication()
+                    .public_key(),
+            ),
         },
         verification_code: None,
     }
@@ -35,3 +51,56 @@ pub fn lost_app(client: &Client, db: &sled::Db) -> Result<()> {
 
     Ok(())
 }
+
+pub fn lost_hw(client: &Client, db: &Db, use_fake_hardware: bool) -> Result<()> {
+    let account_id = Account::from_database(db)?.id;
+    let signers = SignerHistory::from_database(db)?;
+
+    // Starting again resumes the recovery to the replacement hardware we already paired
+    let destination = match RecoveryDestination::from_database(db) {
+        Ok(destination) => destination,
+        Err(_) => {
+            let network = signers.active.network;
+            RecoveryDestination {
+                signers: SignerPair {
+                    network,
+                    // The server won't reuse an authentication key, so the app gets a new one too
+                    application: SeedSigner::new(network, 0),
+                    hardware: pair_hardware(network, use_fake_hardware)?,
+                },
+            }
+        }
+    };
+    println!("{}", &destination.signers);
+
+    let response = CreateAccountDelayNotifyRequest {
+        account_id,
+        delay_period_num_sec: Some(60), // TODO: make a plan for testing in production
+        lost_factor: Factor::Hw,
+        auth: AuthKeypairRequest {
+            app: destination.signers.application.public_key(),
+            hardware: destination.signers.hardware.public_key(),
+            recovery: Some(
+                destination
+                    .signers
+                    .application
+                    .recovery_authentication()
+                    .public_key(),
+            ),
+        },
+        verification_code: None,
+    }
+    .exec_keyproofed(
+        client,
+        &AuthenticationToken::from_database(db)?,
+        Some(&signers.active.application),
+        None::<&HardwareSignerProxy>,
+        &SafeTransactor::new(NullTransactor),
+    )?;
+
+    destination.to_database(db)?;
+
+    println!("{response}");
+
+    Ok(())
+}
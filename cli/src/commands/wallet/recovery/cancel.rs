@@ -3,8 +3,10 @@ use rustify::blocking::clients::reqwest::Client;
 use wca::pcsc::NullTransactor;
 
 use crate::{
-    db::transactions::FromDatabase,
-    entities::{Account, AuthenticationToken, HardwareSignerProxy, SignerHistory},
+    db::{transactions::FromDatabase, Db},
+    entities::{
+        Account, AuthenticationToken, HardwareSignerProxy, RecoveryDestination, SignerHistory,
+    },
     nfc::SafeTransactor,
     requests::{
         helper::EndpointExt, CancelDelayNotifyRequest, Factor, PendingDelayNotify,
@@ -13,7 +15,7 @@ use crate::{
     signers::seed::SeedSigner,
 };
 
-pub fn cancel_delay_notify(client: &Client, db: &sled::Db) -> Result<()> {
+pub fn cancel_delay_notify(client: &Client, db: &Db) -> Result<()> {
     let account_id = Account::from_database(db)?.id;
     let token = AuthenticationToken::from_database(db)?;
 
@@ -54,5 +56,8 @@ pub fn cancel_delay_notify(client: &Client, db: &sled::Db) -> Result<()> {
     }
     .exec_keyproofed(client, &token, application, hardware, &context)?;
 
+    // Any replacement hardware paired for the recovery won't be used
+    RecoveryDestination::clear(db)?;
+
     Ok(())
 }
@@ -1,15 +1,39 @@
 use anyhow::Result;
+use bdk::blockchain::ElectrumBlockchain;
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 
 use crate::{
-    db::transactions::FromDatabase,
-    entities::{Account, SignerHistory},
+    commands::account::{activate_keyset, authenticate_with_app_key},
+    db::{
+        transactions::{FromDatabase, ToDatabase},
+        Db,
+    },
+    entities::{Account, RecoveryDestination, SignerHistory},
     requests::{helper::EndpointExt, CompleteDelayNotifyRequest},
 };
 
-pub(crate) fn complete_delay_notify(client: &Client, db: &Db) -> Result<()> {
+use super::sweep::sweep;
+
+pub(crate) fn complete_delay_notify(
+    client: &Client,
+    db: &Db,
+    auth_client_id: &str,
+    blockchain: ElectrumBlockchain,
+    fee_rate: Option<f32>,
+) -> Result<()> {
     let account_id = Account::from_database(db)?.id;
+
+    if let Ok(destination) = RecoveryDestination::from_database(db) {
+        return complete_lost_hw(
+            client,
+            db,
+            auth_client_id,
+            blockchain,
+            destination,
+            fee_rate,
+        );
+    }
+
     let signers = SignerHistory::from_database(db)?;
     let context = signers.active.hardware.sign_context()?;
 
@@ -23,3 +47,87 @@ pub(crate) fn complete_delay_notify(client: &Client, db: &Db) -> Result<()> {
 
     Ok(())
 }
+
+fn complete_lost_hw(
+    client: &Client,
+    db: &Db,
+    auth_client_id: &str,
+    blockchain: ElectrumBlockchain,
+    destination: RecoveryDestination,
+    fee_rate: Option<f32>,
+) -> Result<()> {
+    let account_id = Account::from_database(db)?.id;
+    let context = destination.signers.hardware.sign_context()?;
+
+    CompleteDelayNotifyRequest::new(
+        account_id.clone(),
+        &destination.signers.application,
+        &destination.signers.hardware,
+        &context,
+    )?
+    .exec_unauthenticated(client)?;
+
+    activate_recovery_destination(db, destination)?;
+
+    // The recovery rotated the authentication keys, so the old token is no good anymore
+    authenticate_with_app_key(db, auth_client_id)?;
+
+    let signers = SignerHistory::from_database(db)?;
+    activate_keyset(client, db, account_id, &signers.active, &context)?;
+
+    sweep(client, db, blockchain, fee_rate)
+}
+
+/// Makes the recovery's signers the active ones. The old signers stay in the history, since the
+/// old app key is still needed for the sweep.
+fn activate_recovery_destination(db: &Db, destination: RecoveryDestination) -> Result<()> {
+    SignerHistory::from_database(db)?
+        .rotate_to(destination.signers)
+        .to_database(db)?;
+    RecoveryDestination::clear(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::bitcoin::Network;
+
+    use super::*;
+    use crate::{db::profiles::DEFAULT_PROFILE, entities::SignerPair, signers::Spending};
+
+    #[test]
+    fn completing_lost_hw_rotates_to_the_recovery_signers() {
+        let db = Db::open(
+            sled::Config::new().temporary(true).open().unwrap(),
+            DEFAULT_PROFILE,
+        )
+        .unwrap();
+        let (original, lost, recovered) = (
+            SignerPair::fake(Network::Signet),
+            SignerPair::fake(Network::Signet),
+            SignerPair::fake(Network::Signet),
+        );
+        let expected = [&recovered, &lost, &original]
+            .map(|signers| Spending::public_key(&signers.application));
+
+        SignerHistory {
+            active: lost,
+            inactive: vec![original],
+        }
+        .to_database(&db)
+        .unwrap();
+        RecoveryDestination { signers: recovered }
+            .to_database(&db)
+            .unwrap();
+
+        activate_recovery_destination(&db, RecoveryDestination::from_database(&db).unwrap())
+            .unwrap();
+
+        let signers = SignerHistory::from_database(&db).unwrap();
+        let keys: Vec<_> = std::iter::once(&signers.active)
+            .chain(&signers.inactive)
+            .map(|signers| Spending::public_key(&signers.application))
+            .collect();
+        assert_eq!(keys, expected);
+        assert!(RecoveryDestination::from_database(&db).is_err());
+    }
+}
@@ -2,3 +2,4 @@ pub mod begin;
 pub mod cancel;
 pub mod complete;
 pub mod status;
+pub mod sweep;
@@ -1,20 +1,84 @@
+use std::{thread::sleep, time::Duration as StdDuration};
+
 use anyhow::Result;
+use indicatif::{ProgressBar, ProgressStyle};
 use rustify::blocking::client::Client;
+use time::{Duration, OffsetDateTime};
 
 use crate::requests::helper::EndpointExt;
 use crate::{
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, AuthenticationToken},
-    requests::RecoveryStatusRequest,
+    requests::{PendingDelayNotify, RecoveryStatusRequest},
+    serde_helpers::AccountId,
 };
 
-pub fn status_delay_notify(client: &impl Client, db: &sled::Db) -> Result<()> {
+// How often to check with the server that the recovery is still pending while counting down
+const POLL_INTERVAL: Duration = Duration::seconds(15);
+
+pub fn status_delay_notify(client: &impl Client, db: &Db, wait: bool) -> Result<()> {
     let account_id = Account::from_database(db)?.id;
+    let token = AuthenticationToken::from_database(db)?;
 
-    let response = RecoveryStatusRequest { account_id }
-        .exec_authenticated(client, &AuthenticationToken::from_database(db)?)?;
+    let response = RecoveryStatusRequest {
+        account_id: account_id.clone(),
+    }
+    .exec_authenticated(client, &token)?;
 
     println!("{response}");
 
+    match response.pending_delay_notify {
+        Some(pending) if wait => count_down(client, &token, account_id, &pending),
+        _ => Ok(()),
+    }
+}
+
+fn count_down(
+    client: &impl Client,
+    token: &AuthenticationToken,
+    account_id: AccountId,
+    pending: &PendingDelayNotify,
+) -> Result<()> {
+    let delay = pending.delay_end_time - pending.delay_start_time;
+    let bar = ProgressBar::new(delay.whole_seconds().max(0) as u64)
+        .with_style(ProgressStyle::with_template("{bar:40} {msg}")?);
+
+    let mut polled_at = OffsetDateTime::now_utc();
+    loop {
+        let now = OffsetDateTime::now_utc();
+        let remaining = pending.delay_end_time - now;
+        if remaining <= Duration::ZERO {
+            break;
+        }
+
+        if now - polled_at >= POLL_INTERVAL {
+            polled_at = now;
+            let response = RecoveryStatusRequest {
+                account_id: account_id.clone(),
+            }
+            .exec_authenticated(client, token)?;
+            if response.pending_delay_notify.is_none() {
+                bar.abandon_with_message("Recovery is no longer pending");
+                return Ok(());
+            }
+        }
+
+        bar.set_position((delay - remaining).whole_seconds().max(0) as u64);
+        bar.set_message(format!("{} remaining", format_remaining(remaining)));
+        sleep(StdDuration::from_secs(1));
+    }
+
+    bar.finish_with_message("Ready to complete");
+
     Ok(())
 }
+
+fn format_remaining(remaining: Duration) -> String {
+    let seconds = remaining.whole_seconds();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
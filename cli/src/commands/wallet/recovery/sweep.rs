@@ -0,0 +1,227 @@
+use anyhow::{ensure, Context, Result};
+use bdk::{
+    bitcoin::{psbt::PartiallySignedTransaction, Script},
+    blockchain::{log_progress, Blockchain, ElectrumBlockchain},
+    database::{BatchDatabase, MemoryDatabase},
+    wallet::AddressIndex,
+    FeeRate, SignOptions, Wallet,
+};
+use rustify::blocking::clients::reqwest::Client;
+
+use crate::{
+    cache::FromCache,
+    db::{transactions::FromDatabase, Db},
+    entities::{Account, AuthenticationToken, SignerHistory, SignerPair},
+    requests::{helper::EndpointExt, SignTransactionWithKeysetRequest},
+    serde_helpers::KeysetId,
+    signers::Spending,
+};
+
+/// How many blocks the sweep's estimated fee rate aims to confirm within
+const SWEEP_CONFIRMATION_TARGET: usize = 6;
+
+/// Sweeps the funds left on the keyset that was active before the last rotation into the active
+/// keyset. Only the app can still sign for it, so the server co-signs under its sweep rules.
+pub(crate) fn sweep(
+    client: &Client,
+    db: &Db,
+    blockchain: ElectrumBlockchain,
+    fee_rate: Option<f32>,
+) -> Result<()> {
+    let account = Account::from_cache(client, db)?;
+    let signers = SignerHistory::from_database(db)?;
+    let (source, keyset_id) = sweep_source(&account, &signers)?;
+
+    // The profile's wallet tree caches the active keyset, so the source is synced from scratch
+    let source_wallet = source.wallet_with_database(&account, MemoryDatabase::default(), None)?;
+    source_wallet.sync(
+        &blockchain,
+        bdk::SyncOptions {
+            progress: Some(Box::new(log_progress())),
+        },
+    )?;
+
+    let balance = source_wallet.get_balance()?;
+    if balance.get_total() == 0 {
+        println!("Nothing to sweep");
+        return Ok(());
+    }
+    println!("Sweeping {balance}");
+
+    let destination = signers
+        .active
+        .wallet(&account, db, None)?
+        .get_address(AddressIndex::New)?;
+
+    let fee_rate = match fee_rate {
+        Some(fee_rate) => FeeRate::from_sat_per_vb(fee_rate),
+        None => {
+            let estimate = blockchain.estimate_fee(SWEEP_CONFIRMATION_TARGET)?;
+            // Electrum reports a negative rate when it has no estimate
+            ensure!(
+                estimate.as_sat_per_vb() > 0.0,
+                "no fee estimate available, pass --fee-rate instead"
+            );
+            estimate
+        }
+    };
+    println!("Fee rate: {} sat/vB", fee_rate.as_sat_per_vb());
+    let mut psbt = sweep_psbt(&source_wallet, &destination.script_pubkey(), fee_rate)?;
+
+    let response = SignTransactionWithKeysetRequest {
+        account_id: account.id,
+        keyset_id,
+        psbt: psbt.clone(),
+    }
+    .exec_authenticated(client, &AuthenticationToken::from_database(db)?)?;
+
+    // Don't trust the server too much!
+    psbt.combine(response.tx).context("psbt combine error")?;
+    let finalised = source_wallet.finalize_psbt(&mut psbt, Default::default())?;
+    ensure!(finalised, "server didn't co-sign the sweep");
+
+    let transaction = psbt.extract_tx();
+    blockchain.broadcast(&transaction)?;
+    println!("{}", transaction.txid());
+
+    Ok(())
+}
+
+/// The signers to sweep from, which are the ones most recently replaced, and their keyset.
+fn sweep_source<'a>(
+    account: &Account,
+    signers: &'a SignerHistory,
+) -> Result<(&'a SignerPair, KeysetId)> {
+    let source = signers
+        .inactive
+        .first()
+        .context("no previous signers to sweep from")?;
+    let keyset_id = account
+        .keysets
+        .iter()
+        .find(|ks| {
+            ks.keys.application == source.application.public_key()
+                && ks.keys.hardware == source.hardware.public_key()
+        })
+        .context("previous signers aren't in any of the account's keysets")?
+        .id
+        .clone();
+    Ok((source, keyset_id))
+}
+
+/// Drains every UTXO of `source_wallet` to `destination`, signing with the keys the wallet has.
+fn sweep_psbt<D: BatchDatabase>(
+    source_wallet: &Wallet<D>,
+    destination: &Script,
+    fee_rate: FeeRate,
+) -> Result<PartiallySignedTransaction> {
+    let mut builder = source_wallet.build_tx();
+    builder
+        .drain_wallet()
+        .drain_to(destination.clone())
+        .fee_rate(fee_rate)
+        .enable_rbf();
+    let (mut psbt, _) = builder.finish()?;
+    source_wallet.sign(
+        &mut psbt,
+        SignOptions {
+            try_finalize: false,
+            ..Default::default()
+        },
+    )?;
+    Ok(psbt)
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk::{
+        bitcoin::Network,
+        psbt::PsbtUtils,
+        wallet::{get_funded_wallet, AddressIndex},
+    };
+
+    use super::*;
+    use crate::{
+        entities::{DescriptorKeyset, Keyset},
+        serde_helpers::AccountId,
+    };
+
+    fn keyset(id: &str, signers: &SignerPair) -> Keyset {
+        Keyset {
+            id: KeysetId(id.to_string()),
+            network: signers.network,
+            keys: DescriptorKeyset {
+                application: signers.application.public_key(),
+                hardware: signers.hardware.public_key(),
+                server: signers.application.public_key(),
+            },
+        }
+    }
+
+    #[test]
+    fn sweeps_from_the_most_recently_replaced_signers() {
+        let (oldest, previous, active) = (
+            SignerPair::fake(Network::Signet),
+            SignerPair::fake(Network::Signet),
+            SignerPair::fake(Network::Signet),
+        );
+        let account = Account {
+            id: AccountId("account".to_string()),
+            keysets: vec![
+                keyset("oldest", &oldest),
+                keyset("previous", &previous),
+                keyset("active", &active),
+            ],
+        };
+        let signers = SignerHistory {
+            active,
+            inactive: vec![previous, oldest],
+        };
+
+        let (source, keyset_id) = sweep_source(&account, &signers).unwrap();
+        assert!(std::ptr::eq(source, &signers.inactive[0]));
+        assert_eq!(keyset_id.0, "previous");
+    }
+
+    #[test]
+    fn sweep_source_needs_known_previous_signers() {
+        let account = Account {
+            id: AccountId("account".to_string()),
+            keysets: vec![],
+        };
+        let never_rotated = SignerHistory {
+            active: SignerPair::fake(Network::Signet),
+            inactive: vec![],
+        };
+        assert!(sweep_source(&account, &never_rotated).is_err());
+
+        let unknown = never_rotated.rotate_to(SignerPair::fake(Network::Signet));
+        assert!(sweep_source(&account, &unknown).is_err());
+    }
+
+    #[test]
+    fn sweep_drains_the_wallet_at_the_fee_rate() {
+        let (wallet, _, _) =
+            get_funded_wallet("wpkh(cVpPVruEDdmutPzisEsYvtST1usBR3ntr8pXSyt6D2YYqXRyPcFW)");
+        let destination = wallet
+            .get_address(AddressIndex::New)
+            .unwrap()
+            .script_pubkey();
+        let fee_rate = FeeRate::from_sat_per_vb(5.0);
+
+        let psbt = sweep_psbt(&wallet, &destination, fee_rate).unwrap();
+
+        let outputs = &psbt.unsigned_tx.output;
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].script_pubkey, destination);
+        assert_eq!(
+            outputs[0].value + psbt.fee_amount().unwrap(),
+            wallet.get_balance().unwrap().get_total()
+        );
+        assert!(psbt.fee_rate().unwrap() >= fee_rate);
+        assert!(psbt
+            .inputs
+            .iter()
+            .all(|input| !input.partial_sigs.is_empty()));
+    }
+}
@@ -2,10 +2,10 @@ use anyhow::{Context, Result};
 use bdk::blockchain::ElectrumBlockchain;
 use bdk::{bitcoin::Address, blockchain::Blockchain};
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 
 use crate::cache::FromCache;
 use crate::db::transactions::FromDatabase;
+use crate::db::Db;
 use crate::entities::{Account, AuthenticationToken, SignerHistory};
 use crate::requests::helper::EndpointExt;
 use crate::{commands::wallet::psbt_from, requests::SignTransactionRequest};
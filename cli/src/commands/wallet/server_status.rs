@@ -2,12 +2,12 @@ use anyhow::Result;
 use rustify::blocking::clients::reqwest::Client;
 
 use crate::{
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, AuthenticationToken},
     requests::{helper::EndpointExt, GetWalletStatusRequest},
 };
 
-pub fn server_status(client: &Client, db: &sled::Db) -> Result<()> {
+pub fn server_status(client: &Client, db: &Db) -> Result<()> {
     let account_id = Account::from_database(db)?.id;
     let response = GetWalletStatusRequest {
         account_id: account_id.clone(),
@@ -3,11 +3,12 @@ use rustify::blocking::clients::reqwest::Client;
 use time::UtcOffset;
 
 use crate::db::transactions::FromDatabase;
+use crate::db::Db;
 use crate::entities::{Account, AuthenticationToken, SignerHistory};
 use crate::requests::helper::EndpointExt;
 use crate::requests::{CurrencyCode, MobilePaySetupRequest, Money, SpendingLimit};
 
-pub fn setup_mobile_pay(client: &Client, db: &sled::Db, amount: u64) -> Result<()> {
+pub fn setup_mobile_pay(client: &Client, db: &Db, amount: u64) -> Result<()> {
     let account_id = Account::from_database(db)?.id;
     let signers = SignerHistory::from_database(db)?;
     let context = signers.active.hardware.sign_context()?;
@@ -7,11 +7,11 @@ use rustify::blocking::clients::reqwest::Client;
 
 use crate::{
     cache::FromCache,
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, AuthenticationToken, SignerHistory},
 };
 
-pub fn status(client: &Client, db: &sled::Db) -> Result<()> {
+pub fn status(client: &Client, db: &Db) -> Result<()> {
     let signers = SignerHistory::from_database(db)?;
     println!("Active:");
     println!("{}", indent(&signers.active.to_string()));
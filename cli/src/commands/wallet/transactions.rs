@@ -5,11 +5,11 @@ use time::OffsetDateTime;
 
 use crate::{
     cache::FromCache,
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, SignerHistory},
 };
 
-pub fn transactions(client: &Client, db: &sled::Db, blockchain: ElectrumBlockchain) -> Result<()> {
+pub fn transactions(client: &Client, db: &Db, blockchain: ElectrumBlockchain) -> Result<()> {
     let account = Account::from_cache(client, db)?;
     let wallet = SignerHistory::from_database(db)?
         .active
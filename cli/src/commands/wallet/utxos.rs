@@ -4,11 +4,10 @@ use bdk::{
     KeychainKind,
 };
 use rustify::blocking::clients::reqwest::Client;
-use sled::Db;
 
 use crate::{
     cache::FromCache,
-    db::transactions::FromDatabase,
+    db::{transactions::FromDatabase, Db},
     entities::{Account, SignerHistory},
 };
 
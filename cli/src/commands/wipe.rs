@@ -1,10 +1,9 @@
 use anyhow::{bail, Result};
-use wca::pcsc::PCSCTransactor;
 
-use crate::nfc::NFCTransactions;
+use crate::nfc::{self, NFCTransactions};
 
 pub(crate) fn wipe() -> Result<()> {
-    if !PCSCTransactor::new()?.wipe()? {
+    if !nfc::connect()?.wipe()? {
         bail!("failed")
     }
 
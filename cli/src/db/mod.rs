@@ -1,7 +1,70 @@
+use std::ops::Deref;
+
+use anyhow::{bail, Result};
+
+pub mod profiles;
 pub mod transactions;
 pub mod wallet;
 
 const DB_ACCOUNT: &str = "account";
 const DB_AUTHENTICATION_TOKEN: &str = "authentication-token";
+const DB_FWUP_CHECKPOINT: &str = "fwup-checkpoint";
+const DB_PROFILE: &str = "profile";
+const DB_RECOVERY_DESTINATION: &str = "recovery-destination";
+const DB_SELECTED_PROFILE: &str = "selected-profile";
 const DB_SIGNER_HISTORY: &str = "signer-history";
+const DB_SOCIAL_RECOVERY: &str = "social-recovery";
 const DB_WALLET: &str = "wallet";
+
+const PROFILE_TREE_PREFIX: &str = "profile/";
+
+/// The part of the wallet database belonging to one profile.
+///
+/// It reads and writes like a sled tree, and `open_tree` opens trees private to the profile. The
+/// default profile lives at the top level of the database, where everything was kept before
+/// profiles existed.
+#[derive(Clone)]
+pub(crate) struct Db {
+    db: sled::Db,
+    tree: sled::Tree,
+    profile: String,
+}
+
+impl Db {
+    pub(crate) fn open(db: sled::Db, profile: &str) -> Result<Self> {
+        let tree = if profile == profiles::DEFAULT_PROFILE {
+            (*db).clone()
+        } else if profiles::exists(&db, profile) {
+            db.open_tree(profile_tree_name(profile))?
+        } else {
+            bail!("no profile named {profile}");
+        };
+
+        Ok(Self {
+            db,
+            tree,
+            profile: profile.to_string(),
+        })
+    }
+
+    pub(crate) fn open_tree(&self, name: &str) -> sled::Result<sled::Tree> {
+        if self.profile == profiles::DEFAULT_PROFILE {
+            self.db.open_tree(name)
+        } else {
+            self.db
+                .open_tree(format!("{}/{name}", profile_tree_name(&self.profile)))
+        }
+    }
+}
+
+impl Deref for Db {
+    type Target = sled::Tree;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tree
+    }
+}
+
+fn profile_tree_name(profile: &str) -> String {
+    format!("{PROFILE_TREE_PREFIX}{profile}")
+}
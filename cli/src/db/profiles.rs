@@ -0,0 +1,149 @@
+use anyhow::{bail, Result};
+
+use super::{profile_tree_name, DB_SELECTED_PROFILE, PROFILE_TREE_PREFIX};
+
+/// The profile that uses the top level of the database, so wallets created before profiles
+/// existed carry on working.
+pub(crate) const DEFAULT_PROFILE: &str = "default";
+
+pub(crate) fn selected(db: &sled::Db) -> Result<String> {
+    match db.get(DB_SELECTED_PROFILE)? {
+        Some(name) => Ok(String::from_utf8(name.to_vec())?),
+        None => Ok(DEFAULT_PROFILE.to_string()),
+    }
+}
+
+pub(crate) fn select(db: &sled::Db, name: &str) -> Result<()> {
+    if !exists(db, name) {
+        bail!("no profile named {name}");
+    }
+
+    db.insert(DB_SELECTED_PROFILE, name.as_bytes())?;
+    Ok(())
+}
+
+pub(crate) fn exists(db: &sled::Db, name: &str) -> bool {
+    name == DEFAULT_PROFILE || list(db).iter().any(|profile| profile == name)
+}
+
+/// All profiles, default first and the rest in name order.
+pub(crate) fn list(db: &sled::Db) -> Vec<String> {
+    let mut profiles = vec![DEFAULT_PROFILE.to_string()];
+    profiles.extend(db.tree_names().into_iter().filter_map(|tree| {
+        let name = std::str::from_utf8(&tree).ok()?;
+        // Trees opened by a profile are named `profile/<name>/<tree>`, so skip those
+        match name.strip_prefix(PROFILE_TREE_PREFIX) {
+            Some(profile) if !profile.contains('/') => Some(profile.to_string()),
+            _ => None,
+        }
+    }));
+    profiles[1..].sort();
+    profiles
+}
+
+pub(crate) fn create(db: &sled::Db, name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("profile names may only contain letters, digits, '-' and '_'");
+    }
+    if exists(db, name) {
+        bail!("profile {name} already exists");
+    }
+
+    db.open_tree(profile_tree_name(name))?;
+    Ok(())
+}
+
+/// Deletes a profile and everything stored under it. Deleting the selected profile selects the
+/// default one again.
+pub(crate) fn delete(db: &sled::Db, name: &str) -> Result<()> {
+    if name == DEFAULT_PROFILE {
+        bail!("the default profile can't be deleted");
+    }
+    if !exists(db, name) {
+        bail!("no profile named {name}");
+    }
+
+    let tree = profile_tree_name(name);
+    let prefix = format!("{tree}/");
+    for owned in db.tree_names() {
+        if owned.starts_with(prefix.as_bytes()) {
+            db.drop_tree(owned)?;
+        }
+    }
+    db.drop_tree(tree)?;
+
+    if selected(db)? == name {
+        db.remove(DB_SELECTED_PROFILE)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Db;
+
+    fn temporary() -> sled::Db {
+        sled::Config::new().temporary(true).open().unwrap()
+    }
+
+    #[test]
+    fn profiles_are_listed_and_selected() {
+        let db = temporary();
+        assert_eq!(list(&db), vec![DEFAULT_PROFILE]);
+        assert_eq!(selected(&db).unwrap(), DEFAULT_PROFILE);
+
+        create(&db, "signet").unwrap();
+        create(&db, "regtest").unwrap();
+        assert!(create(&db, "signet").is_err());
+        assert!(create(&db, "no spaces").is_err());
+        assert_eq!(list(&db), vec![DEFAULT_PROFILE, "regtest", "signet"]);
+
+        select(&db, "signet").unwrap();
+        assert_eq!(selected(&db).unwrap(), "signet");
+        assert!(select(&db, "mainnet").is_err());
+    }
+
+    #[test]
+    fn profiles_are_isolated() {
+        let db = temporary();
+        create(&db, "other").unwrap();
+
+        let default = Db::open(db.clone(), DEFAULT_PROFILE).unwrap();
+        let other = Db::open(db.clone(), "other").unwrap();
+        default.insert("key", "default").unwrap();
+        other.insert("key", "other").unwrap();
+        other
+            .open_tree("wallet")
+            .unwrap()
+            .insert("key", "")
+            .unwrap();
+
+        assert_eq!(&*default.get("key").unwrap().unwrap(), b"default");
+        assert_eq!(&*other.get("key").unwrap().unwrap(), b"other");
+        assert!(default.open_tree("wallet").unwrap().is_empty());
+        assert_eq!(list(&db), vec![DEFAULT_PROFILE, "other"]);
+    }
+
+    #[test]
+    fn deleting_the_selected_profile_falls_back_to_default() {
+        let db = temporary();
+        create(&db, "other").unwrap();
+        select(&db, "other").unwrap();
+        Db::open(db.clone(), "other")
+            .unwrap()
+            .open_tree("wallet")
+            .unwrap();
+
+        delete(&db, "other").unwrap();
+        assert_eq!(selected(&db).unwrap(), DEFAULT_PROFILE);
+        assert_eq!(list(&db), vec![DEFAULT_PROFILE]);
+        assert_eq!(db.tree_names().len(), 1);
+        assert!(Db::open(db, "other").is_err());
+        assert!(delete(&temporary(), DEFAULT_PROFILE).is_err());
+    }
+}
@@ -1,11 +1,16 @@
 use anyhow::{anyhow, Error, Result};
 
 use serde::{de::DeserializeOwned, Serialize};
-use sled::Db;
 
-use crate::entities::{Account, AuthenticationToken, SignerHistory};
+use crate::entities::{
+    Account, AuthenticationToken, FwupCheckpoint, ProfileConfig, RecoveryDestination,
+    SignerHistory, SocialRecovery,
+};
 
-use super::{DB_ACCOUNT, DB_AUTHENTICATION_TOKEN, DB_SIGNER_HISTORY};
+use super::{
+    Db, DB_ACCOUNT, DB_AUTHENTICATION_TOKEN, DB_FWUP_CHECKPOINT, DB_PROFILE,
+    DB_RECOVERY_DESTINATION, DB_SIGNER_HISTORY, DB_SOCIAL_RECOVERY,
+};
 
 fn get<T: DeserializeOwned>(tree: &sled::Tree, key: &str) -> Result<T> {
     let value = tree
@@ -76,3 +81,81 @@ impl FromDatabase for AuthenticationToken {
         get(db, DB_AUTHENTICATION_TOKEN)
     }
 }
+
+impl ToDatabase for FwupCheckpoint {
+    fn to_database(self, db: &Db) -> Result<()> {
+        set(db, DB_FWUP_CHECKPOINT, self)?;
+        Ok(())
+    }
+}
+
+impl FromDatabase for FwupCheckpoint {
+    fn from_database(db: &Db) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        get(db, DB_FWUP_CHECKPOINT)
+    }
+}
+
+impl FwupCheckpoint {
+    pub(crate) fn clear(db: &Db) -> Result<()> {
+        db.remove(DB_FWUP_CHECKPOINT)?;
+        Ok(())
+    }
+}
+
+impl ToDatabase for ProfileConfig {
+    fn to_database(self, db: &Db) -> Result<()> {
+        set(db, DB_PROFILE, self)?;
+        Ok(())
+    }
+}
+
+impl FromDatabase for ProfileConfig {
+    fn from_database(db: &Db) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        get(db, DB_PROFILE)
+    }
+}
+
+impl ToDatabase for RecoveryDestination {
+    fn to_database(self, db: &Db) -> Result<()> {
+        set(db, DB_RECOVERY_DESTINATION, self)?;
+        Ok(())
+    }
+}
+
+impl FromDatabase for RecoveryDestination {
+    fn from_database(db: &Db) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        get(db, DB_RECOVERY_DESTINATION)
+    }
+}
+
+impl RecoveryDestination {
+    pub(crate) fn clear(db: &Db) -> Result<()> {
+        db.remove(DB_RECOVERY_DESTINATION)?;
+        Ok(())
+    }
+}
+
+impl ToDatabase for SocialRecovery {
+    fn to_database(self, db: &Db) -> Result<()> {
+        set(db, DB_SOCIAL_RECOVERY, self)?;
+        Ok(())
+    }
+}
+
+impl FromDatabase for SocialRecovery {
+    fn from_database(db: &Db) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        get(db, DB_SOCIAL_RECOVERY)
+    }
+}
@@ -1,12 +1,14 @@
 use anyhow::Result;
 use bdk::{
     bitcoin::util::bip32::ChildNumber,
+    database::BatchDatabase,
     descriptor::{DescriptorPublicKey, ExtendedDescriptor},
     miniscript::Descriptor,
     signer::SignerOrdering,
     Wallet,
 };
-use sled::{Db, Tree};
+use sled::Tree;
+
 use wca::{pcsc::NullTransactor, signing::ExtendDerivationPath};
 
 use crate::{
@@ -15,7 +17,7 @@ use crate::{
     signers::Spending,
 };
 
-use super::DB_WALLET;
+use super::{Db, DB_WALLET};
 
 impl SignerPair {
     pub(crate) fn wallet(
@@ -24,7 +26,18 @@ impl SignerPair {
         db: &Db,
         context: Option<&SafeTransactor>,
     ) -> Result<Wallet<Tree>> {
-        let keyset = find_active_keyset(&account.keysets, self);
+        self.wallet_with_database(account, db.open_tree(DB_WALLET)?, context)
+    }
+
+    /// Like `wallet`, but keeping the chain data in `database` rather than the profile's wallet
+    /// tree, which only caches the active keyset.
+    pub(crate) fn wallet_with_database<D: BatchDatabase>(
+        &self,
+        account: &Account,
+        database: D,
+        context: Option<&SafeTransactor>,
+    ) -> Result<Wallet<D>> {
+        let keyset = self.keyset(account);
         let receive_descriptor = keyset.receiving().into_multisig_descriptor();
         let change_descriptor = keyset.change().into_multisig_descriptor();
 
@@ -32,7 +45,7 @@ impl SignerPair {
             receive_descriptor,
             Some(change_descriptor),
             self.network,
-            db.open_tree(DB_WALLET)?,
+            database,
         )?;
 
         let signer_application = self
@@ -55,6 +68,17 @@ impl SignerPair {
 
         Ok(wallet)
     }
+
+    pub(crate) fn keyset(&self, account: &Account) -> DescriptorKeyset {
+        find_active_keyset(&account.keysets, self)
+    }
+}
+
+/// Drops the chain data cached for the active keyset, which no longer matches the wallet's
+/// descriptors once another keyset becomes active.
+pub(crate) fn clear_wallet_cache(db: &Db) -> Result<()> {
+    db.open_tree(DB_WALLET)?.clear()?;
+    Ok(())
 }
 
 pub const SPENDING_PATH: [ChildNumber; 1] = [ChildNumber::Normal { index: 0 }];
@@ -1,6 +1,6 @@
 pub mod display;
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use bdk::{
     bitcoin::{
@@ -11,23 +11,107 @@ use bdk::{
     signer::TransactionSigner,
 };
 use serde::{Deserialize, Serialize};
-use wca::pcsc::{NullTransactor, PCSCTransactor, Transactor, TransactorError};
+use wca::{
+    emulator::EmulatedTransactor,
+    pcsc::{NullTransactor, Transactor, TransactorError},
+};
 
 use crate::{
-    nfc::SafeTransactor,
-    serde_helpers::{string as serde_string, AccountId, KeysetId},
+    nfc::{self, SafeTransactor},
+    serde_helpers::{
+        string as serde_string, AccountId, KeysetId, RecoveryRelationshipId, SocialChallengeId,
+    },
     signers::{hardware::HardwareSigner, seed::SeedSigner, Authentication, Spending},
 };
 
 #[derive(Clone, Deserialize, Serialize)]
 pub(crate) struct AuthenticationToken(pub(crate) String);
 
+/// How far an interrupted firmware upload got, so the next attempt at the same image can pick up
+/// where it left off.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub(crate) struct FwupCheckpoint {
+    /// SHA-256 of the image being uploaded
+    pub(crate) digest: String,
+    pub(crate) next_sequence_id: u32,
+}
+
+/// Per-profile settings. Anything left unset falls back to the command line default.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub(crate) struct ProfileConfig {
+    pub(crate) network: Option<Network>,
+    pub(crate) server: Option<String>,
+    pub(crate) electrum: Option<String>,
+}
+
 #[derive(Deserialize, Serialize)]
 pub(crate) struct SignerHistory {
     pub(crate) active: SignerPair,
     pub(crate) inactive: Vec<SignerPair>,
 }
 
+impl SignerHistory {
+    /// Makes `signers` the active pair, keeping the pair it replaces as the most recent inactive
+    /// one.
+    pub(crate) fn rotate_to(self, signers: SignerPair) -> Self {
+        let mut inactive = vec![self.active];
+        inactive.extend(self.inactive);
+        Self {
+            active: signers,
+            inactive,
+        }
+    }
+}
+
+/// The signers a pending Lost Hardware recovery rotates the account to. They only become active
+/// once the recovery completes; until then the existing signers still authenticate the account.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct RecoveryDestination {
+    pub(crate) signers: SignerPair,
+}
+
+/// What a profile has to remember between the steps of social recovery, whether it's the protected
+/// customer or a trusted contact. None of it is ever sent to the server.
+#[derive(Default, Deserialize, Serialize)]
+pub(crate) struct SocialRecovery {
+    /// Enrollment PAKE keys for the invitations we've sent, until we endorse who accepted them
+    pub(crate) invitations: BTreeMap<RecoveryRelationshipId, PakeKey>,
+    /// The keys our customers sealed their data encryption keys to, for relationships we accepted
+    pub(crate) delegated_decryption_keys: BTreeMap<RecoveryRelationshipId, DelegatedDecryptionKey>,
+    /// Stands in for the app's cloud backup
+    pub(crate) backup: Option<SocialBackup>,
+    pub(crate) challenge: Option<SocialChallenge>,
+}
+
+/// A SPAKE2 key pair generated by the protected customer, along with the code it was generated
+/// from. Finishing the exchange once the trusted contact answers needs all three.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct PakeKey {
+    pub(crate) code: [u8; 16],
+    pub(crate) private_key: [u8; 32],
+    pub(crate) public_key: [u8; 32],
+}
+
+#[derive(Deserialize, Serialize)]
+pub(crate) struct DelegatedDecryptionKey {
+    pub(crate) secret_key: [u8; 32],
+    pub(crate) public_key: [u8; 32],
+}
+
+/// The data encryption key, and a copy of it sealed to each endorsed trusted contact.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct SocialBackup {
+    pub(crate) dek: [u8; 32],
+    pub(crate) sealed_deks: BTreeMap<RecoveryRelationshipId, String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub(crate) struct SocialChallenge {
+    pub(crate) id: SocialChallengeId,
+    pub(crate) counter: u32,
+    pub(crate) pake: PakeKey,
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Eq)]
 pub(crate) struct SignerPair {
     pub(crate) network: Network,
@@ -35,17 +119,37 @@ pub(crate) struct SignerPair {
     pub(crate) hardware: HardwareSignerProxy,
 }
 
+#[cfg(test)]
+impl SignerPair {
+    /// A pair of freshly seeded signers, with a fake hardware signer standing in for the device.
+    pub(crate) fn fake(network: Network) -> Self {
+        Self {
+            network,
+            application: SeedSigner::new(network, 0),
+            hardware: HardwareSignerProxy::Fake(SeedSigner::new(network, 0)),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, PartialEq, Eq)]
 pub(crate) enum HardwareSignerProxy {
     Fake(SeedSigner),
     Real(HardwareSigner),
+    /// A hardware signer backed by an in-process emulated device, whose keys derive from `seed`.
+    Emulated {
+        seed: [u8; 32],
+        signer: HardwareSigner,
+    },
 }
 
 impl HardwareSignerProxy {
     pub(crate) fn sign_context(&self) -> Result<SafeTransactor, TransactorError> {
         match self {
             HardwareSignerProxy::Fake(_) => Ok(SafeTransactor::new(NullTransactor)),
-            HardwareSignerProxy::Real(_) => Ok(SafeTransactor::new(PCSCTransactor::new()?)),
+            HardwareSignerProxy::Real(_) => Ok(SafeTransactor::new(nfc::connect()?)),
+            HardwareSignerProxy::Emulated { seed, .. } => Ok(SafeTransactor::new(
+                EmulatedTransactor::from_seed(*seed).enrolled(),
+            )),
         }
     }
 }
@@ -55,6 +159,7 @@ impl Authentication for HardwareSignerProxy {
         match self {
             HardwareSignerProxy::Fake(s) => Authentication::public_key(s),
             HardwareSignerProxy::Real(s) => Authentication::public_key(s),
+            HardwareSignerProxy::Emulated { signer, .. } => Authentication::public_key(signer),
         }
     }
 
@@ -66,6 +171,9 @@ impl Authentication for HardwareSignerProxy {
         match self {
             HardwareSignerProxy::Fake(s) => Authentication::sign(s, message, context),
             HardwareSignerProxy::Real(s) => Authentication::sign(s, message, context),
+            HardwareSignerProxy::Emulated { signer, .. } => {
+                Authentication::sign(signer, message, context)
+            }
         }
     }
 }
@@ -75,6 +183,7 @@ impl Spending for HardwareSignerProxy {
         match self {
             HardwareSignerProxy::Fake(s) => Spending::public_key(s),
             HardwareSignerProxy::Real(s) => Spending::public_key(s),
+            HardwareSignerProxy::Emulated { signer, .. } => Spending::public_key(signer),
         }
     }
 
@@ -86,6 +195,10 @@ impl Spending for HardwareSignerProxy {
         let proxy = match self {
             HardwareSignerProxy::Fake(s) => Self::Fake(Spending::next(s, seen, context)?),
             HardwareSignerProxy::Real(s) => Self::Real(Spending::next(s, seen, context)?),
+            HardwareSignerProxy::Emulated { seed, signer } => Self::Emulated {
+                seed: *seed,
+                signer: Spending::next(signer, seen, context)?,
+            },
         };
         Ok(proxy)
     }
@@ -94,6 +207,7 @@ impl Spending for HardwareSignerProxy {
         match self {
             HardwareSignerProxy::Fake(s) => Spending::signer(s, context),
             HardwareSignerProxy::Real(s) => Spending::signer(s, context),
+            HardwareSignerProxy::Emulated { signer, .. } => Spending::signer(signer, context),
         }
     }
 }
@@ -10,6 +10,7 @@ mod serde_helpers;
 mod signers;
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::Result;
 use bdk::bitcoin::network::constants::Network;
@@ -21,6 +22,11 @@ use bdk::electrum_client::Client as ElectrumClient;
 use clap::{Parser, Subcommand};
 use rustify::blocking::clients::reqwest::Client;
 use tracing_subscriber::{prelude::*, EnvFilter, Registry};
+use wca::pcsc::ReaderOptions;
+
+use crate::commands::wallet::psbt::{Export, Spend};
+use crate::db::{profiles, transactions::FromDatabase, Db};
+use crate::entities::ProfileConfig;
 
 #[derive(Clone, Parser)]
 #[clap()]
@@ -29,18 +35,30 @@ pub struct Cli {
     #[clap(short, long, default_value = "wallet.db")]
     wallet: String,
 
-    /// URL for the server
-    #[clap(short, long, default_value = "https://api.dev.wallet.build")]
-    server: String,
+    /// Profile within the wallet database to use (defaults to the selected profile)
+    #[clap(short, long)]
+    profile: Option<String>,
+
+    /// URL for the server (defaults to the profile's server, or https://api.dev.wallet.build)
+    #[clap(short, long)]
+    server: Option<String>,
 
     /// Cognito Client ID for the wallet user-pool
     /// This one is from user pool us-west-2_YNkgrK6JC in the dev account
     #[clap(short, long, default_value = "dk4rvffhp6k55bjb05vbemdn5")]
     auth_client_id: String,
 
-    /// URL for the Electrum node
-    #[clap(short, long, default_value = "ssl://electrum.nodes.wallet.build:51002")]
-    electrum: String,
+    /// URL for the Electrum node (defaults to the profile's node, or ssl://electrum.nodes.wallet.build:51002)
+    #[clap(short, long)]
+    electrum: Option<String>,
+
+    /// Smartcard reader to use, matched by name (defaults to the first reader)
+    #[clap(long)]
+    reader: Option<String>,
+
+    /// Seconds to wait for the hardware to be tapped before giving up (defaults to not waiting)
+    #[clap(long)]
+    tap_timeout: Option<u64>,
 
     #[clap(subcommand)]
     command: Commands,
@@ -50,11 +68,11 @@ pub struct Cli {
 enum Commands {
     /// Pair with the hardware
     Pair {
-        /// Use which network
-        #[clap(short, long, default_value_t = Network::Signet)]
-        network: Network,
+        /// Use which network (defaults to the profile's network, or signet)
+        #[clap(short, long)]
+        network: Option<Network>,
 
-        /// Fake the hardware key (does NOT talk to the hardware)
+        /// Pair with an emulated hardware device (does NOT talk to the hardware)
         #[clap(short, long)]
         fake: bool,
     },
@@ -70,6 +88,11 @@ enum Commands {
         #[clap(subcommand)]
         command: WalletCommands,
     },
+    /// Social recovery operations (e.g. invite a trusted contact, respond to a challenge)
+    Social {
+        #[clap(subcommand)]
+        command: SocialCommands,
+    },
     /// Do an end-to-end test, exercising all(?) the features
     EndToEnd {
         /// root key for treasury wallet that is used to provide sats for the test
@@ -80,6 +103,11 @@ enum Commands {
         #[clap(subcommand)]
         command: FirmwareCommands,
     },
+    /// Profile operations (e.g. create, select)
+    Profile {
+        #[clap(subcommand)]
+        command: ProfileCommands,
+    },
 
     CheckKeyproofs {
         // default is the account table in dev
@@ -133,6 +161,11 @@ enum WalletCommands {
     },
     /// List the UTXOs in the wallet
     Utxos {},
+    /// Build, sign and broadcast transactions in separate steps (e.g. for offline signing)
+    Psbt {
+        #[clap(subcommand)]
+        command: PsbtCommands,
+    },
     /// Debug a wallet
     Debug {
         // default is the account table in dev
@@ -155,12 +188,98 @@ enum WalletCommands {
 enum RecoveryCommands {
     /// Begin a Lost Appplication recovery
     LostApplication {},
+    /// Begin a Lost Hardware recovery, pairing the replacement hardware
+    LostHardware {
+        /// Pair with an emulated hardware device (does NOT talk to the hardware)
+        #[clap(short, long)]
+        fake: bool,
+    },
     /// Status of a recovery
-    Status {},
+    Status {
+        /// Count down until the delay period ends
+        #[clap(short, long)]
+        wait: bool,
+    },
     /// Cancel a recovery
     Cancel {},
-    /// Complete a recovery
-    Complete {},
+    /// Complete a recovery (for Lost Hardware, also rotating the keyset and sweeping the funds)
+    Complete {
+        /// Fee rate of the sweep in sat/vB (defaults to the blockchain's estimate)
+        #[clap(long)]
+        fee_rate: Option<f32>,
+    },
+    /// Sweep the funds from the keyset replaced by a Lost Hardware recovery
+    Sweep {
+        /// Fee rate in sat/vB (defaults to the blockchain's estimate)
+        #[clap(long)]
+        fee_rate: Option<f32>,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+enum SocialCommands {
+    /// Invite a trusted contact, printing the code to give them
+    Invite { trusted_contact_alias: String },
+    /// List invitations, trusted contacts and the customers we're a trusted contact for
+    Relationships {},
+    /// Accept an invitation to be someone's trusted contact
+    Accept {
+        code: String,
+        customer_alias: String,
+    },
+    /// Endorse the trusted contacts that accepted, sealing the recovery key to them
+    Endorse {},
+    /// Challenge the trusted contacts, printing the code to give them
+    StartChallenge {},
+    /// Respond to a customer's challenge
+    Respond {
+        code: String,
+        /// Which relationship to respond for (only needed if we're a trusted contact for several customers)
+        #[clap(long)]
+        relationship: Option<String>,
+    },
+    /// Check the trusted contacts' responses to the challenge
+    Verify {},
+}
+
+#[derive(Clone, Subcommand)]
+enum PsbtCommands {
+    /// Build an unsigned PSBT
+    Create {
+        #[clap(flatten)]
+        spend: Spend,
+        #[clap(flatten)]
+        export: Export,
+    },
+    /// Decode a PSBT, showing its inputs, outputs and who has signed
+    Show { psbt: PathBuf },
+    /// Sign a PSBT with the app key, and optionally the hardware and server
+    Sign {
+        psbt: PathBuf,
+        /// Also sign with the hardware
+        #[clap(long)]
+        hardware: bool,
+        /// Also ask the server to sign
+        #[clap(long)]
+        server: bool,
+        #[clap(flatten)]
+        export: Export,
+    },
+    /// Combine the signatures from several copies of a PSBT
+    Combine {
+        #[clap(required = true, num_args = 2..)]
+        psbts: Vec<PathBuf>,
+        #[clap(flatten)]
+        export: Export,
+    },
+    /// Re-export a PSBT in another format
+    Convert {
+        psbt: PathBuf,
+        #[clap(flatten)]
+        export: Export,
+    },
+    /// Finalise a fully signed PSBT and broadcast it
+    Broadcast { psbt: PathBuf },
 }
 
 #[derive(Clone, Subcommand)]
@@ -174,6 +293,32 @@ enum FirmwareCommands {
     },
 }
 
+#[derive(Clone, Subcommand)]
+enum ProfileCommands {
+    /// Create a profile
+    Create {
+        name: String,
+        /// Network for wallets paired in this profile
+        #[clap(short, long)]
+        network: Option<Network>,
+        /// URL for the server
+        #[clap(short, long)]
+        server: Option<String>,
+        /// URL for the Electrum node
+        #[clap(short, long)]
+        electrum: Option<String>,
+    },
+    /// List the profiles (the selected one is marked with *)
+    List {},
+    /// Select the profile used when --profile isn't given
+    Select { name: String },
+    /// Delete a profile along with its wallet
+    Delete { name: String },
+}
+
+const DEFAULT_SERVER: &str = "https://api.dev.wallet.build";
+const DEFAULT_ELECTRUM: &str = "ssl://electrum.nodes.wallet.build:51002";
+
 fn main() -> Result<()> {
     Registry::default()
         .with(EnvFilter::from_default_env())
@@ -181,12 +326,57 @@ fn main() -> Result<()> {
         .init();
 
     let cli = Cli::parse();
-    let client = Client::default(&cli.server);
     let db = sled::open(&cli.wallet)?;
-    let blockchain = blockchain(&cli.electrum)?;
+
+    if let Commands::Profile { command } = cli.command {
+        return match command {
+            ProfileCommands::Create {
+                name,
+                network,
+                server,
+                electrum,
+            } => commands::profile::create(
+                db,
+                &name,
+                ProfileConfig {
+                    network,
+                    server,
+                    electrum,
+                },
+            ),
+            ProfileCommands::List {} => commands::profile::list(db),
+            ProfileCommands::Select { name } => commands::profile::select(db, &name),
+            ProfileCommands::Delete { name } => commands::profile::delete(db, &name),
+        };
+    }
+
+    let profile = match &cli.profile {
+        Some(profile) => profile.clone(),
+        None => profiles::selected(&db)?,
+    };
+    let db = Db::open(db, &profile)?;
+    let config = ProfileConfig::from_database(&db).unwrap_or_default();
+    let server = cli
+        .server
+        .or(config.server)
+        .unwrap_or(DEFAULT_SERVER.to_string());
+    let electrum = cli
+        .electrum
+        .or(config.electrum)
+        .unwrap_or(DEFAULT_ELECTRUM.to_string());
+
+    let client = Client::default(&server);
+    let blockchain = blockchain(&electrum)?;
+    nfc::set_reader_options(ReaderOptions {
+        reader: cli.reader.clone(),
+        timeout: cli.tap_timeout.map(Duration::from_secs),
+    });
 
     match cli.command {
-        Commands::Pair { network, fake } => commands::pair::pair(&db, network, fake)?,
+        Commands::Pair { network, fake } => {
+            let network = network.or(config.network).unwrap_or(Network::Signet);
+            commands::pair::pair(&db, network, fake)?
+        }
         Commands::Wipe {} => commands::wipe()?,
         Commands::Account { command } => match command {
             AccountCommands::Create {} => commands::account::create(&client, &db)?,
@@ -225,17 +415,50 @@ fn main() -> Result<()> {
                 RecoveryCommands::LostApplication {} => {
                     commands::wallet::recovery::begin::lost_app(&client, &db)?
                 }
-                RecoveryCommands::Status {} => {
-                    commands::wallet::recovery::status::status_delay_notify(&client, &db)?
+                RecoveryCommands::LostHardware { fake } => {
+                    commands::wallet::recovery::begin::lost_hw(&client, &db, fake)?
+                }
+                RecoveryCommands::Status { wait } => {
+                    commands::wallet::recovery::status::status_delay_notify(&client, &db, wait)?
                 }
                 RecoveryCommands::Cancel {} => {
                     commands::wallet::recovery::cancel::cancel_delay_notify(&client, &db)?
                 }
-                RecoveryCommands::Complete {} => {
-                    commands::wallet::recovery::complete::complete_delay_notify(&client, &db)?
+                RecoveryCommands::Complete { fee_rate } => {
+                    commands::wallet::recovery::complete::complete_delay_notify(
+                        &client,
+                        &db,
+                        &cli.auth_client_id,
+                        blockchain,
+                        fee_rate,
+                    )?
+                }
+                RecoveryCommands::Sweep { fee_rate } => {
+                    commands::wallet::recovery::sweep::sweep(&client, &db, blockchain, fee_rate)?
                 }
             },
             WalletCommands::Utxos {} => commands::wallet::utxos(&client, &db, blockchain)?,
+            WalletCommands::Psbt { command } => match command {
+                PsbtCommands::Create { spend, export } => {
+                    commands::wallet::psbt::create(&client, &db, blockchain, spend, &export)?
+                }
+                PsbtCommands::Show { psbt } => commands::wallet::psbt::show(&client, &db, &psbt)?,
+                PsbtCommands::Sign {
+                    psbt,
+                    hardware,
+                    server,
+                    export,
+                } => commands::wallet::psbt::sign(&client, &db, &psbt, hardware, server, &export)?,
+                PsbtCommands::Combine { psbts, export } => {
+                    commands::wallet::psbt::combine(&psbts, &export)?
+                }
+                PsbtCommands::Convert { psbt, export } => {
+                    commands::wallet::psbt::convert(&psbt, &export)?
+                }
+                PsbtCommands::Broadcast { psbt } => {
+                    commands::wallet::psbt::broadcast(&client, &db, blockchain, &psbt)?
+                }
+            },
             WalletCommands::Debug {
                 account_table,
                 recovery_table,
@@ -244,7 +467,7 @@ fn main() -> Result<()> {
                 address_balance,
                 account_id,
             } => commands::wallet::debug(
-                cli.electrum,
+                electrum,
                 account_table,
                 recovery_table,
                 social_recovery_table,
@@ -253,6 +476,28 @@ fn main() -> Result<()> {
                 account_id,
             )?,
         },
+        Commands::Social { command } => match command {
+            SocialCommands::Invite {
+                trusted_contact_alias,
+            } => commands::social::invite(&client, &db, trusted_contact_alias)?,
+            SocialCommands::Relationships {} => commands::social::relationships(&client, &db)?,
+            SocialCommands::Accept {
+                code,
+                customer_alias,
+            } => {
+                commands::social::accept(&client, &db, &cli.auth_client_id, &code, customer_alias)?
+            }
+            SocialCommands::Endorse {} => commands::social::endorse(&client, &db)?,
+            SocialCommands::StartChallenge {} => {
+                commands::social::start_challenge(&client, &db, &cli.auth_client_id)?
+            }
+            SocialCommands::Respond { code, relationship } => {
+                commands::social::respond(&client, &db, &cli.auth_client_id, &code, relationship)?
+            }
+            SocialCommands::Verify {} => {
+                commands::social::verify(&client, &db, &cli.auth_client_id)?
+            }
+        },
         Commands::EndToEnd {
             ref treasury_root_key,
         } => commands::end_to_end::end_to_end(
@@ -265,10 +510,10 @@ fn main() -> Result<()> {
             FirmwareCommands::Metadata {} => commands::firmware::metadata()?,
             FirmwareCommands::Upload {
                 firmware_bundle: None,
-            } => commands::firmware::upload_latest(&client)?,
+            } => commands::firmware::upload_latest(&client, &db)?,
             FirmwareCommands::Upload {
                 firmware_bundle: Some(firmware_bundle),
-            } => commands::firmware::upload_bundle(firmware_bundle)?,
+            } => commands::firmware::upload_bundle(&db, firmware_bundle)?,
         },
 
         Commands::CheckKeyproofs {
@@ -284,6 +529,7 @@ fn main() -> Result<()> {
             app_signature,
             hardware_signature,
         )?,
+        Commands::Profile { .. } => unreachable!("profile commands are handled above"),
     }
 
     Ok(())
@@ -1,5 +1,5 @@
 use std::{
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, OnceLock},
     thread::sleep,
     time::Duration,
 };
@@ -14,11 +14,11 @@ use bdk::{
 use thiserror::Error;
 use wca::{
     commands::{
-        DeviceInfo, FirmwareMetadata, FwupFinish, FwupFinishRspStatus, FwupStart, FwupTransfer,
-        GetAuthenticationKey, GetFirmwareMetadata, GetInitialSpendingKey, QueryAuthentication,
-        SignTransaction,
+        DeviceInfo, FirmwareMetadata, FwupFinish, FwupFinishRspStatus, FwupMode, FwupStart,
+        FwupTransfer, GetAuthenticationKey, GetFirmwareMetadata, GetInitialSpendingKey,
+        QueryAuthentication, SignTransaction,
     },
-    pcsc::{Performer, Transactor, TransactorError},
+    pcsc::{PCSCTransactor, Performer, ReaderOptions, Transactor, TransactorError},
 };
 use wca::{
     commands::{
@@ -28,6 +28,18 @@ use wca::{
     errors::CommandError,
 };
 
+static READER_OPTIONS: OnceLock<ReaderOptions> = OnceLock::new();
+
+/// Set which reader `connect` uses and how long it waits for a tap, for the rest of the process.
+pub(crate) fn set_reader_options(options: ReaderOptions) {
+    let _ = READER_OPTIONS.set(options);
+}
+
+/// Connect to the hardware through the reader picked on the command line.
+pub(crate) fn connect() -> Result<PCSCTransactor, TransactorError> {
+    PCSCTransactor::connect(READER_OPTIONS.get_or_init(ReaderOptions::default))
+}
+
 #[derive(Clone)]
 pub(crate) struct SafeTransactor(Arc<Mutex<dyn Transactor>>);
 
@@ -70,7 +82,12 @@ pub trait NFCTransactions {
     ) -> Result<PartiallySignedTransaction, TransactorError>;
     fn device_info(&self) -> Result<DeviceInfo, TransactorError>;
     fn metadata(&self) -> Result<FirmwareMetadata, TransactorError>;
-    fn upload(&mut self, upload: &Upload) -> Result<FwupFinishRspStatus, PairingError>;
+    fn upload(
+        &mut self,
+        upload: &Upload,
+        resume_from: Option<u32>,
+        checkpoint: &mut dyn FnMut(u32),
+    ) -> Result<FwupFinishRspStatus, PairingError>;
     fn get_authentication_key(&self) -> Result<PublicKey, TransactorError>;
     fn get_initial_spending_key(
         &self,
@@ -124,18 +141,43 @@ impl<T: Transactor + ?Sized> NFCTransactions for T {
         self.perform(GetFirmwareMetadata::new())
     }
 
-    fn upload(&mut self, upload: &Upload) -> Result<FwupFinishRspStatus, PairingError> {
-        if !self.perform(FwupStart::new(None, wca::commands::FwupMode::Normal))? {
+    /// Upload firmware, starting from the application chunk after `resume_from` if a previous
+    /// upload was interrupted. `checkpoint` is told the next sequence ID to send after every
+    /// application chunk the hardware acknowledges.
+    fn upload(
+        &mut self,
+        upload: &Upload,
+        resume_from: Option<u32>,
+        checkpoint: &mut dyn FnMut(u32),
+    ) -> Result<FwupFinishRspStatus, PairingError> {
+        if resume_from.is_none()
+            && !self.perform(FwupStart::new(upload.patch_size(), upload.mode.clone()))?
+        {
             return Err(PairingError::FwupStart);
         }
 
-        upload_asset(self, upload.chunk_size, &upload.application)?;
-        upload_asset(self, upload.chunk_size, &upload.signature)?;
+        upload_asset(
+            self,
+            upload.chunk_size,
+            &upload.application,
+            upload.mode.clone(),
+            resume_from.unwrap_or(0),
+            checkpoint,
+        )?;
+        // Delta or not, the signature is always sent as a normal transfer.
+        upload_asset(
+            self,
+            upload.chunk_size,
+            &upload.signature,
+            FwupMode::Normal,
+            0,
+            &mut |_| {},
+        )?;
 
         Ok(self.perform(FwupFinish::new(
             upload.app_properties_offset,
             upload.signature.offset,
-            wca::commands::FwupMode::Normal,
+            upload.mode.clone(),
         ))?)
     }
 
@@ -169,27 +211,44 @@ pub struct Asset {
 }
 
 pub struct Upload {
+    pub mode: FwupMode,
     pub chunk_size: usize,
     pub app_properties_offset: u32,
     pub application: Asset,
     pub signature: Asset,
 }
 
+impl Upload {
+    fn patch_size(&self) -> Option<u32> {
+        match self.mode {
+            FwupMode::Normal => None,
+            FwupMode::Delta => Some(self.application.data.len() as u32),
+        }
+    }
+}
+
 fn upload_asset<T: Transactor + ?Sized>(
     transactor: &mut T,
     chunk_size: usize,
     asset: &Asset,
+    mode: FwupMode,
+    start: u32,
+    checkpoint: &mut dyn FnMut(u32),
 ) -> Result<(), TransactorError> {
-    let chunks = asset.data.chunks(chunk_size).enumerate();
+    let chunks = asset.data.chunks(chunk_size).collect::<Vec<_>>();
     let bar = indicatif::ProgressBar::new(chunks.len() as u64);
-    for (sequence_id, chunk) in chunks {
+    bar.set_position(start as u64);
+    let mut sequence_id = start;
+    while let Some(chunk) = chunks.get(sequence_id as usize) {
         let command = transactor.perform(FwupTransfer::new(
-            sequence_id as u32,
+            sequence_id,
             chunk.to_vec(),
             asset.offset,
-            wca::commands::FwupMode::Normal,
+            mode.clone(),
         ));
 
+        // Chunks are written at an offset derived from their sequence ID, so resending one the
+        // hardware may already have seen is harmless.
         match command {
             Err(TransactorError::CommandError(CommandError::Unauthenticated)) => {
                 bar.println("Please unlock your hardware...");
@@ -198,7 +257,24 @@ fn upload_asset<T: Transactor + ?Sized>(
                     sleep(Duration::from_secs(1));
 
                     match transactor.reset() {
-                        Ok(_) | Err(pcsc::Error::NoSmartcard) => continue,
+                        Ok(_) => continue,
+                        Err(err) if is_tap_dropped(err) => continue,
+                        Err(err) => {
+                            bar.abandon_with_message("Giving up due to an error");
+                            return Err(TransactorError::ReaderError(err));
+                        }
+                    }
+                }
+            }
+            Err(TransactorError::ReaderError(err)) if is_tap_dropped(err) => {
+                bar.println("Lost the hardware, tap it again to resume...");
+
+                loop {
+                    sleep(Duration::from_secs(1));
+
+                    match transactor.reset() {
+                        Ok(_) => break,
+                        Err(err) if is_tap_dropped(err) => continue,
                         Err(err) => {
                             bar.abandon_with_message("Giving up due to an error");
                             return Err(TransactorError::ReaderError(err));
@@ -210,10 +286,27 @@ fn upload_asset<T: Transactor + ?Sized>(
                 bar.abandon();
                 return Err(e);
             }
-            Ok(_) => bar.inc(1),
+            Ok(_) => {
+                sequence_id += 1;
+                checkpoint(sequence_id);
+                bar.inc(1);
+            }
         }
     }
     bar.finish_and_clear();
 
     Ok(())
 }
+
+/// Whether the hardware was taken away from (or bounced on) the reader, rather than the reader
+/// itself failing.
+fn is_tap_dropped(err: pcsc::Error) -> bool {
+    matches!(
+        err,
+        pcsc::Error::NoSmartcard
+            | pcsc::Error::RemovedCard
+            | pcsc::Error::ResetCard
+            | pcsc::Error::UnpoweredCard
+            | pcsc::Error::UnresponsiveCard
+    )
+}
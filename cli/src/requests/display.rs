@@ -4,7 +4,7 @@ use time::format_description::well_known::Rfc2822;
 
 use super::{
     DelayAndNotifyRecoveryStatus, Factor, PendingDelayNotify,
-    PendingRecoveryForWalletStatusResponse, RecoveryStatusResponse,
+    PendingRecoveryForWalletStatusResponse, RecoveryRelationshipsResponse, RecoveryStatusResponse,
 };
 
 impl Display for Factor {
@@ -84,3 +84,43 @@ impl Display for PendingDelayNotify {
         Ok(())
     }
 }
+
+impl Display for RecoveryRelationshipsResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        for invitation in &self.invitations {
+            writeln!(
+                f,
+                "{id}: invited {alias}, expires at {time}",
+                id = invitation.recovery_relationship_id,
+                alias = invitation.trusted_contact_alias,
+                time = invitation
+                    .expires_at
+                    .format(&Rfc2822)
+                    .expect("malformated datetime"),
+            )?;
+        }
+        for contact in &self.unendorsed_trusted_contacts {
+            writeln!(
+                f,
+                "{}: {} accepted, awaiting endorsement",
+                contact.recovery_relationship_id, contact.trusted_contact_alias
+            )?;
+        }
+        for contact in &self.endorsed_trusted_contacts {
+            writeln!(
+                f,
+                "{}: {} is a trusted contact",
+                contact.recovery_relationship_id, contact.trusted_contact_alias
+            )?;
+        }
+        for customer in &self.customers {
+            writeln!(
+                f,
+                "{}: trusted contact for {}",
+                customer.recovery_relationship_id, customer.customer_alias
+            )?;
+        }
+
+        Ok(())
+    }
+}
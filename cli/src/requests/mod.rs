@@ -14,7 +14,10 @@ use time::serde::rfc3339;
 use time::{OffsetDateTime, UtcOffset};
 
 use crate::nfc::SafeTransactor;
-use crate::serde_helpers::{fromagerie_network, string as serde_string, AccountId, KeysetId};
+use crate::serde_helpers::{
+    fromagerie_network, string as serde_string, AccountId, KeysetId, RecoveryRelationshipId,
+    SocialChallengeId,
+};
 use crate::signers::Authentication;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -22,6 +25,8 @@ pub struct AuthKeypairRequest {
     // TODO: [W-774] Update visibility of struct after migration
     pub app: PublicKey,
     pub hardware: PublicKey,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<PublicKey>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -136,6 +141,21 @@ pub struct SignTransactionResponse {
     pub tx: PartiallySignedTransaction,
 }
 
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/keysets/{self.keyset_id}/sign-transaction",
+    method = "POST",
+    response = "SignTransactionResponse"
+)]
+pub struct SignTransactionWithKeysetRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    #[endpoint(skip)]
+    pub keyset_id: KeysetId,
+    #[serde(with = "serde_string")]
+    pub psbt: PartiallySignedTransaction,
+}
+
 #[derive(Debug, Endpoint, Serialize)]
 #[endpoint(
     path = "api/accounts/{self.account_id}/keysets",
@@ -406,3 +426,243 @@ pub struct MobilePaySetupRequest {
 
 #[derive(Deserialize, Debug)]
 pub struct MobilePaySetupResponse {}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/relationships",
+    method = "POST",
+    response = "CreateRecoveryRelationshipResponse"
+)]
+pub struct CreateRecoveryRelationshipRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    pub trusted_contact_alias: String,
+    pub protected_customer_enrollment_pake_pubkey: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRecoveryRelationshipResponse {
+    pub invitation: OutboundInvitation,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OutboundInvitation {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub trusted_contact_alias: String,
+    pub code: String,
+    #[serde(with = "rfc3339")]
+    pub expires_at: OffsetDateTime,
+}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/relationships",
+    method = "GET",
+    response = "RecoveryRelationshipsResponse"
+)]
+pub struct RecoveryRelationshipsRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecoveryRelationshipsResponse {
+    pub invitations: Vec<OutboundInvitation>,
+    pub unendorsed_trusted_contacts: Vec<UnendorsedTrustedContact>,
+    pub endorsed_trusted_contacts: Vec<EndorsedTrustedContact>,
+    pub customers: Vec<Customer>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnendorsedTrustedContact {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub trusted_contact_alias: String,
+    pub sealed_delegated_decryption_pubkey: String,
+    pub trusted_contact_enrollment_pake_pubkey: String,
+    pub enrollment_pake_confirmation: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EndorsedTrustedContact {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub trusted_contact_alias: String,
+    pub delegated_decryption_pubkey_certificate: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Customer {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub customer_alias: String,
+}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/relationship-invitations/{self.code}",
+    method = "GET",
+    response = "InvitationForCodeResponse"
+)]
+pub struct InvitationForCodeRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    #[endpoint(skip)]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InvitationForCodeResponse {
+    pub invitation: InboundInvitation,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InboundInvitation {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub protected_customer_enrollment_pake_pubkey: String,
+}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/relationships/{self.recovery_relationship_id}",
+    method = "PUT",
+    response = "AcceptRecoveryRelationshipResponse"
+)]
+pub struct AcceptRecoveryRelationshipRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    #[endpoint(skip)]
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub action: RecoveryRelationshipAction,
+    pub code: String,
+    pub customer_alias: String,
+    pub trusted_contact_enrollment_pake_pubkey: String,
+    pub enrollment_pake_confirmation: String,
+    pub sealed_delegated_decryption_pubkey: String,
+}
+
+#[derive(Debug, Serialize)]
+pub enum RecoveryRelationshipAction {
+    Accept,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptRecoveryRelationshipResponse {
+    pub customer: Customer,
+}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/relationships",
+    method = "PUT",
+    response = "EndorseRecoveryRelationshipsResponse"
+)]
+pub struct EndorseRecoveryRelationshipsRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    pub endorsements: Vec<RecoveryRelationshipEndorsement>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecoveryRelationshipEndorsement {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub delegated_decryption_pubkey_certificate: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EndorseRecoveryRelationshipsResponse {
+    pub endorsed_trusted_contacts: Vec<EndorsedTrustedContact>,
+}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/social-challenges",
+    method = "POST",
+    response = "SocialChallengeResponse"
+)]
+pub struct StartSocialChallengeRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    pub trusted_contacts: Vec<StartChallengeTrustedContact>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartChallengeTrustedContact {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub protected_customer_recovery_pake_pubkey: String,
+    pub sealed_dek: String,
+}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/social-challenges/{self.social_challenge_id}",
+    method = "GET",
+    response = "SocialChallengeResponse"
+)]
+pub struct FetchSocialChallengeRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    #[endpoint(skip)]
+    pub social_challenge_id: SocialChallengeId,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SocialChallengeResponse {
+    pub social_challenge: CustomerSocialChallenge,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CustomerSocialChallenge {
+    pub social_challenge_id: SocialChallengeId,
+    pub counter: u32,
+    pub responses: Vec<SocialChallengeResponseFromContact>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SocialChallengeResponseFromContact {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub trusted_contact_recovery_pake_pubkey: String,
+    pub recovery_pake_confirmation: String,
+    pub resealed_dek: String,
+}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/verify-social-challenge",
+    method = "POST",
+    response = "VerifySocialChallengeResponse"
+)]
+pub struct VerifySocialChallengeRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub counter: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifySocialChallengeResponse {
+    pub social_challenge: TrustedContactSocialChallenge,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrustedContactSocialChallenge {
+    pub social_challenge_id: SocialChallengeId,
+    pub protected_customer_recovery_pake_pubkey: String,
+    pub sealed_dek: String,
+}
+
+#[derive(Debug, Endpoint, Serialize)]
+#[endpoint(
+    path = "api/accounts/{self.account_id}/recovery/social-challenges/{self.social_challenge_id}",
+    method = "PUT",
+    response = "RespondToSocialChallengeResponse"
+)]
+pub struct RespondToSocialChallengeRequest {
+    #[endpoint(skip)]
+    pub account_id: AccountId,
+    #[endpoint(skip)]
+    pub social_challenge_id: SocialChallengeId,
+    pub trusted_contact_recovery_pake_pubkey: String,
+    pub recovery_pake_confirmation: String,
+    pub resealed_dek: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RespondToSocialChallengeResponse {}
@@ -20,6 +20,24 @@ impl Display for KeysetId {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RecoveryRelationshipId(pub String);
+
+impl Display for RecoveryRelationshipId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SocialChallengeId(pub String);
+
+impl Display for SocialChallengeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 pub mod fromagerie_network {
     use bdk::bitcoin::Network;
     use serde::Deserialize;
@@ -33,6 +33,12 @@ use crate::nfc::{NFCTransactions, SafeTransactor};
 
 use super::{Authentication, Spending};
 
+/// Like the authentication key, but at the next index: "W1HW" / 1
+const RECOVERY_AUTHENTICATION_DERIVATION_PATH: [ChildNumber; 2] = [
+    ChildNumber::Hardened { index: 87497287 },
+    ChildNumber::Hardened { index: 1 },
+];
+
 #[derive(Deserialize, Serialize, Eq)]
 pub(crate) struct SeedSigner {
     #[serde(default, skip)]
@@ -69,6 +75,19 @@ impl SeedSigner {
             .private_key
     }
 
+    /// The key the account authenticates with for recovery-scoped routes, e.g. to act as a trusted
+    /// contact or to start a social challenge. It lives alongside the app key, so it rotates with it.
+    pub(crate) fn recovery_authentication(&self) -> RecoveryAuthentication {
+        RecoveryAuthentication {
+            secp: Secp256k1::new(),
+            key: self
+                .master_key()
+                .derive_priv(&self.secp, &RECOVERY_AUTHENTICATION_DERIVATION_PATH)
+                .expect("could not derive recovery authentication xprv")
+                .private_key,
+        }
+    }
+
     fn account_private_key(&self) -> DescriptorXKey<ExtendedPrivKey> {
         let master = self.master_key();
         let path = DerivationPath::from_iter(bip84(self.network, self.account));
@@ -118,6 +137,22 @@ impl Authentication for SeedSigner {
     }
 }
 
+pub(crate) struct RecoveryAuthentication {
+    secp: Secp256k1<All>,
+    key: SecretKey,
+}
+
+impl Authentication for RecoveryAuthentication {
+    fn public_key(&self) -> PublicKey {
+        self.key.public_key(&self.secp)
+    }
+
+    fn sign(&self, message: &[u8], _: &impl Transactor) -> Result<Signature, TransactorError> {
+        let message = Message::from_hashed_data::<sha256::Hash>(message);
+        Ok(self.secp.sign_ecdsa(&message, &self.key))
+    }
+}
+
 impl Spending for SeedSigner {
     fn public_key(&self) -> DescriptorPublicKey {
         self.account_public_key()
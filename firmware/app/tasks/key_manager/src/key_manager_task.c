@@ -177,6 +177,69 @@ out:
   proto_send_rsp(m_cmd, m_rsp);
 }
 
+static void handle_derive_and_sign_schnorr(ipc_ref_t* message) {
+  fwpb_wallet_cmd* m_cmd = proto_get_cmd((uint8_t*)message->object, message->length);
+  fwpb_wallet_rsp* m_rsp = proto_get_rsp();
+
+  m_rsp->which_msg = fwpb_wallet_rsp_derive_and_sign_rsp_tag;
+
+  fwpb_derive_key_descriptor_and_sign_schnorr_cmd* cmd =
+    &m_cmd->msg.derive_key_descriptor_and_sign_schnorr_cmd;
+  fwpb_derive_and_sign_rsp* rsp = &m_rsp->msg.derive_and_sign_rsp;
+
+  if (!cmd->has_derivation_path) {
+    rsp->status = fwpb_derive_and_sign_rsp_derive_and_sign_rsp_status_ERROR;
+    LOGE("derivation path not provided");
+    goto out;
+  } else if (cmd->derivation_path.child_count > BIP32_MAX_DERIVATION_DEPTH) {
+    rsp->status = fwpb_derive_and_sign_rsp_derive_and_sign_rsp_status_ERROR;
+    LOGE("derivation path too long");
+    goto out;
+  }
+
+  derivation_path_t derivation_path = {
+    .indices = cmd->derivation_path.child,
+    .num_indices = cmd->derivation_path.child_count,
+  };
+  extended_key_t key_priv __attribute__((__cleanup__(bip32_zero_key)));
+  fingerprint_t key_priv_master_fingerprint;
+  fingerprint_t key_priv_childs_parent_fingerprint;
+  if (seed_derive_bip32(derivation_path, &key_priv, &key_priv_master_fingerprint,
+                        &key_priv_childs_parent_fingerprint) != SEED_RES_OK) {
+    rsp->status = fwpb_derive_and_sign_rsp_derive_and_sign_rsp_status_DERIVATION_FAILED;
+    LOGE("seed_derive failed");
+    goto out;
+  }
+
+  if (cmd->hash.size != SHA256_DIGEST_SIZE) {
+    rsp->status = fwpb_derive_and_sign_rsp_derive_and_sign_rsp_status_ERROR;
+    LOGE("invalid hash length");
+    goto out;
+  }
+
+  // An empty tweak means a script-path spend, signed with the untweaked key.
+  uint8_t* tap_tweak = NULL;
+  if (cmd->tap_tweak.size == SECP256K1_KEY_SIZE) {
+    tap_tweak = cmd->tap_tweak.bytes;
+  } else if (cmd->tap_tweak.size != 0) {
+    rsp->status = fwpb_derive_and_sign_rsp_derive_and_sign_rsp_status_ERROR;
+    LOGE("invalid tap tweak length");
+    goto out;
+  }
+
+  if (!bip32_sign_schnorr(&key_priv, cmd->hash.bytes, tap_tweak, rsp->signature.bytes)) {
+    rsp->status = fwpb_derive_and_sign_rsp_derive_and_sign_rsp_status_ERROR;
+    LOGE("bip32_sign_schnorr failed");
+    goto out;
+  }
+
+  rsp->signature.size = ECC_SIG_SIZE;
+  rsp->status = fwpb_derive_and_sign_rsp_derive_and_sign_rsp_status_SUCCESS;
+
+out:
+  proto_send_rsp(m_cmd, m_rsp);
+}
+
 static void handle_seal_csek(ipc_ref_t* message) {
   fwpb_wallet_cmd* cmd = proto_get_cmd((uint8_t*)message->object, message->length);
   fwpb_wallet_rsp* rsp = proto_get_rsp();
@@ -483,6 +546,9 @@ void key_manager_thread(void* UNUSED(args)) {
       case IPC_PROTO_DERIVE_KEY_DESCRIPTOR_AND_SIGN_CMD:
         handle_derive_and_sign(&message);
         break;
+      case IPC_PROTO_DERIVE_KEY_DESCRIPTOR_AND_SIGN_SCHNORR_CMD:
+        handle_derive_and_sign_schnorr(&message);
+        break;
       case IPC_KEY_MANAGER_REMOVE_WALLET_STATE:
         handle_remove_wallet_state();
         break;
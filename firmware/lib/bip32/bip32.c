@@ -386,6 +386,38 @@ bool bip32_sign(extended_key_t* priv_key, uint8_t digest[SHA256_DIGEST_SIZE],
   return true;
 }
 
+bool bip32_sign_schnorr(extended_key_t* priv_key, uint8_t digest[SHA256_DIGEST_SIZE],
+                        uint8_t tap_tweak[SECP256K1_KEY_SIZE], uint8_t signature_out[ECC_SIG_SIZE]) {
+  uint8_t keypair_bytes[SECP256K1_KEYPAIR_SIZE] = {0};
+
+  key_buffer_t key_buffer = {
+    .bytes = keypair_bytes,
+    .size = SECP256K1_KEYPAIR_SIZE,
+  };
+
+  key_handle_t key_handle CLEANUP(zeroize_key) = {
+    .alg = ALG_ECC_SECP256K1,  // Not actually used, since this key is software only.
+    .storage_type = KEY_STORAGE_EXTERNAL_PLAINTEXT,
+    .key = key_buffer,
+  };
+
+  if (!crypto_ecc_secp256k1_load_keypair(priv_key->key, &key_handle)) {
+    return false;
+  }
+
+  if (tap_tweak && !crypto_ecc_secp256k1_keypair_xonly_tweak_add(&key_handle, tap_tweak)) {
+    return false;
+  }
+
+  if (!crypto_ecc_secp256k1_schnorr_sign_hash32(&key_handle, digest, signature_out,
+                                                ECC_SIG_SIZE)) {
+    memzero(signature_out, ECC_SIG_SIZE);  // Clear signature if signing failed.
+    return false;
+  }
+
+  return true;
+}
+
 void bip32_zero_key(extended_key_t* const key) {
   memzero(key, sizeof(extended_key_t));
 }
@@ -72,4 +72,9 @@ bool bip32_serialize_ext_key(extended_key_t* ext_key, extended_key_t* parent_pub
 bool bip32_sign(extended_key_t* priv_key, uint8_t digest[SHA256_DIGEST_SIZE],
                 uint8_t signature_out[ECC_SIG_SIZE]);
 
+// BIP-340 signature over `digest`. If `tap_tweak` is non-NULL, the key is tweaked per BIP-341
+// before signing (a taproot key-path spend).
+bool bip32_sign_schnorr(extended_key_t* priv_key, uint8_t digest[SHA256_DIGEST_SIZE],
+                        uint8_t tap_tweak[SECP256K1_KEY_SIZE], uint8_t signature_out[ECC_SIG_SIZE]);
+
 void bip32_zero_key(extended_key_t* const key);
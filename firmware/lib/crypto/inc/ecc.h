@@ -72,3 +72,7 @@ bool crypto_ecc_secp256k1_priv_tweak_add(uint8_t privkey[SECP256K1_KEY_SIZE], ui
 
 bool crypto_ecc_secp256k1_pub_tweak_add(uint8_t sec_encoded_pubkey[SECP256K1_SEC1_KEY_SIZE],
                                         uint8_t tweak[SECP256K1_KEY_SIZE]);
+
+// Apply a BIP-341 x-only tweak to a keypair loaded with `crypto_ecc_secp256k1_load_keypair`.
+bool crypto_ecc_secp256k1_keypair_xonly_tweak_add(key_handle_t* key,
+                                                  uint8_t tweak[SECP256K1_KEY_SIZE]);
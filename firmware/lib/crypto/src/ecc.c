@@ -344,6 +344,17 @@ out:
   return status;
 }
 
+bool crypto_ecc_secp256k1_keypair_xonly_tweak_add(key_handle_t* key,
+                                                  uint8_t tweak[SECP256K1_KEY_SIZE]) {
+  ASSERT(key && key->key.bytes && (key->key.size == SECP256K1_KEYPAIR_SIZE) && tweak);
+  ENSURE_CTX();
+  rtos_mutex_lock(&ctx_lock);
+  secp256k1_keypair* keypair = (secp256k1_keypair*)key->key.bytes;
+  bool ret = (secp256k1_keypair_xonly_tweak_add(ctx, keypair, tweak) == 1);
+  rtos_mutex_unlock(&ctx_lock);
+  return ret;
+}
+
 static inline bool all_zeroes(uint8_t* buf, uint32_t size) {
   for (size_t i = 0; i < size; i++) {
     if (buf[i] != 0)
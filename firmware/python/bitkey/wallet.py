@@ -349,6 +349,17 @@ class Wallet:
         cmd.derive_key_descriptor_and_sign_cmd.CopyFrom(msg)
         return self.comms.transceive(cmd)
 
+    def derive_and_sign_schnorr(self, digest: bytes, path: list, tap_tweak: bytes = b''):
+        cmd = self._build_cmd()
+        msg = ops_keys.derive_key_descriptor_and_sign_schnorr_cmd()
+        msg.hash = digest
+        msg.tap_tweak = tap_tweak
+        p = ops_keybundle.derivation_path()
+        p.child.extend(path)
+        msg.derivation_path.CopyFrom(p)
+        cmd.derive_key_descriptor_and_sign_schnorr_cmd.CopyFrom(msg)
+        return self.comms.transceive(cmd)
+
     def derive_public_key(self, curve, label: str):
         cmd = self._build_cmd()
         msg = ops_keys.derive_public_key_cmd()
@@ -19,7 +19,9 @@ use types::notification::{NotificationChannel, NotificationsPreferences};
 use utoipa::ToSchema;
 
 use crate::error::AccountError;
+use crate::recovery_policy::{PendingRecoveryPolicy, RecoveryPolicy};
 use crate::spend_limit::SpendingLimit;
+use crate::spend_policy::SpendPolicyRule;
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy, ToSchema, StrumDisplay)]
 pub enum AuthFactor {
@@ -270,6 +272,14 @@ pub struct FullAccount {
     pub spending_keysets: HashMap<KeysetId, SpendingKeyset>,
     // Spending limit
     pub spending_limit: Option<SpendingLimit>,
+    // Additional rules enforced when cosigning Mobile Pay transactions
+    #[serde(default)]
+    pub spend_policy: Vec<SpendPolicyRule>,
+    // Delay & Notify recovery policy, and a requested change to it that's still waiting
+    #[serde(default)]
+    pub recovery_policy: RecoveryPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_recovery_policy: Option<PendingRecoveryPolicy>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub application_auth_pubkey: Option<PublicKey>,
     // Hardware Authentication Key
@@ -302,6 +312,9 @@ impl FullAccount {
             auth_keys: HashMap::from([(active_auth_keys_id.clone(), auth)]),
             spending_keysets: HashMap::from([(active_keyset_id, spending)]),
             spending_limit: None,
+            spend_policy: vec![],
+            recovery_policy: Default::default(),
+            pending_recovery_policy: None,
             application_auth_pubkey,
             hardware_auth_pubkey,
             comms_verification_claims: vec![],
@@ -331,6 +344,14 @@ impl FullAccount {
             .as_ref()
             .map_or(false, |limit| limit.active)
     }
+
+    /// The recovery policy in effect at `now`, including a pending change once it's due.
+    pub fn recovery_policy_at(&self, now: OffsetDateTime) -> RecoveryPolicy {
+        match self.pending_recovery_policy {
+            Some(pending) if pending.effective_at <= now => pending.policy,
+            _ => self.recovery_policy,
+        }
+    }
 }
 
 impl From<FullAccount> for Account {
@@ -390,6 +411,9 @@ impl LiteAccount {
             active_keyset_id: keyset_id.clone(),
             spending_keysets: HashMap::from([(keyset_id, spending_keyset)]),
             spending_limit: None,
+            spend_policy: vec![],
+            recovery_policy: Default::default(),
+            pending_recovery_policy: None,
             application_auth_pubkey: Some(auth_keys.app_pubkey),
             hardware_auth_pubkey: auth_keys.hardware_pubkey,
             comms_verification_claims: vec![],
@@ -648,6 +672,9 @@ mod tests {
             auth_keys: Default::default(),
             spending_keysets: Default::default(),
             spending_limit: None,
+            spend_policy: vec![],
+            recovery_policy: Default::default(),
+            pending_recovery_policy: None,
             application_auth_pubkey: None,
             hardware_auth_pubkey: PublicKey::from_slice(&pubkey).unwrap(),
             comms_verification_claims: vec![],
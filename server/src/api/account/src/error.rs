@@ -32,6 +32,8 @@ pub enum AccountError {
     UserPoolError(#[from] userpool::userpool::UserPoolError),
     #[error("Unauthorized device token registration")]
     UnauthorizedDeviceTokenRegistration,
+    #[error("Session not found")]
+    SessionNotFound,
 }
 
 impl From<AccountError> for ApiError {
@@ -47,7 +49,9 @@ impl From<AccountError> for ApiError {
             | AccountError::InvalidUpdateAccountProperties
             | AccountError::Unexpected
             | AccountError::UserPoolError(_) => ApiError::GenericInternalApplicationError(err_msg),
-            AccountError::TouchpointNotFound => ApiError::GenericNotFound(err_msg),
+            AccountError::TouchpointNotFound | AccountError::SessionNotFound => {
+                ApiError::GenericNotFound(err_msg)
+            }
             AccountError::InvalidSpendingKeysetIdentifierForRotation => {
                 ApiError::GenericBadRequest(err_msg)
             }
@@ -1,5 +1,7 @@
 pub mod entities;
 pub mod error;
+pub mod recovery_policy;
 pub mod repository;
 pub mod service;
 pub mod spend_limit;
+pub mod spend_policy;
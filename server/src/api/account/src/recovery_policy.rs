@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use time::{serde::rfc3339, Duration, OffsetDateTime};
+use utoipa::ToSchema;
+
+pub const DEFAULT_DELAY_PERIOD_DAYS: u32 = 7;
+pub const MIN_DELAY_PERIOD_DAYS: u32 = 3;
+pub const MAX_DELAY_PERIOD_DAYS: u32 = 30;
+
+pub const DEFAULT_CONTEST_LOOKBACK_DAYS: u32 = 30;
+pub const MIN_CONTEST_LOOKBACK_DAYS: u32 = 30;
+pub const MAX_CONTEST_LOOKBACK_DAYS: u32 = 180;
+
+/// How the server runs Delay & Notify recoveries for an account.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct RecoveryPolicy {
+    /// How long a recovery waits before it can be completed.
+    pub delay_period_days: u32,
+    /// How far back a contested recovery still requires comms verification for new recoveries.
+    pub contest_lookback_days: u32,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            delay_period_days: DEFAULT_DELAY_PERIOD_DAYS,
+            contest_lookback_days: DEFAULT_CONTEST_LOOKBACK_DAYS,
+        }
+    }
+}
+
+impl RecoveryPolicy {
+    pub fn is_within_bounds(&self) -> bool {
+        (MIN_DELAY_PERIOD_DAYS..=MAX_DELAY_PERIOD_DAYS).contains(&self.delay_period_days)
+            && (MIN_CONTEST_LOOKBACK_DAYS..=MAX_CONTEST_LOOKBACK_DAYS)
+                .contains(&self.contest_lookback_days)
+    }
+
+    pub fn delay_period(&self) -> Duration {
+        Duration::days(self.delay_period_days.into())
+    }
+
+    pub fn contest_lookback(&self) -> Duration {
+        Duration::days(self.contest_lookback_days.into())
+    }
+}
+
+/// A change to an account's [`RecoveryPolicy`] that has been requested but hasn't taken effect.
+/// Changes wait out the delay period of the policy they replace, so a policy can't be weakened
+/// any faster than a recovery could be completed.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct PendingRecoveryPolicy {
+    pub policy: RecoveryPolicy,
+    #[serde(with = "rfc3339")]
+    pub effective_at: OffsetDateTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery_policy_bounds() {
+        assert!(RecoveryPolicy::default().is_within_bounds());
+        assert!(RecoveryPolicy {
+            delay_period_days: MAX_DELAY_PERIOD_DAYS,
+            contest_lookback_days: MAX_CONTEST_LOOKBACK_DAYS,
+        }
+        .is_within_bounds());
+        assert!(!RecoveryPolicy {
+            delay_period_days: MIN_DELAY_PERIOD_DAYS - 1,
+            ..Default::default()
+        }
+        .is_within_bounds());
+        assert!(!RecoveryPolicy {
+            contest_lookback_days: MIN_CONTEST_LOOKBACK_DAYS - 1,
+            ..Default::default()
+        }
+        .is_within_bounds());
+    }
+}
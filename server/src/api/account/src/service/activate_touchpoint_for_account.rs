@@ -1,11 +1,12 @@
 use tracing::{event, Level};
+use types::security_event::SecurityEventType;
 
 use crate::{
     entities::{Account, Touchpoint},
     error::AccountError,
 };
 
-use super::{ActivateTouchpointForAccountInput, Service};
+use super::{ActivateTouchpointForAccountInput, RecordSecurityEventInput, Service};
 
 impl Service {
     pub async fn activate_touchpoint_for_account(
@@ -63,7 +64,16 @@ impl Service {
             Account::Full(full_account) => full_account.common_fields.touchpoints = touchpoints,
             Account::Lite(lite_account) => lite_account.common_fields.touchpoints = touchpoints,
         };
-        self.account_repo.persist(&account).await?;
+        self.record_security_event(
+            RecordSecurityEventInput {
+                account_id: &input.account_id,
+                event: SecurityEventType::TouchpointActivated {
+                    touchpoint_id: input.touchpoint_id,
+                },
+            },
+            async { Ok::<_, AccountError>(self.account_repo.persist(&account).await?) },
+        )
+        .await?;
 
         Ok(())
     }
@@ -1,6 +1,8 @@
 use aws_config::BehaviorVersion;
 use aws_sdk_sns::{error::ProvideErrorMetadata, Client as SNSClient};
+use database::ddb::DatabaseError;
 use tracing::{event, Level};
+use types::account::identifiers::AccountId;
 
 use crate::{
     entities::{Account, Touchpoint, TouchpointPlatform},
@@ -16,6 +18,11 @@ impl Service {
     ) -> Result<Touchpoint, AccountError> {
         let mut account = self.account_repo.fetch(&input.account_id).await?;
 
+        if let Some(session_id) = &input.session_id {
+            self.attach_device_token_to_session(&input.account_id, session_id, &input.device_token)
+                .await?;
+        }
+
         let device_token = input.device_token.clone();
         if let Some(touchpoint) = account.get_common_fields().touchpoints.iter().find(|t| {
             if let Touchpoint::Push {
@@ -76,6 +83,34 @@ impl Service {
         self.account_repo.persist(&account).await?;
         Ok(new_touchpoint)
     }
+
+    /// Remembers which session registered a device token so revoking the session also stops its
+    /// push notifications. Sessions that were never registered are left alone.
+    async fn attach_device_token_to_session(
+        &self,
+        account_id: &AccountId,
+        session_id: &str,
+        device_token: &str,
+    ) -> Result<(), AccountError> {
+        let session = match self.session_repo.fetch(account_id, session_id).await {
+            Ok(session) => session,
+            Err(DatabaseError::ObjectNotFound(_)) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        if session.is_revoked() {
+            return Err(AccountError::UnauthorizedDeviceTokenRegistration);
+        }
+        if session.push_device_tokens.iter().any(|t| t == device_token)
+            || self
+                .session_repo
+                .add_push_device_token(account_id, session_id, device_token)
+                .await?
+        {
+            return Ok(());
+        }
+        // The session was revoked after we fetched it
+        Err(AccountError::UnauthorizedDeviceTokenRegistration)
+    }
 }
 
 pub async fn generate_device_arn(
@@ -1,9 +1,11 @@
+use types::security_event::SecurityEventType;
+
 use crate::{
     entities::{Account, Touchpoint},
     error::AccountError,
 };
 
-use super::{ClearPushTouchpointsInput, Service};
+use super::{ClearPushTouchpointsInput, RecordSecurityEventInput, Service};
 
 impl Service {
     pub async fn clear_push_touchpoints(
@@ -19,7 +21,14 @@ impl Service {
             Account::Full(full_account) => full_account.common_fields.touchpoints = touchpoints,
             Account::Lite(lite_account) => lite_account.common_fields.touchpoints = touchpoints,
         };
-        self.account_repo.persist(&account).await?;
+        self.record_security_event(
+            RecordSecurityEventInput {
+                account_id: input.account_id,
+                event: SecurityEventType::PushTouchpointsCleared,
+            },
+            async { Ok::<_, AccountError>(self.account_repo.persist(&account).await?) },
+        )
+        .await?;
         Ok(())
     }
 }
@@ -2,9 +2,9 @@ use crate::{
     entities::{Account, CommonAccountFields, FullAccount, FullAccountAuthKeys},
     error::AccountError,
 };
-use types::account::identifiers::AuthKeysId;
+use types::{account::identifiers::AuthKeysId, security_event::SecurityEventType};
 
-use super::{CreateAndRotateAuthKeysInput, Service};
+use super::{CreateAndRotateAuthKeysInput, RecordSecurityEventInput, Service};
 
 impl Service {
     pub async fn create_and_rotate_auth_keys(
@@ -22,11 +22,12 @@ impl Service {
 
         // Update authentication keys
         let auth_keys_id = AuthKeysId::gen().map_err(AccountError::from)?;
+        let account_id = input.account_id;
         let mut auth_keys = full_account.auth_keys.clone();
         auth_keys.insert(auth_keys_id.clone(), input.into());
 
         let updated_common_fields = CommonAccountFields {
-            active_auth_keys_id: auth_keys_id,
+            active_auth_keys_id: auth_keys_id.clone(),
             recovery_auth_pubkey,
             ..common_fields
         };
@@ -38,7 +39,14 @@ impl Service {
             ..full_account
         }
         .into();
-        self.account_repo.persist(&updated_account).await?;
+        self.record_security_event(
+            RecordSecurityEventInput {
+                account_id,
+                event: SecurityEventType::AuthKeysRotated { auth_keys_id },
+            },
+            async { Ok::<_, AccountError>(self.account_repo.persist(&updated_account).await?) },
+        )
+        .await?;
         Ok(updated_account)
     }
 }
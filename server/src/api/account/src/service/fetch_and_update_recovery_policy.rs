@@ -0,0 +1,26 @@
+use super::{FetchAccountInput, FetchAndUpdateRecoveryPolicyInput, Service};
+use crate::entities::FullAccount;
+use crate::error::AccountError;
+
+impl Service {
+    pub async fn fetch_and_update_recovery_policy(
+        &self,
+        input: FetchAndUpdateRecoveryPolicyInput<'_>,
+    ) -> Result<(), AccountError> {
+        let full_account = self
+            .fetch_full_account(FetchAccountInput {
+                account_id: input.account_id,
+            })
+            .await?;
+
+        let updated_account = FullAccount {
+            recovery_policy: input.new_recovery_policy,
+            pending_recovery_policy: input.new_pending_recovery_policy,
+            ..full_account
+        }
+        .into();
+
+        self.account_repo.persist(&updated_account).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use super::{FetchAccountInput, FetchAndUpdateSpendPolicyInput, Service};
+use crate::entities::FullAccount;
+use crate::error::AccountError;
+
+impl Service {
+    pub async fn fetch_and_update_spend_policy(
+        &self,
+        input: FetchAndUpdateSpendPolicyInput<'_>,
+    ) -> Result<(), AccountError> {
+        let full_account = self
+            .fetch_full_account(FetchAccountInput {
+                account_id: input.account_id,
+            })
+            .await?;
+
+        let updated_account = FullAccount {
+            spend_policy: input.new_spend_policy,
+            ..full_account
+        }
+        .into();
+
+        self.account_repo.persist(&updated_account).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use types::security_event::SecurityEvent;
+
+use crate::error::AccountError;
+
+use super::{FetchSecurityEventsInput, Service};
+
+impl Service {
+    /// Returns a page of the account's security events, newest first, along with a token for the
+    /// next page if there may be one
+    pub async fn fetch_security_events(
+        &self,
+        input: FetchSecurityEventsInput<'_>,
+    ) -> Result<(Vec<SecurityEvent>, Option<String>), AccountError> {
+        Ok(self
+            .security_event_repo
+            .fetch_page_for_account_id(input.account_id, input.limit, input.page_token)
+            .await?)
+    }
+}
@@ -0,0 +1,34 @@
+use database::ddb::DatabaseError;
+use types::authn_authz::session::DeviceSession;
+
+use crate::error::AccountError;
+
+use super::{FetchSessionInput, FetchSessionsInput, Service};
+
+impl Service {
+    pub async fn fetch_session(
+        &self,
+        input: FetchSessionInput<'_>,
+    ) -> Result<DeviceSession, AccountError> {
+        self.session_repo
+            .fetch(input.account_id, input.session_id)
+            .await
+            .map_err(|err| match err {
+                DatabaseError::ObjectNotFound(_) => AccountError::SessionNotFound,
+                err => err.into(),
+            })
+    }
+
+    /// Returns the account's sessions, most recently seen first
+    pub async fn fetch_sessions(
+        &self,
+        input: FetchSessionsInput<'_>,
+    ) -> Result<Vec<DeviceSession>, AccountError> {
+        let mut sessions = self
+            .session_repo
+            .fetch_for_account_id(input.account_id)
+            .await?;
+        sessions.sort_by(|a, b| b.last_seen_at.cmp(&a.last_seen_at));
+        Ok(sessions)
+    }
+}
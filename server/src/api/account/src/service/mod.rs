@@ -2,7 +2,9 @@ use crate::entities::{
     CommsVerificationClaim, CommsVerificationScope, FullAccountAuthKeys, LiteAccount,
     LiteAccountAuthKeys, SpendingKeyset, TouchpointPlatform,
 };
+use crate::recovery_policy::{PendingRecoveryPolicy, RecoveryPolicy};
 use crate::spend_limit::SpendingLimit;
+use crate::spend_policy::SpendPolicyRule;
 use crate::{
     entities::{Keyset, Network},
     repository::Repository,
@@ -10,7 +12,11 @@ use crate::{
 use bdk_utils::bdk::bitcoin::secp256k1::PublicKey;
 use isocountry::CountryCode;
 use repository::consent::Repository as ConsentRepository;
+use repository::security_event::Repository as SecurityEventRepository;
+use repository::session::Repository as SessionRepository;
 use types::account::identifiers::{AccountId, AuthKeysId, KeysetId, TouchpointId};
+use types::authn_authz::session::DeviceInfo;
+use types::security_event::SecurityEventType;
 use userpool::userpool::UserPoolService;
 
 mod activate_touchpoint_for_account;
@@ -23,11 +29,18 @@ mod create_inactive_spending_keyset;
 mod create_lite_account;
 mod delete_account;
 mod fetch_account;
+mod fetch_and_update_recovery_policy;
 mod fetch_and_update_spend_limit;
+mod fetch_and_update_spend_policy;
 mod fetch_or_create_comms_verification_claim;
+mod fetch_security_events;
+mod fetch_sessions;
 mod fetch_touchpoint;
 mod migrations;
 mod put_comms_verification_claim;
+mod record_security_event;
+mod record_session_activity;
+mod revoke_sessions;
 mod rotate_to_spending_keyset;
 mod upgrade_lite_account_to_full_account;
 
@@ -35,6 +48,8 @@ mod upgrade_lite_account_to_full_account;
 pub struct Service {
     account_repo: Repository,
     consent_repo: ConsentRepository,
+    session_repo: SessionRepository,
+    security_event_repo: SecurityEventRepository,
     userpool_service: UserPoolService,
 }
 
@@ -42,11 +57,15 @@ impl Service {
     pub fn new(
         account_repo: Repository,
         consent_repo: ConsentRepository,
+        session_repo: SessionRepository,
+        security_event_repo: SecurityEventRepository,
         userpool_service: UserPoolService,
     ) -> Self {
         Self {
             account_repo,
             consent_repo,
+            session_repo,
+            security_event_repo,
             userpool_service,
         }
     }
@@ -109,6 +128,8 @@ pub struct AddPushTouchpointToAccountInput {
     pub platform: TouchpointPlatform,
     pub device_token: String,
     pub access_token: String,
+    // The session registering the device token, if it's known
+    pub session_id: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -136,6 +157,19 @@ pub struct FetchAndUpdateSpendingLimitInput<'a> {
     pub new_spending_limit: Option<SpendingLimit>,
 }
 
+#[derive(Debug)]
+pub struct FetchAndUpdateSpendPolicyInput<'a> {
+    pub account_id: &'a AccountId,
+    pub new_spend_policy: Vec<SpendPolicyRule>,
+}
+
+#[derive(Debug)]
+pub struct FetchAndUpdateRecoveryPolicyInput<'a> {
+    pub account_id: &'a AccountId,
+    pub new_recovery_policy: RecoveryPolicy,
+    pub new_pending_recovery_policy: Option<PendingRecoveryPolicy>,
+}
+
 #[derive(Debug, Clone)]
 pub struct FetchOrCreateCommsVerificationClaimInput {
     pub account_id: AccountId,
@@ -183,3 +217,45 @@ pub struct UpgradeLiteAccountToFullAccountInput<'a> {
 pub struct DeleteAccountInput<'a> {
     pub account_id: &'a AccountId,
 }
+
+#[derive(Debug, Clone)]
+pub struct FetchSessionInput<'a> {
+    pub account_id: &'a AccountId,
+    pub session_id: &'a str,
+}
+
+#[derive(Debug, Clone)]
+pub struct FetchSessionsInput<'a> {
+    pub account_id: &'a AccountId,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordSessionActivityInput<'a> {
+    pub account_id: &'a AccountId,
+    pub session_id: &'a str,
+    pub device: DeviceInfo,
+}
+
+#[derive(Debug, Clone)]
+pub struct RevokeSessionInput<'a> {
+    pub account_id: &'a AccountId,
+    pub session_id: &'a str,
+}
+
+#[derive(Debug, Clone)]
+pub struct RevokeAllSessionsInput<'a> {
+    pub account_id: &'a AccountId,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordSecurityEventInput<'a> {
+    pub account_id: &'a AccountId,
+    pub event: SecurityEventType,
+}
+
+#[derive(Debug, Clone)]
+pub struct FetchSecurityEventsInput<'a> {
+    pub account_id: &'a AccountId,
+    pub limit: i32,
+    pub page_token: Option<&'a str>,
+}
@@ -0,0 +1,64 @@
+use std::future::Future;
+
+use time::OffsetDateTime;
+use tracing::{event, Level};
+use types::account::identifiers::AccountId;
+use types::security_event::{SecurityEvent, SecurityEventStatus, SecurityEventType};
+
+use crate::error::AccountError;
+
+use super::{RecordSecurityEventInput, Service};
+
+impl Service {
+    /// Makes the change `change` describes, logging it to the account's security log. The event
+    /// is appended as attempted before the change is made, so that a change can never be saved
+    /// without a trace in the log, and as completed once it has been made. If the change fails,
+    /// only the attempt is logged.
+    pub async fn record_security_event<T, E>(
+        &self,
+        input: RecordSecurityEventInput<'_>,
+        change: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E>
+    where
+        E: From<AccountError>,
+    {
+        self.append_security_event(
+            input.account_id,
+            input.event.clone(),
+            SecurityEventStatus::Attempted,
+        )
+        .await?;
+        let result = change.await?;
+        // The change is made by now, so failing the request would only invite a retry
+        if let Err(err) = self
+            .append_security_event(
+                input.account_id,
+                input.event,
+                SecurityEventStatus::Completed,
+            )
+            .await
+        {
+            event!(
+                Level::ERROR,
+                "Could not record completed security event: {err}"
+            );
+        }
+        Ok(result)
+    }
+
+    async fn append_security_event(
+        &self,
+        account_id: &AccountId,
+        event: SecurityEventType,
+        status: SecurityEventStatus,
+    ) -> Result<(), AccountError> {
+        let security_event = SecurityEvent::new(
+            account_id.to_owned(),
+            event,
+            status,
+            OffsetDateTime::now_utc(),
+        );
+        self.security_event_repo.persist(&security_event).await?;
+        Ok(())
+    }
+}
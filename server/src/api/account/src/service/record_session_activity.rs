@@ -0,0 +1,59 @@
+use database::ddb::DatabaseError;
+use time::OffsetDateTime;
+use tracing::{event, Level};
+use types::authn_authz::session::DeviceSession;
+
+use crate::error::AccountError;
+
+use super::{RecordSessionActivityInput, Service};
+
+impl Service {
+    /// Returns the caller's session, registering it on its first request. Callers are expected
+    /// to reject the request if the returned session has been revoked.
+    ///
+    /// Only reading the session can fail. Activity is written at most once per
+    /// [`LAST_SEEN_RESOLUTION`](types::authn_authz::session::LAST_SEEN_RESOLUTION) unless the
+    /// device changes, and failing to write it is logged rather than returned, so that requests
+    /// don't depend on it.
+    pub async fn record_session_activity(
+        &self,
+        input: RecordSessionActivityInput<'_>,
+    ) -> Result<DeviceSession, AccountError> {
+        let now = OffsetDateTime::now_utc();
+        let mut session = match self
+            .session_repo
+            .fetch(input.account_id, input.session_id)
+            .await
+        {
+            Ok(session) => session,
+            Err(DatabaseError::ObjectNotFound(_)) => {
+                let session = DeviceSession::new(
+                    input.account_id.to_owned(),
+                    input.session_id.to_owned(),
+                    input.device,
+                    now,
+                );
+                if let Err(err) = self.session_repo.persist(&session).await {
+                    event!(Level::ERROR, "Could not register session: {err}");
+                }
+                return Ok(session);
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if !session.is_revoked() && session.record_activity(input.device, now) {
+            match self.session_repo.update_activity(&session).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    // The session was revoked after we fetched it
+                    session = self
+                        .session_repo
+                        .fetch(input.account_id, input.session_id)
+                        .await?;
+                }
+                Err(err) => event!(Level::ERROR, "Could not record session activity: {err}"),
+            }
+        }
+        Ok(session)
+    }
+}
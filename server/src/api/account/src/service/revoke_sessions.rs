@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use time::OffsetDateTime;
+use types::account::identifiers::AccountId;
+
+use crate::{
+    entities::{Account, Touchpoint},
+    error::AccountError,
+};
+
+use super::{RevokeAllSessionsInput, RevokeSessionInput, Service};
+
+impl Service {
+    pub async fn revoke_session(&self, input: RevokeSessionInput<'_>) -> Result<(), AccountError> {
+        if !self
+            .session_repo
+            .revoke(
+                input.account_id,
+                input.session_id,
+                OffsetDateTime::now_utc(),
+            )
+            .await?
+        {
+            return Err(AccountError::SessionNotFound);
+        }
+        self.clear_push_touchpoints_for_revoked_sessions(input.account_id)
+            .await
+    }
+
+    pub async fn revoke_all_sessions(
+        &self,
+        input: RevokeAllSessionsInput<'_>,
+    ) -> Result<(), AccountError> {
+        let now = OffsetDateTime::now_utc();
+        let sessions = self
+            .session_repo
+            .fetch_for_account_id(input.account_id)
+            .await?;
+        for session in sessions.iter().filter(|s| !s.is_revoked()) {
+            self.session_repo
+                .revoke(input.account_id, &session.session_id, now)
+                .await?;
+        }
+        self.clear_push_touchpoints_for_revoked_sessions(input.account_id)
+            .await
+    }
+
+    /// Stops push notifications to devices that are only signed in with revoked sessions. A device
+    /// token is kept if the same device has since signed in again.
+    async fn clear_push_touchpoints_for_revoked_sessions(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<(), AccountError> {
+        let sessions = self.session_repo.fetch_for_account_id(account_id).await?;
+        let (revoked, active): (Vec<_>, Vec<_>) =
+            sessions.into_iter().partition(|s| s.is_revoked());
+        let active_tokens: HashSet<String> = active
+            .into_iter()
+            .flat_map(|s| s.push_device_tokens)
+            .collect();
+        let revoked_tokens: HashSet<String> = revoked
+            .into_iter()
+            .flat_map(|s| s.push_device_tokens)
+            .filter(|token| !active_tokens.contains(token))
+            .collect();
+        if revoked_tokens.is_empty() {
+            return Ok(());
+        }
+
+        let mut account = self.account_repo.fetch(account_id).await?;
+        let mut touchpoints: Vec<Touchpoint> = account.get_common_fields().touchpoints.clone();
+        let count = touchpoints.len();
+        touchpoints.retain(|t| {
+            !matches!(t, Touchpoint::Push { device_token, .. } if revoked_tokens.contains(device_token))
+        });
+        if touchpoints.len() == count {
+            return Ok(());
+        }
+
+        match &mut account {
+            Account::Full(full_account) => full_account.common_fields.touchpoints = touchpoints,
+            Account::Lite(lite_account) => lite_account.common_fields.touchpoints = touchpoints,
+        };
+        self.account_repo.persist(&account).await?;
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
+use types::security_event::SecurityEventType;
+
 use crate::{
     entities::{Account, FullAccount},
     error::AccountError,
 };
 
-use super::{FetchAccountInput, RotateToSpendingKeysetInput, Service};
+use super::{FetchAccountInput, RecordSecurityEventInput, RotateToSpendingKeysetInput, Service};
 
 impl Service {
     pub async fn rotate_to_spending_keyset(
@@ -24,7 +26,16 @@ impl Service {
             ..full_account
         }
         .into();
-        self.account_repo.persist(&account).await?;
+        self.record_security_event(
+            RecordSecurityEventInput {
+                account_id: input.account_id,
+                event: SecurityEventType::SpendingKeysetRotated {
+                    keyset_id: input.keyset_id.to_owned(),
+                },
+            },
+            async { Ok::<_, AccountError>(self.account_repo.persist(&account).await?) },
+        )
+        .await?;
         Ok(account)
     }
 }
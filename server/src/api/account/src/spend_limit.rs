@@ -13,6 +13,20 @@ pub struct SpendingLimit {
     pub active: bool,
     pub amount: Money,
     pub time_zone_offset: UtcOffset,
+    // Limits set before rolling windows were supported are measured over the calendar day.
+    #[serde(default)]
+    pub window: SpendWindow,
+}
+
+/// How the day a [`SpendingLimit`] applies to is measured.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SpendWindow {
+    /// The day starts at 3AM in the limit's `time_zone_offset`.
+    #[default]
+    Calendar,
+    /// The 24 hours leading up to the transaction being signed.
+    Rolling,
 }
 
 fn default_true() -> bool {
@@ -28,6 +42,7 @@ impl Default for SpendingLimit {
                 currency_code: USD,
             },
             time_zone_offset: UtcOffset::UTC,
+            window: SpendWindow::Calendar,
         }
     }
 }
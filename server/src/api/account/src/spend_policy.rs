@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::Display as StrumDisplay;
+use time::Duration;
+use utoipa::ToSchema;
+
+use crate::spend_limit::Money;
+
+/// A rule the server enforces before cosigning a Mobile Pay transaction, in addition to the
+/// account's daily [`SpendingLimit`](crate::spend_limit::SpendingLimit).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SpendPolicyRule {
+    /// Caps the outflow of any single transaction.
+    PerTransactionCap { limit: Money },
+    /// Caps the total outflow over a rolling period, including the transaction being signed.
+    PeriodCap { period: SpendPeriod, limit: Money },
+    /// Only allows transactions whose external outputs all pay one of these addresses.
+    AllowlistedDestinations { addresses: Vec<String> },
+    /// Caps the transaction's estimated fee rate.
+    MaxFeeRate { sats_per_vbyte: f32 },
+    /// Caps the number of transactions cosigned over a rolling period, including the transaction
+    /// being signed.
+    Velocity {
+        period: SpendPeriod,
+        max_transactions: u32,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, ToSchema, StrumDisplay)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "lowercase")]
+pub enum SpendPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl SpendPeriod {
+    /// The length of the rolling window ending now that a period covers. Monthly windows are
+    /// bounded by how long spending records are retained.
+    pub fn duration(&self) -> Duration {
+        match self {
+            SpendPeriod::Daily => Duration::DAY,
+            SpendPeriod::Weekly => Duration::WEEK,
+            SpendPeriod::Monthly => Duration::days(30),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::currencies::CurrencyCode::USD;
+
+    use super::*;
+
+    #[test]
+    fn test_spend_policy_rule_serialization() {
+        let rules = vec![
+            SpendPolicyRule::PerTransactionCap {
+                limit: Money {
+                    amount: 10_000,
+                    currency_code: USD,
+                },
+            },
+            SpendPolicyRule::Velocity {
+                period: SpendPeriod::Weekly,
+                max_transactions: 10,
+            },
+        ];
+
+        let serialized = serde_json::to_value(&rules).unwrap();
+        assert_eq!(
+            serialized,
+            serde_json::json!([
+                {
+                    "type": "PER_TRANSACTION_CAP",
+                    "limit": { "amount": 10000, "currency_code": "USD" }
+                },
+                {
+                    "type": "VELOCITY",
+                    "period": "WEEKLY",
+                    "max_transactions": 10
+                }
+            ])
+        );
+        assert_eq!(
+            serde_json::from_value::<Vec<SpendPolicyRule>>(serialized).unwrap(),
+            rules
+        );
+    }
+}
@@ -10,12 +10,14 @@ use jwt_authorizer::{JwtAuthorizer, JwtClaims, Refresh, RefreshStrategy, Validat
 use types::account::identifiers::AccountId;
 use types::authn_authz::cognito::CognitoUser;
 use types::authn_authz::AccessTokenClaims;
+use userpool::native::NativeAuthConfig;
 use userpool::test_utils::TEST_JWT_SIGNING_SECRET;
 use userpool::userpool::CognitoMode;
 
 pub enum AuthorizerConfig {
     Test,
     Cognito,
+    Native(NativeAuthConfig),
 }
 
 impl From<userpool::userpool::Config> for AuthorizerConfig {
@@ -23,6 +25,7 @@ impl From<userpool::userpool::Config> for AuthorizerConfig {
         match value.cognito {
             CognitoMode::Environment => Self::Cognito,
             CognitoMode::Test => Self::Test,
+            CognitoMode::Native => Self::Native(NativeAuthConfig::from_env()),
         }
     }
 }
@@ -54,6 +57,14 @@ impl AuthorizerConfig {
                         ..Default::default()
                     })
             }
+            AuthorizerConfig::Native(config) => {
+                // Tokens are signed in-process, so the published key set is known up front and
+                // changes only when the configured keys do
+                let jwks = serde_json::to_string(&config.signing_keys.jwks())
+                    .expect("could not serialize signing keys");
+                let validation = Validation::new().iss(&[&config.issuer]);
+                JwtAuthorizer::from_jwks_text(&jwks).validation(validation)
+            }
         }
     }
 }
@@ -0,0 +1,7 @@
+use feature_flags::flag::Flag;
+
+pub(crate) const FLAG_REQUIRE_REQUEST_SIGNATURES: Flag<bool> =
+    Flag::new("f8e-require-request-signatures");
+
+pub(crate) const FLAG_SESSION_REGISTRY_ENABLED: Flag<bool> =
+    Flag::new("f8e-session-registry-enabled");
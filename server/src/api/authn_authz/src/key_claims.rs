@@ -13,6 +13,8 @@ use types::authn_authz::cognito::{CognitoUser, CognitoUsername};
 use types::authn_authz::AccessTokenClaims;
 use userpool::userpool::{UserPoolError, UserPoolService};
 
+use crate::signed_request::{SignedRequestContext, APP_REQUEST_SIG_HEADER, HW_REQUEST_SIG_HEADER};
+
 pub const APP_SIG_HEADER: &str = "X-App-Signature";
 pub const HW_SIG_HEADER: &str = "X-Hw-Signature";
 
@@ -20,8 +22,43 @@ pub const HW_SIG_HEADER: &str = "X-Hw-Signature";
 pub struct KeyClaims {
     pub account_id: String,
     pub username: CognitoUsername,
-    pub app_signed: bool,
-    pub hw_signed: bool,
+    /// The app key signed either the access token or this request
+    app_signed: bool,
+    /// The hardware key signed either the access token or this request
+    hw_signed: bool,
+    /// The app key signed the canonical message of this request
+    app_signed_request: bool,
+    /// The hardware key signed the canonical message of this request
+    hw_signed_request: bool,
+    token_signatures_accepted: bool,
+}
+
+/// The key factors a route requires before it will act on a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequiredFactors {
+    App,
+    Hw,
+    AppOrHw,
+    AppAndHw,
+}
+
+impl KeyClaims {
+    /// Whether the request was signed by the `required` factors. A signature over the access
+    /// token can be replayed for the token's lifetime, so it only counts until
+    /// `f8e-require-request-signatures` is turned on.
+    pub fn has_factors(&self, required: RequiredFactors) -> bool {
+        let (app, hw) = if self.token_signatures_accepted {
+            (self.app_signed, self.hw_signed)
+        } else {
+            (self.app_signed_request, self.hw_signed_request)
+        };
+        match required {
+            RequiredFactors::App => app,
+            RequiredFactors::Hw => hw,
+            RequiredFactors::AppOrHw => app || hw,
+            RequiredFactors::AppAndHw => app && hw,
+        }
+    }
 }
 
 #[async_trait]
@@ -37,6 +74,16 @@ where
         let auth_header = parts.headers.get(header::AUTHORIZATION).cloned();
         let app_sig_header = parts.headers.get(APP_SIG_HEADER).cloned();
         let hw_sig_header = parts.headers.get(HW_SIG_HEADER).cloned();
+        let app_request_sig_header = parts.headers.get(APP_REQUEST_SIG_HEADER).cloned();
+        let hw_request_sig_header = parts.headers.get(HW_REQUEST_SIG_HEADER).cloned();
+        // Requests that didn't pass through `digest_signed_request` can't carry request
+        // signatures, and fall back to signatures over the access token.
+        let (request_message, token_signatures_accepted) = parts
+            .extensions
+            .get::<SignedRequestContext>()
+            .map_or((None, true), |context| {
+                (context.message.clone(), context.token_signatures_accepted)
+            });
 
         let jwt = auth_header
             .and_then(|value| value.to_str().ok().map(String::from))
@@ -55,29 +102,51 @@ where
                     username,
                     app_signed: false,
                     hw_signed: false,
+                    app_signed_request: false,
+                    hw_signed_request: false,
+                    token_signatures_accepted,
                 });
             }
         };
         let (app_pubkey, hw_pubkey) =
             get_pubkeys_from_cognito(&user_pool, account_id.clone()).await?;
 
-        let app_signed = app_sig_header
+        let app_signed_request = app_request_sig_header
             .and_then(|value| value.to_str().ok().map(String::from))
-            .map_or(false, |app_sig_header| {
-                verify_signature(&app_sig_header, jwt.clone(), app_pubkey)
+            .zip(request_message.clone())
+            .map_or(false, |(app_sig_header, message)| {
+                verify_signature(&app_sig_header, message, app_pubkey.clone())
             });
 
-        let hw_signed = hw_sig_header
+        let hw_signed_request = hw_request_sig_header
             .and_then(|value| value.to_str().ok().map(String::from))
-            .map_or(false, |hw_sig_header| {
-                verify_signature(&hw_sig_header, jwt, hw_pubkey)
+            .zip(request_message)
+            .map_or(false, |(hw_sig_header, message)| {
+                verify_signature(&hw_sig_header, message, hw_pubkey.clone())
             });
 
+        let app_signed = app_signed_request
+            || app_sig_header
+                .and_then(|value| value.to_str().ok().map(String::from))
+                .map_or(false, |app_sig_header| {
+                    verify_signature(&app_sig_header, jwt.clone(), app_pubkey)
+                });
+
+        let hw_signed = hw_signed_request
+            || hw_sig_header
+                .and_then(|value| value.to_str().ok().map(String::from))
+                .map_or(false, |hw_sig_header| {
+                    verify_signature(&hw_sig_header, jwt, hw_pubkey)
+                });
+
         Ok(Self {
             account_id: account_id.to_string(),
             username,
             app_signed,
             hw_signed,
+            app_signed_request,
+            hw_signed_request,
+            token_signatures_accepted,
         })
     }
 }
@@ -95,20 +164,21 @@ pub fn verify_signature(signature: &str, message: String, pubkey: String) -> boo
 }
 
 fn get_user_name_from_jwt(jwt: &str) -> Option<CognitoUsername> {
+    claims_from_verified_jwt(jwt).map(|claims| claims.username)
+}
+
+/// Reads the claims of an access token whose signature the authorizer has already checked.
+pub fn claims_from_verified_jwt(jwt: &str) -> Option<AccessTokenClaims> {
     let mut validation = Validation::new(jsonwebtoken::Algorithm::RS256);
     // We already validate the signature on the token in [authorizer.rs:23].
     // By the time we get to here, a 401 has already been returned if the signature is invalid.
     // Because cognito rotates in signing keys every 24 hours, we don't want to have to fetch
     // and cache it in multiple places.
-    // Since we're just pulling out the user name, we skip signature validation here.
+    // Since we're just pulling out claims, we skip signature validation here.
     validation.insecure_disable_signature_validation();
-    if let Ok(token) =
-        jsonwebtoken::decode::<AccessTokenClaims>(jwt, &DecodingKey::from_secret(&[]), &validation)
-    {
-        Some(token.claims.username)
-    } else {
-        None
-    }
+    jsonwebtoken::decode::<AccessTokenClaims>(jwt, &DecodingKey::from_secret(&[]), &validation)
+        .ok()
+        .map(|token| token.claims)
 }
 
 async fn get_pubkeys_from_cognito(
@@ -130,11 +200,15 @@ mod tests {
 
     use axum::body::Body;
     use axum::extract::FromRequestParts;
-    use axum::http::Request;
+    use axum::http::{Method, Request};
     use types::authn_authz::cognito::CognitoUsername;
     use userpool::userpool::{CognitoMode, UserPoolService};
 
-    use crate::key_claims::{KeyClaims, APP_SIG_HEADER, HW_SIG_HEADER};
+    use crate::key_claims::{KeyClaims, RequiredFactors, APP_SIG_HEADER, HW_SIG_HEADER};
+    use crate::signed_request::{
+        canonical_request_message, SignedRequestContext, APP_REQUEST_SIG_HEADER,
+        HW_REQUEST_SIG_HEADER,
+    };
     use crate::test_utils::{
         get_test_access_token, sign_with_app_key, sign_with_hw_key, TEST_USERNAME,
     };
@@ -169,6 +243,7 @@ mod tests {
         let userpool = UserPoolService::new(
             userpool::userpool::Config {
                 cognito: CognitoMode::Test,
+                dynamodb: None,
             }
             .to_connection()
             .await,
@@ -199,6 +274,7 @@ mod tests {
         let userpool = UserPoolService::new(
             userpool::userpool::Config {
                 cognito: CognitoMode::Test,
+                dynamodb: None,
             }
             .to_connection()
             .await,
@@ -231,6 +307,7 @@ mod tests {
         let userpool = UserPoolService::new(
             userpool::userpool::Config {
                 cognito: CognitoMode::Test,
+                dynamodb: None,
             }
             .to_connection()
             .await,
@@ -243,4 +320,122 @@ mod tests {
         assert!(key_proof.app_signed);
         assert!(key_proof.hw_signed);
     }
+
+    async fn extract_with_context(
+        request: Request<Body>,
+        context: SignedRequestContext,
+    ) -> KeyClaims {
+        let (mut request_parts, _) = request.into_parts();
+        request_parts.extensions.insert(context);
+
+        let userpool = UserPoolService::new(
+            userpool::userpool::Config {
+                cognito: CognitoMode::Test,
+                dynamodb: None,
+            }
+            .to_connection()
+            .await,
+        );
+
+        KeyClaims::from_request_parts(&mut request_parts, &userpool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_signatures_satisfy_factors() {
+        let access_token = get_test_access_token();
+        let message = canonical_request_message(
+            &Method::PUT,
+            "/api/accounts/000000000000000000000000000/keysets/1",
+            b"{}",
+            "nonce",
+        );
+
+        let request = Request::builder()
+            .uri("http://example.com/")
+            .header("Authorization", format!("Bearer {}", access_token))
+            .header(APP_REQUEST_SIG_HEADER, sign_with_app_key(&message))
+            .header(HW_REQUEST_SIG_HEADER, sign_with_hw_key(&message))
+            .body(Body::empty())
+            .unwrap();
+
+        let key_proof = extract_with_context(
+            request,
+            SignedRequestContext {
+                message: Some(message),
+                token_signatures_accepted: false,
+            },
+        )
+        .await;
+        assert!(key_proof.app_signed_request);
+        assert!(key_proof.hw_signed_request);
+        assert!(key_proof.app_signed);
+        assert!(key_proof.hw_signed);
+        assert!(key_proof.has_factors(RequiredFactors::AppAndHw));
+    }
+
+    #[tokio::test]
+    async fn test_request_signatures_over_another_request_are_rejected() {
+        let access_token = get_test_access_token();
+        let signed_message = canonical_request_message(&Method::GET, "/other", b"", "nonce");
+        let message = canonical_request_message(&Method::DELETE, "/target", b"", "nonce");
+
+        let request = Request::builder()
+            .uri("http://example.com/")
+            .header("Authorization", format!("Bearer {}", access_token))
+            .header(APP_REQUEST_SIG_HEADER, sign_with_app_key(&signed_message))
+            .header(HW_REQUEST_SIG_HEADER, sign_with_hw_key(&signed_message))
+            .body(Body::empty())
+            .unwrap();
+
+        let key_proof = extract_with_context(
+            request,
+            SignedRequestContext {
+                message: Some(message),
+                token_signatures_accepted: false,
+            },
+        )
+        .await;
+        assert!(!key_proof.app_signed_request);
+        assert!(!key_proof.hw_signed_request);
+        assert!(!key_proof.has_factors(RequiredFactors::AppOrHw));
+    }
+
+    #[tokio::test]
+    async fn test_token_signatures_only_count_while_accepted() {
+        let access_token = get_test_access_token();
+        let build_request = || {
+            Request::builder()
+                .uri("http://example.com/")
+                .header("Authorization", format!("Bearer {}", access_token))
+                .header(APP_SIG_HEADER, sign_with_app_key(&access_token))
+                .header(HW_SIG_HEADER, sign_with_hw_key(&access_token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let key_proof = extract_with_context(
+            build_request(),
+            SignedRequestContext {
+                message: None,
+                token_signatures_accepted: true,
+            },
+        )
+        .await;
+        assert!(key_proof.has_factors(RequiredFactors::AppAndHw));
+
+        let key_proof = extract_with_context(
+            build_request(),
+            SignedRequestContext {
+                message: None,
+                token_signatures_accepted: false,
+            },
+        )
+        .await;
+        assert!(key_proof.app_signed);
+        assert!(key_proof.hw_signed);
+        assert!(!key_proof.has_factors(RequiredFactors::App));
+        assert!(!key_proof.has_factors(RequiredFactors::Hw));
+    }
 }
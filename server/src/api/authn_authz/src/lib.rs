@@ -1,4 +1,7 @@
 pub mod authorizer;
+pub(crate) mod flags;
 pub mod key_claims;
 pub mod routes;
+pub mod session;
+pub mod signed_request;
 pub mod test_utils;
@@ -1,28 +1,64 @@
-use axum::routing::post;
+use std::str::FromStr;
+
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::routing::{delete, get, post};
 use axum::Router;
 use axum::{extract::State, Json};
+use jsonwebtoken::jwk::JwkSet;
+use jwt_authorizer::JwtClaims;
 use serde::{Deserialize, Serialize};
-use tracing::{error, instrument};
+use time::{serde::rfc3339, OffsetDateTime};
+use tracing::{error, event, instrument, Level};
 use types::authn_authz::cognito::{CognitoUser, CognitoUsername};
+use types::authn_authz::session::{DeviceInfo, DeviceSession};
+use types::authn_authz::AccessTokenClaims;
 use userpool::userpool::{AuthTokens, UserPoolError, UserPoolService};
 use utoipa::{OpenApi, ToSchema};
 
-use account::service::{FetchAccountByAuthKeyInput, Service as AccountService};
+use account::service::{
+    FetchAccountByAuthKeyInput, FetchSessionsInput, RecordSessionActivityInput,
+    RevokeAllSessionsInput, RevokeSessionInput, Service as AccountService,
+};
 use bdk_utils::bdk::bitcoin::secp256k1::PublicKey;
-use errors::ApiError;
+use errors::{ApiError, ErrorCategory, ErrorCode, ErrorResponseBody, ErrorResponseBodyError};
 use http_server::swagger::{SwaggerEndpoint, Url};
 use types::account::identifiers::AccountId;
 
+use crate::key_claims::claims_from_verified_jwt;
+use crate::session::device_info;
+use crate::signed_request::NonceIssuer;
+
 #[derive(Clone, axum_macros::FromRef)]
-pub struct RouteState(pub UserPoolService, pub AccountService);
+pub struct RouteState(pub UserPoolService, pub AccountService, pub NonceIssuer);
 
 impl RouteState {
+    pub fn authed_router(&self) -> Router {
+        Router::new()
+            .route("/api/request-nonce", post(issue_request_nonce))
+            .with_state(self.to_owned())
+    }
+
+    pub fn account_authed_router(&self) -> Router {
+        Router::new()
+            .route(
+                "/api/accounts/:account_id/sessions",
+                get(list_sessions).delete(revoke_all_sessions),
+            )
+            .route(
+                "/api/accounts/:account_id/sessions/:session_id",
+                delete(revoke_session),
+            )
+            .with_state(self.to_owned())
+    }
+
     pub fn unauthed_router(&self) -> Router {
         Router::new()
             .route("/api/recovery-auth", post(authenticate_with_recovery))
             .route("/api/hw-auth", post(authenticate_with_hardware))
             .route("/api/authenticate", post(authenticate))
             .route("/api/authenticate/tokens", post(get_tokens))
+            .route("/.well-known/jwks.json", get(get_jwks))
             .with_state(self.to_owned())
     }
 }
@@ -43,6 +79,11 @@ impl From<RouteState> for SwaggerEndpoint {
         authenticate_with_hardware,
         authenticate_with_recovery,
         get_tokens,
+        get_jwks,
+        issue_request_nonce,
+        list_sessions,
+        revoke_all_sessions,
+        revoke_session,
     ),
     components(
         schemas(
@@ -57,10 +98,18 @@ impl From<RouteState> for SwaggerEndpoint {
             CognitoUsername,
             GetTokensRequest,
             GetTokensResponse,
+            ListSessionsResponse,
+            RequestNonceResponse,
+            SessionResponse,
+            ErrorCategory,
+            ErrorCode,
+            ErrorResponseBody,
+            ErrorResponseBodyError,
         ),
     ),
     tags(
-        (name = "Authentication", description = "Account Authentication")
+        (name = "Authentication", description = "Account Authentication"),
+        (name = "Sessions", description = "Signed-in Devices")
     )
 )]
 struct ApiDoc;
@@ -88,7 +137,7 @@ pub struct AuthenticateWithRecoveryResponse {
     request_body = AuthenticateWithRecoveryAuthkeyRequest,
     responses(
         (status = 200, description = "Authentication Challenge and Session", body=AuthenticateWithRecoveryResponse),
-        (status = 404, description = "Account not found")
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn authenticate_with_recovery(
@@ -141,7 +190,7 @@ pub struct AuthenticateWithHardwareResponse {
     request_body = AuthenticateWithHardwareRequest,
     responses(
         (status = 200, description = "Authentication Challenge and Session", body=AuthenticateWithHardwareResponse),
-        (status = 404, description = "Account not found")
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn authenticate_with_hardware(
@@ -201,7 +250,7 @@ pub struct AuthenticationResponse {
     request_body = AuthenticationRequest,
     responses(
         (status = 200, description = "Authentication Challenge and Session", body=AuthenticateWithHardwareResponse),
-        (status = 404, description = "Account not found")
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn authenticate(
@@ -283,18 +332,21 @@ pub struct GetTokensResponse {
     pub refresh_token: String,
 }
 
-#[instrument(fields(account_id), skip(user_pool_service))]
+#[instrument(fields(account_id), skip(account_service, user_pool_service, headers))]
 #[utoipa::path(
     post,
     path = "/api/authenticate/tokens",
     request_body = GetTokensRequest,
     responses(
         (status = 200, description = "Authentication Tokens", body=GetTokensResponse),
-        (status = 404, description = "Account not found")
+        (status = 401, description = "The session was revoked", body = ErrorResponseBody),
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn get_tokens(
+    State(account_service): State<AccountService>,
     State(user_pool_service): State<UserPoolService>,
+    headers: HeaderMap,
     Json(request): Json<GetTokensRequest>,
 ) -> Result<Json<GetTokensResponse>, ApiError> {
     let (challenge_present, refresh_token_present) =
@@ -310,6 +362,7 @@ pub async fn get_tokens(
         ));
     }
 
+    let refreshing = refresh_token_present;
     let tokens: AuthTokens = if let Some(refresh_token) = request.refresh_token {
         user_pool_service
             .refresh_access_token(refresh_token)
@@ -334,8 +387,208 @@ pub async fn get_tokens(
         ));
     };
 
+    let session = record_token_issuance(
+        &account_service,
+        &tokens.access_token,
+        device_info(&headers),
+    )
+    .await?;
+    // Refresh tokens outlive a revoked session, so refreshing must not hand out new access
+    // tokens for it
+    if refreshing && session.is_some_and(|session| session.is_revoked()) {
+        event!(
+            Level::INFO,
+            "Refusing to refresh tokens for revoked session"
+        );
+        return Err(ApiError::specific(
+            ErrorCode::SessionRevoked,
+            "Session has been revoked",
+        ));
+    }
+
     Ok(Json(GetTokensResponse {
         access_token: tokens.access_token,
         refresh_token: tokens.refresh_token,
     }))
 }
+
+/// Records token issuance against the session the tokens belong to. Unlike [`track_session`],
+/// this happens whether or not the session registry is enabled, so that `last_seen_at` is
+/// always a reliable sign of life (e.g. when deciding whether an inheritance claim may start).
+///
+/// [`track_session`]: crate::session::track_session
+async fn record_token_issuance(
+    account_service: &AccountService,
+    access_token: &str,
+    device: DeviceInfo,
+) -> Result<Option<DeviceSession>, ApiError> {
+    let Some(claims) = claims_from_verified_jwt(access_token) else {
+        return Ok(None);
+    };
+    let Ok(cognito_user) = CognitoUser::from_str(claims.username.as_ref()) else {
+        return Ok(None);
+    };
+    let session = account_service
+        .record_session_activity(RecordSessionActivityInput {
+            account_id: &cognito_user.get_account_id(),
+            session_id: &claims.origin_jti,
+            device,
+        })
+        .await?;
+    Ok(Some(session))
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct RequestNonceResponse {
+    pub nonce: String,
+}
+
+#[instrument(skip(nonce_issuer, claims))]
+#[utoipa::path(
+    post,
+    path = "/api/request-nonce",
+    responses(
+        (status = 200, description = "Nonce to include in the canonical message of a signed request", body=RequestNonceResponse),
+    ),
+)]
+pub async fn issue_request_nonce(
+    State(nonce_issuer): State<NonceIssuer>,
+    JwtClaims(claims): JwtClaims<AccessTokenClaims>,
+) -> Json<RequestNonceResponse> {
+    Json(RequestNonceResponse {
+        nonce: nonce_issuer.issue(&claims.origin_jti),
+    })
+}
+
+#[instrument(skip(user_pool_service))]
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "JSON Web Key Set that verifies access tokens issued by this server"),
+        (status = 404, description = "Access tokens are issued by an external identity provider", body = ErrorResponseBody)
+    ),
+)]
+pub async fn get_jwks(
+    State(user_pool_service): State<UserPoolService>,
+) -> Result<Json<JwkSet>, ApiError> {
+    user_pool_service.jwks().map(Json).ok_or_else(|| {
+        ApiError::GenericNotFound("Access tokens are not issued by this server".to_string())
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SessionResponse {
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_country: Option<String>,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "rfc3339")]
+    pub last_seen_at: OffsetDateTime,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+impl SessionResponse {
+    fn new(session: DeviceSession, current_session_id: &str) -> Self {
+        Self {
+            current: session.session_id == current_session_id,
+            session_id: session.session_id,
+            device_name: session.device_name,
+            platform: session.platform,
+            ip_country: session.ip_country,
+            created_at: session.created_at,
+            last_seen_at: session.last_seen_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ListSessionsResponse {
+    pub sessions: Vec<SessionResponse>,
+}
+
+#[instrument(fields(account_id), skip(account_service, claims))]
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/sessions",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    responses(
+        (status = 200, description = "Sessions that haven't been revoked, most recently seen first", body=ListSessionsResponse),
+    ),
+    tag = "Sessions",
+)]
+pub async fn list_sessions(
+    State(account_service): State<AccountService>,
+    Path(account_id): Path<AccountId>,
+    JwtClaims(claims): JwtClaims<AccessTokenClaims>,
+) -> Result<Json<ListSessionsResponse>, ApiError> {
+    let sessions = account_service
+        .fetch_sessions(FetchSessionsInput {
+            account_id: &account_id,
+        })
+        .await?
+        .into_iter()
+        .filter(|session| !session.is_revoked())
+        .map(|session| SessionResponse::new(session, &claims.origin_jti))
+        .collect();
+    Ok(Json(ListSessionsResponse { sessions }))
+}
+
+#[instrument(fields(account_id), skip(account_service))]
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{account_id}/sessions/{session_id}",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+        ("session_id" = String, Path, description = "SessionId"),
+    ),
+    responses(
+        (status = 200, description = "Session was revoked and its push notifications stopped"),
+        (status = 404, description = "Session not found", body = ErrorResponseBody),
+    ),
+    tag = "Sessions",
+)]
+pub async fn revoke_session(
+    State(account_service): State<AccountService>,
+    Path((account_id, session_id)): Path<(AccountId, String)>,
+) -> Result<(), ApiError> {
+    account_service
+        .revoke_session(RevokeSessionInput {
+            account_id: &account_id,
+            session_id: &session_id,
+        })
+        .await?;
+    Ok(())
+}
+
+#[instrument(fields(account_id), skip(account_service))]
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{account_id}/sessions",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    responses(
+        (status = 200, description = "Every session, including the caller's, was revoked"),
+    ),
+    tag = "Sessions",
+)]
+pub async fn revoke_all_sessions(
+    State(account_service): State<AccountService>,
+    Path(account_id): Path<AccountId>,
+) -> Result<(), ApiError> {
+    account_service
+        .revoke_all_sessions(RevokeAllSessionsInput {
+            account_id: &account_id,
+        })
+        .await?;
+    Ok(())
+}
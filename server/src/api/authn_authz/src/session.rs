@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use account::service::{RecordSessionActivityInput, Service as AccountService};
+use axum::extract::{Request, State};
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::Response;
+use errors::{ApiError, ErrorCode};
+use feature_flags::service::Service as FeatureFlagsService;
+use jwt_authorizer::JwtClaims;
+use tracing::{event, Level};
+use types::authn_authz::cognito::CognitoUser;
+use types::authn_authz::session::DeviceInfo;
+use types::authn_authz::AccessTokenClaims;
+
+use crate::flags::FLAG_SESSION_REGISTRY_ENABLED;
+
+pub const DEVICE_NAME_HEADER: &str = "X-Device-Name";
+pub const DEVICE_PLATFORM_HEADER: &str = "X-Device-Platform";
+pub const VIEWER_COUNTRY_HEADER: &str = "CloudFront-Viewer-Country";
+
+const MAX_DEVICE_HEADER_LEN: usize = 128;
+
+#[derive(Clone)]
+pub struct SessionTrackingState {
+    pub account_service: AccountService,
+    pub feature_flags: FeatureFlagsService,
+}
+
+/// Records each authenticated request against the caller's session and rejects requests from
+/// sessions that have been revoked. Sessions are keyed by the access token's `origin_jti`, so a
+/// revoked session stays revoked across token refreshes. Activity is throttled and best effort, so
+/// the session table is only written to every few minutes per session, and only a failure to read
+/// it rejects the request.
+pub async fn track_session(
+    State(state): State<SessionTrackingState>,
+    JwtClaims(claims): JwtClaims<AccessTokenClaims>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if !FLAG_SESSION_REGISTRY_ENABLED
+        .resolver(&state.feature_flags)
+        .resolve()
+    {
+        return Ok(next.run(request).await);
+    }
+
+    let account_id = CognitoUser::from_str(claims.username.as_ref())
+        .map_err(|_| ApiError::GenericUnauthorized("Unrecognized token username".to_string()))?
+        .get_account_id();
+    let session = state
+        .account_service
+        .record_session_activity(RecordSessionActivityInput {
+            account_id: &account_id,
+            session_id: &claims.origin_jti,
+            device: device_info(request.headers()),
+        })
+        .await
+        .map_err(|err| {
+            event!(Level::ERROR, "Could not fetch session: {err}");
+            ApiError::GenericInternalApplicationError("Could not fetch session".to_string())
+        })?;
+    if session.is_revoked() {
+        event!(Level::INFO, "Rejecting request from revoked session");
+        return Err(ApiError::specific(
+            ErrorCode::SessionRevoked,
+            "Session has been revoked",
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+pub(crate) fn device_info(headers: &HeaderMap) -> DeviceInfo {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty() && value.len() <= MAX_DEVICE_HEADER_LEN)
+            .map(String::from)
+    };
+    DeviceInfo {
+        device_name: header(DEVICE_NAME_HEADER),
+        platform: header(DEVICE_PLATFORM_HEADER),
+        ip_country: header(VIEWER_COUNTRY_HEADER),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+
+    use super::{device_info, DEVICE_NAME_HEADER, DEVICE_PLATFORM_HEADER, VIEWER_COUNTRY_HEADER};
+
+    #[test]
+    fn test_device_info_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(DEVICE_NAME_HEADER, HeaderValue::from_static(" Pixel 8 "));
+        headers.insert(DEVICE_PLATFORM_HEADER, HeaderValue::from_static(""));
+        headers.insert(VIEWER_COUNTRY_HEADER, HeaderValue::from_static("US"));
+
+        let device = device_info(&headers);
+        assert_eq!(device.device_name.as_deref(), Some("Pixel 8"));
+        assert_eq!(device.platform, None);
+        assert_eq!(device.ip_country.as_deref(), Some("US"));
+
+        headers.insert(
+            DEVICE_NAME_HEADER,
+            HeaderValue::from_str(&"x".repeat(129)).unwrap(),
+        );
+        assert_eq!(device_info(&headers).device_name, None);
+    }
+}
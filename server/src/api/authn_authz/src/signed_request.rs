@@ -0,0 +1,253 @@
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::{Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use feature_flags::service::Service as FeatureFlagsService;
+use hmac::{Hmac, Mac};
+use jwt_authorizer::JwtClaims;
+use rand::RngCore;
+use repository::request_nonce::Repository as RequestNonceRepository;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use time::{Duration, OffsetDateTime};
+use tracing::{event, Level};
+use types::authn_authz::request_nonce::UsedRequestNonce;
+use types::authn_authz::AccessTokenClaims;
+
+use crate::flags::FLAG_REQUIRE_REQUEST_SIGNATURES;
+
+pub const REQUEST_NONCE_HEADER: &str = "X-Request-Nonce";
+pub const APP_REQUEST_SIG_HEADER: &str = "X-App-Request-Signature";
+pub const HW_REQUEST_SIG_HEADER: &str = "X-Hw-Request-Signature";
+
+const REQUEST_SIGNATURE_DOMAIN: &str = "BitkeyRequestV1";
+const NONCE_TTL_SECS: u64 = 5 * 60;
+const MAX_SIGNED_BODY_BYTES: usize = 2 * 1024 * 1024;
+const TEST_NONCE_SECRET: &[u8] = &[0x5a; 32];
+
+#[derive(Deserialize)]
+pub struct Config {
+    pub request_signing: RequestSigningMode,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "lowercase", tag = "mode")]
+pub enum RequestSigningMode {
+    Test,
+    Environment,
+}
+
+impl Config {
+    pub fn to_nonce_issuer(&self) -> NonceIssuer {
+        match self.request_signing {
+            RequestSigningMode::Test => NonceIssuer::new(TEST_NONCE_SECRET.to_vec()),
+            RequestSigningMode::Environment => NonceIssuer::new(
+                env::var("REQUEST_NONCE_SECRET")
+                    .expect("REQUEST_NONCE_SECRET environment variable not set")
+                    .into_bytes(),
+            ),
+        }
+    }
+}
+
+/// Issues and checks the server nonces that bind a request signature to a short window.
+///
+/// Nonces are stateless (`{issued_at}.{salt}.{mac}`) so any instance can verify a nonce
+/// issued by another one. The MAC also covers the `origin_jti` of the session that asked for
+/// the nonce, so a nonce only verifies for requests from that session. Stopping a nonce from
+/// being used twice is up to [`digest_signed_request`].
+#[derive(Clone)]
+pub struct NonceIssuer {
+    secret: Vec<u8>,
+}
+
+impl NonceIssuer {
+    pub fn new(secret: Vec<u8>) -> Self {
+        Self { secret }
+    }
+
+    pub fn issue(&self, origin_jti: &str) -> String {
+        self.issue_at(origin_jti, now_secs())
+    }
+
+    pub fn verify(&self, nonce: &str, origin_jti: &str) -> bool {
+        self.verify_at(nonce, origin_jti, now_secs())
+    }
+
+    fn issue_at(&self, origin_jti: &str, issued_at: u64) -> String {
+        let mut salt = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let payload = format!("{issued_at}.{}", hex::encode(salt));
+        let mac = hex::encode(self.mac(&payload, origin_jti).finalize().into_bytes());
+        format!("{payload}.{mac}")
+    }
+
+    fn verify_at(&self, nonce: &str, origin_jti: &str, now: u64) -> bool {
+        let Some((payload, mac)) = nonce.rsplit_once('.') else {
+            return false;
+        };
+        let Some(Ok(issued_at)) = payload.split_once('.').map(|(ts, _)| ts.parse::<u64>()) else {
+            return false;
+        };
+        let Ok(mac) = hex::decode(mac) else {
+            return false;
+        };
+        self.mac(payload, origin_jti).verify_slice(&mac).is_ok()
+            && now.abs_diff(issued_at) <= NONCE_TTL_SECS
+    }
+
+    fn mac(&self, payload: &str, origin_jti: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any size");
+        mac.update(format!("{payload}.{origin_jti}").as_bytes());
+        mac
+    }
+}
+
+/// The message the app and hardware sign to authorize a single request. The signature
+/// headers carry an ECDSA signature over the SHA-256 of this string.
+pub fn canonical_request_message(
+    method: &Method,
+    path_and_query: &str,
+    body: &[u8],
+    nonce: &str,
+) -> String {
+    format!(
+        "{REQUEST_SIGNATURE_DOMAIN}\n{method}\n{path_and_query}\n{}\n{nonce}",
+        hex::encode(Sha256::digest(body))
+    )
+}
+
+/// Attached to each authenticated request by [`digest_signed_request`] and consumed by
+/// [`crate::key_claims::KeyClaims`].
+#[derive(Debug, Clone)]
+pub struct SignedRequestContext {
+    /// Canonical message for this request, present only when it carried a valid nonce
+    pub message: Option<String>,
+    /// Whether signatures over the access token still satisfy factor requirements
+    pub token_signatures_accepted: bool,
+}
+
+#[derive(Clone)]
+pub struct RequestSigningState {
+    pub nonces: NonceIssuer,
+    pub used_nonces: RequestNonceRepository,
+    pub feature_flags: FeatureFlagsService,
+}
+
+/// Builds the canonical message of requests that carry a valid nonce for the caller's session.
+/// Each nonce is recorded the first time it's used, and is ignored on any later request, so a
+/// signed request can't be replayed.
+pub async fn digest_signed_request(
+    State(state): State<RequestSigningState>,
+    JwtClaims(claims): JwtClaims<AccessTokenClaims>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let token_signatures_accepted = !FLAG_REQUIRE_REQUEST_SIGNATURES
+        .resolver(&state.feature_flags)
+        .resolve();
+    let nonce = request
+        .headers()
+        .get(REQUEST_NONCE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
+
+    let nonce = match nonce {
+        Some(nonce) if state.nonces.verify(&nonce, &claims.origin_jti) => {
+            let used_nonce = UsedRequestNonce {
+                nonce,
+                expiring_at: OffsetDateTime::now_utc() + Duration::seconds(NONCE_TTL_SECS as i64),
+            };
+            let unused = state
+                .used_nonces
+                .persist_if_unused(&used_nonce)
+                .await
+                .map_err(|err| {
+                    event!(Level::ERROR, "Could not record used request nonce: {err}");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            if !unused {
+                event!(Level::INFO, "Ignoring request nonce that was already used");
+            }
+            unused.then_some(used_nonce.nonce)
+        }
+        Some(_) => {
+            event!(Level::INFO, "Ignoring invalid or expired request nonce");
+            None
+        }
+        None => None,
+    };
+
+    let (mut request, message) = match nonce {
+        Some(nonce) => {
+            let (parts, body) = request.into_parts();
+            let body = to_bytes(body, MAX_SIGNED_BODY_BYTES)
+                .await
+                .map_err(|_| StatusCode::PAYLOAD_TOO_LARGE)?;
+            let path_and_query = parts
+                .uri
+                .path_and_query()
+                .map_or(parts.uri.path(), |path_and_query| path_and_query.as_str());
+            let message = canonical_request_message(&parts.method, path_and_query, &body, &nonce);
+            (Request::from_parts(parts, Body::from(body)), Some(message))
+        }
+        None => (request, None),
+    };
+
+    request.extensions_mut().insert(SignedRequestContext {
+        message,
+        token_signatures_accepted,
+    });
+    Ok(next.run(request).await)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is after the unix epoch")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NonceIssuer, NONCE_TTL_SECS};
+
+    const NOW: u64 = 1_700_000_000;
+    const ORIGIN_JTI: &str = "origin-jti";
+
+    #[test]
+    fn test_nonce_round_trip() {
+        let issuer = NonceIssuer::new(vec![1; 32]);
+        let nonce = issuer.issue_at(ORIGIN_JTI, NOW);
+        assert!(issuer.verify_at(&nonce, ORIGIN_JTI, NOW));
+        assert!(issuer.verify_at(&nonce, ORIGIN_JTI, NOW + NONCE_TTL_SECS));
+        assert!(!issuer.verify_at(&nonce, ORIGIN_JTI, NOW + NONCE_TTL_SECS + 1));
+    }
+
+    #[test]
+    fn test_nonce_from_other_secret_is_rejected() {
+        let nonce = NonceIssuer::new(vec![1; 32]).issue_at(ORIGIN_JTI, NOW);
+        assert!(!NonceIssuer::new(vec![2; 32]).verify_at(&nonce, ORIGIN_JTI, NOW));
+    }
+
+    #[test]
+    fn test_nonce_from_other_session_is_rejected() {
+        let issuer = NonceIssuer::new(vec![1; 32]);
+        let nonce = issuer.issue_at(ORIGIN_JTI, NOW);
+        assert!(!issuer.verify_at(&nonce, "other-origin-jti", NOW));
+    }
+
+    #[test]
+    fn test_tampered_nonce_is_rejected() {
+        let issuer = NonceIssuer::new(vec![1; 32]);
+        let nonce = issuer.issue_at(ORIGIN_JTI, NOW);
+        let (_, rest) = nonce.split_once('.').unwrap();
+        assert!(!issuer.verify_at(&format!("{}.{rest}", NOW + 60), ORIGIN_JTI, NOW + 60));
+        assert!(!issuer.verify_at("not-a-nonce", ORIGIN_JTI, NOW));
+    }
+}
@@ -50,6 +50,8 @@ mod tests {
     use tower::util::ServiceExt;
     use types::account::identifiers::AccountId;
     use types::authn_authz::AccessTokenClaims;
+    use userpool::native::keys::SigningKeys;
+    use userpool::native::NativeAuthConfig;
     use userpool::test_utils::TEST_JWT_SIGNING_SECRET;
 
     const TEST_APP_AUTH_PUBKEY: &str =
@@ -179,4 +181,60 @@ mod tests {
             .unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_native_authorizer() {
+        let issuer = "https://bitkey.example.com";
+        let signing_keys = SigningKeys::parse(&format!("key-1:{}", "07".repeat(32))).unwrap();
+        let authorizer = AuthorizerConfig::Native(NativeAuthConfig {
+            issuer: issuer.to_string(),
+            signing_keys: signing_keys.clone(),
+        })
+        .into_authorizer()
+        .layer()
+        .await
+        .unwrap();
+        let app = Router::new()
+            .route(
+                "/with/:account_id/in/path",
+                get(|| async { StatusCode::OK }),
+            )
+            .route_layer(middleware::from_fn(authorize_token_for_path))
+            .layer(authorizer);
+
+        let key = DecodingKey::from_secret(TEST_JWT_SIGNING_SECRET.as_ref());
+        let claims = decode::<AccessTokenClaims>(
+            &get_test_access_token(),
+            &key,
+            &jsonwebtoken::Validation::default(),
+        )
+        .unwrap()
+        .claims;
+        let native_token = signing_keys
+            .sign(&AccessTokenClaims {
+                iss: issuer.to_string(),
+                ..claims.clone()
+            })
+            .unwrap();
+        let foreign_issuer_token = signing_keys.sign(&claims).unwrap();
+
+        for (token, expected) in [
+            (native_token, StatusCode::OK),
+            (foreign_issuer_token, StatusCode::UNAUTHORIZED),
+            (get_test_access_token(), StatusCode::UNAUTHORIZED),
+        ] {
+            let resp = app
+                .to_owned()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/with/{}/in/path", TEST_USERNAME))
+                        .header("Authorization", format!("Bearer {}", token))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), expected);
+        }
+    }
 }
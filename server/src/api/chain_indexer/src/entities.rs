@@ -1,9 +1,16 @@
+use std::fmt;
+
 use bdk_utils::bdk::bitcoin::block::Version;
-use bdk_utils::bdk::bitcoin::{
-    blockdata::block::Bip34Error, Block as BdkBlock, BlockHash, Network,
-};
+use bdk_utils::bdk::bitcoin::{BlockHash, Network, Txid};
 use serde::{Deserialize, Serialize};
 use time::{serde::rfc3339, OffsetDateTime};
+use types::account::identifiers::AccountId;
+
+use crate::source::ChainBlock;
+
+const CURSOR_PREFIX: &str = "CURSOR#";
+const PAYMENT_NOTIFICATION_PREFIX: &str = "PAYMENT#";
+const OUTGOING_PAYMENT_PREFIX: &str = "OUTGOING#";
 
 #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
 pub struct Block {
@@ -18,15 +25,125 @@ pub struct Block {
 }
 
 impl Block {
-    pub fn from_bdk_block(bdk_block: &BdkBlock, network: Network) -> Result<Self, Bip34Error> {
-        Ok(Block {
-            block_hash: bdk_block.block_hash(),
-            prev_hash: bdk_block.header.prev_blockhash,
-            height: bdk_block.bip34_block_height()?,
-            version: bdk_block.header.version,
-            time: bdk_block.header.time,
+    pub fn from_chain_block(chain_block: &ChainBlock, network: Network) -> Self {
+        Block {
+            block_hash: chain_block.block_hash(),
+            prev_hash: chain_block.header.prev_blockhash,
+            height: chain_block.height,
+            version: chain_block.header.version,
+            time: chain_block.header.time,
             created_at: OffsetDateTime::now_utc(),
             network,
-        })
+        }
+    }
+}
+
+/// The last block whose payments have been processed for a network. Indexing resumes from here,
+/// so it's only moved forward once a block's notifications have been sent.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct ChainCursor {
+    pub partition_key: String,
+    pub network: Network,
+    pub block_hash: BlockHash,
+    pub height: u64,
+    #[serde(with = "rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl ChainCursor {
+    pub fn new(network: Network, block_hash: BlockHash, height: u64) -> Self {
+        Self {
+            partition_key: cursor_partition_key(network),
+            network,
+            block_hash,
+            height,
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentNotificationKind {
+    /// The transaction was seen in the mempool
+    Pending,
+    /// The transaction was included in a block
+    Confirmed,
+    /// The transaction reached the configured confirmation depth
+    Settled,
+    /// A transaction the account sent was included in a block
+    OutgoingConfirmed,
+}
+
+impl fmt::Display for PaymentNotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaymentNotificationKind::Pending => write!(f, "pending"),
+            PaymentNotificationKind::Confirmed => write!(f, "confirmed"),
+            PaymentNotificationKind::Settled => write!(f, "settled"),
+            PaymentNotificationKind::OutgoingConfirmed => write!(f, "outgoing_confirmed"),
+        }
+    }
+}
+
+/// Records that an account has been notified of a payment, so replaying blocks after a crash or
+/// a reorg doesn't notify it again.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct PaymentNotificationRecord {
+    pub partition_key: String,
+    pub account_id: AccountId,
+    pub txid: Txid,
+    pub kind: PaymentNotificationKind,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl PaymentNotificationRecord {
+    pub fn new(account_id: AccountId, txid: Txid, kind: PaymentNotificationKind) -> Self {
+        Self {
+            partition_key: payment_notification_partition_key(&account_id, &txid, kind),
+            account_id,
+            txid,
+            kind,
+            created_at: OffsetDateTime::now_utc(),
+        }
+    }
+}
+
+/// A transaction cosigned for an account, so its confirmation can be reported as an outgoing
+/// payment rather than as a payment to the account's change address.
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+pub struct OutgoingPaymentRecord {
+    pub partition_key: String,
+    pub account_id: AccountId,
+    pub txid: Txid,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl OutgoingPaymentRecord {
+    pub fn new(account_id: AccountId, txid: Txid) -> Self {
+        Self {
+            partition_key: outgoing_payment_partition_key(&txid),
+            account_id,
+            txid,
+            created_at: OffsetDateTime::now_utc(),
+        }
     }
 }
+
+pub(crate) fn cursor_partition_key(network: Network) -> String {
+    format!("{CURSOR_PREFIX}{network}")
+}
+
+pub(crate) fn payment_notification_partition_key(
+    account_id: &AccountId,
+    txid: &Txid,
+    kind: PaymentNotificationKind,
+) -> String {
+    format!("{PAYMENT_NOTIFICATION_PREFIX}{kind}#{txid}#{account_id}")
+}
+
+pub(crate) fn outgoing_payment_partition_key(txid: &Txid) -> String {
+    format!("{OUTGOING_PAYMENT_PREFIX}{txid}")
+}
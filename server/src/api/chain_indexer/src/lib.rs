@@ -4,6 +4,8 @@ use thiserror::Error;
 pub mod entities;
 pub mod repository;
 pub mod service;
+pub mod source;
+pub mod state_repository;
 
 #[derive(Error, Debug)]
 pub enum ChainIndexerError {
@@ -19,4 +21,12 @@ pub enum ChainIndexerError {
     BlockHashParseError(#[from] bdk_utils::bdk::bitcoin::hashes::hex::Error),
     #[error("BIP34 error: {0}")]
     Bip34Error(#[from] bdk_utils::bdk::bitcoin::blockdata::block::Bip34Error),
+    #[error("Chain source has no {0}")]
+    ChainSourceNotFound(String),
+    #[error("Electrum client error: {0}")]
+    ElectrumClientError(#[from] bdk_utils::bdk::electrum_client::Error),
+    #[error("Chain source task failed: {0}")]
+    BlockingTaskError(#[from] tokio::task::JoinError),
+    #[error("bitcoind RPC error: {0}")]
+    BitcoindRpcError(String),
 }
@@ -31,8 +31,9 @@ impl Repository {
             .transpose()
     }
 
+    /// Returns the highest block stored for the network.
     #[instrument(skip(self))]
-    pub(crate) async fn fetch_init_block(
+    pub(crate) async fn fetch_latest_block(
         &self,
         network: Network,
     ) -> Result<Option<Block>, DatabaseError> {
@@ -47,7 +48,7 @@ impl Repository {
             .key_condition_expression(format!("{NETWORK_HEIGHT_PARTITION_KEY} = :val"))
             .expression_attribute_values(":val", try_to_attribute_val(network, database_object)?)
             .limit(1)
-            .scan_index_forward(true)
+            .scan_index_forward(false)
             .send()
             .await
             .map_err(|err| {
@@ -14,23 +14,28 @@ impl Repository {
         let database_object = self.get_database_object();
         let item = try_to_item(block, database_object)?;
 
-        self.connection
+        let result = self
+            .connection
             .client
             .put_item()
             .table_name(table_name)
             .set_item(Some(item))
             .condition_expression("attribute_not_exists(block_hash)")
             .send()
-            .await
-            .map_err(|err| {
-                let service_err = err.into_service_error();
+            .await;
+
+        if let Err(err) = result {
+            let service_err = err.into_service_error();
+            // Blocks are replayed after a crash or reorg, so they may already be stored.
+            if !service_err.is_conditional_check_failed_exception() {
                 event!(
                     Level::ERROR,
-                    "Could not persist account: {service_err:?} with message: {:?}",
+                    "Could not persist block: {service_err:?} with message: {:?}",
                     service_err.message()
                 );
-                DatabaseError::PersistenceError(database_object)
-            })?;
+                return Err(DatabaseError::PersistenceError(database_object));
+            }
+        }
 
         Ok(())
     }
@@ -1,88 +1,171 @@
 use super::Service;
-use crate::ChainIndexerError;
-use bdk_utils::bdk::bitcoin::{consensus::encode::deserialize, Block as BdkBlock, BlockHash};
+use crate::{entities::ChainCursor, source::ChainBlock, ChainIndexerError};
+use bdk_utils::bdk::bitcoin::Transaction;
 use tracing::{event, Level};
 
+// Bounds how many blocks are returned by a single sync, e.g. after a long outage. Any further
+// blocks are picked up from the cursor by the next sync.
+const MAX_BLOCKS_PER_SYNC: usize = 24;
+
 impl Service {
-    pub async fn get_new_blocks(&self) -> Result<Vec<BdkBlock>, ChainIndexerError> {
+    /// Returns the blocks between the cursor and the source's tip, oldest first. If the tip is
+    /// on a different branch than the cursor, the blocks are those since the fork point, so the
+    /// new branch is replayed. At most `MAX_BLOCKS_PER_SYNC` blocks are returned, starting from
+    /// the cursor, when the tip is further ahead than that.
+    pub async fn get_new_blocks(&self) -> Result<Vec<ChainBlock>, ChainIndexerError> {
+        let network = self.settings.network;
         event!(
             Level::INFO,
-            "Getting new blocks for network {} using base url {}",
-            self.settings.network,
-            self.settings.base_url,
+            "Getting new blocks for network {network} using the {:?} backend",
+            self.settings.backend,
         );
-        let tip_hash = self.get_tip_hash().await?;
+        let tip_hash = self.source.tip_hash().await?;
         event!(Level::INFO, "Retrieved tip hash {tip_hash} from network");
-        let mut new_blocks = Vec::new();
 
-        if let Some(init_block) = self.repo.fetch_init_block(self.settings.network).await? {
-            let mut current_hash = tip_hash;
-            loop {
-                // We've already seen the block, so we've found a common parent with the tip.
-                if self.repo.fetch(current_hash).await?.is_some() {
-                    // TODO: to detect re-orgs, we should query the DB for any blocks with a
-                    // prev_hash that is equal to the current_hash.
-                    break;
-                }
+        let Some(cursor) = self.fetch_cursor().await? else {
+            event!(Level::INFO, "No cursor found for network {network}");
+            let block = self.source.block(&tip_hash).await?;
+            event!(
+                Level::INFO,
+                "Retrieved block {tip_hash} from network and starting the cursor from it."
+            );
+            return Ok(vec![block]);
+        };
 
-                let new_block = self.get_block(&current_hash).await?;
-                event!(Level::INFO, "Retrieved block {current_hash} from network");
-                let init_block_height = init_block.height;
-                let new_block_height = new_block.bip34_block_height()?;
-                if new_block_height <= init_block_height {
-                    // Any blocks below the init block are stale.
+        let mut new_blocks: Vec<ChainBlock> = Vec::new();
+        let mut current_hash = tip_hash;
+        loop {
+            if current_hash == cursor.block_hash {
+                break;
+            }
+            // We've already processed the block, so we've found a common parent with the tip.
+            if let Some(known_block) = self.repo.fetch(current_hash).await? {
+                if known_block.height < cursor.height {
                     event!(
                         Level::WARN,
-                        "Stale block detected! The init block has a height of \
-                        {init_block_height}, but block {current_hash} has a height of \
-                        {new_block_height}.",
+                        "Reorg detected! Replaying {} blocks from fork point {current_hash} at \
+                        height {}, the cursor was at height {}.",
+                        new_blocks.len(),
+                        known_block.height,
+                        cursor.height,
                     );
-                    break;
                 }
-                current_hash = new_block.header.prev_blockhash;
-                new_blocks.push(new_block);
+                break;
             }
-        } else {
-            event!(Level::INFO, "No blocks found in database");
-            let block = self.get_block(&tip_hash).await?;
-            event!(
-                Level::INFO,
-                "Retrieved block {tip_hash} from network and setting it as the init block."
-            );
-            new_blocks.push(block);
+            if new_blocks.len() >= MAX_BLOCKS_PER_SYNC {
+                // The fork point is further back than a reorg we'd walk back from the tip.
+                let tip_height = new_blocks[0].height;
+                return self.get_blocks_after_cursor(&cursor, tip_height).await;
+            }
+
+            let new_block = self.source.block(&current_hash).await?;
+            event!(Level::INFO, "Retrieved block {current_hash} from network");
+            let new_block_height = new_block.height;
+            if new_blocks.is_empty() && new_block_height <= cursor.height {
+                // The source is lagging behind or serving a stale branch, so wait for it to
+                // build past the cursor.
+                event!(
+                    Level::WARN,
+                    "Stale block detected! The cursor has a height of {}, but tip \
+                    {current_hash} has a height of {new_block_height}.",
+                    cursor.height,
+                );
+                return Ok(Vec::new());
+            }
+            if new_blocks.is_empty()
+                && new_block_height > cursor.height + MAX_BLOCKS_PER_SYNC as u64
+            {
+                event!(
+                    Level::INFO,
+                    "Tip {current_hash} at height {new_block_height} is more than \
+                    {MAX_BLOCKS_PER_SYNC} blocks ahead of the cursor at height {}, catching up \
+                    from the cursor.",
+                    cursor.height,
+                );
+                return self
+                    .get_blocks_after_cursor(&cursor, new_block_height)
+                    .await;
+            }
+            current_hash = new_block.header.prev_blockhash;
+            new_blocks.push(new_block);
         }
 
         new_blocks.reverse();
         Ok(new_blocks)
     }
 
-    pub(crate) async fn get_block(
+    /// Returns up to `MAX_BLOCKS_PER_SYNC` blocks of the source's active chain, oldest first,
+    /// following the latest block we've processed that's still on it.
+    async fn get_blocks_after_cursor(
         &self,
-        block_hash: &BlockHash,
-    ) -> Result<BdkBlock, ChainIndexerError> {
-        Ok(deserialize(
-            &self
-                .http_client
-                .get(&format!(
-                    "{}/block/{block_hash}/raw",
-                    self.settings.base_url
-                ))
-                .send()
-                .await?
-                .bytes()
-                .await
-                .map(|bytes| bytes.to_vec())?,
-        )?)
+        cursor: &ChainCursor,
+        tip_height: u64,
+    ) -> Result<Vec<ChainBlock>, ChainIndexerError> {
+        let mut fork_height = cursor.height;
+        loop {
+            let block_hash = self.source.block_hash_at_height(fork_height).await?;
+            if block_hash == cursor.block_hash || self.repo.fetch(block_hash).await?.is_some() {
+                break;
+            }
+            if fork_height == 0 || cursor.height - fork_height >= MAX_BLOCKS_PER_SYNC as u64 {
+                event!(
+                    Level::ERROR,
+                    "No known block within {MAX_BLOCKS_PER_SYNC} blocks of the cursor at \
+                    height {}, continuing from the cursor.",
+                    cursor.height,
+                );
+                fork_height = cursor.height;
+                break;
+            }
+            fork_height -= 1;
+        }
+        if fork_height < cursor.height {
+            event!(
+                Level::WARN,
+                "Reorg detected! Replaying blocks from fork point at height {fork_height}, the \
+                cursor was at height {}.",
+                cursor.height,
+            );
+        }
+
+        let last_height = tip_height.min(fork_height + MAX_BLOCKS_PER_SYNC as u64);
+        let mut new_blocks = Vec::new();
+        for height in fork_height + 1..=last_height {
+            let block_hash = self.source.block_hash_at_height(height).await?;
+            new_blocks.push(self.source.block(&block_hash).await?);
+            event!(Level::INFO, "Retrieved block {block_hash} from network");
+        }
+        Ok(new_blocks)
     }
 
-    pub(crate) async fn get_tip_hash(&self) -> Result<BlockHash, ChainIndexerError> {
+    async fn fetch_cursor(&self) -> Result<Option<ChainCursor>, ChainIndexerError> {
+        let network = self.settings.network;
+        if let Some(cursor) = self.state_repo.fetch_cursor(network).await? {
+            return Ok(Some(cursor));
+        }
+
+        // Blocks stored before the cursor existed were processed when they were stored.
         Ok(self
-            .http_client
-            .get(&format!("{}/blocks/tip/hash", self.settings.base_url))
-            .send()
+            .repo
+            .fetch_latest_block(network)
             .await?
-            .text()
-            .await?
-            .parse()?)
+            .map(|block| ChainCursor::new(network, block.block_hash, block.height)))
+    }
+
+    /// Returns the transactions that reach the configured confirmation depth with `block`, i.e.
+    /// those in the block `depth - 1` below it on the source's active chain.
+    pub async fn get_settled_transactions(
+        &self,
+        block: &ChainBlock,
+    ) -> Result<Vec<Transaction>, ChainIndexerError> {
+        let Some(height) = self
+            .confirmation_depth()
+            .and_then(|depth| (block.height + 1).checked_sub(depth))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let block_hash = self.source.block_hash_at_height(height).await?;
+        Ok(self.source.block(&block_hash).await?.txdata)
     }
 }
@@ -0,0 +1,52 @@
+use super::Service;
+use crate::ChainIndexerError;
+use bdk_utils::bdk::bitcoin::Transaction;
+use tracing::{event, Level};
+
+// Bounds the number of transactions fetched in a single sync while the mempool is congested.
+const MAX_MEMPOOL_TRANSACTIONS_PER_SYNC: usize = 500;
+
+impl Service {
+    /// Returns mempool transactions that haven't been returned before. Always empty unless
+    /// mempool indexing is enabled.
+    pub async fn get_new_mempool_transactions(
+        &self,
+    ) -> Result<Vec<Transaction>, ChainIndexerError> {
+        if !self.settings.mempool_enabled {
+            return Ok(Vec::new());
+        }
+
+        let txids = self.source.mempool_txids().await?;
+        let unseen_txids = {
+            let mut seen_txids = self.seen_mempool_txids.lock().unwrap();
+            seen_txids.retain(|txid| txids.contains(txid));
+            txids
+                .into_iter()
+                .filter(|txid| !seen_txids.contains(txid))
+                .take(MAX_MEMPOOL_TRANSACTIONS_PER_SYNC)
+                .collect::<Vec<_>>()
+        };
+
+        let mut transactions = Vec::with_capacity(unseen_txids.len());
+        for txid in unseen_txids {
+            match self.source.transaction(&txid).await {
+                Ok(transaction) => {
+                    self.seen_mempool_txids.lock().unwrap().insert(txid);
+                    transactions.push(transaction);
+                }
+                // The transaction may have been mined or evicted since the txids were listed.
+                Err(e) => event!(
+                    Level::WARN,
+                    "Unable to retrieve mempool transaction {txid}: {e}"
+                ),
+            }
+        }
+        event!(
+            Level::INFO,
+            "Retrieved {} new mempool transactions",
+            transactions.len()
+        );
+
+        Ok(transactions)
+    }
+}
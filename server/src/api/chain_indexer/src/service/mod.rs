@@ -1,12 +1,34 @@
-use crate::repository::Repository;
-use bdk_utils::bdk::bitcoin::Network;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    repository::Repository,
+    source::{
+        bitcoind::{BitcoindAuth, BitcoindSource},
+        electrum::ElectrumSource,
+        esplora::EsploraSource,
+        ChainBackend, ChainSource,
+    },
+    state_repository::StateRepository,
+    ChainIndexerError,
+};
+use bdk_utils::{
+    bdk::bitcoin::{address::NetworkUnchecked, Address, Network, ScriptBuf, Txid},
+    flags::{
+        DEFAULT_MAINNET_ELECTRUM_RPC_URI, DEFAULT_SIGNET_ELECTRUM_RPC_URI,
+        DEFAULT_TESTNET_ELECTRUM_RPC_URI,
+    },
+};
 use config::{Config, ConfigError, Environment};
-use reqwest::Client;
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::Deserialize;
+use time::OffsetDateTime;
 
 mod fetch_blockchain_data;
+mod fetch_mempool_data;
+mod payment_notifications;
 mod update_blockchain_data;
 
 const MEMPOOL_SPACE_SIGNET_URL: &str = "https://bitkey.mempool.space/signet/api";
@@ -14,48 +36,180 @@ const MEMPOOL_SPACE_SIGNET_URL: &str = "https://bitkey.mempool.space/signet/api"
 #[derive(Clone)]
 pub struct Service {
     repo: Repository,
-    http_client: ClientWithMiddleware,
+    state_repo: StateRepository,
+    source: Arc<dyn ChainSource>,
     settings: Settings,
+    // Mempool transactions already returned by this instance, pruned as they leave the mempool.
+    seen_mempool_txids: Arc<Mutex<HashSet<Txid>>>,
+    // When the watchlist was last passed to the source, so later syncs only add newer addresses.
+    watchlist_synced_at: Arc<Mutex<Option<OffsetDateTime>>>,
 }
 
 #[derive(Clone, Deserialize)]
 pub struct Settings {
-    base_url: String,
+    backend: ChainBackend,
     network: Network,
+    mempool_enabled: bool,
+    // Payments are also notified once they reach this many confirmations, if set above 1
+    confirmation_depth: Option<u64>,
+    // Esplora
+    base_url: String,
+    // Electrum, defaulting to our server for the network
+    electrum_url: Option<String>,
+    // bitcoind, defaulting to the network's RPC port on localhost. Authenticates with the user
+    // and password if both are set, otherwise with the cookie file.
+    bitcoind_rpc_url: Option<String>,
+    bitcoind_rpc_user: Option<String>,
+    bitcoind_rpc_password: Option<String>,
+    bitcoind_rpc_cookie_file: Option<String>,
+    bitcoind_zmq_url: Option<String>,
 }
 
 impl Settings {
     pub fn new() -> Result<Self, ConfigError> {
         Config::builder()
+            .set_default("backend", "esplora")?
             .set_default("base_url", MEMPOOL_SPACE_SIGNET_URL)?
             .set_default("network", Network::Signet.to_string())?
+            .set_default("mempool_enabled", false)?
             .add_source(Environment::with_prefix("CHAIN_INDEXER"))
             .build()?
             .try_deserialize()
     }
+
+    fn chain_source(&self) -> Arc<dyn ChainSource> {
+        match self.backend {
+            ChainBackend::Esplora => Arc::new(EsploraSource::new(self.base_url.clone())),
+            ChainBackend::Electrum => {
+                let url = self.electrum_url.clone().unwrap_or_else(|| {
+                    match self.network {
+                        Network::Bitcoin => DEFAULT_MAINNET_ELECTRUM_RPC_URI,
+                        Network::Testnet => DEFAULT_TESTNET_ELECTRUM_RPC_URI,
+                        Network::Signet => DEFAULT_SIGNET_ELECTRUM_RPC_URI,
+                        _ => panic!(
+                            "CHAIN_INDEXER_ELECTRUM_URL must be set for {}",
+                            self.network
+                        ),
+                    }
+                    .to_string()
+                });
+                Arc::new(ElectrumSource::new(url))
+            }
+            ChainBackend::Bitcoind => {
+                let rpc_url = self.bitcoind_rpc_url.clone().unwrap_or_else(|| {
+                    let port = match self.network {
+                        Network::Bitcoin => 8332,
+                        Network::Testnet => 18332,
+                        Network::Signet => 38332,
+                        _ => 18443,
+                    };
+                    format!("http://127.0.0.1:{port}")
+                });
+                let auth = match (
+                    &self.bitcoind_rpc_user,
+                    &self.bitcoind_rpc_password,
+                    &self.bitcoind_rpc_cookie_file,
+                ) {
+                    (Some(user), Some(password), _) => BitcoindAuth::UserPassword {
+                        user: user.clone(),
+                        password: password.clone(),
+                    },
+                    (_, _, Some(cookie_file)) => BitcoindAuth::CookieFile(cookie_file.clone()),
+                    _ => panic!(
+                        "CHAIN_INDEXER_BITCOIND_RPC_USER and CHAIN_INDEXER_BITCOIND_RPC_PASSWORD, \
+                        or CHAIN_INDEXER_BITCOIND_RPC_COOKIE_FILE, must be set for bitcoind"
+                    ),
+                };
+                Arc::new(BitcoindSource::new(
+                    rpc_url,
+                    auth,
+                    self.bitcoind_zmq_url.clone(),
+                ))
+            }
+        }
+    }
 }
 
 impl Service {
-    pub fn new(repo: Repository) -> Self {
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(5);
-        let http_client = ClientBuilder::new(Client::new())
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build();
+    pub fn new(repo: Repository, state_repo: StateRepository) -> Self {
         let settings = Settings::new().unwrap();
+        let source = settings.chain_source();
 
         Self {
             repo,
-            http_client,
+            state_repo,
+            source,
             settings,
+            seen_mempool_txids: Arc::new(Mutex::new(HashSet::new())),
+            watchlist_synced_at: Arc::new(Mutex::new(None)),
         }
     }
 
     pub fn set_mock_server(mut self, base_url: String) -> Self {
+        self.source = Arc::new(EsploraSource::new(base_url.clone()));
+        self.settings.backend = ChainBackend::Esplora;
         self.settings.base_url = base_url;
         self
     }
 
+    pub fn set_chain_source(mut self, source: Arc<dyn ChainSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn set_mempool_enabled(mut self, mempool_enabled: bool) -> Self {
+        self.settings.mempool_enabled = mempool_enabled;
+        self
+    }
+
+    pub fn set_confirmation_depth(mut self, confirmation_depth: Option<u64>) -> Self {
+        self.settings.confirmation_depth = confirmation_depth;
+        self
+    }
+
+    pub fn set_network(mut self, network: Network) -> Self {
+        self.settings.network = network;
+        self
+    }
+
     pub fn network(&self) -> Network {
         self.settings.network
     }
+
+    /// The depth at which payments are notified as settled, if enabled.
+    pub fn confirmation_depth(&self) -> Option<u64> {
+        self.settings.confirmation_depth.filter(|depth| *depth > 1)
+    }
+
+    /// Whether the source needs to be told which addresses to index, via `watch_addresses`.
+    pub fn requires_watchlist(&self) -> bool {
+        self.source.requires_watchlist()
+    }
+
+    /// When the watchlist was last synced with `watch_addresses`, or `None` if it never has been.
+    pub fn watchlist_synced_at(&self) -> Option<OffsetDateTime> {
+        *self.watchlist_synced_at.lock().unwrap()
+    }
+
+    /// Adds addresses for the source to index, recording the watchlist as synced as of
+    /// `synced_at`. Addresses for other networks are ignored.
+    pub async fn watch_addresses(
+        &self,
+        addresses: &[Address<NetworkUnchecked>],
+        synced_at: OffsetDateTime,
+    ) -> Result<(), ChainIndexerError> {
+        let scripts: Vec<ScriptBuf> = addresses
+            .iter()
+            .filter_map(|address| address.clone().require_network(self.settings.network).ok())
+            .map(|address| address.script_pubkey())
+            .collect();
+        self.source.watch_scripts(&scripts).await?;
+        *self.watchlist_synced_at.lock().unwrap() = Some(synced_at);
+        Ok(())
+    }
+
+    /// Waits until the source reports a new block, or for up to `timeout`.
+    pub async fn wait_for_new_block(&self, timeout: Duration) {
+        self.source.wait_for_new_block(timeout).await
+    }
 }
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use super::Service;
+use crate::{
+    entities::{
+        payment_notification_partition_key, OutgoingPaymentRecord, PaymentNotificationKind,
+        PaymentNotificationRecord,
+    },
+    ChainIndexerError,
+};
+use bdk_utils::bdk::bitcoin::Txid;
+use types::account::identifiers::AccountId;
+
+impl Service {
+    /// Records that the account is being notified of the payment, returning false if it already
+    /// was.
+    pub async fn claim_payment_notification(
+        &self,
+        account_id: &AccountId,
+        txid: &Txid,
+        kind: PaymentNotificationKind,
+    ) -> Result<bool, ChainIndexerError> {
+        Ok(self
+            .state_repo
+            .create_payment_notification(&PaymentNotificationRecord::new(
+                account_id.clone(),
+                *txid,
+                kind,
+            ))
+            .await?)
+    }
+
+    /// Releases a claim whose notification couldn't be sent, so it's retried on the next sync.
+    pub async fn release_payment_notification(
+        &self,
+        account_id: &AccountId,
+        txid: &Txid,
+        kind: PaymentNotificationKind,
+    ) -> Result<(), ChainIndexerError> {
+        Ok(self
+            .state_repo
+            .delete_payment_notification(&payment_notification_partition_key(
+                account_id, txid, kind,
+            ))
+            .await?)
+    }
+
+    /// Records a transaction cosigned for the account, so it's notified as an outgoing payment
+    /// once confirmed.
+    pub async fn track_outgoing_payment(
+        &self,
+        account_id: &AccountId,
+        txid: &Txid,
+    ) -> Result<(), ChainIndexerError> {
+        Ok(self
+            .state_repo
+            .persist_outgoing_payment(&OutgoingPaymentRecord::new(account_id.clone(), *txid))
+            .await?)
+    }
+
+    /// Returns the sending account of each of the transactions that were cosigned for one.
+    pub async fn get_outgoing_payments(
+        &self,
+        txids: &[Txid],
+    ) -> Result<HashMap<Txid, AccountId>, ChainIndexerError> {
+        Ok(self
+            .state_repo
+            .fetch_outgoing_payments(txids)
+            .await?
+            .into_iter()
+            .map(|record| (record.txid, record.account_id))
+            .collect())
+    }
+}
@@ -1,12 +1,25 @@
 use super::Service;
-use crate::{entities::Block, ChainIndexerError};
-use bdk_utils::bdk::bitcoin::Block as BdkBlock;
+use crate::{
+    entities::{Block, ChainCursor},
+    source::ChainBlock,
+    ChainIndexerError,
+};
 
 impl Service {
-    pub async fn add_block(&self, block: &BdkBlock) -> Result<(), ChainIndexerError> {
-        self.repo
-            .persist(&Block::from_bdk_block(block, self.settings.network)?)
+    /// Marks the block as processed. This should only be called once its payments have been
+    /// handled, as indexing resumes after the cursor.
+    pub async fn advance_cursor(&self, block: &ChainBlock) -> Result<(), ChainIndexerError> {
+        let block = Block::from_chain_block(block, self.settings.network);
+        // The cursor is written first, as a stored block is treated as processed when walking
+        // back from the tip.
+        self.state_repo
+            .persist_cursor(&ChainCursor::new(
+                block.network,
+                block.block_hash,
+                block.height,
+            ))
             .await?;
+        self.repo.persist(&block).await?;
 
         Ok(())
     }
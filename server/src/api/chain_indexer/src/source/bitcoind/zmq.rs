@@ -0,0 +1,189 @@
+//! A minimal ZMTP 3.0 SUB socket, enough to receive bitcoind's `hashblock` notifications without
+//! linking libzmq.
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    sync::Notify,
+};
+use tracing::{event, Level};
+
+const TOPIC: &[u8] = b"hashblock";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const FLAG_MORE: u8 = 0x01;
+const FLAG_LONG: u8 = 0x02;
+const FLAG_COMMAND: u8 = 0x04;
+
+/// Spawns a task that stays subscribed to `hashblock` on `url` (e.g. `tcp://bitcoind:28332`),
+/// reconnecting on failure, and notifies the returned handle for each new block.
+pub(super) fn spawn_block_subscriber(url: String) -> Arc<Notify> {
+    let notify = Arc::new(Notify::new());
+    let task_notify = notify.clone();
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = subscribe(&url, &task_notify).await {
+                event!(Level::WARN, "ZMQ subscription to {url} failed: {e}");
+            }
+            tokio::time::sleep(RECONNECT_DELAY).await;
+        }
+    });
+    notify
+}
+
+async fn subscribe(url: &str, notify: &Notify) -> std::io::Result<()> {
+    let mut stream = TcpStream::connect(url.trim_start_matches("tcp://")).await?;
+    handshake(&mut stream).await?;
+
+    let mut subscription = vec![0x01];
+    subscription.extend_from_slice(TOPIC);
+    write_frame(&mut stream, 0, &subscription).await?;
+    event!(
+        Level::INFO,
+        "Subscribed to ZMQ block notifications on {url}"
+    );
+
+    loop {
+        let message = read_message(&mut stream).await?;
+        if message.first().map(Vec::as_slice) == Some(TOPIC) {
+            notify.notify_one();
+        }
+    }
+}
+
+async fn handshake(stream: &mut TcpStream) -> std::io::Result<()> {
+    // Signature, version 3.0, NULL mechanism, as-server false and filler.
+    let mut greeting = [0u8; 64];
+    greeting[0] = 0xff;
+    greeting[9] = 0x7f;
+    greeting[10] = 3;
+    greeting[12..16].copy_from_slice(b"NULL");
+    stream.write_all(&greeting).await?;
+
+    let mut peer_greeting = [0u8; 64];
+    stream.read_exact(&mut peer_greeting).await?;
+    if peer_greeting[0] != 0xff || peer_greeting[9] != 0x7f || peer_greeting[10] < 3 {
+        return Err(invalid_data("unsupported ZMTP greeting"));
+    }
+
+    let mut ready = vec![5];
+    ready.extend_from_slice(b"READY");
+    ready.push(11);
+    ready.extend_from_slice(b"Socket-Type");
+    ready.extend_from_slice(&3u32.to_be_bytes());
+    ready.extend_from_slice(b"SUB");
+    write_frame(stream, FLAG_COMMAND, &ready).await?;
+
+    let (flags, body) = read_frame(stream).await?;
+    if flags & FLAG_COMMAND == 0 || !body.starts_with(b"\x05READY") {
+        return Err(invalid_data("expected READY command"));
+    }
+    Ok(())
+}
+
+async fn write_frame(stream: &mut TcpStream, flags: u8, body: &[u8]) -> std::io::Result<()> {
+    let mut frame = Vec::with_capacity(body.len() + 9);
+    if body.len() > u8::MAX as usize {
+        frame.push(flags | FLAG_LONG);
+        frame.extend_from_slice(&(body.len() as u64).to_be_bytes());
+    } else {
+        frame.push(flags);
+        frame.push(body.len() as u8);
+    }
+    frame.extend_from_slice(body);
+    stream.write_all(&frame).await
+}
+
+async fn read_frame(stream: &mut TcpStream) -> std::io::Result<(u8, Vec<u8>)> {
+    let flags = stream.read_u8().await?;
+    let size = if flags & FLAG_LONG != 0 {
+        stream.read_u64().await? as usize
+    } else {
+        stream.read_u8().await? as usize
+    };
+    let mut body = vec![0u8; size];
+    stream.read_exact(&mut body).await?;
+    Ok((flags, body))
+}
+
+/// Reads the parts of the next message, skipping any commands in between.
+async fn read_message(stream: &mut TcpStream) -> std::io::Result<Vec<Vec<u8>>> {
+    let mut parts = Vec::new();
+    loop {
+        let (flags, body) = read_frame(stream).await?;
+        if flags & FLAG_COMMAND != 0 {
+            continue;
+        }
+        parts.push(body);
+        if flags & FLAG_MORE == 0 {
+            return Ok(parts);
+        }
+    }
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_notifies_on_hashblock() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("tcp://{}", listener.local_addr().unwrap());
+        let notify = spawn_block_subscriber(url);
+
+        // Act as bitcoind's PUB socket
+        let (mut peer, _) = listener.accept().await.unwrap();
+        let mut greeting = [0u8; 64];
+        peer.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(&greeting[12..16], b"NULL");
+        let mut pub_greeting = [0u8; 64];
+        pub_greeting[0] = 0xff;
+        pub_greeting[9] = 0x7f;
+        pub_greeting[10] = 3;
+        pub_greeting[11] = 1;
+        pub_greeting[12..16].copy_from_slice(b"NULL");
+        peer.write_all(&pub_greeting).await.unwrap();
+
+        let (flags, ready) = read_frame(&mut peer).await.unwrap();
+        assert_eq!(flags, FLAG_COMMAND);
+        assert!(ready.ends_with(b"Socket-Type\x00\x00\x00\x03SUB"));
+        let mut pub_ready = vec![5];
+        pub_ready.extend_from_slice(b"READY");
+        pub_ready.push(11);
+        pub_ready.extend_from_slice(b"Socket-Type");
+        pub_ready.extend_from_slice(&3u32.to_be_bytes());
+        pub_ready.extend_from_slice(b"PUB");
+        write_frame(&mut peer, FLAG_COMMAND, &pub_ready)
+            .await
+            .unwrap();
+
+        let (_, subscription) = read_frame(&mut peer).await.unwrap();
+        assert_eq!(subscription, b"\x01hashblock");
+
+        // Other topics are ignored
+        write_frame(&mut peer, FLAG_MORE, b"rawtx").await.unwrap();
+        write_frame(&mut peer, 0, &[0u8; 300]).await.unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), notify.notified())
+                .await
+                .is_err()
+        );
+
+        write_frame(&mut peer, FLAG_MORE, TOPIC).await.unwrap();
+        write_frame(&mut peer, FLAG_MORE, &[0u8; 32]).await.unwrap();
+        write_frame(&mut peer, 0, &1u32.to_le_bytes())
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), notify.notified())
+            .await
+            .unwrap();
+    }
+}
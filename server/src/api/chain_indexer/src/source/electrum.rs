@@ -0,0 +1,246 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use bdk_utils::bdk::{
+    bitcoin::{BlockHash, ScriptBuf, Transaction, Txid},
+    electrum_client::{Client, ConfigBuilder, ElectrumApi, Error as ElectrumError},
+};
+use tracing::{event, Level};
+
+use super::{ChainBlock, ChainSource};
+use crate::ChainIndexerError;
+
+/// Reads chain data from an Electrum server. Rather than downloading whole blocks, it subscribes
+/// to the watched scripts and only fetches the transactions in their history.
+pub struct ElectrumSource {
+    url: String,
+    state: Arc<Mutex<ElectrumState>>,
+}
+
+#[derive(Default)]
+struct ElectrumState {
+    // Connected lazily, and dropped on error so the next call reconnects and resubscribes.
+    client: Option<Client>,
+    // Electrum looks blocks up by height, so we track the heights of the blocks we've been told
+    // about while walking back from the tip.
+    heights: HashMap<BlockHash, u64>,
+    // The (txid, height) history of each watched script. A height of 0 or less is unconfirmed.
+    histories: HashMap<ScriptBuf, Vec<(Txid, i32)>>,
+}
+
+impl ElectrumSource {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            state: Arc::new(Mutex::new(ElectrumState::default())),
+        }
+    }
+
+    /// Runs `f` against the state on the blocking pool. The Electrum client blocks on the network
+    /// while the state is locked, so neither may happen on the async runtime.
+    async fn with_state<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut ElectrumState) -> T + Send + 'static,
+    ) -> Result<T, ChainIndexerError> {
+        let state = self.state.clone();
+        Ok(tokio::task::spawn_blocking(move || f(&mut state.lock().unwrap())).await?)
+    }
+
+    async fn with_client<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut ElectrumState, &Client) -> Result<T, ElectrumError> + Send + 'static,
+    ) -> Result<T, ChainIndexerError> {
+        let url = self.url.clone();
+        self.with_state(move |state| {
+            let client = match state.client.take() {
+                Some(client) => client,
+                None => connect(&url, state)?,
+            };
+
+            match f(state, &client) {
+                Ok(result) => {
+                    state.client = Some(client);
+                    Ok(result)
+                }
+                Err(e) => {
+                    event!(
+                        Level::WARN,
+                        "Electrum request failed, reconnecting on next request: {e}"
+                    );
+                    Err(e.into())
+                }
+            }
+        })
+        .await?
+    }
+}
+
+fn connect(url: &str, state: &mut ElectrumState) -> Result<Client, ElectrumError> {
+    // Retries would reconnect without our subscriptions, so we reconnect ourselves instead.
+    let config = ConfigBuilder::new().timeout(Some(10)).retry(0).build();
+    let client = Client::from_config(url, config)?;
+
+    let scripts: Vec<ScriptBuf> = state.histories.keys().cloned().collect();
+    for script in &scripts {
+        client.script_subscribe(script)?;
+    }
+    refresh_histories(state, &client, &scripts)?;
+    event!(
+        Level::INFO,
+        "Connected to Electrum server and subscribed to {} scripts",
+        scripts.len()
+    );
+
+    Ok(client)
+}
+
+fn refresh_histories(
+    state: &mut ElectrumState,
+    client: &Client,
+    scripts: &[ScriptBuf],
+) -> Result<(), ElectrumError> {
+    if scripts.is_empty() {
+        return Ok(());
+    }
+
+    let histories = client.batch_script_get_history(scripts.iter().map(|s| s.as_script()))?;
+    for (script, history) in scripts.iter().zip(histories) {
+        state.histories.insert(
+            script.clone(),
+            history
+                .into_iter()
+                .map(|entry| (entry.tx_hash, entry.height))
+                .collect(),
+        );
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ChainSource for ElectrumSource {
+    async fn tip_hash(&self) -> Result<BlockHash, ChainIndexerError> {
+        self.with_client(|state, client| {
+            let tip = client.block_headers_subscribe()?;
+            while client.block_headers_pop()?.is_some() {}
+            let tip_hash = tip.header.block_hash();
+            state.heights.insert(tip_hash, tip.height as u64);
+
+            // Refresh the history of every script whose status changed since the last sync.
+            let mut changed_scripts = Vec::new();
+            for script in state.histories.keys() {
+                let mut changed = false;
+                while client.script_pop(script)?.is_some() {
+                    changed = true;
+                }
+                if changed {
+                    changed_scripts.push(script.clone());
+                }
+            }
+            refresh_histories(state, client, &changed_scripts)?;
+
+            Ok(tip_hash)
+        })
+        .await
+    }
+
+    async fn block(&self, block_hash: &BlockHash) -> Result<ChainBlock, ChainIndexerError> {
+        let block_hash = *block_hash;
+        let height = self
+            .with_state(move |state| state.heights.get(&block_hash).copied())
+            .await?
+            .ok_or_else(|| ChainIndexerError::ChainSourceNotFound(format!("block {block_hash}")))?;
+
+        let block = self
+            .with_client(move |state, client| {
+                let header = client.block_header(height as usize)?;
+                if header.block_hash() != block_hash {
+                    // The block has been reorged out since we were told about it.
+                    return Ok(None);
+                }
+                state.heights.insert(header.prev_blockhash, height - 1);
+
+                let txids: HashSet<Txid> = state
+                    .histories
+                    .values()
+                    .flatten()
+                    .filter(|(_, tx_height)| *tx_height as i64 == height as i64)
+                    .map(|(txid, _)| *txid)
+                    .collect();
+                let txdata = txids
+                    .iter()
+                    .map(|txid| client.transaction_get(txid))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(Some(ChainBlock {
+                    header,
+                    height,
+                    txdata,
+                }))
+            })
+            .await?;
+
+        block.ok_or_else(|| ChainIndexerError::ChainSourceNotFound(format!("block {block_hash}")))
+    }
+
+    async fn block_hash_at_height(&self, height: u64) -> Result<BlockHash, ChainIndexerError> {
+        self.with_client(move |state, client| {
+            let block_hash = client.block_header(height as usize)?.block_hash();
+            state.heights.insert(block_hash, height);
+            Ok(block_hash)
+        })
+        .await
+    }
+
+    async fn mempool_txids(&self) -> Result<Vec<Txid>, ChainIndexerError> {
+        self.with_state(|state| {
+            let txids: HashSet<Txid> = state
+                .histories
+                .values()
+                .flatten()
+                .filter(|(_, height)| *height <= 0)
+                .map(|(txid, _)| *txid)
+                .collect();
+            txids.into_iter().collect()
+        })
+        .await
+    }
+
+    async fn transaction(&self, txid: &Txid) -> Result<Transaction, ChainIndexerError> {
+        let txid = *txid;
+        self.with_client(move |_, client| client.transaction_get(&txid))
+            .await
+    }
+
+    fn requires_watchlist(&self) -> bool {
+        true
+    }
+
+    async fn watch_scripts(&self, scripts: &[ScriptBuf]) -> Result<(), ChainIndexerError> {
+        let scripts = scripts.to_vec();
+        self.with_client(move |state, client| {
+            let new_scripts: Vec<ScriptBuf> = scripts
+                .into_iter()
+                .filter(|script| !state.histories.contains_key(script))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            for script in &new_scripts {
+                client.script_subscribe(script)?;
+            }
+            refresh_histories(state, client, &new_scripts)?;
+            if !new_scripts.is_empty() {
+                event!(
+                    Level::INFO,
+                    "Subscribed to {} new scripts",
+                    new_scripts.len()
+                );
+            }
+
+            Ok(())
+        })
+        .await
+    }
+}
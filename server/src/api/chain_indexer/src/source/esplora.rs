@@ -0,0 +1,95 @@
+use async_trait::async_trait;
+use bdk_utils::bdk::bitcoin::{
+    consensus::encode::deserialize, Block as BdkBlock, BlockHash, Transaction, Txid,
+};
+use reqwest::Client;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+
+use super::{ChainBlock, ChainSource};
+use crate::ChainIndexerError;
+
+/// Reads chain data from an Esplora REST API, such as mempool.space or electrs.
+#[derive(Clone)]
+pub struct EsploraSource {
+    http_client: ClientWithMiddleware,
+    base_url: String,
+}
+
+impl EsploraSource {
+    pub fn new(base_url: String) -> Self {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(5);
+        let http_client = ClientBuilder::new(Client::new())
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build();
+
+        Self {
+            http_client,
+            base_url,
+        }
+    }
+
+    async fn get_bytes(&self, path: &str) -> Result<Vec<u8>, ChainIndexerError> {
+        Ok(self
+            .http_client
+            .get(&format!("{}{path}", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await
+            .map(|bytes| bytes.to_vec())?)
+    }
+}
+
+#[async_trait]
+impl ChainSource for EsploraSource {
+    async fn tip_hash(&self) -> Result<BlockHash, ChainIndexerError> {
+        Ok(self
+            .http_client
+            .get(&format!("{}/blocks/tip/hash", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?
+            .parse()?)
+    }
+
+    async fn block(&self, block_hash: &BlockHash) -> Result<ChainBlock, ChainIndexerError> {
+        let block: BdkBlock =
+            deserialize(&self.get_bytes(&format!("/block/{block_hash}/raw")).await?)?;
+        Ok(block.try_into()?)
+    }
+
+    async fn block_hash_at_height(&self, height: u64) -> Result<BlockHash, ChainIndexerError> {
+        Ok(self
+            .http_client
+            .get(&format!("{}/block-height/{height}", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?
+            .parse()?)
+    }
+
+    async fn mempool_txids(&self) -> Result<Vec<Txid>, ChainIndexerError> {
+        self.http_client
+            .get(&format!("{}/mempool/txids", self.base_url))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<String>>()
+            .await?
+            .iter()
+            .map(|txid| Ok(txid.parse()?))
+            .collect()
+    }
+
+    async fn transaction(&self, txid: &Txid) -> Result<Transaction, ChainIndexerError> {
+        Ok(deserialize(
+            &self.get_bytes(&format!("/tx/{txid}/raw")).await?,
+        )?)
+    }
+}
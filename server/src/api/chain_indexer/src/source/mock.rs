@@ -0,0 +1,233 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use async_trait::async_trait;
+use bdk_utils::bdk::bitcoin::{
+    absolute::LockTime,
+    block::{Header, Version},
+    hash_types::TxMerkleNode,
+    hashes::Hash,
+    script::Builder,
+    Block as BdkBlock, BlockHash, CompactTarget, OutPoint, ScriptBuf, Sequence, Transaction, TxIn,
+    TxOut, Txid, Witness,
+};
+
+use super::{ChainBlock, ChainSource};
+use crate::ChainIndexerError;
+
+const START_HEIGHT: u64 = 100;
+
+// Shared between instances so that blocks and transactions are unique across mock chains, as
+// tests share a database.
+static NONCE: AtomicU32 = AtomicU32::new(0);
+
+/// In-memory chain for tests. Blocks can be mined on top of any known block, so forks and
+/// reorgs can be simulated by mining on an older parent and moving the tip to it.
+#[derive(Clone, Default)]
+pub struct MockChainSource {
+    state: Arc<Mutex<MockChainState>>,
+}
+
+#[derive(Default)]
+struct MockChainState {
+    blocks: HashMap<BlockHash, (BdkBlock, u64)>,
+    tip: Option<BlockHash>,
+    mempool: HashMap<Txid, Transaction>,
+}
+
+impl MockChainSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mines a block containing `txdata` on top of `parent` (or a new chain if `None`), makes it
+    /// the tip and removes its transactions from the mempool.
+    pub fn mine(&self, parent: Option<BlockHash>, txdata: Vec<Transaction>) -> BlockHash {
+        let mut state = self.state.lock().unwrap();
+        let (prev_blockhash, height) = match parent {
+            Some(parent) => {
+                let (_, parent_height) = state
+                    .blocks
+                    .get(&parent)
+                    .expect("parent block should have been mined");
+                (parent, parent_height + 1)
+            }
+            None => (BlockHash::all_zeros(), START_HEIGHT),
+        };
+        let nonce = NONCE.fetch_add(1, Ordering::Relaxed);
+
+        let coinbase = Transaction {
+            version: 1,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: Builder::new()
+                    .push_int(height as i64)
+                    .push_int(nonce as i64)
+                    .into_script(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: 0,
+                script_pubkey: ScriptBuf::new_op_return(&[]),
+            }],
+        };
+        let mut block = BdkBlock {
+            header: Header {
+                version: Version::TWO,
+                prev_blockhash,
+                merkle_root: TxMerkleNode::all_zeros(),
+                time: height as u32,
+                bits: CompactTarget::from_consensus(0x207fffff),
+                nonce,
+            },
+            txdata: std::iter::once(coinbase).chain(txdata).collect(),
+        };
+        block.header.merkle_root = block
+            .compute_merkle_root()
+            .expect("block should have a coinbase");
+
+        let block_hash = block.block_hash();
+        for tx in &block.txdata {
+            state.mempool.remove(&tx.txid());
+        }
+        state.blocks.insert(block_hash, (block, height));
+        state.tip = Some(block_hash);
+        block_hash
+    }
+
+    pub fn set_tip(&self, block_hash: BlockHash) {
+        self.state.lock().unwrap().tip = Some(block_hash);
+    }
+
+    pub fn add_to_mempool(&self, tx: Transaction) -> Txid {
+        let txid = tx.txid();
+        self.state.lock().unwrap().mempool.insert(txid, tx);
+        txid
+    }
+
+    pub fn remove_from_mempool(&self, txid: &Txid) {
+        self.state.lock().unwrap().mempool.remove(txid);
+    }
+
+    /// Builds a transaction paying `script_pubkey` that's distinct from any built before it.
+    pub fn payment_to(&self, script_pubkey: ScriptBuf, value: u64) -> Transaction {
+        let nonce = NONCE.fetch_add(1, Ordering::Relaxed);
+
+        Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::all_zeros(), nonce),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value,
+                script_pubkey,
+            }],
+        }
+    }
+}
+
+#[async_trait]
+impl ChainSource for MockChainSource {
+    async fn tip_hash(&self) -> Result<BlockHash, ChainIndexerError> {
+        self.state
+            .lock()
+            .unwrap()
+            .tip
+            .ok_or_else(|| ChainIndexerError::ChainSourceNotFound("tip".to_string()))
+    }
+
+    async fn block(&self, block_hash: &BlockHash) -> Result<ChainBlock, ChainIndexerError> {
+        let block = self
+            .state
+            .lock()
+            .unwrap()
+            .blocks
+            .get(block_hash)
+            .map(|(block, _)| block.clone())
+            .ok_or_else(|| ChainIndexerError::ChainSourceNotFound(format!("block {block_hash}")))?;
+        Ok(block.try_into()?)
+    }
+
+    async fn block_hash_at_height(&self, height: u64) -> Result<BlockHash, ChainIndexerError> {
+        let state = self.state.lock().unwrap();
+        let mut block_hash = state.tip;
+        while let Some((block, block_height)) = block_hash.and_then(|hash| state.blocks.get(&hash))
+        {
+            if *block_height == height {
+                return Ok(block.block_hash());
+            }
+            block_hash = Some(block.header.prev_blockhash);
+        }
+        Err(ChainIndexerError::ChainSourceNotFound(format!(
+            "block at height {height}"
+        )))
+    }
+
+    async fn mempool_txids(&self) -> Result<Vec<Txid>, ChainIndexerError> {
+        Ok(self.state.lock().unwrap().mempool.keys().copied().collect())
+    }
+
+    async fn transaction(&self, txid: &Txid) -> Result<Transaction, ChainIndexerError> {
+        self.state
+            .lock()
+            .unwrap()
+            .mempool
+            .get(txid)
+            .cloned()
+            .ok_or_else(|| ChainIndexerError::ChainSourceNotFound(format!("transaction {txid}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mined_blocks_form_a_chain() {
+        let source = MockChainSource::new();
+        let genesis = source.mine(None, vec![]);
+        let first = source.mine(Some(genesis), vec![]);
+        let fork = source.mine(Some(genesis), vec![]);
+
+        let first_block = source.block(&first).await.unwrap();
+        let fork_block = source.block(&fork).await.unwrap();
+        assert_ne!(first, fork);
+        assert_eq!(first_block.header.prev_blockhash, genesis);
+        assert_eq!(fork_block.header.prev_blockhash, genesis);
+        assert_eq!(first_block.height, START_HEIGHT + 1);
+        assert_eq!(source.tip_hash().await.unwrap(), fork);
+        assert_eq!(
+            source.block_hash_at_height(START_HEIGHT + 1).await.unwrap(),
+            fork
+        );
+        assert_eq!(
+            source.block_hash_at_height(START_HEIGHT).await.unwrap(),
+            genesis
+        );
+        assert!(source.block_hash_at_height(START_HEIGHT + 2).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mining_clears_mempool() {
+        let source = MockChainSource::new();
+        let genesis = source.mine(None, vec![]);
+        let tx = source.payment_to(ScriptBuf::new(), 1_000);
+        let txid = source.add_to_mempool(tx.clone());
+        assert_eq!(source.mempool_txids().await.unwrap(), vec![txid]);
+        assert_eq!(source.transaction(&txid).await.unwrap(), tx);
+
+        source.mine(Some(genesis), vec![tx]);
+        assert!(source.mempool_txids().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bdk_utils::bdk::bitcoin::{
+    block::{Bip34Error, Header},
+    Block as BdkBlock, BlockHash, ScriptBuf, Transaction, Txid,
+};
+use serde::Deserialize;
+
+use crate::ChainIndexerError;
+
+pub mod bitcoind;
+pub mod electrum;
+pub mod esplora;
+pub mod mock;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainBackend {
+    #[default]
+    Esplora,
+    Electrum,
+    Bitcoind,
+}
+
+/// A block as returned by a [`ChainSource`]. Sources that filter by watched scripts only include
+/// the transactions paying them, so `txdata` isn't necessarily the whole block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChainBlock {
+    pub header: Header,
+    pub height: u64,
+    pub txdata: Vec<Transaction>,
+}
+
+impl ChainBlock {
+    pub fn block_hash(&self) -> BlockHash {
+        self.header.block_hash()
+    }
+}
+
+impl TryFrom<BdkBlock> for ChainBlock {
+    type Error = Bip34Error;
+
+    fn try_from(block: BdkBlock) -> Result<Self, Self::Error> {
+        Ok(Self {
+            height: block.bip34_block_height()?,
+            header: block.header,
+            txdata: block.txdata,
+        })
+    }
+}
+
+/// Where the indexer reads chain data from.
+#[async_trait]
+pub trait ChainSource: Send + Sync {
+    async fn tip_hash(&self) -> Result<BlockHash, ChainIndexerError>;
+    async fn block(&self, block_hash: &BlockHash) -> Result<ChainBlock, ChainIndexerError>;
+    /// The hash of the block at `height` on the source's active chain.
+    async fn block_hash_at_height(&self, height: u64) -> Result<BlockHash, ChainIndexerError>;
+    async fn mempool_txids(&self) -> Result<Vec<Txid>, ChainIndexerError>;
+    async fn transaction(&self, txid: &Txid) -> Result<Transaction, ChainIndexerError>;
+
+    /// Whether the source only returns transactions paying the scripts passed to
+    /// [`ChainSource::watch_scripts`].
+    fn requires_watchlist(&self) -> bool {
+        false
+    }
+
+    /// Adds scripts to those the source returns transactions for. Scripts already being watched
+    /// are ignored.
+    async fn watch_scripts(&self, _scripts: &[ScriptBuf]) -> Result<(), ChainIndexerError> {
+        Ok(())
+    }
+
+    /// Waits until the source reports a new block, or the timeout elapses for sources that
+    /// can't push notifications.
+    async fn wait_for_new_block(&self, timeout: Duration) {
+        tokio::time::sleep(timeout).await;
+    }
+}
@@ -0,0 +1,40 @@
+use database::{
+    aws_sdk_dynamodb::error::ProvideErrorMetadata,
+    ddb::{try_to_attribute_val, DDBService, DatabaseError},
+};
+use tracing::{event, instrument, Level};
+
+use super::{StateRepository, PARTITION_KEY};
+
+impl StateRepository {
+    #[instrument(skip(self))]
+    pub(crate) async fn delete_payment_notification(
+        &self,
+        partition_key: &str,
+    ) -> Result<(), DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        self.connection
+            .client
+            .delete_item()
+            .table_name(table_name)
+            .key(
+                PARTITION_KEY,
+                try_to_attribute_val(partition_key, database_object)?,
+            )
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not delete payment notification record: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::DeleteItemsError(database_object)
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use bdk_utils::bdk::bitcoin::{Network, Txid};
+use database::{
+    aws_sdk_dynamodb::{error::ProvideErrorMetadata, types::KeysAndAttributes},
+    ddb::{try_from_item, try_to_attribute_val, DDBService, DatabaseError},
+};
+use tracing::{event, instrument, Level};
+
+use super::{StateRepository, PARTITION_KEY};
+use crate::entities::{
+    cursor_partition_key, outgoing_payment_partition_key, ChainCursor, OutgoingPaymentRecord,
+};
+
+const DDB_BATCH_READ_SIZE_MAX: usize = 100;
+
+impl StateRepository {
+    #[instrument(skip(self))]
+    pub(crate) async fn fetch_cursor(
+        &self,
+        network: Network,
+    ) -> Result<Option<ChainCursor>, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        self.connection
+            .client
+            .get_item()
+            .table_name(table_name)
+            .key(
+                PARTITION_KEY,
+                try_to_attribute_val(cursor_partition_key(network), database_object)?,
+            )
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not fetch chain cursor: {service_err:?}"
+                );
+                DatabaseError::FetchError(database_object)
+            })?
+            .item
+            .map(|item| try_from_item(item, database_object))
+            .transpose()
+    }
+
+    /// Fetches the outgoing payment records for whichever of the transactions have one.
+    #[instrument(skip(self, txids))]
+    pub(crate) async fn fetch_outgoing_payments(
+        &self,
+        txids: &[Txid],
+    ) -> Result<Vec<OutgoingPaymentRecord>, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let mut records = Vec::new();
+        for chunk in txids.chunks(DDB_BATCH_READ_SIZE_MAX) {
+            let keys = chunk
+                .iter()
+                .map(|txid| {
+                    Ok(HashMap::from([(
+                        PARTITION_KEY.to_string(),
+                        try_to_attribute_val(
+                            outgoing_payment_partition_key(txid),
+                            database_object,
+                        )?,
+                    )]))
+                })
+                .collect::<Result<Vec<_>, DatabaseError>>()?;
+            let mut unprocessed_opt = Some(HashMap::from([(
+                table_name.clone(),
+                KeysAndAttributes::builder().set_keys(Some(keys)).build()?,
+            )]));
+
+            // On completion, unprocessed_keys is Some({}). Use a filter to break out of the loop.
+            while let Some(unprocessed) = unprocessed_opt.filter(|m| !m.is_empty()) {
+                let result = self
+                    .connection
+                    .client
+                    .batch_get_item()
+                    .set_request_items(Some(unprocessed))
+                    .send()
+                    .await
+                    .map_err(|err| {
+                        let service_err = err.into_service_error();
+                        event!(
+                            Level::ERROR,
+                            "Could not fetch outgoing payment records: {service_err:?} with message: {:?}",
+                            service_err.message()
+                        );
+                        DatabaseError::FetchError(database_object)
+                    })?;
+
+                for item in result
+                    .responses()
+                    .and_then(|tables| tables.get(&table_name))
+                    .into_iter()
+                    .flatten()
+                {
+                    records.push(try_from_item(item.clone(), database_object)?);
+                }
+                unprocessed_opt = result.unprocessed_keys().cloned();
+            }
+        }
+
+        Ok(records)
+    }
+}
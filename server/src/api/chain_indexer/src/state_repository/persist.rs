@@ -0,0 +1,102 @@
+use database::{
+    aws_sdk_dynamodb::error::ProvideErrorMetadata,
+    ddb::{try_to_item, DDBService, DatabaseError},
+};
+use tracing::{event, instrument, Level};
+
+use super::{StateRepository, PARTITION_KEY};
+use crate::entities::{ChainCursor, OutgoingPaymentRecord, PaymentNotificationRecord};
+
+impl StateRepository {
+    #[instrument(skip(self))]
+    pub(crate) async fn persist_cursor(&self, cursor: &ChainCursor) -> Result<(), DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+        let item = try_to_item(cursor, database_object)?;
+
+        self.connection
+            .client
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(item))
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not persist chain cursor: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::PersistenceError(database_object)
+            })?;
+
+        Ok(())
+    }
+
+    /// Persists the record unless one already exists, returning whether it was created.
+    #[instrument(skip(self))]
+    pub(crate) async fn create_payment_notification(
+        &self,
+        record: &PaymentNotificationRecord,
+    ) -> Result<bool, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+        let item = try_to_item(record, database_object)?;
+
+        let result = self
+            .connection
+            .client
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(item))
+            .condition_expression(format!("attribute_not_exists({PARTITION_KEY})"))
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(err) => {
+                let service_err = err.into_service_error();
+                if service_err.is_conditional_check_failed_exception() {
+                    return Ok(false);
+                }
+                event!(
+                    Level::ERROR,
+                    "Could not persist payment notification record: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                Err(DatabaseError::PersistenceError(database_object))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub(crate) async fn persist_outgoing_payment(
+        &self,
+        record: &OutgoingPaymentRecord,
+    ) -> Result<(), DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+        let item = try_to_item(record, database_object)?;
+
+        self.connection
+            .client
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(item))
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not persist outgoing payment record: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::PersistenceError(database_object)
+            })?;
+
+        Ok(())
+    }
+}
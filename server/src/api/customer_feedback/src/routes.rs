@@ -10,7 +10,7 @@ use axum::{
     routing::post,
     Json, Router,
 };
-use errors::ApiError;
+use errors::{ApiError, ErrorCategory, ErrorCode, ErrorResponseBody, ErrorResponseBodyError};
 use http_server::swagger::{SwaggerEndpoint, Url};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
@@ -90,6 +90,10 @@ impl From<RouteState> for SwaggerEndpoint {
             SupportTicketAttachmentUpload,
             SupportTicketDebugData,
             TicketFieldValue,
+            ErrorCategory,
+            ErrorCode,
+            ErrorResponseBody,
+            ErrorResponseBodyError,
         )
     ),
     tags(
@@ -246,7 +250,7 @@ pub struct CreateTaskResponse {
     request_body = CreateTaskRequest,
     responses(
         (status = 200, description = "Customer Feedback ticket was successfully created", body=CreateTaskResponse),
-        (status = 500, description = "Ticket couldn't be created"),
+        (status = 500, description = "Ticket couldn't be created", body = ErrorResponseBody),
 
     ),
 )]
@@ -433,7 +437,7 @@ impl From<TicketFormEndUserConditionPayload> for TicketFormCondition {
     path = "/api/support/ticket-form",
     responses(
         (status = 200, description = "Customer Feedback form structure", body=TicketFormAndFieldsResponse),
-        (status = 500, description = "Couldn't fetch feedback form structure")
+        (status = 500, description = "Couldn't fetch feedback form structure", body = ErrorResponseBody)
     )
 )]
 #[instrument(err, skip(zendesk_client,))]
@@ -509,7 +513,7 @@ struct TicketAttachmentQuery {
     path = "/api/support/attachments",
     responses(
         (status = 200, description = "Ticket attachment uploaded successfully", body=TicketAttachmentResponse),
-        (status = 500, description = "Ticket attachment upload failed")
+        (status = 500, description = "Ticket attachment upload failed", body = ErrorResponseBody)
     )
 )]
 #[instrument(err, skip(zendesk_client,))]
@@ -106,6 +106,7 @@ impl Connection {
             DatabaseObject::Notification => ("NOTIFICATION_TABLE", "Notification"),
             DatabaseObject::Account => ("ACCOUNT_TABLE", "Account"),
             DatabaseObject::ChainIndexer => ("CHAIN_INDEXER_TABLE", "ChainIndexer"),
+            DatabaseObject::ChainIndexerState => ("CHAIN_INDEXER_STATE_TABLE", "ChainIndexerState"),
             DatabaseObject::DailySpendingRecord => {
                 ("DAILY_SPENDING_RECORD_TABLE", "DailySpendingRecord")
             }
@@ -114,6 +115,10 @@ impl Connection {
             DatabaseObject::Migration => ("MIGRATION_TABLE", "Migration"),
             DatabaseObject::SocialRecovery => ("SOCIAL_RECOVERY_TABLE", "SocialRecovery"),
             DatabaseObject::Consent => ("CONSENT_TABLE", "Consent"),
+            DatabaseObject::Auth => ("AUTH_TABLE", "Auth"),
+            DatabaseObject::Session => ("SESSION_TABLE", "Session"),
+            DatabaseObject::SecurityEvent => ("SECURITY_EVENT_TABLE", "SecurityEvent"),
+            DatabaseObject::RequestNonce => ("REQUEST_NONCE_TABLE", "RequestNonce"),
         };
 
         match self {
@@ -197,12 +202,17 @@ pub enum DatabaseObject {
     #[default]
     Account,
     ChainIndexer,
+    ChainIndexerState,
     DailySpendingRecord,
     SignedPsbtCache,
     AddressWatchlist,
     Migration,
     SocialRecovery,
     Consent,
+    Auth,
+    Session,
+    SecurityEvent,
+    RequestNonce,
 }
 
 impl fmt::Display for DatabaseObject {
@@ -213,12 +223,17 @@ impl fmt::Display for DatabaseObject {
             DatabaseObject::Notification => write!(f, "Notification"),
             DatabaseObject::Account => write!(f, "Account"),
             DatabaseObject::ChainIndexer => write!(f, "ChainIndexer"),
+            DatabaseObject::ChainIndexerState => write!(f, "ChainIndexerState"),
             DatabaseObject::DailySpendingRecord => write!(f, "DailySpendingRecord"),
             DatabaseObject::SignedPsbtCache => write!(f, "SignedPsbtCache"),
             DatabaseObject::AddressWatchlist => write!(f, "AddressWatchList"),
             DatabaseObject::Migration => write!(f, "Migration"),
             DatabaseObject::SocialRecovery => write!(f, "SocialRecovery"),
             DatabaseObject::Consent => write!(f, "Consent"),
+            DatabaseObject::Auth => write!(f, "Auth"),
+            DatabaseObject::Session => write!(f, "Session"),
+            DatabaseObject::SecurityEvent => write!(f, "SecurityEvent"),
+            DatabaseObject::RequestNonce => write!(f, "RequestNonce"),
         }
     }
 }
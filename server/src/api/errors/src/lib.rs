@@ -6,6 +6,7 @@ use serde::Serialize;
 use strum_macros::Display;
 use thiserror::Error;
 use tracing::{error, event, Level};
+use utoipa::ToSchema;
 
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum ApiError {
@@ -29,10 +30,24 @@ pub enum ApiError {
         detail: Option<String>,
         field: Option<String>,
     },
+    /// Several errors reported together, e.g. every spend rule a transaction violated. The
+    /// response status is that of the most severe error, see [`status_severity`].
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    Multiple(Vec<ApiError>),
+}
+
+impl ApiError {
+    pub fn specific(code: ErrorCode, detail: impl Into<String>) -> Self {
+        ApiError::Specific {
+            code,
+            detail: Some(detail.into()),
+            field: None,
+        }
+    }
 }
 
 // Ref: https://github.com/squareup/go-square/blob/f142a1b4e6d96e0a4ab938e8553cfbe50fbf53b4/xp/connect-public-protos/protos/squareup/connect/v2/resources/error.proto
-#[derive(Clone, Debug, Serialize, Display)]
+#[derive(Clone, Debug, Serialize, Display, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ErrorCategory {
     // An error occurred with the API itself.
@@ -48,7 +63,9 @@ pub enum ErrorCategory {
 }
 
 // Ref: https://github.com/squareup/go-square/blob/f142a1b4e6d96e0a4ab938e8553cfbe50fbf53b4/xp/connect-public-protos/protos/squareup/connect/v2/resources/error.proto
-#[derive(Clone, Debug, Serialize, Display, PartialEq)]
+//
+// Codes are part of the API contract: clients branch on them, so never rename or reuse one.
+#[derive(Clone, Debug, Serialize, Display, PartialEq, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ErrorCode {
     // Category: ApiError
@@ -61,6 +78,10 @@ pub enum ErrorCode {
     // A general access error occurred.
     Forbidden,
     Unauthorized,
+    // Authentication
+    AppAndHwSignatureRequired,
+    WrongAccessTokenType,
+    SessionRevoked,
     // Recovery
     CommsVerificationRequired,
 
@@ -73,6 +94,8 @@ pub enum ErrorCode {
     NotFound,
 
     AccountNotFound,
+    FeatureNotEnabled,
+    InvalidAccountType,
 
     // Comms Verification
     CodeMismatch, // Also used for RecoveryRelationship
@@ -82,6 +105,7 @@ pub enum ErrorCode {
     TouchpointAlreadyActive,
     InvalidPhoneNumber,
     InvalidEmailAddress,
+    AccountAlreadyUpgraded,
     // Onboarding & Recovery
     AppAuthPubkeyInUse,
     HwAuthPubkeyInUse,
@@ -89,12 +113,51 @@ pub enum ErrorCode {
     // Recovery,
     RecoveryAlreadyExists,
     NoRecoveryExists,
+    DelayPeriodNotFinished,
+    InvalidRecoveryPolicy,
+    KeyProofRequired,
+    RecoveryAuthKeyRequired,
+    HwAuthKeyMismatch,
     // RecoveryRelationship
     InvitationExpired,
+    InvitationNonEndorsable,
     MaxTrustedContactsReached,
     MaxProtectedCustomersReached,
+    RelationshipAlreadyEstablished,
+    AccountAlreadyTrustedContact,
+    CustomerIsTrustedContact,
+    UnauthorizedRelationshipOperation,
+    // Social Challenge
+    AccountNotCustomer,
+    AccountNotTrustedContact,
+    ChallengeRelationshipMismatch,
+    RelationshipStatusMismatch,
+    // Inheritance
+    CustomerRecentlyActive,
+    InheritanceClaimAlreadyExists,
+    InheritanceClaimNotPending,
+    InvalidInactivityPeriod,
+    // Notification
+    InvalidAddress,
+    InvalidCallbackSignature,
     // Money Movement,
     NoSpendingLimitExists,
+    MobilePayDisabled,
+    MobilePayNotActive,
+    InvalidSpendPolicy,
+    // Mobile Pay spend rules
+    InvalidTransaction,
+    InputsNotFromWallet,
+    OutputsToSelf,
+    OutputsNotToDestination,
+    SanctionedDestinationAddress,
+    SpendingLimitExceeded,
+    PerTransactionLimitExceeded,
+    PeriodSpendingLimitExceeded,
+    DestinationNotAllowlisted,
+    FeeRateTooHigh,
+    FeeRatioTooHigh,
+    TransactionVelocityExceeded,
 }
 
 // An ErrorCode always maps to a single ErrorCategory
@@ -106,6 +169,10 @@ impl From<ErrorCode> for ErrorCategory {
             }
             ErrorCode::Forbidden
             | ErrorCode::Unauthorized
+            | ErrorCode::AppAndHwSignatureRequired
+            | ErrorCode::WrongAccessTokenType
+            | ErrorCode::SessionRevoked
+            | ErrorCode::InvalidCallbackSignature
             | ErrorCode::CommsVerificationRequired => ErrorCategory::AuthenticationError,
             ErrorCode::BadRequest
             | ErrorCode::NotFound
@@ -125,7 +192,44 @@ impl From<ErrorCode> for ErrorCategory {
             | ErrorCode::InvitationExpired
             | ErrorCode::AccountNotFound
             | ErrorCode::MaxTrustedContactsReached
-            | ErrorCode::MaxProtectedCustomersReached => ErrorCategory::InvalidRequestError,
+            | ErrorCode::MaxProtectedCustomersReached
+            | ErrorCode::SanctionedDestinationAddress
+            | ErrorCode::SpendingLimitExceeded
+            | ErrorCode::PerTransactionLimitExceeded
+            | ErrorCode::PeriodSpendingLimitExceeded
+            | ErrorCode::DestinationNotAllowlisted
+            | ErrorCode::FeeRateTooHigh
+            | ErrorCode::FeeRatioTooHigh
+            | ErrorCode::TransactionVelocityExceeded
+            | ErrorCode::FeatureNotEnabled
+            | ErrorCode::InvalidAccountType
+            | ErrorCode::AccountAlreadyUpgraded
+            | ErrorCode::DelayPeriodNotFinished
+            | ErrorCode::InvalidRecoveryPolicy
+            | ErrorCode::KeyProofRequired
+            | ErrorCode::RecoveryAuthKeyRequired
+            | ErrorCode::HwAuthKeyMismatch
+            | ErrorCode::InvitationNonEndorsable
+            | ErrorCode::RelationshipAlreadyEstablished
+            | ErrorCode::AccountAlreadyTrustedContact
+            | ErrorCode::CustomerIsTrustedContact
+            | ErrorCode::UnauthorizedRelationshipOperation
+            | ErrorCode::AccountNotCustomer
+            | ErrorCode::AccountNotTrustedContact
+            | ErrorCode::ChallengeRelationshipMismatch
+            | ErrorCode::RelationshipStatusMismatch
+            | ErrorCode::CustomerRecentlyActive
+            | ErrorCode::InheritanceClaimAlreadyExists
+            | ErrorCode::InheritanceClaimNotPending
+            | ErrorCode::InvalidInactivityPeriod
+            | ErrorCode::InvalidAddress
+            | ErrorCode::MobilePayDisabled
+            | ErrorCode::MobilePayNotActive
+            | ErrorCode::InvalidSpendPolicy
+            | ErrorCode::InvalidTransaction
+            | ErrorCode::InputsNotFromWallet
+            | ErrorCode::OutputsToSelf
+            | ErrorCode::OutputsNotToDestination => ErrorCategory::InvalidRequestError,
         }
     }
 }
@@ -136,7 +240,18 @@ impl From<ErrorCode> for StatusCode {
         match value {
             ErrorCode::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
             ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
-            ErrorCode::Forbidden | ErrorCode::CommsVerificationRequired => StatusCode::FORBIDDEN,
+            ErrorCode::Forbidden
+            | ErrorCode::CommsVerificationRequired
+            | ErrorCode::AppAndHwSignatureRequired
+            | ErrorCode::WrongAccessTokenType
+            | ErrorCode::FeatureNotEnabled
+            | ErrorCode::InvalidAccountType
+            | ErrorCode::CustomerIsTrustedContact
+            | ErrorCode::UnauthorizedRelationshipOperation
+            | ErrorCode::AccountNotCustomer
+            | ErrorCode::AccountNotTrustedContact
+            | ErrorCode::MobilePayDisabled
+            | ErrorCode::MobilePayNotActive => StatusCode::FORBIDDEN,
             ErrorCode::BadRequest
             | ErrorCode::UnsupportedCountryCode
             | ErrorCode::CodeMismatch
@@ -145,7 +260,29 @@ impl From<ErrorCode> for StatusCode {
             | ErrorCode::HwAuthPubkeyInUse
             | ErrorCode::RecoveryAuthPubkeyInUse
             | ErrorCode::InvalidPhoneNumber
-            | ErrorCode::InvalidEmailAddress => StatusCode::BAD_REQUEST,
+            | ErrorCode::InvalidEmailAddress
+            | ErrorCode::SanctionedDestinationAddress
+            | ErrorCode::SpendingLimitExceeded
+            | ErrorCode::PerTransactionLimitExceeded
+            | ErrorCode::PeriodSpendingLimitExceeded
+            | ErrorCode::DestinationNotAllowlisted
+            | ErrorCode::FeeRateTooHigh
+            | ErrorCode::FeeRatioTooHigh
+            | ErrorCode::TransactionVelocityExceeded
+            | ErrorCode::DelayPeriodNotFinished
+            | ErrorCode::InvalidRecoveryPolicy
+            | ErrorCode::KeyProofRequired
+            | ErrorCode::RecoveryAuthKeyRequired
+            | ErrorCode::HwAuthKeyMismatch
+            | ErrorCode::InvitationNonEndorsable
+            | ErrorCode::ChallengeRelationshipMismatch
+            | ErrorCode::InvalidAddress
+            | ErrorCode::InvalidSpendPolicy
+            | ErrorCode::InvalidInactivityPeriod
+            | ErrorCode::InvalidTransaction
+            | ErrorCode::InputsNotFromWallet
+            | ErrorCode::OutputsToSelf
+            | ErrorCode::OutputsNotToDestination => StatusCode::BAD_REQUEST,
             ErrorCode::NotFound | ErrorCode::AccountNotFound => StatusCode::NOT_FOUND,
             ErrorCode::TouchpointAlreadyActive
             | ErrorCode::RecoveryAlreadyExists
@@ -153,15 +290,24 @@ impl From<ErrorCode> for StatusCode {
             | ErrorCode::Conflict
             | ErrorCode::InvitationExpired
             | ErrorCode::MaxTrustedContactsReached
-            | ErrorCode::MaxProtectedCustomersReached => StatusCode::CONFLICT,
+            | ErrorCode::MaxProtectedCustomersReached
+            | ErrorCode::AccountAlreadyUpgraded
+            | ErrorCode::RelationshipAlreadyEstablished
+            | ErrorCode::AccountAlreadyTrustedContact
+            | ErrorCode::RelationshipStatusMismatch
+            | ErrorCode::CustomerRecentlyActive
+            | ErrorCode::InheritanceClaimAlreadyExists
+            | ErrorCode::InheritanceClaimNotPending => StatusCode::CONFLICT,
             ErrorCode::NoSpendingLimitExists => StatusCode::METHOD_NOT_ALLOWED,
-            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized
+            | ErrorCode::SessionRevoked
+            | ErrorCode::InvalidCallbackSignature => StatusCode::UNAUTHORIZED,
         }
     }
 }
 
 // Ref: https://plathome.sqprod.co/styleguide/guidance/error-overview?bu=block&p=rest
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct ErrorResponseBodyError {
     pub category: ErrorCategory,
     pub code: ErrorCode,
@@ -172,13 +318,15 @@ pub struct ErrorResponseBodyError {
 }
 
 // Ref: https://plathome.sqprod.co/styleguide/guidance/error-overview?bu=block&p=rest
-#[derive(Clone, Debug, Serialize)]
+//
+// Every error response has this body, so routes should document their error responses with it.
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct ErrorResponseBody {
     pub errors: Vec<ErrorResponseBodyError>,
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> axum::response::Response {
+impl ApiError {
+    fn into_body_errors(self) -> Vec<ErrorResponseBodyError> {
         let (code, detail, field) = match self {
             ApiError::GenericInternalApplicationError(message) => {
                 (ErrorCode::InternalServerError, Some(message), None)
@@ -198,24 +346,53 @@ impl IntoResponse for ApiError {
                 detail,
                 field,
             } => (code, detail, field),
+            ApiError::Multiple(errors) => {
+                return errors
+                    .into_iter()
+                    .flat_map(ApiError::into_body_errors)
+                    .collect()
+            }
         };
 
         if let Some(detail) = detail.as_ref() {
             error!(detail);
         }
 
-        let error = ErrorResponseBodyError {
+        vec![ErrorResponseBodyError {
             category: code.clone().into(),
-            code: code.clone(),
+            code,
             detail,
             field,
-        };
+        }]
+    }
+}
 
-        let body = ErrorResponseBody {
-            errors: vec![error],
-        };
+// How severe a status is when several errors are reported together. Server errors outrank
+// client errors, and a caller that isn't authenticated or allowed to make the request learns
+// that before anything else about it.
+fn status_severity(status: StatusCode) -> u8 {
+    match status {
+        StatusCode::INTERNAL_SERVER_ERROR => 7,
+        status if status.is_server_error() => 6,
+        StatusCode::UNAUTHORIZED => 5,
+        StatusCode::FORBIDDEN => 4,
+        StatusCode::NOT_FOUND => 3,
+        StatusCode::CONFLICT => 2,
+        StatusCode::METHOD_NOT_ALLOWED => 1,
+        _ => 0,
+    }
+}
 
-        (StatusCode::from(code), Json(body)).into_response()
+impl IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        let errors = self.into_body_errors();
+        let status = errors
+            .iter()
+            .map(|error| StatusCode::from(error.code.clone()))
+            .max_by_key(|status| (status_severity(*status), status.as_u16()))
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        (status, Json(ErrorResponseBody { errors })).into_response()
     }
 }
 
@@ -334,3 +511,76 @@ impl From<RouteError> for ApiError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn multiple() -> ApiError {
+        ApiError::Multiple(vec![
+            ApiError::specific(ErrorCode::SpendingLimitExceeded, "over the daily limit"),
+            ApiError::Specific {
+                code: ErrorCode::MobilePayDisabled,
+                detail: None,
+                field: None,
+            },
+            ApiError::Multiple(vec![ApiError::GenericNotFound("no keyset".to_string())]),
+        ])
+    }
+
+    #[test]
+    fn multiple_body_lists_every_error_in_order() {
+        let body = ErrorResponseBody {
+            errors: multiple().into_body_errors(),
+        };
+        assert_eq!(
+            serde_json::to_value(body).unwrap(),
+            json!({
+                "errors": [
+                    {
+                        "category": "INVALID_REQUEST_ERROR",
+                        "code": "SPENDING_LIMIT_EXCEEDED",
+                        "detail": "over the daily limit",
+                    },
+                    {
+                        "category": "INVALID_REQUEST_ERROR",
+                        "code": "MOBILE_PAY_DISABLED",
+                    },
+                    {
+                        "category": "INVALID_REQUEST_ERROR",
+                        "code": "NOT_FOUND",
+                        "detail": "no keyset",
+                    },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn multiple_status_is_most_severe_regardless_of_order() {
+        assert_eq!(multiple().into_response().status(), StatusCode::FORBIDDEN);
+
+        let errors = vec![
+            ApiError::GenericBadRequest("bad".to_string()),
+            ApiError::GenericInternalApplicationError("broken".to_string()),
+            ApiError::GenericServiceUnavailable("down".to_string()),
+            ApiError::GenericUnauthorized("who".to_string()),
+        ];
+        for rotation in 0..errors.len() {
+            let mut errors = errors.clone();
+            errors.rotate_left(rotation);
+            assert_eq!(
+                ApiError::Multiple(errors).into_response().status(),
+                StatusCode::INTERNAL_SERVER_ERROR
+            );
+        }
+    }
+
+    #[test]
+    fn empty_multiple_is_internal_error() {
+        let response = ApiError::Multiple(vec![]).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -44,10 +44,7 @@ where
     T: Deserialize<'a>,
 {
     // TODO: replace ROCKET_ with SERVER_
-    let profile = profile
-        .map(|p| p.into())
-        .or_else(|| figment::Profile::from_env("ROCKET_PROFILE"))
-        .unwrap_or(DEFAULT_PROFILE);
+    let profile = selected_profile(profile);
     let config = Figment::new()
         .merge(Toml::file(Env::var_or("ROCKET_CONFIG", "Rocket.toml")).nested())
         .merge(Env::prefixed("ROCKET_").ignore(&["PROFILE"]).global())
@@ -57,3 +54,11 @@ where
 
     Ok(config)
 }
+
+/// The profile `extract` selects the configuration for, given its `profile` argument.
+pub fn selected_profile(profile: Option<&str>) -> figment::Profile {
+    profile
+        .map(|p| p.into())
+        .or_else(|| figment::Profile::from_env("ROCKET_PROFILE"))
+        .unwrap_or(DEFAULT_PROFILE)
+}
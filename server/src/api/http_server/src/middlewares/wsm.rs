@@ -2,16 +2,48 @@ use serde::Deserialize;
 
 use wsm_rust_client::WsmClient;
 
+use crate::config::selected_profile;
+
 pub use wsm_rust_client::Error;
 
 #[derive(Deserialize)]
 pub struct Config {
     pub wsm_endpoint: String,
+    #[serde(default)]
+    pub wsm: WsmMode,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase", tag = "mode")]
+pub enum WsmMode {
+    /// Talk to wsm-api at `wsm_endpoint`
+    #[default]
+    Remote,
+    /// Sign in-process with keys derived from `seed`; no enclave, KMS or AWS needed
+    Local { seed: Option<String> },
 }
 
+const DEFAULT_LOCAL_SEED: &str = "local-wsm";
+
+/// Anyone can derive the keys for the default local seed, so it's only used by these profiles.
+const DEFAULT_LOCAL_SEED_PROFILES: [&str; 2] = ["test", "debug"];
+
 impl Config {
-    pub fn to_client(self) -> Result<Service, Error> {
-        let client = WsmClient::new(&self.wsm_endpoint)?;
+    /// `profile` is the one the config was extracted with.
+    pub fn to_client(self, profile: Option<&str>) -> Result<Service, Error> {
+        let client = match self.wsm {
+            WsmMode::Remote => WsmClient::new(&self.wsm_endpoint)?,
+            WsmMode::Local { seed: Some(seed) } => WsmClient::local(&seed),
+            WsmMode::Local { seed: None } => {
+                let profile = selected_profile(profile);
+                if !DEFAULT_LOCAL_SEED_PROFILES.contains(&profile.as_str().as_str()) {
+                    return Err(Error::Local(format!(
+                        "a seed must be configured for the local WSM in the {profile} profile"
+                    )));
+                }
+                WsmClient::local(DEFAULT_LOCAL_SEED)
+            }
+        };
         Ok(Service { client })
     }
 }
@@ -20,3 +52,28 @@ impl Config {
 pub struct Service {
     pub client: WsmClient,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, WsmMode};
+
+    fn local_config(seed: Option<&str>) -> Config {
+        Config {
+            wsm_endpoint: "http://localhost:9090".to_string(),
+            wsm: WsmMode::Local {
+                seed: seed.map(String::from),
+            },
+        }
+    }
+
+    #[test]
+    fn default_local_seed_is_limited_to_test_profiles() {
+        assert!(local_config(None).to_client(Some("test")).is_ok());
+        assert!(local_config(None).to_client(Some("debug")).is_ok());
+        assert!(local_config(None).to_client(Some("development")).is_err());
+        assert!(local_config(None).to_client(Some("production")).is_err());
+        assert!(local_config(Some("seed"))
+            .to_client(Some("development"))
+            .is_ok());
+    }
+}
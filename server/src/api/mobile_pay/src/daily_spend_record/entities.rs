@@ -8,9 +8,9 @@ use bdk_utils::AttributableWallet;
 use types::account::identifiers::AccountId;
 use types::serde::{deserialize_ts, serialize_ts};
 
-use crate::util::{get_total_outflow_for_psbt, MobilepayDatetimeError};
+use crate::util::{get_fee_for_psbt, get_total_outflow_for_psbt, MobilepayDatetimeError};
 
-const RETENTION_DAYS: i64 = 30;
+pub(crate) const RETENTION_DAYS: i64 = 30;
 
 /// Uniquely defines a transaction in `DailySpendingRecord` so we can avoid updating a spending list with a transaction that is already accounted for
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -70,7 +70,10 @@ impl DailySpendingRecord {
             self.spending_entries.push(SpendingEntry {
                 txid: tx.txid(),
                 timestamp: OffsetDateTime::now_utc(),
-                outflow_amount: get_total_outflow_for_psbt(wallet, psbt),
+                // Fees count towards spending limits. PSBTs whose fee can't be calculated never
+                // pass the spend rules, so there is no fee to record for them.
+                outflow_amount: get_total_outflow_for_psbt(wallet, psbt)
+                    + get_fee_for_psbt(psbt).unwrap_or_default(),
             });
             if self.spending_entries.len() > 2000 {
                 // NB: DDB max item size is 400kb
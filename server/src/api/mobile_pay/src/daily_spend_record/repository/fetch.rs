@@ -2,7 +2,10 @@ use time::Date;
 use tracing::{event, instrument, Level};
 
 use database::aws_sdk_dynamodb::error::ProvideErrorMetadata;
-use database::ddb::{try_from_item, try_to_attribute_val, DDBService, DatabaseError};
+use database::aws_sdk_dynamodb::types::AttributeValue;
+use database::ddb::{
+    try_from_item, try_from_items, try_to_attribute_val, DDBService, DatabaseError,
+};
 use types::account::identifiers::AccountId;
 
 use crate::daily_spend_record::entities::DailySpendingRecord;
@@ -51,4 +54,66 @@ impl Repository {
         })?;
         try_from_item(item, database_object)
     }
+
+    /// Returns the account's records dated between `start_date` and `end_date`, inclusive.
+    #[instrument(skip(self))]
+    pub(crate) async fn fetch_between(
+        &self,
+        id: &AccountId,
+        start_date: Date,
+        end_date: Date,
+    ) -> Result<Vec<DailySpendingRecord>, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let account_id_attr: AttributeValue = try_to_attribute_val(id, database_object)?;
+        let start_date_attr: AttributeValue =
+            try_to_attribute_val(start_date.to_string(), database_object)?;
+        let end_date_attr: AttributeValue =
+            try_to_attribute_val(end_date.to_string(), database_object)?;
+
+        let mut exclusive_start_key = None;
+        let mut results = Vec::new();
+
+        loop {
+            let item_output = self
+                .connection
+                .client
+                .query()
+                .table_name(table_name.clone())
+                .set_exclusive_start_key(exclusive_start_key)
+                .key_condition_expression(
+                    "#account_id = :account_id and #date between :start_date and :end_date",
+                )
+                .expression_attribute_names("#account_id", PARTITION_KEY)
+                .expression_attribute_names("#date", SORT_KEY)
+                .expression_attribute_values(":account_id", account_id_attr.clone())
+                .expression_attribute_values(":start_date", start_date_attr.clone())
+                .expression_attribute_values(":end_date", end_date_attr.clone())
+                .send()
+                .await
+                .map_err(|err| {
+                    let service_err = err.into_service_error();
+                    event!(
+                        Level::ERROR,
+                        "Could not query database: {service_err:?} with message: {:?}",
+                        service_err.message()
+                    );
+                    DatabaseError::FetchError(database_object)
+                })?;
+
+            results.append(&mut try_from_items(
+                item_output.items().to_owned(),
+                database_object,
+            )?);
+
+            if let Some(last_evaluated_key) = item_output.last_evaluated_key() {
+                exclusive_start_key = Some(last_evaluated_key.to_owned());
+            } else {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
 }
@@ -42,6 +42,20 @@ impl Service {
         }
     }
 
+    /// Fetches the account's existing records dated between `start_date` and `end_date`, inclusive.
+    #[instrument(skip(self))]
+    pub async fn fetch_daily_spending_records_between(
+        &self,
+        account_id: &AccountId,
+        start_date: Date,
+        end_date: Date,
+    ) -> Result<Vec<DailySpendingRecord>, ApiError> {
+        Ok(self
+            .repo
+            .fetch_between(account_id, start_date, end_date)
+            .await?)
+    }
+
     #[instrument(err, skip(self))]
     pub async fn save_daily_spending_record(
         &self,
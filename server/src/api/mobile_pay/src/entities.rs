@@ -7,6 +7,10 @@ use account::spend_limit::SpendingLimit;
 pub struct Features {
     pub settings: Settings,
     pub daily_limit_sats: u64,
+    /// Fee rate, in sats per vbyte, currently recommended for quick confirmation, if it could be
+    /// estimated.
+    pub recommended_fee_rate: Option<f32>,
+    pub fee_limits: FeeLimits,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
@@ -15,3 +19,23 @@ pub struct Features {
 pub struct Settings {
     pub limit: SpendingLimit,
 }
+
+/// Bounds on the fee a Mobile Pay transaction may pay, so that a compromised app key can't burn
+/// the spending limit on fees.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct FeeLimits {
+    /// How many times the recommended fee rate a transaction may pay.
+    pub max_fee_rate_multiple: f32,
+    /// The largest fee a transaction may pay, as a fraction of the amount it sends.
+    pub max_fee_ratio: f32,
+}
+
+impl Default for FeeLimits {
+    fn default() -> Self {
+        Self {
+            max_fee_rate_multiple: 3.0,
+            max_fee_ratio: 0.1,
+        }
+    }
+}
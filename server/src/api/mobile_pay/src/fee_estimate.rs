@@ -0,0 +1,236 @@
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use thiserror::Error;
+
+use bdk_utils::bdk::bitcoin::Network;
+use errors::ApiError;
+
+const MEMPOOL_SPACE_URL: &str = "https://bitkey.mempool.space";
+
+#[derive(Deserialize)]
+pub struct Config {
+    pub fee_estimator: FeeEstimatorMode,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "lowercase", tag = "mode")]
+pub enum FeeEstimatorMode {
+    Test,
+    Mempool,
+}
+
+impl Config {
+    pub fn to_fee_estimator(&self) -> Arc<dyn FeeEstimatorTrait> {
+        match self.fee_estimator {
+            FeeEstimatorMode::Test => Arc::new(LocalFeeEstimator::new()),
+            FeeEstimatorMode::Mempool => Arc::new(MempoolFeeEstimator::new()),
+        }
+    }
+}
+
+/// Fee rates, in sats per vbyte, as returned by mempool.space's `/api/v1/fees/recommended`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendedFees {
+    pub fastest_fee: f32,
+    pub half_hour_fee: f32,
+    pub hour_fee: f32,
+    pub economy_fee: f32,
+    pub minimum_fee: f32,
+}
+
+#[derive(Debug, Error)]
+pub enum FeeEstimateError {
+    #[error("Fee estimates are not available for network {0}")]
+    UnsupportedNetwork(Network),
+    #[error("Could not fetch fee estimates: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+impl From<FeeEstimateError> for ApiError {
+    fn from(value: FeeEstimateError) -> Self {
+        let err_msg = value.to_string();
+        match value {
+            FeeEstimateError::UnsupportedNetwork(_) => {
+                ApiError::GenericInternalApplicationError(err_msg)
+            }
+            FeeEstimateError::Request(_) => ApiError::GenericServiceUnavailable(err_msg),
+        }
+    }
+}
+
+#[async_trait]
+pub trait FeeEstimatorTrait: Send + Sync {
+    async fn recommended_fees(&self, network: Network)
+        -> Result<RecommendedFees, FeeEstimateError>;
+}
+
+impl fmt::Debug for dyn FeeEstimatorTrait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Trait object of FeeEstimatorTrait")
+    }
+}
+
+/// Fetches fee estimates from a mempool.space instance.
+#[derive(Clone)]
+pub struct MempoolFeeEstimator {
+    http_client: Client,
+    base_url: String,
+}
+
+impl Default for MempoolFeeEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MempoolFeeEstimator {
+    pub fn new() -> Self {
+        Self {
+            http_client: Client::new(),
+            base_url: MEMPOOL_SPACE_URL.to_string(),
+        }
+    }
+
+    pub fn set_mock_server(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    fn recommended_fees_url(&self, network: Network) -> Result<String, FeeEstimateError> {
+        let network_path = match network {
+            Network::Bitcoin => "",
+            Network::Testnet => "/testnet",
+            Network::Signet => "/signet",
+            _ => return Err(FeeEstimateError::UnsupportedNetwork(network)),
+        };
+        Ok(format!(
+            "{}{network_path}/api/v1/fees/recommended",
+            self.base_url
+        ))
+    }
+}
+
+#[async_trait]
+impl FeeEstimatorTrait for MempoolFeeEstimator {
+    async fn recommended_fees(
+        &self,
+        network: Network,
+    ) -> Result<RecommendedFees, FeeEstimateError> {
+        Ok(self
+            .http_client
+            .get(self.recommended_fees_url(network)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<RecommendedFees>()
+            .await?)
+    }
+}
+
+/// Serves fixed fee estimates on every network, for environments without access to mempool.space.
+#[derive(Clone)]
+pub struct LocalFeeEstimator {
+    fees: RecommendedFees,
+}
+
+impl Default for LocalFeeEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalFeeEstimator {
+    pub fn new() -> Self {
+        Self::with_fees(RecommendedFees {
+            fastest_fee: 10.0,
+            half_hour_fee: 8.0,
+            hour_fee: 6.0,
+            economy_fee: 3.0,
+            minimum_fee: 1.0,
+        })
+    }
+
+    pub fn with_fees(fees: RecommendedFees) -> Self {
+        Self { fees }
+    }
+}
+
+#[async_trait]
+impl FeeEstimatorTrait for LocalFeeEstimator {
+    async fn recommended_fees(
+        &self,
+        _network: Network,
+    ) -> Result<RecommendedFees, FeeEstimateError> {
+        Ok(self.fees.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mempool_recommended_fees() {
+        let mock_server = MockServer::start().await;
+        let fee_estimator = MempoolFeeEstimator::new().set_mock_server(mock_server.uri());
+        let response_body = r#"{
+            "fastestFee": 25,
+            "halfHourFee": 20,
+            "hourFee": 15,
+            "economyFee": 5,
+            "minimumFee": 1
+        }"#;
+
+        Mock::given(method("GET"))
+            .and(path("/signet/api/v1/fees/recommended"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(response_body, "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let fees = fee_estimator
+            .recommended_fees(Network::Signet)
+            .await
+            .unwrap();
+        assert_eq!(
+            fees,
+            RecommendedFees {
+                fastest_fee: 25.0,
+                half_hour_fee: 20.0,
+                hour_fee: 15.0,
+                economy_fee: 5.0,
+                minimum_fee: 1.0,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mempool_recommended_fees_unavailable() {
+        let mock_server = MockServer::start().await;
+        let fee_estimator = MempoolFeeEstimator::new().set_mock_server(mock_server.uri());
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/fees/recommended"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        assert!(matches!(
+            fee_estimator.recommended_fees(Network::Bitcoin).await,
+            Err(FeeEstimateError::Request(_))
+        ));
+        assert!(matches!(
+            fee_estimator.recommended_fees(Network::Regtest).await,
+            Err(FeeEstimateError::UnsupportedNetwork(Network::Regtest))
+        ));
+    }
+}
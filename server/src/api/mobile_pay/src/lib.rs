@@ -2,6 +2,7 @@ use feature_flags::flag::Flag;
 
 pub mod daily_spend_record;
 pub mod entities;
+pub mod fee_estimate;
 pub(crate) mod metrics;
 pub mod routes;
 pub mod signed_psbt_cache;
@@ -14,16 +14,24 @@ use tracing::{error, event, instrument, Level};
 use userpool::userpool::UserPoolService;
 use utoipa::{OpenApi, ToSchema};
 
+use account::service::FetchAndUpdateSpendPolicyInput;
 use account::service::FetchAndUpdateSpendingLimitInput;
+use account::service::RecordSecurityEventInput;
 use account::service::{FetchAccountInput, Service as AccountService};
-use account::spend_limit::{Money, SpendingLimit};
-use authn_authz::key_claims::KeyClaims;
+use account::spend_limit::{Money, SpendWindow, SpendingLimit};
+use account::spend_policy::{SpendPeriod, SpendPolicyRule};
+use authn_authz::key_claims::{KeyClaims, RequiredFactors};
 use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction as Psbt;
-use bdk_utils::bdk::SignOptions;
+use bdk_utils::bdk::bitcoin::{Address, Network};
+use bdk_utils::bdk::database::AnyDatabase;
+use bdk_utils::bdk::{SignOptions, Wallet};
 use bdk_utils::generate_electrum_rpc_uris;
 use bdk_utils::{DescriptorKeyset, TransactionBroadcasterTrait};
+use chain_indexer::service::Service as ChainIndexerService;
 use errors::ErrorCode::NoSpendingLimitExists;
-use errors::{ApiError, RouteError};
+use errors::{
+    ApiError, ErrorCategory, ErrorCode, ErrorResponseBody, ErrorResponseBodyError, RouteError,
+};
 use exchange_rate::currency_conversion::sats_for;
 use exchange_rate::flags::FLAG_USE_CASH_EXCHANGE_RATE_PROVIDER;
 use exchange_rate::service::Service as ExchangeRateService;
@@ -37,19 +45,28 @@ use types::currencies::CurrencyCode::BTC;
 use types::exchange_rate::bitstamp::BitstampRateProvider;
 use types::exchange_rate::cash::CashAppRateProvider;
 use types::exchange_rate::local_rate_provider::LocalRateProvider;
+use types::security_event::SecurityEventType;
 use wsm_rust_client::{SigningService, WsmClient};
 
-use crate::daily_spend_record::entities::{DailySpendingRecord, SpendingEntry};
+use crate::daily_spend_record::entities::{DailySpendingRecord, SpendingEntry, RETENTION_DAYS};
 use crate::daily_spend_record::service::Service as DailySpendRecordService;
-use crate::entities::{Features, Settings};
+use crate::entities::{Features, FeeLimits, Settings};
+use crate::fee_estimate::FeeEstimatorTrait;
 use crate::signed_psbt_cache::service::Service as SignedPsbtCacheService;
-use crate::spend_rules::SpendRuleSet;
-use crate::util::total_sats_spent_today;
+use crate::spend_rules::allowlisted_destinations_rule::AllowlistedDestinationsRule;
+use crate::spend_rules::max_fee_rate_rule::MaxFeeRateRule;
+use crate::spend_rules::per_transaction_cap_rule::PerTransactionCapRule;
+use crate::spend_rules::period_spend_cap_rule::PeriodSpendCapRule;
+use crate::spend_rules::velocity_rule::VelocityRule;
+use crate::spend_rules::{Rule, SpendRuleSet};
+use crate::util::{total_sats_spent_since, total_sats_spent_today};
 use crate::{metrics as mobile_pay_metrics, FLAG_MOBILE_PAY_ENABLED};
 
 #[derive(Clone, Deserialize)]
 pub struct Config {
     pub use_local_currency_exchange: bool,
+    #[serde(default)]
+    pub fee_limits: FeeLimits,
 }
 
 #[derive(Clone, axum_macros::FromRef)]
@@ -64,6 +81,8 @@ pub struct RouteState(
     pub SignedPsbtCacheService,
     pub FeatureFlagsService,
     pub Arc<ScreenerService>,
+    pub Arc<dyn FeeEstimatorTrait>,
+    pub ChainIndexerService,
 );
 
 impl From<RouteState> for Router {
@@ -89,6 +108,14 @@ impl From<RouteState> for Router {
                 "/api/accounts/:account_id/mobile-pay",
                 delete(delete_mobile_pay_for_account),
             )
+            .route(
+                "/api/accounts/:account_id/mobile-pay/spend-policy",
+                put(put_spend_policy_for_account),
+            )
+            .route(
+                "/api/accounts/:account_id/mobile-pay/spend-policy",
+                get(get_spend_policy_for_account),
+            )
             .route_layer(
                 mobile_pay_metrics::FACTORY
                     .route_layer(mobile_pay_metrics::FACTORY_NAME.to_owned()),
@@ -113,9 +140,12 @@ impl From<RouteState> for SwaggerEndpoint {
         sign_transaction_with_keyset,
         setup_mobile_pay_for_account,
         get_mobile_pay_for_account,
+        put_spend_policy_for_account,
+        get_spend_policy_for_account,
     ),
     components(
-        schemas(CurrencyCode, SpendingLimit, Settings, Money, MobilePaySetupRequest, MobilePaySetupResponse, SignTransactionData, SignTransactionResponse)
+        schemas(CurrencyCode, SpendingLimit, Settings, Money, MobilePaySetupRequest, MobilePaySetupResponse, GetMobilePayResponse, MobilePayConfiguration, SpendAllowance, AllowancePeriod, SpendWindow, SignTransactionData, SignTransactionResponse, SpendPeriod, SpendPolicyRule, SpendPolicyRequest, SpendPolicyResponse),
+        schemas(ErrorCategory, ErrorCode, ErrorResponseBody, ErrorResponseBodyError)
     ),
     tags(
         (name = "Mobile Pay", description = "Spend Limits & Transaction Signing")
@@ -144,7 +174,9 @@ pub struct SignTransactionResponse {
         exchange_rate_service,
         feature_flags_service,
         screener_service,
-        transaction_broadcaster
+        transaction_broadcaster,
+        fee_estimator,
+        chain_indexer_service
     ),
     fields(keyset_id, active_keyset_id)
 )]
@@ -160,6 +192,8 @@ async fn sign_transaction_maybe_broadcast_impl(
     signed_psbt_cache_service: SignedPsbtCacheService,
     feature_flags_service: FeatureFlagsService,
     screener_service: Arc<ScreenerService>,
+    fee_estimator: Arc<dyn FeeEstimatorTrait>,
+    chain_indexer_service: ChainIndexerService,
 ) -> Result<SignTransactionResponse, ApiError> {
     // At the earliest opportunity, we block the request if mobile pay is disabled by feature flag.
     let is_mobile_pay_enabled = FLAG_MOBILE_PAY_ENABLED
@@ -169,7 +203,7 @@ async fn sign_transaction_maybe_broadcast_impl(
     if !is_mobile_pay_enabled {
         let msg = "Signing with Bitkey's servers is currently disabled.";
         error!("{msg}");
-        return Err(ApiError::GenericForbidden(msg.to_string()));
+        return Err(ApiError::specific(ErrorCode::MobilePayDisabled, msg));
     }
 
     let signing_start_time = OffsetDateTime::now_utc();
@@ -215,7 +249,7 @@ async fn sign_transaction_maybe_broadcast_impl(
         if !full_account.is_spending_limit_active() {
             let msg = "Attempted to sign with Mobile Pay when user has Mobile Pay turned off.";
             error!("{msg}");
-            return Err(ApiError::GenericForbidden(msg.to_string()));
+            return Err(ApiError::specific(ErrorCode::MobilePayNotActive, msg));
         }
         let limit = full_account
             .spending_limit
@@ -236,25 +270,61 @@ async fn sign_transaction_maybe_broadcast_impl(
         // bundle up yesterday and today's spending records for spend rule checking
         let spending_entries = mobile_pay_spending_record.spending_entries();
 
+        // Fee estimates are best-effort, so fall back to the static fee limits without one
+        let recommended_fee_rate = match fee_estimator
+            .recommended_fees(unsynced_source_wallet.network())
+            .await
+        {
+            Ok(recommended_fees) => Some(recommended_fees.fastest_fee),
+            Err(e) => {
+                event!(
+                    Level::WARN,
+                    "Could not get recommended fees, only checking the fee against the amount sent: {e}"
+                );
+                None
+            }
+        };
+
         let features = Features {
             settings: Settings { limit },
             daily_limit_sats,
+            recommended_fee_rate,
+            fee_limits: config.fee_limits.clone(),
         };
 
+        let spend_policy_limits_sats = spend_policy_limits_in_sats(
+            &full_account.spend_policy,
+            &config,
+            &exchange_rate_service,
+            &feature_flags_service,
+        )
+        .await?;
+
         SpendRuleSet::mobile_pay(
             &unsynced_source_wallet,
             &features,
             &spending_entries,
             screener_service,
         )
+        .with_rules(spend_policy_rules(
+            &full_account.spend_policy,
+            spend_policy_limits_sats,
+            &unsynced_source_wallet,
+            &spending_entries,
+        )?)
         .check_spend_rules(&psbt)
-        .map_err(|reasons| {
-            let error_message = format!(
+        .map_err(|violations| {
+            event!(
+                Level::INFO,
                 "Transaction failed to pass mobile pay spend rules: {}",
-                reasons.first().expect("should be at least one error")
+                violations
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
             );
-            event!(Level::INFO, error_message);
-            ApiError::GenericBadRequest(error_message)
+            // Report every violated rule so clients can explain all of them at once
+            ApiError::Multiple(violations.into_iter().map(ApiError::from).collect())
         })?;
 
         let mut today_spending_record = mobile_pay_spending_record.today;
@@ -276,13 +346,18 @@ async fn sign_transaction_maybe_broadcast_impl(
 
         SpendRuleSet::sweep(&unsynced_source_wallet, &active_wallet, screener_service)
             .check_spend_rules(&psbt)
-            .map_err(|reasons| {
-                let error_message = format!(
+            .map_err(|violations| {
+                event!(
+                    Level::INFO,
                     "Transaction failed to pass sweep spend rules: {}",
-                    reasons.first().expect("should be at least one error")
+                    violations
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
                 );
-                event!(Level::INFO, error_message);
-                ApiError::GenericBadRequest(error_message)
+                // Report every violated rule so clients can explain all of them at once
+                ApiError::Multiple(violations.into_iter().map(ApiError::from).collect())
             })?;
 
         None
@@ -341,6 +416,22 @@ async fn sign_transaction_maybe_broadcast_impl(
     // retries the request, we can return the same signed psbt
     signed_psbt_cache_service.put(signed_psbt.clone()).await?;
 
+    // Mobile Pay spends are notified once confirmed. A retried request is served from the cache
+    // above, so failing here would leave the spend untracked for good; we log instead.
+    if is_mobile_pay {
+        let txid = signed_psbt.unsigned_tx.txid();
+        if let Err(e) = chain_indexer_service
+            .track_outgoing_payment(&full_account.id, &txid)
+            .await
+        {
+            event!(
+                Level::ERROR,
+                "Could not track outgoing payment {txid} for account {}: {e}",
+                full_account.id
+            );
+        }
+    }
+
     if psbt_fully_signed {
         let broadcast_start_time = OffsetDateTime::now_utc();
         transaction_broadcaster.broadcast(unsynced_source_wallet, &mut signed_psbt, &rpc_uris)?;
@@ -368,7 +459,9 @@ async fn sign_transaction_maybe_broadcast_impl(
         signed_psbt_cache_service,
         request,
         feature_flags_service,
-        screener_service
+        screener_service,
+        fee_estimator,
+        chain_indexer_service
     )
 )]
 #[utoipa::path(
@@ -381,8 +474,8 @@ async fn sign_transaction_maybe_broadcast_impl(
     request_body = SignTransactionData,
     responses(
         (status = 200, description = "Transaction was validated and signed with the server key in the specified keyset", body=SignTransactionResponse),
-        (status = 400, description = "Transaction didn't pass spend rules"),
-        (status = 404, description = "Account could not be found")
+        (status = 400, description = "Transaction didn't pass spend rules", body = ErrorResponseBody),
+        (status = 404, description = "Account could not be found", body = ErrorResponseBody)
     ),
 )]
 async fn sign_transaction_with_keyset(
@@ -396,6 +489,8 @@ async fn sign_transaction_with_keyset(
     State(signed_psbt_cache_service): State<SignedPsbtCacheService>,
     State(feature_flags_service): State<FeatureFlagsService>,
     State(screener_service): State<Arc<ScreenerService>>,
+    State(fee_estimator): State<Arc<dyn FeeEstimatorTrait>>,
+    State(chain_indexer_service): State<ChainIndexerService>,
     Json(request): Json<SignTransactionData>,
 ) -> Result<Json<SignTransactionResponse>, ApiError> {
     let full_account = account_service
@@ -416,6 +511,8 @@ async fn sign_transaction_with_keyset(
         signed_psbt_cache_service,
         feature_flags_service,
         screener_service,
+        fee_estimator,
+        chain_indexer_service,
     )
     .await?;
 
@@ -434,7 +531,9 @@ async fn sign_transaction_with_keyset(
         signed_psbt_cache_service,
         request,
         screener_service,
-        feature_flags_service
+        feature_flags_service,
+        fee_estimator,
+        chain_indexer_service
     )
 )]
 #[utoipa::path(
@@ -446,8 +545,8 @@ async fn sign_transaction_with_keyset(
     request_body = SignTransactionData,
     responses(
         (status = 200, description = "Transaction was validated and signed with the server key for the active keyset", body=SignTransactionResponse),
-        (status = 400, description = "Transaction didn't pass spend rules"),
-        (status = 404, description = "Account could not be found")
+        (status = 400, description = "Transaction didn't pass spend rules", body = ErrorResponseBody),
+        (status = 404, description = "Account could not be found", body = ErrorResponseBody)
     ),
 )]
 #[deprecated(note = "Use /api/accounts/{account_id}/keysets/{keyset_id}/sign-transaction instead")]
@@ -462,6 +561,8 @@ async fn sign_transaction_with_active_keyset(
     State(signed_psbt_cache_service): State<SignedPsbtCacheService>,
     State(feature_flags_service): State<FeatureFlagsService>,
     State(screener_service): State<Arc<ScreenerService>>,
+    State(fee_estimator): State<Arc<dyn FeeEstimatorTrait>>,
+    State(chain_indexer_service): State<ChainIndexerService>,
     Json(request): Json<SignTransactionData>,
 ) -> Result<Json<SignTransactionResponse>, ApiError> {
     let full_account = account_service
@@ -482,6 +583,8 @@ async fn sign_transaction_with_active_keyset(
         signed_psbt_cache_service,
         feature_flags_service,
         screener_service,
+        fee_estimator,
+        chain_indexer_service,
     )
     .await?;
     Ok(Json(response))
@@ -506,7 +609,7 @@ pub struct MobilePaySetupResponse {}
     request_body = MobilePaySetupRequest,
     responses(
         (status = 200, description = "Mobile Pay Spend Limit was successfully set", body=MobilePaySetupResponse),
-        (status = 404, description = "Account could not be found")
+        (status = 404, description = "Account could not be found", body = ErrorResponseBody)
     ),
 )]
 async fn setup_mobile_pay_for_account(
@@ -525,21 +628,36 @@ async fn setup_mobile_pay(
     key_proof: KeyClaims,
     request: MobilePaySetupRequest,
 ) -> Result<Json<MobilePaySetupResponse>, ApiError> {
-    if !(key_proof.hw_signed && key_proof.app_signed) {
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
         event!(
             Level::WARN,
             "valid signature over access token required by both app and hw auth keys"
         );
-        return Err(ApiError::GenericForbidden(
-            "valid signature over access token required by both app and hw auth keys".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            "valid signature over access token required by both app and hw auth keys",
         ));
     }
 
+    let event = if request.limit.active {
+        SecurityEventType::SpendingLimitUpdated {
+            amount: request.limit.amount.amount,
+            currency_code: request.limit.amount.currency_code.clone(),
+        }
+    } else {
+        SecurityEventType::SpendingLimitDisabled
+    };
     account_service
-        .fetch_and_update_spend_limit(FetchAndUpdateSpendingLimitInput {
-            account_id: &account_id,
-            new_spending_limit: Some(request.limit),
-        })
+        .record_security_event(
+            RecordSecurityEventInput {
+                account_id: &account_id,
+                event,
+            },
+            account_service.fetch_and_update_spend_limit(FetchAndUpdateSpendingLimitInput {
+                account_id: &account_id,
+                new_spending_limit: Some(request.limit),
+            }),
+        )
         .await?;
 
     Ok(Json(MobilePaySetupResponse {}))
@@ -592,6 +710,7 @@ impl Default for GetMobilePayResponse {
                     currency_code: BTC,
                 },
                 time_zone_offset: time::UtcOffset::UTC,
+                window: SpendWindow::Calendar,
             },
             mobile_pay: None,
         }
@@ -607,6 +726,61 @@ pub struct MobilePayConfiguration {
     pub available: Money,
     /// The configured Mobile Pay limit the user has set.
     pub limit: SpendingLimit,
+    /// What is left of each limit enforced on the account, including the caps in its spend
+    /// policy.
+    #[serde(default)]
+    pub allowances: Vec<SpendAllowance>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct SpendAllowance {
+    pub period: AllowancePeriod,
+    /// The limit, converted to sats.
+    pub limit: Money,
+    /// Amount spent over the period. Always zero for per-transaction limits.
+    pub spent: Money,
+    /// Amount that can still be spent over the period.
+    pub available: Money,
+}
+
+impl SpendAllowance {
+    fn new(period: AllowancePeriod, limit_sats: u64, spent_sats: u64) -> Self {
+        Self {
+            period,
+            limit: Money {
+                amount: limit_sats,
+                currency_code: BTC,
+            },
+            spent: Money {
+                amount: spent_sats,
+                currency_code: BTC,
+            },
+            available: Money {
+                amount: limit_sats.saturating_sub(spent_sats),
+                currency_code: BTC,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AllowancePeriod {
+    Daily,
+    Weekly,
+    Monthly,
+    PerTransaction,
+}
+
+impl From<SpendPeriod> for AllowancePeriod {
+    fn from(value: SpendPeriod) -> Self {
+        match value {
+            SpendPeriod::Daily => AllowancePeriod::Daily,
+            SpendPeriod::Weekly => AllowancePeriod::Weekly,
+            SpendPeriod::Monthly => AllowancePeriod::Monthly,
+        }
+    }
 }
 
 #[instrument(
@@ -626,8 +800,8 @@ pub struct MobilePayConfiguration {
         ("account_id" = AccountId, Path, description = "AccountId"),
     ),
     responses(
-        (status = 200, description = "The account's Mobile Pay settings are returned", body=MobilePaySetupResponse),
-        (status = 404, description = "Account or mobile pay settings not found")
+        (status = 200, description = "The account's Mobile Pay settings are returned", body=GetMobilePayResponse),
+        (status = 404, description = "Account or mobile pay settings not found", body = ErrorResponseBody)
     ),
 )]
 async fn get_mobile_pay_for_account(
@@ -649,32 +823,60 @@ async fn get_mobile_pay_for_account(
         return Ok(Json(GetMobilePayResponse::default()));
     };
 
-    let limit_in_sats = match limit.amount.currency_code {
-        BTC => limit.clone(),
-        _ => SpendingLimit {
-            active: limit.active,
-            amount: Money {
-                amount: sats_for_limit(
-                    &limit,
-                    &config,
-                    &exchange_rate_service,
-                    &feature_flags_service,
-                )
-                .await?,
-                currency_code: BTC,
-            },
-            time_zone_offset: limit.time_zone_offset,
-        },
-    };
+    let limit_sats = sats_for_limit(
+        &limit,
+        &config,
+        &exchange_rate_service,
+        &feature_flags_service,
+    )
+    .await?;
+    let spend_policy_limits_sats = spend_policy_limits_in_sats(
+        &full_account.spend_policy,
+        &config,
+        &exchange_rate_service,
+        &feature_flags_service,
+    )
+    .await?;
 
     let spending_history =
         get_mobile_pay_spending_record(&account_id, &daily_spend_record_service).await?;
-    let total_spent = total_sats_spent_today(
-        &spending_history.spending_entries(),
-        &limit,
-        OffsetDateTime::now_utc(),
+    let spending_entries = spending_history.spending_entries();
+    let now_utc = OffsetDateTime::now_utc();
+    let total_spent = total_sats_spent_today(&spending_entries, &limit, now_utc)
+        .map_err(ApiError::GenericBadRequest)?;
+
+    let allowances = std::iter::once(SpendAllowance::new(
+        AllowancePeriod::Daily,
+        limit_sats,
+        total_spent,
+    ))
+    .chain(
+        full_account
+            .spend_policy
+            .iter()
+            .zip(spend_policy_limits_sats)
+            .filter_map(|(rule, limit_sats)| match (rule, limit_sats) {
+                (SpendPolicyRule::PerTransactionCap { .. }, Some(limit_sats)) => Some(
+                    SpendAllowance::new(AllowancePeriod::PerTransaction, limit_sats, 0),
+                ),
+                (SpendPolicyRule::PeriodCap { period, .. }, Some(limit_sats)) => {
+                    Some(SpendAllowance::new(
+                        (*period).into(),
+                        limit_sats,
+                        total_sats_spent_since(&spending_entries, now_utc - period.duration()),
+                    ))
+                }
+                _ => None,
+            }),
     )
-    .map_err(ApiError::GenericBadRequest)?;
+    .collect::<Vec<_>>();
+
+    // Every limit is enforced when cosigning, so the tightest one bounds what can be spent today.
+    let available_sats = allowances
+        .iter()
+        .map(|allowance| allowance.available.amount)
+        .min()
+        .unwrap_or_default();
 
     let response = GetMobilePayResponse::new(Some(MobilePayConfiguration {
         spent: Money {
@@ -682,10 +884,11 @@ async fn get_mobile_pay_for_account(
             currency_code: BTC,
         },
         available: Money {
-            amount: limit_in_sats.amount.amount.saturating_sub(total_spent),
+            amount: available_sats,
             currency_code: BTC,
         },
         limit,
+        allowances,
     }));
 
     Ok(Json(response))
@@ -700,7 +903,7 @@ async fn get_mobile_pay_for_account(
     ),
     responses(
         (status = 200, description = "The account's Mobile Pay is disabled"),
-        (status = 404, description = "Account or mobile pay settings not found")
+        (status = 404, description = "Account or mobile pay settings not found", body = ErrorResponseBody)
     ),
 )]
 async fn delete_mobile_pay_for_account(
@@ -723,36 +926,191 @@ async fn delete_mobile_pay_for_account(
     })?;
 
     account_service
-        .fetch_and_update_spend_limit(FetchAndUpdateSpendingLimitInput {
+        .record_security_event(
+            RecordSecurityEventInput {
+                account_id: &account_id,
+                event: SecurityEventType::SpendingLimitDisabled,
+            },
+            account_service.fetch_and_update_spend_limit(FetchAndUpdateSpendingLimitInput {
+                account_id: &account_id,
+                new_spending_limit: Some(SpendingLimit {
+                    active: false,
+                    ..spending_limit
+                }),
+            }),
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SpendPolicyRequest {
+    pub rules: Vec<SpendPolicyRule>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct SpendPolicyResponse {
+    pub rules: Vec<SpendPolicyRule>,
+}
+
+#[instrument(err, skip(account_service, request))]
+#[utoipa::path(
+    put,
+    path = "/api/accounts/{account_id}/mobile-pay/spend-policy",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    request_body = SpendPolicyRequest,
+    responses(
+        (status = 200, description = "The account's spend policy was replaced", body=SpendPolicyResponse),
+        (status = 400, description = "One of the rules is invalid", body = ErrorResponseBody),
+        (status = 404, description = "Account could not be found", body = ErrorResponseBody)
+    ),
+)]
+async fn put_spend_policy_for_account(
+    Path(account_id): Path<AccountId>,
+    State(account_service): State<AccountService>,
+    key_proof: KeyClaims,
+    Json(request): Json<SpendPolicyRequest>,
+) -> Result<Json<SpendPolicyResponse>, ApiError> {
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
+        event!(
+            Level::WARN,
+            "valid signature over access token required by both app and hw auth keys"
+        );
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            "valid signature over access token required by both app and hw auth keys",
+        ));
+    }
+
+    let full_account = account_service
+        .fetch_full_account(FetchAccountInput {
             account_id: &account_id,
-            new_spending_limit: Some(SpendingLimit {
-                active: false,
-                ..spending_limit
+        })
+        .await?;
+    let network: Network = full_account
+        .active_spending_keyset()
+        .ok_or(RouteError::NoActiveSpendKeyset)?
+        .network
+        .into();
+    let rules = request
+        .rules
+        .into_iter()
+        .map(|rule| validate_spend_policy_rule(rule, network))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    account_service
+        .record_security_event(
+            RecordSecurityEventInput {
+                account_id: &account_id,
+                event: SecurityEventType::SpendPolicyUpdated {
+                    rule_count: rules.len(),
+                },
+            },
+            account_service.fetch_and_update_spend_policy(FetchAndUpdateSpendPolicyInput {
+                account_id: &account_id,
+                new_spend_policy: rules.clone(),
             }),
+        )
+        .await?;
+
+    Ok(Json(SpendPolicyResponse { rules }))
+}
+
+#[instrument(err, skip(account_service))]
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/mobile-pay/spend-policy",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    responses(
+        (status = 200, description = "The account's spend policy is returned", body=SpendPolicyResponse),
+        (status = 404, description = "Account could not be found", body = ErrorResponseBody)
+    ),
+)]
+async fn get_spend_policy_for_account(
+    Path(account_id): Path<AccountId>,
+    State(account_service): State<AccountService>,
+) -> Result<Json<SpendPolicyResponse>, ApiError> {
+    let full_account = account_service
+        .fetch_full_account(FetchAccountInput {
+            account_id: &account_id,
         })
         .await?;
 
-    Ok(())
+    Ok(Json(SpendPolicyResponse {
+        rules: full_account.spend_policy,
+    }))
+}
+
+/// Checks that a rule can be enforced on the given network, normalizing allowlisted addresses so
+/// they can be compared against the addresses of a transaction's outputs.
+fn validate_spend_policy_rule(
+    rule: SpendPolicyRule,
+    network: Network,
+) -> Result<SpendPolicyRule, ApiError> {
+    match rule {
+        SpendPolicyRule::AllowlistedDestinations { addresses } => {
+            let addresses = addresses
+                .iter()
+                .map(|address| {
+                    Address::from_str(address)
+                        .ok()
+                        .and_then(|address| address.require_network(network).ok())
+                        .map(|address| address.to_string())
+                        .ok_or_else(|| {
+                            invalid_spend_policy(format!(
+                                "Invalid allowlisted address {address} for network {network}"
+                            ))
+                        })
+                })
+                .collect::<Result<Vec<String>, ApiError>>()?;
+            Ok(SpendPolicyRule::AllowlistedDestinations { addresses })
+        }
+        SpendPolicyRule::MaxFeeRate { sats_per_vbyte } if sats_per_vbyte < 1.0 => Err(
+            invalid_spend_policy("Max fee rate must be at least 1 sat/vB".to_string()),
+        ),
+        SpendPolicyRule::Velocity {
+            max_transactions: 0,
+            ..
+        } => Err(invalid_spend_policy(
+            "Velocity limit must allow at least one transaction".to_string(),
+        )),
+        rule => Ok(rule),
+    }
+}
+
+fn invalid_spend_policy(detail: String) -> ApiError {
+    ApiError::Specific {
+        code: ErrorCode::InvalidSpendPolicy,
+        detail: Some(detail),
+        field: Some("rules".to_string()),
+    }
 }
+
 /// Data structure used to represent [`DailySpendingRecord`]s that are relevant to Mobile Pay.
 ///
 /// Currently, 3AM is the start of each Mobile Pay window, so "yesterday's" spending record may
-/// still be relevant. See [`get_mobile_pay_spending_record`] for more information.
+/// still be relevant, and spend policy rules may look back over every record we retain. See
+/// [`get_mobile_pay_spending_record`] for more information.
 struct MobilePaySpendingRecord {
-    yesterday: DailySpendingRecord,
+    previous: Vec<DailySpendingRecord>,
     today: DailySpendingRecord,
 }
 
 impl MobilePaySpendingRecord {
-    /// Returns a flattened list of [`SpendingEntry`] from yesterday and today.
+    /// Returns a flattened list of [`SpendingEntry`] from every retained day, including today.
     fn spending_entries(&self) -> Vec<&SpendingEntry> {
-        vec![
-            self.yesterday.get_spending_entries(),
-            self.today.get_spending_entries(),
-        ]
-        .into_iter()
-        .flatten()
-        .collect()
+        self.previous
+            .iter()
+            .chain(std::iter::once(&self.today))
+            .flat_map(|record| record.get_spending_entries())
+            .collect()
     }
 }
 
@@ -778,48 +1136,139 @@ async fn sats_for_limit(
     exchange_rate_service: &ExchangeRateService,
     feature_flags_service: &FeatureFlagsService,
 ) -> Result<u64, ApiError> {
+    sats_for_money(
+        &limit.amount,
+        config,
+        exchange_rate_service,
+        feature_flags_service,
+    )
+    .await
+}
+
+async fn sats_for_money(
+    money: &Money,
+    config: &Config,
+    exchange_rate_service: &ExchangeRateService,
+    feature_flags_service: &FeatureFlagsService,
+) -> Result<u64, ApiError> {
+    // Amounts denominated in BTC are already in sats.
+    if money.currency_code == BTC {
+        return Ok(money.amount);
+    }
+
     let use_cash_app_rate = FLAG_USE_CASH_EXCHANGE_RATE_PROVIDER
         .resolver(feature_flags_service)
         .resolve();
 
     match select_exchange_rate_provider(config, use_cash_app_rate) {
-        RateProvider::Local(provider) => {
-            sats_for(exchange_rate_service, provider, &limit.amount).await
-        }
-        RateProvider::CashApp(provider) => {
-            sats_for(exchange_rate_service, provider, &limit.amount).await
-        }
-        RateProvider::Bitstamp(provider) => {
-            sats_for(exchange_rate_service, provider, &limit.amount).await
-        }
+        RateProvider::Local(provider) => sats_for(exchange_rate_service, provider, money).await,
+        RateProvider::CashApp(provider) => sats_for(exchange_rate_service, provider, money).await,
+        RateProvider::Bitstamp(provider) => sats_for(exchange_rate_service, provider, money).await,
     }
     .map_err(|_| {
         ApiError::GenericInternalApplicationError("Could not convert limit to sats".to_string())
     })
 }
 
+/// Converts the fiat caps in the account's spend policy to sats at the current exchange rate,
+/// returning `None` for rules without a cap.
+async fn spend_policy_limits_in_sats(
+    spend_policy: &[SpendPolicyRule],
+    config: &Config,
+    exchange_rate_service: &ExchangeRateService,
+    feature_flags_service: &FeatureFlagsService,
+) -> Result<Vec<Option<u64>>, ApiError> {
+    let mut limits_sats = Vec::with_capacity(spend_policy.len());
+    for rule in spend_policy {
+        limits_sats.push(match rule {
+            SpendPolicyRule::PerTransactionCap { limit }
+            | SpendPolicyRule::PeriodCap { limit, .. } => Some(
+                sats_for_money(limit, config, exchange_rate_service, feature_flags_service).await?,
+            ),
+            _ => None,
+        });
+    }
+    Ok(limits_sats)
+}
+
+/// Builds the rules declared in the account's spend policy, given the caps from
+/// [`spend_policy_limits_in_sats`].
+fn spend_policy_rules<'a>(
+    spend_policy: &'a [SpendPolicyRule],
+    limits_sats: Vec<Option<u64>>,
+    wallet: &'a Wallet<AnyDatabase>,
+    spending_history: &'a [&'a SpendingEntry],
+) -> Result<Vec<Box<dyn Rule + 'a>>, ApiError> {
+    let now_utc = OffsetDateTime::now_utc();
+    let mut rules: Vec<Box<dyn Rule + 'a>> = Vec::with_capacity(spend_policy.len());
+    for (rule, limit_sats) in spend_policy.iter().zip(limits_sats) {
+        rules.push(match (rule, limit_sats) {
+            (SpendPolicyRule::PerTransactionCap { .. }, Some(limit_sats)) => {
+                Box::new(PerTransactionCapRule::new(wallet, limit_sats))
+            }
+            (SpendPolicyRule::PeriodCap { period, .. }, Some(limit_sats)) => Box::new(
+                PeriodSpendCapRule::new(wallet, *period, limit_sats, spending_history, now_utc),
+            ),
+            (
+                SpendPolicyRule::PerTransactionCap { .. } | SpendPolicyRule::PeriodCap { .. },
+                None,
+            ) => {
+                return Err(ApiError::GenericInternalApplicationError(
+                    "Spend policy cap wasn't converted to sats".to_string(),
+                ))
+            }
+            (SpendPolicyRule::AllowlistedDestinations { addresses }, _) => {
+                Box::new(AllowlistedDestinationsRule::new(wallet, addresses))
+            }
+            (SpendPolicyRule::MaxFeeRate { sats_per_vbyte }, _) => {
+                Box::new(MaxFeeRateRule::new(*sats_per_vbyte))
+            }
+            (
+                SpendPolicyRule::Velocity {
+                    period,
+                    max_transactions,
+                },
+                _,
+            ) => Box::new(VelocityRule::new(
+                *period,
+                *max_transactions,
+                spending_history,
+                now_utc,
+            )),
+        });
+    }
+    Ok(rules)
+}
+
 async fn get_mobile_pay_spending_record(
     account_id: &AccountId,
     daily_spend_record_service: &DailySpendRecordService,
 ) -> Result<MobilePaySpendingRecord, ApiError> {
-    // If a spend is before the daily roll-over, we'll need to check yesterday's spending record as well
-    let yesterday_spending_record = daily_spend_record_service
-        .fetch_or_create_daily_spending_record(
+    // If a spend is before the daily roll-over, we'll need to check yesterday's spending record as
+    // well, and spend policy rules may look back over every record we still retain.
+    let now_utc = OffsetDateTime::now_utc();
+    let date_math_error = || {
+        ApiError::GenericInternalApplicationError("arithmetic error subtracting date".to_string())
+    };
+    let previous_spending_records = daily_spend_record_service
+        .fetch_daily_spending_records_between(
             account_id,
-            OffsetDateTime::now_utc()
+            now_utc
+                .checked_sub(Duration::days(RETENTION_DAYS))
+                .ok_or_else(date_math_error)?
+                .date(),
+            now_utc
                 .checked_sub(Duration::days(1))
-                .ok_or(ApiError::GenericInternalApplicationError(
-                    "arithmetic error subtracting date".to_string(),
-                ))?
+                .ok_or_else(date_math_error)?
                 .date(),
         )
         .await?;
     let today_spending_record = daily_spend_record_service
-        .fetch_or_create_daily_spending_record(account_id, OffsetDateTime::now_utc().date())
+        .fetch_or_create_daily_spending_record(account_id, now_utc.date())
         .await?;
 
     Ok(MobilePaySpendingRecord {
-        yesterday: yesterday_spending_record,
+        previous: previous_spending_records,
         today: today_spending_record,
     })
 }
@@ -834,6 +1283,7 @@ mod tests {
         match select_exchange_rate_provider(
             &Config {
                 use_local_currency_exchange: true,
+                fee_limits: Default::default(),
             },
             true,
         ) {
@@ -844,6 +1294,7 @@ mod tests {
         match select_exchange_rate_provider(
             &Config {
                 use_local_currency_exchange: false,
+                fee_limits: Default::default(),
             },
             false,
         ) {
@@ -854,6 +1305,7 @@ mod tests {
         match select_exchange_rate_provider(
             &Config {
                 use_local_currency_exchange: false,
+                fee_limits: Default::default(),
             },
             true,
         ) {
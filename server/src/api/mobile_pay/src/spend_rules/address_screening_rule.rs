@@ -5,7 +5,7 @@ use bdk_utils::bdk::bitcoin::{Address, Network};
 
 use screener::service::SanctionsScreener;
 
-use crate::spend_rules::Rule;
+use crate::spend_rules::{Rule, SpendRuleViolation};
 
 pub(crate) struct AddressScreeningRule {
     screener_service: Arc<dyn SanctionsScreener>,
@@ -13,7 +13,10 @@ pub(crate) struct AddressScreeningRule {
 }
 
 impl Rule for AddressScreeningRule {
-    fn check_transaction(&self, psbt: &PartiallySignedTransaction) -> Result<(), String> {
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
         let tx = psbt.clone().unsigned_tx;
         let destination_addresses = tx
             .output
@@ -22,17 +25,19 @@ impl Rule for AddressScreeningRule {
                 Address::from_script(&output.script_pubkey, self.network)
                     .map(|address| address.to_string())
                     .map_err(|_| {
-                        "One or more script pub keys are invalid. Cannot check transaction"
-                            .to_string()
+                        SpendRuleViolation::InvalidTransaction(
+                            "One or more script pub keys are invalid. Cannot check transaction"
+                                .to_string(),
+                        )
                     })
             })
-            .collect::<Result<Vec<String>, String>>()?;
+            .collect::<Result<Vec<String>, SpendRuleViolation>>()?;
 
         if self
             .screener_service
             .should_block_transaction(&destination_addresses)
         {
-            Err("One or more outputs belong to sanctioned individuals.".to_string())
+            Err(SpendRuleViolation::SanctionedDestination)
         } else {
             Ok(())
         }
@@ -6,7 +6,7 @@ use bdk_utils::bdk::Wallet;
 
 use crate::metrics;
 
-use super::Rule;
+use super::{Rule, SpendRuleViolation};
 
 pub(crate) struct AllPsbtInputsBelongToWalletRule<'a> {
     wallet: &'a Wallet<AnyDatabase>,
@@ -14,16 +14,17 @@ pub(crate) struct AllPsbtInputsBelongToWalletRule<'a> {
 
 impl<'a> Rule for AllPsbtInputsBelongToWalletRule<'a> {
     /// Ensure all the inputs in the PSBT belong to this wallet
-    fn check_transaction(&self, psbt: &PartiallySignedTransaction) -> Result<(), String> {
-        if self
-            .wallet
-            .all_inputs_are_from_self(psbt)
-            .map_err(|err| format!("Invalid PSBT for given wallet: {err}"))?
-        {
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        if self.wallet.all_inputs_are_from_self(psbt).map_err(|err| {
+            SpendRuleViolation::InvalidTransaction(format!("Invalid PSBT for given wallet: {err}"))
+        })? {
             Ok(())
         } else {
             metrics::MOBILE_PAY_INPUTS_DO_NOT_BELONG_TO_SELF.add(1, &[]);
-            Err("Invalid PSBT for given Wallet".to_string())
+            Err(SpendRuleViolation::InputsNotFromWallet)
         }
     }
 }
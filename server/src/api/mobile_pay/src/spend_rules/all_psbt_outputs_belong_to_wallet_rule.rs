@@ -6,7 +6,7 @@ use bdk_utils::bdk::Wallet;
 
 use crate::metrics;
 
-use super::Rule;
+use super::{Rule, SpendRuleViolation};
 
 pub(crate) struct AllPsbtOutputsBelongToWalletRule<'a> {
     wallet: &'a Wallet<AnyDatabase>,
@@ -14,12 +14,15 @@ pub(crate) struct AllPsbtOutputsBelongToWalletRule<'a> {
 
 impl<'a> Rule for AllPsbtOutputsBelongToWalletRule<'a> {
     /// Ensure all the outputs in the PSBT belong to this wallet
-    fn check_transaction(&self, psbt: &PartiallySignedTransaction) -> Result<(), String> {
-        if !is_psbt_addressed_to_wallet(self.wallet, psbt)
-            .map_err(|err| format!("Invalid PSBT for given wallet: {}", err))?
-        {
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        if !is_psbt_addressed_to_wallet(self.wallet, psbt).map_err(|err| {
+            SpendRuleViolation::InvalidTransaction(format!("Invalid PSBT for given wallet: {err}"))
+        })? {
             metrics::SWEEP_OUTPUTS_DONT_BELONG_TO_ACTIVE_KEYSET.add(1, &[]);
-            Err("Invalid Sweep transaction. Contains output not to self.".to_string())
+            Err(SpendRuleViolation::OutputsNotToDestinationWallet)
         } else {
             Ok(())
         }
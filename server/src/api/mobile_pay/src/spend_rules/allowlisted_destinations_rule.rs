@@ -0,0 +1,73 @@
+use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
+use bdk_utils::bdk::bitcoin::Address;
+use bdk_utils::bdk::database::AnyDatabase;
+use bdk_utils::bdk::Wallet;
+
+use crate::util::get_external_outputs_for_psbt;
+
+use super::{Rule, SpendRuleViolation};
+
+pub(crate) struct AllowlistedDestinationsRule<'a> {
+    wallet: &'a Wallet<AnyDatabase>,
+    addresses: &'a [String],
+}
+
+impl<'a> AllowlistedDestinationsRule<'a> {
+    pub fn new(wallet: &'a Wallet<AnyDatabase>, addresses: &'a [String]) -> Self {
+        AllowlistedDestinationsRule { wallet, addresses }
+    }
+}
+
+impl<'a> Rule for AllowlistedDestinationsRule<'a> {
+    /// Ensure every output in the PSBT that leaves the wallet pays an allowlisted address
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        for output in get_external_outputs_for_psbt(self.wallet, psbt) {
+            let address = Address::from_script(&output.script_pubkey, self.wallet.network())
+                .map_err(|_| {
+                    SpendRuleViolation::InvalidTransaction(
+                        "One or more script pub keys are invalid. Cannot check transaction"
+                            .to_string(),
+                    )
+                })?
+                .to_string();
+            if !self.addresses.contains(&address) {
+                return Err(SpendRuleViolation::DestinationNotAllowlisted { address });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk_utils::bdk::wallet::AddressIndex;
+
+    use super::*;
+    use crate::spend_rules::test_utils::{alice_wallet, bob_wallet, carol_wallet, generate_psbt};
+
+    #[test]
+    fn allowlisted_destinations_rule() {
+        let alice_wallet = alice_wallet();
+        let bob_address = bob_wallet().get_address(AddressIndex::New).unwrap();
+        let carol_address = carol_wallet().get_address(AddressIndex::New).unwrap();
+
+        let psbt = generate_psbt(&alice_wallet, &bob_address, 10_000, 5.0);
+
+        // Alice's change output doesn't need to be allowlisted.
+        let allowlist = vec![bob_address.to_string()];
+        let rule = AllowlistedDestinationsRule::new(&alice_wallet, &allowlist);
+        assert!(rule.check_transaction(&psbt).is_ok());
+
+        let allowlist = vec![carol_address.to_string()];
+        let rule = AllowlistedDestinationsRule::new(&alice_wallet, &allowlist);
+        assert_eq!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::DestinationNotAllowlisted {
+                address: bob_address.to_string(),
+            })
+        );
+    }
+}
@@ -7,9 +7,9 @@ use bdk_utils::bdk::Wallet;
 use crate::daily_spend_record::entities::SpendingEntry;
 use crate::entities::Features;
 use crate::metrics;
-use crate::util::{get_total_outflow_for_psbt, total_sats_spent_today};
+use crate::util::{get_fee_for_psbt, get_total_outflow_for_psbt, total_sats_spent_today};
 
-use super::Rule;
+use super::{Rule, SpendRuleViolation};
 
 pub(crate) struct DailySpendingLimitRule<'a> {
     wallet: &'a Wallet<AnyDatabase>,
@@ -35,25 +35,32 @@ impl<'a> DailySpendingLimitRule<'a> {
 }
 
 impl<'a> Rule for DailySpendingLimitRule<'a> {
-    /// Ensure that the total outflows for this PSBT plus the outflows so far today do not exceed
-    /// the set spending limit
-    fn check_transaction(&self, psbt: &PartiallySignedTransaction) -> Result<(), String> {
+    /// Ensure that the total outflows for this PSBT, including its fee, plus the outflows so far
+    /// today do not exceed the set spending limit
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
         let total_spent = total_sats_spent_today(
             self.spending_history,
             &self.features.settings.limit,
             self.now_utc,
-        )?;
+        )
+        .map_err(SpendRuleViolation::EvaluationFailed)?;
 
         let total_spend_for_unsigned_transaction_sats =
-            get_total_outflow_for_psbt(self.wallet, psbt);
+            get_total_outflow_for_psbt(self.wallet, psbt)
+                + get_fee_for_psbt(psbt).map_err(SpendRuleViolation::InvalidTransaction)?;
         if self.features.daily_limit_sats >= total_spend_for_unsigned_transaction_sats + total_spent
         {
             Ok(())
         } else {
             metrics::MOBILE_PAY_COSIGN_OVERFLOW.add(1, &[]);
-            Err(format!(
-                "Transaction spend total of {total_spend_for_unsigned_transaction_sats} with existing spend of {total_spent} for the day exceeds limit."
-            ))
+            Err(SpendRuleViolation::DailySpendingLimitExceeded {
+                spend_sats: total_spend_for_unsigned_transaction_sats,
+                spent_sats: total_spent,
+                limit_sats: self.features.daily_limit_sats,
+            })
         }
     }
 }
@@ -62,7 +69,7 @@ impl<'a> Rule for DailySpendingLimitRule<'a> {
 mod tests {
     use time::{macros::datetime, Duration, OffsetDateTime, UtcOffset};
 
-    use account::spend_limit::{Money, SpendingLimit};
+    use account::spend_limit::{Money, SpendWindow, SpendingLimit};
     use bdk_utils::bdk::bitcoin::consensus::deserialize;
     use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
     use bdk_utils::bdk::bitcoin::ScriptBuf;
@@ -77,7 +84,7 @@ mod tests {
     use crate::daily_spend_record::entities::SpendingEntry;
     use crate::entities::{Features, Settings};
     use crate::spend_rules::daily_spend_limit_rule::DailySpendingLimitRule;
-    use crate::spend_rules::Rule;
+    use crate::spend_rules::{Rule, SpendRuleViolation};
 
     fn generate_test_wallets_and_address() -> (Wallet<AnyDatabase>, AddressInfo) {
         let source_wallet = get_funded_wallet("wpkh([c258d2e4/84h/1h/0h]tpubDDYkZojQFQjht8Tm4jsS3iuEmKjTiEGjG6KnuFNKKJb5A6ZUCUZKdvLdSDWofKi4ToRCwb9poe1XdqfUnP4jaJjCB2Zwv11ZLgSbnZSNecE/0/*)").0;
@@ -275,7 +282,10 @@ mod tests {
             &spending_entries,
             OffsetDateTime::now_utc(),
         );
-        assert!(rule.check_transaction(&psbt).is_err());
+        assert!(matches!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::DailySpendingLimitExceeded { .. })
+        ));
     }
 
     #[tokio::test]
@@ -450,7 +460,7 @@ mod tests {
             .await,
             generate_fake_transaction_details(
                 &Money {
-                    amount: 3_00,
+                    amount: 2_50, // Leaves room for the fee, which also counts towards the limit
                     currency_code: USD,
                 },
                 datetime!(2022-12-31 03:01:00 -5),
@@ -467,4 +477,132 @@ mod tests {
         );
         assert!(rule.check_transaction(&psbt).is_ok());
     }
+
+    #[tokio::test]
+    async fn daily_spend_limit_rule_for_rolling_window() {
+        let (alice_wallet, bob_address) = generate_test_wallets_and_address();
+        let four_am_utc = datetime!(2023-03-01 04:00:00 UTC);
+        let psbt = generate_psbt(
+            &alice_wallet,
+            bob_address.address.script_pubkey(),
+            &Money {
+                amount: 2_00,
+                currency_code: USD,
+            },
+        )
+        .await;
+        let calendar_settings = Settings {
+            limit: SpendingLimit {
+                amount: Money {
+                    amount: 5_00,
+                    currency_code: USD,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let daily_limit_sats = sats_for(
+            &ExchangeRateService::new(),
+            LocalRateProvider::new(),
+            &calendar_settings.limit.amount,
+        )
+        .await
+        .unwrap();
+
+        // This transaction was made before today's 3 am window started, but within the last 24 hours
+        let transaction_history = generate_daily_spend_entries_for_tx_history(vec![
+            generate_fake_transaction_details(
+                &Money {
+                    amount: 4_00,
+                    currency_code: USD,
+                },
+                four_am_utc - Duration::hours(2),
+            )
+            .await,
+        ]);
+        let spending_entries = transaction_history.iter().collect();
+
+        let calendar_features = Features {
+            settings: calendar_settings.clone(),
+            daily_limit_sats,
+            ..Default::default()
+        };
+        let rule = DailySpendingLimitRule::new(
+            &alice_wallet,
+            &calendar_features,
+            &spending_entries,
+            four_am_utc,
+        );
+        assert!(rule.check_transaction(&psbt).is_ok());
+
+        let rolling_features = Features {
+            settings: Settings {
+                limit: SpendingLimit {
+                    window: SpendWindow::Rolling,
+                    ..calendar_settings.limit
+                },
+            },
+            daily_limit_sats,
+            ..Default::default()
+        };
+        let rule = DailySpendingLimitRule::new(
+            &alice_wallet,
+            &rolling_features,
+            &spending_entries,
+            four_am_utc,
+        );
+        assert!(matches!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::DailySpendingLimitExceeded { .. })
+        ));
+
+        // Once the transaction is more than 24 hours old it no longer counts towards a rolling limit
+        let rule = DailySpendingLimitRule::new(
+            &alice_wallet,
+            &rolling_features,
+            &spending_entries,
+            four_am_utc + Duration::hours(23),
+        );
+        assert!(rule.check_transaction(&psbt).is_ok());
+    }
+
+    #[tokio::test]
+    async fn daily_spend_limit_rule_counts_fee() {
+        let (alice_wallet, bob_address) = generate_test_wallets_and_address();
+        let psbt = generate_psbt(
+            &alice_wallet,
+            bob_address.address.script_pubkey(),
+            &Money {
+                amount: 2_00,
+                currency_code: USD,
+            },
+        )
+        .await;
+        let spend_sats = psbt
+            .unsigned_tx
+            .output
+            .iter()
+            .find(|output| output.script_pubkey == bob_address.address.script_pubkey())
+            .unwrap()
+            .value;
+
+        // The limit covers the amount sent, but not the fee on top of it
+        let features = Features {
+            daily_limit_sats: spend_sats,
+            ..Default::default()
+        };
+
+        let spending_entries = Vec::new();
+        let rule = DailySpendingLimitRule::new(
+            &alice_wallet,
+            &features,
+            &spending_entries,
+            OffsetDateTime::now_utc(),
+        );
+        assert!(matches!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::DailySpendingLimitExceeded { .. })
+        ));
+    }
 }
@@ -0,0 +1,144 @@
+use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
+use bdk_utils::bdk::database::AnyDatabase;
+use bdk_utils::bdk::Wallet;
+
+use crate::entities::Features;
+use crate::util::{estimate_fee_rate_for_psbt, get_fee_for_psbt, get_total_outflow_for_psbt};
+
+use super::{Rule, SpendRuleViolation};
+
+pub(crate) struct FeeSanityRule<'a> {
+    wallet: &'a Wallet<AnyDatabase>,
+    features: &'a Features,
+}
+
+impl<'a> FeeSanityRule<'a> {
+    pub fn new(wallet: &'a Wallet<AnyDatabase>, features: &'a Features) -> Self {
+        FeeSanityRule { wallet, features }
+    }
+}
+
+impl<'a> Rule for FeeSanityRule<'a> {
+    /// Ensure the PSBT's fee is in line with the recommended fee rate and with the amount being
+    /// sent. Without a recommended fee rate, only the amount being sent is checked.
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        let fee_limits = &self.features.fee_limits;
+
+        if let Some(recommended_fee_rate) = self.features.recommended_fee_rate {
+            let fee_rate =
+                estimate_fee_rate_for_psbt(psbt).map_err(SpendRuleViolation::InvalidTransaction)?;
+            if fee_rate > recommended_fee_rate * fee_limits.max_fee_rate_multiple {
+                return Err(SpendRuleViolation::FeeRateAboveEstimate {
+                    fee_rate,
+                    recommended_fee_rate,
+                });
+            }
+        }
+
+        let fee_sats = get_fee_for_psbt(psbt).map_err(SpendRuleViolation::InvalidTransaction)?;
+        let spend_sats = get_total_outflow_for_psbt(self.wallet, psbt);
+        if fee_sats as f64 > spend_sats as f64 * fee_limits.max_fee_ratio as f64 {
+            return Err(SpendRuleViolation::FeeRatioTooHigh {
+                fee_sats,
+                spend_sats,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
+    use bdk_utils::bdk::wallet::AddressIndex;
+
+    use crate::entities::FeeLimits;
+    use crate::spend_rules::test_utils::{alice_wallet, bob_wallet, generate_psbt};
+
+    use super::*;
+
+    fn pay_bob(
+        source_wallet: &Wallet<AnyDatabase>,
+        amount_sats: u64,
+        fee_rate: f32,
+    ) -> PartiallySignedTransaction {
+        let bob_address = bob_wallet().get_address(AddressIndex::New).unwrap();
+        generate_psbt(source_wallet, &bob_address, amount_sats, fee_rate)
+    }
+
+    #[test]
+    fn fee_sanity_rule_allows_fee_near_estimate() {
+        let alice_wallet = alice_wallet();
+        let psbt = pay_bob(&alice_wallet, 40_000, 20.0);
+        let features = Features {
+            recommended_fee_rate: Some(10.0),
+            ..Default::default()
+        };
+
+        let rule = FeeSanityRule::new(&alice_wallet, &features);
+        assert!(rule.check_transaction(&psbt).is_ok());
+    }
+
+    #[test]
+    fn fee_sanity_rule_rejects_fee_rate_far_above_estimate() {
+        let alice_wallet = alice_wallet();
+        let psbt = pay_bob(&alice_wallet, 10_000, 50.0);
+        let features = Features {
+            recommended_fee_rate: Some(10.0),
+            ..Default::default()
+        };
+
+        let rule = FeeSanityRule::new(&alice_wallet, &features);
+        assert!(matches!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::FeeRateAboveEstimate { .. })
+        ));
+    }
+
+    #[test]
+    fn fee_sanity_rule_without_estimate_only_checks_fee_ratio() {
+        let alice_wallet = alice_wallet();
+        let psbt = pay_bob(&alice_wallet, 10_000, 50.0);
+        let features = Features {
+            recommended_fee_rate: None,
+            fee_limits: FeeLimits {
+                max_fee_ratio: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let rule = FeeSanityRule::new(&alice_wallet, &features);
+        assert!(rule.check_transaction(&psbt).is_ok());
+
+        let features = Features {
+            recommended_fee_rate: None,
+            ..Default::default()
+        };
+        let rule = FeeSanityRule::new(&alice_wallet, &features);
+        assert!(matches!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::FeeRatioTooHigh { .. })
+        ));
+    }
+
+    #[test]
+    fn fee_sanity_rule_rejects_fee_too_large_for_spend() {
+        let alice_wallet = alice_wallet();
+        let psbt = pay_bob(&alice_wallet, 10_000, 20.0);
+        let features = Features {
+            recommended_fee_rate: Some(10.0),
+            ..Default::default()
+        };
+
+        let rule = FeeSanityRule::new(&alice_wallet, &features);
+        assert!(matches!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::FeeRatioTooHigh { .. })
+        ));
+    }
+}
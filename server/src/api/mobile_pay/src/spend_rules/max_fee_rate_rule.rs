@@ -0,0 +1,75 @@
+use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
+
+use crate::util::estimate_fee_rate_for_psbt;
+
+use super::{Rule, SpendRuleViolation};
+
+pub(crate) struct MaxFeeRateRule {
+    max_fee_rate: f32,
+}
+
+impl MaxFeeRateRule {
+    pub fn new(max_fee_rate: f32) -> Self {
+        MaxFeeRateRule { max_fee_rate }
+    }
+}
+
+impl Rule for MaxFeeRateRule {
+    /// Ensure the PSBT's estimated fee rate once fully signed does not exceed the maximum
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        let fee_rate =
+            estimate_fee_rate_for_psbt(psbt).map_err(SpendRuleViolation::InvalidTransaction)?;
+        if fee_rate <= self.max_fee_rate {
+            Ok(())
+        } else {
+            Err(SpendRuleViolation::FeeRateTooHigh {
+                fee_rate,
+                max_fee_rate: self.max_fee_rate,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk_utils::bdk::wallet::AddressIndex;
+
+    use super::*;
+    use crate::spend_rules::test_utils::{alice_wallet, bob_wallet, generate_psbt};
+
+    #[test]
+    fn max_fee_rate_rule() {
+        let alice_wallet = alice_wallet();
+        let bob_address = bob_wallet().get_address(AddressIndex::New).unwrap();
+        let psbt = generate_psbt(&alice_wallet, &bob_address, 10_000, 50.0);
+
+        let rule = MaxFeeRateRule::new(100.0);
+        assert!(rule.check_transaction(&psbt).is_ok());
+
+        let rule = MaxFeeRateRule::new(10.0);
+        assert!(matches!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::FeeRateTooHigh { .. })
+        ));
+    }
+
+    #[test]
+    fn max_fee_rate_rule_rejects_psbt_without_utxos() {
+        let alice_wallet = alice_wallet();
+        let bob_address = bob_wallet().get_address(AddressIndex::New).unwrap();
+        let mut psbt = generate_psbt(&alice_wallet, &bob_address, 10_000, 5.0);
+        for input in psbt.inputs.iter_mut() {
+            input.witness_utxo = None;
+            input.non_witness_utxo = None;
+        }
+
+        let rule = MaxFeeRateRule::new(100.0);
+        assert!(matches!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::InvalidTransaction(_))
+        ));
+    }
+}
@@ -1,10 +1,14 @@
 use std::sync::Arc;
+use thiserror::Error;
 use time::OffsetDateTime;
 use tracing::instrument;
 
+use account::spend_policy::SpendPeriod;
+
 use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
 use bdk_utils::bdk::database::AnyDatabase;
 use bdk_utils::bdk::Wallet;
+use errors::{ApiError, ErrorCode};
 
 use screener::service::Service as ScreenerService;
 
@@ -15,18 +19,168 @@ use self::address_screening_rule::AddressScreeningRule;
 use self::all_psbt_inputs_belong_to_wallet_rule::AllPsbtInputsBelongToWalletRule;
 use self::all_psbt_outputs_belong_to_wallet_rule::AllPsbtOutputsBelongToWalletRule;
 use self::daily_spend_limit_rule::DailySpendingLimitRule;
+use self::fee_sanity_rule::FeeSanityRule;
 use self::no_psbt_outputs_belong_to_wallet_rule::NoPsbtOutputsBelongToWalletRule;
 
 mod address_screening_rule;
 mod all_psbt_inputs_belong_to_wallet_rule;
 mod daily_spend_limit_rule;
+mod fee_sanity_rule;
 
 mod no_psbt_outputs_belong_to_wallet_rule;
 
 mod all_psbt_outputs_belong_to_wallet_rule;
 
-trait Rule {
-    fn check_transaction(&self, psbt: &PartiallySignedTransaction) -> Result<(), String>;
+pub(crate) mod allowlisted_destinations_rule;
+pub(crate) mod max_fee_rate_rule;
+pub(crate) mod per_transaction_cap_rule;
+pub(crate) mod period_spend_cap_rule;
+pub(crate) mod velocity_rule;
+
+#[cfg(test)]
+mod test_utils {
+    use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
+    use bdk_utils::bdk::bitcoin::Address;
+    use bdk_utils::bdk::database::AnyDatabase;
+    use bdk_utils::bdk::wallet::get_funded_wallet;
+    use bdk_utils::bdk::{FeeRate, Wallet};
+
+    fn funded_wallet(index: u32) -> Wallet<AnyDatabase> {
+        get_funded_wallet(&format!(
+            "wpkh([c258d2e4/84h/1h/0h]tpubDDYkZojQFQjht8Tm4jsS3iuEmKjTiEGjG6KnuFNKKJb5A6ZUCUZKdvLdSDWofKi4ToRCwb9poe1XdqfUnP4jaJjCB2Zwv11ZLgSbnZSNecE/{index}/*)"
+        ))
+        .0
+    }
+
+    pub(super) fn alice_wallet() -> Wallet<AnyDatabase> {
+        funded_wallet(0)
+    }
+
+    pub(super) fn bob_wallet() -> Wallet<AnyDatabase> {
+        funded_wallet(1)
+    }
+
+    pub(super) fn carol_wallet() -> Wallet<AnyDatabase> {
+        funded_wallet(2)
+    }
+
+    /// Builds a PSBT paying `amount_sats` from `source_wallet` to `recipient`.
+    pub(super) fn generate_psbt(
+        source_wallet: &Wallet<AnyDatabase>,
+        recipient: &Address,
+        amount_sats: u64,
+        fee_rate: f32,
+    ) -> PartiallySignedTransaction {
+        let mut builder = source_wallet.build_tx();
+        builder
+            .add_recipient(recipient.script_pubkey(), amount_sats)
+            .fee_rate(FeeRate::from_sat_per_vb(fee_rate));
+        let (psbt, _) = builder.finish().unwrap();
+        psbt
+    }
+}
+
+pub trait Rule {
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation>;
+}
+
+/// The reason a [`Rule`] refused a transaction.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SpendRuleViolation {
+    #[error("{0}")]
+    InvalidTransaction(String),
+    #[error("One or more outputs belong to sanctioned individuals.")]
+    SanctionedDestination,
+    #[error("Invalid PSBT for given Wallet")]
+    InputsNotFromWallet,
+    #[error("Invalid Mobile Pay transaction. Contains outputs to self.")]
+    OutputsToSelf,
+    #[error("Invalid Sweep transaction. Contains output not to self.")]
+    OutputsNotToDestinationWallet,
+    #[error("Transaction spend total of {spend_sats} with existing spend of {spent_sats} for the day exceeds limit.")]
+    DailySpendingLimitExceeded {
+        spend_sats: u64,
+        spent_sats: u64,
+        limit_sats: u64,
+    },
+    #[error(
+        "Transaction spend total of {spend_sats} exceeds per-transaction limit of {limit_sats}."
+    )]
+    PerTransactionLimitExceeded { spend_sats: u64, limit_sats: u64 },
+    #[error("Transaction spend total of {spend_sats} with existing spend of {spent_sats} exceeds {period} limit of {limit_sats}.")]
+    PeriodSpendingLimitExceeded {
+        period: SpendPeriod,
+        spend_sats: u64,
+        spent_sats: u64,
+        limit_sats: u64,
+    },
+    #[error("Output to {address} is not an allowlisted destination.")]
+    DestinationNotAllowlisted { address: String },
+    #[error("Transaction fee rate of {fee_rate} sat/vB exceeds maximum of {max_fee_rate} sat/vB.")]
+    FeeRateTooHigh { fee_rate: f32, max_fee_rate: f32 },
+    #[error("Transaction fee rate of {fee_rate} sat/vB is too far above the recommended fee rate of {recommended_fee_rate} sat/vB.")]
+    FeeRateAboveEstimate {
+        fee_rate: f32,
+        recommended_fee_rate: f32,
+    },
+    #[error("Transaction fee of {fee_sats} is too large for spend total of {spend_sats}.")]
+    FeeRatioTooHigh { fee_sats: u64, spend_sats: u64 },
+    #[error("Transaction would be number {transaction_count} in the {period} period, exceeding limit of {max_transactions}.")]
+    TransactionVelocityExceeded {
+        period: SpendPeriod,
+        transaction_count: usize,
+        max_transactions: u32,
+    },
+    #[error("Could not evaluate spend rule: {0}")]
+    EvaluationFailed(String),
+}
+
+impl From<SpendRuleViolation> for ApiError {
+    fn from(value: SpendRuleViolation) -> Self {
+        let detail = value.to_string();
+        let specific = |code| ApiError::Specific {
+            code,
+            detail: Some(detail.clone()),
+            field: None,
+        };
+        match value {
+            SpendRuleViolation::InvalidTransaction(_) => specific(ErrorCode::InvalidTransaction),
+            SpendRuleViolation::InputsNotFromWallet => specific(ErrorCode::InputsNotFromWallet),
+            SpendRuleViolation::OutputsToSelf => specific(ErrorCode::OutputsToSelf),
+            SpendRuleViolation::OutputsNotToDestinationWallet => {
+                specific(ErrorCode::OutputsNotToDestination)
+            }
+            SpendRuleViolation::EvaluationFailed(_) => {
+                ApiError::GenericInternalApplicationError(detail)
+            }
+            SpendRuleViolation::SanctionedDestination => {
+                specific(ErrorCode::SanctionedDestinationAddress)
+            }
+            SpendRuleViolation::DailySpendingLimitExceeded { .. } => {
+                specific(ErrorCode::SpendingLimitExceeded)
+            }
+            SpendRuleViolation::PerTransactionLimitExceeded { .. } => {
+                specific(ErrorCode::PerTransactionLimitExceeded)
+            }
+            SpendRuleViolation::PeriodSpendingLimitExceeded { .. } => {
+                specific(ErrorCode::PeriodSpendingLimitExceeded)
+            }
+            SpendRuleViolation::DestinationNotAllowlisted { .. } => {
+                specific(ErrorCode::DestinationNotAllowlisted)
+            }
+            SpendRuleViolation::FeeRateTooHigh { .. }
+            | SpendRuleViolation::FeeRateAboveEstimate { .. } => {
+                specific(ErrorCode::FeeRateTooHigh)
+            }
+            SpendRuleViolation::FeeRatioTooHigh { .. } => specific(ErrorCode::FeeRatioTooHigh),
+            SpendRuleViolation::TransactionVelocityExceeded { .. } => {
+                specific(ErrorCode::TransactionVelocityExceeded)
+            }
+        }
+    }
 }
 
 pub struct SpendRuleSet<'a> {
@@ -52,6 +206,7 @@ impl<'a> SpendRuleSet<'a> {
                     spending_history,
                     OffsetDateTime::now_utc(),
                 )),
+                Box::new(FeeSanityRule::new(source_wallet, features)),
                 Box::new(AllPsbtInputsBelongToWalletRule::new(source_wallet)),
                 Box::new(NoPsbtOutputsBelongToWalletRule::new(source_wallet)),
             ],
@@ -75,9 +230,19 @@ impl<'a> SpendRuleSet<'a> {
         }
     }
 
+    /// Adds rules to be checked alongside the ones already in the set, e.g. those declared in an
+    /// account's spend policy.
+    pub fn with_rules(mut self, rules: impl IntoIterator<Item = Box<dyn Rule + 'a>>) -> Self {
+        self.rules.extend(rules);
+        self
+    }
+
     #[instrument(skip(self, psbt))]
-    pub fn check_spend_rules(&self, psbt: &PartiallySignedTransaction) -> Result<(), Vec<String>> {
-        let errors: Vec<String> = self
+    pub fn check_spend_rules(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), Vec<SpendRuleViolation>> {
+        let errors: Vec<SpendRuleViolation> = self
             .rules
             .iter()
             .map(|p| p.check_transaction(psbt))
@@ -6,7 +6,7 @@ use bdk_utils::bdk::Wallet;
 
 use crate::metrics;
 
-use super::Rule;
+use super::{Rule, SpendRuleViolation};
 
 pub(crate) struct NoPsbtOutputsBelongToWalletRule<'a> {
     wallet: &'a Wallet<AnyDatabase>,
@@ -14,14 +14,15 @@ pub(crate) struct NoPsbtOutputsBelongToWalletRule<'a> {
 
 impl<'a> Rule for NoPsbtOutputsBelongToWalletRule<'a> {
     /// Ensure no outputs in the PSBT belong to this wallet
-    fn check_transaction(&self, psbt: &PartiallySignedTransaction) -> Result<(), String> {
-        if self
-            .wallet
-            .is_addressed_to_self(psbt)
-            .map_err(|err| format!("Invalid PSBT for given wallet: {err}"))?
-        {
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        if self.wallet.is_addressed_to_self(psbt).map_err(|err| {
+            SpendRuleViolation::InvalidTransaction(format!("Invalid PSBT for given wallet: {err}"))
+        })? {
             metrics::MOBILE_PAY_OUTPUTS_BELONG_TO_SELF.add(1, &[]);
-            Err("Invalid Mobile Pay transaction. Contains outputs to self.".to_string())
+            Err(SpendRuleViolation::OutputsToSelf)
         } else {
             Ok(())
         }
@@ -0,0 +1,67 @@
+use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
+use bdk_utils::bdk::database::AnyDatabase;
+use bdk_utils::bdk::Wallet;
+
+use crate::util::{get_fee_for_psbt, get_total_outflow_for_psbt};
+
+use super::{Rule, SpendRuleViolation};
+
+pub(crate) struct PerTransactionCapRule<'a> {
+    wallet: &'a Wallet<AnyDatabase>,
+    limit_sats: u64,
+}
+
+impl<'a> PerTransactionCapRule<'a> {
+    pub fn new(wallet: &'a Wallet<AnyDatabase>, limit_sats: u64) -> Self {
+        PerTransactionCapRule { wallet, limit_sats }
+    }
+}
+
+impl<'a> Rule for PerTransactionCapRule<'a> {
+    /// Ensure that the outflows for this PSBT alone, including its fee, do not exceed the cap
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        let spend_sats = get_total_outflow_for_psbt(self.wallet, psbt)
+            + get_fee_for_psbt(psbt).map_err(SpendRuleViolation::InvalidTransaction)?;
+        if spend_sats <= self.limit_sats {
+            Ok(())
+        } else {
+            Err(SpendRuleViolation::PerTransactionLimitExceeded {
+                spend_sats,
+                limit_sats: self.limit_sats,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bdk_utils::bdk::wallet::AddressIndex;
+
+    use super::*;
+    use crate::spend_rules::test_utils::{alice_wallet, bob_wallet, generate_psbt};
+
+    #[test]
+    fn per_transaction_cap_rule() {
+        let alice_wallet = alice_wallet();
+        let bob_address = bob_wallet().get_address(AddressIndex::New).unwrap();
+        let psbt = generate_psbt(&alice_wallet, &bob_address, 10_000, 5.0);
+        let fee_sats = get_fee_for_psbt(&psbt).unwrap();
+        assert!(fee_sats > 0);
+
+        let rule = PerTransactionCapRule::new(&alice_wallet, 10_000 + fee_sats);
+        assert!(rule.check_transaction(&psbt).is_ok());
+
+        // The amount sent fits under the cap, but not once the fee is added.
+        let rule = PerTransactionCapRule::new(&alice_wallet, 10_000);
+        assert_eq!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::PerTransactionLimitExceeded {
+                spend_sats: 10_000 + fee_sats,
+                limit_sats: 10_000,
+            })
+        );
+    }
+}
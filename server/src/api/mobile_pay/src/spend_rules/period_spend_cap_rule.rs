@@ -0,0 +1,142 @@
+use time::OffsetDateTime;
+
+use account::spend_policy::SpendPeriod;
+use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
+use bdk_utils::bdk::database::AnyDatabase;
+use bdk_utils::bdk::Wallet;
+
+use crate::daily_spend_record::entities::SpendingEntry;
+use crate::util::{get_fee_for_psbt, get_total_outflow_for_psbt, total_sats_spent_since};
+
+use super::{Rule, SpendRuleViolation};
+
+pub(crate) struct PeriodSpendCapRule<'a> {
+    wallet: &'a Wallet<AnyDatabase>,
+    period: SpendPeriod,
+    limit_sats: u64,
+    spending_history: &'a [&'a SpendingEntry],
+    now_utc: OffsetDateTime,
+}
+
+impl<'a> PeriodSpendCapRule<'a> {
+    pub fn new(
+        wallet: &'a Wallet<AnyDatabase>,
+        period: SpendPeriod,
+        limit_sats: u64,
+        spending_history: &'a [&'a SpendingEntry],
+        now_utc: OffsetDateTime,
+    ) -> Self {
+        PeriodSpendCapRule {
+            wallet,
+            period,
+            limit_sats,
+            spending_history,
+            now_utc,
+        }
+    }
+}
+
+impl<'a> Rule for PeriodSpendCapRule<'a> {
+    /// Ensure that the total outflows for this PSBT, including its fee, plus the outflows over the
+    /// rolling period do not exceed the cap
+    fn check_transaction(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        let spent_sats =
+            total_sats_spent_since(self.spending_history, self.now_utc - self.period.duration());
+        let spend_sats = get_total_outflow_for_psbt(self.wallet, psbt)
+            + get_fee_for_psbt(psbt).map_err(SpendRuleViolation::InvalidTransaction)?;
+        if spend_sats + spent_sats <= self.limit_sats {
+            Ok(())
+        } else {
+            Err(SpendRuleViolation::PeriodSpendingLimitExceeded {
+                period: self.period,
+                spend_sats,
+                spent_sats,
+                limit_sats: self.limit_sats,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use bdk_utils::bdk::bitcoin::consensus::deserialize;
+    use bdk_utils::bdk::wallet::AddressIndex;
+
+    use super::*;
+    use crate::spend_rules::test_utils::{alice_wallet, bob_wallet, generate_psbt};
+
+    fn spending_entry(outflow_amount: u64, timestamp: OffsetDateTime) -> SpendingEntry {
+        SpendingEntry {
+            txid: deserialize(&[0_u8; 32]).unwrap(),
+            timestamp,
+            outflow_amount,
+        }
+    }
+
+    #[test]
+    fn period_spend_cap_rule_only_counts_spends_within_period() {
+        let now_utc = datetime!(2023-03-31 12:00:00 UTC);
+        let alice_wallet = alice_wallet();
+        let bob_address = bob_wallet().get_address(AddressIndex::New).unwrap();
+        let psbt = generate_psbt(&alice_wallet, &bob_address, 10_000, 5.0);
+        let fee_sats = get_fee_for_psbt(&psbt).unwrap();
+        assert!(fee_sats > 0);
+
+        let history = vec![
+            spending_entry(20_000, now_utc - Duration::days(2)),
+            spending_entry(30_000, now_utc - Duration::days(10)),
+        ];
+        let spending_entries = history.iter().collect::<Vec<_>>();
+
+        // Only the spend from two days ago falls within the week.
+        let rule = PeriodSpendCapRule::new(
+            &alice_wallet,
+            SpendPeriod::Weekly,
+            30_000 + fee_sats,
+            &spending_entries,
+            now_utc,
+        );
+        assert!(rule.check_transaction(&psbt).is_ok());
+
+        // The fee counts towards the cap as well as the amount sent.
+        let rule = PeriodSpendCapRule::new(
+            &alice_wallet,
+            SpendPeriod::Weekly,
+            30_000,
+            &spending_entries,
+            now_utc,
+        );
+        assert_eq!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::PeriodSpendingLimitExceeded {
+                period: SpendPeriod::Weekly,
+                spend_sats: 10_000 + fee_sats,
+                spent_sats: 20_000,
+                limit_sats: 30_000,
+            })
+        );
+
+        // Both spends fall within the month.
+        let rule = PeriodSpendCapRule::new(
+            &alice_wallet,
+            SpendPeriod::Monthly,
+            50_000,
+            &spending_entries,
+            now_utc,
+        );
+        assert_eq!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::PeriodSpendingLimitExceeded {
+                period: SpendPeriod::Monthly,
+                spend_sats: 10_000 + fee_sats,
+                spent_sats: 50_000,
+                limit_sats: 50_000,
+            })
+        );
+    }
+}
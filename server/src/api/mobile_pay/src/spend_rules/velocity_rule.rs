@@ -0,0 +1,103 @@
+use time::OffsetDateTime;
+
+use account::spend_policy::SpendPeriod;
+use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
+
+use crate::daily_spend_record::entities::SpendingEntry;
+
+use super::{Rule, SpendRuleViolation};
+
+pub(crate) struct VelocityRule<'a> {
+    period: SpendPeriod,
+    max_transactions: u32,
+    spending_history: &'a [&'a SpendingEntry],
+    now_utc: OffsetDateTime,
+}
+
+impl<'a> VelocityRule<'a> {
+    pub fn new(
+        period: SpendPeriod,
+        max_transactions: u32,
+        spending_history: &'a [&'a SpendingEntry],
+        now_utc: OffsetDateTime,
+    ) -> Self {
+        VelocityRule {
+            period,
+            max_transactions,
+            spending_history,
+            now_utc,
+        }
+    }
+}
+
+impl<'a> Rule for VelocityRule<'a> {
+    /// Ensure that cosigning this PSBT does not take the number of transactions over the rolling
+    /// period past the limit
+    fn check_transaction(
+        &self,
+        _psbt: &PartiallySignedTransaction,
+    ) -> Result<(), SpendRuleViolation> {
+        let start_of_window_utc = self.now_utc - self.period.duration();
+        let transaction_count = self
+            .spending_history
+            .iter()
+            .filter(|spend| spend.timestamp >= start_of_window_utc)
+            .count()
+            + 1;
+        if transaction_count <= self.max_transactions as usize {
+            Ok(())
+        } else {
+            Err(SpendRuleViolation::TransactionVelocityExceeded {
+                period: self.period,
+                transaction_count,
+                max_transactions: self.max_transactions,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, Duration};
+
+    use bdk_utils::bdk::bitcoin::absolute::LockTime;
+    use bdk_utils::bdk::bitcoin::consensus::deserialize;
+    use bdk_utils::bdk::bitcoin::Transaction;
+
+    use super::*;
+
+    #[test]
+    fn velocity_rule_counts_transactions_within_period() {
+        let now_utc = datetime!(2023-03-31 12:00:00 UTC);
+        let psbt = PartiallySignedTransaction::from_unsigned_tx(Transaction {
+            version: 0,
+            lock_time: LockTime::ZERO,
+            input: Vec::new(),
+            output: Vec::new(),
+        })
+        .unwrap();
+
+        let history = [Duration::hours(1), Duration::hours(23), Duration::hours(25)]
+            .into_iter()
+            .map(|age| SpendingEntry {
+                txid: deserialize(&[0_u8; 32]).unwrap(),
+                timestamp: now_utc - age,
+                outflow_amount: 1_000,
+            })
+            .collect::<Vec<_>>();
+        let spending_entries = history.iter().collect::<Vec<_>>();
+
+        let rule = VelocityRule::new(SpendPeriod::Daily, 3, &spending_entries, now_utc);
+        assert!(rule.check_transaction(&psbt).is_ok());
+
+        let rule = VelocityRule::new(SpendPeriod::Weekly, 3, &spending_entries, now_utc);
+        assert_eq!(
+            rule.check_transaction(&psbt),
+            Err(SpendRuleViolation::TransactionVelocityExceeded {
+                period: SpendPeriod::Weekly,
+                transaction_count: 4,
+                max_transactions: 3,
+            })
+        );
+    }
+}
@@ -1,5 +1,6 @@
-use account::spend_limit::SpendingLimit;
+use account::spend_limit::{SpendWindow, SpendingLimit};
 use bdk_utils::bdk::bitcoin::psbt::Psbt;
+use bdk_utils::bdk::bitcoin::{TxOut, Weight};
 use bdk_utils::{AttributableWallet, PsbtWithDerivation};
 use thiserror::Error;
 use time::{Duration, OffsetDateTime, Time, UtcOffset};
@@ -9,6 +10,12 @@ use errors::ApiError;
 
 const START_OF_WINDOW_HOUR: u8 = 3; // 3 AM is the start of the quickspend window
 
+// Witness weight of a signed 2-of-3 P2WSH input: the item count, the empty element consumed by
+// OP_CHECKMULTISIG, two DER signatures with sighash flags, and the length-prefixed witness script.
+const MULTISIG_INPUT_WITNESS_WEIGHT: u64 = 1 + 1 + 2 * (1 + 72) + (1 + 105);
+// Segwit marker and flag bytes
+const SEGWIT_HEADER_WEIGHT: u64 = 2;
+
 #[derive(Error, Debug, Clone)]
 pub enum MobilepayDatetimeError {
     #[error("Could not perform datetime arithmetic {0}")]
@@ -28,6 +35,17 @@ impl From<MobilepayDatetimeError> for ApiError {
 }
 
 pub(crate) fn get_total_outflow_for_psbt(wallet: &dyn AttributableWallet, psbt: &Psbt) -> u64 {
+    get_external_outputs_for_psbt(wallet, psbt)
+        .iter()
+        .map(|output| output.value)
+        .sum()
+}
+
+/// Returns the outputs of the PSBT that don't pay back into `wallet`.
+pub(crate) fn get_external_outputs_for_psbt<'a>(
+    wallet: &dyn AttributableWallet,
+    psbt: &'a Psbt,
+) -> Vec<&'a TxOut> {
     psbt.unsigned_tx
         .output
         .iter()
@@ -40,15 +58,30 @@ pub(crate) fn get_total_outflow_for_psbt(wallet: &dyn AttributableWallet, psbt:
                 .get_output_spk_and_derivation(*idx)
                 .is_some_and(|spk| wallet.is_my_psbt_address(&spk).is_ok_and(|x| x))
         })
-        .map(|(_idx, output)| output.value)
-        .sum()
+        .map(|(_idx, output)| output)
+        .collect()
 }
 
 pub(crate) fn total_sats_spent_today(
-    spending_entries: &Vec<&SpendingEntry>,
+    spending_entries: &[&SpendingEntry],
     limit: &SpendingLimit,
     now_utc: OffsetDateTime,
 ) -> Result<u64, String> {
+    Ok(total_sats_spent_since(
+        spending_entries,
+        start_of_limit_window(limit, now_utc)?,
+    ))
+}
+
+/// Returns the start of the window the daily [`SpendingLimit`] currently covers, in UTC.
+pub(crate) fn start_of_limit_window(
+    limit: &SpendingLimit,
+    now_utc: OffsetDateTime,
+) -> Result<OffsetDateTime, String> {
+    if limit.window == SpendWindow::Rolling {
+        return Ok(now_utc - Duration::DAY);
+    }
+
     let timezone_offset = limit.time_zone_offset;
     let current_timezone_dt = now_utc.to_offset(timezone_offset);
     let start_of_window_time = Time::from_hms(START_OF_WINDOW_HOUR, 0, 0)
@@ -60,10 +93,36 @@ pub(crate) fn total_sats_spent_today(
         current_timezone_dt.replace_time(start_of_window_time)
     };
 
-    let start_of_window_utc = start_of_window_dt.to_offset(UtcOffset::UTC);
+    Ok(start_of_window_dt.to_offset(UtcOffset::UTC))
+}
 
-    Ok(spending_entries
+/// Sums the outflows of the spends made at or after `start_utc`.
+pub(crate) fn total_sats_spent_since(
+    spending_entries: &[&SpendingEntry],
+    start_utc: OffsetDateTime,
+) -> u64 {
+    spending_entries
         .iter()
-        .filter(|&spend| spend.timestamp >= start_of_window_utc)
-        .fold(0, |acc, &spend| acc + spend.outflow_amount))
+        .filter(|spend| spend.timestamp >= start_utc)
+        .map(|spend| spend.outflow_amount)
+        .sum()
+}
+
+/// Estimates the fee rate, in sats per vbyte, the PSBT will pay once both of its signatures have
+/// been added.
+pub(crate) fn estimate_fee_rate_for_psbt(psbt: &Psbt) -> Result<f32, String> {
+    let fee = get_fee_for_psbt(psbt)?;
+    let weight = Weight::from_wu(
+        psbt.unsigned_tx.weight().to_wu()
+            + SEGWIT_HEADER_WEIGHT
+            + MULTISIG_INPUT_WITNESS_WEIGHT * psbt.inputs.len() as u64,
+    );
+    Ok(fee as f32 / weight.to_vbytes_ceil() as f32)
+}
+
+/// Returns the fee, in sats, the PSBT pays.
+pub(crate) fn get_fee_for_psbt(psbt: &Psbt) -> Result<u64, String> {
+    psbt.fee()
+        .map(|fee| fee.to_sat())
+        .map_err(|err| format!("Could not calculate fee for PSBT: {err}"))
 }
@@ -1,15 +1,28 @@
 use bdk_utils::bdk::bitcoin::address::NetworkUnchecked;
 use bdk_utils::bdk::bitcoin::Address;
 use serde::{Deserialize, Serialize};
+use time::format_description::FormatItem;
+use time::macros::format_description;
 use time::OffsetDateTime;
 use types::account::identifiers::{AccountId, KeysetId};
 
+pub(crate) const CREATED_DATE_FORMAT: &[FormatItem<'_>] =
+    format_description!("[year]-[month]-[day]");
+// Fixed width, so that times sort lexicographically within a date.
+pub(crate) const CREATED_TIME_FORMAT: &[FormatItem<'_>] =
+    format_description!("[hour]:[minute]:[second].[subsecond digits:9]");
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) struct WatchedAddress {
     pub(crate) account_id: AccountId,
     pub(crate) address: Address<NetworkUnchecked>,
     spending_keyset_id: KeysetId,
     created_at: OffsetDateTime,
+    // Keys of the CreatedDateIndex. Addresses watched before the index existed don't have them.
+    #[serde(default)]
+    created_date: String,
+    #[serde(default)]
+    created_time: String,
 }
 
 impl WatchedAddress {
@@ -19,11 +32,14 @@ impl WatchedAddress {
         account_id: &AccountId,
         created_at: &OffsetDateTime,
     ) -> Self {
+        let created_at = created_at.to_offset(time::UtcOffset::UTC);
         Self {
             account_id: account_id.clone(),
             address: address.clone(),
             spending_keyset_id: spending_keyset_id.clone(),
-            created_at: *created_at,
+            created_at,
+            created_date: created_at.format(&CREATED_DATE_FORMAT).unwrap(),
+            created_time: created_at.format(&CREATED_TIME_FORMAT).unwrap(),
         }
     }
 }
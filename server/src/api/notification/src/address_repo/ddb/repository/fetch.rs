@@ -1,5 +1,7 @@
-use crate::address_repo::ddb::entities::WatchedAddress;
-use crate::address_repo::ddb::repository::{Repository, HASH_KEY};
+use crate::address_repo::ddb::entities::{
+    WatchedAddress, CREATED_DATE_FORMAT, CREATED_TIME_FORMAT,
+};
+use crate::address_repo::ddb::repository::{Repository, CREATED_SECONDARY_INDEX_NAME, HASH_KEY};
 use bdk_utils::bdk::bitcoin::address::NetworkUnchecked;
 use bdk_utils::bdk::bitcoin::Address;
 use database::aws_sdk_dynamodb::error::ProvideErrorMetadata;
@@ -10,6 +12,7 @@ use database::ddb::{
 };
 use futures::future::try_join_all;
 use std::collections::HashMap;
+use time::{Duration, OffsetDateTime, UtcOffset};
 use tracing::{event, instrument, Level};
 
 const DDB_BATCH_READ_SIZE_MAX: usize = 100;
@@ -48,6 +51,137 @@ impl Repository {
             .flatten()
             .collect::<Vec<WatchedAddress>>())
     }
+
+    #[instrument(skip(self))]
+    pub(crate) async fn fetch_all_addresses(
+        &self,
+    ) -> Result<Vec<Address<NetworkUnchecked>>, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let mut addresses = vec![];
+        let mut exclusive_start_key = None;
+
+        loop {
+            let item_output = self
+                .connection
+                .client
+                .scan()
+                .set_exclusive_start_key(exclusive_start_key)
+                .table_name(table_name.clone())
+                .send()
+                .await
+                .map_err(|err| {
+                    let service_err = err.into_service_error();
+                    event!(
+                        Level::ERROR,
+                        "Could not query database: {service_err:?} with message: {:?}",
+                        service_err.message()
+                    );
+                    DatabaseError::FetchError(database_object)
+                })?;
+
+            for item in item_output.items() {
+                let watched_address: WatchedAddress = try_from_item(item.clone(), database_object)?;
+                addresses.push(watched_address.address);
+            }
+
+            match item_output.last_evaluated_key() {
+                Some(last_evaluated_key) => {
+                    exclusive_start_key = Some(last_evaluated_key.clone());
+                }
+                None => {
+                    break;
+                }
+            }
+        }
+        Ok(addresses)
+    }
+
+    /// Returns the addresses added at or after `since`, querying the CreatedDateIndex one day at
+    /// a time up to today.
+    #[instrument(skip(self))]
+    pub(crate) async fn fetch_addresses_created_since(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<Address<NetworkUnchecked>>, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let since = since.to_offset(UtcOffset::UTC);
+        let today = OffsetDateTime::now_utc().date();
+        let mut date = since.date();
+        // Only the first day is bounded by time, later days are read in full.
+        let mut created_time_lower = Some(since.format(&CREATED_TIME_FORMAT).map_err(|err| {
+            event!(Level::ERROR, "Could not format time: {err}");
+            DatabaseError::FetchError(database_object)
+        })?);
+
+        let mut addresses = vec![];
+        while date <= today {
+            let created_date = date.format(&CREATED_DATE_FORMAT).map_err(|err| {
+                event!(Level::ERROR, "Could not format date: {err}");
+                DatabaseError::FetchError(database_object)
+            })?;
+            let mut exclusive_start_key = None;
+
+            loop {
+                let query = self
+                    .connection
+                    .client
+                    .query()
+                    .table_name(table_name.clone())
+                    .index_name(CREATED_SECONDARY_INDEX_NAME)
+                    .expression_attribute_values(
+                        ":created_date",
+                        AttributeValue::S(created_date.clone()),
+                    );
+                let query = match &created_time_lower {
+                    Some(created_time_lower) => query
+                        .key_condition_expression(
+                            "created_date = :created_date AND created_time >= :created_time_lower",
+                        )
+                        .expression_attribute_values(
+                            ":created_time_lower",
+                            AttributeValue::S(created_time_lower.clone()),
+                        ),
+                    None => query.key_condition_expression("created_date = :created_date"),
+                };
+                let item_output = query
+                    .set_exclusive_start_key(exclusive_start_key)
+                    .send()
+                    .await
+                    .map_err(|err| {
+                        let service_err = err.into_service_error();
+                        event!(
+                            Level::ERROR,
+                            "Could not query database: {service_err:?} with message: {:?}",
+                            service_err.message()
+                        );
+                        DatabaseError::FetchError(database_object)
+                    })?;
+
+                for item in item_output.items() {
+                    let watched_address: WatchedAddress =
+                        try_from_item(item.clone(), database_object)?;
+                    addresses.push(watched_address.address);
+                }
+
+                match item_output.last_evaluated_key() {
+                    Some(last_evaluated_key) => {
+                        exclusive_start_key = Some(last_evaluated_key.clone());
+                    }
+                    None => {
+                        break;
+                    }
+                }
+            }
+
+            created_time_lower = None;
+            date += Duration::days(1);
+        }
+        Ok(addresses)
+    }
 }
 
 #[instrument]
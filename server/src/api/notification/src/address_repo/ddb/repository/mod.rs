@@ -2,7 +2,8 @@ use async_trait::async_trait;
 use database::aws_sdk_dynamodb::error::ProvideErrorMetadata;
 use database::{
     aws_sdk_dynamodb::types::{
-        AttributeDefinition, BillingMode, KeySchemaElement, KeyType, ScalarAttributeType,
+        AttributeDefinition, BillingMode, GlobalSecondaryIndex, KeySchemaElement, KeyType,
+        Projection, ProjectionType, ScalarAttributeType,
     },
     ddb::{Connection, DDBService, DatabaseError, DatabaseObject},
 };
@@ -12,6 +13,9 @@ mod fetch;
 mod persist;
 
 const HASH_KEY: &str = "address";
+const CREATED_SECONDARY_INDEX_NAME: &str = "CreatedDateIndex";
+const CREATED_SECONDARY_PARTITION_KEY: &str = "created_date";
+const CREATED_SECONDARY_SORT_KEY: &str = "created_time";
 
 #[derive(Clone, Debug)]
 pub struct Repository {
@@ -58,6 +62,34 @@ impl DDBService for Repository {
             .attribute_name(HASH_KEY)
             .key_type(KeyType::Hash)
             .build()?;
+        let created_secondary_pk = AttributeDefinition::builder()
+            .attribute_name(CREATED_SECONDARY_PARTITION_KEY)
+            .attribute_type(ScalarAttributeType::S)
+            .build()?;
+        let created_secondary_sk = AttributeDefinition::builder()
+            .attribute_name(CREATED_SECONDARY_SORT_KEY)
+            .attribute_type(ScalarAttributeType::S)
+            .build()?;
+        let created_secondary_index = GlobalSecondaryIndex::builder()
+            .index_name(CREATED_SECONDARY_INDEX_NAME)
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name(CREATED_SECONDARY_PARTITION_KEY)
+                    .key_type(KeyType::Hash)
+                    .build()?,
+            )
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name(CREATED_SECONDARY_SORT_KEY)
+                    .key_type(KeyType::Range)
+                    .build()?,
+            )
+            .projection(
+                Projection::builder()
+                    .projection_type(ProjectionType::All)
+                    .build(),
+            )
+            .build()?;
 
         self.connection
             .client
@@ -65,7 +97,10 @@ impl DDBService for Repository {
             .table_name(table_name.clone())
             .billing_mode(BillingMode::PayPerRequest)
             .attribute_definitions(pk_attribute_definition)
+            .attribute_definitions(created_secondary_pk)
+            .attribute_definitions(created_secondary_sk)
             .key_schema(pk_key_schema)
+            .global_secondary_indexes(created_secondary_index)
             .send()
             .await
             .map_err(|err| {
@@ -79,4 +79,15 @@ impl AddressWatchlistTrait for Service {
             .map(|item| (item.address, item.account_id))
             .collect());
     }
+
+    async fn fetch_all(&self) -> Result<Vec<Address<NetworkUnchecked>>, Error> {
+        Ok(self.repo.fetch_all_addresses().await?)
+    }
+
+    async fn fetch_added_since(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<Address<NetworkUnchecked>>, Error> {
+        Ok(self.repo.fetch_addresses_created_since(since).await?)
+    }
 }
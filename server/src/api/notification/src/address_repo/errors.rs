@@ -1,6 +1,6 @@
 use bdk_utils::bdk::bitcoin::address;
 use database::ddb::DatabaseError;
-use errors::ApiError;
+use errors::{ApiError, ErrorCode};
 use thiserror::Error;
 use tracing::{event, Level};
 use types::account::identifiers::AccountId;
@@ -34,7 +34,7 @@ impl From<Error> for ApiError {
                     "Address serialization error: {}",
                     err.to_string()
                 );
-                return ApiError::GenericBadRequest(err.to_string());
+                return ApiError::specific(ErrorCode::InvalidAddress, err.to_string());
             }
             Error::DatabaseError(err) => {
                 event!(Level::ERROR, "Database error: {}", err.to_string());
@@ -8,11 +8,15 @@ use bdk_utils::bdk::bitcoin::address::NetworkUnchecked;
 use bdk_utils::bdk::bitcoin::Address;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use time::OffsetDateTime;
 use types::account::identifiers::AccountId;
 
+// Each address's account and when it was added
+type WatchedAddresses = HashMap<Address<NetworkUnchecked>, (AccountId, OffsetDateTime)>;
+
 #[derive(Debug, Default, Clone)]
 pub struct Service {
-    repository: Arc<Mutex<HashMap<Address<NetworkUnchecked>, AccountId>>>,
+    repository: Arc<Mutex<WatchedAddresses>>,
 }
 
 #[async_trait]
@@ -28,7 +32,7 @@ impl AddressWatchlistTrait for Service {
             .map_err(|err| InternalError(err.to_string()))?;
 
         for AddressAndKeysetId { address, .. } in addresses {
-            if let Some(id) = repo.get(&address.clone()) {
+            if let Some((id, _)) = repo.get(&address.clone()) {
                 if id != account_id {
                     return Err(Error::AccountMismatchError(
                         address.clone().assume_checked().to_string(),
@@ -37,7 +41,10 @@ impl AddressWatchlistTrait for Service {
                 }
             }
 
-            repo.insert(address.clone(), account_id.clone());
+            repo.insert(
+                address.clone(),
+                (account_id.clone(), OffsetDateTime::now_utc()),
+            );
         }
         Ok(())
     }
@@ -53,7 +60,32 @@ impl AddressWatchlistTrait for Service {
 
         return Ok(addrs
             .iter()
-            .filter_map(|k| repo.get(k).map(|v| (k.clone(), v.clone())))
+            .filter_map(|k| repo.get(k).map(|(v, _)| (k.clone(), v.clone())))
             .collect());
     }
+
+    async fn fetch_all(&self) -> Result<Vec<Address<NetworkUnchecked>>, Error> {
+        let repo = self
+            .repository
+            .lock()
+            .map_err(|err| InternalError(err.to_string()))?;
+
+        Ok(repo.keys().cloned().collect())
+    }
+
+    async fn fetch_added_since(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<Address<NetworkUnchecked>>, Error> {
+        let repo = self
+            .repository
+            .lock()
+            .map_err(|err| InternalError(err.to_string()))?;
+
+        Ok(repo
+            .iter()
+            .filter(|(_, (_, created_at))| *created_at >= since)
+            .map(|(address, _)| address.clone())
+            .collect())
+    }
 }
@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use std::fmt;
 
 use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 
 use crate::address_repo::errors::Error;
 use bdk_utils::bdk::bitcoin::address::NetworkUnchecked;
@@ -34,6 +35,17 @@ pub trait AddressWatchlistTrait: DynClone + fmt::Debug + Send + Sync {
         &self,
         addresses: &[Address<NetworkUnchecked>],
     ) -> Result<HashMap<Address<NetworkUnchecked>, AccountId>, Error>;
+
+    /// Return every address in the AddressWatchlist. This reads the whole watchlist, so it's only
+    /// meant for chain backends that need to subscribe to each address.
+    async fn fetch_all(&self) -> Result<Vec<Address<NetworkUnchecked>>, Error>;
+
+    /// Return the addresses added to the AddressWatchlist at or after `since`, so that chain
+    /// backends can pick up new addresses without rereading the whole watchlist.
+    async fn fetch_added_since(
+        &self,
+        since: OffsetDateTime,
+    ) -> Result<Vec<Address<NetworkUnchecked>>, Error>;
 }
 
 dyn_clone::clone_trait_object!(AddressWatchlistTrait);
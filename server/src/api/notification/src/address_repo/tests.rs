@@ -8,6 +8,7 @@ use bdk_utils::bdk::bitcoin::{Address, Network, PublicKey};
 use database::ddb;
 use http_server::config;
 use rstest::{fixture, rstest};
+use time::OffsetDateTime;
 use types::account::identifiers::{AccountId, KeysetId};
 
 fn memory_repo() -> impl AddressWatchlistTrait {
@@ -278,3 +279,44 @@ async fn test_batch_get_mixed_known_and_unknown_query_superset_succeeds<F>(
     // no additional items are in the returned hashmap
     assert_eq!(subset.len(), stored_addresses.len());
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_fetch_all_includes_inserted(
+    #[values(memory_repo(), ddb_repo().await)] mut repo: impl AddressWatchlistTrait,
+) {
+    let acct_id = AccountId::gen().unwrap();
+    let addresses: Vec<AddressAndKeysetId> = (1..=3).map(|_| testnet_address()).collect();
+
+    repo.insert(&addresses, &acct_id).await.unwrap();
+
+    // The DDB table is shared with other tests, so it may contain other addresses too
+    let all_addresses = repo.fetch_all().await.unwrap();
+    for addr in &addresses {
+        assert!(all_addresses.contains(&addr.address));
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_fetch_added_since_excludes_earlier(
+    #[values(memory_repo(), ddb_repo().await)] mut repo: impl AddressWatchlistTrait,
+) {
+    let acct_id = AccountId::gen().unwrap();
+    let earlier_address = testnet_address();
+    let later_addresses: Vec<AddressAndKeysetId> = (1..=3).map(|_| testnet_address()).collect();
+
+    repo.insert(&[earlier_address.clone()], &acct_id)
+        .await
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    let since = OffsetDateTime::now_utc();
+    repo.insert(&later_addresses, &acct_id).await.unwrap();
+
+    // The DDB table is shared with other tests, so it may contain other addresses too
+    let added_addresses = repo.fetch_added_since(since).await.unwrap();
+    assert!(!added_addresses.contains(&earlier_address.address));
+    for addr in &later_addresses {
+        assert!(added_addresses.contains(&addr.address));
+    }
+}
@@ -60,7 +60,7 @@ impl From<NotificationClientsError> for ApiError {
             },
             NotificationClientsError::MacError(_)
             | NotificationClientsError::Base64DecodeError(_) => {
-                ApiError::GenericUnauthorized(err_msg)
+                ApiError::specific(ErrorCode::InvalidCallbackSignature, err_msg)
             }
         }
     }
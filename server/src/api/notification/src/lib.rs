@@ -5,7 +5,10 @@ use database::ddb::DatabaseError;
 use derive_builder::Builder;
 use errors::ApiError;
 use payloads::{
-    comms_verification::CommsVerificationPayload, push_blast::PushBlastPayload,
+    comms_verification::CommsVerificationPayload,
+    inheritance_claim_canceled::InheritanceClaimCanceledPayload,
+    inheritance_claim_completed::InheritanceClaimCompletedPayload,
+    inheritance_claim_pending::InheritanceClaimPendingPayload, push_blast::PushBlastPayload,
     recovery_canceled_delay_period::RecoveryCanceledDelayPeriodPayload,
     recovery_relationship_deleted::RecoveryRelationshipDeletedPayload,
     recovery_relationship_invitation_accepted::RecoveryRelationshipInvitationAcceptedPayload,
@@ -23,10 +26,11 @@ use ulid::DecodeError;
 use self::{
     email::EmailPayload,
     payloads::{
-        payment::PaymentPayload,
+        outgoing_payment::OutgoingPaymentPayload, payment::PaymentPayload,
+        pending_payment::PendingPaymentPayload,
         recovery_completed_delay_period::RecoveryCompletedDelayPeriodPayload,
         recovery_pending_delay_period::RecoveryPendingDelayPeriodPayload,
-        test_notification::TestNotificationPayload,
+        settled_payment::SettledPaymentPayload, test_notification::TestNotificationPayload,
     },
     push::SNSPushPayload,
 };
@@ -102,11 +106,17 @@ pub enum NotificationPayloadType {
     RecoveryCompletedDelayPeriod,
     RecoveryCanceledDelayPeriod,
     PaymentNotification,
+    PendingPaymentNotification,
+    SettledPaymentNotification,
+    OutgoingPaymentNotification,
     CommsVerification,
     RecoveryRelationshipInvitationAccepted,
     RecoveryRelationshipDeleted,
     SocialChallengeResponseReceived,
     PushBlast,
+    InheritanceClaimPending,
+    InheritanceClaimCanceled,
+    InheritanceClaimCompleted,
 }
 
 impl From<NotificationPayloadType> for NotificationCategory {
@@ -120,10 +130,18 @@ impl From<NotificationPayloadType> for NotificationCategory {
             | NotificationPayloadType::RecoveryRelationshipInvitationAccepted
             | NotificationPayloadType::SocialChallengeResponseReceived
             | NotificationPayloadType::PushBlast
+            | NotificationPayloadType::InheritanceClaimPending
+            | NotificationPayloadType::InheritanceClaimCanceled
+            | NotificationPayloadType::InheritanceClaimCompleted
             | NotificationPayloadType::TestPushNotification => {
                 NotificationCategory::AccountSecurity
             }
-            NotificationPayloadType::PaymentNotification => NotificationCategory::MoneyMovement,
+            NotificationPayloadType::PaymentNotification
+            | NotificationPayloadType::PendingPaymentNotification
+            | NotificationPayloadType::SettledPaymentNotification
+            | NotificationPayloadType::OutgoingPaymentNotification => {
+                NotificationCategory::MoneyMovement
+            }
         }
     }
 }
@@ -161,6 +179,18 @@ impl NotificationPayloadType {
                 builder.payment_payload(payload.payment_payload.clone());
                 payload.payment_payload.is_some()
             }
+            NotificationPayloadType::PendingPaymentNotification => {
+                builder.pending_payment_payload(payload.pending_payment_payload.clone());
+                payload.pending_payment_payload.is_some()
+            }
+            NotificationPayloadType::SettledPaymentNotification => {
+                builder.settled_payment_payload(payload.settled_payment_payload.clone());
+                payload.settled_payment_payload.is_some()
+            }
+            NotificationPayloadType::OutgoingPaymentNotification => {
+                builder.outgoing_payment_payload(payload.outgoing_payment_payload.clone());
+                payload.outgoing_payment_payload.is_some()
+            }
             NotificationPayloadType::CommsVerification => {
                 builder.comms_verification_payload(payload.comms_verification_payload.clone());
                 payload.comms_verification_payload.is_some()
@@ -191,6 +221,24 @@ impl NotificationPayloadType {
                 builder.push_blast_payload(payload.push_blast_payload.clone());
                 payload.push_blast_payload.is_some()
             }
+            NotificationPayloadType::InheritanceClaimPending => {
+                builder.inheritance_claim_pending_payload(
+                    payload.inheritance_claim_pending_payload.clone(),
+                );
+                payload.inheritance_claim_pending_payload.is_some()
+            }
+            NotificationPayloadType::InheritanceClaimCanceled => {
+                builder.inheritance_claim_canceled_payload(
+                    payload.inheritance_claim_canceled_payload.clone(),
+                );
+                payload.inheritance_claim_canceled_payload.is_some()
+            }
+            NotificationPayloadType::InheritanceClaimCompleted => {
+                builder.inheritance_claim_completed_payload(
+                    payload.inheritance_claim_completed_payload.clone(),
+                );
+                payload.inheritance_claim_completed_payload.is_some()
+            }
         };
         if valid_payload {
             builder
@@ -262,6 +310,26 @@ impl
                     .payment_payload
                     .ok_or(NotificationError::PayloadNotFound(payload_type))?,
             )),
+            NotificationPayloadType::PendingPaymentNotification => NotificationMessage::try_from((
+                composite_key,
+                payload
+                    .pending_payment_payload
+                    .ok_or(NotificationError::PayloadNotFound(payload_type))?,
+            )),
+            NotificationPayloadType::SettledPaymentNotification => NotificationMessage::try_from((
+                composite_key,
+                payload
+                    .settled_payment_payload
+                    .ok_or(NotificationError::PayloadNotFound(payload_type))?,
+            )),
+            NotificationPayloadType::OutgoingPaymentNotification => {
+                NotificationMessage::try_from((
+                    composite_key,
+                    payload
+                        .outgoing_payment_payload
+                        .ok_or(NotificationError::PayloadNotFound(payload_type))?,
+                ))
+            }
             NotificationPayloadType::RecoveryCanceledDelayPeriod => {
                 NotificationMessage::try_from((
                     composite_key,
@@ -320,6 +388,26 @@ impl
                     .push_blast_payload
                     .ok_or(NotificationError::PayloadNotFound(payload_type))?,
             )),
+            NotificationPayloadType::InheritanceClaimPending => NotificationMessage::try_from((
+                composite_key,
+                payload
+                    .inheritance_claim_pending_payload
+                    .ok_or(NotificationError::PayloadNotFound(payload_type))?,
+            )),
+            NotificationPayloadType::InheritanceClaimCanceled => NotificationMessage::try_from((
+                composite_key,
+                payload
+                    .inheritance_claim_canceled_payload
+                    .ok_or(NotificationError::PayloadNotFound(payload_type))?,
+            )),
+            NotificationPayloadType::InheritanceClaimCompleted => {
+                NotificationMessage::try_from((
+                    composite_key,
+                    payload
+                        .inheritance_claim_completed_payload
+                        .ok_or(NotificationError::PayloadNotFound(payload_type))?,
+                ))
+            }
         }
     }
 }
@@ -354,6 +442,12 @@ pub struct NotificationPayload {
     #[serde(default)]
     pub payment_payload: Option<PaymentPayload>,
     #[serde(default)]
+    pub pending_payment_payload: Option<PendingPaymentPayload>,
+    #[serde(default)]
+    pub settled_payment_payload: Option<SettledPaymentPayload>,
+    #[serde(default)]
+    pub outgoing_payment_payload: Option<OutgoingPaymentPayload>,
+    #[serde(default)]
     pub comms_verification_payload: Option<CommsVerificationPayload>,
     #[serde(default)]
     pub recovery_relationship_invitation_accepted_payload:
@@ -364,4 +458,10 @@ pub struct NotificationPayload {
     pub social_challenge_response_received_payload: Option<SocialChallengeResponseReceivedPayload>,
     #[serde(default)]
     pub push_blast_payload: Option<PushBlastPayload>,
+    #[serde(default)]
+    pub inheritance_claim_pending_payload: Option<InheritanceClaimPendingPayload>,
+    #[serde(default)]
+    pub inheritance_claim_canceled_payload: Option<InheritanceClaimCanceledPayload>,
+    #[serde(default)]
+    pub inheritance_claim_completed_payload: Option<InheritanceClaimCompletedPayload>,
 }
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use types::recovery::inheritance::InheritanceClaimId;
+
+use crate::{
+    entities::NotificationCompositeKey,
+    push::{AndroidChannelId, SNSPushPayload},
+    sms::SmsPayload,
+    NotificationError, NotificationMessage,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub enum InheritanceClaimRecipient {
+    Customer,
+    Beneficiary,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct InheritanceClaimCanceledPayload {
+    pub inheritance_claim_id: InheritanceClaimId,
+    pub recipient: InheritanceClaimRecipient,
+    // The recipient's alias for the other party to the claim
+    pub alias: String,
+}
+
+impl TryFrom<(NotificationCompositeKey, InheritanceClaimCanceledPayload)> for NotificationMessage {
+    type Error = NotificationError;
+
+    fn try_from(
+        v: (NotificationCompositeKey, InheritanceClaimCanceledPayload),
+    ) -> Result<Self, Self::Error> {
+        let (composite_key, payload) = v;
+        let (account_id, _) = composite_key.clone();
+
+        let message = match payload.recipient {
+            InheritanceClaimRecipient::Customer => format!(
+                "The inheritance claim {} started on your Bitkey wallet has been canceled.",
+                payload.alias
+            ),
+            InheritanceClaimRecipient::Beneficiary => format!(
+                "Your inheritance claim on {}'s Bitkey wallet has been canceled.",
+                payload.alias
+            ),
+        };
+
+        Ok(NotificationMessage {
+            composite_key,
+            account_id,
+            email_payload: None,
+            push_payload: Some(SNSPushPayload {
+                message: message.clone(),
+                android_channel_id: AndroidChannelId::RecoveryAccountSecurity,
+                ..Default::default()
+            }),
+            sms_payload: Some(SmsPayload {
+                message,
+                unsupported_country_codes: None,
+            }),
+        })
+    }
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use types::recovery::inheritance::InheritanceClaimId;
+
+use crate::{
+    entities::NotificationCompositeKey,
+    push::{AndroidChannelId, SNSPushPayload},
+    sms::SmsPayload,
+    NotificationError, NotificationMessage,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct InheritanceClaimCompletedPayload {
+    pub inheritance_claim_id: InheritanceClaimId,
+    pub trusted_contact_alias: String,
+}
+
+impl TryFrom<(NotificationCompositeKey, InheritanceClaimCompletedPayload)> for NotificationMessage {
+    type Error = NotificationError;
+
+    fn try_from(
+        v: (NotificationCompositeKey, InheritanceClaimCompletedPayload),
+    ) -> Result<Self, Self::Error> {
+        let (composite_key, payload) = v;
+        let (account_id, _) = composite_key.clone();
+
+        let message = format!(
+            "The funds in your Bitkey wallet have been transferred to {} as part of their inheritance claim.",
+            payload.trusted_contact_alias
+        );
+
+        Ok(NotificationMessage {
+            composite_key,
+            account_id,
+            email_payload: None,
+            push_payload: Some(SNSPushPayload {
+                message: message.clone(),
+                android_channel_id: AndroidChannelId::RecoveryAccountSecurity,
+                ..Default::default()
+            }),
+            sms_payload: Some(SmsPayload {
+                message,
+                unsupported_country_codes: None,
+            }),
+        })
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use time::{serde::rfc3339, OffsetDateTime};
+use types::recovery::inheritance::InheritanceClaimId;
+
+use crate::{
+    entities::NotificationCompositeKey,
+    push::{AndroidChannelId, SNSPushPayload},
+    sms::SmsPayload,
+    NotificationError, NotificationMessage,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct InheritanceClaimPendingPayload {
+    pub inheritance_claim_id: InheritanceClaimId,
+    pub trusted_contact_alias: String,
+    #[serde(with = "rfc3339")]
+    pub delay_end_time: OffsetDateTime,
+}
+
+impl TryFrom<(NotificationCompositeKey, InheritanceClaimPendingPayload)> for NotificationMessage {
+    type Error = NotificationError;
+
+    fn try_from(
+        v: (NotificationCompositeKey, InheritanceClaimPendingPayload),
+    ) -> Result<Self, Self::Error> {
+        let (composite_key, payload) = v;
+        let (account_id, _) = composite_key.clone();
+
+        let calendar_end_date = payload.delay_end_time.to_calendar_date();
+        let message = format!(
+            "{} has started an inheritance claim on your Bitkey wallet, and will be able to transfer your funds after {} {} {}. If you didn't expect this, please cancel the claim in your Bitkey app.",
+            payload.trusted_contact_alias,
+            calendar_end_date.2,
+            calendar_end_date.1,
+            calendar_end_date.0
+        );
+
+        Ok(NotificationMessage {
+            composite_key,
+            account_id,
+            email_payload: None,
+            push_payload: Some(SNSPushPayload {
+                message: message.clone(),
+                android_channel_id: AndroidChannelId::RecoveryAccountSecurity,
+                ..Default::default()
+            }),
+            sms_payload: Some(SmsPayload {
+                message,
+                unsupported_country_codes: None,
+            }),
+        })
+    }
+}
@@ -1,10 +1,16 @@
 pub mod comms_verification;
+pub mod inheritance_claim_canceled;
+pub mod inheritance_claim_completed;
+pub mod inheritance_claim_pending;
+pub mod outgoing_payment;
 pub mod payment;
+pub mod pending_payment;
 pub mod push_blast;
 pub mod recovery_canceled_delay_period;
 pub mod recovery_completed_delay_period;
 pub mod recovery_pending_delay_period;
 pub mod recovery_relationship_deleted;
 pub mod recovery_relationship_invitation_accepted;
+pub mod settled_payment;
 pub mod social_challenge_response_received;
 pub mod test_notification;
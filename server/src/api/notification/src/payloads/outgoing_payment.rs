@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use types::account::identifiers::AccountId;
+
+use crate::{
+    entities::NotificationCompositeKey, push::AndroidChannelId, push::SNSPushPayload,
+    NotificationError, NotificationMessage,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct OutgoingPaymentPayload {
+    pub account_id: AccountId,
+    pub txid: String,
+}
+
+impl TryFrom<(NotificationCompositeKey, OutgoingPaymentPayload)> for NotificationMessage {
+    type Error = NotificationError;
+
+    fn try_from(
+        v: (NotificationCompositeKey, OutgoingPaymentPayload),
+    ) -> Result<Self, Self::Error> {
+        let (composite_key, payload) = v;
+        Ok(NotificationMessage {
+            composite_key,
+            account_id: payload.account_id,
+            email_payload: None,
+            push_payload: Some(SNSPushPayload {
+                message: "Your payment has been confirmed.".to_owned(),
+                android_channel_id: AndroidChannelId::Transactions,
+                ..Default::default()
+            }),
+            sms_payload: None,
+        })
+    }
+}
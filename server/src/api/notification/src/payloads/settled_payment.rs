@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use types::account::identifiers::AccountId;
+
+use crate::{
+    entities::NotificationCompositeKey, push::AndroidChannelId, push::SNSPushPayload,
+    NotificationError, NotificationMessage,
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SettledPaymentPayload {
+    pub account_id: AccountId,
+    pub txid: String,
+    pub confirmations: u64,
+}
+
+impl TryFrom<(NotificationCompositeKey, SettledPaymentPayload)> for NotificationMessage {
+    type Error = NotificationError;
+
+    fn try_from(v: (NotificationCompositeKey, SettledPaymentPayload)) -> Result<Self, Self::Error> {
+        let (composite_key, payload) = v;
+        Ok(NotificationMessage {
+            composite_key,
+            account_id: payload.account_id,
+            email_payload: None,
+            push_payload: Some(SNSPushPayload {
+                message: format!(
+                    "Your received bitcoin has {} confirmations and is fully settled.",
+                    payload.confirmations
+                ),
+                android_channel_id: AndroidChannelId::Transactions,
+                ..Default::default()
+            }),
+            sms_payload: None,
+        })
+    }
+}
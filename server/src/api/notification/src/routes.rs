@@ -11,7 +11,7 @@ use axum::{
     Form, Json, Router,
 };
 use axum_extra::TypedHeader;
-use errors::ApiError;
+use errors::{ApiError, ErrorCategory, ErrorCode, ErrorResponseBody, ErrorResponseBodyError};
 use http_server::swagger::{SwaggerEndpoint, Url};
 
 use serde::{Deserialize, Serialize};
@@ -99,6 +99,7 @@ impl From<RouteState> for SwaggerEndpoint {
         schemas(SendTestPushData, SendTestPushResponse),
         schemas(RegisterWatchAddressRequest, RegisterWatchAddressResponse),
         schemas(NotificationsPreferences, NotificationChannel),
+        schemas(ErrorCategory, ErrorCode, ErrorResponseBody, ErrorResponseBodyError),
     ),
     tags(
         (name = "Notification", description = "Touchpoints with Users")
@@ -124,7 +125,7 @@ pub struct SendTestPushResponse {}
     request_body = SendTestPushData,
     responses(
         (status = 200, description = "Test Notification was created", body=SendTestPushResponse),
-        (status = 404, description = "Wallet not found")
+        (status = 404, description = "Wallet not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn send_test_push(
@@ -194,7 +195,7 @@ pub struct RegisterWatchAddressResponse {}
     request_body = RegisterWatchAddressRequest,
     responses(
         (status = 200, description = "Addresses successfully registered", body=RegisterWatchAddressResponse),
-        (status = 404, description = "Account not found")
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn add_address(
@@ -256,7 +257,7 @@ mod x_twilio_signature {
     request_body = HashMap<String, String>,
     responses(
         (status = 204, description = "Callback successful"),
-        (status = 401, description = "Request failed signature validation")
+        (status = 401, description = "Request failed signature validation", body = ErrorResponseBody)
     ),
 )]
 pub async fn twilio_status_callback(
@@ -14,6 +14,7 @@ pub type ScheduledNotificationEvent = (
 pub enum ScheduleNotificationType {
     TestPushNotification,
     RecoveryPendingDelayNotify(OffsetDateTime),
+    InheritanceClaimPending(OffsetDateTime),
 }
 
 impl ScheduleNotificationType {
@@ -35,14 +36,15 @@ impl ScheduleNotificationType {
                 vec![
                     (
                         // Starts now
-                        // Sends every 2 days
+                        // Sends every third of the delay period (at least daily, at most weekly)
                         // Ends at delay end
                         // =
                         // DAYS 0, 2, 4, 6 (standard 7-day window)
+                        // DAYS 0, 7, 14, 21, 28 (30-day window)
                         NotificationPayloadType::RecoveryPendingDelayPeriod,
                         now,
                         Some(NotificationSchedule {
-                            interval: Duration::days(2),
+                            interval: pending_delay_notify_interval(*delay_end_time - now),
                             end_date_time: Some(*delay_end_time),
                             jitter: Some(Duration::ZERO),
                         }),
@@ -77,6 +79,46 @@ impl ScheduleNotificationType {
                     ),
                 ]
             }
+            ScheduleNotificationType::InheritanceClaimPending(delay_end_time) => {
+                vec![(
+                    // Starts now
+                    // Sends every 3 days
+                    // Ends at delay end
+                    NotificationPayloadType::InheritanceClaimPending,
+                    now,
+                    Some(NotificationSchedule {
+                        interval: Duration::days(3),
+                        end_date_time: Some(*delay_end_time),
+                        jitter: Some(Duration::ZERO),
+                    }),
+                )]
+            }
+        }
+    }
+}
+
+/// How often to remind the customer of a pending recovery, so that a delay period of any length
+/// gets around three reminders before it ends.
+fn pending_delay_notify_interval(delay_period: Duration) -> Duration {
+    Duration::days((delay_period.whole_days() / 3).clamp(1, 7))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::Duration;
+
+    use super::pending_delay_notify_interval;
+
+    #[test]
+    fn test_pending_delay_notify_interval() {
+        for (delay_period, interval) in [
+            (Duration::seconds(20), Duration::days(1)),
+            (Duration::days(3), Duration::days(1)),
+            (Duration::days(7), Duration::days(2)),
+            (Duration::days(14), Duration::days(4)),
+            (Duration::days(30), Duration::days(7)),
+        ] {
+            assert_eq!(pending_delay_notify_interval(delay_period), interval);
         }
     }
 }
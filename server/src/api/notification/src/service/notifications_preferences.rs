@@ -1,8 +1,12 @@
 use account::entities::{Account, CommonAccountFields, Touchpoint};
-use errors::ApiError;
+use account::service::RecordSecurityEventInput;
+use authn_authz::key_claims::RequiredFactors;
+use errors::{ApiError, ErrorCode};
 use repository::consent::Repository as ConsentRepository;
 use tracing::instrument;
-use types::{consent::Consent, notification::NotificationsPreferences};
+use types::{
+    consent::Consent, notification::NotificationsPreferences, security_event::SecurityEventType,
+};
 
 use crate::clients::iterable::IterableUserId;
 
@@ -54,45 +58,70 @@ impl Service {
             .is_subset(&input.notifications_preferences.account_security)
             && !input
                 .key_proof
-                .map_or(false, |kp| kp.app_signed && kp.hw_signed)
+                .map_or(false, |kp| kp.has_factors(RequiredFactors::AppAndHw))
         {
-            return Err(ApiError::GenericForbidden(
-                "valid signature over access token required by both app and hw auth keys"
-                    .to_string(),
+            return Err(ApiError::specific(
+                ErrorCode::AppAndHwSignatureRequired,
+                "valid signature over access token required by both app and hw auth keys",
             ));
         }
 
-        if !account.get_common_fields().onboarding_complete {
-            self.iterable_client
-                .set_initial_subscribed_notification_categories(
-                    IterableUserId::Account(input.account_id),
-                    input
-                        .notifications_preferences
-                        .get_email_notification_categories(),
+        let update = async {
+            if !account.get_common_fields().onboarding_complete {
+                self.iterable_client
+                    .set_initial_subscribed_notification_categories(
+                        IterableUserId::Account(input.account_id),
+                        input
+                            .notifications_preferences
+                            .get_email_notification_categories(),
+                    )
+                    .await?;
+            } else {
+                self.iterable_client
+                    .set_subscribed_notification_categories(
+                        IterableUserId::Account(input.account_id),
+                        input
+                            .notifications_preferences
+                            .get_email_notification_categories(),
+                    )
+                    .await?;
+            }
+
+            let updated_account = account
+                .update(CommonAccountFields {
+                    notifications_preferences: input.notifications_preferences.clone(),
+                    ..account.get_common_fields().clone()
+                })
+                .map_err(ApiError::from)?;
+
+            self.account_repo
+                .persist(&updated_account)
+                .await
+                .map_err(ApiError::from)?;
+            Ok::<_, ApiError>(updated_account)
+        };
+
+        let account_security = &input.notifications_preferences.account_security;
+        let updated_account = if *account_security
+            != account
+                .get_common_fields()
+                .notifications_preferences
+                .account_security
+        {
+            self.account_service
+                .record_security_event(
+                    RecordSecurityEventInput {
+                        account_id: input.account_id,
+                        event: SecurityEventType::AccountSecurityNotificationsUpdated {
+                            channels: account_security.clone(),
+                        },
+                    },
+                    update,
                 )
-                .await?;
+                .await?
         } else {
-            self.iterable_client
-                .set_subscribed_notification_categories(
-                    IterableUserId::Account(input.account_id),
-                    input
-                        .notifications_preferences
-                        .get_email_notification_categories(),
-                )
-                .await?;
-        }
-
-        let updated_account = account
-            .update(CommonAccountFields {
-                notifications_preferences: input.notifications_preferences.clone(),
-                ..account.get_common_fields().clone()
-            })
-            .map_err(ApiError::from)?;
-
-        self.account_repo
-            .persist(&updated_account)
-            .await
-            .map_err(ApiError::from)?;
+            update.await?
+        };
 
         capture_consents(
             &self.consent_repo,
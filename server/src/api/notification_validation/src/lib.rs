@@ -3,19 +3,25 @@ use error::NotificationValidationError;
 use notification::{
     entities::NotificationCompositeKey,
     payloads::{
-        comms_verification::CommsVerificationPayload, payment::PaymentPayload,
-        push_blast::PushBlastPayload,
+        comms_verification::CommsVerificationPayload,
+        inheritance_claim_canceled::InheritanceClaimCanceledPayload,
+        inheritance_claim_completed::InheritanceClaimCompletedPayload,
+        inheritance_claim_pending::InheritanceClaimPendingPayload,
+        outgoing_payment::OutgoingPaymentPayload, payment::PaymentPayload,
+        pending_payment::PendingPaymentPayload, push_blast::PushBlastPayload,
         recovery_canceled_delay_period::RecoveryCanceledDelayPeriodPayload,
         recovery_completed_delay_period::RecoveryCompletedDelayPeriodPayload,
         recovery_pending_delay_period::RecoveryPendingDelayPeriodPayload,
         recovery_relationship_deleted::RecoveryRelationshipDeletedPayload,
         recovery_relationship_invitation_accepted::RecoveryRelationshipInvitationAcceptedPayload,
+        settled_payment::SettledPaymentPayload,
         social_challenge_response_received::SocialChallengeResponseReceivedPayload,
         test_notification::TestNotificationPayload,
     },
     NotificationPayload, NotificationPayloadType,
 };
 use recovery::{entities::RecoveryStatus, repository::Repository as RecoveryRepository};
+use repository::recovery::social::Repository as SocialRecoveryRepository;
 use time::OffsetDateTime;
 
 mod error;
@@ -23,11 +29,18 @@ mod error;
 #[derive(Clone)]
 pub struct NotificationValidationState {
     recovery_service: RecoveryRepository,
+    social_recovery_repository: SocialRecoveryRepository,
 }
 
 impl NotificationValidationState {
-    pub fn new(recovery_service: RecoveryRepository) -> Self {
-        Self { recovery_service }
+    pub fn new(
+        recovery_service: RecoveryRepository,
+        social_recovery_repository: SocialRecoveryRepository,
+    ) -> Self {
+        Self {
+            recovery_service,
+            social_recovery_repository,
+        }
     }
 }
 
@@ -70,6 +83,18 @@ pub fn to_validator(
                 .payment_payload
                 .as_ref()
                 .ok_or(NotificationValidationError::ToValidatorError)?,
+            NotificationPayloadType::PendingPaymentNotification => payload
+                .pending_payment_payload
+                .as_ref()
+                .ok_or(NotificationValidationError::ToValidatorError)?,
+            NotificationPayloadType::SettledPaymentNotification => payload
+                .settled_payment_payload
+                .as_ref()
+                .ok_or(NotificationValidationError::ToValidatorError)?,
+            NotificationPayloadType::OutgoingPaymentNotification => payload
+                .outgoing_payment_payload
+                .as_ref()
+                .ok_or(NotificationValidationError::ToValidatorError)?,
             NotificationPayloadType::RecoveryRelationshipInvitationAccepted => payload
                 .recovery_relationship_invitation_accepted_payload
                 .as_ref()
@@ -86,6 +111,18 @@ pub fn to_validator(
                 .push_blast_payload
                 .as_ref()
                 .ok_or(NotificationValidationError::ToValidatorError)?,
+            NotificationPayloadType::InheritanceClaimPending => payload
+                .inheritance_claim_pending_payload
+                .as_ref()
+                .ok_or(NotificationValidationError::ToValidatorError)?,
+            NotificationPayloadType::InheritanceClaimCanceled => payload
+                .inheritance_claim_canceled_payload
+                .as_ref()
+                .ok_or(NotificationValidationError::ToValidatorError)?,
+            NotificationPayloadType::InheritanceClaimCompleted => payload
+                .inheritance_claim_completed_payload
+                .as_ref()
+                .ok_or(NotificationValidationError::ToValidatorError)?,
         };
     Ok(validator)
 }
@@ -187,6 +224,39 @@ impl ValidateNotificationDelivery for PaymentPayload {
     }
 }
 
+#[async_trait]
+impl ValidateNotificationDelivery for PendingPaymentPayload {
+    async fn validate_delivery(
+        &self,
+        _state: &NotificationValidationState,
+        _composite_key: &NotificationCompositeKey,
+    ) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl ValidateNotificationDelivery for SettledPaymentPayload {
+    async fn validate_delivery(
+        &self,
+        _state: &NotificationValidationState,
+        _composite_key: &NotificationCompositeKey,
+    ) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl ValidateNotificationDelivery for OutgoingPaymentPayload {
+    async fn validate_delivery(
+        &self,
+        _state: &NotificationValidationState,
+        _composite_key: &NotificationCompositeKey,
+    ) -> bool {
+        true
+    }
+}
+
 #[async_trait]
 impl ValidateNotificationDelivery for RecoveryRelationshipInvitationAcceptedPayload {
     async fn validate_delivery(
@@ -230,3 +300,44 @@ impl ValidateNotificationDelivery for PushBlastPayload {
         true
     }
 }
+
+#[async_trait]
+impl ValidateNotificationDelivery for InheritanceClaimPendingPayload {
+    async fn validate_delivery(
+        &self,
+        state: &NotificationValidationState,
+        _composite_key: &NotificationCompositeKey,
+    ) -> bool {
+        let claim_result = state
+            .social_recovery_repository
+            .fetch_inheritance_claim(&self.inheritance_claim_id)
+            .await;
+
+        if let Ok(claim) = claim_result {
+            return claim.is_pending() && OffsetDateTime::now_utc() < claim.delay_end_time;
+        }
+        false
+    }
+}
+
+#[async_trait]
+impl ValidateNotificationDelivery for InheritanceClaimCanceledPayload {
+    async fn validate_delivery(
+        &self,
+        _state: &NotificationValidationState,
+        _composite_key: &NotificationCompositeKey,
+    ) -> bool {
+        true
+    }
+}
+
+#[async_trait]
+impl ValidateNotificationDelivery for InheritanceClaimCompletedPayload {
+    async fn validate_delivery(
+        &self,
+        _state: &NotificationValidationState,
+        _composite_key: &NotificationCompositeKey,
+    ) -> bool {
+        true
+    }
+}
@@ -9,7 +9,7 @@ use argon2::{
 use axum::routing::{delete, get, post, put};
 use axum::Router;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
 use axum_extra::{
@@ -22,7 +22,7 @@ use isocountry::CountryCode;
 use notification::entities::NotificationTouchpoint;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use time::Duration;
+use time::{serde::rfc3339, Duration, OffsetDateTime};
 use tracing::{error, event, instrument, Level};
 use userpool::userpool::{CreateRecoveryUserInput, CreateWalletUserInput, UserPoolService};
 use utoipa::{OpenApi, ToSchema};
@@ -37,10 +37,10 @@ use account::service::{
     ActivateTouchpointForAccountInput, AddPushTouchpointToAccountInput, CompleteOnboardingInput,
     CreateAccountAndKeysetsInput, CreateInactiveSpendingKeysetInput, CreateLiteAccountInput,
     DeleteAccountInput, FetchAccountInput, FetchOrCreateEmailTouchpointInput,
-    FetchOrCreatePhoneTouchpointInput, FetchTouchpointByIdInput, RotateToSpendingKeysetInput,
-    Service as AccountService, UpgradeLiteAccountToFullAccountInput,
+    FetchOrCreatePhoneTouchpointInput, FetchSecurityEventsInput, FetchTouchpointByIdInput,
+    RotateToSpendingKeysetInput, Service as AccountService, UpgradeLiteAccountToFullAccountInput,
 };
-use authn_authz::key_claims::KeyClaims;
+use authn_authz::key_claims::{claims_from_verified_jwt, KeyClaims, RequiredFactors};
 use bdk_utils::{
     bdk::{
         bitcoin::{secp256k1::PublicKey, Network},
@@ -52,7 +52,9 @@ use comms_verification::{
     ConsumeVerificationForScopeInput, InitiateVerificationForScopeInput,
     Service as CommsVerificationService, VerifyForScopeInput,
 };
-use errors::{ApiError, ErrorCode, RouteError};
+use errors::{
+    ApiError, ErrorCategory, ErrorCode, ErrorResponseBody, ErrorResponseBodyError, RouteError,
+};
 use external_identifier::ExternalIdentifier;
 use feature_flags::service::Service as FeatureFlagsService;
 use http_server::middlewares::identifier_generator::IdentifierGenerator;
@@ -62,6 +64,7 @@ use notification::clients::twilio::{TwilioClient, TwilioMode};
 use notification::service::Service as NotificationService;
 use recovery::repository::Repository as RecoveryService;
 use types::account::identifiers::{AccountId, AuthKeysId, KeysetId, TouchpointId};
+use types::security_event::{SecurityEvent, SecurityEventStatus, SecurityEventType};
 use wsm_rust_client::{SigningService, WsmClient};
 
 use crate::account_validation::{AccountValidation, AccountValidationRequest};
@@ -76,6 +79,9 @@ static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
     .unwrap()
 });
 
+const DEFAULT_SECURITY_EVENTS_PAGE_SIZE: i32 = 50;
+const MAX_SECURITY_EVENTS_PAGE_SIZE: i32 = 100;
+
 #[derive(Clone, Deserialize)]
 pub struct Config {
     use_local_sns: bool,
@@ -139,6 +145,10 @@ impl RouteState {
                 "/api/accounts/:account_id/complete-onboarding",
                 post(complete_onboarding),
             )
+            .route(
+                "/api/accounts/:account_id/security-events",
+                get(list_security_events),
+            )
             .route_layer(metrics::FACTORY.route_layer("onboarding".to_owned()))
             .with_state(self.to_owned())
     }
@@ -185,6 +195,7 @@ impl From<RouteState> for SwaggerEndpoint {
         get_bdk_config,
         get_touchpoints_for_account,
         initiate_demo_mode,
+        list_security_events,
         rotate_spending_keyset,
         upgrade_account,
         verify_touchpoint_for_account,
@@ -217,16 +228,25 @@ impl From<RouteState> for SwaggerEndpoint {
             InitiateDemoModeRequest,
             InitiateDemoModeResponse,
             LiteAccountAuthKeysRequest,
+            ListSecurityEventsResponse,
             RotateSpendingKeysetRequest,
+            SecurityEventResponse,
+            SecurityEventStatus,
+            SecurityEventType,
             RotateSpendingKeysetResponse,
             SpendingKeysetRequest,
             TouchpointPlatform,
             UpgradeAccountRequest,
             UpgradeLiteAccountAuthKeysPayload,
+            ErrorCategory,
+            ErrorCode,
+            ErrorResponseBody,
+            ErrorResponseBodyError,
         ),
     ),
     tags(
-        (name = "Onboarding", description = "Wallet Creation and Touchpoint Setup")
+        (name = "Onboarding", description = "Wallet Creation and Touchpoint Setup"),
+        (name = "Security Events", description = "Account Security History")
     )
 )]
 struct ApiDoc;
@@ -250,7 +270,7 @@ pub struct AccountAddDeviceTokenResponse {}
     request_body = AccountAddDeviceTokenRequest,
     responses(
         (status = 200, description = "Device token was added", body=AccountAddDeviceTokenResponse),
-        (status = 400, description = "Input validation failed")
+        (status = 400, description = "Input validation failed", body = ErrorResponseBody)
     ),
 )]
 async fn add_device_token_to_account(
@@ -267,6 +287,7 @@ async fn add_device_token_to_account(
             platform: request.platform,
             device_token: request.device_token,
             access_token: bearer.token().to_string(),
+            session_id: claims_from_verified_jwt(bearer.token()).map(|claims| claims.origin_jti),
         })
         .await?;
 
@@ -551,11 +572,14 @@ async fn activate_touchpoint_for_account(
         })
         .await?;
     if account.get_common_fields().onboarding_complete
-        && !(key_proof.app_signed && key_proof.hw_signed)
+        && !key_proof.has_factors(RequiredFactors::AppAndHw)
     {
         let msg = "valid signature over access token required by both app and hw auth keys";
         error!("{msg}");
-        return Err(ApiError::GenericForbidden(msg.to_string()));
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            msg,
+        ));
     }
 
     let touchpoint = account_service
@@ -669,7 +693,7 @@ pub struct AccountStatusResponse {}
     ),
     responses(
         (status = 200, description = "Retrieved status for Account", body=AccountStatusResponse),
-        (status = 404, description = "Account not found")
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 async fn account_status(
@@ -824,7 +848,7 @@ impl TryFrom<&Account> for CreateAccountResponse {
     request_body = CreateAccountRequest,
     responses(
         (status = 200, description = "Account was created", body=CreateAccountResponse),
-        (status = 400, description = "Input validation failed")
+        (status = 400, description = "Input validation failed", body = ErrorResponseBody)
     ),
 )]
 // TODO: [W-1218] Abstract root key generation/public key derivation logic shared with
@@ -1033,7 +1057,7 @@ impl From<Network> for BitcoinNetwork {
     request_body = UpgradeAccountRequest,
     responses(
         (status = 200, description = "Account was upgraded to a full account", body=CreateAccountResponse),
-        (status = 400, description = "Input validation failed")
+        (status = 400, description = "Input validation failed", body = ErrorResponseBody)
     ),
 )]
 pub async fn upgrade_account(
@@ -1077,8 +1101,9 @@ pub async fn upgrade_account(
                 }
                 return Ok(Json(account));
             } else {
-                return Err(ApiError::GenericConflict(
-                    "Account is already a full account".to_string(),
+                return Err(ApiError::specific(
+                    ErrorCode::AccountAlreadyUpgraded,
+                    "Account is already a full account",
                 ));
             }
         }
@@ -1200,7 +1225,7 @@ pub struct CreateKeysetResponse {
     request_body = CreateKeysetRequest,
     responses(
         (status = 200, description = "New keyset was created for account", body=CreateKeysetResponse),
-        (status = 404, description = "Account not found")
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn create_keyset(
@@ -1210,13 +1235,14 @@ pub async fn create_keyset(
     State(wsm_client): State<WsmClient>,
     Json(request): Json<CreateKeysetRequest>,
 ) -> Result<Json<CreateKeysetResponse>, ApiError> {
-    if !(key_proof.hw_signed && key_proof.app_signed) {
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
         event!(
             Level::WARN,
             "valid signature over access token required by both app and hw auth keys"
         );
-        return Err(ApiError::GenericForbidden(
-            "valid signature over access token required by both app and hw auth keys".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            "valid signature over access token required by both app and hw auth keys",
         ));
     }
 
@@ -1322,8 +1348,8 @@ pub struct GetAccountKeysetsResponse {
     ),
     responses(
         (status = 200, description = "Retrieved the keysets for Account", body=AccountStatusResponse),
-        (status = 403, description = "Invalid access token"),
-        (status = 404, description = "Account not found")
+        (status = 403, description = "Invalid access token", body = ErrorResponseBody),
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn account_keysets(
@@ -1353,6 +1379,85 @@ pub async fn account_keysets(
     Ok(Json(GetAccountKeysetsResponse { keysets }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ListSecurityEventsQuery {
+    pub limit: Option<i32>,
+    pub page_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SecurityEventResponse {
+    pub event_id: String,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(flatten)]
+    pub event: SecurityEventType,
+    pub status: SecurityEventStatus,
+}
+
+impl From<SecurityEvent> for SecurityEventResponse {
+    fn from(security_event: SecurityEvent) -> Self {
+        Self {
+            event_id: security_event.event_id,
+            created_at: security_event.created_at,
+            event: security_event.event,
+            status: security_event.status,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListSecurityEventsResponse {
+    pub events: Vec<SecurityEventResponse>,
+    /// Passed back as `page_token` to fetch older events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+#[instrument(fields(account_id), skip(account_service))]
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/security-events",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+        ("limit" = Option<i32>, Query, description = "Maximum number of events to return"),
+        ("page_token" = Option<String>, Query, description = "next_page_token from the previous page"),
+    ),
+    responses(
+        (status = 200, description = "The account's security events, newest first", body=ListSecurityEventsResponse),
+        (status = 400, description = "Invalid page size", body = ErrorResponseBody),
+    ),
+    tag = "Security Events",
+)]
+pub async fn list_security_events(
+    Path(account_id): Path<AccountId>,
+    State(account_service): State<AccountService>,
+    Query(query): Query<ListSecurityEventsQuery>,
+) -> Result<Json<ListSecurityEventsResponse>, ApiError> {
+    let limit = query.limit.unwrap_or(DEFAULT_SECURITY_EVENTS_PAGE_SIZE);
+    if !(1..=MAX_SECURITY_EVENTS_PAGE_SIZE).contains(&limit) {
+        return Err(ApiError::GenericBadRequest(format!(
+            "limit must be between 1 and {MAX_SECURITY_EVENTS_PAGE_SIZE}"
+        )));
+    }
+
+    let (events, next_page_token) = account_service
+        .fetch_security_events(FetchSecurityEventsInput {
+            account_id: &account_id,
+            limit,
+            page_token: query.page_token.as_deref(),
+        })
+        .await?;
+
+    Ok(Json(ListSecurityEventsResponse {
+        events: events
+            .into_iter()
+            .map(SecurityEventResponse::from)
+            .collect(),
+        next_page_token,
+    }))
+}
+
 #[derive(Serialize, Deserialize, Debug, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct RotateSpendingKeysetRequest {}
@@ -1370,8 +1475,8 @@ pub struct RotateSpendingKeysetResponse {}
     ),
     responses(
         (status = 200, description = "Updated the active spending keyset", body=AccountStatusResponse),
-        (status = 403, description = "Invalid access token"),
-        (status = 404, description = "Account not found")
+        (status = 403, description = "Invalid access token", body = ErrorResponseBody),
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn rotate_spending_keyset(
@@ -1380,13 +1485,14 @@ pub async fn rotate_spending_keyset(
     State(account_service): State<AccountService>,
     Json(request): Json<RotateSpendingKeysetRequest>,
 ) -> Result<Json<RotateSpendingKeysetResponse>, ApiError> {
-    if !(key_proof.hw_signed && key_proof.app_signed) {
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
         event!(
             Level::WARN,
             "valid signature over access token required by both app and hw auth keys"
         );
-        return Err(ApiError::GenericForbidden(
-            "valid signature over access token required by both app and hw auth keys".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            "valid signature over access token required by both app and hw auth keys",
         ));
     }
 
@@ -1509,7 +1615,7 @@ pub async fn get_bdk_config(
     ),
     responses(
         (status = 200, description = "Account deleted"),
-        (status = 404, description = "Account not found"),
+        (status = 404, description = "Account not found", body = ErrorResponseBody),
     ),
 )]
 pub async fn delete_account(
@@ -1517,10 +1623,13 @@ pub async fn delete_account(
     key_proof: KeyClaims,
     Path(account_id): Path<AccountId>,
 ) -> Result<(), ApiError> {
-    if !(key_proof.app_signed && key_proof.hw_signed) {
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
         let msg = "valid signature over access token required by both app and hw auth keys";
         error!("{msg}");
-        return Err(ApiError::GenericForbidden(msg.to_string()));
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            msg,
+        ));
     }
 
     account_service
@@ -1546,7 +1655,7 @@ pub struct InitiateDemoModeResponse {}
     path = "/api/demo/initiate",
     responses(
         (status = 200, description = "Code is valid"),
-        (status = 400, description = "Code is not valid"),
+        (status = 400, description = "Code is not valid", body = ErrorResponseBody),
     ),
 )]
 pub async fn initiate_demo_mode(
@@ -1570,7 +1679,7 @@ pub async fn initiate_demo_mode(
     {
         Ok(Json(InitiateDemoModeResponse {}))
     } else {
-        Err(ApiError::GenericBadRequest("Invalid code".to_string()))
+        Err(ApiError::specific(ErrorCode::CodeMismatch, "Invalid code"))
     }
 }
 
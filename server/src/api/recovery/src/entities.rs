@@ -5,7 +5,7 @@ use time::{serde::rfc3339, Duration, OffsetDateTime};
 use utoipa::ToSchema;
 
 use account::entities::{Factor, FullAccount};
-use authn_authz::key_claims::KeyClaims;
+use authn_authz::key_claims::{KeyClaims, RequiredFactors};
 use bdk_utils::bdk::bitcoin::secp256k1::PublicKey;
 use types::account::identifiers::{AccountId, AuthKeysId};
 
@@ -130,20 +130,22 @@ pub(crate) trait ToActor {
 
 impl ToActor for KeyClaims {
     fn to_actor(&self, strategy: ToActorStrategy) -> Result<Factor, RecoveryError> {
+        let app_signed = self.has_factors(RequiredFactors::App);
+        let hw_signed = self.has_factors(RequiredFactors::Hw);
         match strategy {
             ToActorStrategy::PreferNonLostFactor(lost_factor) => {
                 match lost_factor {
                     Factor::App => {
-                        if self.hw_signed {
+                        if hw_signed {
                             return Ok(Factor::Hw);
-                        } else if self.app_signed {
+                        } else if app_signed {
                             return Ok(Factor::App);
                         }
                     }
                     Factor::Hw => {
-                        if self.app_signed {
+                        if app_signed {
                             return Ok(Factor::App);
-                        } else if self.hw_signed {
+                        } else if hw_signed {
                             return Ok(Factor::Hw);
                         }
                     }
@@ -151,11 +153,11 @@ impl ToActor for KeyClaims {
                 Err(RecoveryError::KeyProofRequired)
             }
             ToActorStrategy::ExclusiveOr => {
-                if self.app_signed && self.hw_signed {
+                if app_signed && hw_signed {
                     Err(RecoveryError::UnexpectedKeyProof)
-                } else if self.app_signed {
+                } else if app_signed {
                     Ok(Factor::App)
-                } else if self.hw_signed {
+                } else if hw_signed {
                     Ok(Factor::Hw)
                 } else {
                     Err(RecoveryError::KeyProofRequired)
@@ -166,14 +168,22 @@ impl ToActor for KeyClaims {
 }
 
 pub trait RecoveryValuesPerAccountType {
-    fn recovery_delay_period(&self) -> Duration;
+    fn recovery_delay_period(&self, now: OffsetDateTime) -> Duration;
+    fn inheritance_delay_period(&self) -> Duration;
 }
 
 impl RecoveryValuesPerAccountType for FullAccount {
-    fn recovery_delay_period(&self) -> Duration {
+    fn recovery_delay_period(&self, now: OffsetDateTime) -> Duration {
         match self.common_fields.properties.is_test_account {
             true => Duration::seconds(20),
-            false => Duration::days(7),
+            false => self.recovery_policy_at(now).delay_period(),
+        }
+    }
+
+    fn inheritance_delay_period(&self) -> Duration {
+        match self.common_fields.properties.is_test_account {
+            true => Duration::seconds(20),
+            false => Duration::days(30),
         }
     }
 }
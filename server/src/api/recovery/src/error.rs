@@ -78,6 +78,13 @@ pub enum RecoveryError {
     MalformedRecoveryRequirements,
     #[error("Cannot update parameters for non-test account")]
     InvalidUpdateForNonTestAccount,
+    #[error("Delay period must be between {min_delay} and {max_delay} days, and contest lookback between {min_lookback} and {max_lookback} days")]
+    InvalidRecoveryPolicy {
+        min_delay: u32,
+        max_delay: u32,
+        min_lookback: u32,
+        max_lookback: u32,
+    },
     #[error(transparent)]
     ApiError(#[from] ApiError),
     #[error("Destination hardware auth pubkey in use by an account")]
@@ -122,14 +129,12 @@ impl From<RecoveryError> for ApiError {
             | RecoveryError::InvalidRecoveryRelationshipType => {
                 ApiError::GenericInternalApplicationError(err_msg)
             }
-            RecoveryError::DelayPeriodNotFinished
-            | RecoveryError::ParsePSBT
+            RecoveryError::ParsePSBT
             | RecoveryError::SignPSBT
             | RecoveryError::NoSignaturePresent
             | RecoveryError::InvalidAuthKey
             | RecoveryError::InvalidFactorForCompletion
             | RecoveryError::InvalidInputForCompletion
-            | RecoveryError::KeyProofRequired
             | RecoveryError::UnexpectedKeyProof
             | RecoveryError::TouchpointStatusMismatch
             | RecoveryError::TouchpointTypeMismatch
@@ -139,6 +144,15 @@ impl From<RecoveryError> for ApiError {
             | RecoveryError::NoDestinationRecoveryAuthPubkey => {
                 ApiError::GenericBadRequest(err_msg)
             }
+            RecoveryError::DelayPeriodNotFinished => {
+                ApiError::specific(ErrorCode::DelayPeriodNotFinished, err_msg)
+            }
+            RecoveryError::InvalidRecoveryPolicy { .. } => {
+                ApiError::specific(ErrorCode::InvalidRecoveryPolicy, err_msg)
+            }
+            RecoveryError::KeyProofRequired => {
+                ApiError::specific(ErrorCode::KeyProofRequired, err_msg)
+            }
             RecoveryError::AccountService(err) => match err {
                 AccountError::DDBError(err) => err.into(),
                 _ => ApiError::GenericInternalApplicationError(err_msg),
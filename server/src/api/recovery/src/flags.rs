@@ -1,3 +1,4 @@
 use feature_flags::flag::Flag;
 
 pub(crate) const FLAG_SOCIAL_RECOVERY_ENABLE: Flag<bool> = Flag::new("f8e-social-recovery-enable");
+pub(crate) const FLAG_INHERITANCE_ENABLE: Flag<bool> = Flag::new("f8e-inheritance-enable");
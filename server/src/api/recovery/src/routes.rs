@@ -16,6 +16,9 @@ use time::serde::rfc3339;
 use time::{Duration, OffsetDateTime};
 use tracing::{event, instrument, Level};
 use types::authn_authz::cognito::CognitoUser;
+use types::recovery::inheritance::{
+    InheritanceClaim, InheritanceClaimId, InheritanceClaimStatus, InheritancePackage,
+};
 use types::recovery::social::challenge::{
     SocialChallenge, SocialChallengeId, SocialChallengeResponse, TrustedContactChallengeRequest,
 };
@@ -27,26 +30,39 @@ use utoipa::{OpenApi, ToSchema};
 use account::{
     entities::{CommsVerificationScope, Factor, FullAccountAuthKeysPayload, Touchpoint},
     error::AccountError,
+    recovery_policy::{
+        PendingRecoveryPolicy, RecoveryPolicy, MAX_CONTEST_LOOKBACK_DAYS, MAX_DELAY_PERIOD_DAYS,
+        MIN_CONTEST_LOOKBACK_DAYS, MIN_DELAY_PERIOD_DAYS,
+    },
     service::{
         ClearPushTouchpointsInput, CreateAndRotateAuthKeysInput, FetchAccountInput,
-        Service as AccountService,
+        FetchAndUpdateRecoveryPolicyInput, RecordSecurityEventInput, Service as AccountService,
     },
 };
-use authn_authz::key_claims::KeyClaims;
+use authn_authz::key_claims::{KeyClaims, RequiredFactors};
 use bdk_utils::{bdk::bitcoin::secp256k1::PublicKey, signature::check_signature};
 use comms_verification::{
     error::CommsVerificationError, InitiateVerificationForScopeInput,
     Service as CommsVerificationService, VerifyForScopeInput,
 };
-use errors::{ApiError, ErrorCode};
+use errors::{ApiError, ErrorCategory, ErrorCode, ErrorResponseBody, ErrorResponseBodyError};
 use feature_flags::service::Service as FeatureFlagsService;
 use http_server::swagger::{SwaggerEndpoint, Url};
 use notification::{entities::NotificationTouchpoint, service::Service as NotificationService};
 use types::account::identifiers::{AccountId, TouchpointId};
+use types::security_event::SecurityEventType;
 use wsm_rust_client::WsmClient;
 
 use crate::ensure_pubkeys_unique;
-use crate::flags::FLAG_SOCIAL_RECOVERY_ENABLE;
+use crate::flags::{FLAG_INHERITANCE_ENABLE, FLAG_SOCIAL_RECOVERY_ENABLE};
+use crate::service::inheritance::cancel_inheritance_claim::CancelInheritanceClaimInput;
+use crate::service::inheritance::complete_inheritance_claim::CompleteInheritanceClaimInput;
+use crate::service::inheritance::fetch_inheritance::{
+    FetchInheritanceClaimInput, FetchInheritanceForAccountInput,
+};
+use crate::service::inheritance::start_inheritance_claim::StartInheritanceClaimInput;
+use crate::service::inheritance::upload_inheritance_package::UploadInheritancePackageInput;
+use crate::service::inheritance::DEFAULT_INACTIVITY_PERIOD_DAYS;
 use crate::service::social::challenge::create_social_challenge::CreateSocialChallengeInput;
 use crate::service::social::challenge::fetch_social_challenge::{
     FetchSocialChallengeAsCustomerInput, FetchSocialChallengeAsTrustedContactInput,
@@ -67,6 +83,7 @@ use crate::{
     error::RecoveryError,
     metrics,
     repository::Repository as RecoveryRepository,
+    service::inheritance::Service as InheritanceService,
     service::social::challenge::Service as SocialChallengeService,
     service::social::relationship::Service as RecoveryRelationshipService,
     state_machine::{
@@ -87,6 +104,7 @@ pub struct RouteState(
     pub RecoveryRelationshipService,
     pub SocialChallengeService,
     pub FeatureFlagsService,
+    pub InheritanceService,
 );
 
 impl RouteState {
@@ -114,6 +132,14 @@ impl RouteState {
                 "/api/accounts/:account_id/delay-notify",
                 delete(cancel_delay_notify),
             )
+            .route(
+                "/api/accounts/:account_id/recovery/policy",
+                get(get_recovery_policy),
+            )
+            .route(
+                "/api/accounts/:account_id/recovery/policy",
+                put(update_recovery_policy),
+            )
             .route(
                 "/api/accounts/:account_id/recovery",
                 get(get_recovery_status),
@@ -138,6 +164,14 @@ impl RouteState {
                 "/api/accounts/:account_id/recovery/relationships",
                 put(endorse_recovery_relationships),
             )
+            .route(
+                "/api/accounts/:account_id/recovery/inheritance/packages",
+                put(upload_inheritance_package),
+            )
+            .route(
+                "/api/accounts/:account_id/recovery/inheritance/claims/:inheritance_claim_id/complete",
+                post(complete_inheritance_claim),
+            )
             .route_layer(metrics::FACTORY.route_layer("recovery".to_owned()))
             .with_state(self.to_owned())
     }
@@ -182,6 +216,22 @@ impl RouteState {
                 "/api/accounts/:account_id/recovery/relationship-invitations/:code",
                 get(get_recovery_relationship_invitation_for_code),
             )
+            .route(
+                "/api/accounts/:account_id/recovery/inheritance",
+                get(get_inheritance),
+            )
+            .route(
+                "/api/accounts/:account_id/recovery/inheritance/claims",
+                post(start_inheritance_claim),
+            )
+            .route(
+                "/api/accounts/:account_id/recovery/inheritance/claims/:inheritance_claim_id",
+                get(fetch_inheritance_claim),
+            )
+            .route(
+                "/api/accounts/:account_id/recovery/inheritance/claims/:inheritance_claim_id/cancel",
+                post(cancel_inheritance_claim),
+            )
             .route_layer(metrics::FACTORY.route_layer("recovery".to_owned()))
             .with_state(self.to_owned())
     }
@@ -200,29 +250,40 @@ impl From<RouteState> for SwaggerEndpoint {
 #[openapi(
     paths(
         cancel_delay_notify,
+        cancel_inheritance_claim,
         complete_delay_notify_transaction,
+        complete_inheritance_claim,
         create_delay_notify,
         create_recovery_relationship,
         delete_recovery_relationship,
         endorse_recovery_relationships,
+        fetch_inheritance_claim,
         fetch_social_challenge,
+        get_inheritance,
         get_recovery_relationship_invitation_for_code,
         get_recovery_relationships,
+        get_recovery_policy,
         get_recovery_status,
         respond_to_social_challenge,
         rotate_authentication_keys,
         send_verification_code,
+        start_inheritance_claim,
         start_social_challenge,
+        update_recovery_policy,
         update_recovery_relationship,
+        upload_inheritance_package,
         verify_code,
         verify_social_challenge,
     ),
     components(
         schemas(
             AuthenticationKey,
+            CancelInheritanceClaimResponse,
             CanceledRecoveryState,
             CompleteDelayNotifyRequest,
             CompleteDelayNotifyResponse,
+            CompleteInheritanceClaimRequest,
+            CompleteInheritanceClaimResponse,
             CreateAccountDelayNotifyRequest,
             CreateRecoveryRelationshipRequest,
             CreateRecoveryRelationshipResponse,
@@ -235,15 +296,23 @@ impl From<RouteState> for SwaggerEndpoint {
             EndorseRecoveryRelationshipsResponse,
             EndorsedTrustedContact,
             Factor,
+            FetchInheritanceClaimResponse,
             FetchSocialChallengeResponse,
             FullAccountAuthKeysPayload,
+            GetInheritanceResponse,
             GetRecoveryRelationshipInvitationForCodeResponse,
             GetRecoveryRelationshipsResponse,
             InboundInvitation,
+            InheritanceClaimStatus,
+            InheritanceClaimSummary,
+            InheritancePackageSummary,
             OutboundInvitation,
             PendingDelayNotifyRecovery,
+            PendingRecoveryPolicy,
             PendingRecoveryResponse,
             RecoveryAction,
+            RecoveryPolicy,
+            RecoveryPolicyResponse,
             RecoveryRequirements,
             RecoveryResponse,
             RecoveryRelationshipEndorsement,
@@ -255,18 +324,27 @@ impl From<RouteState> for SwaggerEndpoint {
             SendAccountVerificationCodeRequest,
             SendAccountVerificationCodeResponse,
             StartChallengeTrustedContactRequest,
+            StartInheritanceClaimRequest,
+            StartInheritanceClaimResponse,
             StartSocialChallengeRequest,
             StartSocialChallengeResponse,
             TrustedContact,
             TrustedContactSocialChallenge,
             UnendorsedTrustedContact,
+            UpdateRecoveryPolicyRequest,
             UpdateRecoveryRelationshipRequest,
             UpdateRecoveryRelationshipResponse,
+            UploadInheritancePackageRequest,
+            UploadInheritancePackageResponse,
             VerifyAccountVerificationCodeRequest,
             VerifyAccountVerificationCodeResponse,
             VerifySocialChallengeRequest,
             VerifySocialChallengeResponse,
             WalletRecovery,
+            ErrorCategory,
+            ErrorCode,
+            ErrorResponseBody,
+            ErrorResponseBodyError,
         )
     ),
     tags(
@@ -319,7 +397,7 @@ pub struct CreateAccountDelayNotifyRequest {
     request_body = CreateAccountDelayNotifyRequest,
     responses(
         (status = 200, description = "D&N Recovery was created", body=PendingRecoveryResponse),
-        (status = 404, description = "Account not found")
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn create_delay_notify(
@@ -439,8 +517,8 @@ async fn update_recovery_delay_for_test_account(
     request_body = UpdateDelayForTestRecoveryRequest,
     responses(
         (status = 200, description = "D&N Recovery was updated for test accounts only", body=PendingRecoveryResponse),
-        (status = 400, description = "Could not update the delay for this account"),
-        (status = 404, description = "Account not found")
+        (status = 400, description = "Could not update the delay for this account", body = ErrorResponseBody),
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn update_delay_for_test_account(
@@ -465,6 +543,168 @@ pub async fn update_delay_for_test_account(
     .await
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct UpdateRecoveryPolicyRequest {
+    pub policy: RecoveryPolicy,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct RecoveryPolicyResponse {
+    pub policy: RecoveryPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_policy: Option<PendingRecoveryPolicy>,
+}
+
+#[instrument(err, skip(account_service, recovery_service))]
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/recovery/policy",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    responses(
+        (status = 200, description = "The account's recovery policy and any pending change to it", body=RecoveryPolicyResponse),
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
+    ),
+)]
+pub async fn get_recovery_policy(
+    Path(account_id): Path<AccountId>,
+    State(account_service): State<AccountService>,
+    State(recovery_service): State<RecoveryRepository>,
+) -> Result<Json<RecoveryPolicyResponse>, ApiError> {
+    let full_account = account_service
+        .fetch_full_account(FetchAccountInput {
+            account_id: &account_id,
+        })
+        .await?;
+    let now = recovery_service.cur_time();
+
+    Ok(Json(RecoveryPolicyResponse {
+        policy: full_account.recovery_policy_at(now),
+        pending_policy: full_account
+            .pending_recovery_policy
+            .filter(|pending| pending.effective_at > now),
+    }))
+}
+
+///
+/// Requests a change to the account's recovery policy. The change only takes effect once the
+/// delay period of the policy in effect has passed, and requesting the policy in effect cancels
+/// a pending change.
+///
+#[instrument(err, skip(account_service, recovery_service))]
+#[utoipa::path(
+    put,
+    path = "/api/accounts/{account_id}/recovery/policy",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    request_body = UpdateRecoveryPolicyRequest,
+    responses(
+        (status = 200, description = "The change to the account's recovery policy was scheduled", body=RecoveryPolicyResponse),
+        (status = 400, description = "The policy is out of bounds", body = ErrorResponseBody),
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
+    ),
+)]
+pub async fn update_recovery_policy(
+    Path(account_id): Path<AccountId>,
+    State(account_service): State<AccountService>,
+    State(recovery_service): State<RecoveryRepository>,
+    key_proof: KeyClaims,
+    Json(request): Json<UpdateRecoveryPolicyRequest>,
+) -> Result<Json<RecoveryPolicyResponse>, ApiError> {
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
+        event!(
+            Level::WARN,
+            "valid signature over access token required by both app and hw auth keys"
+        );
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            "valid signature over access token required by both app and hw auth keys",
+        ));
+    }
+
+    if !request.policy.is_within_bounds() {
+        return Err(RecoveryError::InvalidRecoveryPolicy {
+            min_delay: MIN_DELAY_PERIOD_DAYS,
+            max_delay: MAX_DELAY_PERIOD_DAYS,
+            min_lookback: MIN_CONTEST_LOOKBACK_DAYS,
+            max_lookback: MAX_CONTEST_LOOKBACK_DAYS,
+        }
+        .into());
+    }
+
+    let full_account = account_service
+        .fetch_full_account(FetchAccountInput {
+            account_id: &account_id,
+        })
+        .await?;
+    let now = recovery_service.cur_time();
+    let current_policy = full_account.recovery_policy_at(now);
+
+    // Test accounts skip the wait so the policy can be exercised end to end
+    let pending_policy = (request.policy != current_policy
+        && !full_account.common_fields.properties.is_test_account)
+        .then(|| PendingRecoveryPolicy {
+            policy: request.policy,
+            effective_at: now + current_policy.delay_period(),
+        });
+    let policy = if pending_policy.is_some() {
+        current_policy
+    } else {
+        request.policy
+    };
+
+    let security_event = match pending_policy {
+        Some(pending) => Some(SecurityEventType::RecoveryPolicyChangeRequested {
+            delay_period_days: pending.policy.delay_period_days,
+            contest_lookback_days: pending.policy.contest_lookback_days,
+            effective_at: pending.effective_at,
+        }),
+        None if policy != current_policy => {
+            Some(SecurityEventType::RecoveryPolicyChangeRequested {
+                delay_period_days: policy.delay_period_days,
+                contest_lookback_days: policy.contest_lookback_days,
+                effective_at: now,
+            })
+        }
+        None if full_account
+            .pending_recovery_policy
+            .is_some_and(|pending| pending.effective_at > now) =>
+        {
+            Some(SecurityEventType::RecoveryPolicyChangeCanceled)
+        }
+        None => None,
+    };
+    let update =
+        account_service.fetch_and_update_recovery_policy(FetchAndUpdateRecoveryPolicyInput {
+            account_id: &account_id,
+            new_recovery_policy: policy,
+            new_pending_recovery_policy: pending_policy,
+        });
+    match security_event {
+        Some(event) => {
+            account_service
+                .record_security_event(
+                    RecordSecurityEventInput {
+                        account_id: &account_id,
+                        event,
+                    },
+                    update,
+                )
+                .await?
+        }
+        None => update.await?,
+    }
+
+    Ok(Json(RecoveryPolicyResponse {
+        policy,
+        pending_policy,
+    }))
+}
+
 #[instrument(
     err,
     skip(
@@ -483,7 +723,7 @@ pub async fn update_delay_for_test_account(
     ),
     responses(
         (status = 200, description = "D&N Recovery was canceled"),
-        (status = 404, description = "Account not found")
+        (status = 404, description = "Account not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn cancel_delay_notify(
@@ -495,6 +735,10 @@ pub async fn cancel_delay_notify(
     State(comms_verification_service): State<CommsVerificationService>,
     key_proof: KeyClaims,
 ) -> Result<(), ApiError> {
+    if !key_proof.has_factors(RequiredFactors::AppOrHw) {
+        return Err(RecoveryError::KeyProofRequired.into());
+    }
+
     let events = vec![
         RecoveryEvent::CheckAccountRecoveryState,
         RecoveryEvent::CancelRecovery { key_proof },
@@ -531,7 +775,7 @@ pub async fn cancel_delay_notify(
     ),
     responses(
         (status = 200, description = "D&N Recovery fetched status", body=RecoveryResponse),
-        (status = 404, description = "Account or D&N recovery not found")
+        (status = 404, description = "Account or D&N recovery not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn get_recovery_status(
@@ -586,8 +830,8 @@ pub struct CompleteDelayNotifyResponse {}
     request_body = CompleteDelayNotifyRequest,
     responses(
         (status = 200, description = "D&N Recovery transaction was completed", body=CompleteDelayNotifyResponse),
-        (status = 400, description = "D&N Recovery not found or recovery still pending."),
-        (status = 404, description = "Account not found, D&N recovery not found or D&N recovery still pending.")
+        (status = 400, description = "D&N Recovery not found or recovery still pending.", body = ErrorResponseBody),
+        (status = 404, description = "Account not found, D&N recovery not found or D&N recovery still pending.", body = ErrorResponseBody)
     ),
 )]
 pub async fn complete_delay_notify_transaction(
@@ -642,7 +886,7 @@ pub struct SendAccountVerificationCodeResponse {}
     request_body = SendAccountVerificationCodeRequest,
     responses(
         (status = 200, description = "Verification code sent", body=SendAccountVerificationCodeResponse),
-        (status = 404, description = "Touchpoint not found")
+        (status = 404, description = "Touchpoint not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn send_verification_code(
@@ -710,7 +954,7 @@ pub struct VerifyAccountVerificationCodeResponse {}
     request_body = VerifyAccountVerificationCodeRequest,
     responses(
         (status = 200, description = "Verification code sent", body=VerifyAccountVerificationCodeResponse),
-        (status = 404, description = "Touchpoint not found")
+        (status = 404, description = "Touchpoint not found", body = ErrorResponseBody)
     ),
 )]
 pub async fn verify_code(
@@ -779,8 +1023,8 @@ pub struct RotateAuthenticationKeysResponse {}
     request_body = RotateAuthenticationKeysRequest,
     responses(
         (status = 200, description = "Rotation of app authentication key was completed.", body=RotateAuthenticationKeysResponse),
-        (status = 400, description = "Rotation of app authentication key failed due to invalid signature or keyset."),
-        (status = 404, description = "Account not found.")
+        (status = 400, description = "Rotation of app authentication key failed due to invalid signature or keyset.", body = ErrorResponseBody),
+        (status = 404, description = "Account not found.", body = ErrorResponseBody)
     ),
 )]
 pub async fn rotate_authentication_keys(
@@ -794,13 +1038,14 @@ pub async fn rotate_authentication_keys(
     key_proof: KeyClaims,
     Json(request): Json<RotateAuthenticationKeysRequest>,
 ) -> Result<Json<RotateAuthenticationKeysResponse>, ApiError> {
-    if !(key_proof.hw_signed && key_proof.app_signed) {
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
         event!(
             Level::WARN,
             "valid signature over access token required by both app and hw auth keys"
         );
-        return Err(ApiError::GenericForbidden(
-            "valid signature over access token required by both app and hw auth keys".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            "valid signature over access token required by both app and hw auth keys",
         ));
     }
 
@@ -828,8 +1073,9 @@ pub async fn rotate_authentication_keys(
     let existing_recovery_key = current_auth.recovery_pubkey.is_some();
     let rotate_to_new_recovery_key = request.recovery.is_some();
     if existing_recovery_key && !rotate_to_new_recovery_key {
-        return Err(ApiError::GenericBadRequest(
-            "Recovery Authentication key required".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::RecoveryAuthKeyRequired,
+            "Recovery Authentication key required",
         ));
     }
 
@@ -853,8 +1099,9 @@ pub async fn rotate_authentication_keys(
 
     //TODO: Remove this when the endpoint should allow hw key rotations
     if request.hardware.key != current_auth.hardware_pubkey {
-        return Err(ApiError::GenericBadRequest(
-            "Hardware Authentication key mismatch".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::HwAuthKeyMismatch,
+            "Hardware Authentication key mismatch",
         ));
     }
 
@@ -1097,13 +1344,14 @@ pub async fn create_recovery_relationship(
     key_proof: KeyClaims,
     Json(request): Json<CreateRecoveryRelationshipRequest>,
 ) -> Result<Json<CreateRecoveryRelationshipResponse>, ApiError> {
-    if !(key_proof.hw_signed && key_proof.app_signed) {
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
         event!(
             Level::WARN,
             "valid signature over access token required by both app and hw auth keys"
         );
-        return Err(ApiError::GenericForbidden(
-            "valid signature over access token required by both app and hw auth keys".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            "valid signature over access token required by both app and hw auth keys",
         ));
     }
 
@@ -1117,8 +1365,9 @@ pub async fn create_recovery_relationship(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1169,8 +1418,9 @@ pub async fn delete_recovery_relationship(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1227,8 +1477,9 @@ pub async fn get_recovery_relationships(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1323,8 +1574,9 @@ pub async fn update_recovery_relationship(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1341,8 +1593,9 @@ pub async fn update_recovery_relationship(
                     Level::ERROR,
                     "The provided access token is for the incorrect domain."
                 );
-                return Err(ApiError::GenericForbidden(
-                    "The provided access token is for the incorrect domain.".to_string(),
+                return Err(ApiError::specific(
+                    ErrorCode::WrongAccessTokenType,
+                    "The provided access token is for the incorrect domain.",
                 ));
             }
             let result = recovery_relationship_service
@@ -1366,8 +1619,9 @@ pub async fn update_recovery_relationship(
         }
         UpdateRecoveryRelationshipRequest::Reissue => {
             let Account::Full(full_account) = account else {
-                return Err(ApiError::GenericForbidden(
-                    "Incorrect calling account type".to_string(),
+                return Err(ApiError::specific(
+                    ErrorCode::InvalidAccountType,
+                    "Incorrect calling account type",
                 ));
             };
 
@@ -1376,12 +1630,13 @@ pub async fn update_recovery_relationship(
                     Level::ERROR,
                     "The provided access token is for the incorrect domain."
                 );
-                return Err(ApiError::GenericForbidden(
-                    "The provided access token is for the incorrect domain.".to_string(),
+                return Err(ApiError::specific(
+                    ErrorCode::WrongAccessTokenType,
+                    "The provided access token is for the incorrect domain.",
                 ));
             }
 
-            if !key_proof.hw_signed || !key_proof.app_signed {
+            if !key_proof.has_factors(RequiredFactors::AppAndHw) {
                 event!(
                     Level::WARN,
                     "valid signature over access token requires both app and hw auth keys"
@@ -1456,14 +1711,16 @@ pub async fn endorse_recovery_relationships(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
     let Account::Full(_) = account else {
-        return Err(ApiError::GenericForbidden(
-            "Incorrect calling account type".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::InvalidAccountType,
+            "Incorrect calling account type",
         ));
     };
 
@@ -1524,8 +1781,9 @@ pub async fn get_recovery_relationship_invitation_for_code(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1669,8 +1927,9 @@ pub async fn start_social_challenge(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1735,8 +1994,9 @@ pub async fn verify_social_challenge(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1792,8 +2052,9 @@ pub async fn respond_to_social_challenge(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1853,8 +2114,9 @@ pub async fn fetch_social_challenge(
         .resolver(&feature_flags_service)
         .resolve()
     {
-        return Err(ApiError::GenericForbidden(
-            "Feature not enabled".to_string(),
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
         ));
     }
 
@@ -1869,3 +2131,437 @@ pub async fn fetch_social_challenge(
         social_challenge: result.into(),
     }))
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct InheritancePackageSummary {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub beneficiary_account_id: AccountId,
+    pub inactivity_period_days: u32,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl From<InheritancePackage> for InheritancePackageSummary {
+    fn from(value: InheritancePackage) -> Self {
+        Self {
+            recovery_relationship_id: value.recovery_relationship_id,
+            beneficiary_account_id: value.trusted_contact_account_id,
+            inactivity_period_days: value.inactivity_period_days,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct InheritanceClaimSummary {
+    pub id: InheritanceClaimId,
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub status: InheritanceClaimStatus,
+    #[serde(with = "rfc3339")]
+    pub delay_end_time: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sweep_txid: Option<String>,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl From<InheritanceClaim> for InheritanceClaimSummary {
+    fn from(value: InheritanceClaim) -> Self {
+        Self {
+            id: value.id,
+            recovery_relationship_id: value.recovery_relationship_id,
+            status: value.status,
+            delay_end_time: value.delay_end_time,
+            sweep_txid: value.sweep_txid,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct UploadInheritancePackageRequest {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub sealed_dek: String,
+    pub sealed_mobile_key: String,
+    #[serde(default)]
+    pub inactivity_period_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct UploadInheritancePackageResponse {
+    pub package: InheritancePackageSummary,
+}
+
+///
+/// Used by the Customer to designate an endorsed Trusted Contact as a beneficiary,
+/// uploading the material sealed to them that they'll receive once an inheritance
+/// claim completes. Uploading again replaces the package for the relationship.
+///
+/// The customer must provide:
+/// - Account access token
+/// - Both App and Hardware keyproofs
+///
+#[instrument(
+    err,
+    skip(account_service, inheritance_service, feature_flags_service, request)
+)]
+#[utoipa::path(
+    put,
+    path = "/api/accounts/{account_id}/recovery/inheritance/packages",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    request_body = UploadInheritancePackageRequest,
+    responses(
+        (status = 200, description = "Inheritance package uploaded", body=UploadInheritancePackageResponse),
+        (status = 400, description = "Inactivity period out of bounds", body=ErrorResponseBody),
+        (status = 409, description = "Recovery relationship isn't endorsed", body=ErrorResponseBody),
+    ),
+)]
+pub async fn upload_inheritance_package(
+    Path(account_id): Path<AccountId>,
+    State(account_service): State<AccountService>,
+    State(inheritance_service): State<InheritanceService>,
+    State(feature_flags_service): State<FeatureFlagsService>,
+    key_proof: KeyClaims,
+    Json(request): Json<UploadInheritancePackageRequest>,
+) -> Result<Json<UploadInheritancePackageResponse>, ApiError> {
+    if !FLAG_INHERITANCE_ENABLE
+        .resolver(&feature_flags_service)
+        .resolve()
+    {
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
+        ));
+    }
+
+    if !key_proof.has_factors(RequiredFactors::AppAndHw) {
+        event!(
+            Level::WARN,
+            "valid signature over access token required by both app and hw auth keys"
+        );
+        return Err(ApiError::specific(
+            ErrorCode::AppAndHwSignatureRequired,
+            "valid signature over access token required by both app and hw auth keys",
+        ));
+    }
+
+    let customer_account = account_service
+        .fetch_full_account(FetchAccountInput {
+            account_id: &account_id,
+        })
+        .await?;
+
+    let package = inheritance_service
+        .upload_inheritance_package(UploadInheritancePackageInput {
+            customer_account: &customer_account,
+            recovery_relationship_id: &request.recovery_relationship_id,
+            sealed_dek: &request.sealed_dek,
+            sealed_mobile_key: &request.sealed_mobile_key,
+            inactivity_period_days: request
+                .inactivity_period_days
+                .unwrap_or(DEFAULT_INACTIVITY_PERIOD_DAYS),
+        })
+        .await?;
+
+    Ok(Json(UploadInheritancePackageResponse {
+        package: package.into(),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct GetInheritanceResponse {
+    pub packages: Vec<InheritancePackageSummary>,
+    pub customer_claims: Vec<InheritanceClaimSummary>,
+    pub beneficiary_claims: Vec<InheritanceClaimSummary>,
+}
+
+///
+/// This route is used by both Customers and Beneficiaries to retrieve inheritance state.
+///
+/// We will show:
+/// - The packages the account has left for its beneficiaries
+/// - The claims made against the account's wallet
+/// - The claims the account has made as a beneficiary
+///
+#[instrument(err, skip(inheritance_service, feature_flags_service))]
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/recovery/inheritance",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    responses(
+        (status = 200, description = "Inheritance packages and claims", body=GetInheritanceResponse),
+    ),
+)]
+pub async fn get_inheritance(
+    Path(account_id): Path<AccountId>,
+    State(inheritance_service): State<InheritanceService>,
+    State(feature_flags_service): State<FeatureFlagsService>,
+) -> Result<Json<GetInheritanceResponse>, ApiError> {
+    if !FLAG_INHERITANCE_ENABLE
+        .resolver(&feature_flags_service)
+        .resolve()
+    {
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
+        ));
+    }
+
+    let result = inheritance_service
+        .fetch_inheritance_for_account(FetchInheritanceForAccountInput {
+            account_id: &account_id,
+        })
+        .await?;
+
+    Ok(Json(GetInheritanceResponse {
+        packages: result.packages.into_iter().map(Into::into).collect(),
+        customer_claims: result.customer_claims.into_iter().map(Into::into).collect(),
+        beneficiary_claims: result
+            .beneficiary_claims
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StartInheritanceClaimRequest {
+    pub recovery_relationship_id: RecoveryRelationshipId,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct StartInheritanceClaimResponse {
+    pub claim: InheritanceClaimSummary,
+}
+
+///
+/// Used by a Beneficiary to start an inheritance claim against a Customer's wallet.
+///
+/// The claim can only be started once the Customer has been inactive for the period
+/// they chose, and the Customer is notified throughout the delay period that follows.
+///
+#[instrument(err, skip(inheritance_service, feature_flags_service))]
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{account_id}/recovery/inheritance/claims",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+    ),
+    request_body = StartInheritanceClaimRequest,
+    responses(
+        (status = 200, description = "Inheritance claim started", body=StartInheritanceClaimResponse),
+        (status = 403, description = "Beneficiary has no full account with an active keyset to sweep into", body=ErrorResponseBody),
+        (status = 409, description = "Customer recently active or claim already pending", body=ErrorResponseBody),
+    ),
+)]
+pub async fn start_inheritance_claim(
+    Path(account_id): Path<AccountId>,
+    State(inheritance_service): State<InheritanceService>,
+    State(feature_flags_service): State<FeatureFlagsService>,
+    Json(request): Json<StartInheritanceClaimRequest>,
+) -> Result<Json<StartInheritanceClaimResponse>, ApiError> {
+    if !FLAG_INHERITANCE_ENABLE
+        .resolver(&feature_flags_service)
+        .resolve()
+    {
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
+        ));
+    }
+
+    let claim = inheritance_service
+        .start_inheritance_claim(StartInheritanceClaimInput {
+            beneficiary_account_id: &account_id,
+            recovery_relationship_id: &request.recovery_relationship_id,
+        })
+        .await?;
+
+    Ok(Json(StartInheritanceClaimResponse {
+        claim: claim.into(),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct FetchInheritanceClaimResponse {
+    pub claim: InheritanceClaimSummary,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed_dek: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed_mobile_key: Option<String>,
+}
+
+///
+/// Used by either the Customer or the Beneficiary to fetch an inheritance claim.
+///
+/// The sealed package is only included for the Beneficiary, once the claim's
+/// delay period has finished.
+///
+#[instrument(err, skip(inheritance_service, feature_flags_service))]
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{account_id}/recovery/inheritance/claims/{inheritance_claim_id}",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+        ("inheritance_claim_id" = InheritanceClaimId, Path, description = "InheritanceClaimId"),
+    ),
+    responses(
+        (status = 200, description = "Inheritance claim", body=FetchInheritanceClaimResponse),
+    ),
+)]
+pub async fn fetch_inheritance_claim(
+    Path((account_id, inheritance_claim_id)): Path<(AccountId, InheritanceClaimId)>,
+    State(inheritance_service): State<InheritanceService>,
+    State(feature_flags_service): State<FeatureFlagsService>,
+) -> Result<Json<FetchInheritanceClaimResponse>, ApiError> {
+    if !FLAG_INHERITANCE_ENABLE
+        .resolver(&feature_flags_service)
+        .resolve()
+    {
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
+        ));
+    }
+
+    let (claim, package) = inheritance_service
+        .fetch_inheritance_claim(FetchInheritanceClaimInput {
+            acting_account_id: &account_id,
+            inheritance_claim_id: &inheritance_claim_id,
+        })
+        .await?;
+
+    let (sealed_dek, sealed_mobile_key) =
+        package.map(|p| (p.sealed_dek, p.sealed_mobile_key)).unzip();
+    Ok(Json(FetchInheritanceClaimResponse {
+        claim: claim.into(),
+        sealed_dek,
+        sealed_mobile_key,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CancelInheritanceClaimResponse {
+    pub claim: InheritanceClaimSummary,
+}
+
+///
+/// Used by either the Customer or the Beneficiary to cancel a pending inheritance claim.
+///
+#[instrument(err, skip(inheritance_service, feature_flags_service))]
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{account_id}/recovery/inheritance/claims/{inheritance_claim_id}/cancel",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+        ("inheritance_claim_id" = InheritanceClaimId, Path, description = "InheritanceClaimId"),
+    ),
+    responses(
+        (status = 200, description = "Inheritance claim canceled", body=CancelInheritanceClaimResponse),
+        (status = 409, description = "Inheritance claim isn't pending", body=ErrorResponseBody),
+    ),
+)]
+pub async fn cancel_inheritance_claim(
+    Path((account_id, inheritance_claim_id)): Path<(AccountId, InheritanceClaimId)>,
+    State(inheritance_service): State<InheritanceService>,
+    State(feature_flags_service): State<FeatureFlagsService>,
+) -> Result<Json<CancelInheritanceClaimResponse>, ApiError> {
+    if !FLAG_INHERITANCE_ENABLE
+        .resolver(&feature_flags_service)
+        .resolve()
+    {
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
+        ));
+    }
+
+    let claim = inheritance_service
+        .cancel_inheritance_claim(CancelInheritanceClaimInput {
+            acting_account_id: &account_id,
+            inheritance_claim_id: &inheritance_claim_id,
+        })
+        .await?;
+
+    Ok(Json(CancelInheritanceClaimResponse {
+        claim: claim.into(),
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CompleteInheritanceClaimRequest {
+    pub psbt: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct CompleteInheritanceClaimResponse {
+    pub claim: InheritanceClaimSummary,
+    pub psbt: String,
+}
+
+///
+/// Used by the Beneficiary, once a claim's delay period has finished, to have the
+/// server cosign a sweep of the Customer's wallet into the Beneficiary's active wallet.
+/// The transaction is broadcast if it's fully signed.
+///
+#[instrument(err, skip(inheritance_service, feature_flags_service, request))]
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{account_id}/recovery/inheritance/claims/{inheritance_claim_id}/complete",
+    params(
+        ("account_id" = AccountId, Path, description = "AccountId"),
+        ("inheritance_claim_id" = InheritanceClaimId, Path, description = "InheritanceClaimId"),
+    ),
+    request_body = CompleteInheritanceClaimRequest,
+    responses(
+        (status = 200, description = "Inheritance claim completed", body=CompleteInheritanceClaimResponse),
+        (status = 400, description = "Delay period not finished or sweep rejected", body=ErrorResponseBody),
+    ),
+)]
+pub async fn complete_inheritance_claim(
+    Path((account_id, inheritance_claim_id)): Path<(AccountId, InheritanceClaimId)>,
+    State(inheritance_service): State<InheritanceService>,
+    State(feature_flags_service): State<FeatureFlagsService>,
+    Json(request): Json<CompleteInheritanceClaimRequest>,
+) -> Result<Json<CompleteInheritanceClaimResponse>, ApiError> {
+    if !FLAG_INHERITANCE_ENABLE
+        .resolver(&feature_flags_service)
+        .resolve()
+    {
+        return Err(ApiError::specific(
+            ErrorCode::FeatureNotEnabled,
+            "Feature not enabled",
+        ));
+    }
+
+    let (claim, psbt) = inheritance_service
+        .complete_inheritance_claim(CompleteInheritanceClaimInput {
+            beneficiary_account_id: &account_id,
+            inheritance_claim_id: &inheritance_claim_id,
+            psbt: &request.psbt,
+        })
+        .await?;
+
+    Ok(Json(CompleteInheritanceClaimResponse {
+        claim: claim.into(),
+        psbt,
+    }))
+}
@@ -0,0 +1,124 @@
+use account::service::RecordSecurityEventInput;
+use database::ddb::DatabaseError;
+use notification::{
+    payloads::inheritance_claim_canceled::{
+        InheritanceClaimCanceledPayload, InheritanceClaimRecipient,
+    },
+    service::SendNotificationInput,
+    NotificationPayloadBuilder, NotificationPayloadType,
+};
+use tracing::instrument;
+use types::{
+    account::identifiers::AccountId,
+    recovery::{
+        inheritance::{InheritanceClaim, InheritanceClaimId},
+        social::relationship::RecoveryRelationship,
+    },
+    security_event::SecurityEventType,
+};
+
+use super::{error::ServiceError, Service};
+
+pub struct CancelInheritanceClaimInput<'a> {
+    pub acting_account_id: &'a AccountId,
+    pub inheritance_claim_id: &'a InheritanceClaimId,
+}
+
+impl Service {
+    /// Cancels a pending inheritance claim. Either the customer or the beneficiary may cancel, and
+    /// the other party is notified.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Contains the account id of the acting account and the claim to be canceled
+    #[instrument(skip(self, input))]
+    pub async fn cancel_inheritance_claim(
+        &self,
+        input: CancelInheritanceClaimInput<'_>,
+    ) -> Result<InheritanceClaim, ServiceError> {
+        let prev_claim = self
+            .repository
+            .fetch_inheritance_claim(input.inheritance_claim_id)
+            .await?;
+
+        let (recipient, recipient_account_id) =
+            if prev_claim.customer_account_id == *input.acting_account_id {
+                (
+                    InheritanceClaimRecipient::Beneficiary,
+                    prev_claim.trusted_contact_account_id.clone(),
+                )
+            } else if prev_claim.trusted_contact_account_id == *input.acting_account_id {
+                (
+                    InheritanceClaimRecipient::Customer,
+                    prev_claim.customer_account_id.clone(),
+                )
+            } else {
+                return Err(ServiceError::AccountNotPartyToClaim);
+            };
+
+        if !prev_claim.is_pending() {
+            return Err(ServiceError::ClaimNotPending);
+        }
+
+        let claim = self
+            .account_service
+            .record_security_event(
+                RecordSecurityEventInput {
+                    account_id: &prev_claim.customer_account_id,
+                    event: SecurityEventType::InheritanceClaimCanceled {
+                        inheritance_claim_id: prev_claim.id.clone(),
+                    },
+                },
+                async {
+                    Ok::<_, ServiceError>(
+                        self.repository
+                            .persist_inheritance_claim(
+                                &prev_claim.canceled_by(input.acting_account_id),
+                            )
+                            .await?,
+                    )
+                },
+            )
+            .await?;
+
+        // The relationship may have been severed since the claim started, in which case there's
+        // no alias left to address the other party by
+        let relationship = match self
+            .repository
+            .fetch_recovery_relationship(&claim.recovery_relationship_id)
+            .await
+        {
+            Ok(RecoveryRelationship::Endorsed(r)) => Some(r),
+            Ok(_) | Err(DatabaseError::ObjectNotFound(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(relationship) = relationship {
+            let alias = match recipient {
+                InheritanceClaimRecipient::Customer => {
+                    relationship.common_fields.trusted_contact_alias
+                }
+                InheritanceClaimRecipient::Beneficiary => {
+                    relationship.connection_fields.customer_alias
+                }
+            };
+
+            self.notification_service
+                .send_notification(SendNotificationInput {
+                    account_id: &recipient_account_id,
+                    payload_type: NotificationPayloadType::InheritanceClaimCanceled,
+                    payload: &NotificationPayloadBuilder::default()
+                        .inheritance_claim_canceled_payload(Some(InheritanceClaimCanceledPayload {
+                            inheritance_claim_id: claim.id.clone(),
+                            recipient,
+                            alias,
+                        }))
+                        .build()?,
+                    only_touchpoints: None,
+                })
+                .await?;
+        }
+
+        Ok(claim)
+    }
+}
@@ -0,0 +1,190 @@
+use std::str::FromStr;
+
+use account::{
+    entities::Account,
+    service::{FetchAccountInput, RecordSecurityEventInput},
+};
+use bdk_utils::{
+    bdk::{bitcoin::psbt::PartiallySignedTransaction as Psbt, SignOptions},
+    generate_electrum_rpc_uris, DescriptorKeyset,
+};
+use mobile_pay::spend_rules::SpendRuleSet;
+use notification::{
+    payloads::inheritance_claim_completed::InheritanceClaimCompletedPayload,
+    service::SendNotificationInput, NotificationPayloadBuilder, NotificationPayloadType,
+};
+use time::OffsetDateTime;
+use tracing::{event, instrument, Level};
+use types::{
+    account::identifiers::AccountId,
+    recovery::{
+        inheritance::{InheritanceClaim, InheritanceClaimId},
+        social::relationship::RecoveryRelationship,
+    },
+    security_event::SecurityEventType,
+};
+use wsm_rust_client::SigningService;
+
+use super::{error::ServiceError, Service};
+
+pub struct CompleteInheritanceClaimInput<'a> {
+    pub beneficiary_account_id: &'a AccountId,
+    pub inheritance_claim_id: &'a InheritanceClaimId,
+    pub psbt: &'a str,
+}
+
+impl Service {
+    /// Completes an inheritance claim whose delay period has finished by cosigning a sweep of the
+    /// customer's active wallet into the beneficiary's active wallet, broadcasting it if it's
+    /// fully signed. Returns the completed claim along with the signed PSBT.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Contains the beneficiary account id, the claim to be completed and the sweep PSBT
+    #[instrument(skip(self, input))]
+    pub async fn complete_inheritance_claim(
+        &self,
+        input: CompleteInheritanceClaimInput<'_>,
+    ) -> Result<(InheritanceClaim, String), ServiceError> {
+        let prev_claim = self
+            .repository
+            .fetch_inheritance_claim(input.inheritance_claim_id)
+            .await?;
+
+        if prev_claim.trusted_contact_account_id != *input.beneficiary_account_id {
+            return Err(ServiceError::AccountNotBeneficiary);
+        }
+        if !prev_claim.is_pending() {
+            return Err(ServiceError::ClaimNotPending);
+        }
+        if !prev_claim.is_delay_complete(OffsetDateTime::now_utc()) {
+            return Err(ServiceError::DelayPeriodNotFinished);
+        }
+
+        // The customer may have severed the relationship during the delay period
+        let RecoveryRelationship::Endorsed(relationship) = self
+            .repository
+            .fetch_recovery_relationship(&prev_claim.recovery_relationship_id)
+            .await?
+        else {
+            return Err(ServiceError::RecoveryRelationshipStatusMismatch);
+        };
+
+        let Account::Full(beneficiary_account) = self
+            .account_service
+            .fetch_account(FetchAccountInput {
+                account_id: input.beneficiary_account_id,
+            })
+            .await?
+        else {
+            return Err(ServiceError::InvalidBeneficiaryAccount);
+        };
+        let beneficiary_descriptor: DescriptorKeyset = beneficiary_account
+            .active_spending_keyset()
+            .ok_or(ServiceError::InvalidBeneficiaryAccount)?
+            .to_owned()
+            .into();
+
+        let customer_account = self
+            .account_service
+            .fetch_full_account(FetchAccountInput {
+                account_id: &prev_claim.customer_account_id,
+            })
+            .await?;
+        let customer_descriptor: DescriptorKeyset = customer_account
+            .active_spending_keyset()
+            .ok_or(ServiceError::NoActiveKeyset)?
+            .to_owned()
+            .into();
+
+        let psbt =
+            Psbt::from_str(input.psbt).map_err(|err| ServiceError::InvalidPsbt(err.to_string()))?;
+
+        let rpc_uris = generate_electrum_rpc_uris(&self.feature_flags_service)?;
+        let source_wallet = customer_descriptor.generate_wallet(false, &rpc_uris)?;
+        // A full sync is required here, because we don't have derivation path information in
+        // the PSBT for sweep outputs so we need to generate addresses and check one-by-one.
+        let destination_wallet = beneficiary_descriptor.generate_wallet(true, &rpc_uris)?;
+
+        SpendRuleSet::sweep(
+            &source_wallet,
+            &destination_wallet,
+            self.screener_service.clone(),
+        )
+        .check_spend_rules(&psbt)
+        .map_err(ServiceError::SpendRules)?;
+
+        // currently, wsm constructs a BDK wallet to do its signing, so we need to construct external and internal descriptors for it
+        let receiving = customer_descriptor.receiving().into_multisig_descriptor()?;
+        let change = customer_descriptor.change().into_multisig_descriptor()?;
+
+        let result = self
+            .wsm_client
+            .sign_psbt(
+                &customer_account.active_keyset_id.to_string(),
+                &receiving.to_string(),
+                &change.to_string(),
+                input.psbt,
+            )
+            .await
+            .map_err(|err| {
+                event!(
+                    Level::INFO,
+                    "Could not sign PSBT with WSM due to error: {}",
+                    err.to_string()
+                );
+                ServiceError::WsmSigning
+            })?;
+
+        let mut signed_psbt = Psbt::from_str(&result.psbt)
+            .map_err(|err| ServiceError::InvalidPsbt(err.to_string()))?;
+        let psbt_fully_signed = source_wallet
+            .finalize_psbt(&mut signed_psbt, SignOptions::default())
+            .map_err(|err| ServiceError::InvalidPsbt(err.to_string()))?;
+
+        let txid = signed_psbt.unsigned_tx.txid();
+        let claim = self
+            .account_service
+            .record_security_event(
+                RecordSecurityEventInput {
+                    account_id: &prev_claim.customer_account_id,
+                    event: SecurityEventType::InheritanceClaimCompleted {
+                        inheritance_claim_id: prev_claim.id.clone(),
+                    },
+                },
+                async {
+                    if psbt_fully_signed {
+                        self.transaction_broadcaster.broadcast(
+                            source_wallet,
+                            &mut signed_psbt,
+                            &rpc_uris,
+                        )?;
+                    }
+                    Ok::<_, ServiceError>(
+                        self.repository
+                            .persist_inheritance_claim(
+                                &prev_claim.completed_with(&txid.to_string()),
+                            )
+                            .await?,
+                    )
+                },
+            )
+            .await?;
+
+        self.notification_service
+            .send_notification(SendNotificationInput {
+                account_id: &claim.customer_account_id,
+                payload_type: NotificationPayloadType::InheritanceClaimCompleted,
+                payload: &NotificationPayloadBuilder::default()
+                    .inheritance_claim_completed_payload(Some(InheritanceClaimCompletedPayload {
+                        inheritance_claim_id: claim.id.clone(),
+                        trusted_contact_alias: relationship.common_fields.trusted_contact_alias,
+                    }))
+                    .build()?,
+                only_touchpoints: None,
+            })
+            .await?;
+
+        Ok((claim, result.psbt))
+    }
+}
@@ -0,0 +1,103 @@
+use errors::{ApiError, ErrorCode};
+use mobile_pay::spend_rules::SpendRuleViolation;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ServiceError {
+    #[error("Failed to generate inheritance claim id")]
+    GenerateId(#[from] external_identifier::Error),
+    #[error(transparent)]
+    Database(#[from] database::ddb::DatabaseError),
+    #[error("Account is not the customer")]
+    AccountNotCustomer,
+    #[error("Account is not the beneficiary")]
+    AccountNotBeneficiary,
+    #[error("Account is neither the customer nor the beneficiary of the inheritance claim")]
+    AccountNotPartyToClaim,
+    #[error("Recovery relationship status mismatch")]
+    RecoveryRelationshipStatusMismatch,
+    #[error("Inactivity period must be between {min} and {max} days")]
+    InvalidInactivityPeriod { min: u32, max: u32 },
+    #[error("No inheritance package exists for the recovery relationship")]
+    NoInheritancePackage,
+    #[error("Customer has been active within the inactivity period")]
+    CustomerRecentlyActive,
+    #[error("An inheritance claim is already pending for the recovery relationship")]
+    ClaimAlreadyExists,
+    #[error("Inheritance claim is not pending")]
+    ClaimNotPending,
+    #[error("Inheritance claim delay period has not finished")]
+    DelayPeriodNotFinished,
+    #[error("Beneficiary must have a full account with an active keyset")]
+    InvalidBeneficiaryAccount,
+    #[error("Customer has no active keyset")]
+    NoActiveKeyset,
+    #[error("Invalid PSBT: {0}")]
+    InvalidPsbt(String),
+    #[error("WSM could not sign PSBT")]
+    WsmSigning,
+    #[error("Transaction failed to pass sweep spend rules")]
+    SpendRules(Vec<SpendRuleViolation>),
+    #[error(transparent)]
+    BdkUtils(#[from] bdk_utils::error::BdkUtilError),
+    #[error(transparent)]
+    Account(#[from] account::error::AccountError),
+    #[error(transparent)]
+    Notification(#[from] notification::NotificationError),
+    #[error(transparent)]
+    NotificationPayloadBuilder(#[from] notification::NotificationPayloadBuilderError),
+    #[error(transparent)]
+    Api(#[from] ApiError),
+}
+
+impl From<ServiceError> for ApiError {
+    fn from(value: ServiceError) -> Self {
+        let msg = value.to_string();
+        match value {
+            ServiceError::GenerateId(_)
+            | ServiceError::NotificationPayloadBuilder(_)
+            | ServiceError::WsmSigning => ApiError::GenericInternalApplicationError(msg),
+            ServiceError::Database(e) => e.into(),
+            ServiceError::AccountNotCustomer => {
+                ApiError::specific(ErrorCode::AccountNotCustomer, msg)
+            }
+            ServiceError::AccountNotBeneficiary => {
+                ApiError::specific(ErrorCode::AccountNotTrustedContact, msg)
+            }
+            ServiceError::AccountNotPartyToClaim => ApiError::specific(ErrorCode::Forbidden, msg),
+            ServiceError::RecoveryRelationshipStatusMismatch => {
+                ApiError::specific(ErrorCode::RelationshipStatusMismatch, msg)
+            }
+            ServiceError::InvalidInactivityPeriod { .. } => {
+                ApiError::specific(ErrorCode::InvalidInactivityPeriod, msg)
+            }
+            ServiceError::NoInheritancePackage => ApiError::specific(ErrorCode::NotFound, msg),
+            ServiceError::CustomerRecentlyActive => {
+                ApiError::specific(ErrorCode::CustomerRecentlyActive, msg)
+            }
+            ServiceError::ClaimAlreadyExists => {
+                ApiError::specific(ErrorCode::InheritanceClaimAlreadyExists, msg)
+            }
+            ServiceError::ClaimNotPending => {
+                ApiError::specific(ErrorCode::InheritanceClaimNotPending, msg)
+            }
+            ServiceError::DelayPeriodNotFinished => {
+                ApiError::specific(ErrorCode::DelayPeriodNotFinished, msg)
+            }
+            ServiceError::InvalidBeneficiaryAccount => {
+                ApiError::specific(ErrorCode::InvalidAccountType, msg)
+            }
+            ServiceError::NoActiveKeyset | ServiceError::InvalidPsbt(_) => {
+                ApiError::GenericBadRequest(msg)
+            }
+            // Report every violated rule so clients can explain all of them at once
+            ServiceError::SpendRules(violations) => {
+                ApiError::Multiple(violations.into_iter().map(ApiError::from).collect())
+            }
+            ServiceError::BdkUtils(e) => e.into(),
+            ServiceError::Account(e) => e.into(),
+            ServiceError::Notification(e) => e.into(),
+            ServiceError::Api(e) => e,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use repository::recovery::social::fetch::InheritanceForAccount;
+use time::OffsetDateTime;
+use tracing::instrument;
+use types::{
+    account::identifiers::AccountId,
+    recovery::inheritance::{
+        InheritanceClaim, InheritanceClaimId, InheritancePackage, InheritancePackageId,
+    },
+};
+
+use super::{error::ServiceError, Service};
+
+pub struct FetchInheritanceForAccountInput<'a> {
+    pub account_id: &'a AccountId,
+}
+
+pub struct FetchInheritanceClaimInput<'a> {
+    pub acting_account_id: &'a AccountId,
+    pub inheritance_claim_id: &'a InheritanceClaimId,
+}
+
+impl Service {
+    #[instrument(skip(self, input))]
+    pub async fn fetch_inheritance_for_account(
+        &self,
+        input: FetchInheritanceForAccountInput<'_>,
+    ) -> Result<InheritanceForAccount, ServiceError> {
+        Ok(self
+            .repository
+            .fetch_inheritance_for_account(input.account_id)
+            .await?)
+    }
+
+    /// Fetches an inheritance claim for either of its parties. The sealed package is only
+    /// released to the beneficiary, and only once the claim's delay period has finished.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Contains the account id of the acting account and the claim to be fetched
+    #[instrument(skip(self, input))]
+    pub async fn fetch_inheritance_claim(
+        &self,
+        input: FetchInheritanceClaimInput<'_>,
+    ) -> Result<(InheritanceClaim, Option<InheritancePackage>), ServiceError> {
+        let claim = self
+            .repository
+            .fetch_inheritance_claim(input.inheritance_claim_id)
+            .await?;
+
+        if claim.customer_account_id == *input.acting_account_id {
+            return Ok((claim, None));
+        }
+        if claim.trusted_contact_account_id != *input.acting_account_id {
+            return Err(ServiceError::AccountNotPartyToClaim);
+        }
+
+        if !claim.is_pending() || !claim.is_delay_complete(OffsetDateTime::now_utc()) {
+            return Ok((claim, None));
+        }
+
+        let package = self
+            .repository
+            .fetch_optional_inheritance_package(&InheritancePackageId::derive(
+                &claim.recovery_relationship_id,
+            ))
+            .await?;
+
+        Ok((claim, package))
+    }
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use account::service::Service as AccountService;
+use bdk_utils::TransactionBroadcasterTrait;
+use feature_flags::service::Service as FeatureFlagsService;
+use mobile_pay::daily_spend_record::service::Service as DailySpendRecordService;
+use notification::service::Service as NotificationService;
+use repository::recovery::social::Repository;
+use screener::service::Service as ScreenerService;
+use wsm_rust_client::WsmClient;
+
+use super::social::relationship::Service as RecoveryRelationshipService;
+
+pub mod cancel_inheritance_claim;
+pub mod complete_inheritance_claim;
+pub mod error;
+pub mod fetch_inheritance;
+pub mod start_inheritance_claim;
+pub mod upload_inheritance_package;
+
+pub const DEFAULT_INACTIVITY_PERIOD_DAYS: u32 = 180;
+const MIN_INACTIVITY_PERIOD_DAYS: u32 = 30;
+const MAX_INACTIVITY_PERIOD_DAYS: u32 = 1825;
+
+#[derive(Clone)]
+pub struct Service {
+    pub repository: Repository,
+    pub recovery_relationship_service: RecoveryRelationshipService,
+    pub notification_service: NotificationService,
+    pub account_service: AccountService,
+    pub daily_spend_record_service: DailySpendRecordService,
+    pub wsm_client: WsmClient,
+    pub screener_service: Arc<ScreenerService>,
+    pub transaction_broadcaster: Arc<dyn TransactionBroadcasterTrait>,
+    pub feature_flags_service: FeatureFlagsService,
+}
+
+impl Service {
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn new(
+        repository: Repository,
+        recovery_relationship_service: RecoveryRelationshipService,
+        notification_service: NotificationService,
+        account_service: AccountService,
+        daily_spend_record_service: DailySpendRecordService,
+        wsm_client: WsmClient,
+        screener_service: Arc<ScreenerService>,
+        transaction_broadcaster: Arc<dyn TransactionBroadcasterTrait>,
+        feature_flags_service: FeatureFlagsService,
+    ) -> Self {
+        Self {
+            repository,
+            recovery_relationship_service,
+            notification_service,
+            account_service,
+            daily_spend_record_service,
+            wsm_client,
+            screener_service,
+            transaction_broadcaster,
+            feature_flags_service,
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use account::{
+    entities::Account,
+    service::{FetchAccountInput, FetchSessionsInput, RecordSecurityEventInput},
+};
+use notification::{
+    payloads::inheritance_claim_pending::InheritanceClaimPendingPayload,
+    schedule::ScheduleNotificationType, service::ScheduleNotificationsInput,
+    NotificationPayloadBuilder,
+};
+use time::{Duration, OffsetDateTime};
+use tracing::instrument;
+use types::{
+    account::identifiers::AccountId,
+    recovery::{
+        inheritance::{InheritanceClaim, InheritanceClaimId, InheritancePackageId},
+        social::relationship::{RecoveryRelationship, RecoveryRelationshipId},
+    },
+    security_event::SecurityEventType,
+};
+
+use crate::entities::RecoveryValuesPerAccountType;
+
+use super::{error::ServiceError, Service};
+
+pub struct StartInheritanceClaimInput<'a> {
+    pub beneficiary_account_id: &'a AccountId,
+    pub recovery_relationship_id: &'a RecoveryRelationshipId,
+}
+
+impl Service {
+    /// Starts an inheritance claim on behalf of a beneficiary, provided the customer has shown no
+    /// sign of life for the inactivity period they chose. The customer is notified throughout the
+    /// delay period and may cancel the claim at any point before it completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Contains the beneficiary account id and the recovery relationship to claim against
+    #[instrument(skip(self, input))]
+    pub async fn start_inheritance_claim(
+        &self,
+        input: StartInheritanceClaimInput<'_>,
+    ) -> Result<InheritanceClaim, ServiceError> {
+        let RecoveryRelationship::Endorsed(relationship) = self
+            .repository
+            .fetch_recovery_relationship(input.recovery_relationship_id)
+            .await?
+        else {
+            return Err(ServiceError::RecoveryRelationshipStatusMismatch);
+        };
+
+        if relationship.connection_fields.trusted_contact_account_id
+            != *input.beneficiary_account_id
+        {
+            return Err(ServiceError::AccountNotBeneficiary);
+        }
+
+        // Completing a claim sweeps into the beneficiary's active wallet, so a beneficiary
+        // without one couldn't finish the claim
+        let Account::Full(beneficiary_account) = self
+            .account_service
+            .fetch_account(FetchAccountInput {
+                account_id: input.beneficiary_account_id,
+            })
+            .await?
+        else {
+            return Err(ServiceError::InvalidBeneficiaryAccount);
+        };
+        if beneficiary_account.active_spending_keyset().is_none() {
+            return Err(ServiceError::InvalidBeneficiaryAccount);
+        }
+
+        let package = self
+            .repository
+            .fetch_optional_inheritance_package(&InheritancePackageId::derive(
+                input.recovery_relationship_id,
+            ))
+            .await?
+            .ok_or(ServiceError::NoInheritancePackage)?;
+
+        let has_pending_claim = self
+            .repository
+            .fetch_inheritance_for_account(input.beneficiary_account_id)
+            .await?
+            .beneficiary_claims
+            .iter()
+            .any(|c| {
+                c.recovery_relationship_id == *input.recovery_relationship_id && c.is_pending()
+            });
+        if has_pending_claim {
+            return Err(ServiceError::ClaimAlreadyExists);
+        }
+
+        let customer_account = self
+            .account_service
+            .fetch_full_account(FetchAccountInput {
+                account_id: &package.customer_account_id,
+            })
+            .await?;
+
+        let now = OffsetDateTime::now_utc();
+        let inactive_since = now - Duration::days(package.inactivity_period_days.into());
+        let last_activity = self
+            .fetch_last_activity(
+                &customer_account.id,
+                customer_account.common_fields.created_at,
+                inactive_since,
+            )
+            .await?;
+        if last_activity > inactive_since {
+            return Err(ServiceError::CustomerRecentlyActive);
+        }
+
+        let delay_end_time = now + customer_account.inheritance_delay_period();
+        let claim = InheritanceClaim::new(
+            &InheritanceClaimId::gen()?,
+            input.recovery_relationship_id,
+            &customer_account.id,
+            input.beneficiary_account_id,
+            delay_end_time,
+        );
+        let claim = self
+            .account_service
+            .record_security_event(
+                RecordSecurityEventInput {
+                    account_id: &customer_account.id,
+                    event: SecurityEventType::InheritanceClaimStarted {
+                        inheritance_claim_id: claim.id.clone(),
+                    },
+                },
+                async {
+                    Ok::<_, ServiceError>(self.repository.persist_inheritance_claim(&claim).await?)
+                },
+            )
+            .await?;
+
+        self.notification_service
+            .schedule_notifications(ScheduleNotificationsInput {
+                account_id: customer_account.id.clone(),
+                notification_type: ScheduleNotificationType::InheritanceClaimPending(
+                    delay_end_time,
+                ),
+                payload: NotificationPayloadBuilder::default()
+                    .inheritance_claim_pending_payload(Some(InheritanceClaimPendingPayload {
+                        inheritance_claim_id: claim.id.clone(),
+                        trusted_contact_alias: relationship.common_fields.trusted_contact_alias,
+                        delay_end_time,
+                    }))
+                    .build()?,
+            })
+            .await?;
+
+        Ok(claim)
+    }
+
+    // The customer's most recent sign of life: the account's creation, the last time one of its
+    // sessions was seen or issued tokens, or the last Mobile Pay spend since `since`. Token
+    // issuance is recorded whether or not the session registry is enabled.
+    async fn fetch_last_activity(
+        &self,
+        customer_account_id: &AccountId,
+        account_created_at: OffsetDateTime,
+        since: OffsetDateTime,
+    ) -> Result<OffsetDateTime, ServiceError> {
+        let last_seen_at = self
+            .account_service
+            .fetch_sessions(FetchSessionsInput {
+                account_id: customer_account_id,
+            })
+            .await?
+            .into_iter()
+            .map(|s| s.last_seen_at)
+            .max();
+
+        let last_spent_at = self
+            .daily_spend_record_service
+            .fetch_daily_spending_records_between(
+                customer_account_id,
+                since.date(),
+                OffsetDateTime::now_utc().date(),
+            )
+            .await?
+            .iter()
+            .flat_map(|r| r.get_spending_entries().iter().map(|e| e.timestamp))
+            .max();
+
+        Ok([last_seen_at, last_spent_at]
+            .into_iter()
+            .flatten()
+            .fold(account_created_at, Ord::max))
+    }
+}
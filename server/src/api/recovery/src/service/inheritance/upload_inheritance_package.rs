@@ -0,0 +1,89 @@
+use account::entities::FullAccount;
+use tracing::instrument;
+use types::recovery::{
+    inheritance::{InheritancePackage, InheritancePackageId},
+    social::relationship::{RecoveryRelationship, RecoveryRelationshipId},
+};
+
+use super::{error::ServiceError, Service, MAX_INACTIVITY_PERIOD_DAYS, MIN_INACTIVITY_PERIOD_DAYS};
+
+pub struct UploadInheritancePackageInput<'a> {
+    pub customer_account: &'a FullAccount,
+    pub recovery_relationship_id: &'a RecoveryRelationshipId,
+    pub sealed_dek: &'a str,
+    pub sealed_mobile_key: &'a str,
+    pub inactivity_period_days: u32,
+}
+
+impl Service {
+    /// Designates the trusted contact of an endorsed recovery relationship as a beneficiary by
+    /// storing the sealed material they'll receive once an inheritance claim completes. Uploading
+    /// again replaces the existing package for the relationship.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Contains the customer account, the recovery relationship, the sealed material and the inactivity period
+    #[instrument(skip(self, input))]
+    pub async fn upload_inheritance_package(
+        &self,
+        input: UploadInheritancePackageInput<'_>,
+    ) -> Result<InheritancePackage, ServiceError> {
+        // Test accounts may use shorter periods so claims can be exercised end to end
+        if !input
+            .customer_account
+            .common_fields
+            .properties
+            .is_test_account
+            && !(MIN_INACTIVITY_PERIOD_DAYS..=MAX_INACTIVITY_PERIOD_DAYS)
+                .contains(&input.inactivity_period_days)
+        {
+            return Err(ServiceError::InvalidInactivityPeriod {
+                min: MIN_INACTIVITY_PERIOD_DAYS,
+                max: MAX_INACTIVITY_PERIOD_DAYS,
+            });
+        }
+
+        let RecoveryRelationship::Endorsed(relationship) = self
+            .repository
+            .fetch_recovery_relationship(input.recovery_relationship_id)
+            .await?
+        else {
+            return Err(ServiceError::RecoveryRelationshipStatusMismatch);
+        };
+
+        if relationship.common_fields.customer_account_id != input.customer_account.id {
+            return Err(ServiceError::AccountNotCustomer);
+        }
+
+        let package = InheritancePackage::new(
+            input.recovery_relationship_id,
+            &input.customer_account.id,
+            &relationship.connection_fields.trusted_contact_account_id,
+            input.sealed_dek,
+            input.sealed_mobile_key,
+            input.inactivity_period_days,
+        );
+
+        // Keep the original timestamps when replacing a package, so the write is conditioned on
+        // the package we read
+        let package = match self
+            .repository
+            .fetch_optional_inheritance_package(&InheritancePackageId::derive(
+                input.recovery_relationship_id,
+            ))
+            .await?
+        {
+            Some(existing) => InheritancePackage {
+                created_at: existing.created_at,
+                updated_at: existing.updated_at,
+                ..package
+            },
+            None => package,
+        };
+
+        Ok(self
+            .repository
+            .persist_inheritance_package(&package)
+            .await?)
+    }
+}
@@ -1,13 +1,18 @@
 use std::collections::{HashMap, HashSet};
 
 use account::{
-    entities::FullAccount, service::FetchAndUpdateSpendingLimitInput, spend_limit::SpendingLimit,
+    entities::FullAccount,
+    service::{FetchAndUpdateSpendingLimitInput, RecordSecurityEventInput},
+    spend_limit::SpendingLimit,
 };
 
 use tracing::instrument;
-use types::recovery::social::{
-    challenge::{SocialChallenge, SocialChallengeId, TrustedContactChallengeRequest},
-    relationship::RecoveryRelationshipId,
+use types::{
+    recovery::social::{
+        challenge::{SocialChallenge, SocialChallengeId, TrustedContactChallengeRequest},
+        relationship::RecoveryRelationshipId,
+    },
+    security_event::SecurityEventType,
 };
 
 use crate::service::social::relationship::get_recovery_relationships::GetRecoveryRelationshipsInput;
@@ -46,21 +51,6 @@ impl Service {
             return Err(ServiceError::MismatchingRecoveryRelationships);
         }
 
-        self.account_service
-            .fetch_and_update_spend_limit(FetchAndUpdateSpendingLimitInput {
-                account_id: &input.customer_account.id,
-                new_spending_limit: input.customer_account.spending_limit.as_ref().map_or_else(
-                    || None,
-                    |old_limit| {
-                        Some(SpendingLimit {
-                            active: false,
-                            ..old_limit.clone()
-                        })
-                    },
-                ),
-            })
-            .await?;
-
         let counter = u32::try_from(
             self.repository
                 .count_social_challenges_for_customer(&input.customer_account.id)
@@ -68,16 +58,46 @@ impl Service {
         )?;
         let id = SocialChallengeId::derive(&input.customer_account.id, counter);
 
-        let challenge = self
-            .repository
-            .persist_social_challenge(&SocialChallenge::new(
-                &id,
-                &input.customer_account.id,
-                input.requests,
-                counter,
-            ))
-            .await?;
+        self.account_service
+            .record_security_event(
+                RecordSecurityEventInput {
+                    account_id: &input.customer_account.id,
+                    event: SecurityEventType::SocialChallengeStarted {
+                        social_challenge_id: id.clone(),
+                    },
+                },
+                async {
+                    self.account_service
+                        .fetch_and_update_spend_limit(FetchAndUpdateSpendingLimitInput {
+                            account_id: &input.customer_account.id,
+                            new_spending_limit: input
+                                .customer_account
+                                .spending_limit
+                                .as_ref()
+                                .map_or_else(
+                                    || None,
+                                    |old_limit| {
+                                        Some(SpendingLimit {
+                                            active: false,
+                                            ..old_limit.clone()
+                                        })
+                                    },
+                                ),
+                        })
+                        .await?;
 
-        Ok(challenge)
+                    Ok::<_, ServiceError>(
+                        self.repository
+                            .persist_social_challenge(&SocialChallenge::new(
+                                &id,
+                                &input.customer_account.id,
+                                input.requests,
+                                counter,
+                            ))
+                            .await?,
+                    )
+                },
+            )
+            .await
     }
 }
@@ -1,5 +1,5 @@
 use crate::service::social::relationship::error::ServiceError as RecoveryRelationshipServiceError;
-use errors::ApiError;
+use errors::{ApiError, ErrorCode};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -33,12 +33,19 @@ impl From<ServiceError> for ApiError {
             ServiceError::NotificationPayloadBuilder(_) | ServiceError::TryFromIntError(_) => {
                 ApiError::GenericInternalApplicationError(msg)
             }
-            ServiceError::MismatchingRecoveryRelationships => ApiError::GenericBadRequest(msg),
+            ServiceError::MismatchingRecoveryRelationships => {
+                ApiError::specific(ErrorCode::ChallengeRelationshipMismatch, msg)
+            }
             ServiceError::Database(e) => e.into(),
-            ServiceError::AccountNotCustomer | ServiceError::AccountNotTrustedContact => {
-                ApiError::GenericForbidden(msg)
+            ServiceError::AccountNotCustomer => {
+                ApiError::specific(ErrorCode::AccountNotCustomer, msg)
+            }
+            ServiceError::AccountNotTrustedContact => {
+                ApiError::specific(ErrorCode::AccountNotTrustedContact, msg)
+            }
+            ServiceError::RecoveryRelationshipStatusMismatch => {
+                ApiError::specific(ErrorCode::RelationshipStatusMismatch, msg)
             }
-            ServiceError::RecoveryRelationshipStatusMismatch => ApiError::GenericConflict(msg),
             ServiceError::Notification(e) => e.into(),
             ServiceError::RecoveryRelationship(e) => e.into(),
             ServiceError::Account(e) => e.into(),
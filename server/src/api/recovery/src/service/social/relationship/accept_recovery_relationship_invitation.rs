@@ -1,3 +1,4 @@
+use account::service::RecordSecurityEventInput;
 use notification::payloads::recovery_relationship_invitation_accepted::RecoveryRelationshipInvitationAcceptedPayload;
 use notification::service::SendNotificationInput;
 use notification::{NotificationPayloadBuilder, NotificationPayloadType};
@@ -8,6 +9,7 @@ use types::recovery::social::relationship::{
     RecoveryRelationship, RecoveryRelationshipConnectionFieldsBuilder, RecoveryRelationshipId,
     RecoveryRelationshipUnendorsedBuilder,
 };
+use types::security_event::SecurityEventType;
 
 use super::{error::ServiceError, Service};
 
@@ -138,8 +140,24 @@ impl Service {
             .build()?;
 
         let relationship = self
-            .repository
-            .persist_recovery_relationship(&RecoveryRelationship::Unendorsed(connection))
+            .account_service
+            .record_security_event(
+                RecordSecurityEventInput {
+                    account_id: customer_account_id,
+                    event: SecurityEventType::RecoveryRelationshipAccepted {
+                        recovery_relationship_id: input.recovery_relationship_id.to_owned(),
+                    },
+                },
+                async {
+                    Ok::<_, ServiceError>(
+                        self.repository
+                            .persist_recovery_relationship(&RecoveryRelationship::Unendorsed(
+                                connection,
+                            ))
+                            .await?,
+                    )
+                },
+            )
             .await?;
 
         self.notification_service
@@ -1,6 +1,9 @@
-use account::entities::FullAccount;
+use account::{entities::FullAccount, service::RecordSecurityEventInput};
 use tracing::instrument;
-use types::recovery::social::relationship::{RecoveryRelationship, RecoveryRelationshipId};
+use types::{
+    recovery::social::relationship::{RecoveryRelationship, RecoveryRelationshipId},
+    security_event::SecurityEventType,
+};
 
 use super::{error::ServiceError, gen_code, gen_expiration, Service};
 
@@ -55,7 +58,7 @@ impl Service {
             return Err(ServiceError::MaxTrustedContactsReached);
         }
 
-        let mut relationship = RecoveryRelationship::new_invitation(
+        let relationship = RecoveryRelationship::new_invitation(
             &id,
             &input.customer_account.id,
             input.trusted_contact_alias,
@@ -65,11 +68,22 @@ impl Service {
             &expires_at,
         );
 
-        relationship = self
-            .repository
-            .persist_recovery_relationship(&relationship)
-            .await?;
-
-        Ok(relationship)
+        self.account_service
+            .record_security_event(
+                RecordSecurityEventInput {
+                    account_id: &input.customer_account.id,
+                    event: SecurityEventType::RecoveryRelationshipCreated {
+                        recovery_relationship_id: id,
+                    },
+                },
+                async {
+                    Ok::<_, ServiceError>(
+                        self.repository
+                            .persist_recovery_relationship(&relationship)
+                            .await?,
+                    )
+                },
+            )
+            .await
     }
 }
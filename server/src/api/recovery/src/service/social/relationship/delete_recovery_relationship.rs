@@ -1,4 +1,5 @@
-use authn_authz::key_claims::KeyClaims;
+use account::service::RecordSecurityEventInput;
+use authn_authz::key_claims::{KeyClaims, RequiredFactors};
 
 use notification::payloads::recovery_relationship_deleted::RecoveryRelationshipDeletedPayload;
 use notification::service::SendNotificationInput;
@@ -6,10 +7,12 @@ use notification::{NotificationPayloadBuilder, NotificationPayloadType};
 use tracing::{event, instrument, Level};
 use types::account::identifiers::AccountId;
 use types::authn_authz::cognito::CognitoUser;
+use types::recovery::inheritance::InheritancePackageId;
 use types::recovery::social::relationship::{
     RecoveryRelationship, RecoveryRelationshipCommonFields, RecoveryRelationshipConnectionFields,
     RecoveryRelationshipId,
 };
+use types::security_event::SecurityEventType;
 
 use super::{error::ServiceError, Service};
 
@@ -37,11 +40,15 @@ impl Service {
     /// # Arguments
     ///
     /// * `input` - Contains the account id of the acting account, the recovery relationship id to be terminated, the keyproof and the cognito user
+    ///
+    /// # Returns
+    ///
+    /// * The recovery relationship that was deleted
     #[instrument(skip(self, input))]
     pub async fn delete_recovery_relationship(
         &self,
         input: DeleteRecoveryRelationshipInput<'_>,
-    ) -> Result<(), ServiceError> {
+    ) -> Result<RecoveryRelationship, ServiceError> {
         let relationship = self
             .repository
             .fetch_recovery_relationship(input.recovery_relationship_id)
@@ -54,7 +61,7 @@ impl Service {
                 let trusted_contact_account_id = &connection_fields.trusted_contact_account_id;
 
                 if customer_account_id == input.acting_account_id {
-                    if !(input.key_proof.app_signed && input.key_proof.hw_signed) {
+                    if !input.key_proof.has_factors(RequiredFactors::AppAndHw) {
                         event!(
                             Level::WARN,
                             "valid signature over access token required both app and hw auth key"
@@ -115,8 +122,29 @@ impl Service {
             ),
         }?;
 
-        self.repository
-            .delete_recovery_relationship(&relationship)
+        // Logged against the protected customer, whichever side severed the relationship
+        self.account_service
+            .record_security_event(
+                RecordSecurityEventInput {
+                    account_id: &customer_account_id,
+                    event: SecurityEventType::RecoveryRelationshipDeleted {
+                        recovery_relationship_id: input.recovery_relationship_id.to_owned(),
+                    },
+                },
+                async {
+                    self.repository
+                        .delete_recovery_relationship(&relationship)
+                        .await?;
+                    // Any inheritance package left for the trusted contact goes with the
+                    // relationship
+                    self.repository
+                        .delete_inheritance_package(&InheritancePackageId::derive(
+                            input.recovery_relationship_id,
+                        ))
+                        .await?;
+                    Ok::<_, ServiceError>(())
+                },
+            )
             .await?;
 
         if send_notification {
@@ -136,6 +164,6 @@ impl Service {
                 .await?;
         }
 
-        Ok(())
+        Ok(relationship)
     }
 }
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use account::service::RecordSecurityEventInput;
 use futures::future::try_join_all;
 use tracing::instrument;
 use types::account::identifiers::AccountId;
@@ -7,6 +8,7 @@ use types::recovery::social::relationship::{
     RecoveryRelationship, RecoveryRelationshipEndorsed, RecoveryRelationshipEndorsement,
     RecoveryRelationshipId,
 };
+use types::security_event::SecurityEventType;
 
 use super::{error::ServiceError, Service};
 
@@ -88,11 +90,23 @@ impl Service {
             ));
         }
 
-        let updated_relationships = try_join_all(
-            to_update
-                .iter()
-                .map(|r| self.repository.persist_recovery_relationship(r)),
-        )
+        let updated_relationships = try_join_all(to_update.iter().map(|relationship| {
+            self.account_service.record_security_event(
+                RecordSecurityEventInput {
+                    account_id: input.customer_account_id,
+                    event: SecurityEventType::RecoveryRelationshipEndorsed {
+                        recovery_relationship_id: relationship.common_fields().id.to_owned(),
+                    },
+                },
+                async {
+                    Ok::<_, ServiceError>(
+                        self.repository
+                            .persist_recovery_relationship(relationship)
+                            .await?,
+                    )
+                },
+            )
+        }))
         .await?;
         Ok(updated_relationships)
     }
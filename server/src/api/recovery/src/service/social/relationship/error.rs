@@ -12,6 +12,8 @@ pub enum ServiceError {
     #[error(transparent)]
     Database(#[from] database::ddb::DatabaseError),
     #[error(transparent)]
+    Account(#[from] account::error::AccountError),
+    #[error(transparent)]
     ConnectionFieldsBuilder(#[from] RecoveryRelationshipConnectionFieldsBuilderError),
     #[error(transparent)]
     UnendorsedBuilder(#[from] RecoveryRelationshipUnendorsedBuilderError),
@@ -58,15 +60,30 @@ impl From<ServiceError> for ApiError {
             | ServiceError::NotificationPayloadBuilder(_) => {
                 ApiError::GenericInternalApplicationError(msg)
             }
-            ServiceError::InvitationNonEndorsable => ApiError::GenericBadRequest(msg),
+            ServiceError::InvitationNonEndorsable => {
+                ApiError::specific(ErrorCode::InvitationNonEndorsable, msg)
+            }
             ServiceError::Database(e) => e.into(),
-            ServiceError::RelationshipAlreadyEstablished
-            | ServiceError::AccountAlreadyTrustedContact => ApiError::GenericConflict(msg),
+            ServiceError::Account(e) => e.into(),
+            ServiceError::RelationshipAlreadyEstablished => {
+                ApiError::specific(ErrorCode::RelationshipAlreadyEstablished, msg)
+            }
+            ServiceError::AccountAlreadyTrustedContact => {
+                ApiError::specific(ErrorCode::AccountAlreadyTrustedContact, msg)
+            }
             ServiceError::UnauthorizedRelationshipDeletion
-            | ServiceError::UnauthorizedRelationshipUpdate
-            | ServiceError::CustomerIsTrustedContact
-            | ServiceError::InvalidKeyProof
-            | ServiceError::InvalidOperationForAccessToken => Self::GenericForbidden(msg),
+            | ServiceError::UnauthorizedRelationshipUpdate => {
+                ApiError::specific(ErrorCode::UnauthorizedRelationshipOperation, msg)
+            }
+            ServiceError::CustomerIsTrustedContact => {
+                ApiError::specific(ErrorCode::CustomerIsTrustedContact, msg)
+            }
+            ServiceError::InvalidKeyProof => {
+                ApiError::specific(ErrorCode::AppAndHwSignatureRequired, msg)
+            }
+            ServiceError::InvalidOperationForAccessToken => {
+                ApiError::specific(ErrorCode::WrongAccessTokenType, msg)
+            }
             ServiceError::InvitationExpired => ApiError::Specific {
                 code: ErrorCode::InvitationExpired,
                 detail: Some(msg),
@@ -1,4 +1,4 @@
-use account::entities::AccountProperties;
+use account::{entities::AccountProperties, service::Service as AccountService};
 use rand::Rng;
 use repository::recovery::social::Repository;
 use time::{Duration, OffsetDateTime};
@@ -24,14 +24,20 @@ const EXPIRATION_DAYS: i64 = 3;
 pub struct Service {
     pub repository: Repository,
     pub notification_service: NotificationService,
+    pub account_service: AccountService,
 }
 
 impl Service {
     #[must_use]
-    pub fn new(repository: Repository, notification_service: NotificationService) -> Self {
+    pub fn new(
+        repository: Repository,
+        notification_service: NotificationService,
+        account_service: AccountService,
+    ) -> Self {
         Self {
             repository,
             notification_service,
+            account_service,
         }
     }
 }
@@ -4,7 +4,9 @@ use account::entities::FullAccount;
 use account::entities::FullAccountAuthKeysPayload;
 use account::service::ClearPushTouchpointsInput;
 use account::service::CreateAndRotateAuthKeysInput;
+use account::service::RecordSecurityEventInput;
 use async_trait::async_trait;
+use types::security_event::SecurityEventType;
 
 use super::{
     rotated_keyset::RotatedKeysetState, RecoveryEvent, RecoveryServices, RecoveryStateResponse,
@@ -74,58 +76,78 @@ impl TransitioningRecoveryState for CompletableRecoveryState {
                 .recovery_pubkey
                 .is_some();
 
-            // If there's no existing recovery key and we're adding a new one, we need a new recovery cognito user
-            if !has_existing_recovery_key {
-                if let Some(recovery_key) = action.destination.recovery_auth_pubkey {
-                    user_pool_service
-                        .create_recovery_user_if_necessary(&account.id, recovery_key)
-                        .await
-                        .map_err(RecoveryError::RotateAuthKeys)?;
+            let complete_recovery = async {
+                // If there's no existing recovery key and we're adding a new one, we need a new recovery cognito user
+                if !has_existing_recovery_key {
+                    if let Some(recovery_key) = action.destination.recovery_auth_pubkey {
+                        user_pool_service
+                            .create_recovery_user_if_necessary(&account.id, recovery_key)
+                            .await
+                            .map_err(RecoveryError::RotateAuthKeys)?;
+                    }
                 }
-            }
-
-            user_pool_service
-                .rotate_account_auth_keys(
-                    &account.id,
-                    action.destination.app_auth_pubkey,
-                    action.destination.hardware_auth_pubkey,
-                    action.destination.recovery_auth_pubkey,
-                )
-                .await?;
 
-            services
-                .account
-                .create_and_rotate_auth_keys(CreateAndRotateAuthKeysInput {
-                    account_id: &account.id,
-                    app_auth_pubkey: action.destination.app_auth_pubkey,
-                    hardware_auth_pubkey: action.destination.hardware_auth_pubkey,
-                    recovery_auth_pubkey: action.destination.recovery_auth_pubkey,
-                })
-                .await?;
+                user_pool_service
+                    .rotate_account_auth_keys(
+                        &account.id,
+                        action.destination.app_auth_pubkey,
+                        action.destination.hardware_auth_pubkey,
+                        action.destination.recovery_auth_pubkey,
+                    )
+                    .await?;
 
-            if matches!(recovery.get_lost_factor(), Some(Factor::Hw)) {
                 services
-                    .challenge
-                    .clear_social_challenges(ClearSocialChallengesInput {
-                        customer_account_id: &account.id,
+                    .account
+                    .create_and_rotate_auth_keys(CreateAndRotateAuthKeysInput {
+                        account_id: &account.id,
+                        app_auth_pubkey: action.destination.app_auth_pubkey,
+                        hardware_auth_pubkey: action.destination.hardware_auth_pubkey,
+                        recovery_auth_pubkey: action.destination.recovery_auth_pubkey,
                     })
                     .await?;
-            }
 
-            services
-                .account
-                .clear_push_touchpoints(ClearPushTouchpointsInput {
-                    account_id: &account.id,
-                })
-                .await?;
+                if matches!(recovery.get_lost_factor(), Some(Factor::Hw)) {
+                    services
+                        .challenge
+                        .clear_social_challenges(ClearSocialChallengesInput {
+                            customer_account_id: &account.id,
+                        })
+                        .await?;
+                }
 
-            services
-                .recovery
-                .complete(
-                    (recovery.account_id.clone(), recovery.created_at),
-                    RecoveryStatus::Complete,
-                )
-                .await?;
+                services
+                    .account
+                    .clear_push_touchpoints(ClearPushTouchpointsInput {
+                        account_id: &account.id,
+                    })
+                    .await?;
+
+                services
+                    .recovery
+                    .complete(
+                        (recovery.account_id.clone(), recovery.created_at),
+                        RecoveryStatus::Complete,
+                    )
+                    .await?;
+                Ok::<_, RecoveryError>(())
+            };
+            match recovery.get_lost_factor() {
+                Some(lost_factor) => {
+                    services
+                        .account
+                        .record_security_event(
+                            RecordSecurityEventInput {
+                                account_id: &account.id,
+                                event: SecurityEventType::DelayNotifyRecoveryCompleted {
+                                    lost_factor: lost_factor.to_string(),
+                                },
+                            },
+                            complete_recovery,
+                        )
+                        .await?
+                }
+                None => complete_recovery.await?,
+            }
 
             let mut attributes = vec![KeyValue::new(
                 metrics::CREATED_DURING_CONTEST_KEY,
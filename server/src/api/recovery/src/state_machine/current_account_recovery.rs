@@ -1,6 +1,6 @@
 use account::{
     entities::{CommsVerificationScope, Factor, FullAccount, FullAccountAuthKeysPayload},
-    service::FetchAndUpdateSpendingLimitInput,
+    service::{FetchAndUpdateSpendingLimitInput, RecordSecurityEventInput},
 };
 use async_trait::async_trait;
 
@@ -19,6 +19,7 @@ use notification::{
     NotificationPayloadBuilder, NotificationPayloadType,
 };
 use time::{format_description::well_known::Rfc3339, Duration};
+use types::security_event::SecurityEventType;
 
 use crate::{
     ensure_pubkeys_unique,
@@ -157,7 +158,7 @@ impl TransitioningRecoveryState for CurrentAccountRecoveryState {
             let account_id = self.account.clone().id;
             let now = services.recovery.cur_time();
 
-            // If there's been a contested recovery within 30 days, require comms verification
+            // If there's been a contested recovery within the contest lookback, require comms verification
             if self.active_contest {
                 let scope = CommsVerificationScope::DelayNotifyActor(actor);
                 services
@@ -170,7 +171,7 @@ impl TransitioningRecoveryState for CurrentAccountRecoveryState {
             }
 
             let recovery_type = RecoveryType::DelayAndNotify;
-            let delay_period = account.recovery_delay_period();
+            let delay_period = account.recovery_delay_period(now);
             let requirements = RecoveryRequirements {
                 delay_notify_requirements: Some(DelayNotifyRequirements {
                     lost_factor,
@@ -198,7 +199,21 @@ impl TransitioningRecoveryState for CurrentAccountRecoveryState {
                 updated_at: now,
             };
             let recovery_service = services.recovery;
-            recovery_service.create(&new_recovery).await?;
+            services
+                .account
+                .record_security_event(
+                    RecordSecurityEventInput {
+                        account_id: &account_id,
+                        event: SecurityEventType::DelayNotifyRecoveryStarted {
+                            lost_factor: lost_factor.to_string(),
+                        },
+                    },
+                    async {
+                        recovery_service.create(&new_recovery).await?;
+                        Ok::<_, RecoveryError>(())
+                    },
+                )
+                .await?;
 
             // If this recovery is for a lost App, turn off Mobile Pay
             if let Factor::App = lost_factor {
@@ -365,8 +380,23 @@ impl TransitioningRecoveryState for CurrentAccountRecoveryState {
             };
 
             services
-                .recovery
-                .complete((recovery.account_id.clone(), recovery.created_at), status)
+                .account
+                .record_security_event(
+                    RecordSecurityEventInput {
+                        account_id: &account_id,
+                        event: SecurityEventType::DelayNotifyRecoveryCanceled {
+                            lost_factor: requirements.lost_factor.to_string(),
+                            contested: is_contesting_recovery,
+                        },
+                    },
+                    async {
+                        services
+                            .recovery
+                            .complete((recovery.account_id.clone(), recovery.created_at), status)
+                            .await?;
+                        Ok::<_, RecoveryError>(())
+                    },
+                )
                 .await?;
 
             // If this recovery is being contested, turn off Mobile Pay
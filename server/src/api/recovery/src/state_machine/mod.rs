@@ -32,8 +32,6 @@ pub mod pending_recovery; // TODO: [W-774] Update visibility of struct after mig
 pub mod rotated_keyset; // TODO: [W-774] Update visibility of struct after migration
 pub(crate) mod start_recovery; // TODO: [W-774] Update visibility of struct after migration
 
-pub(crate) const CONTEST_LOOKBACK_DAYS: i64 = 30;
-
 pub struct RecoveryServices<'a> {
     pub account: &'a AccountService,
     pub recovery: &'a RecoveryRepository,
@@ -1,6 +1,5 @@
 use account::service::FetchAccountInput;
 use async_trait::async_trait;
-use time::Duration;
 use types::account::identifiers::AccountId;
 
 use crate::{entities::RecoveryType, error::RecoveryError};
@@ -8,7 +7,7 @@ use crate::{entities::RecoveryType, error::RecoveryError};
 use super::{
     current_account_recovery::CurrentAccountRecoveryState, has_recent_contested_delay_notify,
     pending_recovery::PendingRecoveryState, RecoveryEvent, RecoveryServices, RecoveryStateResponse,
-    Transition, TransitionTo, TransitioningRecoveryState, CONTEST_LOOKBACK_DAYS,
+    Transition, TransitionTo, TransitioningRecoveryState,
 };
 
 pub(crate) struct StartRecoveryState {
@@ -42,21 +41,21 @@ impl TransitioningRecoveryState for StartRecoveryState {
                 .fetch_pending(&self.account_id, RecoveryType::DelayAndNotify)
                 .await?;
 
-            // Check CONTEST_LOOKBACK_DAYS from the creation of this recovery rather than from today.
+            // Check the contest lookback from the creation of this recovery rather than from today.
             //   This determines if the ongoing recovery was created during a time of contest.
-            //   If we only checked CONTEST_LOOKBACK_DAYS from today, it would allow a transition
+            //   If we only checked the contest lookback from today, it would allow a transition
             //   from ContestedDelayPeriod to UncontestedDelayPeriod in some cases (namely if the N-day
-            //   CONTEST_LOOKBACK period terminated within the delay period of this recovery).
+            //   contest lookback period terminated within the delay period of this recovery).
             //   EG
             //     day = 0: Recovery created by real user
             //     day = 0: Recovery contested by attacker ("free", no comms verification)
             //     day = 1: Recovery created by real user, with comms verification
             //     day = 31: Recovery contested by attacker, ("free", no comms verification)
-            let since = if let Some(recovery) = recovery.as_ref() {
-                recovery.created_at - Duration::days(CONTEST_LOOKBACK_DAYS)
-            } else {
-                services.recovery.cur_time() - Duration::days(CONTEST_LOOKBACK_DAYS)
-            };
+            //   The account's own contest lookback applies, as of the start of that period.
+            let start = recovery
+                .as_ref()
+                .map_or_else(|| services.recovery.cur_time(), |r| r.created_at);
+            let since = start - full_account.recovery_policy_at(start).contest_lookback();
 
             let active_contest =
                 has_recent_contested_delay_notify(services, &self.account_id, since).await?;
@@ -3,3 +3,12 @@ pub mod consent;
 
 #[cfg(feature = "recovery")]
 pub mod recovery;
+
+#[cfg(feature = "request_nonce")]
+pub mod request_nonce;
+
+#[cfg(feature = "security_event")]
+pub mod security_event;
+
+#[cfg(feature = "session")]
+pub mod session;
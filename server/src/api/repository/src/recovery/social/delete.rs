@@ -9,7 +9,10 @@ use time::format_description::well_known::Rfc3339;
 use tracing::{event, instrument, Level};
 use types::{
     account::identifiers::AccountId,
-    recovery::social::{challenge::SocialChallenge, relationship::RecoveryRelationship},
+    recovery::{
+        inheritance::InheritancePackageId,
+        social::{challenge::SocialChallenge, relationship::RecoveryRelationship},
+    },
 };
 
 use super::{
@@ -60,6 +63,32 @@ impl Repository {
         Ok(())
     }
 
+    #[instrument(skip(self))]
+    pub async fn delete_inheritance_package(
+        &self,
+        id: &InheritancePackageId,
+    ) -> Result<(), DatabaseError> {
+        let database_object = self.get_database_object();
+
+        self.connection
+            .client
+            .delete_item()
+            .table_name(self.get_table_name().await?)
+            .key(PARTITION_KEY, try_to_attribute_val(id, database_object)?)
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not delete inheritance package: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::PersistenceError(database_object)
+            })?;
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     pub async fn delete_challenges_for_customer(
         &self,
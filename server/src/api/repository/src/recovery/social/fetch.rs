@@ -4,7 +4,12 @@ use database::{
 };
 use types::{
     account::identifiers::AccountId,
-    recovery::social::challenge::{SocialChallenge, SocialChallengeId},
+    recovery::{
+        inheritance::{
+            InheritanceClaim, InheritanceClaimId, InheritancePackage, InheritancePackageId,
+        },
+        social::challenge::{SocialChallenge, SocialChallengeId},
+    },
 };
 
 use serde::Serialize;
@@ -24,6 +29,12 @@ pub struct RecoveryRelationshipsForAccount {
     pub customers: Vec<RecoveryRelationship>,
 }
 
+pub struct InheritanceForAccount {
+    pub packages: Vec<InheritancePackage>,
+    pub customer_claims: Vec<InheritanceClaim>,
+    pub beneficiary_claims: Vec<InheritanceClaim>,
+}
+
 impl Repository {
     async fn fetch(&self, partition_key: impl Serialize) -> Result<GetItemOutput, DatabaseError> {
         let table_name = self.get_table_name().await?;
@@ -210,7 +221,7 @@ impl Repository {
 
         if relationships.len() == 1 {
             return Ok(Some(relationships[0].clone()));
-        } else if items.len() > 1 {
+        } else if relationships.len() > 1 {
             return Err(DatabaseError::ObjectNotUnique(database_object));
         }
 
@@ -393,4 +404,141 @@ impl Repository {
 
         Ok(count)
     }
+
+    #[instrument(skip(self))]
+    pub async fn fetch_optional_inheritance_package(
+        &self,
+        id: &InheritancePackageId,
+    ) -> Result<Option<InheritancePackage>, DatabaseError> {
+        let database_object = self.get_database_object();
+
+        let item_output = self.fetch(id).await?;
+
+        match item_output.item {
+            Some(item) => match try_from_item::<_, SocialRecoveryRow>(item, database_object)? {
+                SocialRecoveryRow::InheritancePackage(package) => Ok(Some(package)),
+                _ => Ok(None),
+            },
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn fetch_inheritance_claim(
+        &self,
+        id: &InheritanceClaimId,
+    ) -> Result<InheritanceClaim, DatabaseError> {
+        let database_object = self.get_database_object();
+
+        let item_output = self.fetch(id).await?;
+
+        if let Some(SocialRecoveryRow::InheritanceClaim(claim)) = match item_output.item {
+            Some(item) => Some(try_from_item::<_, SocialRecoveryRow>(
+                item,
+                database_object,
+            )?),
+            None => None,
+        } {
+            Ok(claim)
+        } else {
+            event!(
+                Level::WARN,
+                "inheritance claim {id} not found in the database"
+            );
+            Err(DatabaseError::ObjectNotFound(database_object))
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn fetch_inheritance_for_account(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<InheritanceForAccount, DatabaseError> {
+        let (mut packages, mut customer_claims, mut beneficiary_claims) =
+            (Vec::new(), Vec::new(), Vec::new());
+
+        // Packages and claims for which account is the customer
+        for row in self
+            .query_account_index(CUSTOMER_IDX, CUSTOMER_IDX_PARTITION_KEY, account_id)
+            .await?
+        {
+            match row {
+                SocialRecoveryRow::InheritancePackage(package) => packages.push(package),
+                SocialRecoveryRow::InheritanceClaim(claim) => customer_claims.push(claim),
+                _ => {}
+            }
+        }
+
+        // Claims for which account is the beneficiary
+        for row in self
+            .query_account_index(
+                TRUSTED_CONTACT_IDX,
+                TRUSTED_CONTACT_IDX_PARTITION_KEY,
+                account_id,
+            )
+            .await?
+        {
+            if let SocialRecoveryRow::InheritanceClaim(claim) = row {
+                beneficiary_claims.push(claim);
+            }
+        }
+
+        Ok(InheritanceForAccount {
+            packages,
+            customer_claims,
+            beneficiary_claims,
+        })
+    }
+
+    async fn query_account_index(
+        &self,
+        index_name: &str,
+        partition_key: &str,
+        account_id: &AccountId,
+    ) -> Result<Vec<SocialRecoveryRow>, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let mut rows = Vec::new();
+        let mut exclusive_start_key = None;
+
+        loop {
+            let item_output = self
+                .connection
+                .client
+                .query()
+                .table_name(table_name.clone())
+                .index_name(index_name)
+                .key_condition_expression(format!("{} = :{}", partition_key, partition_key))
+                .expression_attribute_values(
+                    format!(":{}", partition_key),
+                    try_to_attribute_val(account_id, database_object)?,
+                )
+                .set_exclusive_start_key(exclusive_start_key.clone())
+                .send()
+                .await
+                .map_err(|err| {
+                    let service_err = err.into_service_error();
+                    event!(
+                        Level::ERROR,
+                        "Could not query social recovery index {index_name}: {service_err:?} with message: {:?}",
+                        service_err.message()
+                    );
+                    DatabaseError::FetchError(database_object)
+                })?;
+
+            rows.extend(try_from_items::<_, SocialRecoveryRow>(
+                item_output.items().to_owned(),
+                database_object,
+            )?);
+
+            if let Some(last_evaluated_key) = item_output.last_evaluated_key() {
+                exclusive_start_key = Some(last_evaluated_key.to_owned());
+            } else {
+                break;
+            }
+        }
+
+        Ok(rows)
+    }
 }
@@ -11,7 +11,10 @@ use database::{
 };
 use serde::{Deserialize, Serialize};
 use tracing::{event, Level};
-use types::recovery::social::{challenge::SocialChallenge, relationship::RecoveryRelationship};
+use types::recovery::{
+    inheritance::{InheritanceClaim, InheritancePackage},
+    social::{challenge::SocialChallenge, relationship::RecoveryRelationship},
+};
 
 pub mod delete;
 pub mod fetch;
@@ -35,6 +38,8 @@ pub(super) const CODE_IDX_PARTITION_KEY: &str = "code";
 enum SocialRecoveryRow {
     Relationship(RecoveryRelationship),
     Challenge(SocialChallenge),
+    InheritancePackage(InheritancePackage),
+    InheritanceClaim(InheritanceClaim),
 }
 
 #[derive(Clone)]
@@ -6,7 +6,10 @@ use database::{
 };
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use tracing::{event, instrument, Level};
-use types::recovery::social::{challenge::SocialChallenge, relationship::RecoveryRelationship};
+use types::recovery::{
+    inheritance::{InheritanceClaim, InheritancePackage},
+    social::{challenge::SocialChallenge, relationship::RecoveryRelationship},
+};
 
 use super::{Repository, SocialRecoveryRow};
 
@@ -90,4 +93,42 @@ impl Repository {
 
         Ok(updated_challenge)
     }
+
+    #[instrument(skip(self, package))]
+    pub async fn persist_inheritance_package(
+        &self,
+        package: &InheritancePackage,
+    ) -> Result<InheritancePackage, DatabaseError> {
+        let updated_package = package.with_updated_at(OffsetDateTime::now_utc());
+
+        let database_object = self.get_database_object();
+
+        let item = try_to_item(
+            SocialRecoveryRow::InheritancePackage(updated_package.clone()),
+            database_object,
+        )?;
+
+        self.persist(item, package.updated_at).await?;
+
+        Ok(updated_package)
+    }
+
+    #[instrument(skip(self, claim))]
+    pub async fn persist_inheritance_claim(
+        &self,
+        claim: &InheritanceClaim,
+    ) -> Result<InheritanceClaim, DatabaseError> {
+        let updated_claim = claim.with_updated_at(OffsetDateTime::now_utc());
+
+        let database_object = self.get_database_object();
+
+        let item = try_to_item(
+            SocialRecoveryRow::InheritanceClaim(updated_claim.clone()),
+            database_object,
+        )?;
+
+        self.persist(item, claim.updated_at).await?;
+
+        Ok(updated_claim)
+    }
 }
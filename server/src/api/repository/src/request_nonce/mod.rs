@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use database::{
+    aws_sdk_dynamodb::{
+        error::ProvideErrorMetadata,
+        types::{AttributeDefinition, BillingMode, KeySchemaElement, KeyType, ScalarAttributeType},
+    },
+    ddb::{Connection, DDBService, DatabaseError, DatabaseObject},
+};
+use tracing::{event, Level};
+
+pub mod persist;
+
+const PARTITION_KEY: &str = "partition_key";
+
+#[derive(Clone)]
+pub struct Repository {
+    connection: Connection,
+}
+
+#[async_trait]
+impl DDBService for Repository {
+    fn new(connection: Connection) -> Self {
+        Self { connection }
+    }
+
+    fn get_database_object(&self) -> DatabaseObject {
+        DatabaseObject::RequestNonce
+    }
+
+    fn get_connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    async fn get_table_name(&self) -> Result<String, DatabaseError> {
+        self.connection.get_table_name(self.get_database_object())
+    }
+
+    async fn table_exists(&self) -> Result<bool, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        Ok(self
+            .connection
+            .client
+            .describe_table()
+            .table_name(table_name)
+            .send()
+            .await
+            .is_ok())
+    }
+
+    async fn create_table(&self) -> Result<(), DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let pk = AttributeDefinition::builder()
+            .attribute_name(PARTITION_KEY)
+            .attribute_type(ScalarAttributeType::S)
+            .build()?;
+        let pk_ks = KeySchemaElement::builder()
+            .attribute_name(PARTITION_KEY)
+            .key_type(KeyType::Hash)
+            .build()?;
+
+        self.connection
+            .client
+            .create_table()
+            .table_name(table_name)
+            .key_schema(pk_ks)
+            .attribute_definitions(pk)
+            .billing_mode(BillingMode::PayPerRequest)
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not create RequestNonce table: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::CreateTableError(database_object)
+            })?;
+        Ok(())
+    }
+}
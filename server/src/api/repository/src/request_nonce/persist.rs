@@ -0,0 +1,47 @@
+use database::{
+    aws_sdk_dynamodb::error::ProvideErrorMetadata,
+    ddb::{try_to_item, DDBService, DatabaseError},
+};
+use tracing::{event, instrument, Level};
+use types::authn_authz::request_nonce::UsedRequestNonce;
+
+use super::{Repository, PARTITION_KEY};
+
+impl Repository {
+    /// Records that a nonce has been used, returning `false` if it had already been recorded.
+    #[instrument(skip(self, used_nonce))]
+    pub async fn persist_if_unused(
+        &self,
+        used_nonce: &UsedRequestNonce,
+    ) -> Result<bool, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+        let item = try_to_item(used_nonce, database_object)?;
+
+        let result = self
+            .connection
+            .client
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(item))
+            .condition_expression(format!("attribute_not_exists({PARTITION_KEY})"))
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(err) => {
+                let service_err = err.into_service_error();
+                if service_err.is_conditional_check_failed_exception() {
+                    return Ok(false);
+                }
+                event!(
+                    Level::ERROR,
+                    "Could not persist used request nonce: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                Err(DatabaseError::PersistenceError(database_object))
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use database::{
+    aws_sdk_dynamodb::{error::ProvideErrorMetadata, types::AttributeValue},
+    ddb::{try_from_items, try_to_attribute_val, DDBService, DatabaseError},
+};
+use tracing::{event, instrument, Level};
+use types::{account::identifiers::AccountId, security_event::SecurityEvent};
+
+use super::{Repository, PARTITION_KEY, SORT_KEY};
+
+impl Repository {
+    /// Returns up to `limit` of the account's events, newest first, starting after the event with
+    /// id `start_after` if one is given. The id of the last event returned is passed back when
+    /// there may be more to fetch.
+    #[instrument(skip(self))]
+    pub async fn fetch_page_for_account_id(
+        &self,
+        account_id: &AccountId,
+        limit: i32,
+        start_after: Option<&str>,
+    ) -> Result<(Vec<SecurityEvent>, Option<String>), DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let account_id_attr: AttributeValue = try_to_attribute_val(account_id, database_object)?;
+        let exclusive_start_key = match start_after {
+            Some(event_id) => Some(HashMap::from([
+                (PARTITION_KEY.to_owned(), account_id_attr.clone()),
+                (
+                    SORT_KEY.to_owned(),
+                    try_to_attribute_val(event_id, database_object)?,
+                ),
+            ])),
+            None => None,
+        };
+
+        let item_output = self
+            .connection
+            .client
+            .query()
+            .table_name(table_name)
+            .key_condition_expression(format!("{PARTITION_KEY} = :{PARTITION_KEY}"))
+            .expression_attribute_values(format!(":{PARTITION_KEY}"), account_id_attr)
+            .scan_index_forward(false)
+            .limit(limit)
+            .set_exclusive_start_key(exclusive_start_key)
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not fetch security events for account id: {account_id} with err: {service_err:?} and message: {:?}",
+                    service_err.message(),
+                );
+                DatabaseError::FetchError(database_object)
+            })?;
+
+        let events: Vec<SecurityEvent> =
+            try_from_items(item_output.items().to_owned(), database_object)?;
+        let next_event_id = item_output
+            .last_evaluated_key()
+            .and_then(|key| key.get(SORT_KEY))
+            .and_then(|event_id| event_id.as_s().ok())
+            .cloned();
+
+        Ok((events, next_event_id))
+    }
+}
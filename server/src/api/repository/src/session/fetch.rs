@@ -0,0 +1,102 @@
+use database::{
+    aws_sdk_dynamodb::{error::ProvideErrorMetadata, types::AttributeValue},
+    ddb::{try_from_item, try_from_items, try_to_attribute_val, DDBService, DatabaseError},
+};
+use tracing::{event, instrument, Level};
+use types::{account::identifiers::AccountId, authn_authz::session::DeviceSession};
+
+use super::{Repository, PARTITION_KEY, SORT_KEY};
+
+impl Repository {
+    #[instrument(skip(self))]
+    pub async fn fetch(
+        &self,
+        account_id: &AccountId,
+        session_id: &str,
+    ) -> Result<DeviceSession, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let item_output = self
+            .connection
+            .client
+            .get_item()
+            .table_name(table_name)
+            .key(
+                PARTITION_KEY,
+                try_to_attribute_val(account_id, database_object)?,
+            )
+            .key(SORT_KEY, try_to_attribute_val(session_id, database_object)?)
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not query database: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::FetchError(database_object)
+            })?;
+
+        let item = item_output.item.ok_or_else(|| {
+            event!(
+                Level::INFO,
+                "session {session_id} for account {account_id} not found in the database"
+            );
+            DatabaseError::ObjectNotFound(database_object)
+        })?;
+        try_from_item(item, database_object)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn fetch_for_account_id(
+        &self,
+        account_id: &AccountId,
+    ) -> Result<Vec<DeviceSession>, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let account_id_attr: AttributeValue = try_to_attribute_val(account_id, database_object)?;
+
+        let mut exclusive_start_key = None;
+        let mut result = Vec::new();
+
+        loop {
+            let item_output = self
+                .connection
+                .client
+                .query()
+                .table_name(table_name.clone())
+                .key_condition_expression(format!("{PARTITION_KEY} = :{PARTITION_KEY}"))
+                .expression_attribute_values(format!(":{PARTITION_KEY}"), account_id_attr.clone())
+                .consistent_read(true)
+                .set_exclusive_start_key(exclusive_start_key.clone())
+                .send()
+                .await
+                .map_err(|err| {
+                    let service_err = err.into_service_error();
+                    event!(
+                        Level::ERROR,
+                        "Could not fetch sessions for account id: {account_id} with err: {service_err:?} and message: {:?}",
+                        service_err.message(),
+                    );
+                    DatabaseError::FetchError(database_object)
+                })?;
+
+            let items = item_output.items();
+            let mut sessions: Vec<DeviceSession> =
+                try_from_items(items.to_owned(), database_object)?;
+            result.append(&mut sessions);
+
+            if let Some(last_evaluated_key) = item_output.last_evaluated_key() {
+                exclusive_start_key = Some(last_evaluated_key.to_owned());
+            } else {
+                break;
+            }
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,145 @@
+use database::{
+    aws_sdk_dynamodb::{error::ProvideErrorMetadata, types::AttributeValue},
+    ddb::{try_to_attribute_val, DDBService, DatabaseError},
+};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tracing::{event, instrument, Level};
+use types::{account::identifiers::AccountId, authn_authz::session::DeviceSession};
+
+use super::{Repository, PARTITION_KEY, SORT_KEY};
+
+const ACTIVE_SESSION_CONDITION: &str =
+    "attribute_exists(sort_key) AND attribute_not_exists(revoked_at)";
+
+impl Repository {
+    /// Writes the activity recorded on `session`, unless it was revoked in the meantime. Returns
+    /// whether the session is still active.
+    #[instrument(skip(self, session))]
+    pub async fn update_activity(&self, session: &DeviceSession) -> Result<bool, DatabaseError> {
+        let database_object = self.get_database_object();
+
+        let mut assignments = vec!["last_seen_at = :last_seen_at".to_string()];
+        let mut values = vec![(
+            ":last_seen_at".to_string(),
+            self.format_time(session.last_seen_at)?,
+        )];
+        for (name, value) in [
+            ("device_name", &session.device_name),
+            ("platform", &session.platform),
+            ("ip_country", &session.ip_country),
+        ] {
+            if let Some(value) = value {
+                assignments.push(format!("{name} = :{name}"));
+                values.push((
+                    format!(":{name}"),
+                    try_to_attribute_val(value, database_object)?,
+                ));
+            }
+        }
+
+        self.update(
+            &session.account_id,
+            &session.session_id,
+            &format!("SET {}", assignments.join(", ")),
+            ACTIVE_SESSION_CONDITION,
+            values,
+        )
+        .await
+    }
+
+    /// Remembers a push device token registered by an active session, so it can be cleared when
+    /// the session is revoked. Returns whether the session is still active.
+    #[instrument(skip(self, device_token))]
+    pub async fn add_push_device_token(
+        &self,
+        account_id: &AccountId,
+        session_id: &str,
+        device_token: &str,
+    ) -> Result<bool, DatabaseError> {
+        self.update(
+            account_id,
+            session_id,
+            "SET push_device_tokens = list_append(if_not_exists(push_device_tokens, :empty_list), :device_token)",
+            ACTIVE_SESSION_CONDITION,
+            vec![
+                (":empty_list".to_string(), AttributeValue::L(Vec::new())),
+                (
+                    ":device_token".to_string(),
+                    AttributeValue::L(vec![AttributeValue::S(device_token.to_string())]),
+                ),
+            ],
+        )
+        .await
+    }
+
+    /// Marks a session as revoked. Revoking a session again keeps the original revocation time.
+    /// Returns whether the session exists.
+    #[instrument(skip(self))]
+    pub async fn revoke(
+        &self,
+        account_id: &AccountId,
+        session_id: &str,
+        revoked_at: OffsetDateTime,
+    ) -> Result<bool, DatabaseError> {
+        self.update(
+            account_id,
+            session_id,
+            "SET revoked_at = if_not_exists(revoked_at, :revoked_at)",
+            "attribute_exists(sort_key)",
+            vec![(":revoked_at".to_string(), self.format_time(revoked_at)?)],
+        )
+        .await
+    }
+
+    async fn update(
+        &self,
+        account_id: &AccountId,
+        session_id: &str,
+        update_expression: &str,
+        condition_expression: &str,
+        values: Vec<(String, AttributeValue)>,
+    ) -> Result<bool, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let result = self
+            .connection
+            .client
+            .update_item()
+            .table_name(table_name)
+            .key(
+                PARTITION_KEY,
+                try_to_attribute_val(account_id, database_object)?,
+            )
+            .key(SORT_KEY, try_to_attribute_val(session_id, database_object)?)
+            .update_expression(update_expression)
+            .condition_expression(condition_expression)
+            .set_expression_attribute_values(Some(values.into_iter().collect()))
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(err) => {
+                let service_err = err.into_service_error();
+                if service_err.is_conditional_check_failed_exception() {
+                    return Ok(false);
+                }
+                event!(
+                    Level::ERROR,
+                    "Could not update session: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                Err(DatabaseError::UpdateError(database_object))
+            }
+        }
+    }
+
+    fn format_time(&self, time: OffsetDateTime) -> Result<AttributeValue, DatabaseError> {
+        let database_object = self.get_database_object();
+        let formatted = time
+            .format(&Rfc3339)
+            .map_err(|_| DatabaseError::DatetimeFormatError(database_object))?;
+        try_to_attribute_val(formatted, database_object)
+    }
+}
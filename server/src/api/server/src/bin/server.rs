@@ -85,6 +85,7 @@ async fn main() -> Result<(), Error> {
                 notification_service: bootstrap.services.notification_service,
                 account_service: bootstrap.services.account_service,
                 recovery_service: bootstrap.services.recovery_service,
+                social_recovery_repository: bootstrap.services.social_recovery_repository,
                 chain_indexer_service: bootstrap.services.chain_indexer_service,
                 address_repo: bootstrap.services.address_repo,
                 sqs: bootstrap.services.sqs,
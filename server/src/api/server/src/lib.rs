@@ -24,9 +24,12 @@ use authn_authz::authorizer::{
     authorize_account_or_recovery_token_for_path, authorize_recovery_token_for_path,
     authorize_token_for_path, AuthorizerConfig,
 };
+use authn_authz::session::{track_session, SessionTrackingState};
+use authn_authz::signed_request::{digest_signed_request, RequestSigningState};
 use bdk_utils::{TransactionBroadcaster, TransactionBroadcasterTrait};
 use chain_indexer::{
     repository::Repository as ChainIndexerRepository, service::Service as ChainIndexerService,
+    state_repository::StateRepository as ChainIndexerStateRepository,
 };
 use comms_verification::Service as CommsVerificationService;
 use database::ddb::{self, DDBService};
@@ -41,6 +44,7 @@ use mobile_pay::daily_spend_record::{
     repository::Repository as DailySpendRecordRepository,
     service::Service as DailySpendRecordService,
 };
+use mobile_pay::fee_estimate::FeeEstimatorTrait;
 use mobile_pay::signed_psbt_cache::{
     repository::Repository as SignedPsbtCacheRepository, service::Service as SignedPsbtCacheService,
 };
@@ -50,12 +54,16 @@ use notification::address_repo::AddressWatchlistTrait;
 use notification::repository::Repository as NotificationRepository;
 use notification::service::Service as NotificationService;
 use recovery::repository::Repository as RecoveryRepository;
+use recovery::service::inheritance::Service as InheritanceService;
 use recovery::service::social::{
     challenge::Service as SocialChallengeService,
     relationship::Service as RecoveryRelationshipService,
 };
 use repository::consent::Repository as ConsentRepository;
 use repository::recovery::social::Repository as SocialRecoveryRepository;
+use repository::request_nonce::Repository as RequestNonceRepository;
+use repository::security_event::Repository as SecurityEventRepository;
+use repository::session::Repository as SessionRepository;
 pub use routes::axum::axum;
 use screener::service::Service as ScreenerService;
 use wallet_telemetry::{set_global_telemetry, METRICS_REPORTING_PERIOD_SECS};
@@ -90,6 +98,9 @@ pub struct Services {
     pub iterable_client: IterableClient,
     pub consent_repository: ConsentRepository,
     pub social_challenge_service: SocialChallengeService,
+    pub social_recovery_repository: SocialRecoveryRepository,
+    pub inheritance_service: InheritanceService,
+    pub account_repository: AccountRepository,
 }
 
 #[derive(Debug, Error)]
@@ -118,6 +129,7 @@ pub enum BootstrapError {
 pub struct GenServiceOverrides {
     pub address_repo: Option<Box<dyn AddressWatchlistTrait>>,
     pub broadcaster: Option<Arc<dyn TransactionBroadcasterTrait>>,
+    pub fee_estimator: Option<Arc<dyn FeeEstimatorTrait>>,
     pub blocked_addresses: Option<HashSet<String>>,
     pub feature_flags: Option<HashMap<String, String>>,
 }
@@ -137,6 +149,11 @@ impl GenServiceOverrides {
         self
     }
 
+    pub fn fee_estimator(mut self, fee_estimator: Arc<dyn FeeEstimatorTrait>) -> Self {
+        self.fee_estimator = Some(fee_estimator);
+        self
+    }
+
     pub fn blocked_addresses(mut self, blocked_addresses: HashSet<String>) -> Self {
         self.blocked_addresses = Some(blocked_addresses);
         self
@@ -165,10 +182,12 @@ pub async fn create_bootstrap_with_overrides(
         .await;
     let userpool_service = UserPoolService::new(cognito_connection.clone());
 
-    // Construct overriden feature config if feature_flags is present.
+    // Apply the feature_flags overrides, if present, over the profile's own.
     let feature_flags = match overrides.feature_flags {
         None => config::extract::<feature_flags::config::Config>(profile)?,
-        Some(flags) => feature_flags::config::Config::new_with_overrides(flags),
+        Some(flags) => {
+            config::extract::<feature_flags::config::Config>(profile)?.with_overrides(flags)
+        }
     }
     .to_service()
     .await?;
@@ -183,16 +202,24 @@ pub async fn create_bootstrap_with_overrides(
     let ddb = config::extract::<ddb::Config>(profile)?
         .to_connection()
         .await;
-    let wsm_service = config::extract::<wsm::Config>(profile)?.to_client()?;
+    let wsm_service = config::extract::<wsm::Config>(profile)?.to_client(profile)?;
     let health_checks = healthcheck::Service::new(feature_flags.clone())?;
 
     let account_repository = AccountRepository::new(ddb.clone());
     account_repository.create_table_if_necessary().await?;
     let consent_repository = ConsentRepository::new(ddb.clone());
     consent_repository.create_table_if_necessary().await?;
+    let session_repository = SessionRepository::new(ddb.clone());
+    session_repository.create_table_if_necessary().await?;
+    let security_event_repository = SecurityEventRepository::new(ddb.clone());
+    security_event_repository
+        .create_table_if_necessary()
+        .await?;
     let account_service = AccountService::new(
         account_repository.clone(),
         consent_repository.clone(),
+        session_repository,
+        security_event_repository,
         userpool_service.clone(),
     );
 
@@ -204,7 +231,7 @@ pub async fn create_bootstrap_with_overrides(
     >(profile)?);
     let notification_service = NotificationService::new(
         notification_repository,
-        account_repository,
+        account_repository.clone(),
         account_service.clone(),
         sqs.clone(),
         iterable_client.clone(),
@@ -221,16 +248,22 @@ pub async fn create_bootstrap_with_overrides(
     let recovery_relationship_service = RecoveryRelationshipService::new(
         social_recovery_repository.clone(),
         notification_service.clone(),
+        account_service.clone(),
     );
     let social_challenge_service = SocialChallengeService::new(
-        social_recovery_repository,
+        social_recovery_repository.clone(),
         recovery_relationship_service.clone(),
         notification_service.clone(),
         account_service.clone(),
     );
     let chain_indexer_repository = ChainIndexerRepository::new(ddb.clone());
     chain_indexer_repository.create_table_if_necessary().await?;
-    let chain_indexer_service = ChainIndexerService::new(chain_indexer_repository);
+    let chain_indexer_state_repository = ChainIndexerStateRepository::new(ddb.clone());
+    chain_indexer_state_repository
+        .create_table_if_necessary()
+        .await?;
+    let chain_indexer_service =
+        ChainIndexerService::new(chain_indexer_repository, chain_indexer_state_repository);
 
     let identifier_generator = config::extract::<IdentifierGenerator>(profile)?;
 
@@ -249,6 +282,10 @@ pub async fn create_bootstrap_with_overrides(
     let broadcaster = overrides
         .broadcaster
         .unwrap_or(Arc::new(TransactionBroadcaster));
+    let fee_estimator = match overrides.fee_estimator {
+        Some(fee_estimator) => fee_estimator,
+        None => config::extract::<mobile_pay::fee_estimate::Config>(profile)?.to_fee_estimator(),
+    };
     let address_repo = overrides.address_repo.unwrap_or(Box::new(
         AddressWatchlistService::create(ddb.clone()).await?,
     ));
@@ -262,6 +299,18 @@ pub async fn create_bootstrap_with_overrides(
     let screener_service =
         ScreenerService::new_and_load_data(overrides.blocked_addresses, screener_config).await;
 
+    let inheritance_service = InheritanceService::new(
+        social_recovery_repository.clone(),
+        recovery_relationship_service.clone(),
+        notification_service.clone(),
+        account_service.clone(),
+        daily_spend_record_service.clone(),
+        wsm_service.client.clone(),
+        Arc::new(screener_service.clone()),
+        broadcaster.clone(),
+        feature_flags.clone(),
+    );
+
     let notification = notification::routes::RouteState(
         notification_service.clone(),
         account_service.clone(),
@@ -297,6 +346,8 @@ pub async fn create_bootstrap_with_overrides(
         signed_psbt_cache_service.clone(),
         feature_flags.clone(),
         Arc::new(screener_service.clone()),
+        fee_estimator,
+        chain_indexer_service.clone(),
     );
     let recovery = recovery::routes::RouteState(
         account_service.clone(),
@@ -308,6 +359,7 @@ pub async fn create_bootstrap_with_overrides(
         recovery_relationship_service.clone(),
         social_challenge_service.clone(),
         feature_flags.clone(),
+        inheritance_service.clone(),
     );
     let exchange_rate =
         exchange_rate::routes::RouteState(exchange_rate_service.clone(), feature_flags.clone());
@@ -317,8 +369,24 @@ pub async fn create_bootstrap_with_overrides(
         customer_feedback_config.zendesk.to_client(),
         customer_feedback_config.known_fields.into(),
     );
-    let authentication =
-        authn_authz::routes::RouteState(userpool_service.clone(), account_service.clone());
+    let nonce_issuer =
+        config::extract::<authn_authz::signed_request::Config>(profile)?.to_nonce_issuer();
+    let request_nonce_repository = RequestNonceRepository::new(ddb.clone());
+    request_nonce_repository.create_table_if_necessary().await?;
+    let request_signing = RequestSigningState {
+        nonces: nonce_issuer.clone(),
+        used_nonces: request_nonce_repository,
+        feature_flags: feature_flags.clone(),
+    };
+    let session_tracking = SessionTrackingState {
+        account_service: account_service.clone(),
+        feature_flags: feature_flags.clone(),
+    };
+    let authentication = authn_authz::routes::RouteState(
+        userpool_service.clone(),
+        account_service.clone(),
+        nonce_issuer,
+    );
     let analytics = config::extract::<analytics::routes::Config>(profile)?.to_state();
     #[allow(unused_mut)]
     let mut router = Router::new()
@@ -326,6 +394,7 @@ pub async fn create_bootstrap_with_overrides(
         .merge(Router::from(mobile_pay.clone()))
         .merge(recovery.authed_router())
         .merge(onboarding.authed_router())
+        .merge(authentication.account_authed_router())
         .route_layer(middleware::from_fn(authorize_token_for_path));
 
     let recovery_router = Router::new()
@@ -351,6 +420,15 @@ pub async fn create_bootstrap_with_overrides(
     router = router
         .merge(exchange_rate.basic_validation_router())
         .merge(customer_feedback.basic_validation_router())
+        .merge(authentication.authed_router())
+        .layer(middleware::from_fn_with_state(
+            request_signing,
+            digest_signed_request,
+        ))
+        .layer(middleware::from_fn_with_state(
+            session_tracking,
+            track_session,
+        ))
         .layer(authorizer)
         .merge(authentication.unauthed_router())
         .merge(notification.unauthed_router())
@@ -391,6 +469,9 @@ pub async fn create_bootstrap_with_overrides(
             iterable_client,
             consent_repository,
             social_challenge_service,
+            social_recovery_repository,
+            inheritance_service,
+            account_repository,
         },
         router,
     })
@@ -342,7 +342,7 @@ async fn test_inactive_keyset_id_exists_in_wsm() {
     // This is to ensure the root key id provided is the one in the WSM
     let wsm_service = http_server::config::extract::<wsm::Config>("test".into())
         .unwrap()
-        .to_client()
+        .to_client("test".into())
         .unwrap();
 
     let _ = wsm_service
@@ -3,19 +3,27 @@ use crate::tests::{
     lib::{create_account, create_default_account_with_predefined_wallet},
     requests::{axum::TestClient, worker::TestWorker},
 };
+use crate::Services;
 use account::entities::FullAccount;
 use account::{entities::TouchpointPlatform, service::AddPushTouchpointToAccountInput};
+use bdk_utils::bdk::bitcoin::{hashes::Hash, Address, Network, ScriptBuf, WPubkeyHash};
+use chain_indexer::{service::Service as ChainIndexerService, source::mock::MockChainSource};
+use database::{aws_sdk_dynamodb::types::AttributeValue, ddb::DDBService};
 use httpmock::{prelude::*, Mock, MockExt};
 use notification::address_repo::AddressAndKeysetId;
 use notification::service::FetchForAccountInput;
 use notification::service::Service as NotificationService;
 use notification::NotificationPayloadType;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use types::{
     account::identifiers::{AccountId, KeysetId},
     notification::{NotificationChannel, NotificationsPreferences},
 };
 
+// Receives a payment in mocked block 107036.
+const PAYMENT_ADDRESS: &str = "tb1p2u3xcjt9x64s9u3lqwfndn5td5dkasf7amz0h7643k5ds9vvvacq7dvf7k";
+
 struct ChainMockData {
     tip_hash_mock_id: Option<usize>,
     expected_tip_hash_hits: usize,
@@ -101,6 +109,124 @@ async fn test_init_block_received_payment() {
     test_queue_message(&notification_service, &account.id, 1).await;
 }
 
+#[tokio::test]
+async fn test_mock_chain_pending_reorg_and_resume() {
+    // Testnet keeps this chain's cursor apart from the signet one used by other tests, while
+    // still deriving `tb1` addresses.
+    let source = MockChainSource::new();
+    let chain_source = Arc::new(source.clone());
+    let mut chain_indexer_service = None;
+    let (account, worker, services) = setup_accounts_and_worker(|service| {
+        let service = service
+            .set_network(Network::Testnet)
+            .set_chain_source(chain_source)
+            .set_mempool_enabled(true)
+            .set_confirmation_depth(Some(3));
+        chain_indexer_service = Some(service.clone());
+        service
+    })
+    .await;
+    let chain_indexer_service = chain_indexer_service.unwrap();
+    let notification_service = services.notification_service.clone();
+    let script_pubkey: ScriptBuf = PAYMENT_ADDRESS
+        .parse::<Address<_>>()
+        .unwrap()
+        .assume_checked()
+        .script_pubkey();
+    let assert_notifications = |expected: PaymentNotificationCounts| {
+        assert_payment_notifications(&notification_service, &account.id, expected)
+    };
+
+    // The first run starts the cursor at the tip
+    let genesis = source.mine(None, vec![]);
+    let base = source.mine(Some(genesis), vec![]);
+    let base = source.mine(Some(base), vec![]);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::default()).await;
+
+    // A payment in the mempool is notified as pending, once
+    let payment = source.payment_to(script_pubkey.clone(), 10_000);
+    source.add_to_mempool(payment.clone());
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 0, 0, 0)).await;
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 0, 0, 0)).await;
+
+    // Once mined, it's notified as confirmed
+    let stale_tip = source.mine(Some(base), vec![payment.clone()]);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 1, 0, 0)).await;
+
+    // A longer branch from the base that also contains the payment reorgs out the stale tip. The
+    // branch is replayed, but the payment isn't notified again.
+    let fork = source.mine(Some(base), vec![payment]);
+    let fork_tip = source.mine(Some(fork), vec![]);
+    assert_ne!(stale_tip, fork);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 1, 0, 0)).await;
+
+    // Payments in blocks mined between runs are picked up from the cursor. The first payment
+    // reaches 3 confirmations with the first of these blocks, and the second with the last.
+    let second_payment = source.payment_to(script_pubkey.clone(), 20_000);
+    let third_payment = source.payment_to(script_pubkey.clone(), 30_000);
+    let next = source.mine(Some(fork_tip), vec![second_payment]);
+    let next = source.mine(Some(next), vec![]);
+    let next = source.mine(Some(next), vec![third_payment]);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 3, 2, 0)).await;
+
+    // Nothing new is notified once caught up
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 3, 2, 0)).await;
+
+    // A cosigned payment sending change back to the account is only notified as outgoing once
+    // confirmed, rather than as money received
+    let outgoing_payment = source.payment_to(script_pubkey.clone(), 5_000);
+    chain_indexer_service
+        .track_outgoing_payment(&account.id, &outgoing_payment.txid())
+        .await
+        .unwrap();
+    source.add_to_mempool(outgoing_payment.clone());
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 3, 2, 0)).await;
+    let next = source.mine(Some(next), vec![outgoing_payment]);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 3, 2, 1)).await;
+
+    // The third payment settles, while the outgoing payment doesn't
+    let next = source.mine(Some(next), vec![]);
+    let next = source.mine(Some(next), vec![]);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 3, 3, 1)).await;
+
+    // A backlog longer than a single sync is caught up from the cursor over several runs, rather
+    // than skipping the blocks furthest from the tip
+    let fourth_payment = source.payment_to(script_pubkey.clone(), 40_000);
+    let fifth_payment = source.payment_to(script_pubkey.clone(), 50_000);
+    let mut next = source.mine(Some(next), vec![fourth_payment]);
+    for _ in 0..28 {
+        next = source.mine(Some(next), vec![]);
+    }
+    let last = source.mine(Some(next), vec![fifth_payment]);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 4, 4, 1)).await;
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 5, 4, 1)).await;
+
+    // A payment to an account that can't be notified doesn't hold up the rest of the block, and
+    // the cursor still moves past it
+    let unreadable_address = add_unreadable_account(&services, Network::Testnet).await;
+    let failing_payment = source.payment_to(unreadable_address.script_pubkey(), 60_000);
+    let sixth_payment = source.payment_to(script_pubkey.clone(), 60_000);
+    let next = source.mine(Some(last), vec![failing_payment, sixth_payment]);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 6, 4, 1)).await;
+    let seventh_payment = source.payment_to(script_pubkey, 70_000);
+    source.mine(Some(next), vec![seventh_payment]);
+    worker.blockchain_polling().await;
+    assert_notifications(PaymentNotificationCounts::new(1, 7, 4, 1)).await;
+}
+
 async fn setup_full_accounts_and_server() -> (
     MockServer,
     FullAccount,
@@ -109,6 +235,46 @@ async fn setup_full_accounts_and_server() -> (
     ChainMockData,
 ) {
     let mock_server = MockServer::start();
+    let base_url = mock_server.base_url();
+    let (account_with_payment, worker, services) =
+        setup_accounts_and_worker(|service| service.set_mock_server(base_url)).await;
+
+    let chain_mock_data = setup_raw_block_mocks(
+        &mock_server,
+        vec![
+            BlockHeader {
+                block_hash: "00000091c3089d71ac2ed150c18cfb96898cb0a63e5a4695cd79536de452e5fa", // 107035
+                prev_hash: "00000086a8b5a64017cbcc88db2e78999a75ce2f2924a665d60b607c753d297b",
+            },
+            BlockHeader {
+                block_hash: "00000049405168aecc9bdc996f2d35ae8f7855685dfd4c6513f68679428cdbfe", // 107036
+                prev_hash: "00000091c3089d71ac2ed150c18cfb96898cb0a63e5a4695cd79536de452e5fa",
+            },
+            BlockHeader {
+                block_hash: "000000d3d12016125e10320dc1e2b3a719266c48313c8529d812cd58b190d0d4", // 107037
+                prev_hash: "00000049405168aecc9bdc996f2d35ae8f7855685dfd4c6513f68679428cdbfe",
+            },
+            BlockHeader {
+                block_hash: "0000012b9852f41934927b43ebac7354b10627f17ebc85e1de6bae593312591a", // 107038
+                prev_hash: "000000d3d12016125e10320dc1e2b3a719266c48313c8529d812cd58b190d0d4",
+            },
+        ],
+    );
+
+    (
+        mock_server,
+        account_with_payment,
+        worker,
+        services.notification_service,
+        chain_mock_data,
+    )
+}
+
+// Sets up one account watching `PAYMENT_ADDRESS` and one without any payments, both with push
+// notifications enabled.
+async fn setup_accounts_and_worker(
+    configure_chain_indexer: impl FnOnce(ChainIndexerService) -> ChainIndexerService,
+) -> (FullAccount, TestWorker, Services) {
     let bootstrap = gen_services().await;
     let client = TestClient::new(bootstrap.router.clone()).await;
     let (account_with_payment, _) =
@@ -124,12 +290,11 @@ async fn setup_full_accounts_and_server() -> (
         notification_service: bootstrap.services.notification_service.clone(),
         account_service: bootstrap.services.account_service.clone(),
         recovery_service: bootstrap.services.recovery_service.clone(),
+        social_recovery_repository: bootstrap.services.social_recovery_repository.clone(),
         address_repo: bootstrap.services.address_repo.clone(),
-        chain_indexer_service: bootstrap
-            .services
-            .chain_indexer_service
-            .clone()
-            .set_mock_server(mock_server.base_url()),
+        chain_indexer_service: configure_chain_indexer(
+            bootstrap.services.chain_indexer_service.clone(),
+        ),
         sqs: bootstrap.services.sqs.clone(),
         feature_flags_service: bootstrap.services.feature_flags_service.clone(),
     };
@@ -141,6 +306,7 @@ async fn setup_full_accounts_and_server() -> (
             platform: TouchpointPlatform::ApnsTeam,
             device_token: "test".to_string(),
             access_token: Default::default(),
+            session_id: None,
         })
         .await
         .unwrap();
@@ -164,6 +330,7 @@ async fn setup_full_accounts_and_server() -> (
             platform: TouchpointPlatform::ApnsTeam,
             device_token: "test".to_string(),
             access_token: Default::default(),
+            session_id: None,
         })
         .await
         .unwrap();
@@ -181,13 +348,9 @@ async fn setup_full_accounts_and_server() -> (
         .await;
 
     // Add destination address from mocked block 107036 to watchlist table.
-    // Note, these addresses aren't actually derivable from the account's bdk wallet.
-    let fake_registration = AddressAndKeysetId::new(
-        "tb1p2u3xcjt9x64s9u3lqwfndn5td5dkasf7amz0h7643k5ds9vvvacq7dvf7k"
-            .parse()
-            .unwrap(),
-        KeysetId::gen().unwrap(),
-    );
+    // Note, this address isn't actually derivable from the account's bdk wallet.
+    let fake_registration =
+        AddressAndKeysetId::new(PAYMENT_ADDRESS.parse().unwrap(), KeysetId::gen().unwrap());
     bootstrap
         .services
         .address_repo
@@ -198,35 +361,38 @@ async fn setup_full_accounts_and_server() -> (
 
     let worker = TestWorker::new(state).await;
 
-    let chain_mock_data = setup_raw_block_mocks(
-        &mock_server,
-        vec![
-            BlockHeader {
-                block_hash: "00000091c3089d71ac2ed150c18cfb96898cb0a63e5a4695cd79536de452e5fa", // 107035
-                prev_hash: "00000086a8b5a64017cbcc88db2e78999a75ce2f2924a665d60b607c753d297b",
-            },
-            BlockHeader {
-                block_hash: "00000049405168aecc9bdc996f2d35ae8f7855685dfd4c6513f68679428cdbfe", // 107036
-                prev_hash: "00000091c3089d71ac2ed150c18cfb96898cb0a63e5a4695cd79536de452e5fa",
-            },
-            BlockHeader {
-                block_hash: "000000d3d12016125e10320dc1e2b3a719266c48313c8529d812cd58b190d0d4", // 107037
-                prev_hash: "00000049405168aecc9bdc996f2d35ae8f7855685dfd4c6513f68679428cdbfe",
-            },
-            BlockHeader {
-                block_hash: "0000012b9852f41934927b43ebac7354b10627f17ebc85e1de6bae593312591a", // 107038
-                prev_hash: "000000d3d12016125e10320dc1e2b3a719266c48313c8529d812cd58b190d0d4",
-            },
-        ],
-    );
+    (account_with_payment, worker, bootstrap.services)
+}
 
-    (
-        mock_server,
-        account_with_payment,
-        worker,
-        bootstrap.services.notification_service,
-        chain_mock_data,
-    )
+// Watches an address for an account whose record can't be read, so that notifying it fails.
+async fn add_unreadable_account(services: &Services, network: Network) -> Address {
+    let account_id = AccountId::gen().unwrap();
+    let account_repository = &services.account_repository;
+    account_repository
+        .get_connection()
+        .client
+        .put_item()
+        .table_name(account_repository.get_table_name().await.unwrap())
+        .item("partition_key", AttributeValue::S(account_id.to_string()))
+        .send()
+        .await
+        .unwrap();
+
+    let script_pubkey = ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::from_byte_array(rand::random()));
+    let address = Address::from_script(&script_pubkey, network).unwrap();
+    services
+        .address_repo
+        .clone()
+        .insert(
+            &[AddressAndKeysetId::new(
+                address.to_string().parse().unwrap(),
+                KeysetId::gen().unwrap(),
+            )],
+            &account_id,
+        )
+        .await
+        .unwrap();
+    address
 }
 
 fn setup_raw_block_mocks(
@@ -374,3 +540,66 @@ async fn test_queue_message(
         notifications
     );
 }
+
+#[derive(Debug, Default)]
+struct PaymentNotificationCounts {
+    pending: usize,
+    confirmed: usize,
+    settled: usize,
+    outgoing: usize,
+}
+
+impl PaymentNotificationCounts {
+    fn new(pending: usize, confirmed: usize, settled: usize, outgoing: usize) -> Self {
+        Self {
+            pending,
+            confirmed,
+            settled,
+            outgoing,
+        }
+    }
+}
+
+async fn assert_payment_notifications(
+    notification_service: &NotificationService,
+    account_id: &AccountId,
+    expected: PaymentNotificationCounts,
+) {
+    let notifications = notification_service
+        .fetch_customer_for_account(FetchForAccountInput {
+            account_id: account_id.clone(),
+        })
+        .await
+        .unwrap();
+    let count = |payload_type| {
+        notifications
+            .iter()
+            .filter(|n| n.payload_type == payload_type)
+            .count()
+    };
+
+    assert_eq!(
+        count(NotificationPayloadType::PendingPaymentNotification),
+        expected.pending,
+        "{:?}",
+        notifications
+    );
+    assert_eq!(
+        count(NotificationPayloadType::PaymentNotification),
+        expected.confirmed,
+        "{:?}",
+        notifications
+    );
+    assert_eq!(
+        count(NotificationPayloadType::SettledPaymentNotification),
+        expected.settled,
+        "{:?}",
+        notifications
+    );
+    assert_eq!(
+        count(NotificationPayloadType::OutgoingPaymentNotification),
+        expected.outgoing,
+        "{:?}",
+        notifications
+    );
+}
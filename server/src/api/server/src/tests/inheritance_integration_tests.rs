@@ -0,0 +1,689 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use account::entities::{Account, FullAccount, FullAccountAuthKeys, Network};
+use authn_authz::routes::{
+    AuthRequestKey, AuthenticationRequest, ChallengeResponseParameters, GetTokensRequest,
+};
+use bdk_utils::{
+    bdk::{bitcoin::psbt::PartiallySignedTransaction as Psbt, wallet::AddressIndex, SignOptions},
+    DescriptorKeyset,
+};
+use http::StatusCode;
+use recovery::routes::{
+    CompleteInheritanceClaimRequest, StartInheritanceClaimRequest, StartInheritanceClaimResponse,
+    UploadInheritancePackageRequest,
+};
+use time::{Duration, OffsetDateTime};
+use types::{
+    account::identifiers::AccountId,
+    recovery::{inheritance::InheritanceClaimStatus, social::relationship::RecoveryRelationshipId},
+};
+
+use super::{
+    gen_services, gen_services_with_overrides,
+    lib::{
+        build_sweep_transaction, create_account, create_default_account_with_predefined_wallet,
+        create_lite_account, create_pubkey, default_electrum_rpc_uris, get_static_test_authkeys,
+    },
+    recovery_relationship_integration_tests::{
+        try_accept_recovery_relationship_invitation, try_create_recovery_relationship,
+        try_endorse_recovery_relationship, CodeOverride,
+    },
+    requests::{axum::TestClient, CognitoAuthentication},
+    transaction_integration_tests::MockTransactionBroadcaster,
+};
+use crate::{tests, GenServiceOverrides};
+
+const CUSTOMER_AUTH: CognitoAuthentication = CognitoAuthentication::Wallet {
+    is_app_signed: true,
+    is_hardware_signed: true,
+};
+
+// Beneficiaries get their own auth keys so that they can't be mistaken for the customer when
+// authenticating by pubkey
+async fn create_beneficiary_account(services: &crate::Services) -> FullAccount {
+    create_account(
+        services,
+        Network::BitcoinSignet,
+        Some(FullAccountAuthKeys::new(
+            create_pubkey(),
+            create_pubkey(),
+            Some(create_pubkey()),
+        )),
+    )
+    .await
+}
+
+async fn setup_recovery_relationship(
+    client: &TestClient,
+    customer_account_id: &AccountId,
+    trusted_contact_account_id: &AccountId,
+    endorse: bool,
+) -> RecoveryRelationshipId {
+    let create_body = try_create_recovery_relationship(
+        client,
+        customer_account_id,
+        &CUSTOMER_AUTH,
+        StatusCode::OK,
+        1,
+        0,
+    )
+    .await
+    .unwrap();
+
+    try_accept_recovery_relationship_invitation(
+        client,
+        customer_account_id,
+        trusted_contact_account_id,
+        &CognitoAuthentication::Recovery,
+        &create_body.invitation,
+        CodeOverride::None,
+        StatusCode::OK,
+        1,
+    )
+    .await;
+
+    let recovery_relationship_id = create_body.invitation.recovery_relationship_id;
+    if endorse {
+        try_endorse_recovery_relationship(
+            client,
+            customer_account_id,
+            &recovery_relationship_id,
+            "ENDORSEMENT_CERT",
+            StatusCode::OK,
+        )
+        .await;
+    }
+
+    recovery_relationship_id
+}
+
+async fn try_upload_inheritance_package(
+    client: &TestClient,
+    customer_account_id: &AccountId,
+    recovery_relationship_id: &RecoveryRelationshipId,
+    inactivity_period_days: u32,
+    auth: &CognitoAuthentication,
+    expected_status_code: StatusCode,
+) {
+    let upload_response = client
+        .upload_inheritance_package(
+            &customer_account_id.to_string(),
+            &UploadInheritancePackageRequest {
+                recovery_relationship_id: recovery_relationship_id.to_owned(),
+                sealed_dek: "SEALED_DEK".to_string(),
+                sealed_mobile_key: "SEALED_MOBILE_KEY".to_string(),
+                inactivity_period_days: Some(inactivity_period_days),
+            },
+            auth,
+        )
+        .await;
+
+    assert_eq!(
+        upload_response.status_code, expected_status_code,
+        "{:?}",
+        upload_response.body_string
+    );
+
+    if expected_status_code == StatusCode::OK {
+        let upload_body = upload_response.body.unwrap();
+        assert_eq!(
+            &upload_body.package.recovery_relationship_id,
+            recovery_relationship_id
+        );
+        assert_eq!(
+            upload_body.package.inactivity_period_days,
+            inactivity_period_days
+        );
+    }
+}
+
+async fn try_start_inheritance_claim(
+    client: &TestClient,
+    beneficiary_account_id: &AccountId,
+    recovery_relationship_id: &RecoveryRelationshipId,
+    expected_status_code: StatusCode,
+) -> Option<StartInheritanceClaimResponse> {
+    let start_response = client
+        .start_inheritance_claim(
+            &beneficiary_account_id.to_string(),
+            &StartInheritanceClaimRequest {
+                recovery_relationship_id: recovery_relationship_id.to_owned(),
+            },
+            &CognitoAuthentication::Recovery,
+        )
+        .await;
+
+    assert_eq!(
+        start_response.status_code, expected_status_code,
+        "{:?}",
+        start_response.body_string
+    );
+
+    if expected_status_code == StatusCode::OK {
+        let start_body = start_response.body.unwrap();
+        assert_eq!(start_body.claim.status, InheritanceClaimStatus::Pending);
+        return Some(start_body);
+    }
+
+    None
+}
+
+#[derive(Debug)]
+struct UploadInheritancePackageTestVector {
+    is_endorsed: bool,
+    auth: CognitoAuthentication,
+    expected_status_code: StatusCode,
+}
+
+async fn upload_inheritance_package_test(vector: UploadInheritancePackageTestVector) {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+
+    let customer_account = create_account(&bootstrap.services, Network::BitcoinSignet, None).await;
+    let tc_account = create_lite_account(&bootstrap.services, None, true).await;
+
+    let recovery_relationship_id = setup_recovery_relationship(
+        &client,
+        &customer_account.id,
+        &tc_account.id,
+        vector.is_endorsed,
+    )
+    .await;
+
+    try_upload_inheritance_package(
+        &client,
+        &customer_account.id,
+        &recovery_relationship_id,
+        0,
+        &vector.auth,
+        vector.expected_status_code,
+    )
+    .await;
+
+    if vector.expected_status_code == StatusCode::OK {
+        let customer_response = client
+            .get_inheritance(&customer_account.id.to_string(), &CUSTOMER_AUTH)
+            .await;
+        assert_eq!(customer_response.status_code, StatusCode::OK);
+        assert_eq!(customer_response.body.unwrap().packages.len(), 1);
+
+        let tc_response = client
+            .get_inheritance(&tc_account.id.to_string(), &CognitoAuthentication::Recovery)
+            .await;
+        assert_eq!(tc_response.status_code, StatusCode::OK);
+        assert_eq!(tc_response.body.unwrap().packages.len(), 1);
+    }
+}
+
+tests! {
+    runner = upload_inheritance_package_test,
+    test_upload_inheritance_package: UploadInheritancePackageTestVector {
+        is_endorsed: true,
+        auth: CUSTOMER_AUTH,
+        expected_status_code: StatusCode::OK,
+    },
+    test_upload_inheritance_package_unendorsed_relationship: UploadInheritancePackageTestVector {
+        is_endorsed: false,
+        auth: CUSTOMER_AUTH,
+        expected_status_code: StatusCode::CONFLICT,
+    },
+    test_upload_inheritance_package_without_hw_keyproof: UploadInheritancePackageTestVector {
+        is_endorsed: true,
+        auth: CognitoAuthentication::Wallet {
+            is_app_signed: true,
+            is_hardware_signed: false,
+        },
+        expected_status_code: StatusCode::FORBIDDEN,
+    },
+}
+
+#[derive(Debug)]
+struct StartInheritanceClaimTestVector {
+    upload_package: bool,
+    inactivity_period_days: u32,
+    is_beneficiary: bool,
+    is_full_beneficiary: bool,
+    expected_status_code: StatusCode,
+}
+
+async fn start_inheritance_claim_test(vector: StartInheritanceClaimTestVector) {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+
+    let customer_account = create_account(&bootstrap.services, Network::BitcoinSignet, None).await;
+    let tc_account_id = if vector.is_full_beneficiary {
+        create_beneficiary_account(&bootstrap.services).await.id
+    } else {
+        create_lite_account(&bootstrap.services, None, true)
+            .await
+            .id
+    };
+    let other_account = create_lite_account(&bootstrap.services, None, true).await;
+
+    let recovery_relationship_id =
+        setup_recovery_relationship(&client, &customer_account.id, &tc_account_id, true).await;
+
+    if vector.upload_package {
+        try_upload_inheritance_package(
+            &client,
+            &customer_account.id,
+            &recovery_relationship_id,
+            vector.inactivity_period_days,
+            &CUSTOMER_AUTH,
+            StatusCode::OK,
+        )
+        .await;
+    }
+
+    let start_body = try_start_inheritance_claim(
+        &client,
+        if vector.is_beneficiary {
+            &tc_account_id
+        } else {
+            &other_account.id
+        },
+        &recovery_relationship_id,
+        vector.expected_status_code,
+    )
+    .await;
+
+    if let Some(start_body) = start_body {
+        // Only one claim may be pending per relationship
+        try_start_inheritance_claim(
+            &client,
+            &tc_account_id,
+            &recovery_relationship_id,
+            StatusCode::CONFLICT,
+        )
+        .await;
+
+        // The sealed material isn't released while the delay period is running
+        let fetch_response = client
+            .fetch_inheritance_claim(
+                &tc_account_id.to_string(),
+                &start_body.claim.id.to_string(),
+                &CognitoAuthentication::Recovery,
+            )
+            .await;
+        assert_eq!(
+            fetch_response.status_code,
+            StatusCode::OK,
+            "{:?}",
+            fetch_response.body_string
+        );
+        let fetch_body = fetch_response.body.unwrap();
+        assert_eq!(fetch_body.claim.status, InheritanceClaimStatus::Pending);
+        assert!(fetch_body.sealed_dek.is_none());
+        assert!(fetch_body.sealed_mobile_key.is_none());
+
+        let get_response = client
+            .get_inheritance(&customer_account.id.to_string(), &CUSTOMER_AUTH)
+            .await;
+        assert_eq!(get_response.status_code, StatusCode::OK);
+        assert_eq!(get_response.body.unwrap().customer_claims.len(), 1);
+    }
+}
+
+tests! {
+    runner = start_inheritance_claim_test,
+    test_start_inheritance_claim: StartInheritanceClaimTestVector {
+        upload_package: true,
+        inactivity_period_days: 0,
+        is_beneficiary: true,
+        is_full_beneficiary: true,
+        expected_status_code: StatusCode::OK,
+    },
+    test_start_inheritance_claim_customer_recently_active: StartInheritanceClaimTestVector {
+        upload_package: true,
+        inactivity_period_days: 30,
+        is_beneficiary: true,
+        is_full_beneficiary: true,
+        expected_status_code: StatusCode::CONFLICT,
+    },
+    test_start_inheritance_claim_without_package: StartInheritanceClaimTestVector {
+        upload_package: false,
+        inactivity_period_days: 0,
+        is_beneficiary: true,
+        is_full_beneficiary: true,
+        expected_status_code: StatusCode::NOT_FOUND,
+    },
+    test_start_inheritance_claim_not_beneficiary: StartInheritanceClaimTestVector {
+        upload_package: true,
+        inactivity_period_days: 0,
+        is_beneficiary: false,
+        is_full_beneficiary: true,
+        expected_status_code: StatusCode::FORBIDDEN,
+    },
+    test_start_inheritance_claim_lite_beneficiary: StartInheritanceClaimTestVector {
+        upload_package: true,
+        inactivity_period_days: 0,
+        is_beneficiary: true,
+        is_full_beneficiary: false,
+        expected_status_code: StatusCode::FORBIDDEN,
+    },
+}
+
+#[derive(Debug)]
+struct StartInheritanceClaimForOldAccountTestVector {
+    customer_authenticated: bool,
+    expected_status_code: StatusCode,
+}
+
+async fn start_inheritance_claim_for_old_account_test(
+    vector: StartInheritanceClaimForOldAccountTestVector,
+) {
+    // Token issuance has to count as a sign of life even when requests aren't being tracked
+    // against sessions
+    let overrides = GenServiceOverrides::new().feature_flags(HashMap::from([(
+        "f8e-session-registry-enabled".to_string(),
+        "false".to_string(),
+    )]));
+    let bootstrap = gen_services_with_overrides(overrides).await;
+    let client = TestClient::new(bootstrap.router).await;
+
+    let customer_account = create_account(&bootstrap.services, Network::BitcoinSignet, None).await;
+    let tc_account = create_beneficiary_account(&bootstrap.services).await;
+
+    let Account::Full(mut old_account) = bootstrap
+        .services
+        .account_repository
+        .fetch(&customer_account.id)
+        .await
+        .unwrap()
+    else {
+        panic!("Expected a full account");
+    };
+    old_account.common_fields.created_at = OffsetDateTime::now_utc() - Duration::days(365);
+    bootstrap
+        .services
+        .account_repository
+        .persist(&Account::Full(old_account))
+        .await
+        .unwrap();
+
+    if vector.customer_authenticated {
+        let (_, hw_pubkey, _) = get_static_test_authkeys();
+        let auth_response = client
+            .authenticate(&AuthenticationRequest {
+                auth_request_key: AuthRequestKey::HwPubkey(hw_pubkey),
+            })
+            .await;
+        assert_eq!(
+            auth_response.status_code,
+            StatusCode::OK,
+            "{}",
+            auth_response.body_string
+        );
+        let auth_body = auth_response.body.unwrap();
+        let tokens_response = client
+            .get_tokens(&GetTokensRequest {
+                challenge: Some(ChallengeResponseParameters {
+                    username: auth_body.username,
+                    challenge_response: authn_authz::test_utils::sign_with_hw_key(
+                        &auth_body.challenge,
+                    ),
+                    session: auth_body.session,
+                }),
+                refresh_token: None,
+            })
+            .await;
+        assert_eq!(
+            tokens_response.status_code,
+            StatusCode::OK,
+            "{}",
+            tokens_response.body_string
+        );
+    }
+
+    let recovery_relationship_id =
+        setup_recovery_relationship(&client, &customer_account.id, &tc_account.id, true).await;
+    try_upload_inheritance_package(
+        &client,
+        &customer_account.id,
+        &recovery_relationship_id,
+        30,
+        &CUSTOMER_AUTH,
+        StatusCode::OK,
+    )
+    .await;
+
+    try_start_inheritance_claim(
+        &client,
+        &tc_account.id,
+        &recovery_relationship_id,
+        vector.expected_status_code,
+    )
+    .await;
+}
+
+tests! {
+    runner = start_inheritance_claim_for_old_account_test,
+    test_start_inheritance_claim_for_old_account_with_recent_sign_in: StartInheritanceClaimForOldAccountTestVector {
+        customer_authenticated: true,
+        expected_status_code: StatusCode::CONFLICT,
+    },
+    test_start_inheritance_claim_for_old_inactive_account: StartInheritanceClaimForOldAccountTestVector {
+        customer_authenticated: false,
+        expected_status_code: StatusCode::OK,
+    },
+}
+
+#[derive(Debug)]
+struct CancelInheritanceClaimTestVector {
+    canceled_by_customer: bool,
+}
+
+async fn cancel_inheritance_claim_test(vector: CancelInheritanceClaimTestVector) {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+
+    let customer_account = create_account(&bootstrap.services, Network::BitcoinSignet, None).await;
+    let tc_account = create_beneficiary_account(&bootstrap.services).await;
+
+    let recovery_relationship_id =
+        setup_recovery_relationship(&client, &customer_account.id, &tc_account.id, true).await;
+    try_upload_inheritance_package(
+        &client,
+        &customer_account.id,
+        &recovery_relationship_id,
+        0,
+        &CUSTOMER_AUTH,
+        StatusCode::OK,
+    )
+    .await;
+    let start_body = try_start_inheritance_claim(
+        &client,
+        &tc_account.id,
+        &recovery_relationship_id,
+        StatusCode::OK,
+    )
+    .await
+    .unwrap();
+
+    let (acting_account_id, auth) = if vector.canceled_by_customer {
+        (&customer_account.id, CUSTOMER_AUTH)
+    } else {
+        (&tc_account.id, CognitoAuthentication::Recovery)
+    };
+
+    let cancel_response = client
+        .cancel_inheritance_claim(
+            &acting_account_id.to_string(),
+            &start_body.claim.id.to_string(),
+            &auth,
+        )
+        .await;
+    assert_eq!(
+        cancel_response.status_code,
+        StatusCode::OK,
+        "{:?}",
+        cancel_response.body_string
+    );
+    assert_eq!(
+        cancel_response.body.unwrap().claim.status,
+        InheritanceClaimStatus::Canceled
+    );
+
+    // A claim can only be canceled while it's pending
+    let cancel_response = client
+        .cancel_inheritance_claim(
+            &acting_account_id.to_string(),
+            &start_body.claim.id.to_string(),
+            &auth,
+        )
+        .await;
+    assert_eq!(
+        cancel_response.status_code,
+        StatusCode::CONFLICT,
+        "{:?}",
+        cancel_response.body_string
+    );
+
+    // Once canceled, the beneficiary can start a new claim
+    try_start_inheritance_claim(
+        &client,
+        &tc_account.id,
+        &recovery_relationship_id,
+        StatusCode::OK,
+    )
+    .await;
+}
+
+tests! {
+    runner = cancel_inheritance_claim_test,
+    test_cancel_inheritance_claim_by_customer: CancelInheritanceClaimTestVector {
+        canceled_by_customer: true,
+    },
+    test_cancel_inheritance_claim_by_beneficiary: CancelInheritanceClaimTestVector {
+        canceled_by_customer: false,
+    },
+}
+
+#[tokio::test]
+async fn test_complete_inheritance_claim() {
+    let mut broadcaster_mock = MockTransactionBroadcaster::new();
+    broadcaster_mock
+        .expect_broadcast()
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    let overrides = GenServiceOverrides::new().broadcaster(Arc::new(broadcaster_mock));
+    let bootstrap = gen_services_with_overrides(overrides).await;
+    let client = TestClient::new(bootstrap.router).await;
+
+    let (customer_account, customer_wallet) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+    let beneficiary_account = create_beneficiary_account(&bootstrap.services).await;
+
+    let recovery_relationship_id =
+        setup_recovery_relationship(&client, &customer_account.id, &beneficiary_account.id, true)
+            .await;
+    try_upload_inheritance_package(
+        &client,
+        &customer_account.id,
+        &recovery_relationship_id,
+        0,
+        &CUSTOMER_AUTH,
+        StatusCode::OK,
+    )
+    .await;
+    let start_body = try_start_inheritance_claim(
+        &client,
+        &beneficiary_account.id,
+        &recovery_relationship_id,
+        StatusCode::OK,
+    )
+    .await
+    .unwrap();
+
+    let beneficiary_descriptor: DescriptorKeyset = beneficiary_account
+        .active_spending_keyset()
+        .unwrap()
+        .to_owned()
+        .into();
+    let beneficiary_wallet = beneficiary_descriptor
+        .generate_wallet(false, &default_electrum_rpc_uris())
+        .unwrap();
+    let sweep_psbt = build_sweep_transaction(
+        &customer_wallet,
+        beneficiary_wallet
+            .get_address(AddressIndex::Peek(0))
+            .unwrap(),
+    );
+    let request = CompleteInheritanceClaimRequest {
+        psbt: sweep_psbt.to_string(),
+    };
+    let claim_id = start_body.claim.id.to_string();
+
+    // The sweep can't happen until the delay period has finished
+    let complete_response = client
+        .complete_inheritance_claim(
+            &beneficiary_account.id.to_string(),
+            &claim_id,
+            &request,
+            &CUSTOMER_AUTH,
+        )
+        .await;
+    assert_eq!(
+        complete_response.status_code,
+        StatusCode::BAD_REQUEST,
+        "{:?}",
+        complete_response.body_string
+    );
+
+    let repository = &bootstrap.services.social_recovery_repository;
+    let mut claim = repository
+        .fetch_inheritance_claim(&start_body.claim.id)
+        .await
+        .unwrap();
+    claim.delay_end_time = OffsetDateTime::now_utc() - Duration::seconds(1);
+    repository.persist_inheritance_claim(&claim).await.unwrap();
+
+    let complete_response = client
+        .complete_inheritance_claim(
+            &beneficiary_account.id.to_string(),
+            &claim_id,
+            &request,
+            &CUSTOMER_AUTH,
+        )
+        .await;
+    assert_eq!(
+        complete_response.status_code,
+        StatusCode::OK,
+        "{:?}",
+        complete_response.body_string
+    );
+    let complete_body = complete_response.body.unwrap();
+    assert_eq!(
+        complete_body.claim.status,
+        InheritanceClaimStatus::Completed
+    );
+
+    // The server cosigned the sweep with the customer's active keyset
+    let mut signed_psbt = Psbt::from_str(&complete_body.psbt).unwrap();
+    assert_eq!(
+        complete_body.claim.sweep_txid,
+        Some(signed_psbt.unsigned_tx.txid().to_string())
+    );
+    assert!(customer_wallet
+        .finalize_psbt(&mut signed_psbt, SignOptions::default())
+        .unwrap());
+
+    // A claim can only be completed once
+    let complete_response = client
+        .complete_inheritance_claim(
+            &beneficiary_account.id.to_string(),
+            &claim_id,
+            &request,
+            &CUSTOMER_AUTH,
+        )
+        .await;
+    assert_eq!(
+        complete_response.status_code,
+        StatusCode::CONFLICT,
+        "{:?}",
+        complete_response.body_string
+    );
+}
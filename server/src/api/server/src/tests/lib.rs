@@ -422,6 +422,7 @@ pub(crate) async fn create_push_touchpoint(
             platform: TouchpointPlatform::ApnsTeam,
             device_token: "test-device-token".to_owned(),
             access_token: Default::default(),
+            session_id: None,
         })
         .await
         .unwrap();
@@ -4,15 +4,18 @@ use ulid::Ulid;
 
 use account::service::{FetchAccountInput, FetchAndUpdateSpendingLimitInput};
 use account::spend_limit::{Money, SpendingLimit};
+use account::spend_policy::{SpendPeriod, SpendPolicyRule};
 
 use external_identifier::ExternalIdentifier;
-use mobile_pay::routes::{MobilePaySetupRequest, MobilePaySetupResponse};
+use mobile_pay::routes::{MobilePaySetupRequest, MobilePaySetupResponse, SpendPolicyRequest};
 use types::account::identifiers::AccountId;
 use types::currencies::CurrencyCode::BTC;
 
 use crate::tests;
 use crate::tests::gen_services;
-use crate::tests::lib::create_default_account_with_predefined_wallet;
+use crate::tests::lib::{
+    create_default_account_with_predefined_wallet, gen_external_wallet_address,
+};
 
 use super::requests::axum::TestClient;
 
@@ -227,6 +230,81 @@ async fn mobile_pay_disable() {
     assert_eq!(account.spending_limit, Some(disabled_spend_limit));
 }
 
+#[tokio::test]
+async fn spend_policy_is_persisted_on_account() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, _) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+    assert!(account.spend_policy.is_empty());
+
+    let rules = vec![
+        SpendPolicyRule::PeriodCap {
+            period: SpendPeriod::Weekly,
+            limit: Money {
+                amount: 500_000,
+                currency_code: BTC,
+            },
+        },
+        SpendPolicyRule::AllowlistedDestinations {
+            addresses: vec![gen_external_wallet_address().to_string()],
+        },
+        SpendPolicyRule::Velocity {
+            period: SpendPeriod::Daily,
+            max_transactions: 5,
+        },
+    ];
+    let response = client
+        .put_spend_policy(
+            &account.id,
+            &SpendPolicyRequest {
+                rules: rules.clone(),
+            },
+        )
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+
+    let response = client.get_spend_policy(&account.id).await;
+    assert_eq!(response.status_code, StatusCode::OK);
+    assert_eq!(response.body.unwrap().rules, rules);
+
+    let account = bootstrap
+        .services
+        .account_service
+        .fetch_full_account(FetchAccountInput {
+            account_id: &account.id,
+        })
+        .await
+        .unwrap();
+    assert_eq!(account.spend_policy, rules);
+}
+
+#[tokio::test]
+async fn spend_policy_rejects_allowlisted_address_for_other_network() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, _) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+
+    let request = SpendPolicyRequest {
+        rules: vec![SpendPolicyRule::AllowlistedDestinations {
+            addresses: vec!["bc1qvh30c5k24q4z2h6e88tvsv7x3xyj7m4g37e498".to_string()],
+        }],
+    };
+    let response = client.put_spend_policy(&account.id, &request).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::BAD_REQUEST,
+        "{}",
+        response.body_string
+    );
+}
+
 pub(crate) fn build_mobile_pay_request(limit: SpendingLimit) -> MobilePaySetupRequest {
     MobilePaySetupRequest { limit }
 }
@@ -238,7 +316,8 @@ mod get_mobile_pay_tests {
     use http::StatusCode;
     use time::{OffsetDateTime, UtcOffset};
 
-    use account::spend_limit::{Money, SpendingLimit};
+    use account::spend_limit::{Money, SpendWindow, SpendingLimit};
+    use account::spend_policy::{SpendPeriod, SpendPolicyRule};
     use bdk_utils::bdk::bitcoin::absolute::LockTime;
     use bdk_utils::bdk::bitcoin::psbt::Psbt;
     use bdk_utils::bdk::bitcoin::{Address, ScriptBuf, Transaction, TxOut};
@@ -246,7 +325,7 @@ mod get_mobile_pay_tests {
     use bdk_utils::error::BdkUtilError;
     use bdk_utils::{AttributableWallet, SpkWithDerivationPaths};
     use mobile_pay::daily_spend_record::entities::DailySpendingRecord;
-    use mobile_pay::routes::MobilePaySetupResponse;
+    use mobile_pay::routes::{AllowancePeriod, MobilePaySetupResponse, SpendPolicyRequest};
     use types::currencies::CurrencyCode::{BTC, EUR, USD};
     use types::exchange_rate::local_rate_provider::LOCAL_ONE_BTC_IN_FIAT;
 
@@ -300,6 +379,7 @@ mod get_mobile_pay_tests {
             currency_code: BTC,
         },
         time_zone_offset: UtcOffset::UTC,
+        window: SpendWindow::Calendar,
     };
     const USD_SPENDING_LIMIT: SpendingLimit = SpendingLimit {
         active: true,
@@ -308,6 +388,7 @@ mod get_mobile_pay_tests {
             currency_code: USD,
         },
         time_zone_offset: UtcOffset::UTC,
+        window: SpendWindow::Calendar,
     };
 
     tests! {
@@ -443,6 +524,7 @@ mod get_mobile_pay_tests {
                 currency_code: BTC,
             },
             time_zone_offset: UtcOffset::UTC,
+            window: SpendWindow::Calendar,
         };
         let setup_mobile_pay_request = build_mobile_pay_request(limit.clone());
         let mobile_pay_setup_response = client
@@ -459,6 +541,118 @@ mod get_mobile_pay_tests {
         assert_eq!(mobile_pay_config.limit, limit);
     }
 
+    #[tokio::test]
+    async fn test_get_mobile_pay_allowances_with_spend_policy() {
+        let payee_script_pubkey = Address::from_str("bc1qvh30c5k24q4z2h6e88tvsv7x3xyj7m4g37e498")
+            .unwrap()
+            .assume_checked()
+            .script_pubkey();
+        let change_script_pubkey =
+            Address::from_str("bc1qwqdg6squsna38e46795at95yu9atm8azzmyvckulcc7kytlcckxswvvzej")
+                .unwrap()
+                .assume_checked()
+                .script_pubkey();
+
+        let bootstrap = gen_services().await;
+        let client = TestClient::new(bootstrap.router).await;
+        let (account, _) =
+            create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+
+        // Set up mobile pay with a rolling daily limit, and tighter weekly and per-transaction caps.
+        let limit = SpendingLimit {
+            window: SpendWindow::Rolling,
+            ..BTC_SPENDING_LIMIT
+        };
+        let setup_mobile_pay_request = build_mobile_pay_request(limit.clone());
+        let mobile_pay_setup_response = client
+            .put_mobile_pay(&account.id, &setup_mobile_pay_request)
+            .await;
+        assert_eq!(mobile_pay_setup_response.status_code, StatusCode::OK);
+
+        let spend_policy_response = client
+            .put_spend_policy(
+                &account.id,
+                &SpendPolicyRequest {
+                    rules: vec![
+                        SpendPolicyRule::PeriodCap {
+                            period: SpendPeriod::Weekly,
+                            limit: Money {
+                                amount: 900_000,
+                                currency_code: BTC,
+                            },
+                        },
+                        SpendPolicyRule::PerTransactionCap {
+                            limit: Money {
+                                amount: 500_000,
+                                currency_code: BTC,
+                            },
+                        },
+                    ],
+                },
+            )
+            .await;
+        assert_eq!(spend_policy_response.status_code, StatusCode::OK);
+
+        // Fake spending 800_000 sats cosigned transaction
+        let mut spending_record =
+            DailySpendingRecord::try_new(&account.id, OffsetDateTime::now_utc().date()).unwrap();
+        let psbt = Psbt::from_unsigned_tx(Transaction {
+            version: 0,
+            lock_time: LockTime::ZERO,
+            input: Vec::new(),
+            output: vec![
+                // payee output
+                TxOut {
+                    value: 800_000,
+                    script_pubkey: payee_script_pubkey,
+                },
+                // change output
+                TxOut {
+                    value: 100,
+                    script_pubkey: change_script_pubkey.clone(),
+                },
+            ],
+        })
+        .unwrap();
+        spending_record
+            .update_with_psbt(&DummyWallet::new(vec![change_script_pubkey.clone()]), &psbt);
+        let _ = bootstrap
+            .services
+            .daily_spend_record_service
+            .save_daily_spending_record(spending_record)
+            .await;
+
+        let get_mobile_pay_response = client.get_mobile_pay(&account.id).await;
+        let resp_body = get_mobile_pay_response.body.unwrap();
+        let mobile_pay_config = resp_body.mobile_pay().unwrap();
+        assert_eq!(mobile_pay_config.limit, limit);
+        assert_eq!(mobile_pay_config.spent.amount, 800_000);
+        // The weekly cap leaves less to spend than the daily limit.
+        assert_eq!(mobile_pay_config.available.amount, 100_000);
+        assert_eq!(
+            mobile_pay_config
+                .allowances
+                .iter()
+                .map(|allowance| (
+                    allowance.period,
+                    allowance.limit.amount,
+                    allowance.spent.amount,
+                    allowance.available.amount
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    AllowancePeriod::Daily,
+                    SPENDING_LIMIT_SATS,
+                    800_000,
+                    SPENDING_LIMIT_SATS - 800_000
+                ),
+                (AllowancePeriod::Weekly, 900_000, 800_000, 100_000),
+                (AllowancePeriod::PerTransaction, 500_000, 0, 500_000),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn changing_mobile_pay_currencies_preserves_balance() {
         let payee_script_pubkey = Address::from_str("bc1qvh30c5k24q4z2h6e88tvsv7x3xyj7m4g37e498")
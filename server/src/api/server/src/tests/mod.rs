@@ -8,6 +8,7 @@ mod blockchain_polling_integration_tests;
 mod cloud_recovery_integration_tests;
 mod currency_exchange_integration_tests;
 mod exchange_rate_integration_tests;
+mod inheritance_integration_tests;
 mod lib;
 mod mobile_pay_tests;
 mod notification_integration_tests;
@@ -17,7 +18,9 @@ mod recovery_relationship_integration_tests;
 mod register_watch_address_integration_tests;
 mod requests;
 mod scheduled_notifications_integration_tests;
+mod security_event_integration_tests;
 mod send_customer_notifications_integration_tests;
+mod session_integration_tests;
 mod social_challenge_integration_tests;
 mod transaction_integration_tests;
 
@@ -1,4 +1,5 @@
 use account::entities::{Factor, FullAccountAuthKeys, FullAccountAuthKeysPayload, Network};
+use account::recovery_policy::RecoveryPolicy;
 use account::service::FetchAccountInput;
 use http_body_util::BodyExt;
 use types::account::identifiers::AccountId;
@@ -12,9 +13,9 @@ use notification::NotificationPayloadType;
 use recovery::entities::{RecoveryDestination, RecoveryStatus, RecoveryType};
 use recovery::error::RecoveryError;
 use recovery::routes::{
-    CompleteDelayNotifyRequest, CreateAccountDelayNotifyRequest,
+    CompleteDelayNotifyRequest, CreateAccountDelayNotifyRequest, RecoveryPolicyResponse,
     SendAccountVerificationCodeRequest, UpdateDelayForTestRecoveryRequest,
-    VerifyAccountVerificationCodeRequest,
+    UpdateRecoveryPolicyRequest, VerifyAccountVerificationCodeRequest,
 };
 
 use time::{Duration, OffsetDateTime};
@@ -1568,3 +1569,132 @@ tests! {
         expected_error: Some(RecoveryError::RecoveryAuthPubkeyReuseRecovery.into()),
     },
 }
+
+#[tokio::test]
+async fn recovery_policy_change_waits_out_the_current_delay() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let account = create_account(&bootstrap.services, Network::BitcoinMain, None).await;
+    let now = bootstrap.services.recovery_service.cur_time();
+
+    let response = client.get_recovery_policy(&account.id).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    let body = response.body.unwrap();
+    assert_eq!(body.policy, RecoveryPolicy::default());
+    assert_eq!(body.pending_policy, None);
+
+    let policy = RecoveryPolicy {
+        delay_period_days: 14,
+        contest_lookback_days: 60,
+    };
+    let request = UpdateRecoveryPolicyRequest { policy };
+    let response = client
+        .update_recovery_policy(&account.id, &request, true, false)
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::FORBIDDEN,
+        "{}",
+        response.body_string
+    );
+
+    let out_of_bounds = UpdateRecoveryPolicyRequest {
+        policy: RecoveryPolicy {
+            delay_period_days: 1,
+            ..policy
+        },
+    };
+    let response = client
+        .update_recovery_policy(&account.id, &out_of_bounds, true, true)
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::BAD_REQUEST,
+        "{}",
+        response.body_string
+    );
+
+    // The change waits out the 7-day delay of the policy it replaces
+    let response = client
+        .update_recovery_policy(&account.id, &request, true, true)
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    let body = response.body.unwrap();
+    assert_eq!(body.policy, RecoveryPolicy::default());
+    let pending = body.pending_policy.unwrap();
+    assert_eq!(pending.policy, policy);
+    assert_eq!(pending.effective_at, now + Duration::days(7));
+
+    let account = bootstrap
+        .services
+        .account_service
+        .fetch_full_account(FetchAccountInput {
+            account_id: &account.id,
+        })
+        .await
+        .unwrap();
+    assert_eq!(account.recovery_policy_at(now), RecoveryPolicy::default());
+    assert_eq!(account.recovery_policy_at(pending.effective_at), policy);
+
+    // Asking for the policy in effect cancels the pending change
+    let response = client
+        .update_recovery_policy(
+            &account.id,
+            &UpdateRecoveryPolicyRequest {
+                policy: RecoveryPolicy::default(),
+            },
+            true,
+            true,
+        )
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    assert_eq!(response.body.unwrap().pending_policy, None);
+}
+
+#[tokio::test]
+async fn recovery_policy_change_applies_immediately_for_test_accounts() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let account = create_account(&bootstrap.services, Network::BitcoinSignet, None).await;
+
+    let policy = RecoveryPolicy {
+        delay_period_days: 3,
+        contest_lookback_days: 90,
+    };
+    let response = client
+        .update_recovery_policy(
+            &account.id,
+            &UpdateRecoveryPolicyRequest { policy },
+            true,
+            true,
+        )
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    assert_eq!(
+        response.body.unwrap(),
+        RecoveryPolicyResponse {
+            policy,
+            pending_policy: None,
+        }
+    );
+}
@@ -18,11 +18,12 @@ use authn_authz::routes::{
     AuthenticateWithHardwareRequest, AuthenticateWithHardwareResponse,
     AuthenticateWithRecoveryAuthkeyRequest, AuthenticateWithRecoveryResponse,
     AuthenticationRequest, AuthenticationResponse, GetTokensRequest, GetTokensResponse,
+    ListSessionsResponse,
 };
 use exchange_rate::routes::SupportedFiatCurrenciesResponse;
 use mobile_pay::routes::{
     GetMobilePayResponse, MobilePaySetupRequest, MobilePaySetupResponse, SignTransactionData,
-    SignTransactionResponse,
+    SignTransactionResponse, SpendPolicyRequest, SpendPolicyResponse,
 };
 use notification::routes::{
     RegisterWatchAddressRequest, RegisterWatchAddressResponse, SendTestPushData,
@@ -35,20 +36,24 @@ use onboarding::routes::{
     AccountVerifyTouchpointResponse, BdkConfigResponse, CompleteOnboardingRequest,
     CompleteOnboardingResponse, CreateAccountRequest, CreateAccountResponse, CreateKeysetRequest,
     CreateKeysetResponse, GetAccountKeysetsResponse, GetAccountStatusResponse,
-    RotateSpendingKeysetRequest, UpgradeAccountRequest,
+    ListSecurityEventsResponse, RotateSpendingKeysetRequest, UpgradeAccountRequest,
 };
 use types::account::identifiers::{AccountId, KeysetId};
 
 use recovery::routes::{
-    CompleteDelayNotifyRequest, CreateAccountDelayNotifyRequest, CreateRecoveryRelationshipRequest,
-    CreateRecoveryRelationshipResponse, EndorseRecoveryRelationshipsRequest,
-    EndorseRecoveryRelationshipsResponse, FetchSocialChallengeResponse,
+    CancelInheritanceClaimResponse, CompleteDelayNotifyRequest, CompleteInheritanceClaimRequest,
+    CompleteInheritanceClaimResponse, CreateAccountDelayNotifyRequest,
+    CreateRecoveryRelationshipRequest, CreateRecoveryRelationshipResponse,
+    EndorseRecoveryRelationshipsRequest, EndorseRecoveryRelationshipsResponse,
+    FetchInheritanceClaimResponse, FetchSocialChallengeResponse, GetInheritanceResponse,
     GetRecoveryRelationshipInvitationForCodeResponse, GetRecoveryRelationshipsResponse,
-    RespondToSocialChallengeRequest, RespondToSocialChallengeResponse,
+    RecoveryPolicyResponse, RespondToSocialChallengeRequest, RespondToSocialChallengeResponse,
     RotateAuthenticationKeysRequest, RotateAuthenticationKeysResponse,
     SendAccountVerificationCodeRequest, SendAccountVerificationCodeResponse,
-    StartSocialChallengeRequest, StartSocialChallengeResponse, UpdateDelayForTestRecoveryRequest,
+    StartInheritanceClaimRequest, StartInheritanceClaimResponse, StartSocialChallengeRequest,
+    StartSocialChallengeResponse, UpdateDelayForTestRecoveryRequest, UpdateRecoveryPolicyRequest,
     UpdateRecoveryRelationshipRequest, UpdateRecoveryRelationshipResponse,
+    UploadInheritancePackageRequest, UploadInheritancePackageResponse,
     VerifyAccountVerificationCodeRequest, VerifyAccountVerificationCodeResponse,
     VerifySocialChallengeRequest, VerifySocialChallengeResponse,
 };
@@ -214,6 +219,51 @@ impl TestClient {
             .await
     }
 
+    pub(crate) async fn get_sessions(&self, account_id: &str) -> Response<ListSessionsResponse> {
+        Request::builder()
+            .uri(format!("/api/accounts/{account_id}/sessions"))
+            .authenticated(&AccountId::from_str(account_id).unwrap(), false, false)
+            .get()
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn revoke_session(&self, account_id: &str, session_id: &str) -> Response<()> {
+        Request::builder()
+            .uri(format!("/api/accounts/{account_id}/sessions/{session_id}"))
+            .authenticated(&AccountId::from_str(account_id).unwrap(), false, false)
+            .delete()
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn revoke_all_sessions(&self, account_id: &str) -> Response<()> {
+        Request::builder()
+            .uri(format!("/api/accounts/{account_id}/sessions"))
+            .authenticated(&AccountId::from_str(account_id).unwrap(), false, false)
+            .delete()
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn get_security_events(
+        &self,
+        account_id: &AccountId,
+        limit: i32,
+        page_token: Option<&str>,
+    ) -> Response<ListSecurityEventsResponse> {
+        let mut uri = format!("/api/accounts/{account_id}/security-events?limit={limit}");
+        if let Some(page_token) = page_token {
+            uri.push_str(&format!("&page_token={page_token}"));
+        }
+        Request::builder()
+            .uri(uri)
+            .authenticated(account_id, false, false)
+            .get()
+            .call(&self.router)
+            .await
+    }
+
     pub(crate) async fn add_touchpoint(
         &self,
         account_id: &str,
@@ -333,6 +383,35 @@ impl TestClient {
             .await
     }
 
+    pub(crate) async fn put_spend_policy(
+        &self,
+        account_id: &AccountId,
+        request: &SpendPolicyRequest,
+    ) -> Response<SpendPolicyResponse> {
+        Request::builder()
+            .uri(format!(
+                "/api/accounts/{account_id}/mobile-pay/spend-policy"
+            ))
+            .authenticated(account_id, true, true)
+            .put(request)
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn get_spend_policy(
+        &self,
+        account_id: &AccountId,
+    ) -> Response<SpendPolicyResponse> {
+        Request::builder()
+            .uri(format!(
+                "/api/accounts/{account_id}/mobile-pay/spend-policy"
+            ))
+            .authenticated(account_id, true, false)
+            .get()
+            .call(&self.router)
+            .await
+    }
+
     pub(crate) async fn create_delay_notify_recovery(
         &self,
         account_id: &str,
@@ -371,6 +450,33 @@ impl TestClient {
             .await
     }
 
+    pub(crate) async fn update_recovery_policy(
+        &self,
+        account_id: &AccountId,
+        request: &UpdateRecoveryPolicyRequest,
+        app_signed: bool,
+        hw_signed: bool,
+    ) -> Response<RecoveryPolicyResponse> {
+        Request::builder()
+            .uri(format!("/api/accounts/{account_id}/recovery/policy"))
+            .authenticated(account_id, app_signed, hw_signed)
+            .put(request)
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn get_recovery_policy(
+        &self,
+        account_id: &AccountId,
+    ) -> Response<RecoveryPolicyResponse> {
+        Request::builder()
+            .uri(format!("/api/accounts/{account_id}/recovery/policy"))
+            .authenticated(account_id, false, false)
+            .get()
+            .call(&self.router)
+            .await
+    }
+
     pub(crate) async fn get_recovery_status(&self, account_id: &str) -> Response<RecoveryResponse> {
         Request::builder()
             .uri(format!("/api/accounts/{account_id}/recovery"))
@@ -853,6 +959,102 @@ impl TestClient {
             .await
     }
 
+    pub(crate) async fn upload_inheritance_package(
+        &self,
+        account_id: &str,
+        request: &UploadInheritancePackageRequest,
+        auth: &CognitoAuthentication,
+    ) -> Response<UploadInheritancePackageResponse> {
+        Request::builder()
+            .uri(format!(
+                "/api/accounts/{account_id}/recovery/inheritance/packages"
+            ))
+            .with_authentication(auth, &AccountId::from_str(account_id).unwrap())
+            .put(&request)
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn get_inheritance(
+        &self,
+        account_id: &str,
+        auth: &CognitoAuthentication,
+    ) -> Response<GetInheritanceResponse> {
+        Request::builder()
+            .uri(format!("/api/accounts/{account_id}/recovery/inheritance"))
+            .with_authentication(auth, &AccountId::from_str(account_id).unwrap())
+            .get()
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn start_inheritance_claim(
+        &self,
+        account_id: &str,
+        request: &StartInheritanceClaimRequest,
+        auth: &CognitoAuthentication,
+    ) -> Response<StartInheritanceClaimResponse> {
+        Request::builder()
+            .uri(format!(
+                "/api/accounts/{account_id}/recovery/inheritance/claims"
+            ))
+            .with_authentication(auth, &AccountId::from_str(account_id).unwrap())
+            .post(&request)
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn fetch_inheritance_claim(
+        &self,
+        account_id: &str,
+        inheritance_claim_id: &str,
+        auth: &CognitoAuthentication,
+    ) -> Response<FetchInheritanceClaimResponse> {
+        Request::builder()
+            .uri(format!(
+                "/api/accounts/{account_id}/recovery/inheritance/claims/{inheritance_claim_id}"
+            ))
+            .with_authentication(auth, &AccountId::from_str(account_id).unwrap())
+            .get()
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn cancel_inheritance_claim(
+        &self,
+        account_id: &str,
+        inheritance_claim_id: &str,
+        auth: &CognitoAuthentication,
+    ) -> Response<CancelInheritanceClaimResponse> {
+        Request::builder()
+            .uri(format!(
+                "/api/accounts/{account_id}/recovery/inheritance/claims/{inheritance_claim_id}/cancel"
+            ))
+            .with_authentication(auth, &AccountId::from_str(account_id).unwrap())
+            .method(Method::POST)
+            .body(Body::empty())
+            .unwrap()
+            .call(&self.router)
+            .await
+    }
+
+    pub(crate) async fn complete_inheritance_claim(
+        &self,
+        account_id: &str,
+        inheritance_claim_id: &str,
+        request: &CompleteInheritanceClaimRequest,
+        auth: &CognitoAuthentication,
+    ) -> Response<CompleteInheritanceClaimResponse> {
+        Request::builder()
+            .uri(format!(
+                "/api/accounts/{account_id}/recovery/inheritance/claims/{inheritance_claim_id}/complete"
+            ))
+            .with_authentication(auth, &AccountId::from_str(account_id).unwrap())
+            .post(&request)
+            .call(&self.router)
+            .await
+    }
+
     pub(crate) async fn delete_account(
         &self,
         account_id: &str,
@@ -155,6 +155,7 @@ async fn test_scheduled_handler() {
         notification_service: bootstrap.services.notification_service.clone(),
         account_service: bootstrap.services.account_service.clone(),
         recovery_service: bootstrap.services.recovery_service.clone(),
+        social_recovery_repository: bootstrap.services.social_recovery_repository.clone(),
         chain_indexer_service: bootstrap.services.chain_indexer_service.clone(),
         address_repo: bootstrap.services.address_repo.clone(),
         sqs: bootstrap.services.sqs.clone(),
@@ -178,6 +179,7 @@ async fn test_scheduled_handler() {
             platform: TouchpointPlatform::ApnsTeam,
             device_token: "test".to_string(),
             access_token: Default::default(),
+            session_id: None,
         })
         .await
         .unwrap();
@@ -0,0 +1,124 @@
+use account::spend_limit::{Money, SpendingLimit};
+use http::StatusCode;
+use mobile_pay::routes::SpendPolicyRequest;
+use types::currencies::CurrencyCode::BTC;
+use types::security_event::{SecurityEventStatus, SecurityEventType};
+
+use crate::tests::gen_services;
+use crate::tests::lib::create_default_account_with_predefined_wallet;
+use crate::tests::mobile_pay_tests::build_mobile_pay_request;
+use crate::tests::requests::axum::TestClient;
+
+#[tokio::test]
+async fn test_security_events_are_listed_newest_first_across_pages() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, _) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+
+    let request = build_mobile_pay_request(SpendingLimit {
+        active: true,
+        amount: Money {
+            amount: 50_000,
+            currency_code: BTC,
+        },
+        ..Default::default()
+    });
+    let response = client.put_mobile_pay(&account.id, &request).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    let response = client
+        .put_spend_policy(&account.id, &SpendPolicyRequest { rules: vec![] })
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    let response = client.delete_mobile_pay(&account.id).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+
+    let response = client.get_security_events(&account.id, 4, None).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    let first_page = response.body.unwrap();
+    let events = first_page
+        .events
+        .iter()
+        .map(|e| (e.event.clone(), e.status))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        vec![
+            (
+                SecurityEventType::SpendingLimitDisabled,
+                SecurityEventStatus::Completed
+            ),
+            (
+                SecurityEventType::SpendingLimitDisabled,
+                SecurityEventStatus::Attempted
+            ),
+            (
+                SecurityEventType::SpendPolicyUpdated { rule_count: 0 },
+                SecurityEventStatus::Completed
+            ),
+            (
+                SecurityEventType::SpendPolicyUpdated { rule_count: 0 },
+                SecurityEventStatus::Attempted
+            ),
+        ]
+    );
+
+    let page_token = first_page.next_page_token.expect("there's an older event");
+    let response = client
+        .get_security_events(&account.id, 4, Some(&page_token))
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    let second_page = response.body.unwrap();
+    assert_eq!(
+        second_page.events[0].event,
+        SecurityEventType::SpendingLimitUpdated {
+            amount: 50_000,
+            currency_code: BTC,
+        }
+    );
+    assert_eq!(second_page.events[0].status, SecurityEventStatus::Completed);
+    assert!(second_page.events[0].created_at <= first_page.events[3].created_at);
+}
+
+#[tokio::test]
+async fn test_security_events_page_size_is_bounded() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, _) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+
+    for limit in [0, 101] {
+        let response = client.get_security_events(&account.id, limit, None).await;
+        assert_eq!(
+            response.status_code,
+            StatusCode::BAD_REQUEST,
+            "{}",
+            response.body_string
+        );
+    }
+}
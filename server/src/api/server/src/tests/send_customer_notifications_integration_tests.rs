@@ -47,6 +47,7 @@ async fn send_customer_notifications_test(input: SendCustomerNotificationsTestVe
         notification_service: bootstrap.services.notification_service.clone(),
         account_service: bootstrap.services.account_service.clone(),
         recovery_service: bootstrap.services.recovery_service.clone(),
+        social_recovery_repository: bootstrap.services.social_recovery_repository.clone(),
         chain_indexer_service: bootstrap.services.chain_indexer_service.clone(),
         address_repo: bootstrap.services.address_repo.clone(),
         sqs: bootstrap.services.sqs.clone(),
@@ -0,0 +1,119 @@
+use account::entities::{Touchpoint, TouchpointPlatform};
+use account::service::FetchAccountInput;
+use http::StatusCode;
+use onboarding::routes::AccountAddDeviceTokenRequest;
+
+use crate::tests::gen_services;
+use crate::tests::lib::create_default_account_with_predefined_wallet;
+use crate::tests::requests::axum::TestClient;
+
+// Test access tokens all carry this origin_jti
+const TEST_SESSION_ID: &str = "TEST";
+
+#[tokio::test]
+async fn test_list_sessions() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, _) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+
+    let response = client.get_sessions(&account.id.to_string()).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+    let sessions = response.body.unwrap().sessions;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].session_id, TEST_SESSION_ID);
+    assert!(sessions[0].current);
+}
+
+#[tokio::test]
+async fn test_revoke_session_rejects_requests_and_clears_push_touchpoints() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, _) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+    let account_id = account.id.to_string();
+
+    let response = client
+        .add_device_token(
+            &account_id,
+            &AccountAddDeviceTokenRequest {
+                device_token: "test-device-token".to_string(),
+                platform: TouchpointPlatform::ApnsTeam,
+            },
+        )
+        .await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+
+    let response = client.revoke_session(&account_id, "unknown").await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::NOT_FOUND,
+        "{}",
+        response.body_string
+    );
+
+    let response = client.revoke_session(&account_id, TEST_SESSION_ID).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+
+    let response = client.get_sessions(&account_id).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::UNAUTHORIZED,
+        "{}",
+        response.body_string
+    );
+
+    let account = bootstrap
+        .services
+        .account_service
+        .fetch_full_account(FetchAccountInput {
+            account_id: &account.id,
+        })
+        .await
+        .unwrap();
+    assert!(!account
+        .common_fields
+        .touchpoints
+        .iter()
+        .any(|t| matches!(t, Touchpoint::Push { .. })));
+}
+
+#[tokio::test]
+async fn test_revoke_all_sessions() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, _) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+    let account_id = account.id.to_string();
+
+    let response = client.revoke_all_sessions(&account_id).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+
+    let response = client.get_sessions(&account_id).await;
+    assert_eq!(
+        response.status_code,
+        StatusCode::UNAUTHORIZED,
+        "{}",
+        response.body_string
+    );
+}
@@ -1,7 +1,7 @@
 use crate::tests;
 use account::{
     entities::{Account, Network},
-    spend_limit::{Money, SpendingLimit},
+    spend_limit::{Money, SpendWindow, SpendingLimit},
 };
 use http::StatusCode;
 use mobile_pay::routes::MobilePaySetupRequest;
@@ -210,6 +210,7 @@ async fn start_social_challenge_test(vector: StartSocialChallengeTestVector) {
                                 currency_code: CurrencyCode::USD,
                             },
                             time_zone_offset: UtcOffset::UTC,
+                            window: SpendWindow::Calendar,
                         },
                     },
                 )
@@ -7,6 +7,7 @@ use types::account::identifiers::{AccountId, KeysetId};
 
 use account::service::FetchAccountInput;
 use account::spend_limit::{Money, SpendingLimit};
+use account::spend_policy::SpendPolicyRule;
 use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction as Psbt;
 use bdk_utils::bdk::wallet::{AddressIndex, AddressInfo};
 use bdk_utils::bdk::{KeychainKind, SignOptions, Wallet};
@@ -14,8 +15,13 @@ use bdk_utils::{DescriptorKeyset, ElectrumRpcUris, TransactionBroadcasterTrait};
 use external_identifier::ExternalIdentifier;
 use http::StatusCode;
 
+use async_trait::async_trait;
+use mobile_pay::fee_estimate::{
+    FeeEstimateError, FeeEstimatorTrait, LocalFeeEstimator, RecommendedFees,
+};
 use mobile_pay::routes::SignTransactionData;
 use mobile_pay::routes::SignTransactionResponse;
+use mobile_pay::routes::SpendPolicyRequest;
 use onboarding::routes::RotateSpendingKeysetRequest;
 use ulid::Ulid;
 
@@ -33,7 +39,7 @@ use bdk_utils::bdk::bitcoin::psbt::PartiallySignedTransaction;
 use bdk_utils::bdk::database::AnyDatabase;
 use errors::ApiError;
 
-use bdk_utils::bdk::bitcoin::Address;
+use bdk_utils::bdk::bitcoin::{Address, Network as BitcoinNetwork};
 use mockall::mock;
 use types::currencies::CurrencyCode::{BTC, USD};
 
@@ -47,7 +53,7 @@ struct SignTransactionTestVector {
 }
 
 mock! {
-    TransactionBroadcaster { }
+    pub(crate) TransactionBroadcaster { }
     impl TransactionBroadcasterTrait for TransactionBroadcaster {
         fn broadcast(
             &self,
@@ -554,6 +560,72 @@ async fn test_mismatched_account_id_keyproof() {
     );
 }
 
+#[tokio::test]
+async fn test_fail_sends_that_violate_spend_policy() {
+    let bootstrap = gen_services().await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, bdk_wallet) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+
+    let limit = SpendingLimit {
+        active: true,
+        amount: Money {
+            amount: 5_000,
+            currency_code: USD,
+        },
+        ..Default::default()
+    };
+    let request = build_mobile_pay_request(limit);
+    let mobile_pay_response = client.put_mobile_pay(&account.id, &request).await;
+    assert_eq!(
+        mobile_pay_response.status_code,
+        StatusCode::OK,
+        "{}",
+        mobile_pay_response.body_string
+    );
+
+    // The daily limit allows the spend, but the per-transaction cap doesn't.
+    let spend_policy_response = client
+        .put_spend_policy(
+            &account.id,
+            &SpendPolicyRequest {
+                rules: vec![SpendPolicyRule::PerTransactionCap {
+                    limit: Money {
+                        amount: 1_999,
+                        currency_code: BTC,
+                    },
+                }],
+            },
+        )
+        .await;
+    assert_eq!(
+        spend_policy_response.status_code,
+        StatusCode::OK,
+        "{}",
+        spend_policy_response.body_string
+    );
+
+    let app_signed_psbt =
+        build_transaction_with_amount(&bdk_wallet, gen_external_wallet_address(), 2_000);
+    let request_data = SignTransactionData {
+        psbt: app_signed_psbt.to_string(),
+    };
+    let response = client
+        .sign_transaction_with_keyset(&account.id, &account.active_keyset_id, &request_data)
+        .await;
+
+    assert_eq!(
+        response.status_code,
+        StatusCode::BAD_REQUEST,
+        "{}",
+        response.body_string
+    );
+    assert_eq!(
+        response.body_string,
+        r#"{"errors":[{"category":"INVALID_REQUEST_ERROR","code":"PER_TRANSACTION_LIMIT_EXCEEDED","detail":"Transaction spend total of 2000 exceeds per-transaction limit of 1999."}]}"#
+    );
+}
+
 #[tokio::test]
 async fn test_fail_sends_to_sanctioned_address() {
     let blocked_address_info = AddressInfo {
@@ -609,6 +681,134 @@ async fn test_fail_sends_to_sanctioned_address() {
     );
 }
 
+#[tokio::test]
+async fn test_fail_sends_with_fee_rate_far_above_estimate() {
+    // Transactions are built at 5 sat/vB, more than three times the recommended fee rate.
+    let fee_estimator = LocalFeeEstimator::with_fees(RecommendedFees {
+        fastest_fee: 1.0,
+        half_hour_fee: 1.0,
+        hour_fee: 1.0,
+        economy_fee: 1.0,
+        minimum_fee: 1.0,
+    });
+    let overrides = GenServiceOverrides::new().fee_estimator(Arc::new(fee_estimator));
+
+    let bootstrap = gen_services_with_overrides(overrides).await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, bdk_wallet) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+
+    let limit = SpendingLimit {
+        active: true,
+        amount: Money {
+            amount: 5_000,
+            currency_code: USD,
+        },
+        ..Default::default()
+    };
+
+    // Setup Mobile Pay
+    let request = build_mobile_pay_request(limit);
+    let mobile_pay_response = client.put_mobile_pay(&account.id, &request).await;
+    assert_eq!(
+        mobile_pay_response.status_code,
+        StatusCode::OK,
+        "{}",
+        mobile_pay_response.body_string
+    );
+
+    let app_signed_psbt =
+        build_transaction_with_amount(&bdk_wallet, gen_external_wallet_address(), 2_000);
+
+    let request_data = SignTransactionData {
+        psbt: app_signed_psbt.to_string(),
+    };
+
+    let response = client
+        .sign_transaction_with_keyset(&account.id, &account.active_keyset_id, &request_data)
+        .await;
+
+    assert_eq!(
+        response.status_code,
+        StatusCode::BAD_REQUEST,
+        "{}",
+        response.body_string
+    );
+    assert!(
+        response.body_string.contains("FEE_RATE_TOO_HIGH"),
+        "{}",
+        response.body_string
+    );
+}
+
+// Fails like mempool.space being unreachable would
+struct UnavailableFeeEstimator;
+
+#[async_trait]
+impl FeeEstimatorTrait for UnavailableFeeEstimator {
+    async fn recommended_fees(
+        &self,
+        network: BitcoinNetwork,
+    ) -> Result<RecommendedFees, FeeEstimateError> {
+        Err(FeeEstimateError::UnsupportedNetwork(network))
+    }
+}
+
+#[tokio::test]
+async fn test_send_without_fee_estimate() {
+    let mut broadcaster_mock = MockTransactionBroadcaster::new();
+    broadcaster_mock
+        .expect_broadcast()
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    let overrides = GenServiceOverrides::new()
+        .fee_estimator(Arc::new(UnavailableFeeEstimator))
+        .broadcaster(Arc::new(broadcaster_mock));
+
+    let bootstrap = gen_services_with_overrides(overrides).await;
+    let client = TestClient::new(bootstrap.router).await;
+    let (account, bdk_wallet) =
+        create_default_account_with_predefined_wallet(&client, &bootstrap.services).await;
+
+    let limit = SpendingLimit {
+        active: true,
+        amount: Money {
+            amount: 5_000,
+            currency_code: USD,
+        },
+        ..Default::default()
+    };
+
+    // Setup Mobile Pay
+    let request = build_mobile_pay_request(limit);
+    let mobile_pay_response = client.put_mobile_pay(&account.id, &request).await;
+    assert_eq!(
+        mobile_pay_response.status_code,
+        StatusCode::OK,
+        "{}",
+        mobile_pay_response.body_string
+    );
+
+    let app_signed_psbt =
+        build_transaction_with_amount(&bdk_wallet, gen_external_wallet_address(), 2_000);
+
+    let request_data = SignTransactionData {
+        psbt: app_signed_psbt.to_string(),
+    };
+
+    // Only the static fee limits are checked without a fee estimate
+    let response = client
+        .sign_transaction_with_keyset(&account.id, &account.active_keyset_id, &request_data)
+        .await;
+
+    assert_eq!(
+        response.status_code,
+        StatusCode::OK,
+        "{}",
+        response.body_string
+    );
+}
+
 #[tokio::test]
 async fn test_fail_to_send_if_kill_switch_is_on() {
     let feature_flag_override =
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use self::cognito::CognitoUsername;
 
 pub mod cognito;
+pub mod request_nonce;
+pub mod session;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AccessTokenClaims {
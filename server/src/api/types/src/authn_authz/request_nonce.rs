@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use time::{serde::timestamp, OffsetDateTime};
+
+/// A request nonce that has already authorized a signed request. Nonces are only recorded until
+/// they would have expired anyway, after which the database deletes them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UsedRequestNonce {
+    #[serde(rename = "partition_key")]
+    pub nonce: String,
+    /// The unix epoch time in seconds at which this record will be deleted from the database
+    #[serde(with = "timestamp")]
+    pub expiring_at: OffsetDateTime,
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use time::{serde::rfc3339, Duration, OffsetDateTime};
+
+use crate::account::identifiers::AccountId;
+
+/// How stale `last_seen_at` may get before a request refreshes it
+pub const LAST_SEEN_RESOLUTION: Duration = Duration::minutes(5);
+
+/// Self-reported details about the device behind a session, taken from request headers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceInfo {
+    pub device_name: Option<String>,
+    pub platform: Option<String>,
+    pub ip_country: Option<String>,
+}
+
+/// A signed-in device. Sessions are keyed by the `origin_jti` of the access token, which stays
+/// the same across refreshes until the device authenticates again.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeviceSession {
+    #[serde(rename = "partition_key")]
+    pub account_id: AccountId,
+    #[serde(rename = "sort_key")]
+    pub session_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_country: Option<String>,
+    /// Push device tokens registered while signed in with this session
+    #[serde(default)]
+    pub push_device_tokens: Vec<String>,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "rfc3339")]
+    pub last_seen_at: OffsetDateTime,
+    #[serde(
+        default,
+        with = "rfc3339::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub revoked_at: Option<OffsetDateTime>,
+}
+
+impl DeviceSession {
+    pub fn new(
+        account_id: AccountId,
+        session_id: String,
+        device: DeviceInfo,
+        now: OffsetDateTime,
+    ) -> Self {
+        Self {
+            account_id,
+            session_id,
+            device_name: device.device_name,
+            platform: device.platform,
+            ip_country: device.ip_country,
+            push_device_tokens: Vec::new(),
+            created_at: now,
+            last_seen_at: now,
+            revoked_at: None,
+        }
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    /// Records a request from this session, returning whether anything changed enough to be
+    /// worth persisting. `last_seen_at` is only tracked to within [`LAST_SEEN_RESOLUTION`] so
+    /// that every request doesn't turn into a write.
+    pub fn record_activity(&mut self, device: DeviceInfo, now: OffsetDateTime) -> bool {
+        let mut changed = false;
+        for (field, value) in [
+            (&mut self.device_name, device.device_name),
+            (&mut self.platform, device.platform),
+            (&mut self.ip_country, device.ip_country),
+        ] {
+            if value.is_some() && *field != value {
+                *field = value;
+                changed = true;
+            }
+        }
+        if changed || now - self.last_seen_at >= LAST_SEEN_RESOLUTION {
+            self.last_seen_at = now;
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use time::{Duration, OffsetDateTime};
+
+    use super::{DeviceInfo, DeviceSession, LAST_SEEN_RESOLUTION};
+    use crate::account::identifiers::AccountId;
+
+    fn session(now: OffsetDateTime) -> DeviceSession {
+        DeviceSession::new(
+            AccountId::from_str("urn:wallet-account:000000000000000000000000000").unwrap(),
+            "origin".to_string(),
+            DeviceInfo {
+                device_name: Some("Pixel 8".to_string()),
+                platform: Some("android".to_string()),
+                ip_country: Some("US".to_string()),
+            },
+            now,
+        )
+    }
+
+    #[test]
+    fn test_record_activity_is_throttled() {
+        let now = OffsetDateTime::now_utc();
+        let mut session = session(now);
+
+        assert!(!session.record_activity(DeviceInfo::default(), now + Duration::minutes(1)));
+        assert_eq!(session.last_seen_at, now);
+
+        let later = now + LAST_SEEN_RESOLUTION;
+        assert!(session.record_activity(DeviceInfo::default(), later));
+        assert_eq!(session.last_seen_at, later);
+        assert_eq!(session.device_name.as_deref(), Some("Pixel 8"));
+    }
+
+    #[test]
+    fn test_record_activity_tracks_device_changes() {
+        let now = OffsetDateTime::now_utc();
+        let mut session = session(now);
+        let later = now + Duration::seconds(30);
+
+        let changed = session.record_activity(
+            DeviceInfo {
+                ip_country: Some("CA".to_string()),
+                ..Default::default()
+            },
+            later,
+        );
+        assert!(changed);
+        assert_eq!(session.ip_country.as_deref(), Some("CA"));
+        assert_eq!(session.platform.as_deref(), Some("android"));
+        assert_eq!(session.last_seen_at, later);
+    }
+}
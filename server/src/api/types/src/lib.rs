@@ -12,5 +12,7 @@ pub mod exchange_rate;
 pub mod notification;
 #[cfg(feature = "recovery")]
 pub mod recovery;
+#[cfg(feature = "security_event")]
+pub mod security_event;
 #[cfg(feature = "serde")]
 pub mod serde;
@@ -0,0 +1,229 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use base32::Alphabet;
+use external_identifier::ExternalIdentifier;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use time::{serde::rfc3339, OffsetDateTime};
+use ulid::Ulid;
+use urn::Urn;
+use utoipa::ToSchema;
+
+use crate::account::identifiers::AccountId;
+
+use super::social::relationship::RecoveryRelationshipId;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InheritancePackageId(urn::Urn);
+
+impl FromStr for InheritancePackageId {
+    type Err = urn::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Urn::from_str(s)?.into())
+    }
+}
+
+impl From<urn::Urn> for InheritancePackageId {
+    fn from(urn: urn::Urn) -> Self {
+        Self(urn)
+    }
+}
+
+impl ExternalIdentifier<String> for InheritancePackageId {
+    fn namespace() -> &'static str {
+        "inheritance-package"
+    }
+}
+
+impl InheritancePackageId {
+    // Derive an InheritancePackageId from a recovery relationship, which has at most one package
+    // Encode this the same way Ulids are encoded (Crockford Base32) to be consistent with other IDs
+    pub fn derive(recovery_relationship_id: &RecoveryRelationshipId) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(recovery_relationship_id.to_string().as_bytes());
+        let hash = hasher.finalize();
+        let encoded = base32::encode(Alphabet::Crockford, &hash[0..16]);
+        Self::new(encoded).unwrap()
+    }
+}
+
+impl Display for InheritancePackageId {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InheritanceClaimId(urn::Urn);
+
+impl FromStr for InheritanceClaimId {
+    type Err = urn::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Urn::from_str(s)?.into())
+    }
+}
+
+impl From<urn::Urn> for InheritanceClaimId {
+    fn from(urn: urn::Urn) -> Self {
+        Self(urn)
+    }
+}
+
+impl ExternalIdentifier<Ulid> for InheritanceClaimId {
+    fn namespace() -> &'static str {
+        "inheritance-claim"
+    }
+}
+
+impl InheritanceClaimId {
+    pub fn gen() -> Result<Self, external_identifier::Error> {
+        Self::new(Ulid::new())
+    }
+}
+
+impl Display for InheritanceClaimId {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The material a customer leaves for a beneficiary trusted contact, sealed to the trusted
+/// contact's endorsed delegated decryption key. It's only released to the beneficiary once an
+/// inheritance claim has run its course.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InheritancePackage {
+    #[serde(rename = "partition_key")]
+    pub id: InheritancePackageId,
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub customer_account_id: AccountId,
+    // The beneficiary, named to match the recovery relationship so it shares its index
+    pub trusted_contact_account_id: AccountId,
+    pub sealed_dek: String,
+    pub sealed_mobile_key: String,
+    // How long the customer must have been inactive before the beneficiary can start a claim
+    pub inactivity_period_days: u32,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl InheritancePackage {
+    #[must_use]
+    pub fn new(
+        recovery_relationship_id: &RecoveryRelationshipId,
+        customer_account_id: &AccountId,
+        trusted_contact_account_id: &AccountId,
+        sealed_dek: &str,
+        sealed_mobile_key: &str,
+        inactivity_period_days: u32,
+    ) -> Self {
+        Self {
+            id: InheritancePackageId::derive(recovery_relationship_id),
+            recovery_relationship_id: recovery_relationship_id.to_owned(),
+            customer_account_id: customer_account_id.to_owned(),
+            trusted_contact_account_id: trusted_contact_account_id.to_owned(),
+            sealed_dek: sealed_dek.to_owned(),
+            sealed_mobile_key: sealed_mobile_key.to_owned(),
+            inactivity_period_days,
+            created_at: OffsetDateTime::now_utc(),
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    pub fn with_updated_at(&self, updated_at: OffsetDateTime) -> Self {
+        Self {
+            updated_at,
+            ..self.to_owned()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InheritanceClaimStatus {
+    Pending,
+    Canceled,
+    Completed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InheritanceClaim {
+    #[serde(rename = "partition_key")]
+    pub id: InheritanceClaimId,
+    pub recovery_relationship_id: RecoveryRelationshipId,
+    pub customer_account_id: AccountId,
+    // The beneficiary, named to match the recovery relationship so it shares its index
+    pub trusted_contact_account_id: AccountId,
+    pub status: InheritanceClaimStatus,
+    #[serde(with = "rfc3339")]
+    pub delay_end_time: OffsetDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canceled_by_account_id: Option<AccountId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sweep_txid: Option<String>,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl InheritanceClaim {
+    #[must_use]
+    pub fn new(
+        id: &InheritanceClaimId,
+        recovery_relationship_id: &RecoveryRelationshipId,
+        customer_account_id: &AccountId,
+        trusted_contact_account_id: &AccountId,
+        delay_end_time: OffsetDateTime,
+    ) -> Self {
+        Self {
+            id: id.to_owned(),
+            recovery_relationship_id: recovery_relationship_id.to_owned(),
+            customer_account_id: customer_account_id.to_owned(),
+            trusted_contact_account_id: trusted_contact_account_id.to_owned(),
+            status: InheritanceClaimStatus::Pending,
+            delay_end_time,
+            canceled_by_account_id: None,
+            sweep_txid: None,
+            created_at: OffsetDateTime::now_utc(),
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    pub fn with_updated_at(&self, updated_at: OffsetDateTime) -> Self {
+        Self {
+            updated_at,
+            ..self.to_owned()
+        }
+    }
+
+    pub fn canceled_by(&self, account_id: &AccountId) -> Self {
+        Self {
+            status: InheritanceClaimStatus::Canceled,
+            canceled_by_account_id: Some(account_id.to_owned()),
+            ..self.to_owned()
+        }
+    }
+
+    pub fn completed_with(&self, sweep_txid: &str) -> Self {
+        Self {
+            status: InheritanceClaimStatus::Completed,
+            sweep_txid: Some(sweep_txid.to_owned()),
+            ..self.to_owned()
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == InheritanceClaimStatus::Pending
+    }
+
+    pub fn is_delay_complete(&self, now: OffsetDateTime) -> bool {
+        now >= self.delay_end_time
+    }
+}
@@ -1 +1,2 @@
+pub mod inheritance;
 pub mod social;
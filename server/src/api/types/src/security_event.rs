@@ -0,0 +1,176 @@
+use std::{collections::HashSet, sync::Mutex, time::SystemTime};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use time::{serde::rfc3339, OffsetDateTime};
+use ulid::{Generator, Ulid};
+use utoipa::ToSchema;
+
+use crate::{
+    account::identifiers::{AccountId, AuthKeysId, KeysetId, TouchpointId},
+    currencies::CurrencyCode,
+    notification::NotificationChannel,
+    recovery::{
+        inheritance::InheritanceClaimId,
+        social::{challenge::SocialChallengeId, relationship::RecoveryRelationshipId},
+    },
+};
+
+// Keeps event ids minted within the same millisecond in the order they were minted
+static EVENT_ID_GENERATOR: Lazy<Mutex<Generator>> = Lazy::new(|| Mutex::new(Generator::new()));
+
+/// Something that changed how an account is secured. Events are only ever appended, and are keyed
+/// by a ULID minted from `created_at` so that an account's log reads back in the order it happened.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SecurityEvent {
+    #[serde(rename = "partition_key")]
+    pub account_id: AccountId,
+    #[serde(rename = "sort_key")]
+    pub event_id: String,
+    #[serde(with = "rfc3339")]
+    pub created_at: OffsetDateTime,
+    pub event: SecurityEventType,
+    #[serde(default)]
+    pub status: SecurityEventStatus,
+}
+
+impl SecurityEvent {
+    pub fn new(
+        account_id: AccountId,
+        event: SecurityEventType,
+        status: SecurityEventStatus,
+        now: OffsetDateTime,
+    ) -> Self {
+        Self {
+            account_id,
+            event_id: gen_event_id(now).to_string(),
+            created_at: now,
+            event,
+            status,
+        }
+    }
+}
+
+/// Each change is logged twice: once before it's made, so that no change goes unlogged, and once
+/// it has been made, so that the log never claims a change that failed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SecurityEventStatus {
+    Attempted,
+    #[default]
+    Completed,
+}
+
+fn gen_event_id(now: OffsetDateTime) -> Ulid {
+    let datetime = SystemTime::from(now);
+    EVENT_ID_GENERATOR
+        .lock()
+        .ok()
+        .and_then(|mut generator| generator.generate_from_datetime(datetime).ok())
+        .unwrap_or_else(|| Ulid::from_datetime(datetime))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SecurityEventType {
+    AuthKeysRotated {
+        auth_keys_id: AuthKeysId,
+    },
+    SpendingKeysetRotated {
+        keyset_id: KeysetId,
+    },
+    TouchpointActivated {
+        touchpoint_id: TouchpointId,
+    },
+    PushTouchpointsCleared,
+    DelayNotifyRecoveryStarted {
+        lost_factor: String,
+    },
+    DelayNotifyRecoveryCanceled {
+        lost_factor: String,
+        contested: bool,
+    },
+    DelayNotifyRecoveryCompleted {
+        lost_factor: String,
+    },
+    RecoveryPolicyChangeRequested {
+        delay_period_days: u32,
+        contest_lookback_days: u32,
+        #[serde(with = "rfc3339")]
+        effective_at: OffsetDateTime,
+    },
+    RecoveryPolicyChangeCanceled,
+    RecoveryRelationshipCreated {
+        recovery_relationship_id: RecoveryRelationshipId,
+    },
+    RecoveryRelationshipAccepted {
+        recovery_relationship_id: RecoveryRelationshipId,
+    },
+    RecoveryRelationshipEndorsed {
+        recovery_relationship_id: RecoveryRelationshipId,
+    },
+    RecoveryRelationshipDeleted {
+        recovery_relationship_id: RecoveryRelationshipId,
+    },
+    SocialChallengeStarted {
+        social_challenge_id: SocialChallengeId,
+    },
+    InheritanceClaimStarted {
+        inheritance_claim_id: InheritanceClaimId,
+    },
+    InheritanceClaimCanceled {
+        inheritance_claim_id: InheritanceClaimId,
+    },
+    InheritanceClaimCompleted {
+        inheritance_claim_id: InheritanceClaimId,
+    },
+    SpendingLimitUpdated {
+        amount: u64,
+        currency_code: CurrencyCode,
+    },
+    SpendingLimitDisabled,
+    SpendPolicyUpdated {
+        rule_count: usize,
+    },
+    AccountSecurityNotificationsUpdated {
+        channels: HashSet<NotificationChannel>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use time::{Duration, OffsetDateTime};
+
+    use super::{SecurityEvent, SecurityEventStatus, SecurityEventType};
+    use crate::account::identifiers::AccountId;
+
+    #[test]
+    fn test_event_ids_sort_by_creation_time() {
+        let account_id =
+            AccountId::from_str("urn:wallet-account:000000000000000000000000000").unwrap();
+        let now = OffsetDateTime::now_utc();
+
+        let earlier = SecurityEvent::new(
+            account_id.clone(),
+            SecurityEventType::PushTouchpointsCleared,
+            SecurityEventStatus::Completed,
+            now,
+        );
+        let later = SecurityEvent::new(
+            account_id,
+            SecurityEventType::SpendingLimitDisabled,
+            SecurityEventStatus::Attempted,
+            now + Duration::milliseconds(1),
+        );
+        let same_millisecond = SecurityEvent::new(
+            earlier.account_id.clone(),
+            SecurityEventType::SpendingLimitDisabled,
+            SecurityEventStatus::Completed,
+            now + Duration::milliseconds(1),
+        );
+        assert!(earlier.event_id < later.event_id);
+        assert!(later.event_id < same_millisecond.event_id);
+    }
+}
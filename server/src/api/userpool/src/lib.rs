@@ -1,3 +1,4 @@
 pub mod migrations;
+pub mod native;
 pub mod test_utils;
 pub mod userpool;
@@ -0,0 +1,219 @@
+use std::str::FromStr;
+
+use secp256k1::ecdsa::Signature;
+use secp256k1::hashes::sha256;
+use secp256k1::{Message, PublicKey, Secp256k1};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use types::authn_authz::cognito::CognitoUsername;
+
+const USER_PREFIX: &str = "USER#";
+const SESSION_PREFIX: &str = "SESSION#";
+const REFRESH_TOKEN_PREFIX: &str = "REFRESH#";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum NativeUserKeys {
+    Wallet {
+        app_pubkey: PublicKey,
+        hw_pubkey: PublicKey,
+    },
+    Recovery {
+        recovery_pubkey: PublicKey,
+    },
+}
+
+impl NativeUserKeys {
+    pub(crate) fn wallet(app_pubkey: &str, hw_pubkey: &str) -> Result<Self, secp256k1::Error> {
+        Ok(Self::Wallet {
+            app_pubkey: PublicKey::from_str(app_pubkey)?,
+            hw_pubkey: PublicKey::from_str(hw_pubkey)?,
+        })
+    }
+
+    pub(crate) fn recovery(recovery_pubkey: &str) -> Result<Self, secp256k1::Error> {
+        Ok(Self::Recovery {
+            recovery_pubkey: PublicKey::from_str(recovery_pubkey)?,
+        })
+    }
+
+    /// Checks a response to an auth challenge the same way the Cognito verify-challenge lambda
+    /// does: an ECDSA signature over the SHA-256 of the challenge string by any of the user's keys.
+    pub(crate) fn verify_challenge_response(&self, challenge: &str, response: &str) -> bool {
+        let Ok(signature) = Signature::from_str(response) else {
+            return false;
+        };
+        let secp = Secp256k1::verification_only();
+        let message = Message::from_hashed_data::<sha256::Hash>(challenge.as_bytes());
+        let verify = |key: &PublicKey| secp.verify_ecdsa(&message, &signature, key).is_ok();
+        match self {
+            NativeUserKeys::Wallet {
+                app_pubkey,
+                hw_pubkey,
+            } => verify(app_pubkey) || verify(hw_pubkey),
+            NativeUserKeys::Recovery { recovery_pubkey } => verify(recovery_pubkey),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct NativeUser {
+    pub(crate) partition_key: String,
+    pub(crate) username: CognitoUsername,
+    pub(crate) keys: NativeUserKeys,
+    /// Tokens issued at or before this time (in seconds) have been revoked
+    pub(crate) tokens_valid_after: u64,
+}
+
+impl NativeUser {
+    pub(crate) fn new(username: CognitoUsername, keys: NativeUserKeys) -> Self {
+        Self {
+            partition_key: user_partition_key(&username),
+            username,
+            keys,
+            tokens_valid_after: 0,
+        }
+    }
+
+    /// Token timestamps only have second resolution, so a token issued in the same second as a
+    /// sign-out is treated as revoked rather than risk it outliving the sign-out.
+    pub(crate) fn has_revoked_tokens_issued_at(&self, issued_at: u64) -> bool {
+        issued_at <= self.tokens_valid_after
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct AuthSession {
+    pub(crate) partition_key: String,
+    pub(crate) session_id: String,
+    pub(crate) username: CognitoUsername,
+    pub(crate) challenge: String,
+    pub(crate) expires_at: u64,
+}
+
+impl AuthSession {
+    pub(crate) fn new(
+        session_id: String,
+        username: CognitoUsername,
+        challenge: String,
+        expires_at: u64,
+    ) -> Self {
+        Self {
+            partition_key: session_partition_key(&session_id),
+            session_id,
+            username,
+            challenge,
+            expires_at,
+        }
+    }
+}
+
+/// Only a hash of the refresh token is stored, so the table can't be used to mint tokens.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct RefreshTokenRecord {
+    pub(crate) partition_key: String,
+    pub(crate) username: CognitoUsername,
+    pub(crate) origin_jti: String,
+    pub(crate) issued_at: u64,
+    pub(crate) expires_at: u64,
+}
+
+impl RefreshTokenRecord {
+    pub(crate) fn new(
+        refresh_token: &str,
+        username: CognitoUsername,
+        origin_jti: String,
+        issued_at: u64,
+        expires_at: u64,
+    ) -> Self {
+        Self {
+            partition_key: refresh_token_partition_key(refresh_token),
+            username,
+            origin_jti,
+            issued_at,
+            expires_at,
+        }
+    }
+}
+
+pub(crate) fn user_partition_key(username: &CognitoUsername) -> String {
+    format!("{USER_PREFIX}{username}")
+}
+
+pub(crate) fn session_partition_key(session_id: &str) -> String {
+    format!("{SESSION_PREFIX}{session_id}")
+}
+
+pub(crate) fn refresh_token_partition_key(refresh_token: &str) -> String {
+    format!(
+        "{REFRESH_TOKEN_PREFIX}{}",
+        hex::encode(Sha256::digest(refresh_token.as_bytes()))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::hashes::sha256;
+    use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+    use std::str::FromStr;
+
+    use types::authn_authz::cognito::CognitoUsername;
+
+    use super::{NativeUser, NativeUserKeys};
+
+    const CHALLENGE: &str = "c29tZSBjaGFsbGVuZ2U=";
+
+    fn keypair(byte: u8) -> (SecretKey, PublicKey) {
+        let secp = Secp256k1::new();
+        let secret = SecretKey::from_slice(&[byte; 32]).unwrap();
+        (secret, PublicKey::from_secret_key(&secp, &secret))
+    }
+
+    fn sign(secret: &SecretKey, challenge: &str) -> String {
+        let message = Message::from_hashed_data::<sha256::Hash>(challenge.as_bytes());
+        Secp256k1::new().sign_ecdsa(&message, secret).to_string()
+    }
+
+    #[test]
+    fn test_wallet_user_accepts_app_or_hw_signature() {
+        let (app_secret, app_pubkey) = keypair(1);
+        let (hw_secret, hw_pubkey) = keypair(2);
+        let (other_secret, _) = keypair(3);
+        let keys = NativeUserKeys::Wallet {
+            app_pubkey,
+            hw_pubkey,
+        };
+
+        assert!(keys.verify_challenge_response(CHALLENGE, &sign(&app_secret, CHALLENGE)));
+        assert!(keys.verify_challenge_response(CHALLENGE, &sign(&hw_secret, CHALLENGE)));
+        assert!(!keys.verify_challenge_response(CHALLENGE, &sign(&other_secret, CHALLENGE)));
+        assert!(!keys.verify_challenge_response("other", &sign(&app_secret, CHALLENGE)));
+        assert!(!keys.verify_challenge_response(CHALLENGE, "not-a-signature"));
+    }
+
+    #[test]
+    fn test_recovery_user_only_accepts_recovery_signature() {
+        let (recovery_secret, recovery_pubkey) = keypair(1);
+        let (app_secret, _) = keypair(2);
+        let keys = NativeUserKeys::Recovery { recovery_pubkey };
+
+        assert!(keys.verify_challenge_response(CHALLENGE, &sign(&recovery_secret, CHALLENGE)));
+        assert!(!keys.verify_challenge_response(CHALLENGE, &sign(&app_secret, CHALLENGE)));
+    }
+
+    #[test]
+    fn test_sign_out_revokes_tokens_issued_in_the_same_second() {
+        let (_, recovery_pubkey) = keypair(1);
+        let mut user = NativeUser::new(
+            CognitoUsername::from_str("urn:wallet-account:000000000000000000000000000-recovery")
+                .unwrap(),
+            NativeUserKeys::Recovery { recovery_pubkey },
+        );
+        user.tokens_valid_after = 1_700_000_000;
+
+        assert!(user.has_revoked_tokens_issued_at(1_699_999_999));
+        assert!(user.has_revoked_tokens_issued_at(1_700_000_000));
+        assert!(!user.has_revoked_tokens_issued_at(1_700_000_001));
+    }
+}
@@ -0,0 +1,232 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL;
+use base64::Engine as _;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+    OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use thiserror::Error;
+use types::authn_authz::AccessTokenClaims;
+
+// PKCS#8 v1 wrapper for a raw Ed25519 seed (RFC 8410)
+const ED25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+const ED25519_SEED_LEN: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum SigningKeyError {
+    #[error("No signing keys configured")]
+    NoKeys,
+    #[error("Signing key entries must be formatted as <kid>:<hex seed>")]
+    MalformedEntry,
+    #[error("Duplicate signing key id {0}")]
+    DuplicateKeyId(String),
+    #[error("Signing key {0} is not a 32-byte hex Ed25519 seed")]
+    InvalidSeed(String),
+}
+
+#[derive(Clone)]
+struct SigningKey {
+    kid: String,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    public_key: String,
+}
+
+/// Ed25519 keys used to sign and verify access tokens.
+///
+/// Keys are configured as a comma-separated list of `<kid>:<hex seed>` entries. The first entry
+/// signs new tokens; the rest are only published so tokens signed before a rotation keep
+/// verifying until they expire.
+#[derive(Clone)]
+pub struct SigningKeys {
+    keys: Vec<SigningKey>,
+}
+
+impl SigningKeys {
+    pub fn parse(value: &str) -> Result<Self, SigningKeyError> {
+        let mut keys: Vec<SigningKey> = Vec::new();
+        for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (kid, seed) = entry
+                .split_once(':')
+                .ok_or(SigningKeyError::MalformedEntry)?;
+            if kid.is_empty() {
+                return Err(SigningKeyError::MalformedEntry);
+            }
+            if keys.iter().any(|k| k.kid == kid) {
+                return Err(SigningKeyError::DuplicateKeyId(kid.to_string()));
+            }
+            let seed = hex::decode(seed)
+                .ok()
+                .filter(|seed| seed.len() == ED25519_SEED_LEN)
+                .ok_or_else(|| SigningKeyError::InvalidSeed(kid.to_string()))?;
+            keys.push(SigningKey::from_seed(kid, &seed)?);
+        }
+        if keys.is_empty() {
+            return Err(SigningKeyError::NoKeys);
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn sign(&self, claims: &AccessTokenClaims) -> Result<String, jsonwebtoken::errors::Error> {
+        let key = self.active();
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(key.kid.clone());
+        jsonwebtoken::encode(&header, claims, &key.encoding_key)
+    }
+
+    /// Verifies a token's signature, issuer and expiry against the published keys.
+    pub fn verify(
+        &self,
+        token: &str,
+        issuer: &str,
+    ) -> Result<AccessTokenClaims, jsonwebtoken::errors::Error> {
+        let kid = jsonwebtoken::decode_header(token)?.kid;
+        let key = self
+            .keys
+            .iter()
+            .find(|k| Some(&k.kid) == kid.as_ref())
+            .ok_or(jsonwebtoken::errors::ErrorKind::InvalidSignature)?;
+        let mut validation = Validation::new(Algorithm::EdDSA);
+        validation.set_issuer(&[issuer]);
+        jsonwebtoken::decode::<AccessTokenClaims>(token, &key.decoding_key, &validation)
+            .map(|data| data.claims)
+    }
+
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self
+                .keys
+                .iter()
+                .map(|key| Jwk {
+                    common: CommonParameters {
+                        public_key_use: Some(PublicKeyUse::Signature),
+                        // The algorithm must be explicit; verifiers can't infer it from an OKP key
+                        key_algorithm: Some(KeyAlgorithm::EdDSA),
+                        key_id: Some(key.kid.clone()),
+                        ..Default::default()
+                    },
+                    algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                        key_type: OctetKeyPairType::OctetKeyPair,
+                        curve: EllipticCurve::Ed25519,
+                        x: key.public_key.clone(),
+                    }),
+                })
+                .collect(),
+        }
+    }
+
+    fn active(&self) -> &SigningKey {
+        self.keys
+            .first()
+            .expect("signing keys are non-empty by construction")
+    }
+}
+
+impl SigningKey {
+    fn from_seed(kid: &str, seed: &[u8]) -> Result<Self, SigningKeyError> {
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(seed)
+            .map_err(|_| SigningKeyError::InvalidSeed(kid.to_string()))?;
+        let public_key = BASE64_URL.encode(key_pair.public_key().as_ref());
+        let pkcs8 = [ED25519_PKCS8_PREFIX.as_slice(), seed].concat();
+        Ok(Self {
+            kid: kid.to_string(),
+            encoding_key: EncodingKey::from_ed_der(&pkcs8),
+            decoding_key: DecodingKey::from_ed_components(&public_key)
+                .map_err(|_| SigningKeyError::InvalidSeed(kid.to_string()))?,
+            public_key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use jsonwebtoken::jwk::AlgorithmParameters;
+    use types::account::identifiers::AccountId;
+    use types::authn_authz::cognito::CognitoUser;
+    use types::authn_authz::AccessTokenClaims;
+
+    use super::{SigningKeyError, SigningKeys};
+
+    const ISSUER: &str = "https://bitkey.example.com";
+    const SEED_A: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+    const SEED_B: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+    fn claims() -> AccessTokenClaims {
+        let now = jsonwebtoken::get_current_timestamp();
+        let user = CognitoUser::Wallet(
+            AccountId::from_str("urn:wallet-account:000000000000000000000000000").unwrap(),
+        );
+        AccessTokenClaims {
+            sub: "sub".to_string(),
+            iss: ISSUER.to_string(),
+            client_id: "client".to_string(),
+            origin_jti: "origin".to_string(),
+            event_id: "event".to_string(),
+            token_use: "access".to_string(),
+            scope: String::new(),
+            auth_time: now,
+            exp: now + 60,
+            iat: now,
+            jti: "jti".to_string(),
+            username: (&user).into(),
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify_round_trip() {
+        let keys = SigningKeys::parse(&format!("a:{SEED_A}")).unwrap();
+        let token = keys.sign(&claims()).unwrap();
+        let verified = keys.verify(&token, ISSUER).unwrap();
+        assert_eq!(verified.username, claims().username);
+        assert!(keys.verify(&token, "https://someone.else").is_err());
+    }
+
+    #[test]
+    fn test_rotated_out_key_still_verifies() {
+        let old = SigningKeys::parse(&format!("a:{SEED_A}")).unwrap();
+        let rotated = SigningKeys::parse(&format!("b:{SEED_B},a:{SEED_A}")).unwrap();
+        let token = old.sign(&claims()).unwrap();
+        assert!(rotated.verify(&token, ISSUER).is_ok());
+
+        let dropped = SigningKeys::parse(&format!("b:{SEED_B}")).unwrap();
+        assert!(dropped.verify(&token, ISSUER).is_err());
+    }
+
+    #[test]
+    fn test_jwks_publishes_all_keys() {
+        let keys = SigningKeys::parse(&format!("b:{SEED_B}, a:{SEED_A}")).unwrap();
+        let jwks = keys.jwks();
+        assert_eq!(jwks.keys.len(), 2);
+        let jwk = jwks.find("a").unwrap();
+        let AlgorithmParameters::OctetKeyPair(params) = &jwk.algorithm else {
+            panic!("expected an OKP key");
+        };
+        assert_eq!(params.x.len(), 43);
+        assert!(jsonwebtoken::DecodingKey::from_jwk(jwk).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_configuration() {
+        assert!(matches!(
+            SigningKeys::parse(""),
+            Err(SigningKeyError::NoKeys)
+        ));
+        assert!(matches!(
+            SigningKeys::parse(SEED_A),
+            Err(SigningKeyError::MalformedEntry)
+        ));
+        assert!(matches!(
+            SigningKeys::parse("a:abcd"),
+            Err(SigningKeyError::InvalidSeed(_))
+        ));
+        assert!(matches!(
+            SigningKeys::parse(&format!("a:{SEED_A},a:{SEED_B}")),
+            Err(SigningKeyError::DuplicateKeyId(_))
+        ));
+    }
+}
@@ -0,0 +1,365 @@
+use std::env;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use database::ddb::{Connection, DDBService, DatabaseError};
+use jsonwebtoken::jwk::JwkSet;
+use rand::Rng;
+use tracing::{event, instrument, Level};
+use types::authn_authz::cognito::{CognitoUser, CognitoUsername};
+use types::authn_authz::AccessTokenClaims;
+
+use crate::userpool::{AuthChallenge, AuthTokens, CognitoIdpConnection, UserPoolError};
+
+use self::entities::{AuthSession, NativeUser, NativeUserKeys, RefreshTokenRecord};
+use self::keys::SigningKeys;
+use self::repository::Repository;
+
+mod entities;
+pub mod keys;
+pub mod repository;
+
+const ACCESS_TOKEN_TTL_SECS: u64 = 15 * 60;
+const REFRESH_TOKEN_TTL_SECS: u64 = 30 * 24 * 60 * 60;
+const SESSION_TTL_SECS: u64 = 3 * 60;
+const NATIVE_CLIENT_ID: &str = "bitkey";
+
+/// Issuer and signing keys for natively issued access tokens, shared by the user pool and the
+/// authorizer that verifies its tokens.
+#[derive(Clone)]
+pub struct NativeAuthConfig {
+    pub issuer: String,
+    pub signing_keys: SigningKeys,
+}
+
+impl NativeAuthConfig {
+    pub fn from_env() -> Self {
+        let signing_keys =
+            env::var("AUTH_SIGNING_KEYS").expect("AUTH_SIGNING_KEYS environment variable not set");
+        Self {
+            issuer: env::var("AUTH_ISSUER").expect("AUTH_ISSUER environment variable not set"),
+            signing_keys: SigningKeys::parse(&signing_keys)
+                .expect("Could not parse AUTH_SIGNING_KEYS"),
+        }
+    }
+}
+
+/// Self-hosted replacement for Cognito's custom auth flow. The server issues the challenge for the
+/// app, hardware or recovery auth key, verifies the signed response, and mints its own access and
+/// refresh tokens.
+///
+/// Signing out moves the user's `tokens_valid_after` forward, which revokes every refresh token
+/// and access token issued before then. As with Cognito, access tokens are only checked against
+/// it by [`CognitoIdpConnection::is_access_token_revoked`], so they are kept short-lived.
+#[derive(Clone)]
+pub struct NativeConnection {
+    repository: Repository,
+    config: NativeAuthConfig,
+}
+
+impl NativeConnection {
+    pub async fn new(connection: Connection, config: NativeAuthConfig) -> Self {
+        let repository = Repository::new(connection);
+        repository
+            .create_table_if_necessary()
+            .await
+            .expect("Could not create Auth table");
+        Self { repository, config }
+    }
+
+    fn mint_access_token(
+        &self,
+        username: &CognitoUsername,
+        origin_jti: &str,
+        event_id: &str,
+        auth_time: u64,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let now = jsonwebtoken::get_current_timestamp();
+        let claims = AccessTokenClaims {
+            sub: username.to_string(),
+            iss: self.config.issuer.clone(),
+            client_id: NATIVE_CLIENT_ID.to_string(),
+            origin_jti: origin_jti.to_string(),
+            event_id: event_id.to_string(),
+            token_use: "access".to_string(),
+            scope: String::new(),
+            auth_time,
+            exp: now + ACCESS_TOKEN_TTL_SECS,
+            iat: now,
+            jti: random_hex::<16>(),
+            username: username.clone(),
+        };
+        self.config.signing_keys.sign(&claims)
+    }
+
+    async fn fetch_user(&self, username: &CognitoUsername) -> Result<NativeUser, UserPoolError> {
+        self.repository
+            .fetch_user(username)
+            .await
+            .map_err(|err| match err {
+                DatabaseError::ObjectNotFound(_) => UserPoolError::NonExistentUser,
+                err => err.into(),
+            })
+    }
+
+    async fn create_user(
+        &self,
+        username: &CognitoUsername,
+        keys: NativeUserKeys,
+    ) -> Result<String, UserPoolError> {
+        self.repository
+            .create_user(&NativeUser::new(username.clone(), keys))
+            .await
+            .map_err(|err| UserPoolError::CreateUserError(err.to_string()))?;
+        Ok(username.to_string())
+    }
+
+    async fn replace_keys(
+        &self,
+        username: CognitoUsername,
+        keys: NativeUserKeys,
+    ) -> Result<(), UserPoolError> {
+        let user = self.fetch_user(&username).await?;
+        self.repository
+            .persist_user(&NativeUser { keys, ..user })
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CognitoIdpConnection for NativeConnection {
+    #[instrument(err, skip(self, username, app_key, hw_key))]
+    async fn create_new_wallet_user(
+        &self,
+        username: &CognitoUsername,
+        app_key: String,
+        hw_key: String,
+    ) -> Result<String, UserPoolError> {
+        let keys = NativeUserKeys::wallet(&app_key, &hw_key).map_err(invalid_pubkey)?;
+        self.create_user(username, keys).await
+    }
+
+    #[instrument(err, skip(self, username))]
+    async fn is_existing_cognito_user(
+        &self,
+        username: &CognitoUsername,
+    ) -> Result<bool, UserPoolError> {
+        match self.fetch_user(username).await {
+            Ok(_) => Ok(true),
+            Err(UserPoolError::NonExistentUser) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[instrument(err, skip(self, recovery_key))]
+    async fn create_new_recovery_user(
+        &self,
+        username: &CognitoUsername,
+        recovery_key: String,
+    ) -> Result<String, UserPoolError> {
+        let keys = NativeUserKeys::recovery(&recovery_key).map_err(invalid_pubkey)?;
+        self.create_user(username, keys).await
+    }
+
+    async fn confirm_user(&self, _username: &CognitoUsername) -> Result<(), UserPoolError> {
+        // Native users are usable as soon as they're created
+        Ok(())
+    }
+
+    #[instrument(err, skip(self, app_key, hw_key))]
+    async fn replace_wallet_user_pubkeys(
+        &self,
+        username: CognitoUsername,
+        app_key: String,
+        hw_key: String,
+    ) -> Result<(), UserPoolError> {
+        let keys = NativeUserKeys::wallet(&app_key, &hw_key).map_err(invalid_pubkey)?;
+        self.replace_keys(username, keys).await
+    }
+
+    #[instrument(err, skip(self, recovery_key))]
+    async fn replace_recovery_pubkey(
+        &self,
+        username: CognitoUsername,
+        recovery_key: String,
+    ) -> Result<(), UserPoolError> {
+        let keys = NativeUserKeys::recovery(&recovery_key).map_err(invalid_pubkey)?;
+        self.replace_keys(username, keys).await
+    }
+
+    #[instrument(err, skip(self))]
+    async fn initiate_auth_for_wallet_user(
+        &self,
+        username: CognitoUsername,
+    ) -> Result<AuthChallenge, UserPoolError> {
+        self.fetch_user(&username).await?;
+
+        let challenge = BASE64.encode(random_bytes::<64>());
+        let session = random_hex::<32>();
+        let expires_at = jsonwebtoken::get_current_timestamp() + SESSION_TTL_SECS;
+        self.repository
+            .persist_session(&AuthSession::new(
+                session.clone(),
+                username.clone(),
+                challenge.clone(),
+                expires_at,
+            ))
+            .await
+            .map_err(|err| UserPoolError::InitiateAuthError(err.to_string()))?;
+
+        Ok(AuthChallenge {
+            username,
+            challenge,
+            session,
+        })
+    }
+
+    #[instrument(err, skip(self, refresh_token))]
+    async fn refresh_auth_token(&self, refresh_token: String) -> Result<AuthTokens, UserPoolError> {
+        let invalid_refresh_token =
+            || UserPoolError::InitiateAuthError("invalid refresh token".to_string());
+        let record = self
+            .repository
+            .fetch_refresh_token(&refresh_token)
+            .await
+            .map_err(|err| match err {
+                DatabaseError::ObjectNotFound(_) => invalid_refresh_token(),
+                err => err.into(),
+            })?;
+        let user = self.fetch_user(&record.username).await?;
+
+        let now = jsonwebtoken::get_current_timestamp();
+        if record.expires_at <= now || user.has_revoked_tokens_issued_at(record.issued_at) {
+            event!(Level::INFO, "Rejecting expired or revoked refresh token");
+            return Err(invalid_refresh_token());
+        }
+
+        let access_token = self
+            .mint_access_token(
+                &record.username,
+                &record.origin_jti,
+                &random_hex::<16>(),
+                record.issued_at,
+            )
+            .map_err(|err| UserPoolError::IssueTokenError(err.to_string()))?;
+        Ok(AuthTokens {
+            access_token,
+            refresh_token,
+        })
+    }
+
+    #[instrument(err, skip(self, session, challenge_response))]
+    async fn respond_to_auth_challenge(
+        &self,
+        username: &CognitoUsername,
+        session: String,
+        challenge_response: String,
+    ) -> Result<AuthTokens, UserPoolError> {
+        let auth_session =
+            self.repository
+                .consume_session(&session)
+                .await
+                .map_err(|err| match err {
+                    DatabaseError::ObjectNotFound(_) => UserPoolError::InvalidSession,
+                    err => err.into(),
+                })?;
+        let now = jsonwebtoken::get_current_timestamp();
+        if auth_session.username != *username || auth_session.expires_at <= now {
+            return Err(UserPoolError::InvalidSession);
+        }
+
+        let user = self.fetch_user(username).await?;
+        if !user
+            .keys
+            .verify_challenge_response(&auth_session.challenge, &challenge_response)
+        {
+            return Err(UserPoolError::InvalidChallengeResponse);
+        }
+
+        let refresh_token = random_hex::<32>();
+        let origin_jti = random_hex::<16>();
+        self.repository
+            .persist_refresh_token(&RefreshTokenRecord::new(
+                &refresh_token,
+                username.clone(),
+                origin_jti.clone(),
+                now,
+                now + REFRESH_TOKEN_TTL_SECS,
+            ))
+            .await?;
+
+        let access_token = self
+            .mint_access_token(username, &origin_jti, &auth_session.session_id, now)
+            .map_err(|err| UserPoolError::IssueTokenError(err.to_string()))?;
+        Ok(AuthTokens {
+            access_token,
+            refresh_token,
+        })
+    }
+
+    #[instrument(err, skip(self))]
+    async fn get_pubkeys_for_wallet_user(
+        &self,
+        username: CognitoUsername,
+    ) -> Result<(String, String), UserPoolError> {
+        match self.fetch_user(&username).await?.keys {
+            NativeUserKeys::Wallet {
+                app_pubkey,
+                hw_pubkey,
+            } => Ok((app_pubkey.to_string(), hw_pubkey.to_string())),
+            NativeUserKeys::Recovery { .. } => Err(UserPoolError::WrongUserType),
+        }
+    }
+
+    #[instrument(err, skip(self))]
+    async fn perform_sign_out_for_account(
+        &self,
+        username: CognitoUsername,
+    ) -> Result<(), UserPoolError> {
+        let user = self.fetch_user(&username).await?;
+        self.repository
+            .persist_user(&NativeUser {
+                tokens_valid_after: jsonwebtoken::get_current_timestamp(),
+                ..user
+            })
+            .await?;
+        Ok(())
+    }
+
+    #[instrument(err, skip(self, access_token))]
+    async fn is_access_token_revoked(&self, access_token: String) -> Result<bool, UserPoolError> {
+        let Ok(claims) = self
+            .config
+            .signing_keys
+            .verify(&access_token, &self.config.issuer)
+        else {
+            return Ok(true);
+        };
+        let username = CognitoUser::from_str(claims.username.as_ref())
+            .map_err(|_| UserPoolError::NonExistentUser)?
+            .into();
+        let user = self.fetch_user(&username).await?;
+        Ok(user.has_revoked_tokens_issued_at(claims.iat))
+    }
+
+    fn jwks(&self) -> Option<JwkSet> {
+        Some(self.config.signing_keys.jwks())
+    }
+}
+
+fn invalid_pubkey(err: secp256k1::Error) -> UserPoolError {
+    UserPoolError::CreateUserError(err.to_string())
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::thread_rng().fill(&mut bytes[..]);
+    bytes
+}
+
+fn random_hex<const N: usize>() -> String {
+    hex::encode(random_bytes::<N>())
+}
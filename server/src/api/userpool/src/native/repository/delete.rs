@@ -0,0 +1,49 @@
+use tracing::{event, instrument, Level};
+
+use database::{
+    aws_sdk_dynamodb::{error::ProvideErrorMetadata, types::ReturnValue},
+    ddb::{try_from_item, try_to_attribute_val, DDBService, DatabaseError},
+};
+
+use crate::native::entities::{session_partition_key, AuthSession};
+
+use super::{Repository, PARTITION_KEY};
+
+impl Repository {
+    /// Deletes and returns an auth session, so each challenge can only be answered once.
+    #[instrument(skip(self, session_id))]
+    pub(crate) async fn consume_session(
+        &self,
+        session_id: &str,
+    ) -> Result<AuthSession, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let output = self
+            .connection
+            .client
+            .delete_item()
+            .table_name(table_name)
+            .key(
+                PARTITION_KEY,
+                try_to_attribute_val(session_partition_key(session_id), database_object)?,
+            )
+            .return_values(ReturnValue::AllOld)
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not delete auth session: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::DeleteItemsError(database_object)
+            })?;
+
+        let item = output
+            .attributes
+            .ok_or(DatabaseError::ObjectNotFound(database_object))?;
+        try_from_item(item, database_object)
+    }
+}
@@ -0,0 +1,62 @@
+use serde::de::DeserializeOwned;
+use tracing::{event, instrument, Level};
+
+use database::aws_sdk_dynamodb::error::ProvideErrorMetadata;
+use database::ddb::{try_from_item, try_to_attribute_val, DDBService, DatabaseError};
+use types::authn_authz::cognito::CognitoUsername;
+
+use crate::native::entities::{
+    refresh_token_partition_key, user_partition_key, NativeUser, RefreshTokenRecord,
+};
+
+use super::{Repository, PARTITION_KEY};
+
+impl Repository {
+    #[instrument(skip(self))]
+    pub(crate) async fn fetch_user(
+        &self,
+        username: &CognitoUsername,
+    ) -> Result<NativeUser, DatabaseError> {
+        self.fetch(user_partition_key(username)).await
+    }
+
+    #[instrument(skip(self, refresh_token))]
+    pub(crate) async fn fetch_refresh_token(
+        &self,
+        refresh_token: &str,
+    ) -> Result<RefreshTokenRecord, DatabaseError> {
+        self.fetch(refresh_token_partition_key(refresh_token)).await
+    }
+
+    async fn fetch<T: DeserializeOwned>(&self, partition_key: String) -> Result<T, DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+
+        let item_output = self
+            .connection
+            .client
+            .get_item()
+            .table_name(table_name)
+            .key(
+                PARTITION_KEY,
+                try_to_attribute_val(partition_key, database_object)?,
+            )
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not query database: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::FetchError(database_object)
+            })?;
+
+        let item = item_output
+            .item
+            .ok_or(DatabaseError::ObjectNotFound(database_object))?;
+        try_from_item(item, database_object)
+    }
+}
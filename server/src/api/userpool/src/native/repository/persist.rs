@@ -0,0 +1,67 @@
+use serde::Serialize;
+use tracing::{event, instrument, Level};
+
+use database::{
+    aws_sdk_dynamodb::error::ProvideErrorMetadata,
+    ddb::{try_to_item, DDBService, DatabaseError},
+};
+
+use crate::native::entities::{AuthSession, NativeUser, RefreshTokenRecord};
+
+use super::{Repository, PARTITION_KEY};
+
+impl Repository {
+    /// Persists a new user, failing if one already exists with the same username.
+    #[instrument(skip(self, user))]
+    pub(crate) async fn create_user(&self, user: &NativeUser) -> Result<(), DatabaseError> {
+        self.put(user, Some(format!("attribute_not_exists({PARTITION_KEY})")))
+            .await
+    }
+
+    #[instrument(skip(self, user))]
+    pub(crate) async fn persist_user(&self, user: &NativeUser) -> Result<(), DatabaseError> {
+        self.put(user, None).await
+    }
+
+    #[instrument(skip(self, session))]
+    pub(crate) async fn persist_session(&self, session: &AuthSession) -> Result<(), DatabaseError> {
+        self.put(session, None).await
+    }
+
+    #[instrument(skip(self, record))]
+    pub(crate) async fn persist_refresh_token(
+        &self,
+        record: &RefreshTokenRecord,
+    ) -> Result<(), DatabaseError> {
+        self.put(record, None).await
+    }
+
+    async fn put<T: Serialize>(
+        &self,
+        value: &T,
+        condition_expression: Option<String>,
+    ) -> Result<(), DatabaseError> {
+        let table_name = self.get_table_name().await?;
+        let database_object = self.get_database_object();
+        let item = try_to_item(value, database_object)?;
+
+        self.connection
+            .client
+            .put_item()
+            .table_name(table_name)
+            .set_item(Some(item))
+            .set_condition_expression(condition_expression)
+            .send()
+            .await
+            .map_err(|err| {
+                let service_err = err.into_service_error();
+                event!(
+                    Level::ERROR,
+                    "Could not persist auth record: {service_err:?} with message: {:?}",
+                    service_err.message()
+                );
+                DatabaseError::PersistenceError(database_object)
+            })?;
+        Ok(())
+    }
+}
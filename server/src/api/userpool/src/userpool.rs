@@ -12,7 +12,10 @@ use aws_sdk_cognitoidentityprovider::operation::get_user::GetUserError;
 use aws_sdk_cognitoidentityprovider::types::{AttributeType, AuthFlowType, ChallengeNameType};
 use aws_sdk_cognitoidentityprovider::Client;
 use aws_types::SdkConfig;
+use database::ddb::{self, DatabaseError};
+use database::DBMode;
 use dyn_clone::DynClone;
+use jsonwebtoken::jwk::JwkSet;
 use rand::Rng;
 use secp256k1::ecdsa::Signature;
 use secp256k1::hashes::sha256;
@@ -27,6 +30,7 @@ use errors::ApiError;
 use types::account::PubkeysToAccount;
 use types::authn_authz::cognito::{CognitoUser, CognitoUsername};
 
+use crate::native::{NativeAuthConfig, NativeConnection};
 use crate::test_utils::get_test_access_token_for_cognito_user;
 use crate::userpool::UserPoolError::InitiateAuthError;
 
@@ -71,6 +75,10 @@ pub enum UserPoolError {
     InvalidChallengeResponse,
     #[error("Wrong user type")]
     WrongUserType,
+    #[error("Could not issue access token: {0}")]
+    IssueTokenError(String),
+    #[error(transparent)]
+    DatabaseError(#[from] DatabaseError),
 }
 
 impl From<UserPoolError> for ApiError {
@@ -110,11 +118,15 @@ impl From<UserPoolError> for ApiError {
 pub enum CognitoMode {
     Environment,
     Test,
+    /// Authenticate users and issue tokens from the server itself rather than through Cognito
+    Native,
 }
 
 #[derive(Deserialize)]
 pub struct Config {
     pub cognito: CognitoMode,
+    // Only used in native mode, which keeps users, sessions and refresh tokens in DynamoDB
+    pub dynamodb: Option<DBMode>,
 }
 
 impl Config {
@@ -122,6 +134,13 @@ impl Config {
         match self.cognito {
             CognitoMode::Environment => Box::new(CognitoConnection::new_from_env().await),
             CognitoMode::Test => Box::new(FakeCognitoConnection::new()),
+            CognitoMode::Native => {
+                let dynamodb = self
+                    .dynamodb
+                    .expect("dynamodb must be configured for native authentication");
+                let connection = ddb::Config { dynamodb }.to_connection().await;
+                Box::new(NativeConnection::new(connection, NativeAuthConfig::from_env()).await)
+            }
         }
     }
 }
@@ -188,6 +207,10 @@ pub trait CognitoIdpConnection: DynClone + Send + Sync {
         username: CognitoUsername,
     ) -> Result<(), UserPoolError>;
     async fn is_access_token_revoked(&self, access_token: String) -> Result<bool, UserPoolError>;
+    /// Keys that verify this pool's access tokens, when the server publishes them itself
+    fn jwks(&self) -> Option<JwkSet> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(CognitoIdpConnection);
@@ -794,6 +817,10 @@ impl UserPoolService {
             .is_access_token_revoked(access_token)
             .await
     }
+
+    pub fn jwks(&self) -> Option<JwkSet> {
+        self.cognito_client.jwks()
+    }
 }
 
 #[derive(Debug)]
@@ -1,21 +1,32 @@
-use account::service::FetchAccountInput;
-use bdk_utils::bdk::bitcoin::Address;
-use futures::stream::{FuturesUnordered, StreamExt};
-use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+use account::{error::AccountError, service::FetchAccountInput};
+use bdk_utils::bdk::bitcoin::{Address, Transaction, Txid};
+use chain_indexer::entities::PaymentNotificationKind;
+use database::ddb::DatabaseError;
 use notification::service::SendNotificationInput;
 use notification::{
-    payloads::payment::PaymentPayload, service::Service as NotificationService,
+    payloads::{
+        outgoing_payment::OutgoingPaymentPayload, payment::PaymentPayload,
+        pending_payment::PendingPaymentPayload, settled_payment::SettledPaymentPayload,
+    },
     NotificationPayloadBuilder, NotificationPayloadType,
 };
 use std::str::FromStr;
-use types::account::identifiers::AccountId;
+use types::{
+    account::identifiers::AccountId,
+    notification::{NotificationCategory, NotificationChannel},
+};
 
 use bdk_utils::bdk::bitcoin::address::NetworkUnchecked;
+use time::{Duration, OffsetDateTime};
 use tracing::{event, instrument, Level};
 
 use super::WorkerState;
 use crate::error::WorkerError;
 
+const WATCHLIST_SYNC_OVERLAP: Duration = Duration::minutes(5);
+
 #[instrument(skip(state))]
 pub async fn handler(state: &WorkerState, sleep_duration_seconds: u64) -> Result<(), WorkerError> {
     // TODO: We should schedule events to trigger the job rather than using an infinite poll-loop
@@ -27,123 +38,297 @@ pub async fn handler(state: &WorkerState, sleep_duration_seconds: u64) -> Result
         if let Err(e) = result {
             event!(Level::ERROR, "Failed to run blockchain polling job: {e}")
         }
-        tokio::time::sleep(sleep_duration).await;
+        // Backends that push block notifications wake us up early.
+        state
+            .chain_indexer_service
+            .wait_for_new_block(sleep_duration)
+            .await;
     }
 }
 
 pub async fn run_once(state: &WorkerState) -> Result<(), WorkerError> {
     event!(Level::INFO, "Starting blockchain polling job");
 
-    let blocks = state.chain_indexer_service.get_new_blocks().await?;
-    if blocks.is_empty() {
-        event!(Level::INFO, "No new blocks detected");
-        return Ok(());
+    if state.chain_indexer_service.requires_watchlist() {
+        // The whole watchlist is read once, then only the addresses added since the last sync.
+        // The window reaches back a little further, to allow for the index being eventually
+        // consistent and for clock drift between servers.
+        let synced_at = OffsetDateTime::now_utc();
+        let addresses = match state.chain_indexer_service.watchlist_synced_at() {
+            Some(last_synced_at) => {
+                state
+                    .address_repo
+                    .fetch_added_since(last_synced_at - WATCHLIST_SYNC_OVERLAP)
+                    .await?
+            }
+            None => state.address_repo.fetch_all().await?,
+        };
+        state
+            .chain_indexer_service
+            .watch_addresses(&addresses, synced_at)
+            .await?;
     }
+
+    let blocks = state.chain_indexer_service.get_new_blocks().await?;
     event!(Level::INFO, "{} blocks found", blocks.len());
-    // We update state here to avoid sending duplicate notifications if the job crashes,
-    // however, this could result in missed notifications in that case. We plan on updating
-    // this job with a cursor so that it can resume where it left off.
+    // The cursor only moves past a block once its notifications have been sent, so a crash
+    // replays the block rather than skipping it. Replayed payments aren't notified twice, since
+    // each notification is claimed per transaction before it's sent. A payment that can't be
+    // notified is logged and left unclaimed, rather than holding up the rest of the block.
     for block in &blocks {
-        state.chain_indexer_service.add_block(block).await?;
+        notify_payments(state, &block.txdata, PaymentNotificationKind::Confirmed).await?;
+        notify_outgoing_payments(state, &block.txdata).await?;
+        let settled_transactions = state
+            .chain_indexer_service
+            .get_settled_transactions(block)
+            .await?;
+        notify_payments(
+            state,
+            &settled_transactions,
+            PaymentNotificationKind::Settled,
+        )
+        .await?;
+        state.chain_indexer_service.advance_cursor(block).await?;
     }
-    event!(Level::INFO, "{} blocks added", blocks.len());
+    event!(Level::INFO, "{} blocks processed", blocks.len());
 
-    let addresses: Vec<Address<NetworkUnchecked>> = blocks
-        .into_iter()
-        .flat_map(|block| block.txdata)
-        .flat_map(|transaction| transaction.output)
-        .unique()
-        .filter_map(|output| {
+    // Pending notifications are best-effort, so they don't hold up confirmed ones.
+    match state
+        .chain_indexer_service
+        .get_new_mempool_transactions()
+        .await
+    {
+        Ok(transactions) => {
+            if let Err(e) =
+                notify_payments(state, &transactions, PaymentNotificationKind::Pending).await
+            {
+                event!(Level::ERROR, "Unable to process mempool transactions: {e}");
+            }
+        }
+        Err(e) => event!(Level::ERROR, "Unable to retrieve mempool transactions: {e}"),
+    }
+
+    event!(Level::INFO, "Ending blockchain polling job");
+    Ok(())
+}
+
+async fn notify_payments(
+    state: &WorkerState,
+    transactions: &[Transaction],
+    kind: PaymentNotificationKind,
+) -> Result<(), WorkerError> {
+    let mut txids_by_address: HashMap<Address<NetworkUnchecked>, HashSet<Txid>> = HashMap::new();
+    for transaction in transactions {
+        let txid = transaction.txid();
+        for output in &transaction.output {
             if output.script_pubkey.is_op_return() {
-                None
-            } else {
-                match Address::from_script(
-                    &output.script_pubkey,
-                    state.chain_indexer_service.network(),
-                ) {
-                    Ok(address) => {
-                        // `from_script` returns an Address with `NetworkChecked`. Here, we want one
-                        // with `NetworkUnchecked`.
-                        // [W-5648]: Use `as_unchecked` once it's available in BDK.
-                        let addr_string = address.to_string();
-                        let address = Address::from_str(&addr_string).unwrap();
-                        Some(address)
-                    }
-                    Err(_) => {
-                        event!(
-                            Level::ERROR,
-                            "Unable to parse address from script for output: {:?}",
-                            output
-                        );
-                        None
-                    }
+                continue;
+            }
+            match Address::from_script(&output.script_pubkey, state.chain_indexer_service.network())
+            {
+                Ok(address) => {
+                    // `from_script` returns an Address with `NetworkChecked`. Here, we want one
+                    // with `NetworkUnchecked`.
+                    // [W-5648]: Use `as_unchecked` once it's available in BDK.
+                    let addr_string = address.to_string();
+                    let address = Address::from_str(&addr_string).unwrap();
+                    txids_by_address.entry(address).or_default().insert(txid);
+                }
+                Err(_) => {
+                    event!(
+                        Level::ERROR,
+                        "Unable to parse address from script for output: {:?}",
+                        output
+                    );
                 }
             }
-        })
-        .unique()
-        .collect();
-    event!(Level::INFO, "{} addresses found in blocks", addresses.len());
+        }
+    }
+    if txids_by_address.is_empty() {
+        return Ok(());
+    }
+    let addresses: Vec<Address<NetworkUnchecked>> = txids_by_address.keys().cloned().collect();
+    event!(Level::INFO, "{} addresses found", addresses.len());
 
-    let account_ids: Vec<AccountId> = state
+    let mut payments: HashSet<(AccountId, Txid)> = state
         .address_repo
         .get(&addresses)
         .await?
-        .values()
-        .cloned()
-        .unique()
+        .into_iter()
+        .flat_map(|(address, account_id)| {
+            txids_by_address
+                .get(&address)
+                .into_iter()
+                .flatten()
+                .map(move |txid| (account_id.clone(), *txid))
+        })
         .collect();
+
+    // A payment sent by the account pays its own change address, which it shouldn't be notified
+    // of as money received.
+    let txids: Vec<Txid> = payments
+        .iter()
+        .map(|(_, txid)| *txid)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let outgoing_payments = state
+        .chain_indexer_service
+        .get_outgoing_payments(&txids)
+        .await?;
+    payments.retain(|(account_id, txid)| outgoing_payments.get(txid) != Some(account_id));
+    event!(Level::INFO, "{} {kind} payments found", payments.len());
+
+    for (account_id, txid) in payments {
+        if let Err(e) = notify_payment(state, &account_id, &txid, kind).await {
+            event!(
+                Level::ERROR,
+                "Unable to notify account {account_id} of {kind} payment {txid}: {e}"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn notify_outgoing_payments(
+    state: &WorkerState,
+    transactions: &[Transaction],
+) -> Result<(), WorkerError> {
+    let txids: Vec<Txid> = transactions.iter().map(Transaction::txid).collect();
+    let outgoing_payments = state
+        .chain_indexer_service
+        .get_outgoing_payments(&txids)
+        .await?;
     event!(
         Level::INFO,
-        "{} accounts found with payments",
-        account_ids.len()
+        "{} outgoing payments found",
+        outgoing_payments.len()
     );
-    let mut futures = FuturesUnordered::new();
-    for account_id in account_ids {
-        futures.push(process_account_id(account_id, state));
-    }
-    while let Some(result) = futures.next().await {
-        if let Err(e) = result {
-            event!(Level::ERROR, "Unable to send push notification {e}");
+
+    for (txid, account_id) in outgoing_payments {
+        let kind = PaymentNotificationKind::OutgoingConfirmed;
+        if let Err(e) = notify_payment(state, &account_id, &txid, kind).await {
+            event!(
+                Level::ERROR,
+                "Unable to notify account {account_id} of {kind} payment {txid}: {e}"
+            );
         }
     }
 
-    event!(Level::INFO, "Ending blockchain polling job");
     Ok(())
 }
 
-async fn process_account_id(account_id: AccountId, state: &WorkerState) -> Result<(), WorkerError> {
-    let account = state
+async fn notify_payment(
+    state: &WorkerState,
+    account_id: &AccountId,
+    txid: &Txid,
+    kind: PaymentNotificationKind,
+) -> Result<(), WorkerError> {
+    let account = match state
         .account_service
-        .fetch_account(FetchAccountInput {
-            account_id: &account_id,
-        })
+        .fetch_account(FetchAccountInput { account_id })
         .await
-        .map_err(|e| WorkerError::AccountErrorWithId(account_id.clone(), e))?;
+    {
+        Ok(account) => account,
+        Err(AccountError::DDBError(DatabaseError::ObjectNotFound(_))) => {
+            event!(
+                Level::WARN,
+                "Skipping payment {txid} to unknown account {account_id}"
+            );
+            return Ok(());
+        }
+        Err(e) => return Err(WorkerError::AccountErrorWithId(account_id.clone(), e)),
+    };
 
-    // Dont send a push notification if one isn't registered
-    account
-        .get_push_touchpoint()
-        .ok_or_else(|| WorkerError::TouchpointNotFound(account_id.clone()))?;
+    // Payment notifications are push-only, so skip accounts that can't receive them or have
+    // opted out. These aren't claimed, so later stages are still sent if that changes.
+    let push_enabled = account
+        .get_common_fields()
+        .notifications_preferences
+        .is_enabled(
+            NotificationCategory::MoneyMovement,
+            NotificationChannel::Push,
+        );
+    if account.get_push_touchpoint().is_none() || !push_enabled {
+        return Ok(());
+    }
+
+    if !state
+        .chain_indexer_service
+        .claim_payment_notification(account_id, txid, kind)
+        .await?
+    {
+        event!(
+            Level::INFO,
+            "Already notified account {account_id} of {kind} payment {txid}"
+        );
+        return Ok(());
+    }
+
+    if let Err(e) = send_payment_notification(state, account_id, txid, kind).await {
+        state
+            .chain_indexer_service
+            .release_payment_notification(account_id, txid, kind)
+            .await?;
+        return Err(e);
+    }
 
-    send_new_tx_notifications(&account_id, &state.notification_service).await
+    Ok(())
 }
 
-async fn send_new_tx_notifications(
+async fn send_payment_notification(
+    state: &WorkerState,
     account_id: &AccountId,
-    service: &NotificationService,
+    txid: &Txid,
+    kind: PaymentNotificationKind,
 ) -> Result<(), WorkerError> {
-    event!(Level::INFO, "Sending notification for account {account_id}");
+    event!(
+        Level::INFO,
+        "Sending {kind} payment notification for account {account_id}"
+    );
 
-    let payload = NotificationPayloadBuilder::default()
-        .payment_payload(Some(PaymentPayload {
-            account_id: account_id.clone(),
-        }))
-        .build()?;
+    let mut builder = NotificationPayloadBuilder::default();
+    let payload_type = match kind {
+        PaymentNotificationKind::Confirmed => {
+            builder.payment_payload(Some(PaymentPayload {
+                account_id: account_id.clone(),
+            }));
+            NotificationPayloadType::PaymentNotification
+        }
+        PaymentNotificationKind::Pending => {
+            builder.pending_payment_payload(Some(PendingPaymentPayload {
+                account_id: account_id.clone(),
+                txid: txid.to_string(),
+            }));
+            NotificationPayloadType::PendingPaymentNotification
+        }
+        PaymentNotificationKind::Settled => {
+            builder.settled_payment_payload(Some(SettledPaymentPayload {
+                account_id: account_id.clone(),
+                txid: txid.to_string(),
+                confirmations: state
+                    .chain_indexer_service
+                    .confirmation_depth()
+                    .unwrap_or_default(),
+            }));
+            NotificationPayloadType::SettledPaymentNotification
+        }
+        PaymentNotificationKind::OutgoingConfirmed => {
+            builder.outgoing_payment_payload(Some(OutgoingPaymentPayload {
+                account_id: account_id.clone(),
+                txid: txid.to_string(),
+            }));
+            NotificationPayloadType::OutgoingPaymentNotification
+        }
+    };
+    let payload = builder.build()?;
 
-    service
+    state
+        .notification_service
         .send_notification(SendNotificationInput {
             account_id,
-            payload_type: NotificationPayloadType::PaymentNotification,
+            payload_type,
             payload: &payload,
             only_touchpoints: None,
         })
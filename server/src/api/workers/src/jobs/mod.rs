@@ -7,6 +7,7 @@ use notification::service::Service as NotificationService;
 use notification_validation::NotificationValidationState;
 use queue::sqs::SqsQueue;
 use recovery::repository::Repository as RecoveryRepository;
+use repository::recovery::social::Repository as SocialRecoveryRepository;
 use serde::Deserialize;
 
 use crate::{ses::SESMode, sns::SNSMode};
@@ -31,6 +32,7 @@ pub struct WorkerState {
     pub notification_service: NotificationService,
     pub account_service: AccountService,
     pub recovery_service: RecoveryRepository,
+    pub social_recovery_repository: SocialRecoveryRepository,
     pub chain_indexer_service: ChainIndexerService,
     pub address_repo: Box<dyn AddressWatchlistTrait>,
     pub sqs: SqsQueue,
@@ -39,6 +41,6 @@ pub struct WorkerState {
 
 impl From<WorkerState> for NotificationValidationState {
     fn from(value: WorkerState) -> Self {
-        NotificationValidationState::new(value.recovery_service)
+        NotificationValidationState::new(value.recovery_service, value.social_recovery_repository)
     }
 }
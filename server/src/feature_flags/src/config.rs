@@ -15,6 +15,9 @@ struct Asset;
 pub struct Config {
     launchdarkly: Mode,
     feature_flag_overrides: Option<OverrideMode>,
+    // Applied over `feature_flag_overrides`
+    #[serde(skip)]
+    extra_overrides: HashMap<String, String>,
 }
 
 impl Config {
@@ -22,8 +25,16 @@ impl Config {
         Self {
             launchdarkly: Mode::Test,
             feature_flag_overrides: Some(OverrideMode::Object(overrides)),
+            extra_overrides: HashMap::new(),
         }
     }
+
+    /// Overrides flags on top of the ones the config already overrides, so that e.g. a test only
+    /// has to set the flags it's about.
+    pub fn with_overrides(mut self, overrides: HashMap<String, String>) -> Self {
+        self.extra_overrides.extend(overrides);
+        self
+    }
 }
 
 #[derive(Deserialize)]
@@ -59,11 +70,12 @@ impl Config {
             return Err(Error::Initialize("see logs for details".to_string()));
         }
 
-        let overrides: HashMap<String, String> = match self.feature_flag_overrides {
+        let mut overrides: HashMap<String, String> = match self.feature_flag_overrides {
             Some(OverrideMode::File(file_name)) => parse_toml_overrides(file_name)?,
             Some(OverrideMode::Object(map)) => map,
             _ => HashMap::new(),
         };
+        overrides.extend(self.extra_overrides);
 
         Ok(Service::new(client, overrides))
     }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::Context;
 use aws_sdk_dynamodb::client::Client as DdbClient;
 use aws_sdk_dynamodb::types::AttributeValue;
@@ -136,6 +138,93 @@ impl CustomerKeyStore {
         Ok(customer_keys)
     }
 
+    /// Read one page of customer keys, starting after `start_after`. Returns the root key ID to
+    /// carry on from, or `None` once the table has been read to the end.
+    #[instrument(skip(self))]
+    pub async fn get_customer_keys_page(
+        &self,
+        start_after: Option<&str>,
+    ) -> anyhow::Result<(Vec<CustomerKey>, Option<String>)> {
+        let scan_output = self
+            .client
+            .scan()
+            .table_name(&self.ck_table_name)
+            .set_exclusive_start_key(start_after.map(|root_key_id| {
+                HashMap::from([(
+                    "root_key_id".to_string(),
+                    AttributeValue::S(root_key_id.to_string()),
+                )])
+            }))
+            .send()
+            .await?;
+
+        let customer_keys = scan_output
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .map(|item| from_item(item).context("Unable to parse database object to CustomerKey"))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let next = scan_output
+            .last_evaluated_key
+            .and_then(|key| key.get("root_key_id")?.as_s().ok().cloned());
+
+        Ok((customer_keys, next))
+    }
+
+    /// Store a customer key that has been re-wrapped under a new DEK. The write only goes through
+    /// if the key is still wrapped under `previous_dek_id`.
+    #[instrument(skip(self, customer_key), fields(root_key_id = customer_key.root_key_id))]
+    pub async fn update_wrapped_key(
+        &self,
+        customer_key: &CustomerKey,
+        previous_dek_id: &str,
+    ) -> anyhow::Result<()> {
+        let mut expression_attribute_values = HashMap::from([
+            (
+                ":key_ciphertext".to_string(),
+                AttributeValue::S(customer_key.key_ciphertext.clone()),
+            ),
+            (
+                ":key_nonce".to_string(),
+                AttributeValue::S(customer_key.key_nonce.clone()),
+            ),
+            (
+                ":dek_id".to_string(),
+                AttributeValue::S(customer_key.dek_id.clone()),
+            ),
+            (
+                ":previous_dek_id".to_string(),
+                AttributeValue::S(previous_dek_id.to_string()),
+            ),
+        ]);
+        let mut update_expression =
+            "SET key_ciphertext = :key_ciphertext, key_nonce = :key_nonce, dek_id = :dek_id"
+                .to_string();
+        if let Some(signature) = &customer_key.integrity_signature {
+            update_expression.push_str(", integrity_signature = :integrity_signature");
+            expression_attribute_values.insert(
+                ":integrity_signature".to_string(),
+                AttributeValue::S(signature.clone()),
+            );
+        }
+
+        self.client
+            .update_item()
+            .table_name(&self.ck_table_name)
+            .key(
+                "root_key_id",
+                AttributeValue::S(customer_key.root_key_id.clone()),
+            )
+            .update_expression(update_expression)
+            .condition_expression("dek_id = :previous_dek_id")
+            .set_expression_attribute_values(Some(expression_attribute_values))
+            .send()
+            .await
+            .context("Failed to update re-wrapped customer key in DynamoDB")?;
+
+        Ok(())
+    }
+
     pub async fn update_integrity_signature(
         &self,
         root_key_id: &str,
@@ -143,7 +232,7 @@ impl CustomerKeyStore {
     ) -> anyhow::Result<()> {
         let update_expression = "SET integrity_signature = :new_signature";
 
-        let expression_attribute_values = std::collections::HashMap::from([(
+        let expression_attribute_values = HashMap::from([(
             ":new_signature".to_string(),
             AttributeValue::S(new_signature.to_string()),
         )]);
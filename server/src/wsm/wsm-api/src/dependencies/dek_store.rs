@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, Context};
 use aws_sdk_dynamodb::client::Client as DdbClient;
@@ -24,6 +26,10 @@ const MAX_DEK_USES: u32 = 2_000_000;
 /// is up.
 const LEASE_SIZE: u32 = 50;
 
+/// Leases also expire after this long, so that a server holding a lease on a DEK that a rotation
+/// has since retired moves off it. Rotations wait this long before checking that they're done.
+pub(crate) const LEASE_TTL: Duration = Duration::from_secs(300);
+
 type WrappedDekCache = HashMap<String, String>;
 
 #[derive(Clone, Debug)]
@@ -31,6 +37,7 @@ struct LeasedDek {
     dek_id: String,
     dek_ciphertext: String,
     remaining_uses: u32,
+    leased_at: Instant,
 }
 
 #[derive(Debug)]
@@ -104,6 +111,16 @@ impl DekStore {
     /// Note: We are *not* trying to prevent two servers from using the same DEK. That's OK!
     #[instrument(skip(self))]
     async fn get_or_create_dek(&self) -> anyhow::Result<LeasedDek> {
+        loop {
+            if let Some(dek) = self.lease_available_dek().await? {
+                return Ok(dek);
+            }
+        }
+    }
+
+    /// Lease a DEK with `isAvailable` set to 1, creating one if there isn't any. Returns `None` if the
+    /// DEK we found stopped being available (e.g. a rotation retired it) before we could lease it.
+    async fn lease_available_dek(&self) -> anyhow::Result<Option<LeasedDek>> {
         // Look for any key that has `isAvailable` set to 1.
         let available_key_qo = self
             .ddb
@@ -122,20 +139,7 @@ impl DekStore {
             None => {
                 // No available key found in DDB, create a new one
                 event!(Level::INFO, "No DEK available for use");
-                let dek_ciphertext = BASE64.encode(
-                    self.kms
-                        .generate_data_key_without_plaintext()
-                        .set_key_id(Some(self.kms_cmk_id.clone()))
-                        .key_spec(DataKeySpec::Aes256)
-                        .send()
-                        .await
-                        .context("could not call KMS to generate fresh data key")?
-                        .ciphertext_blob
-                        .unwrap()
-                        .into_inner(),
-                );
-                // Pick a random dek_id
-                let dek_id = Ulid::new().to_string();
+                let (dek_id, dek_ciphertext) = self.generate_dek().await?;
                 // Load the new key into DDB
                 event!(Level::INFO, "Inserting new DEK with ID {} into DDB", dek_id);
                 self.ddb
@@ -148,11 +152,12 @@ impl DekStore {
                     .send()
                     .await
                     .context("could not write new DEK to DDB")?;
-                Ok(LeasedDek {
+                Ok(Some(LeasedDek {
                     dek_id,
                     dek_ciphertext,
                     remaining_uses: LEASE_SIZE,
-                })
+                    leased_at: Instant::now(),
+                }))
             }
             Some(item) => {
                 let dek_id = item
@@ -172,12 +177,17 @@ impl DekStore {
                     .ok_or("No wrapped key found")
                     .map_err(|e| anyhow!(e))?;
                 event!(Level::DEBUG, "Fetched DEK ID {} from DDB", dek_id);
-                self.ddb
+                // The condition stops us from making a DEK available again after a rotation has
+                // taken it out of use, or from leasing a retired DEK.
+                let update_result = self
+                    .ddb
                     .update_item()
                     .table_name(&self.dek_table_name)
                     .key("dek_id", AttributeValue::S(dek_id.to_string()))
                     .update_expression("SET usage_count = usage_count + :u, isAvailable = :a")
+                    .condition_expression("isAvailable = :t AND attribute_not_exists(isRetired)")
                     .expression_attribute_values(":u", AttributeValue::N(LEASE_SIZE.to_string()))
+                    .expression_attribute_values(":t", AttributeValue::N("1".to_string()))
                     .expression_attribute_values(
                         ":a",
                         AttributeValue::N(
@@ -197,20 +207,62 @@ impl DekStore {
                         ),
                     )
                     .send()
-                    .await
-                    .context("could not updated dek record in ddb")?;
-                Ok(LeasedDek {
+                    .await;
+                if let Err(err) = update_result {
+                    if err
+                        .as_service_error()
+                        .is_some_and(|e| e.is_conditional_check_failed_exception())
+                    {
+                        event!(
+                            Level::INFO,
+                            "DEK ID {} stopped being available before it could be leased",
+                            dek_id
+                        );
+                        return Ok(None);
+                    }
+                    return Err(err).context("could not updated dek record in ddb");
+                }
+                Ok(Some(LeasedDek {
                     dek_id: dek_id.to_string(),
                     dek_ciphertext: safe_get_str(&dek_record, "dek_ciphertext")?,
                     remaining_uses: LEASE_SIZE,
-                })
+                    leased_at: Instant::now(),
+                }))
             }
         }
     }
 
+    /// Mint a new DEK under the KMS CMK, returning a fresh ID and the base64-encoded wrapped key.
+    async fn generate_dek(&self) -> anyhow::Result<(String, String)> {
+        let dek_ciphertext = BASE64.encode(
+            self.kms
+                .generate_data_key_without_plaintext()
+                .set_key_id(Some(self.kms_cmk_id.clone()))
+                .key_spec(DataKeySpec::Aes256)
+                .send()
+                .await
+                .context("could not call KMS to generate fresh data key")?
+                .ciphertext_blob
+                .context("KMS response is missing the ciphertext blob")?
+                .into_inner(),
+        );
+        // Pick a random dek_id
+        Ok((Ulid::new().to_string(), dek_ciphertext))
+    }
+
     #[instrument(skip(self))]
     pub async fn get_availabile_dek_id(&self) -> anyhow::Result<String> {
         let mut current_dek = self.current_dek.lock().await;
+        if current_dek
+            .as_ref()
+            .is_some_and(|dek| dek.leased_at.elapsed() >= LEASE_TTL)
+        {
+            event!(
+                Level::DEBUG,
+                "API-Keystore: current DEK lease has expired. invalidating current DEK"
+            );
+            *current_dek = None;
+        }
         if current_dek.is_none() {
             event!(
                 Level::DEBUG,
@@ -231,3 +283,273 @@ impl DekStore {
         Ok(dek_id)
     }
 }
+
+const ROTATION_IN_PROGRESS: &str = "in_progress";
+const ROTATION_COMPLETE: &str = "complete";
+
+/// Progress of a DEK rotation. It lives on the new DEK's record in DDB so that a rotation that gets
+/// interrupted (or is deliberately done in batches) carries on where it stopped.
+#[derive(Clone, Debug)]
+pub struct DekRotation {
+    /// The DEK customer keys are being moved to
+    pub dek_id: String,
+    /// Root key ID of the last customer key handled in the current pass, if the pass is underway
+    pub cursor: Option<String>,
+    /// Customer keys re-wrapped over the whole rotation
+    pub rewrapped: u64,
+    /// Customer keys re-wrapped in the current pass. Other API servers may still be leasing an old
+    /// DEK when the rotation starts, so it only finishes after a full pass re-wraps nothing.
+    pub pass_rewrapped: u64,
+    /// When the rotation started, in seconds since the Unix epoch
+    pub started_at: u64,
+    /// When the current pass started, in seconds since the Unix epoch
+    pub pass_started_at: u64,
+}
+
+impl DekRotation {
+    /// Whether every lease on an old DEK had expired by the time the current pass started, so that
+    /// a pass that re-wraps nothing means that no customer key is left on an old DEK.
+    pub fn old_leases_expired(&self) -> bool {
+        self.pass_started_at >= self.started_at + LEASE_TTL.as_secs()
+    }
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after the Unix epoch")
+        .as_secs()
+}
+
+impl DekStore {
+    #[instrument(skip(self))]
+    pub async fn get_rotation_in_progress(&self) -> anyhow::Result<Option<DekRotation>> {
+        let mut last_evaluated_key = None;
+        loop {
+            let scan_output = self
+                .ddb
+                .scan()
+                .table_name(&self.dek_table_name)
+                .filter_expression("rotation_status = :s")
+                .expression_attribute_values(
+                    ":s",
+                    AttributeValue::S(ROTATION_IN_PROGRESS.to_string()),
+                )
+                .set_exclusive_start_key(last_evaluated_key)
+                .send()
+                .await
+                .context("could not scan DEK table for rotations")?;
+
+            if let Some(item) = scan_output.items().first() {
+                return Ok(Some(DekRotation {
+                    dek_id: safe_get_str(item, "dek_id")?,
+                    cursor: safe_get_str(item, "rotation_cursor").ok(),
+                    rewrapped: safe_get_n(item, "rotation_rewrapped")?,
+                    pass_rewrapped: safe_get_n(item, "rotation_pass_rewrapped")?,
+                    started_at: safe_get_n(item, "rotation_started_at")?,
+                    pass_started_at: safe_get_n(item, "rotation_pass_started_at")?,
+                }));
+            }
+
+            last_evaluated_key = scan_output.last_evaluated_key;
+            if last_evaluated_key.is_none() {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Mint a DEK to rotate to and stop handing out every other DEK, so that new customer keys are
+    /// wrapped under the new one.
+    #[instrument(skip(self))]
+    pub async fn start_rotation(&self) -> anyhow::Result<DekRotation> {
+        let (dek_id, dek_ciphertext) = self.generate_dek().await?;
+        let started_at = now_secs();
+        event!(
+            Level::INFO,
+            "Starting rotation to new DEK with ID {}",
+            dek_id
+        );
+        self.ddb
+            .put_item()
+            .table_name(&self.dek_table_name)
+            .item("dek_id", AttributeValue::S(dek_id.clone()))
+            .item("dek_ciphertext", AttributeValue::S(dek_ciphertext))
+            .item("usage_count", AttributeValue::N("0".to_string()))
+            .item("isAvailable", AttributeValue::N("1".to_string()))
+            .item(
+                "rotation_status",
+                AttributeValue::S(ROTATION_IN_PROGRESS.to_string()),
+            )
+            .item("rotation_rewrapped", AttributeValue::N("0".to_string()))
+            .item(
+                "rotation_pass_rewrapped",
+                AttributeValue::N("0".to_string()),
+            )
+            .item(
+                "rotation_started_at",
+                AttributeValue::N(started_at.to_string()),
+            )
+            .item(
+                "rotation_pass_started_at",
+                AttributeValue::N(started_at.to_string()),
+            )
+            .send()
+            .await
+            .context("could not write new DEK to DDB")?;
+
+        for old_dek_id in self.get_available_dek_ids().await? {
+            if old_dek_id != dek_id {
+                self.ddb
+                    .update_item()
+                    .table_name(&self.dek_table_name)
+                    .key("dek_id", AttributeValue::S(old_dek_id))
+                    .update_expression("SET isAvailable = :a")
+                    .expression_attribute_values(":a", AttributeValue::N("0".to_string()))
+                    .send()
+                    .await
+                    .context("could not mark old DEK unavailable")?;
+            }
+        }
+        // Drop our own lease so this server moves to the new DEK straight away
+        *self.current_dek.lock().await = None;
+
+        Ok(DekRotation {
+            dek_id,
+            cursor: None,
+            rewrapped: 0,
+            pass_rewrapped: 0,
+            started_at,
+            pass_started_at: started_at,
+        })
+    }
+
+    /// Record rotation progress. `newly_rewrapped` keys are also counted as uses of the new DEK.
+    #[instrument(skip(self))]
+    pub async fn save_rotation(
+        &self,
+        rotation: &DekRotation,
+        newly_rewrapped: u64,
+    ) -> anyhow::Result<()> {
+        let mut update = self
+            .ddb
+            .update_item()
+            .table_name(&self.dek_table_name)
+            .key("dek_id", AttributeValue::S(rotation.dek_id.clone()))
+            .expression_attribute_values(":r", AttributeValue::N(rotation.rewrapped.to_string()))
+            .expression_attribute_values(
+                ":p",
+                AttributeValue::N(rotation.pass_rewrapped.to_string()),
+            )
+            .expression_attribute_values(
+                ":s",
+                AttributeValue::N(rotation.pass_started_at.to_string()),
+            )
+            .expression_attribute_values(":u", AttributeValue::N(newly_rewrapped.to_string()));
+        let set = "SET rotation_rewrapped = :r, rotation_pass_rewrapped = :p, rotation_pass_started_at = :s, usage_count = usage_count + :u";
+        update = match &rotation.cursor {
+            Some(cursor) => update
+                .update_expression(format!("{set}, rotation_cursor = :c"))
+                .expression_attribute_values(":c", AttributeValue::S(cursor.clone())),
+            None => update.update_expression(format!("{set} REMOVE rotation_cursor")),
+        };
+        update
+            .send()
+            .await
+            .context("could not save DEK rotation progress")?;
+        Ok(())
+    }
+
+    /// Finish a rotation once no customer key is wrapped under an old DEK, retiring the old DEKs.
+    #[instrument(skip(self))]
+    pub async fn complete_rotation(&self, rotation: &DekRotation) -> anyhow::Result<()> {
+        let mut last_evaluated_key = None;
+        loop {
+            let scan_output = self
+                .ddb
+                .scan()
+                .table_name(&self.dek_table_name)
+                .projection_expression("dek_id")
+                .set_exclusive_start_key(last_evaluated_key)
+                .send()
+                .await
+                .context("could not scan DEK table")?;
+
+            for item in scan_output.items() {
+                let dek_id = safe_get_str(item, "dek_id")?;
+                if dek_id != rotation.dek_id {
+                    self.ddb
+                        .update_item()
+                        .table_name(&self.dek_table_name)
+                        .key("dek_id", AttributeValue::S(dek_id))
+                        .update_expression("SET isAvailable = :a, isRetired = :r")
+                        .expression_attribute_values(":a", AttributeValue::N("0".to_string()))
+                        .expression_attribute_values(":r", AttributeValue::N("1".to_string()))
+                        .send()
+                        .await
+                        .context("could not retire old DEK")?;
+                }
+            }
+
+            last_evaluated_key = scan_output.last_evaluated_key;
+            if last_evaluated_key.is_none() {
+                break;
+            }
+        }
+
+        self.ddb
+            .update_item()
+            .table_name(&self.dek_table_name)
+            .key("dek_id", AttributeValue::S(rotation.dek_id.clone()))
+            .update_expression(
+                "SET rotation_status = :s, rotation_rewrapped = :r REMOVE rotation_cursor, rotation_pass_rewrapped, rotation_pass_started_at",
+            )
+            .expression_attribute_values(":s", AttributeValue::S(ROTATION_COMPLETE.to_string()))
+            .expression_attribute_values(":r", AttributeValue::N(rotation.rewrapped.to_string()))
+            .send()
+            .await
+            .context("could not mark DEK rotation complete")?;
+        event!(
+            Level::INFO,
+            "Rotation to DEK {} complete after re-wrapping {} keys",
+            rotation.dek_id,
+            rotation.rewrapped
+        );
+        Ok(())
+    }
+
+    async fn get_available_dek_ids(&self) -> anyhow::Result<Vec<String>> {
+        let mut dek_ids = Vec::new();
+        let mut last_evaluated_key = None;
+        loop {
+            let query_output = self
+                .ddb
+                .query()
+                .table_name(&self.dek_table_name)
+                .index_name("availableKeysIdx")
+                .key_condition_expression("#hk = :t")
+                .expression_attribute_names("#hk", "isAvailable")
+                .expression_attribute_values(":t", AttributeValue::N("1".to_string()))
+                .set_exclusive_start_key(last_evaluated_key)
+                .send()
+                .await
+                .context("could not query DEK table")?;
+            for item in query_output.items() {
+                dek_ids.push(safe_get_str(item, "dek_id")?);
+            }
+
+            last_evaluated_key = query_output.last_evaluated_key;
+            if last_evaluated_key.is_none() {
+                return Ok(dek_ids);
+            }
+        }
+    }
+}
+
+fn safe_get_n<T: FromStr>(item: &HashMap<String, AttributeValue>, k: &str) -> anyhow::Result<T> {
+    item.get(k)
+        .ok_or(anyhow!(format!("could not get key {k} from ddb item")))?
+        .as_n()
+        .map_err(|value| anyhow!("could not return {:?} as number", value))?
+        .parse()
+        .map_err(|_| anyhow!("could not parse {k} as a number"))
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use anyhow::{anyhow, bail};
 use aws_credential_types::provider::{ProvideCredentials, SharedCredentialsProvider};
 use aws_credential_types::Credentials;
@@ -6,19 +8,19 @@ use aws_types::SdkConfig;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use log::{log, Level};
 use reqwest::Response;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json;
 use thiserror::Error;
 use tracing::{event, instrument};
 
 use wsm_common::enclave_log::LogBuffer;
 use wsm_common::messages::enclave::{
-    DerivedKey, EnclaveCreateKeyRequest, EnclaveDeriveKeyRequest,
-    EnclaveSignWithIntegrityKeyRequest, EnclaveSignWithIntegrityKeyResponse,
-    LoadIntegrityKeyRequest,
+    DerivedKey, EnclaveCreateKeyRequest, EnclaveDeriveKeyRequest, EnclaveRewrapKeyRequest,
+    EnclaveSignMessageRequest, EnclaveSignWithIntegrityKeyRequest,
+    EnclaveSignWithIntegrityKeyResponse, LoadIntegrityKeyRequest, RewrappedKey,
 };
 use wsm_common::messages::{
-    api::SignedPsbt,
+    api::{SignedMessage, SignedPsbt},
     enclave::{CreatedKey, EnclaveSignRequest, KmsRequest, LoadSecretRequest},
     SecretRequest,
 };
@@ -28,6 +30,12 @@ use crate::{DekStore, Settings};
 
 const PROD_WRAPPED_INTEGRITY_KEY_B64: &str = include_str!("../../../keys/prod_integrity_key.b64");
 
+/// Body of the enclave's 404 response when a DEK isn't loaded
+#[derive(Deserialize)]
+struct MissingDek {
+    dek_id: String,
+}
+
 #[derive(Error, Debug)]
 pub enum EnclaveClientError {
     #[error("Could not load kms credentials: {0}")]
@@ -76,11 +84,15 @@ pub struct EnclaveClient {
     endpoint: reqwest::Url,
     client: reqwest::Client,
     kms_config: Option<KmsConfig>,
-    dek_store: DekStore,
+    dek_store: Arc<DekStore>,
 }
 
 impl EnclaveClient {
-    pub fn new(dek_store: DekStore, kms_config: Option<KmsConfig>, settings: &Settings) -> Self {
+    pub fn new(
+        dek_store: Arc<DekStore>,
+        kms_config: Option<KmsConfig>,
+        settings: &Settings,
+    ) -> Self {
         EnclaveClient {
             endpoint: reqwest::Url::try_from(settings.enclave_endpoint.as_str()).unwrap(),
             client: reqwest::Client::new(),
@@ -121,6 +133,25 @@ impl EnclaveClient {
         Ok(result.json().await?)
     }
 
+    #[instrument(skip(self))]
+    pub async fn sign_message(
+        &self,
+        req: EnclaveSignMessageRequest,
+    ) -> anyhow::Result<SignedMessage> {
+        let result = self
+            .post_request_with_dek(SecretRequest::new("sign-message", req.dek_id.clone(), req))
+            .await?;
+        Ok(result.json().await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn rewrap_key(&self, req: EnclaveRewrapKeyRequest) -> anyhow::Result<RewrappedKey> {
+        let result = self
+            .post_request_with_dek(SecretRequest::new("rewrap-key", req.dek_id.clone(), req))
+            .await?;
+        Ok(result.json().await?)
+    }
+
     #[instrument(skip(self))]
     pub async fn backfill_sign(
         &self,
@@ -134,7 +165,8 @@ impl EnclaveClient {
 
     /// This method first tries an "optimistic" call to the enclave with the user's provided
     /// data-encryption key. Upon first failure, which is usually due to the DEK not being loaded
-    /// onto the enclave, we "force" the load. Then, we try again.
+    /// onto the enclave, we "force" the load. Then, we try again. Requests that use two DEKs
+    /// (re-wrapping) can miss both, so the enclave's 404 says which one to load.
     #[instrument(skip(self))]
     async fn post_request_with_dek<T: Serialize>(
         &self,
@@ -144,7 +176,7 @@ impl EnclaveClient {
         // but the state is managed by the enclave (in case the enclave is restarted, for example).
         self.load_integrity_key().await?;
 
-        for _attempt in 0..2 {
+        for _attempt in 0..3 {
             let res = self
                 .client
                 .post(self.endpoint.join(req.endpoint)?)
@@ -172,7 +204,12 @@ impl EnclaveClient {
                     tracing::Level::DEBUG,
                     "404 from enclave, loading DEK into enclave"
                 );
-                self.load_wrapped_dek(&req.dek_id).await?;
+                let missing_dek_id = res
+                    .json::<MissingDek>()
+                    .await
+                    .map(|missing| missing.dek_id)
+                    .unwrap_or_else(|_| req.dek_id.clone());
+                self.load_wrapped_dek(&missing_dek_id).await?;
                 // now that the dek is loaded, try making the call again
                 continue;
             } else if res.status() == 200 {
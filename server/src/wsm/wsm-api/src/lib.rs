@@ -25,11 +25,12 @@ use wsm_common::bitcoin::util::bip32::DerivationPath;
 use wsm_common::derivation::WSMSupportedDomain;
 use wsm_common::messages::api::{
     CreateRootKeyRequest, CreatedSigningKey, GenerateIntegrityKeyResponse, GetIntegritySigRequest,
-    GetIntegritySigResponse, SignPsbtRequest, SignedPsbt,
+    GetIntegritySigResponse, RotateDekRequest, RotateDekResponse, SignMessageRequest,
+    SignPsbtRequest, SignedMessage, SignedPsbt,
 };
 use wsm_common::messages::enclave::{
-    EnclaveCreateKeyRequest, EnclaveDeriveKeyRequest, EnclaveSignRequest,
-    EnclaveSignWithIntegrityKeyRequest,
+    EnclaveCreateKeyRequest, EnclaveDeriveKeyRequest, EnclaveSignMessageRequest,
+    EnclaveSignRequest, EnclaveSignWithIntegrityKeyRequest,
 };
 use wsm_common::messages::DomainFactoredXpub;
 
@@ -41,11 +42,13 @@ use crate::settings::RunMode;
 use crate::settings::Settings;
 
 mod dependencies;
+mod rotation;
 mod settings;
 
 #[derive(Clone, axum_macros::FromRef)]
 pub struct RouteState {
     pub customer_key_store: CustomerKeyStore,
+    pub dek_store: Arc<DekStore>,
     pub enclave: Arc<EnclaveClient>,
     pub kms: KmsClient,
     pub cmk_id: String,
@@ -58,9 +61,11 @@ impl From<RouteState> for Router {
             .route("/health-check", get(health_check))
             .route("/create-key", post(create_key))
             .route("/sign-psbt", post(sign_psbt))
+            .route("/sign-message", post(sign_message))
             .route("/integrity-sig", get(integrity_sig))
             .route("/generate-integrity-key", get(generate_integrity_key))
             .route("/backfill-integrity-hashes", get(backfill_integrity_hashes))
+            .route("/rotate-dek", post(rotate_dek))
             .with_state(state)
     }
 }
@@ -230,6 +235,44 @@ async fn sign_psbt(
     }
 }
 
+/// Sign a message with a customer's server key: either a BIP-322 proof for one of the keyset's
+/// addresses, or a plain ECDSA signature with the key from the Config domain.
+#[instrument(skip(customer_key_store, enclave_client))]
+async fn sign_message(
+    State(customer_key_store): State<CustomerKeyStore>,
+    State(enclave_client): State<Arc<EnclaveClient>>,
+    Json(request): Json<SignMessageRequest>,
+) -> Result<Json<SignedMessage>, ApiError> {
+    let root_key_id = &request.root_key_id;
+
+    match customer_key_store
+        .get_customer_key(root_key_id)
+        .await
+        .map_err(|e| {
+            ApiError::ServerError(format!("Could not read customer keys DDB table: {e}"))
+        })? {
+        Some(ck) => {
+            let req = EnclaveSignMessageRequest {
+                root_key_id: root_key_id.to_string(),
+                wrapped_xprv: ck.key_ciphertext,
+                dek_id: ck.dek_id,
+                key_nonce: ck.key_nonce,
+                message: request.message,
+                scheme: request.scheme,
+                network: ck.network,
+            };
+            let signed_message = enclave_client
+                .sign_message(req)
+                .await
+                .map_err(|e| ApiError::ServerError(format!("Error signing message: {e}")))?;
+            Ok(Json(signed_message))
+        }
+        None => Err(ApiError::NotFound(format!(
+            "Customer signing key for KeySet {root_key_id} not found"
+        ))),
+    }
+}
+
 // Shallow health check
 async fn health_check(State(enclave_client): State<Arc<EnclaveClient>>) -> Result<String, String> {
     enclave_client
@@ -321,6 +364,25 @@ async fn backfill_integrity_hashes(State(state): State<RouteState>) -> Result<Js
     Ok(Json(()))
 }
 
+/// Retire the DEKs currently in use: mint a new DEK under the KMS CMK and have the enclave re-wrap
+/// every customer key under it. Rotations are resumable, so this can be called again with the same
+/// (or a larger) `max_keys` until it reports that the rotation is complete.
+#[instrument(err, skip(state))]
+async fn rotate_dek(
+    State(state): State<RouteState>,
+    Json(request): Json<RotateDekRequest>,
+) -> Result<Json<RotateDekResponse>, ApiError> {
+    rotation::rotate_dek(
+        &state.customer_key_store,
+        &state.dek_store,
+        &state.enclave,
+        request.max_keys,
+    )
+    .await
+    .map(Json)
+    .map_err(|e| ApiError::ServerError(format!("Could not rotate DEK: {e:#}")))
+}
+
 async fn integrity_sig(
     State(state): State<RouteState>,
     request: Json<GetIntegritySigRequest>,
@@ -352,12 +414,12 @@ pub async fn axum() -> (TcpListener, Router) {
                 .unwrap(),
         ),
     };
-    let dek_store = DekStore::new(
+    let dek_store = Arc::new(DekStore::new(
         ddb.clone(),
         kms.clone(),
         &settings.dek_table_name,
         &settings.cmk_id,
-    );
+    ));
     // TODO:[W-1236] Figment to grab the configuration params from Rocket.toml, there should be a local and a production
     set_global_telemetry(&Config {
         service_name: "wsm".to_string(),
@@ -371,6 +433,7 @@ pub async fn axum() -> (TcpListener, Router) {
 
     let mut router = Router::from(RouteState {
         customer_key_store: CustomerKeyStore::new(ddb, &settings.customer_keys_table_name),
+        dek_store: dek_store.clone(),
         enclave: Arc::new(EnclaveClient::new(dek_store, kms_config, &settings)),
         kms,
         cmk_id: settings.cmk_id.clone(),
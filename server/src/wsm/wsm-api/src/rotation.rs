@@ -0,0 +1,301 @@
+use anyhow::{ensure, Context};
+use tracing::{event, instrument, Level};
+
+use wsm_common::messages::api::RotateDekResponse;
+use wsm_common::messages::enclave::EnclaveRewrapKeyRequest;
+
+use crate::dependencies::customer_key_store::{CustomerKey, CustomerKeyStore};
+use crate::dependencies::dek_store::{now_secs, DekRotation, DekStore};
+use crate::dependencies::enclave_client::EnclaveClient;
+
+/// Upper bound on the customer keys re-wrapped by a single request, so that it returns well within
+/// any request timeout. This also applies when `max_keys` isn't given.
+const MAX_KEYS_PER_REQUEST: u32 = 1_000;
+
+/// Move every customer key onto a new DEK, starting a rotation if none is in progress. Progress is
+/// saved after each page of customer keys, so this can be called repeatedly (e.g. with
+/// `max_keys`, or after a failure) until the rotation is complete.
+#[instrument(skip(customer_key_store, dek_store, enclave))]
+pub async fn rotate_dek(
+    customer_key_store: &CustomerKeyStore,
+    dek_store: &DekStore,
+    enclave: &EnclaveClient,
+    max_keys: Option<u32>,
+) -> anyhow::Result<RotateDekResponse> {
+    let mut rotation = match dek_store.get_rotation_in_progress().await? {
+        Some(rotation) => rotation,
+        None => dek_store.start_rotation().await?,
+    };
+    let mut remaining = batch_size(max_keys);
+
+    loop {
+        let (customer_keys, next) = customer_key_store
+            .get_customer_keys_page(rotation.cursor.as_deref())
+            .await?;
+
+        let page = plan_page(customer_keys, next, &rotation, remaining);
+        let rewrapped = page.stale_keys.len() as u64;
+        for customer_key in page.stale_keys {
+            rewrap(customer_key_store, enclave, &rotation, customer_key).await?;
+        }
+        remaining -= rewrapped;
+        rotation.cursor = page.cursor;
+        rotation.rewrapped += rewrapped;
+        rotation.pass_rewrapped += rewrapped;
+
+        if !page.out_of_keys && rotation.cursor.is_none() {
+            // End of a pass over the customer keys
+            let clean_pass = rotation.pass_rewrapped == 0;
+            if clean_pass && rotation.old_leases_expired() {
+                dek_store.complete_rotation(&rotation).await?;
+                return Ok(response(&rotation, true));
+            }
+            rotation.pass_rewrapped = 0;
+            rotation.pass_started_at = now_secs();
+            if clean_pass {
+                // Servers may still be wrapping new keys under an old DEK they leased before the
+                // rotation started, so check again once those leases have expired.
+                dek_store.save_rotation(&rotation, rewrapped).await?;
+                return Ok(response(&rotation, false));
+            }
+        }
+        dek_store.save_rotation(&rotation, rewrapped).await?;
+
+        if page.out_of_keys {
+            return Ok(response(&rotation, false));
+        }
+    }
+}
+
+fn batch_size(max_keys: Option<u32>) -> u64 {
+    max_keys
+        .unwrap_or(MAX_KEYS_PER_REQUEST)
+        .min(MAX_KEYS_PER_REQUEST)
+        .into()
+}
+
+/// The customer keys to re-wrap from a page, and where the rotation has got to afterwards.
+#[derive(Debug)]
+struct PagePlan {
+    stale_keys: Vec<CustomerKey>,
+    cursor: Option<String>,
+    /// Whether `remaining` ran out before the end of the page
+    out_of_keys: bool,
+}
+
+/// Pick out the keys in `customer_keys` that aren't on the rotation's DEK yet, stopping once
+/// `remaining` have been picked and another is found. `next` is the cursor for the following page.
+fn plan_page(
+    customer_keys: Vec<CustomerKey>,
+    next: Option<String>,
+    rotation: &DekRotation,
+    mut remaining: u64,
+) -> PagePlan {
+    let mut stale_keys = Vec::new();
+    let mut cursor = rotation.cursor.clone();
+    for customer_key in customer_keys {
+        let root_key_id = customer_key.root_key_id.clone();
+        if customer_key.dek_id != rotation.dek_id {
+            if remaining == 0 {
+                return PagePlan {
+                    stale_keys,
+                    cursor,
+                    out_of_keys: true,
+                };
+            }
+            stale_keys.push(customer_key);
+            remaining -= 1;
+        }
+        cursor = Some(root_key_id);
+    }
+
+    PagePlan {
+        stale_keys,
+        cursor: next,
+        out_of_keys: false,
+    }
+}
+
+async fn rewrap(
+    customer_key_store: &CustomerKeyStore,
+    enclave: &EnclaveClient,
+    rotation: &DekRotation,
+    customer_key: CustomerKey,
+) -> anyhow::Result<()> {
+    let previous_dek_id = customer_key.dek_id.clone();
+    let rewrapped = enclave
+        .rewrap_key(EnclaveRewrapKeyRequest {
+            root_key_id: customer_key.root_key_id.clone(),
+            wrapped_xprv: customer_key.key_ciphertext.clone(),
+            key_nonce: customer_key.key_nonce.clone(),
+            dek_id: previous_dek_id.clone(),
+            new_dek_id: rotation.dek_id.clone(),
+            network: customer_key.network,
+        })
+        .await
+        .with_context(|| format!("Could not rewrap key {}", customer_key.root_key_id))?;
+    ensure!(
+        rewrapped.dpub == customer_key.xpub_descriptor,
+        "Rewrapped key {} doesn't match its stored xpub",
+        customer_key.root_key_id
+    );
+
+    customer_key_store
+        .update_wrapped_key(
+            &CustomerKey {
+                key_ciphertext: rewrapped.wrapped_xprv,
+                key_nonce: rewrapped.wrapped_xprv_nonce,
+                dek_id: rotation.dek_id.clone(),
+                integrity_signature: Some(rewrapped.xpub_sig),
+                ..customer_key
+            },
+            &previous_dek_id,
+        )
+        .await?;
+    event!(Level::DEBUG, "Rewrapped key under DEK {}", rotation.dek_id);
+    Ok(())
+}
+
+fn response(rotation: &DekRotation, complete: bool) -> RotateDekResponse {
+    RotateDekResponse {
+        dek_id: rotation.dek_id.clone(),
+        rewrapped: rotation.rewrapped,
+        complete,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dependencies::customer_key_store::CustomerKey;
+    use crate::dependencies::dek_store::{DekRotation, LEASE_TTL};
+
+    use super::{batch_size, plan_page, MAX_KEYS_PER_REQUEST};
+
+    const NEW_DEK_ID: &str = "new-dek";
+
+    fn customer_key(root_key_id: &str, dek_id: &str) -> CustomerKey {
+        CustomerKey {
+            root_key_id: root_key_id.to_string(),
+            key_ciphertext: "ciphertext".to_string(),
+            key_nonce: "nonce".to_string(),
+            xpub_descriptor: "xpub".to_string(),
+            dek_id: dek_id.to_string(),
+            xpubs: vec![],
+            network: None,
+            integrity_signature: None,
+        }
+    }
+
+    fn rotation(cursor: Option<&str>) -> DekRotation {
+        DekRotation {
+            dek_id: NEW_DEK_ID.to_string(),
+            cursor: cursor.map(String::from),
+            rewrapped: 0,
+            pass_rewrapped: 0,
+            started_at: 1_000,
+            pass_started_at: 1_000,
+        }
+    }
+
+    fn root_key_ids(keys: &[CustomerKey]) -> Vec<&str> {
+        keys.iter().map(|key| key.root_key_id.as_str()).collect()
+    }
+
+    #[test]
+    fn batch_size_is_capped() {
+        assert_eq!(batch_size(Some(10)), 10);
+        assert_eq!(batch_size(None), MAX_KEYS_PER_REQUEST as u64);
+        assert_eq!(batch_size(Some(u32::MAX)), MAX_KEYS_PER_REQUEST as u64);
+    }
+
+    #[test]
+    fn plan_page_skips_keys_already_on_new_dek() {
+        let page = plan_page(
+            vec![
+                customer_key("a", "old-dek"),
+                customer_key("b", NEW_DEK_ID),
+                customer_key("c", "older-dek"),
+            ],
+            Some("c".to_string()),
+            &rotation(None),
+            10,
+        );
+
+        assert_eq!(root_key_ids(&page.stale_keys), vec!["a", "c"]);
+        assert_eq!(page.cursor.as_deref(), Some("c"));
+        assert!(!page.out_of_keys);
+    }
+
+    #[test]
+    fn plan_page_ends_pass_on_last_page() {
+        let page = plan_page(
+            vec![customer_key("a", NEW_DEK_ID)],
+            None,
+            &rotation(Some("0")),
+            10,
+        );
+
+        assert!(page.stale_keys.is_empty());
+        assert_eq!(page.cursor, None);
+        assert!(!page.out_of_keys);
+    }
+
+    #[test]
+    fn plan_page_stops_at_limit() {
+        let page = plan_page(
+            vec![
+                customer_key("a", "old-dek"),
+                customer_key("b", NEW_DEK_ID),
+                customer_key("c", "old-dek"),
+            ],
+            Some("c".to_string()),
+            &rotation(None),
+            1,
+        );
+
+        // Carries on from after the last key handled, so "c" is picked up next time
+        assert_eq!(root_key_ids(&page.stale_keys), vec!["a"]);
+        assert_eq!(page.cursor.as_deref(), Some("b"));
+        assert!(page.out_of_keys);
+    }
+
+    #[test]
+    fn plan_page_keeps_cursor_when_out_of_keys_at_start() {
+        let page = plan_page(
+            vec![customer_key("b", "old-dek")],
+            None,
+            &rotation(Some("a")),
+            0,
+        );
+
+        assert!(page.stale_keys.is_empty());
+        assert_eq!(page.cursor.as_deref(), Some("a"));
+        assert!(page.out_of_keys);
+    }
+
+    #[test]
+    fn plan_page_continues_when_limit_reached_at_end_of_page() {
+        let page = plan_page(
+            vec![customer_key("a", "old-dek"), customer_key("b", NEW_DEK_ID)],
+            Some("b".to_string()),
+            &rotation(None),
+            1,
+        );
+
+        assert_eq!(root_key_ids(&page.stale_keys), vec!["a"]);
+        assert_eq!(page.cursor.as_deref(), Some("b"));
+        assert!(!page.out_of_keys);
+    }
+
+    #[test]
+    fn old_leases_expire_after_lease_ttl() {
+        let mut rotation = rotation(None);
+        assert!(!rotation.old_leases_expired());
+
+        rotation.pass_started_at = rotation.started_at + LEASE_TTL.as_secs() - 1;
+        assert!(!rotation.old_leases_expired());
+
+        rotation.pass_started_at = rotation.started_at + LEASE_TTL.as_secs();
+        assert!(rotation.old_leases_expired());
+    }
+}
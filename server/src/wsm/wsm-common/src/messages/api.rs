@@ -37,6 +37,50 @@ pub struct SignedPsbt {
     pub root_key_id: String,
 }
 
+/// How the server key should sign a message in `/sign-message`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessageSigningScheme {
+    /// ECDSA over the Bitcoin Signed Message hash, with the key from the Config domain
+    Ecdsa,
+    /// The server's share of a BIP-322 "simple" proof for `address`, which must belong to the
+    /// keyset described by `descriptor` and `change_descriptor`
+    Bip322Simple {
+        address: String,
+        descriptor: String,
+        change_descriptor: String,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SignMessageRequest {
+    pub root_key_id: String,
+    pub message: String,
+    pub scheme: MessageSigningScheme,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessageSignature {
+    Ecdsa {
+        /// Hex-encoded DER signature
+        signature: String,
+        /// Hex-encoded compressed public key of the Config domain key
+        pubkey: String,
+    },
+    Bip322Simple {
+        /// The BIP-322 `to_sign` transaction carrying the server's partial signature. The other
+        /// signers add theirs and finalize it; the finalized witness is the simple proof.
+        psbt: String,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct SignedMessage {
+    pub root_key_id: String,
+    pub signature: MessageSignature,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct SignBlobRequest {
     pub root_key_id: String,
@@ -58,3 +102,19 @@ pub struct GetIntegritySigRequest {
 pub struct GetIntegritySigResponse {
     pub signature: String,
 }
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct RotateDekRequest {
+    /// Stop after re-wrapping this many customer keys, at most 1,000; call again to carry on
+    #[serde(default)]
+    pub max_keys: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RotateDekResponse {
+    /// The DEK customer keys are being moved to
+    pub dek_id: String,
+    /// Customer keys re-wrapped so far in this rotation
+    pub rewrapped: u64,
+    pub complete: bool,
+}
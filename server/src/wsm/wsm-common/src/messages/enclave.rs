@@ -2,6 +2,8 @@ use bitcoin::util::bip32::{DerivationPath, ExtendedPubKey};
 use bitcoin::Network;
 use serde::{Deserialize, Serialize};
 
+use crate::messages::api::MessageSigningScheme;
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct KmsRequest {
     pub region: String,
@@ -62,6 +64,17 @@ pub struct EnclaveGenericSignRequest {
     pub network: Option<Network>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EnclaveSignMessageRequest {
+    pub root_key_id: String,
+    pub wrapped_xprv: String,
+    pub dek_id: String,
+    pub key_nonce: String,
+    pub message: String,
+    pub scheme: MessageSigningScheme,
+    pub network: Option<Network>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EnclaveCreateKeyRequest {
     pub root_key_id: String,
@@ -79,6 +92,26 @@ pub struct EnclaveDeriveKeyRequest {
     pub network: Option<Network>,
 }
 
+/// Re-encrypt a customer root key, currently wrapped under `dek_id`, under `new_dek_id`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EnclaveRewrapKeyRequest {
+    pub root_key_id: String,
+    pub wrapped_xprv: String,
+    pub key_nonce: String,
+    pub dek_id: String,
+    pub new_dek_id: String,
+    pub network: Option<Network>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RewrappedKey {
+    pub wrapped_xprv: String,
+    pub wrapped_xprv_nonce: String,
+    /// Descriptor pubkey of the spend domain, so the caller can check the right key came back
+    pub dpub: String,
+    pub xpub_sig: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct CreatedKey {
     pub xpub: ExtendedPubKey,
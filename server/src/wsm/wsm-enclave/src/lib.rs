@@ -17,14 +17,24 @@ use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use axum::{extract::State, routing::post, Json, Router};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use bdk::bitcoin::absolute::LockTime;
 use bdk::bitcoin::bip32::{DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint};
-use bdk::bitcoin::hashes::{sha256, Hash};
+use bdk::bitcoin::blockdata::opcodes::all::OP_RETURN;
+use bdk::bitcoin::blockdata::script::Builder;
+use bdk::bitcoin::hashes::{sha256, Hash, HashEngine};
 use bdk::bitcoin::psbt::PartiallySignedTransaction;
 use bdk::bitcoin::secp256k1::{ecdsa::Signature, All, Message, Secp256k1, SecretKey};
-use bdk::bitcoin::Network;
+use bdk::bitcoin::sign_message::signed_msg_hash;
+use bdk::bitcoin::{
+    Address, Network, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid,
+    Witness,
+};
 use bdk::database::MemoryDatabase;
 use bdk::descriptor::Segwitv0;
 use bdk::keys::{DerivableKey, DescriptorKey, DescriptorSecretKey, ExtendedKey};
+use bdk::miniscript::descriptor::DefiniteDescriptorKey;
+use bdk::miniscript::psbt::PsbtExt;
+use bdk::miniscript::Descriptor;
 use bdk::signer::{SignerContext, SignerOrdering, SignerWrapper, TransactionSigner};
 use bdk::{bitcoin, KeychainKind, SignOptions, Wallet};
 
@@ -38,12 +48,14 @@ use tokio::sync::RwLock;
 use bdk::bitcoin::base58::from_check;
 use wsm_common::bitcoin::Network::Signet;
 use wsm_common::derivation::WSMSupportedDomain;
-use wsm_common::messages::api::SignedPsbt;
+use wsm_common::messages::api::{
+    MessageSignature, MessageSigningScheme, SignedMessage, SignedPsbt,
+};
 use wsm_common::messages::enclave::{
     CreateResponse, CreatedKey, DeriveResponse, DerivedKey, EnclaveCreateKeyRequest,
-    EnclaveDeriveKeyRequest, EnclaveSignRequest, EnclaveSignWithIntegrityKeyRequest,
-    EnclaveSignWithIntegrityKeyResponse, KmsRequest, LoadIntegrityKeyRequest, LoadSecretRequest,
-    LoadedSecret,
+    EnclaveDeriveKeyRequest, EnclaveRewrapKeyRequest, EnclaveSignMessageRequest,
+    EnclaveSignRequest, EnclaveSignWithIntegrityKeyRequest, EnclaveSignWithIntegrityKeyResponse,
+    KmsRequest, LoadIntegrityKeyRequest, LoadSecretRequest, LoadedSecret, RewrappedKey,
 };
 use wsm_common::messages::TEST_KEY_IDS;
 use wsm_common::{
@@ -63,6 +75,10 @@ mod settings;
 
 const GLOBAL_CONTEXT: &[u8] = b"WsmIntegrityV1";
 const INTEGRITY_KEY_ID: &str = "integrity";
+/// BIP-322 tag for the message hash committed to by the `to_spend` transaction
+const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+/// How many addresses of each keychain to search for the address being proven
+const BIP322_ADDRESS_SEARCH_LIMIT: u32 = 1000;
 const TEST_INTEGRITY_KEY_B64: &str = include_str!("../../keys/test_integrity_key.b64");
 
 type KeyStore = Arc<RwLock<HashMap<String, KeySpec>>>;
@@ -344,13 +360,267 @@ async fn sign_psbt(
         &mut log_buffer,
     )
     .await?;
+    let wallet = spend_wallet(
+        &xprv,
+        &request.descriptor,
+        &request.change_descriptor,
+        request.network,
+        &mut log_buffer,
+    )?;
+    let mut psbt =
+        PartiallySignedTransaction::from_str(request.psbt.as_str()).expect("Could not parse PSBT");
+    // Do we want to do any policy enforcement in the enclave? If so, it needs to go right HERE
+    let _finalized = try_with_log_and_error!(
+        log_buffer,
+        WsmError::ServerError,
+        wallet.sign(&mut psbt, SignOptions::default())
+    )?;
+    Ok(Json(SignedPsbt {
+        psbt: psbt.to_string(),
+        root_key_id: request.root_key_id.clone(),
+    }))
+}
+
+/// Sign a message with the server key. Plain messages are signed with the Config domain key rather
+/// than the spend key, and always over the Bitcoin Signed Message hash, so this route can never be
+/// used to produce a signature over a transaction sighash.
+async fn sign_message(
+    State(keystore): State<KeyStore>,
+    Json(request): Json<EnclaveSignMessageRequest>,
+) -> Result<Json<SignedMessage>, WsmError> {
+    let mut log_buffer = LogBuffer::new();
+
+    // Read request from WSM API and coerce to v30 Network.
+    let network = request
+        .network
+        .map(|n| BitcoinNetwork::from(n).as_v30_network());
+
+    let xprv = decode_wrapped_xprv(
+        keystore,
+        &request.wrapped_xprv,
+        &request.key_nonce,
+        &request.dek_id,
+        &request.root_key_id,
+        network,
+        &mut log_buffer,
+    )
+    .await?;
+
+    let signature = match &request.scheme {
+        MessageSigningScheme::Ecdsa => {
+            wsm_log!(log_buffer, "Signing message with config key");
+            sign_message_ecdsa(&xprv, &request.message, &mut log_buffer)?
+        }
+        MessageSigningScheme::Bip322Simple {
+            address,
+            descriptor,
+            change_descriptor,
+        } => {
+            wsm_log!(log_buffer, "Signing BIP-322 proof");
+            let wallet = spend_wallet(
+                &xprv,
+                descriptor,
+                change_descriptor,
+                request.network,
+                &mut log_buffer,
+            )?;
+            sign_message_bip322(
+                &wallet,
+                address,
+                &request.message,
+                network.unwrap_or(Network::Signet),
+                &mut log_buffer,
+            )?
+        }
+    };
+
+    Ok(Json(SignedMessage {
+        root_key_id: request.root_key_id,
+        signature,
+    }))
+}
+
+fn sign_message_ecdsa(
+    xprv: &ExtendedPrivKey,
+    message: &str,
+    log_buffer: &mut LogBuffer,
+) -> Result<MessageSignature, WsmError> {
+    let secp = Secp256k1::new();
+    let derivation_path = BitcoinDerivationPath::from(
+        wsm_common::bitcoin::util::bip32::DerivationPath::from(WSMSupportedDomain::Config),
+    )
+    .as_v30_path();
+    let config_xprv = try_with_log_and_error!(
+        log_buffer,
+        WsmError::ServerError,
+        xprv.derive_priv(&secp, &derivation_path)
+    )?;
+
+    let message = Message::from(signed_msg_hash(message));
+    let signature = secp.sign_ecdsa(&message, &config_xprv.private_key);
+
+    Ok(MessageSignature::Ecdsa {
+        signature: hex::encode(signature.serialize_der()),
+        pubkey: hex::encode(config_xprv.private_key.public_key(&secp).serialize()),
+    })
+}
+
+/// Build the BIP-322 `to_sign` transaction for `address` and add the server's signature to it.
+/// The address has to belong to one of the wallet's descriptors, within the first
+/// [`BIP322_ADDRESS_SEARCH_LIMIT`] addresses of either keychain.
+fn sign_message_bip322(
+    wallet: &Wallet<MemoryDatabase>,
+    address: &str,
+    message: &str,
+    network: Network,
+    log_buffer: &mut LogBuffer,
+) -> Result<MessageSignature, WsmError> {
+    let script_pubkey = Address::from_str(address)
+        .and_then(|address| address.require_network(network))
+        .map_err(|e| {
+            WsmError::BadRequest(
+                format!("Invalid address {address}: {e}"),
+                log_buffer.clone(),
+            )
+        })?
+        .script_pubkey();
+
+    let descriptor =
+        find_descriptor_for_script(wallet, &script_pubkey, log_buffer)?.ok_or_else(|| {
+            WsmError::BadRequest(
+                format!("Address {address} is not in the keyset"),
+                log_buffer.clone(),
+            )
+        })?;
+
+    let mut psbt = bip322_to_sign_psbt(&script_pubkey, message, log_buffer)?;
+    try_with_log_and_error!(
+        log_buffer,
+        WsmError::ServerError,
+        psbt.update_input_with_descriptor(0, &descriptor)
+    )?;
+    try_with_log_and_error!(
+        log_buffer,
+        WsmError::ServerError,
+        wallet.sign(
+            &mut psbt,
+            SignOptions {
+                // The other signers' signatures are still missing.
+                try_finalize: false,
+                ..Default::default()
+            },
+        )
+    )?;
+    if psbt.inputs[0].partial_sigs.is_empty() {
+        return Err(WsmError::BadRequest(
+            "Server key is not part of the keyset".to_string(),
+            log_buffer.clone(),
+        ));
+    }
+
+    Ok(MessageSignature::Bip322Simple {
+        psbt: psbt.to_string(),
+    })
+}
+
+fn find_descriptor_for_script(
+    wallet: &Wallet<MemoryDatabase>,
+    script_pubkey: &Script,
+    log_buffer: &mut LogBuffer,
+) -> Result<Option<Descriptor<DefiniteDescriptorKey>>, WsmError> {
+    for keychain in [KeychainKind::External, KeychainKind::Internal] {
+        let descriptor = wallet.get_descriptor_for_keychain(keychain);
+        for index in 0..BIP322_ADDRESS_SEARCH_LIMIT {
+            let derived = try_with_log_and_error!(
+                log_buffer,
+                WsmError::ServerError,
+                descriptor.at_derivation_index(index)
+            )?;
+            if derived.script_pubkey().as_script() == script_pubkey {
+                return Ok(Some(derived));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// The unsigned BIP-322 `to_sign` transaction, spending the virtual `to_spend` output that commits
+/// to `message` and pays to `script_pubkey`.
+fn bip322_to_sign_psbt(
+    script_pubkey: &Script,
+    message: &str,
+    log_buffer: &mut LogBuffer,
+) -> Result<PartiallySignedTransaction, WsmError> {
+    let tag = sha256::Hash::hash(BIP322_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_ref());
+    engine.input(tag.as_ref());
+    engine.input(message.as_bytes());
+    let message_hash = sha256::Hash::from_engine(engine);
+
+    let to_spend = Transaction {
+        version: 0,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: Txid::all_zeros(),
+                vout: 0xFFFFFFFF,
+            },
+            script_sig: Builder::new()
+                .push_int(0)
+                .push_slice(message_hash.as_byte_array())
+                .into_script(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: script_pubkey.to_owned(),
+        }],
+    };
+    let to_sign = Transaction {
+        version: 0,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: to_spend.txid(),
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: 0,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    };
+
+    let mut psbt = try_with_log_and_error!(
+        log_buffer,
+        WsmError::ServerError,
+        PartiallySignedTransaction::from_unsigned_tx(to_sign)
+    )?;
+    psbt.inputs[0].witness_utxo = Some(to_spend.output[0].clone());
+    psbt.inputs[0].non_witness_utxo = Some(to_spend);
+    Ok(psbt)
+}
+
+/// A wallet for the keyset's descriptors that signs with the server's spend key.
+fn spend_wallet(
+    xprv: &ExtendedPrivKey,
+    descriptor: &str,
+    change_descriptor: &str,
+    network: Option<wsm_common::bitcoin::Network>,
+    log_buffer: &mut LogBuffer,
+) -> Result<Wallet<MemoryDatabase>, WsmError> {
     let secp = Secp256k1::new();
 
     let derivation_path = BitcoinDerivationPath::from(
         // DerivationPath::from is only supported from wsm-common, which expects a 0.29
         // DerivationPath, so we initialize one here.
         wsm_common::bitcoin::util::bip32::DerivationPath::from(WSMSupportedDomain::Spend(
-            request.network.unwrap_or(Signet).into(),
+            network.unwrap_or(Signet).into(),
         )),
     )
     .as_v30_path();
@@ -401,9 +671,9 @@ async fn sign_psbt(
         log_buffer,
         WsmError::ServerError,
         Wallet::new(
-            request.descriptor.as_str(),
-            Some(request.change_descriptor.as_str()),
-            network.unwrap_or(Network::Signet),
+            descriptor,
+            Some(change_descriptor),
+            BitcoinNetwork::from(network.unwrap_or(Signet)).as_v30_network(),
             MemoryDatabase::default(),
         )
     )?;
@@ -418,18 +688,7 @@ async fn sign_psbt(
         SignerOrdering(9002),
         internal_signer,
     );
-    let mut psbt =
-        PartiallySignedTransaction::from_str(request.psbt.as_str()).expect("Could not parse PSBT");
-    // Do we want to do any policy enforcement in the enclave? If so, it needs to go right HERE
-    let _finalized = try_with_log_and_error!(
-        log_buffer,
-        WsmError::ServerError,
-        wallet.sign(&mut psbt, SignOptions::default())
-    )?;
-    Ok(Json(SignedPsbt {
-        psbt: psbt.to_string(),
-        root_key_id: request.root_key_id.clone(),
-    }))
+    Ok(wallet)
 }
 
 fn descriptor_key_to_signer(
@@ -558,6 +817,78 @@ async fn derive_key(
     })))
 }
 
+/// Re-encrypt a customer root key under a different DEK, as part of retiring the old one. The
+/// integrity signature over the spend xpub is refreshed at the same time.
+async fn rewrap_key(
+    State(route_state): State<RouteState>,
+    Json(request): Json<EnclaveRewrapKeyRequest>,
+) -> Result<Json<RewrappedKey>, WsmError> {
+    let keystore = route_state.keystore.clone();
+    let mut log_buffer = LogBuffer::new();
+
+    // Unlike create-key, a rewrapped key without a fresh signature would lose its existing one.
+    let integrity_key = get_integrity_key(&keystore, &mut log_buffer).await?;
+
+    let secp = Secp256k1::new();
+    // Read request from WSM API and coerce to v30 Network.
+    let network = request
+        .network
+        .map(|n| BitcoinNetwork::from(n).as_v30_network());
+
+    let xprv = decode_wrapped_xprv(
+        keystore.clone(),
+        &request.wrapped_xprv,
+        &request.key_nonce,
+        &request.dek_id,
+        &request.root_key_id,
+        network,
+        &mut log_buffer,
+    )
+    .await?;
+    let new_datakey = get_dek(&request.new_dek_id, keystore, &mut log_buffer).await?;
+    // Keep the AAD exactly as it was, so the key decrypts the same way under the new DEK.
+    let (wrapped_xprv, wrapped_xprv_nonce) = encrypt_root_key(
+        &request.root_key_id,
+        &new_datakey,
+        &xprv,
+        network,
+        &mut log_buffer,
+    )?;
+    wsm_log!(log_buffer, "Rewrapped root key under new DEK");
+
+    let derivation_path =
+        BitcoinDerivationPath::from(wsm_common::bitcoin::util::bip32::DerivationPath::from(
+            WSMSupportedDomain::Spend(request.network.unwrap_or(Signet).into()),
+        ))
+        .as_v30_path();
+    let spend_xprv = try_with_log_and_error!(
+        log_buffer,
+        WsmError::ServerError,
+        xprv.derive_priv(&secp, &derivation_path)
+    )?;
+    let spend_xpub = ExtendedPubKey::from_priv(&secp, &spend_xprv);
+    let keysource = (xprv.fingerprint(&secp), derivation_path);
+    let dpub = calculate_descriptor_pubkey(keysource, &spend_xpub, &mut log_buffer)?;
+
+    let xpub_sig = hex::encode(
+        sign_with_integrity_key(
+            secp,
+            &mut log_buffer,
+            &integrity_key,
+            b"DeriveKeyV1",
+            &spend_xpub.encode(),
+        )?
+        .serialize_compact(),
+    );
+
+    Ok(Json(RewrappedKey {
+        wrapped_xprv,
+        wrapped_xprv_nonce,
+        dpub,
+        xpub_sig,
+    }))
+}
+
 async fn create_root_key_internal(
     key_id: &str,
     network: Network,
@@ -818,9 +1149,11 @@ impl From<RouteState> for Router {
             .route("/load-secret", post(load_secret))
             .route("/load-integrity-key", post(load_integrity_key))
             .route("/sign-psbt", post(sign_psbt))
+            .route("/sign-message", post(sign_message))
             .route("/create-key", post(create_key))
             .route("/derive-key", post(derive_key))
             .route("/backfill-sign", post(backfill_sign))
+            .route("/rewrap-key", post(rewrap_key))
             .with_state(state)
     }
 }
@@ -850,8 +1183,18 @@ mod tests {
     use axum::{http, Router};
     use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 
+    use std::str::FromStr;
+
+    use bdk::bitcoin::bip32::ExtendedPubKey;
     use bdk::bitcoin::hashes::{sha256, Hash};
-    use bdk::bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+    use bdk::bitcoin::psbt::PartiallySignedTransaction;
+    use bdk::bitcoin::secp256k1::{ecdsa::Signature, Message, Secp256k1, SecretKey};
+    use bdk::bitcoin::sign_message::signed_msg_hash;
+    use bdk::bitcoin::{Address, Network};
+    use bdk::database::MemoryDatabase;
+    use bdk::miniscript::psbt::PsbtExt;
+    use bdk::wallet::AddressIndex;
+    use bdk::Wallet;
 
     use http_body_util::BodyExt;
     use tower::ServiceExt;
@@ -860,18 +1203,21 @@ mod tests {
     use wsm_common::bitcoin::util::bip32::DerivationPath;
     use wsm_common::derivation::{CoinType, WSMSupportedDomain};
     use wsm_common::enclave_log::LogBuffer;
+    use wsm_common::messages::api::{MessageSignature, MessageSigningScheme, SignedMessage};
     use wsm_common::messages::enclave::{
-        CreatedKey, DerivedKey, EnclaveCreateKeyRequest, EnclaveDeriveKeyRequest, KmsRequest,
-        LoadIntegrityKeyRequest, LoadSecretRequest,
+        CreatedKey, DerivedKey, EnclaveCreateKeyRequest, EnclaveDeriveKeyRequest,
+        EnclaveRewrapKeyRequest, EnclaveSignMessageRequest, KmsRequest, LoadIntegrityKeyRequest,
+        LoadSecretRequest, RewrappedKey,
     };
     use wsm_common::messages::{
-        TEST_CMK_ID, TEST_DEK_ID, TEST_DPUB_SPEND, TEST_KEY_ID, TEST_XPUB, TEST_XPUB_SPEND,
+        TEST_CMK_ID, TEST_DEK_ID, TEST_DPUB_SPEND, TEST_KEY_ID, TEST_XPUB, TEST_XPUB_CONFIG,
+        TEST_XPUB_SPEND, TEST_XPUB_SPEND_ORIGIN,
     };
     use wsm_common::wsm_log;
 
     use crate::{
-        decode_der_secp256k1_private_key, kms_tool::KmsTool, new_keystore, settings::RunMode,
-        RouteState,
+        bip322_to_sign_psbt, decode_der_secp256k1_private_key, kms_tool::KmsTool, new_keystore,
+        settings::RunMode, RouteState,
     };
 
     fn get_client() -> Router {
@@ -933,6 +1279,10 @@ mod tests {
     }
 
     async fn load_empty_secret(client: Router) -> Response {
+        load_empty_secret_with_id(client, TEST_DEK_ID).await
+    }
+
+    async fn load_empty_secret_with_id(client: Router, dek_id: &str) -> Response {
         client
             .oneshot(
                 Request::builder()
@@ -941,7 +1291,7 @@ mod tests {
                     .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
                     .body(Body::from(
                         serde_json::to_vec(&LoadSecretRequest {
-                            dek_id: TEST_DEK_ID.to_owned(),
+                            dek_id: dek_id.to_owned(),
                             region: Default::default(),
                             proxy_port: Default::default(),
                             akid: Default::default(),
@@ -1058,6 +1408,220 @@ mod tests {
         assert_eq!(actual_created_spend_key.dpub.to_string(), TEST_DPUB_SPEND);
     }
 
+    #[tokio::test]
+    async fn test_rewrap_key() {
+        const NEW_DEK_ID: &str = "THIS_IS_THE_DEK_WE_ARE_ROTATING_TO";
+
+        let client = get_client();
+        load_empty_secret(client.clone()).await;
+        load_test_integrity_key(client.clone()).await;
+        let response = post(
+            client.clone(),
+            "/create-key",
+            &EnclaveCreateKeyRequest {
+                root_key_id: TEST_KEY_ID.to_string(),
+                dek_id: TEST_DEK_ID.to_string(),
+                network: Signet,
+            },
+        )
+        .await;
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let created_key: CreatedKey = serde_json::from_slice(&body).unwrap();
+
+        let rewrap_request = EnclaveRewrapKeyRequest {
+            root_key_id: TEST_KEY_ID.to_string(),
+            wrapped_xprv: created_key.wrapped_xprv.clone(),
+            key_nonce: created_key.wrapped_xprv_nonce.clone(),
+            dek_id: TEST_DEK_ID.to_string(),
+            new_dek_id: NEW_DEK_ID.to_string(),
+            network: Some(Signet),
+        };
+
+        // The new DEK has to be loaded first, and the enclave says which one is missing
+        let response = post(client.clone(), "/rewrap-key", &rewrap_request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let missing: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(missing["dek_id"], NEW_DEK_ID);
+
+        load_empty_secret_with_id(client.clone(), NEW_DEK_ID).await;
+        let response = post(client.clone(), "/rewrap-key", &rewrap_request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let rewrapped: RewrappedKey = serde_json::from_slice(&body).unwrap();
+        assert_eq!(rewrapped.dpub, TEST_DPUB_SPEND);
+        assert!(!rewrapped.xpub_sig.is_empty());
+        assert_ne!(rewrapped.wrapped_xprv_nonce, created_key.wrapped_xprv_nonce);
+
+        // The rewrapped key is usable under the new DEK
+        let response = post(
+            client,
+            "/derive-key",
+            &EnclaveDeriveKeyRequest {
+                key_id: TEST_KEY_ID.to_string(),
+                dek_id: NEW_DEK_ID.to_string(),
+                wrapped_xprv: rewrapped.wrapped_xprv,
+                key_nonce: rewrapped.wrapped_xprv_nonce,
+                derivation_path: DerivationPath::from(WSMSupportedDomain::Spend(CoinType::Testnet)),
+                network: Some(Signet),
+            },
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let derived: DerivedKey = serde_json::from_slice(&body).unwrap();
+        assert_eq!(derived.xpub.to_string(), TEST_XPUB_SPEND);
+    }
+
+    #[tokio::test]
+    async fn test_sign_message() {
+        let client = get_client();
+        load_empty_secret(client.clone()).await;
+        load_test_integrity_key(client.clone()).await;
+        let response = post(
+            client.clone(),
+            "/create-key",
+            &EnclaveCreateKeyRequest {
+                root_key_id: TEST_KEY_ID.to_string(),
+                dek_id: TEST_DEK_ID.to_string(),
+                network: Signet,
+            },
+        )
+        .await;
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let created_key: CreatedKey = serde_json::from_slice(&body).unwrap();
+        let sign_request =
+            |message: &str, scheme: MessageSigningScheme| EnclaveSignMessageRequest {
+                root_key_id: TEST_KEY_ID.to_string(),
+                wrapped_xprv: created_key.wrapped_xprv.clone(),
+                dek_id: TEST_DEK_ID.to_string(),
+                key_nonce: created_key.wrapped_xprv_nonce.clone(),
+                message: message.to_string(),
+                scheme,
+                network: Some(Signet),
+            };
+        let secp = Secp256k1::new();
+
+        // Plain messages are signed by the config key
+        let response = post(
+            client.clone(),
+            "/sign-message",
+            &sign_request("Hello World", MessageSigningScheme::Ecdsa),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let signed: SignedMessage = serde_json::from_slice(&body).unwrap();
+        let MessageSignature::Ecdsa { signature, pubkey } = signed.signature else {
+            panic!("expected an ECDSA signature");
+        };
+        let config_xpub = ExtendedPubKey::from_str(TEST_XPUB_CONFIG).unwrap();
+        assert_eq!(pubkey, config_xpub.public_key.to_string());
+        let signature = Signature::from_der(&hex::decode(signature).unwrap()).unwrap();
+        secp.verify_ecdsa(
+            &Message::from(signed_msg_hash("Hello World")),
+            &signature,
+            &config_xpub.public_key,
+        )
+        .unwrap();
+
+        // A single-sig keyset only needs the server's signature to finalize the proof
+        let descriptor = format!("wpkh({TEST_XPUB_SPEND_ORIGIN}{TEST_XPUB_SPEND}/0/*)");
+        let change_descriptor = format!("wpkh({TEST_XPUB_SPEND_ORIGIN}{TEST_XPUB_SPEND}/1/*)");
+        let wallet = Wallet::new(
+            &descriptor,
+            Some(&change_descriptor),
+            Network::Signet,
+            MemoryDatabase::default(),
+        )
+        .unwrap();
+        let address = wallet.get_address(AddressIndex::Peek(7)).unwrap().address;
+        let bip322 = |address: &str| MessageSigningScheme::Bip322Simple {
+            address: address.to_string(),
+            descriptor: descriptor.clone(),
+            change_descriptor: change_descriptor.clone(),
+        };
+        let response = post(
+            client.clone(),
+            "/sign-message",
+            &sign_request("Hello World", bip322(&address.to_string())),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let signed: SignedMessage = serde_json::from_slice(&body).unwrap();
+        let MessageSignature::Bip322Simple { psbt } = signed.signature else {
+            panic!("expected a BIP-322 proof");
+        };
+        let mut psbt = PartiallySignedTransaction::from_str(&psbt).unwrap();
+        assert_eq!(
+            psbt.inputs[0].witness_utxo.as_ref().unwrap().script_pubkey,
+            address.script_pubkey()
+        );
+        psbt.finalize_mut(&secp).unwrap();
+        assert_eq!(
+            psbt.inputs[0].final_script_witness.as_ref().unwrap().len(),
+            2
+        );
+
+        // Addresses outside of the keyset are rejected
+        let response = post(
+            client,
+            "/sign-message",
+            &sign_request(
+                "Hello World",
+                bip322("tb1q9vza2e8x573nczrlzms0wvx3gsqjx7vaxwd45v"),
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_bip322_to_sign_psbt() {
+        // Test vectors from BIP-322
+        let script_pubkey = Address::from_str("bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l")
+            .unwrap()
+            .assume_checked()
+            .script_pubkey();
+        for (message, to_spend, to_sign) in [
+            (
+                "",
+                "c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7",
+                "1e9654e951a5ba44c8604c4de6c67fd78a27e81dcadcfe1edf638ba3aaebaed6",
+            ),
+            (
+                "Hello World",
+                "b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b",
+                "88737ae86f2077145f93cc4b153ae9a1cb8d56afa511988c149c5c8c9d93bddf",
+            ),
+        ] {
+            let Ok(psbt) = bip322_to_sign_psbt(&script_pubkey, message, &mut LogBuffer::new())
+            else {
+                panic!("could not build the to_sign transaction");
+            };
+            assert_eq!(
+                psbt.unsigned_tx.input[0].previous_output.txid.to_string(),
+                to_spend
+            );
+            assert_eq!(psbt.unsigned_tx.txid().to_string(), to_sign);
+        }
+    }
+
+    async fn post<T: serde::Serialize>(client: Router, uri: &str, request: &T) -> Response {
+        client
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header(http::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+                    .body(Body::from(serde_json::to_vec(request).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
     #[tokio::test]
     async fn test_errors_return_enclave_logs() {
         let client = get_client();
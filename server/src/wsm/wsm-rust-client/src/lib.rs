@@ -2,9 +2,12 @@
 
 extern crate core;
 pub use wsm_common::derivation::WSMSupportedDomain;
-pub use wsm_common::messages::api::CreatedSigningKey;
+pub use wsm_common::messages::api::{
+    CreatedSigningKey, MessageSignature, MessageSigningScheme, SignedMessage,
+};
 
 use std::fmt::Debug;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use reqwest_middleware::ClientBuilder;
@@ -14,13 +17,17 @@ use tracing::instrument;
 use url::Url;
 use wsm_common::bitcoin::Network;
 use wsm_common::messages::api::{
-    CreateRootKeyRequest, GetIntegritySigRequest, GetIntegritySigResponse,
+    CreateRootKeyRequest, GetIntegritySigRequest, GetIntegritySigResponse, SignMessageRequest,
 };
 
 pub use wsm_common::messages::{
     TEST_DPUB_SPEND, TEST_XPUB_CONFIG, TEST_XPUB_SPEND, TEST_XPUB_SPEND_ORIGIN,
 };
 
+pub use crate::local::LocalSigningService;
+
+mod local;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
@@ -31,6 +38,8 @@ pub enum Error {
     RequestMiddleware(#[from] reqwest_middleware::Error),
     #[error("Not Implemented: {0}")]
     NotImplemented(String),
+    #[error("Local WSM error: {0}")]
+    Local(String),
 }
 
 #[derive(Deserialize, Serialize)]
@@ -60,7 +69,7 @@ pub struct SignedBlob {
 }
 
 #[async_trait]
-pub trait SigningService {
+pub trait SigningService: Debug + Send + Sync {
     async fn health_check(&self) -> Result<String, Error>;
     async fn create_root_key(
         &self,
@@ -74,24 +83,23 @@ pub trait SigningService {
         change_descriptor: &str,
         psbt: &str,
     ) -> Result<SignedPsbt, Error>;
+    async fn sign_message(
+        &self,
+        root_key_id: &str,
+        message: &str,
+        scheme: MessageSigningScheme,
+    ) -> Result<SignedMessage, Error>;
     async fn get_key_integrity_sig(
         &self,
         root_key_id: &str,
     ) -> Result<GetIntegritySigResponse, Error>;
 }
 
-#[derive(Clone)]
+/// The signing service the server talks to: either the WSM over HTTP, or an in-process
+/// [`LocalSigningService`].
+#[derive(Clone, Debug)]
 pub struct WsmClient {
-    endpoint: reqwest::Url,
-    client: reqwest_middleware::ClientWithMiddleware,
-}
-
-impl Debug for WsmClient {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("WsmClient")
-            .field("endpoint", &self.endpoint.to_string())
-            .finish_non_exhaustive()
-    }
+    service: Arc<dyn SigningService>,
 }
 
 impl Default for WsmClient {
@@ -103,6 +111,79 @@ impl Default for WsmClient {
 impl WsmClient {
     pub fn new(endpoint: &str) -> Result<Self, Error> {
         Ok(WsmClient {
+            service: Arc::new(HttpSigningService::new(endpoint)?),
+        })
+    }
+
+    pub fn local(seed: &str) -> Self {
+        WsmClient {
+            service: Arc::new(LocalSigningService::new(seed)),
+        }
+    }
+}
+
+#[async_trait]
+impl SigningService for WsmClient {
+    async fn health_check(&self) -> Result<String, Error> {
+        self.service.health_check().await
+    }
+
+    async fn create_root_key(
+        &self,
+        root_key_id: &str,
+        network: Network,
+    ) -> Result<CreatedSigningKey, Error> {
+        self.service.create_root_key(root_key_id, network).await
+    }
+
+    async fn sign_psbt(
+        &self,
+        root_key_id: &str,
+        descriptor: &str,
+        change_descriptor: &str,
+        psbt: &str,
+    ) -> Result<SignedPsbt, Error> {
+        self.service
+            .sign_psbt(root_key_id, descriptor, change_descriptor, psbt)
+            .await
+    }
+
+    async fn sign_message(
+        &self,
+        root_key_id: &str,
+        message: &str,
+        scheme: MessageSigningScheme,
+    ) -> Result<SignedMessage, Error> {
+        self.service
+            .sign_message(root_key_id, message, scheme)
+            .await
+    }
+
+    async fn get_key_integrity_sig(
+        &self,
+        root_key_id: &str,
+    ) -> Result<GetIntegritySigResponse, Error> {
+        self.service.get_key_integrity_sig(root_key_id).await
+    }
+}
+
+#[derive(Clone)]
+struct HttpSigningService {
+    endpoint: reqwest::Url,
+    client: reqwest_middleware::ClientWithMiddleware,
+}
+
+impl Debug for HttpSigningService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WsmClient")
+            .field("endpoint", &self.endpoint.to_string())
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpSigningService {
+    fn new(endpoint: &str) -> Result<Self, Error> {
+        Ok(HttpSigningService {
             endpoint: Url::parse(endpoint)?,
             client: ClientBuilder::new(reqwest::Client::new())
                 .with(TracingMiddleware::default())
@@ -112,7 +193,7 @@ impl WsmClient {
 }
 
 #[async_trait]
-impl SigningService for WsmClient {
+impl SigningService for HttpSigningService {
     #[instrument]
     async fn health_check(&self) -> Result<String, Error> {
         let res = self
@@ -163,6 +244,26 @@ impl SigningService for WsmClient {
         Ok(res.json().await?)
     }
 
+    #[instrument]
+    async fn sign_message(
+        &self,
+        root_key_id: &str,
+        message: &str,
+        scheme: MessageSigningScheme,
+    ) -> Result<SignedMessage, Error> {
+        let res = self
+            .client
+            .post(self.endpoint.join("sign-message")?)
+            .json(&SignMessageRequest {
+                root_key_id: root_key_id.to_string(),
+                message: message.to_string(),
+                scheme,
+            })
+            .send()
+            .await?;
+        Ok(res.json().await?)
+    }
+
     #[instrument]
     async fn get_key_integrity_sig(
         &self,
@@ -182,8 +283,9 @@ impl SigningService for WsmClient {
 
 #[cfg(test)]
 mod tests {
-    use super::{TEST_DPUB_SPEND, TEST_XPUB_SPEND, TEST_XPUB_SPEND_ORIGIN};
-    use crate::{SigningService, WsmClient};
+    use super::{TEST_DPUB_SPEND, TEST_XPUB_CONFIG, TEST_XPUB_SPEND, TEST_XPUB_SPEND_ORIGIN};
+    use crate::{MessageSignature, MessageSigningScheme, SigningService, WsmClient};
+    use bdk::bitcoin::bip32::ExtendedPubKey;
     use bdk::bitcoin::psbt::PartiallySignedTransaction;
 
     use bdk::bitcoin::Network;
@@ -215,6 +317,24 @@ mod tests {
         assert_eq!(root_key.xpub, TEST_DPUB_SPEND);
     }
 
+    #[tokio::test]
+    async fn test_sign_message_with_config_key() {
+        let client = WsmClient::new(&get_wsm_endpoint()).unwrap();
+        client.create_root_key(TEST_KEY_ID, Signet).await.unwrap();
+
+        let signed = client
+            .sign_message(TEST_KEY_ID, "Hello World", MessageSigningScheme::Ecdsa)
+            .await
+            .unwrap();
+
+        assert_eq!(signed.root_key_id, TEST_KEY_ID);
+        let MessageSignature::Ecdsa { pubkey, .. } = signed.signature else {
+            panic!("expected an ECDSA signature");
+        };
+        let config_xpub = ExtendedPubKey::from_str(TEST_XPUB_CONFIG).unwrap();
+        assert_eq!(pubkey, config_xpub.public_key.to_string());
+    }
+
     async fn signing_from_descriptor_is_finalized(
         root_key_id: &str,
         descriptor: &str,
@@ -0,0 +1,447 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use bdk::bitcoin::bip32::{DerivationPath, ExtendedPrivKey, ExtendedPubKey, Fingerprint};
+use bdk::bitcoin::hashes::{sha256, Hash, HashEngine};
+use bdk::bitcoin::psbt::PartiallySignedTransaction;
+use bdk::bitcoin::secp256k1::{All, Message, Secp256k1, SecretKey};
+use bdk::bitcoin::sign_message::signed_msg_hash;
+use bdk::database::MemoryDatabase;
+use bdk::descriptor::{DescriptorPublicKey, Segwitv0};
+use bdk::keys::{DerivableKey, DescriptorKey, DescriptorSecretKey};
+use bdk::miniscript::{Descriptor, ForEachKey};
+use bdk::signer::{SignerContext, SignerOrdering, SignerWrapper};
+use bdk::{KeychainKind, SignOptions, Wallet};
+use tracing::instrument;
+use wsm_common::bitcoin::Network;
+use wsm_common::derivation::WSMSupportedDomain;
+use wsm_common::messages::api::{GetIntegritySigResponse, MessageSignature, SignedMessage};
+use wsm_common::messages::TEST_KEY_IDS;
+
+use crate::{CreatedSigningKey, Error, MessageSigningScheme, SignedPsbt, SigningService};
+
+const GLOBAL_CONTEXT: &[u8] = b"WsmIntegrityV1";
+const TEST_INTEGRITY_KEY_B64: &str = include_str!("../../keys/test_integrity_key.b64");
+/// The network the local WSM's keys are derived for when a request doesn't say. Mainnet keys are
+/// refused, and the test networks share a coin type and key encoding, so this derives the same keys
+/// as whichever test network the key was created for.
+const TEST_NETWORK: Network = Network::Signet;
+
+/// An in-process stand-in for the WSM, for running the server with no enclave, KMS or DynamoDB.
+///
+/// Root keys are derived from `seed` and the root key id rather than stored, so they survive
+/// restarts, and the keys for [`TEST_KEY_IDS`] match the ones the enclave creates for them.
+/// Integrity signatures use the test integrity key. Never use this with real funds, so mainnet
+/// keys are refused.
+#[derive(Clone)]
+pub struct LocalSigningService {
+    seed: [u8; 32],
+    secp: Secp256k1<All>,
+    integrity_key: SecretKey,
+}
+
+impl Debug for LocalSigningService {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalSigningService")
+            .finish_non_exhaustive()
+    }
+}
+
+impl LocalSigningService {
+    pub fn new(seed: &str) -> Self {
+        let integrity_key = BASE64
+            .decode(TEST_INTEGRITY_KEY_B64.trim())
+            .expect("Could not decode test integrity key");
+        Self {
+            seed: sha256::Hash::hash(seed.as_bytes()).to_byte_array(),
+            secp: Secp256k1::new(),
+            integrity_key: SecretKey::from_slice(&integrity_key)
+                .expect("Test integrity key is not a valid secret key"),
+        }
+    }
+
+    fn root_xprv(&self, root_key_id: &str, network: Network) -> Result<ExtendedPrivKey, Error> {
+        if network == Network::Bitcoin {
+            return Err(Error::Local(
+                "the local WSM doesn't hold mainnet keys".to_string(),
+            ));
+        }
+        // Same test keys as the enclave, so fixtures work against either
+        let seed = if TEST_KEY_IDS.contains(&root_key_id) {
+            [0u8; 64].to_vec()
+        } else {
+            let mut engine = sha256::Hash::engine();
+            engine.input(&self.seed);
+            engine.input(root_key_id.as_bytes());
+            sha256::Hash::from_engine(engine).to_byte_array().to_vec()
+        };
+        ExtendedPrivKey::new_master(v30_network(network), &seed).map_err(local_error)
+    }
+
+    fn derive(
+        &self,
+        root_key_id: &str,
+        network: Network,
+        domain: WSMSupportedDomain,
+    ) -> Result<(ExtendedPrivKey, DerivedKey), Error> {
+        let root_xprv = self.root_xprv(root_key_id, network)?;
+        // DerivationPath::from is only supported for wsm-common's 0.29 DerivationPath
+        let derivation_path = DerivationPath::from_str(
+            &wsm_common::bitcoin::util::bip32::DerivationPath::from(domain).to_string(),
+        )
+        .map_err(local_error)?;
+        let xprv = root_xprv
+            .derive_priv(&self.secp, &derivation_path)
+            .map_err(local_error)?;
+        Ok((
+            xprv,
+            DerivedKey {
+                origin: (root_xprv.fingerprint(&self.secp), derivation_path),
+                xpub: ExtendedPubKey::from_priv(&self.secp, &xprv),
+            },
+        ))
+    }
+
+    fn integrity_sig(&self, context: &[u8], xpub: &ExtendedPubKey) -> String {
+        let mut hash_input = Vec::new();
+        hash_input.extend_from_slice(GLOBAL_CONTEXT);
+        hash_input.extend_from_slice(context);
+        hash_input.extend_from_slice(&xpub.encode());
+
+        let message = Message::from_hashed_data::<sha256::Hash>(&hash_input);
+        hex::encode(
+            self.secp
+                .sign_ecdsa(&message, &self.integrity_key)
+                .serialize_compact(),
+        )
+    }
+
+    fn spend_wallet(
+        &self,
+        root_key_id: &str,
+        descriptor: &str,
+        change_descriptor: &str,
+    ) -> Result<Wallet<MemoryDatabase>, Error> {
+        let network = descriptor_network(descriptor)?;
+        let (xprv, key) = self.derive(
+            root_key_id,
+            network,
+            WSMSupportedDomain::Spend(network.into()),
+        )?;
+        let mut wallet = Wallet::new(
+            descriptor,
+            Some(change_descriptor),
+            v30_network(network),
+            MemoryDatabase::default(),
+        )
+        .map_err(local_error)?;
+        for (keychain, path, ordering) in [
+            (KeychainKind::External, "m/0", 9001),
+            (KeychainKind::Internal, "m/1", 9002),
+        ] {
+            let descriptor_key: DescriptorKey<Segwitv0> = xprv
+                .into_descriptor_key(
+                    Some(key.origin.clone()),
+                    DerivationPath::from_str(path).map_err(local_error)?,
+                )
+                .map_err(local_error)?;
+            let DescriptorKey::Secret(DescriptorSecretKey::XPrv(xkey), _, _) = descriptor_key
+            else {
+                unreachable!("an xprv always makes an xprv descriptor key")
+            };
+            wallet.add_signer(
+                keychain,
+                SignerOrdering(ordering),
+                Arc::new(SignerWrapper::new(xkey, SignerContext::Segwitv0)),
+            );
+        }
+        Ok(wallet)
+    }
+}
+
+struct DerivedKey {
+    origin: (Fingerprint, DerivationPath),
+    xpub: ExtendedPubKey,
+}
+
+impl DerivedKey {
+    /// The descriptor form of the key, as the enclave returns it
+    fn dpub(&self) -> Result<String, Error> {
+        let descriptor_key: DescriptorKey<Segwitv0> = self
+            .xpub
+            .into_descriptor_key(Some(self.origin.clone()), DerivationPath::default())
+            .map_err(local_error)?;
+        match descriptor_key {
+            DescriptorKey::Public(public, _, _) => Ok(public.to_string()),
+            DescriptorKey::Secret(..) => unreachable!("an xpub never makes a secret key"),
+        }
+    }
+}
+
+#[async_trait]
+impl SigningService for LocalSigningService {
+    async fn health_check(&self) -> Result<String, Error> {
+        Ok("server healthy".to_string())
+    }
+
+    #[instrument]
+    async fn create_root_key(
+        &self,
+        root_key_id: &str,
+        network: Network,
+    ) -> Result<CreatedSigningKey, Error> {
+        let (_, key) = self.derive(
+            root_key_id,
+            network,
+            WSMSupportedDomain::Spend(network.into()),
+        )?;
+
+        Ok(CreatedSigningKey {
+            root_key_id: root_key_id.to_string(),
+            xpub: key.dpub()?,
+            xpub_sig: self.integrity_sig(b"DeriveKeyV1", &key.xpub),
+        })
+    }
+
+    #[instrument]
+    async fn sign_psbt(
+        &self,
+        root_key_id: &str,
+        descriptor: &str,
+        change_descriptor: &str,
+        psbt: &str,
+    ) -> Result<SignedPsbt, Error> {
+        let wallet = self.spend_wallet(root_key_id, descriptor, change_descriptor)?;
+        let mut psbt = PartiallySignedTransaction::from_str(psbt).map_err(local_error)?;
+        wallet
+            .sign(&mut psbt, SignOptions::default())
+            .map_err(local_error)?;
+
+        Ok(SignedPsbt {
+            psbt: psbt.to_string(),
+            root_key_id: root_key_id.to_string(),
+        })
+    }
+
+    #[instrument]
+    async fn sign_message(
+        &self,
+        root_key_id: &str,
+        message: &str,
+        scheme: MessageSigningScheme,
+    ) -> Result<SignedMessage, Error> {
+        let MessageSigningScheme::Ecdsa = scheme else {
+            return Err(Error::NotImplemented(
+                "BIP-322 proofs in the local WSM".to_string(),
+            ));
+        };
+        let (xprv, _) = self.derive(root_key_id, TEST_NETWORK, WSMSupportedDomain::Config)?;
+        let signature = self
+            .secp
+            .sign_ecdsa(&Message::from(signed_msg_hash(message)), &xprv.private_key);
+
+        Ok(SignedMessage {
+            root_key_id: root_key_id.to_string(),
+            signature: MessageSignature::Ecdsa {
+                signature: hex::encode(signature.serialize_der()),
+                pubkey: hex::encode(xprv.private_key.public_key(&self.secp).serialize()),
+            },
+        })
+    }
+
+    #[instrument]
+    async fn get_key_integrity_sig(
+        &self,
+        root_key_id: &str,
+    ) -> Result<GetIntegritySigResponse, Error> {
+        let (_, key) = self.derive(
+            root_key_id,
+            TEST_NETWORK,
+            WSMSupportedDomain::Spend(TEST_NETWORK.into()),
+        )?;
+
+        Ok(GetIntegritySigResponse {
+            signature: self.integrity_sig(b"DeriveKeyV1", &key.xpub),
+        })
+    }
+}
+
+/// The network of the keys in a descriptor. Keys are derived from the root key id alone, so signing
+/// doesn't need the key to have been created in this process.
+fn descriptor_network(descriptor: &str) -> Result<Network, Error> {
+    let descriptor =
+        Descriptor::<DescriptorPublicKey>::from_str(descriptor).map_err(local_error)?;
+    let mainnet = descriptor.for_any_key(|key| match key {
+        DescriptorPublicKey::XPub(xkey) => xkey.xkey.network == bdk::bitcoin::Network::Bitcoin,
+        _ => false,
+    });
+    Ok(if mainnet {
+        Network::Bitcoin
+    } else {
+        TEST_NETWORK
+    })
+}
+
+fn local_error(e: impl Display) -> Error {
+    Error::Local(e.to_string())
+}
+
+fn v30_network(network: Network) -> bdk::bitcoin::Network {
+    match network {
+        Network::Bitcoin => bdk::bitcoin::Network::Bitcoin,
+        Network::Testnet => bdk::bitcoin::Network::Testnet,
+        Network::Signet => bdk::bitcoin::Network::Signet,
+        Network::Regtest => bdk::bitcoin::Network::Regtest,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use bdk::bitcoin::absolute::LockTime;
+    use bdk::bitcoin::psbt::PartiallySignedTransaction;
+    use bdk::bitcoin::{OutPoint, Transaction, TxIn, TxOut};
+    use bdk::descriptor::DescriptorPublicKey;
+    use bdk::miniscript::psbt::PsbtExt;
+    use bdk::miniscript::Descriptor;
+    use wsm_common::bitcoin::Network::{Bitcoin, Signet, Testnet};
+    use wsm_common::messages::{TEST_DPUB_SPEND, TEST_KEY_ID};
+
+    use super::LocalSigningService;
+    use crate::{MessageSigningScheme, SigningService};
+
+    #[tokio::test]
+    async fn test_keys_match_the_enclave() {
+        let wsm = LocalSigningService::new("local");
+
+        let key = wsm.create_root_key(TEST_KEY_ID, Signet).await.unwrap();
+        assert_eq!(key.xpub, TEST_DPUB_SPEND);
+        assert_eq!(
+            wsm.get_key_integrity_sig(TEST_KEY_ID)
+                .await
+                .unwrap()
+                .signature,
+            key.xpub_sig
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keys_outlive_the_instance_that_created_them() {
+        let root_key_id = "urn:wallet-keyset:01HQZRJ5F2W0V4X9KQ3DDG0MPD";
+        let creator = LocalSigningService::new("local");
+        let key = creator.create_root_key(root_key_id, Testnet).await.unwrap();
+        let signed = creator
+            .sign_message(root_key_id, "hello", MessageSigningScheme::Ecdsa)
+            .await
+            .unwrap();
+
+        // As if the server restarted since creating the key
+        let restarted = LocalSigningService::new("local");
+        assert_eq!(
+            restarted
+                .get_key_integrity_sig(root_key_id)
+                .await
+                .unwrap()
+                .signature,
+            key.xpub_sig
+        );
+        assert_eq!(
+            restarted
+                .sign_message(root_key_id, "hello", MessageSigningScheme::Ecdsa)
+                .await
+                .unwrap()
+                .signature,
+            signed.signature
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refuses_mainnet_keys() {
+        let wsm = LocalSigningService::new("local");
+
+        assert!(wsm.create_root_key(TEST_KEY_ID, Bitcoin).await.is_err());
+        assert!(wsm
+            .create_root_key("urn:wallet-keyset:01HQZRJ5F2W0V4X9KQ3DDG0MPD", Bitcoin)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_deterministic() {
+        let root_key_id = "urn:wallet-keyset:01HQZRJ5F2W0V4X9KQ3DDG0MPD";
+        let first = LocalSigningService::new("local")
+            .create_root_key(root_key_id, Signet)
+            .await
+            .unwrap();
+        let second = LocalSigningService::new("local")
+            .create_root_key(root_key_id, Signet)
+            .await
+            .unwrap();
+        let other_seed = LocalSigningService::new("other")
+            .create_root_key(root_key_id, Signet)
+            .await
+            .unwrap();
+
+        assert_eq!(first.xpub, second.xpub);
+        assert_ne!(first.xpub, other_seed.xpub);
+    }
+
+    #[tokio::test]
+    async fn test_cosigns_psbt() {
+        let wsm = LocalSigningService::new("local");
+        let root_key_id = "urn:wallet-keyset:01HQZRJ5F2W0V4X9KQ3DDG0MPD";
+        let key = wsm.create_root_key(root_key_id, Signet).await.unwrap();
+
+        // A single-sig wallet is enough to check that the server key signs
+        let descriptor = format!("wpkh({})", key.xpub.replace("/*", "/0/*"));
+        let change_descriptor = format!("wpkh({})", key.xpub.replace("/*", "/1/*"));
+        let receive = Descriptor::<DescriptorPublicKey>::from_str(&descriptor)
+            .unwrap()
+            .at_derivation_index(0)
+            .unwrap();
+        let funding = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![TxOut {
+                value: 50_000,
+                script_pubkey: receive.script_pubkey(),
+            }],
+        };
+        let spend = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: funding.txid(),
+                    vout: 0,
+                },
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value: 49_000,
+                script_pubkey: receive.script_pubkey(),
+            }],
+        };
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(spend).unwrap();
+        psbt.inputs[0].witness_utxo = Some(funding.output[0].clone());
+        psbt.inputs[0].non_witness_utxo = Some(funding);
+        psbt.update_input_with_descriptor(0, &receive).unwrap();
+
+        let signed = wsm
+            .sign_psbt(
+                root_key_id,
+                &descriptor,
+                &change_descriptor,
+                &psbt.to_string(),
+            )
+            .await
+            .unwrap();
+        let signed = PartiallySignedTransaction::from_str(&signed.psbt).unwrap();
+        assert!(signed.inputs[0].final_script_witness.is_some());
+    }
+}
@@ -186,6 +186,24 @@ module "chain_indexer_table" {
   deletion_protection_enabled = var.enable_deletion_protection
 }
 
+module "chain_indexer_state_table" {
+  source = "git::https://github.com/terraform-aws-modules/terraform-aws-dynamodb-table//?ref=9b66b76b2d178ca42425378deac9d9ebf95bf14e" // Tag v3.2.0
+
+  create_table = var.create_dynamodb_tables
+
+  name     = var.chain_indexer_state_table_name
+  hash_key = "partition_key"
+
+  attributes = [
+    { name = "partition_key", type = "S" },
+  ]
+
+  point_in_time_recovery_enabled = true
+  server_side_encryption_enabled = true
+
+  deletion_protection_enabled = var.enable_deletion_protection
+}
+
 
 module "daily_spending_record_table" {
   source = "git::https://github.com/terraform-aws-modules/terraform-aws-dynamodb-table//?ref=9b66b76b2d178ca42425378deac9d9ebf95bf14e" // Tag v3.2.0
@@ -241,6 +259,17 @@ module "address_watchlist_table" {
 
   attributes = [
     { name = "address", type = "S" },
+    { name = "created_date", type = "S" },
+    { name = "created_time", type = "S" },
+  ]
+
+  global_secondary_indexes = [
+    {
+      name            = "CreatedDateIndex"
+      hash_key        = "created_date"
+      range_key       = "created_time"
+      projection_type = "ALL"
+    }
   ]
 
   point_in_time_recovery_enabled = true
@@ -297,3 +326,64 @@ module "consent_table" {
 
   deletion_protection_enabled = var.enable_deletion_protection
 }
+
+module "session_table" {
+  source = "git::https://github.com/terraform-aws-modules/terraform-aws-dynamodb-table//?ref=9b66b76b2d178ca42425378deac9d9ebf95bf14e" // Tag v3.2.0
+
+  create_table = var.create_dynamodb_tables
+
+  name      = var.session_table_name
+  hash_key  = "partition_key"
+  range_key = "sort_key"
+
+  attributes = [
+    { name = "partition_key", type = "S" },
+    { name = "sort_key", type = "S" },
+  ]
+
+  point_in_time_recovery_enabled = true
+  server_side_encryption_enabled = true
+
+  deletion_protection_enabled = var.enable_deletion_protection
+}
+
+module "security_event_table" {
+  source = "git::https://github.com/terraform-aws-modules/terraform-aws-dynamodb-table//?ref=9b66b76b2d178ca42425378deac9d9ebf95bf14e" // Tag v3.2.0
+
+  create_table = var.create_dynamodb_tables
+
+  name      = var.security_event_table_name
+  hash_key  = "partition_key"
+  range_key = "sort_key"
+
+  attributes = [
+    { name = "partition_key", type = "S" },
+    { name = "sort_key", type = "S" },
+  ]
+
+  point_in_time_recovery_enabled = true
+  server_side_encryption_enabled = true
+
+  deletion_protection_enabled = var.enable_deletion_protection
+}
+
+module "request_nonce_table" {
+  source = "git::https://github.com/terraform-aws-modules/terraform-aws-dynamodb-table//?ref=9b66b76b2d178ca42425378deac9d9ebf95bf14e" // Tag v3.2.0
+
+  create_table = var.create_dynamodb_tables
+
+  name     = var.request_nonce_table_name
+  hash_key = "partition_key"
+
+  attributes = [
+    { name = "partition_key", type = "S" },
+  ]
+
+  point_in_time_recovery_enabled = true
+  server_side_encryption_enabled = true
+
+  ttl_enabled        = true
+  ttl_attribute_name = "expiring_at"
+
+  deletion_protection_enabled = var.enable_deletion_protection
+}
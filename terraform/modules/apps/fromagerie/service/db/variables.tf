@@ -37,6 +37,11 @@ variable "chain_indexer_table_name" {
   description = "The name of the chain indexer table"
 }
 
+variable "chain_indexer_state_table_name" {
+  type        = string
+  description = "The name of the chain indexer cursor and payment notification table"
+}
+
 variable "daily_spending_record_table_name" {
   type        = string
   description = "Override the name of the daily spend record table"
@@ -61,3 +66,18 @@ variable "consent_table_name" {
   type        = string
   description = "The name of the consent table"
 }
+
+variable "session_table_name" {
+  type        = string
+  description = "The name of the device session table"
+}
+
+variable "security_event_table_name" {
+  type        = string
+  description = "The name of the account security event log table"
+}
+
+variable "request_nonce_table_name" {
+  type        = string
+  description = "The name of the table of request nonces that have already been used"
+}
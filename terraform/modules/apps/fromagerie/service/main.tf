@@ -23,11 +23,15 @@ locals {
     address_watchlist_table_name     = "${module.this.id_dot}.address_watchlist"
     notification_table_name          = "${module.this.id_dot}.notification"
     chain_indexer_table_name         = "${module.this.id_dot}.chain_indexer"
+    chain_indexer_state_table_name   = "${module.this.id_dot}.chain_indexer_state"
     daily_spending_record_table_name = "${module.this.id_dot}.daily_spending_record"
     signed_psbt_cache_table_name     = "${module.this.id_dot}.signed_psbt_cache"
     migration_record_table_name      = "${module.this.id_dot}.migration_records"
     social_recovery_table_name       = "${module.this.id_dot}.social_recovery"
     consent_table_name               = "${module.this.id_dot}.consent"
+    session_table_name               = "${module.this.id_dot}.session"
+    security_event_table_name        = "${module.this.id_dot}.security_event"
+    request_nonce_table_name         = "${module.this.id_dot}.request_nonce"
 
     # Below are old tables that we retain a name override for the deprecated PrototypeOnboardingStack
     # New tables should be added above without coalesce()
@@ -81,12 +85,16 @@ locals {
     ACCOUNT_TABLE               = local.tables.account_table_name
     ADDRESS_WATCHLIST_TABLE     = local.tables.address_watchlist_table_name
     CHAIN_INDEXER_TABLE         = local.tables.chain_indexer_table_name
+    CHAIN_INDEXER_STATE_TABLE   = local.tables.chain_indexer_state_table_name
     DAILY_SPENDING_RECORD_TABLE = local.tables.daily_spending_record_table_name
     NOTIFICATION_TABLE          = local.tables.notification_table_name
     RECOVERY_TABLE              = local.tables.recovery_table_name
     SIGNED_PSBT_CACHE_TABLE     = local.tables.signed_psbt_cache_table_name
     SOCIAL_RECOVERY_TABLE       = local.tables.social_recovery_table_name
     CONSENT_TABLE               = local.tables.consent_table_name
+    SESSION_TABLE               = local.tables.session_table_name
+    SECURITY_EVENT_TABLE        = local.tables.security_event_table_name
+    REQUEST_NONCE_TABLE         = local.tables.request_nonce_table_name
   }
 
   ###############################################
@@ -114,6 +122,10 @@ data "aws_secretsmanager_secret" "fromagerie_twilio_credentials" {
   name = "fromagerie/twilio/credentials"
 }
 
+data "aws_secretsmanager_secret" "fromagerie_request_nonce_secret" {
+  name = "fromagerie/request_nonce/secret"
+}
+
 data "aws_secretsmanager_secret" "fromagerie_launchdarkly_sdk_key" {
   name = "fromagerie/launchdarkly/sdk_key"
 }
@@ -147,6 +159,7 @@ module "dynamodb_tables" {
   account_table_name               = local.tables.account_table_name
   address_watchlist_table_name     = local.tables.address_watchlist_table_name
   chain_indexer_table_name         = local.tables.chain_indexer_table_name
+  chain_indexer_state_table_name   = local.tables.chain_indexer_state_table_name
   daily_spending_record_table_name = local.tables.daily_spending_record_table_name
   notification_table_name          = local.tables.notification_table_name
   recovery_table_name              = local.tables.recovery_table_name
@@ -154,6 +167,9 @@ module "dynamodb_tables" {
   migration_record_table_name      = local.tables.migration_record_table_name
   social_recovery_table_name       = local.tables.social_recovery_table_name
   consent_table_name               = local.tables.consent_table_name
+  session_table_name               = local.tables.session_table_name
+  security_event_table_name        = local.tables.security_event_table_name
+  request_nonce_table_name         = local.tables.request_nonce_table_name
 }
 
 module "ecs_api" {
@@ -184,6 +200,7 @@ module "ecs_api" {
     COINGECKO_API_KEY              = data.aws_secretsmanager_secret.fromagerie_coingecko_api_key.arn,
     COINMARKETCAP_API_KEY          = data.aws_secretsmanager_secret.fromagerie_coinmarketcap_api_key.arn,
     SQ_SDN_URI                     = data.aws_secretsmanager_secret.fromagerie_sq_sdn_s3_uri.arn,
+    REQUEST_NONCE_SECRET           = data.aws_secretsmanager_secret.fromagerie_request_nonce_secret.arn,
     TWILIO_ACCOUNT_SID             = "${data.aws_secretsmanager_secret.fromagerie_twilio_credentials.arn}:TWILIO_ACCOUNT_SID::",
     TWILIO_AUTH_TOKEN              = "${data.aws_secretsmanager_secret.fromagerie_twilio_credentials.arn}:TWILIO_AUTH_TOKEN::",
     TWILIO_KEY_SID                 = "${data.aws_secretsmanager_secret.fromagerie_twilio_credentials.arn}:TWILIO_KEY_SID::",
@@ -214,6 +231,7 @@ module "api_migration" {
     COGNITO_CLIENT_ID       = var.cognito_user_pool_client_id
     MIGRATION_TABLE         = local.tables.migration_record_table_name
     SERVER_ZENDESK          = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
+    SERVER_REQUEST_SIGNING  = "{mode=test}" //TODO: Pick apart bootstrap dependence on request signing,
   })
   environment = var.environment
   secrets = merge(local.common_secrets, {
@@ -280,6 +298,7 @@ module "ecs_job_push" {
     SERVER_TWILIO           = "{mode=test}" //TODO: Pick apart bootstrap dependence on Twilio,
     SERVER_ITERABLE         = "{mode=test}" //TODO: Pick apart bootstrap dependence on Iterable,
     SERVER_ZENDESK          = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
+    SERVER_REQUEST_SIGNING  = "{mode=test}" //TODO: Pick apart bootstrap dependence on request signing,
   })
   secrets          = merge(local.common_secrets, {})
   image_name       = var.image_name
@@ -308,6 +327,7 @@ module "ecs_job_email" {
     SERVER_COGNITO          = "test"        //TODO: Pick apart bootstrap dependence on Cognito,
     SERVER_TWILIO           = "{mode=test}" //TODO: Pick apart bootstrap dependence on Twilio,
     SERVER_ZENDESK          = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
+    SERVER_REQUEST_SIGNING  = "{mode=test}" //TODO: Pick apart bootstrap dependence on request signing,
   })
   secrets = merge(local.common_secrets, {
     ITERABLE_API_KEY = data.aws_secretsmanager_secret.fromagerie_iterable_credentials.arn
@@ -338,6 +358,7 @@ module "ecs_job_sms" {
     SERVER_COGNITO          = "test"        //TODO: Pick apart bootstrap dependence on Cognito,
     SERVER_ITERABLE         = "{mode=test}" //TODO: Pick apart bootstrap dependence on Iterable,
     SERVER_ZENDESK          = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
+    SERVER_REQUEST_SIGNING  = "{mode=test}" //TODO: Pick apart bootstrap dependence on request signing,
   })
   secrets = merge(local.common_secrets, {
     TWILIO_ACCOUNT_SID = "${data.aws_secretsmanager_secret.fromagerie_twilio_credentials.arn}:TWILIO_ACCOUNT_SID::",
@@ -375,6 +396,7 @@ module "ecs_job_scheduled_notification_task" {
     SERVER_TWILIO           = "{mode=test}" //TODO: Pick apart bootstrap dependence on Twilio,
     SERVER_ITERABLE         = "{mode=test}" //TODO: Pick apart bootstrap dependence on Iterable,
     SERVER_ZENDESK          = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
+    SERVER_REQUEST_SIGNING  = "{mode=test}" //TODO: Pick apart bootstrap dependence on request signing,
   })
   secrets          = merge(local.common_secrets, {})
   cpu_architecture = "ARM64"
@@ -398,13 +420,15 @@ module "ecs_job_blockchain_polling_task_signet" {
   command     = ["worker", "blockchain-polling"]
   environment = var.environment
   environment_variables = merge(local.common_env_vars, {
-    SERVER_WALLET_TELEMETRY = "{service_name=${var.name}-job-blockchain-polling,mode=datadog}"
-    SERVER_COGNITO          = "test"        //TODO: Pick apart bootstrap dependence on Cognito,
-    SERVER_TWILIO           = "{mode=test}" //TODO: Pick apart bootstrap dependence on Twilio,
-    SERVER_ITERABLE         = "{mode=test}" //TODO: Pick apart bootstrap dependence on Iterable,
-    SERVER_ZENDESK          = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
-    CHAIN_INDEXER_BASE_URL  = "https://bitkey.mempool.space/signet/api"
-    CHAIN_INDEXER_NETWORK   = "signet"
+    SERVER_WALLET_TELEMETRY       = "{service_name=${var.name}-job-blockchain-polling,mode=datadog}"
+    SERVER_COGNITO                = "test"        //TODO: Pick apart bootstrap dependence on Cognito,
+    SERVER_TWILIO                 = "{mode=test}" //TODO: Pick apart bootstrap dependence on Twilio,
+    SERVER_ITERABLE               = "{mode=test}" //TODO: Pick apart bootstrap dependence on Iterable,
+    SERVER_ZENDESK                = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
+    SERVER_REQUEST_SIGNING        = "{mode=test}" //TODO: Pick apart bootstrap dependence on request signing,
+    CHAIN_INDEXER_BASE_URL        = "https://bitkey.mempool.space/signet/api"
+    CHAIN_INDEXER_NETWORK         = "signet"
+    CHAIN_INDEXER_MEMPOOL_ENABLED = "true"
   })
   secrets          = merge(local.common_secrets, {})
   cpu_architecture = "ARM64"
@@ -433,6 +457,7 @@ module "ecs_job_blockchain_polling_task_mainnet" {
     SERVER_TWILIO           = "{mode=test}" //TODO: Pick apart bootstrap dependence on Twilio,
     SERVER_ITERABLE         = "{mode=test}" //TODO: Pick apart bootstrap dependence on Iterable,
     SERVER_ZENDESK          = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
+    SERVER_REQUEST_SIGNING  = "{mode=test}" //TODO: Pick apart bootstrap dependence on request signing,
     CHAIN_INDEXER_BASE_URL  = "https://bitkey.mempool.space/api"
     CHAIN_INDEXER_NETWORK   = "bitcoin"
   })
@@ -463,6 +488,7 @@ module "ecs_job_metrics" {
     SERVER_TWILIO           = "{mode=test}" //TODO: Pick apart bootstrap dependence on Twilio,
     SERVER_ITERABLE         = "{mode=test}" //TODO: Pick apart bootstrap dependence on Iterable,
     SERVER_ZENDESK          = "{mode=test}" //TODO: Pick apart bootstrap dependence on Zendesk,
+    SERVER_REQUEST_SIGNING  = "{mode=test}" //TODO: Pick apart bootstrap dependence on request signing,
   })
   secrets          = merge(local.common_secrets, {})
   cpu_architecture = "ARM64"